@@ -27,6 +27,13 @@ fn main() -> io::Result<()> {
         );
     });
 
+    let paste_state = Rc::clone(&state);
+    terminal.on_paste_event(move |text| {
+        if let Ok(mut state_text) = paste_state.text.try_borrow_mut() {
+            *state_text = text;
+        }
+    });
+
     let render_state = Rc::clone(&state);
     terminal.draw_web(move |frame| {
         render_state.render(frame);
@@ -74,12 +81,6 @@ impl App {
             KeyCode::Char('c') if key_event.ctrl => {
                 self.set_clipboard("i like rats").await;
             }
-            KeyCode::Char('v') if key_event.ctrl => {
-                if let Ok(mut text) = self.text.try_borrow_mut() {
-                    let clipboard_text = self.get_clipboard().await;
-                    *text = clipboard_text;
-                }
-            }
             _ => {}
         }
     }
@@ -90,12 +91,4 @@ impl App {
         let promise = nav.write_text(text);
         wasm_bindgen_futures::JsFuture::from(promise).await.unwrap();
     }
-
-    async fn get_clipboard(&self) -> String {
-        let window = web_sys::window().unwrap();
-        let nav = window.navigator().clipboard();
-        let promise = nav.read_text();
-        let result = wasm_bindgen_futures::JsFuture::from(promise).await.unwrap();
-        result.as_string().unwrap_or_default()
-    }
 }
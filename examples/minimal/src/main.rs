@@ -16,6 +16,7 @@ use ratzilla::backend::webgl2::WebGl2BackendOptions;
 fn main() -> io::Result<()> {
     let counter = Rc::new(RefCell::new(0));
     let mouse_position = Rc::new(RefCell::new((0, 0)));
+    let mouse_cell = Rc::new(RefCell::new(None));
     let mouse_button = Rc::new(RefCell::new(None::<MouseButton>));
     let mouse_event_kind = Rc::new(RefCell::new(None::<MouseEventKind>));
 
@@ -38,11 +39,13 @@ fn main() -> io::Result<()> {
 
     terminal.on_mouse_event({
         let mouse_position_cloned = mouse_position.clone();
+        let mouse_cell_cloned = mouse_cell.clone();
         let mouse_button_cloned = mouse_button.clone();
         let mouse_event_kind_cloned = mouse_event_kind.clone();
         move |mouse_event| {
             let mut mouse_position = mouse_position_cloned.borrow_mut();
             *mouse_position = (mouse_event.x, mouse_event.y);
+            *mouse_cell_cloned.borrow_mut() = mouse_event.cell;
             let mut mouse_button = mouse_button_cloned.borrow_mut();
             *mouse_button = Some(mouse_event.button);
             let mut mouse_event_kind = mouse_event_kind_cloned.borrow_mut();
@@ -53,6 +56,7 @@ fn main() -> io::Result<()> {
     terminal.draw_web(move |f| {
         let counter = counter.borrow();
         let mouse_position = mouse_position.borrow();
+        let mouse_cell = mouse_cell.borrow();
         let mouse_button = mouse_button.borrow();
         let mouse_event_kind = mouse_event_kind.borrow();
 
@@ -61,6 +65,7 @@ fn main() -> io::Result<()> {
                 "Space pressed: {counter}\n\
                 MouseX: {:?}\n\
                 MouseY: {:?}\n\
+                MouseCell: {mouse_cell:?}\n\
                 MouseButton: {mouse_button:?}\n\
                 MouseEvent: {mouse_event_kind:?}",
                 mouse_position.0, mouse_position.1
@@ -1,4 +1,6 @@
 use crate::{fps, utils::inject_backend_footer};
+#[cfg(feature = "webgpu")]
+use ratzilla::{backend::webgpu::WgpuBackendOptions, WgpuBackend};
 use ratzilla::{
     backend::{canvas::CanvasBackendOptions, dom::DomBackendOptions, webgl2::WebGl2BackendOptions},
     ratatui::{backend::Backend, prelude::backend::ClearType, Terminal, TerminalOptions},
@@ -14,6 +16,8 @@ pub enum BackendType {
     Dom,
     Canvas,
     WebGl2,
+    #[cfg(feature = "webgpu")]
+    WebGpu,
 }
 
 impl BackendType {
@@ -23,6 +27,8 @@ impl BackendType {
             BackendType::Dom => "dom",
             BackendType::Canvas => "canvas",
             BackendType::WebGl2 => "webgl2",
+            #[cfg(feature = "webgpu")]
+            BackendType::WebGpu => "webgpu",
         }
     }
 }
@@ -35,6 +41,8 @@ impl TryFrom<String> for BackendType {
             "dom" => Ok(BackendType::Dom),
             "canvas" => Ok(BackendType::Canvas),
             "webgl2" => Ok(BackendType::WebGl2),
+            #[cfg(feature = "webgpu")]
+            "webgpu" => Ok(BackendType::WebGpu),
             _ => Err(format!(
                 "Invalid backend type: '{s}'. Valid options are: dom, canvas, webgl2"
             )),
@@ -59,10 +67,13 @@ impl fmt::Display for BackendType {
 /// - `Dom`: HTML DOM-based rendering with accessibility features
 /// - `Canvas`: Canvas 2D API rendering with full Unicode support  
 /// - `WebGl2`: GPU-accelerated rendering using WebGL2 and beamterm-renderer
+/// - `WebGpu`: GPU-accelerated rendering using WebGPU (requires the `webgpu` feature)
 pub enum RatzillaBackend {
     Dom(DomBackend),
     Canvas(CanvasBackend),
     WebGl2(WebGl2Backend),
+    #[cfg(feature = "webgpu")]
+    WebGpu(WgpuBackend),
 }
 
 impl RatzillaBackend {
@@ -72,6 +83,8 @@ impl RatzillaBackend {
             RatzillaBackend::Dom(_) => BackendType::Dom,
             RatzillaBackend::Canvas(_) => BackendType::Canvas,
             RatzillaBackend::WebGl2(_) => BackendType::WebGl2,
+            #[cfg(feature = "webgpu")]
+            RatzillaBackend::WebGpu(_) => BackendType::WebGpu,
         }
     }
 }
@@ -87,6 +100,8 @@ impl Backend for RatzillaBackend {
             RatzillaBackend::Dom(backend) => backend.draw(content),
             RatzillaBackend::Canvas(backend) => backend.draw(content),
             RatzillaBackend::WebGl2(backend) => backend.draw(content),
+            #[cfg(feature = "webgpu")]
+            RatzillaBackend::WebGpu(backend) => backend.draw(content),
         }
     }
 
@@ -95,6 +110,8 @@ impl Backend for RatzillaBackend {
             RatzillaBackend::Dom(backend) => backend.flush(),
             RatzillaBackend::Canvas(backend) => backend.flush(),
             RatzillaBackend::WebGl2(backend) => backend.flush(),
+            #[cfg(feature = "webgpu")]
+            RatzillaBackend::WebGpu(backend) => backend.flush(),
         }
     }
 
@@ -103,6 +120,8 @@ impl Backend for RatzillaBackend {
             RatzillaBackend::Dom(backend) => backend.size(),
             RatzillaBackend::Canvas(backend) => backend.size(),
             RatzillaBackend::WebGl2(backend) => backend.size(),
+            #[cfg(feature = "webgpu")]
+            RatzillaBackend::WebGpu(backend) => backend.size(),
         }
     }
 
@@ -111,6 +130,8 @@ impl Backend for RatzillaBackend {
             RatzillaBackend::Dom(backend) => backend.hide_cursor(),
             RatzillaBackend::Canvas(backend) => backend.hide_cursor(),
             RatzillaBackend::WebGl2(backend) => backend.hide_cursor(),
+            #[cfg(feature = "webgpu")]
+            RatzillaBackend::WebGpu(backend) => backend.hide_cursor(),
         }
     }
 
@@ -119,6 +140,8 @@ impl Backend for RatzillaBackend {
             RatzillaBackend::Dom(backend) => backend.show_cursor(),
             RatzillaBackend::Canvas(backend) => backend.show_cursor(),
             RatzillaBackend::WebGl2(backend) => backend.show_cursor(),
+            #[cfg(feature = "webgpu")]
+            RatzillaBackend::WebGpu(backend) => backend.show_cursor(),
         }
     }
 
@@ -127,6 +150,8 @@ impl Backend for RatzillaBackend {
             RatzillaBackend::Dom(backend) => backend.get_cursor_position(),
             RatzillaBackend::Canvas(backend) => backend.get_cursor_position(),
             RatzillaBackend::WebGl2(backend) => backend.get_cursor_position(),
+            #[cfg(feature = "webgpu")]
+            RatzillaBackend::WebGpu(backend) => backend.get_cursor_position(),
         }
     }
 
@@ -138,6 +163,8 @@ impl Backend for RatzillaBackend {
             RatzillaBackend::Dom(backend) => backend.set_cursor_position(position),
             RatzillaBackend::Canvas(backend) => backend.set_cursor_position(position),
             RatzillaBackend::WebGl2(backend) => backend.set_cursor_position(position),
+            #[cfg(feature = "webgpu")]
+            RatzillaBackend::WebGpu(backend) => backend.set_cursor_position(position),
         }
     }
 
@@ -146,6 +173,8 @@ impl Backend for RatzillaBackend {
             RatzillaBackend::Dom(backend) => backend.clear(),
             RatzillaBackend::Canvas(backend) => backend.clear(),
             RatzillaBackend::WebGl2(backend) => backend.clear(),
+            #[cfg(feature = "webgpu")]
+            RatzillaBackend::WebGpu(backend) => backend.clear(),
         }
     }
 
@@ -154,6 +183,8 @@ impl Backend for RatzillaBackend {
             RatzillaBackend::Dom(backend) => backend.append_lines(n),
             RatzillaBackend::Canvas(backend) => backend.append_lines(n),
             RatzillaBackend::WebGl2(backend) => backend.append_lines(n),
+            #[cfg(feature = "webgpu")]
+            RatzillaBackend::WebGpu(backend) => backend.append_lines(n),
         }
     }
 
@@ -162,6 +193,8 @@ impl Backend for RatzillaBackend {
             RatzillaBackend::Dom(backend) => backend.window_size(),
             RatzillaBackend::Canvas(backend) => backend.window_size(),
             RatzillaBackend::WebGl2(backend) => backend.window_size(),
+            #[cfg(feature = "webgpu")]
+            RatzillaBackend::WebGpu(backend) => backend.window_size(),
         }
     }
 
@@ -273,10 +306,13 @@ impl Backend for FpsTrackingBackend {
 /// # Backend Selection
 ///
 /// The builder uses the following priority order for backend selection:
-/// 1. `?backend=<type>` URL query parameter (dom, canvas, or webgl2)
+/// 1. `?backend=<type>` URL query parameter (dom, canvas, webgl2 or webgpu)
 /// 2. Fallback backend specified in `with_fallback()`
 /// 3. Default backend (DOM)
 ///
+/// The WebGPU backend falls back to the WebGL2 backend in browsers without
+/// WebGPU, so apps can prefer it with `with_fallback(BackendType::WebGpu)`.
+///
 /// # Example
 ///
 /// ```rust
@@ -301,6 +337,8 @@ pub struct MultiBackendBuilder {
     canvas_options: CanvasBackendOptions,
     dom_options: DomBackendOptions,
     webgl2_options: WebGl2BackendOptions,
+    #[cfg(feature = "webgpu")]
+    webgpu_options: WgpuBackendOptions,
 }
 
 impl MultiBackendBuilder {
@@ -349,6 +387,16 @@ impl MultiBackendBuilder {
         self
     }
 
+    /// Set options for the WebGPU backend.
+    ///
+    /// These options control WebGPU rendering behavior such as font settings
+    /// and cursor appearance.
+    #[cfg(feature = "webgpu")]
+    pub fn webgpu_options(mut self, options: WgpuBackendOptions) -> Self {
+        self.webgpu_options = options;
+        self
+    }
+
     /// Build the terminal with the configured options and backend selection.
     ///
     /// This method:
@@ -385,7 +433,11 @@ impl MultiBackendBuilder {
             Some(self.dom_options),
             Some(self.canvas_options),
             Some(self.webgl2_options),
+            #[cfg(feature = "webgpu")]
+            Some(self.webgpu_options),
         )?;
+        // the WebGPU backend may have fallen back to another one
+        let backend_type = backend.backend_type();
 
         // Initialize FPS recorder
         fps::init_fps_recorder();
@@ -410,7 +462,7 @@ impl From<BackendType> for MultiBackendBuilder {
 /// Parse the backend type from URL query parameters, with fallback to default.
 ///
 /// Checks for a `?backend=<type>` query parameter in the current page URL.
-/// Valid backend types are "dom", "canvas", and "webgl2" (case-insensitive).
+/// Valid backend types are "dom", "canvas", "webgl2" and "webgpu" (case-insensitive).
 /// If no valid backend is found in the URL, returns the provided default.
 fn parse_backend_from_url(default: BackendType) -> BackendType {
     window()
@@ -432,6 +484,9 @@ fn parse_backend_from_url(default: BackendType) -> BackendType {
 /// * `dom_options` - Configuration options for DOM backend (if applicable)
 /// * `canvas_options` - Configuration options for Canvas backend (if applicable)  
 /// * `webgl2_options` - Configuration options for WebGL2 backend (if applicable)
+/// * `webgpu_options` - Configuration options for WebGPU backend (if applicable)
+///
+/// The WebGPU backend falls back to the WebGL2 backend if it can't be created.
 ///
 /// # Returns
 ///
@@ -445,6 +500,7 @@ fn create_backend_with_options(
     dom_options: Option<DomBackendOptions>,
     canvas_options: Option<CanvasBackendOptions>,
     webgl2_options: Option<WebGl2BackendOptions>,
+    #[cfg(feature = "webgpu")] webgpu_options: Option<WgpuBackendOptions>,
 ) -> io::Result<RatzillaBackend> {
     use RatzillaBackend::*;
 
@@ -458,5 +514,14 @@ fn create_backend_with_options(
         BackendType::WebGl2 => Ok(WebGl2(WebGl2Backend::new_with_options(
             webgl2_options.unwrap_or_default(),
         )?)),
+        #[cfg(feature = "webgpu")]
+        BackendType::WebGpu => {
+            match WgpuBackend::new_with_options(webgpu_options.unwrap_or_default()) {
+                Ok(backend) => Ok(WebGpu(backend)),
+                Err(_) => Ok(WebGl2(WebGl2Backend::new_with_options(
+                    webgl2_options.unwrap_or_default(),
+                )?)),
+            }
+        }
     }
 }
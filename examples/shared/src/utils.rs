@@ -29,7 +29,13 @@ pub(crate) fn inject_backend_footer(current_backend: BackendType) -> Result<(),
     let location = window.location();
     let base_url = location.pathname().unwrap_or_default();
 
-    let backends = [BackendType::Dom, BackendType::Canvas, BackendType::WebGl2];
+    let backends = [
+        BackendType::Dom,
+        BackendType::Canvas,
+        BackendType::WebGl2,
+        #[cfg(feature = "webgpu")]
+        BackendType::WebGpu,
+    ];
     let mut links = Vec::new();
 
     for backend in backends {
@@ -0,0 +1,36 @@
+//! A minimal app built by `scripts/size-report.sh` with each backend
+//! feature to measure the size of the wasm binary.
+//!
+//! The backend is picked from the URL fragment (e.g. `#canvas`) so that every
+//! enabled backend ends up in the binary.
+
+use std::io;
+
+use ratzilla::{
+    app::App,
+    ratatui::{widgets::Paragraph, Frame},
+    web_sys,
+};
+
+struct Hello;
+
+impl App for Hello {
+    fn render(&mut self, frame: &mut Frame) {
+        frame.render_widget(Paragraph::new("Hello, Ratzilla!"), frame.area());
+    }
+}
+
+fn main() -> io::Result<()> {
+    let fragment = web_sys::window()
+        .and_then(|window| window.location().hash().ok())
+        .unwrap_or_default();
+    match fragment.trim_start_matches('#') {
+        #[cfg(feature = "dom")]
+        "dom" => ratzilla::run!(Hello, ratzilla::DomBackend::new()),
+        #[cfg(feature = "canvas")]
+        "canvas" => ratzilla::run!(Hello, ratzilla::CanvasBackend::new()),
+        #[cfg(feature = "webgl2")]
+        "webgl2" => ratzilla::run!(Hello, ratzilla::WebGl2Backend::new()),
+        backend => Err(io::Error::other(format!("Unknown backend: {backend}"))),
+    }
+}
@@ -1,6 +1,6 @@
 use std::{cell::RefCell, io, rc::Rc};
 
-use ratzilla::backend::cursor::CursorShape;
+use ratzilla::backend::cursor::{CursorOptions, CursorShape};
 use ratzilla::event::KeyEvent;
 use ratzilla::ratatui::layout::{Constraint, Layout, Position};
 use ratzilla::ratatui::style::{Modifier, Style, Stylize};
@@ -13,18 +13,27 @@ use ratzilla::ratatui::{
 };
 use ratzilla::{event::KeyCode, WebRenderer};
 use examples_shared::backend::{BackendType, MultiBackendBuilder};
+use ratzilla::backend::canvas::CanvasBackendOptions;
 use ratzilla::backend::dom::DomBackendOptions;
 use ratzilla::backend::webgl2::WebGl2BackendOptions;
 
 fn main() -> io::Result<()> {
-    let dom_options = DomBackendOptions::new(None, CursorShape::SteadyUnderScore);
+    let cursor = CursorOptions::new()
+        .shape(CursorShape::SteadyBar)
+        .blink(true)
+        .color(Color::Yellow);
+
+    let dom_options = DomBackendOptions::default().cursor_options(cursor);
+
+    let canvas_options = CanvasBackendOptions::new().cursor_options(cursor);
 
     let webgl2_options = WebGl2BackendOptions::new()
-        .cursor_shape(CursorShape::SteadyUnderScore)
+        .cursor_options(cursor)
         .enable_console_debug_api()
         .enable_mouse_selection();
 
     let terminal = MultiBackendBuilder::with_fallback(BackendType::Dom)
+        .canvas_options(canvas_options)
         .dom_options(dom_options)
         .webgl2_options(webgl2_options)
         .build_terminal()?;
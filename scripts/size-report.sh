@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+#
+# Prints the size of the wasm binary of a minimal app for each backend
+# feature, and for all of them together.
+#
+# Fails if a binary with a single backend is not smaller than the one with
+# every backend, which means that the features no longer strip the unused
+# backends.
+
+set -euo pipefail
+
+cd "$(dirname "$0")/../examples"
+
+target_dir="target/wasm32-unknown-unknown/release"
+report="| Features | Size (bytes) |\n|---|---|\n"
+
+# Builds the app with the given features and sets `size` to its size.
+build() {
+  cargo build --quiet --release --target wasm32-unknown-unknown \
+    -p size_report --features "$1"
+  size=$(wc -c <"$target_dir/size_report.wasm" | tr -d ' ')
+  report+="| \`$1\` | $size |\n"
+}
+
+build "dom,canvas,webgl2"
+all=$size
+status=0
+for feature in dom canvas webgl2; do
+  build "$feature"
+  if [ "$size" -ge "$all" ]; then
+    echo "error: the \`$feature\` build ($size bytes) is not smaller than the build with every backend ($all bytes)" >&2
+    status=1
+  fi
+done
+
+printf "%b" "$report"
+if [ -n "${GITHUB_STEP_SUMMARY:-}" ]; then
+  printf "## Wasm size report\n\n%b" "$report" >>"$GITHUB_STEP_SUMMARY"
+fi
+exit "$status"
@@ -0,0 +1,372 @@
+//! ## Actions
+//!
+//! A registry of terminal actions, e.g. copying the selection or toggling
+//! fullscreen, that command palettes, keymaps and context menus can all
+//! surface. Every action has an id, a title and an optional default key.
+//!
+//! The built-in actions that only need the browser work out of the box:
+//! [copy](crate::actions::COPY), [paste](crate::actions::PASTE) and
+//! [fullscreen](crate::actions::FULLSCREEN). The others depend on the app,
+//! e.g. what a theme is, and are disabled until the app
+//! [sets their handler](crate::actions::set_handler):
+//!
+//! ```rust no_run
+//! use ratzilla::{actions, keymap::{Keymap, GLOBAL}, WebRenderer};
+//! # fn example(terminal: ratzilla::ratatui::Terminal<ratzilla::DomBackend>) {
+//!
+//! actions::set_handler(actions::SWITCH_THEME, || { /* switch the theme */ });
+//! actions::register("my-app.reload", "Reload", Some("ctrl+r"), || { /* ... */ }).unwrap();
+//!
+//! let mut keymap = Keymap::new();
+//! actions::bind_keys(&mut keymap, GLOBAL);
+//! terminal.on_key_event(move |event| {
+//!     if let Some(id) = keymap.handle_key_event(&event, GLOBAL) {
+//!         actions::run(&id);
+//!     }
+//! });
+//! // e.g. the items of a command palette
+//! for action in actions::list().iter().filter(|action| action.enabled) {
+//!     // ...
+//! }
+//! # }
+//! ```
+
+use std::{cell::RefCell, rc::Rc};
+
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+
+use crate::{
+    backend::utils::{get_document, get_window},
+    error::Error,
+    keymap::{KeyBinding, Keymap},
+    selection, teardown,
+};
+
+/// Copies the selected text to the clipboard.
+pub const COPY: &str = "copy";
+
+/// Pastes the text of the clipboard, as a paste event of the app.
+pub const PASTE: &str = "paste";
+
+/// Searches the content of the terminal.
+pub const SEARCH: &str = "search";
+
+/// Increases the font size.
+pub const ZOOM_IN: &str = "zoom-in";
+
+/// Decreases the font size.
+pub const ZOOM_OUT: &str = "zoom-out";
+
+/// Resets the font size.
+pub const ZOOM_RESET: &str = "zoom-reset";
+
+/// Switches to the next theme.
+pub const SWITCH_THEME: &str = "switch-theme";
+
+/// Saves a screenshot of the terminal.
+pub const SCREENSHOT: &str = "screenshot";
+
+/// Toggles the fullscreen mode of the page.
+pub const FULLSCREEN: &str = "fullscreen";
+
+/// Shows information about the backend, e.g. its memory usage.
+pub const BACKEND_INFO: &str = "backend-info";
+
+/// The built-in actions: id, title and default key.
+const BUILTINS: [(&str, &str, Option<&str>); 10] = [
+    (COPY, "Copy", Some("ctrl+shift+c")),
+    (PASTE, "Paste", Some("ctrl+shift+v")),
+    (SEARCH, "Search", Some("ctrl+shift+f")),
+    (ZOOM_IN, "Zoom in", Some("ctrl+=")),
+    (ZOOM_OUT, "Zoom out", Some("ctrl+-")),
+    (ZOOM_RESET, "Reset zoom", Some("ctrl+0")),
+    (SWITCH_THEME, "Switch theme", Some("ctrl+shift+t")),
+    (SCREENSHOT, "Take a screenshot", Some("ctrl+shift+s")),
+    (FULLSCREEN, "Toggle fullscreen", Some("f11")),
+    (BACKEND_INFO, "Show backend info", None),
+];
+
+/// An action of the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Action {
+    /// Unique id, e.g. [`COPY`].
+    pub id: String,
+    /// Human readable title.
+    pub title: String,
+    /// Default key of the action, if any.
+    pub key: Option<KeyBinding>,
+    /// Whether the action is built into Ratzilla.
+    pub builtin: bool,
+    /// Whether the action has a handler, i.e. can be run.
+    pub enabled: bool,
+}
+
+/// Handler of an action.
+type Handler = Rc<RefCell<dyn FnMut()>>;
+
+/// Callback called with pasted text.
+type PasteCallback = Rc<RefCell<dyn FnMut(String)>>;
+
+/// An action and its handler.
+struct Entry {
+    /// The action.
+    action: Action,
+    /// Handler of the action, if any.
+    handler: Option<Handler>,
+}
+
+/// State of the registry.
+struct Registry {
+    /// Registered actions, in registration order.
+    entries: Vec<Entry>,
+    /// Paste callbacks of the app, called by the [`PASTE`] action.
+    paste_callbacks: Vec<PasteCallback>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let entries = BUILTINS
+            .iter()
+            .map(|&(id, title, key)| {
+                let handler: Option<Handler> = match id {
+                    COPY => Some(Rc::new(RefCell::new(|| {
+                        let _ = selection::copy();
+                    }))),
+                    PASTE => Some(Rc::new(RefCell::new(paste))),
+                    FULLSCREEN => Some(Rc::new(RefCell::new(|| {
+                        let _ = toggle_fullscreen();
+                    }))),
+                    _ => None,
+                };
+                Entry {
+                    action: Action {
+                        id: id.to_string(),
+                        title: title.to_string(),
+                        key: key.and_then(|key| key.parse().ok()),
+                        builtin: true,
+                        enabled: handler.is_some(),
+                    },
+                    handler,
+                }
+            })
+            .collect();
+        Self {
+            entries,
+            paste_callbacks: Vec::new(),
+        }
+    }
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::new(Registry::default());
+}
+
+/// Registers an action, or replaces the action with the same id.
+///
+/// The key is given as a string such as `"ctrl+s"`, see [`KeyBinding`] for
+/// the syntax.
+pub fn register<F>(id: &str, title: &str, key: Option<&str>, handler: F) -> Result<(), Error>
+where
+    F: FnMut() + 'static,
+{
+    let key = key.map(str::parse::<KeyBinding>).transpose()?;
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        let builtin = BUILTINS.iter().any(|(builtin, _, _)| *builtin == id);
+        let entry = Entry {
+            action: Action {
+                id: id.to_string(),
+                title: title.to_string(),
+                key,
+                builtin,
+                enabled: true,
+            },
+            handler: Some(Rc::new(RefCell::new(handler))),
+        };
+        match registry.entries.iter_mut().find(|e| e.action.id == id) {
+            Some(existing) => *existing = entry,
+            None => registry.entries.push(entry),
+        }
+    });
+    Ok(())
+}
+
+/// Sets the handler of an action, enabling it.
+///
+/// Returns `false` if there is no action with the given id.
+pub fn set_handler<F>(id: &str, handler: F) -> bool
+where
+    F: FnMut() + 'static,
+{
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        let Some(entry) = registry.entries.iter_mut().find(|e| e.action.id == id) else {
+            return false;
+        };
+        entry.handler = Some(Rc::new(RefCell::new(handler)));
+        entry.action.enabled = true;
+        true
+    })
+}
+
+/// Removes an action, or disables a built-in one.
+pub fn unregister(id: &str) {
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        registry
+            .entries
+            .retain(|e| e.action.id != id || e.action.builtin);
+        if let Some(entry) = registry.entries.iter_mut().find(|e| e.action.id == id) {
+            entry.handler = None;
+            entry.action.enabled = false;
+        }
+    });
+}
+
+/// Runs the action with the given id.
+///
+/// Returns `false` if the action doesn't exist or is disabled.
+pub fn run(id: &str) -> bool {
+    let handler = REGISTRY.with(|registry| {
+        let registry = registry.borrow();
+        let entry = registry.entries.iter().find(|e| e.action.id == id)?;
+        entry.handler.clone()
+    });
+    let Some(handler) = handler else {
+        return false;
+    };
+    // outside of the borrow, handlers may register other actions
+    (handler.borrow_mut())();
+    true
+}
+
+/// Returns the action with the given id.
+pub fn get(id: &str) -> Option<Action> {
+    REGISTRY.with(|registry| {
+        let registry = registry.borrow();
+        let entry = registry.entries.iter().find(|e| e.action.id == id)?;
+        Some(entry.action.clone())
+    })
+}
+
+/// Returns all actions, in registration order, built-in actions first.
+pub fn list() -> Vec<Action> {
+    REGISTRY.with(|registry| {
+        let registry = registry.borrow();
+        registry.entries.iter().map(|e| e.action.clone()).collect()
+    })
+}
+
+/// Binds the default keys of the enabled actions to their ids in the given
+/// context of a keymap, so they show up in its help overlay.
+pub fn bind_keys(keymap: &mut Keymap<String>, context: &str) {
+    for action in list().into_iter().filter(|action| action.enabled) {
+        if let Some(key) = action.key {
+            keymap.bind_key(context, key, action.id, &action.title);
+        }
+    }
+}
+
+/// Calls the given callback with the text pasted by the [`PASTE`] action.
+///
+/// Registered by [`WebRenderer::on_paste_event`](crate::WebRenderer::on_paste_event).
+pub(crate) fn on_paste(callback: PasteCallback) {
+    REGISTRY.with(|registry| registry.borrow_mut().paste_callbacks.push(callback));
+    teardown::on_destroy(|| {
+        REGISTRY.with(|registry| registry.borrow_mut().paste_callbacks.clear());
+    });
+}
+
+/// Reads the text of the clipboard and passes it to the paste callbacks.
+fn paste() {
+    let Ok(window) = get_window() else {
+        return;
+    };
+    let promise = window.navigator().clipboard().read_text();
+    spawn_local(async move {
+        // denied permission or empty clipboard
+        let Ok(text) = JsFuture::from(promise).await else {
+            return;
+        };
+        let Some(text) = text.as_string().filter(|text| !text.is_empty()) else {
+            return;
+        };
+        let callbacks = REGISTRY.with(|registry| registry.borrow().paste_callbacks.clone());
+        for callback in callbacks {
+            (callback.borrow_mut())(text.clone());
+        }
+    });
+}
+
+/// Enters the fullscreen mode of the page, or leaves it.
+fn toggle_fullscreen() -> Result<(), Error> {
+    let document = get_document()?;
+    if document.fullscreen_element().is_some() {
+        document.exit_fullscreen();
+    } else if let Some(element) = document.document_element() {
+        element.request_fullscreen()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{KeyCode, KeyEvent};
+
+    #[test]
+    fn test_builtins() {
+        let copy = get(COPY).expect("builtin action");
+        assert!(copy.builtin && copy.enabled);
+        assert_eq!(copy.key.expect("default key").to_string(), "Ctrl+Shift+C");
+        // depends on the app
+        assert!(!get(SWITCH_THEME).expect("builtin action").enabled);
+        assert!(!run(SWITCH_THEME));
+        assert!(BUILTINS.iter().all(|(id, _, _)| get(id).is_some()));
+    }
+
+    #[test]
+    fn test_register_and_run() {
+        let count = Rc::new(RefCell::new(0));
+        assert!(set_handler(ZOOM_IN, {
+            let count = count.clone();
+            move || *count.borrow_mut() += 1
+        }));
+        assert!(run(ZOOM_IN));
+        register("app.reload", "Reload", Some("ctrl+r"), {
+            let count = count.clone();
+            move || *count.borrow_mut() += 10
+        })
+        .expect("valid key binding");
+        assert!(run("app.reload"));
+        assert_eq!(*count.borrow(), 11);
+        assert!(register("app.bad", "Bad", Some("hyper+r"), || {}).is_err());
+
+        unregister("app.reload");
+        assert!(get("app.reload").is_none());
+        unregister(ZOOM_IN);
+        assert!(!get(ZOOM_IN).expect("builtin action").enabled);
+        assert!(!run("missing"));
+    }
+
+    #[test]
+    fn test_bind_keys() {
+        set_handler(SEARCH, || {});
+        let mut keymap = Keymap::new();
+        bind_keys(&mut keymap, "terminal");
+        let event = KeyEvent {
+            code: KeyCode::Char('f'),
+            ctrl: true,
+            alt: false,
+            shift: true,
+            meta: false,
+            repeat: false,
+        };
+        assert_eq!(
+            keymap.resolve(&event, "terminal"),
+            Some(&SEARCH.to_string())
+        );
+        // disabled actions are not bound
+        assert!(keymap.bindings().iter().all(|b| b.action != SCREENSHOT));
+    }
+}
@@ -0,0 +1,320 @@
+//! ## Adaptive quality
+//!
+//! On low-end devices, an app may not be able to draw frames as fast as the
+//! browser requests them. When enabled, the adaptive mode monitors the time
+//! it takes to draw frames and lowers the [`QualityLevel`] when they take
+//! longer than a budget, then raises it again once the device keeps up.
+//!
+//! Each level lowers the cost of the app:
+//!
+//! - The render loop of [`draw_web`] skips frames to stay under the frame
+//!   rate cap of the level.
+//! - The [`EffectScheduler`] pauses the effects on the levels that disable
+//!   them.
+//! - The app is informed of the changes with [`on_quality_change`], e.g. to
+//!   render simpler widgets or to switch to a cheaper backend.
+//!
+//! ```rust no_run
+//! use ratzilla::adaptive::{enable_adaptive_quality, on_quality_change, AdaptiveOptions};
+//!
+//! enable_adaptive_quality(AdaptiveOptions::new(16.7));
+//! on_quality_change(|level| {
+//!     web_sys::console::log_1(&format!("quality: {level:?}").into());
+//! });
+//! ```
+//!
+//! [`draw_web`]: crate::WebRenderer::draw_web
+//! [`EffectScheduler`]: https://docs.rs/ratzilla/latest/ratzilla/fx/struct.EffectScheduler.html
+//! [`QualityLevel`]: crate::adaptive::QualityLevel
+//! [`on_quality_change`]: crate::adaptive::on_quality_change
+
+use std::cell::RefCell;
+
+/// Default number of frames averaged before changing the quality level.
+const DEFAULT_WINDOW: usize = 60;
+
+/// Default number of consecutive fast windows before raising the quality
+/// level.
+const DEFAULT_RECOVERY_WINDOWS: usize = 5;
+
+/// Quality levels, from the most to the least expensive.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QualityLevel {
+    /// Every frame is drawn, with effects.
+    #[default]
+    Full,
+    /// At most 30 frames per second, with effects.
+    Reduced,
+    /// At most 20 frames per second, without effects.
+    Low,
+    /// At most 10 frames per second, without effects.
+    Minimal,
+}
+
+impl QualityLevel {
+    /// Returns the frame rate cap of the level, if any.
+    pub fn max_fps(self) -> Option<f64> {
+        match self {
+            QualityLevel::Full => None,
+            QualityLevel::Reduced => Some(30.0),
+            QualityLevel::Low => Some(20.0),
+            QualityLevel::Minimal => Some(10.0),
+        }
+    }
+
+    /// Returns `true` if effects are enabled on the level.
+    pub fn effects(self) -> bool {
+        self <= QualityLevel::Reduced
+    }
+
+    /// Returns the next cheaper level, if any.
+    pub fn lower(self) -> Option<Self> {
+        match self {
+            QualityLevel::Full => Some(QualityLevel::Reduced),
+            QualityLevel::Reduced => Some(QualityLevel::Low),
+            QualityLevel::Low => Some(QualityLevel::Minimal),
+            QualityLevel::Minimal => None,
+        }
+    }
+
+    /// Returns the next more expensive level, if any.
+    pub fn higher(self) -> Option<Self> {
+        match self {
+            QualityLevel::Full => None,
+            QualityLevel::Reduced => Some(QualityLevel::Full),
+            QualityLevel::Low => Some(QualityLevel::Reduced),
+            QualityLevel::Minimal => Some(QualityLevel::Low),
+        }
+    }
+}
+
+/// Options of the adaptive quality mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveOptions {
+    /// Frame time budget, in milliseconds.
+    budget_ms: f64,
+    /// Number of frames averaged before changing the level.
+    window: usize,
+    /// Number of consecutive fast windows before raising the level.
+    recovery_windows: usize,
+    /// The cheapest level to lower the quality to.
+    min_level: QualityLevel,
+}
+
+impl AdaptiveOptions {
+    /// Constructs new [`AdaptiveOptions`] with the given frame time budget,
+    /// in milliseconds.
+    pub fn new(budget_ms: f64) -> Self {
+        Self {
+            budget_ms,
+            window: DEFAULT_WINDOW,
+            recovery_windows: DEFAULT_RECOVERY_WINDOWS,
+            min_level: QualityLevel::Minimal,
+        }
+    }
+
+    /// Sets the number of frames averaged before changing the level.
+    pub fn window(mut self, frames: usize) -> Self {
+        self.window = frames.max(1);
+        self
+    }
+
+    /// Sets the number of consecutive windows under half the budget before
+    /// raising the level.
+    pub fn recovery_windows(mut self, windows: usize) -> Self {
+        self.recovery_windows = windows.max(1);
+        self
+    }
+
+    /// Sets the cheapest level to lower the quality to.
+    pub fn min_level(mut self, level: QualityLevel) -> Self {
+        self.min_level = level;
+        self
+    }
+}
+
+/// Callback informed of quality level changes.
+type QualityListener = Box<dyn FnMut(QualityLevel)>;
+
+/// State of the adaptive mode.
+#[derive(Debug)]
+struct Controller {
+    /// Options.
+    options: AdaptiveOptions,
+    /// Current level.
+    level: QualityLevel,
+    /// Sum of the frame times of the current window, in milliseconds.
+    total_ms: f64,
+    /// Number of frames in the current window.
+    frames: usize,
+    /// Consecutive windows under half the budget.
+    fast_windows: usize,
+    /// Timestamp of the last drawn frame, in milliseconds.
+    last_draw: Option<f64>,
+}
+
+impl Controller {
+    /// Constructs a new [`Controller`].
+    fn new(options: AdaptiveOptions) -> Self {
+        Self {
+            options,
+            level: QualityLevel::Full,
+            total_ms: 0.0,
+            frames: 0,
+            fast_windows: 0,
+            last_draw: None,
+        }
+    }
+
+    /// Returns `true` if a frame should be drawn at the given time to stay
+    /// under the frame rate cap.
+    fn should_draw(&mut self, now_ms: f64) -> bool {
+        let draw = match (self.level.max_fps(), self.last_draw) {
+            // a small tolerance, since animation frames aren't exactly periodic
+            (Some(fps), Some(last)) => now_ms - last >= 1000.0 / fps - 1.0,
+            _ => true,
+        };
+        if draw {
+            self.last_draw = Some(now_ms);
+        }
+        draw
+    }
+
+    /// Records the time it took to draw a frame and returns the new level if
+    /// it changed.
+    fn record(&mut self, duration_ms: f64) -> Option<QualityLevel> {
+        self.total_ms += duration_ms;
+        self.frames += 1;
+        if self.frames < self.options.window {
+            return None;
+        }
+        let average = self.total_ms / self.frames as f64;
+        self.total_ms = 0.0;
+        self.frames = 0;
+
+        let next = if average > self.options.budget_ms {
+            self.fast_windows = 0;
+            self.level
+                .lower()
+                .filter(|&level| level <= self.options.min_level)
+        } else if average < self.options.budget_ms / 2.0 {
+            self.fast_windows += 1;
+            if self.fast_windows < self.options.recovery_windows {
+                return None;
+            }
+            self.fast_windows = 0;
+            self.level.higher()
+        } else {
+            self.fast_windows = 0;
+            None
+        }?;
+        self.level = next;
+        Some(next)
+    }
+}
+
+thread_local! {
+    static CONTROLLER: RefCell<Option<Controller>> = const { RefCell::new(None) };
+    static LISTENERS: RefCell<Vec<QualityListener>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Enables the adaptive quality mode, starting at [`QualityLevel::Full`].
+pub fn enable_adaptive_quality(options: AdaptiveOptions) {
+    CONTROLLER.with(|c| *c.borrow_mut() = Some(Controller::new(options)));
+}
+
+/// Disables the adaptive quality mode, restoring [`QualityLevel::Full`].
+pub fn disable_adaptive_quality() {
+    let was_lowered = CONTROLLER.with(|c| {
+        c.borrow_mut()
+            .take()
+            .is_some_and(|c| c.level != QualityLevel::Full)
+    });
+    if was_lowered {
+        notify(QualityLevel::Full);
+    }
+}
+
+/// Returns the current quality level.
+///
+/// This is always [`QualityLevel::Full`] when the adaptive mode is disabled.
+pub fn quality() -> QualityLevel {
+    CONTROLLER.with(|c| c.borrow().as_ref().map_or(QualityLevel::Full, |c| c.level))
+}
+
+/// Registers a callback informed of quality level changes.
+pub fn on_quality_change<F>(callback: F)
+where
+    F: FnMut(QualityLevel) + 'static,
+{
+    LISTENERS.with(|l| l.borrow_mut().push(Box::new(callback)));
+}
+
+/// Returns `true` if a frame should be drawn now.
+pub(crate) fn should_draw(now_ms: f64) -> bool {
+    CONTROLLER.with(|c| {
+        c.borrow_mut()
+            .as_mut()
+            .is_none_or(|c| c.should_draw(now_ms))
+    })
+}
+
+/// Records the time it took to draw a frame, in milliseconds.
+pub(crate) fn record_frame(duration_ms: f64) {
+    let changed = CONTROLLER.with(|c| c.borrow_mut().as_mut()?.record(duration_ms));
+    if let Some(level) = changed {
+        notify(level);
+    }
+}
+
+/// Calls the listeners with the new quality level.
+fn notify(level: QualityLevel) {
+    // listeners may register other listeners, so don't hold the borrow
+    let mut listeners = LISTENERS.with(|l| std::mem::take(&mut *l.borrow_mut()));
+    for listener in &mut listeners {
+        listener(level);
+    }
+    LISTENERS.with(|l| {
+        let mut l = l.borrow_mut();
+        listeners.append(&mut l);
+        *l = listeners;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lowers_and_recovers() {
+        let mut controller = Controller::new(
+            AdaptiveOptions::new(10.0)
+                .window(2)
+                .recovery_windows(2)
+                .min_level(QualityLevel::Low),
+        );
+        assert_eq!(controller.record(30.0), None);
+        assert_eq!(controller.record(30.0), Some(QualityLevel::Reduced));
+        assert_eq!(controller.record(30.0), None);
+        assert_eq!(controller.record(30.0), Some(QualityLevel::Low));
+        // already at the cheapest allowed level
+        assert_eq!(controller.record(30.0), None);
+        assert_eq!(controller.record(30.0), None);
+
+        for _ in 0..3 {
+            assert_eq!(controller.record(1.0), None);
+        }
+        assert_eq!(controller.record(1.0), Some(QualityLevel::Reduced));
+    }
+
+    #[test]
+    fn test_frame_rate_cap() {
+        let mut controller = Controller::new(AdaptiveOptions::new(10.0));
+        assert!(controller.should_draw(0.0));
+        assert!(controller.should_draw(16.0));
+
+        controller.level = QualityLevel::Low;
+        assert!(!controller.should_draw(32.0));
+        assert!(controller.should_draw(66.0));
+    }
+}
@@ -0,0 +1,199 @@
+//! ## Analytics
+//!
+//! Ratzilla reports a few structured events about the running app to an
+//! optional, user supplied sink, so site owners can forward them to their
+//! analytics service of choice.
+//!
+//! No events are collected until a sink is set with [`set_analytics_sink`].
+//! Key presses are only counted; the pressed keys are never reported.
+//!
+//! ```rust no_run
+//! use ratzilla::analytics::{set_analytics_sink, AnalyticsEvent};
+//!
+//! set_analytics_sink(|event: &AnalyticsEvent| {
+//!     web_sys::console::log_1(&format!("{event:?}").into());
+//! });
+//! ```
+//!
+//! [`set_analytics_sink`]: crate::analytics::set_analytics_sink
+
+use std::cell::RefCell;
+
+use crate::clock;
+
+/// Interval between two reports of the aggregated statistics, in milliseconds.
+const FLUSH_INTERVAL_MS: f64 = 10_000.0;
+
+/// Upper bounds of the frame time buckets, in milliseconds.
+///
+/// The last bucket holds all frames slower than the last bound.
+pub const FRAME_TIME_BUCKETS: [f64; 3] = [16.7, 33.3, 50.0];
+
+/// An event reported to the analytics sink.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnalyticsEvent {
+    /// The render loop started.
+    AppStarted,
+    /// A backend was created.
+    BackendSelected {
+        /// Name of the backend, e.g. `"dom"`.
+        backend: &'static str,
+    },
+    /// Number of key presses since the last report.
+    KeyPresses {
+        /// Number of key presses.
+        count: u32,
+    },
+    /// The route of the [`Router`](crate::router::Router) changed.
+    RouteChanged {
+        /// The new route.
+        route: String,
+    },
+    /// Frame time distribution since the last report.
+    FrameStats {
+        /// Number of frames in each bucket of [`FRAME_TIME_BUCKETS`].
+        buckets: [u32; 4],
+    },
+}
+
+/// Callback receiving analytics events.
+type AnalyticsSink = Box<dyn FnMut(&AnalyticsEvent)>;
+
+/// Aggregated statistics, reported periodically.
+#[derive(Debug, Default)]
+struct Stats {
+    /// Key presses since the last report.
+    key_presses: u32,
+    /// Frame time buckets since the last report.
+    buckets: [u32; 4],
+    /// Timestamp of the last report, in milliseconds.
+    last_flush: Option<f64>,
+}
+
+thread_local! {
+    static SINK: RefCell<Option<AnalyticsSink>> = RefCell::new(None);
+    static STATS: RefCell<Stats> = RefCell::new(Stats::default());
+}
+
+/// Sets the sink receiving analytics events, replacing the previous one.
+pub fn set_analytics_sink<F>(sink: F)
+where
+    F: FnMut(&AnalyticsEvent) + 'static,
+{
+    SINK.with(|s| *s.borrow_mut() = Some(Box::new(sink)));
+}
+
+/// Removes the analytics sink and discards the pending statistics.
+pub fn clear_analytics_sink() {
+    SINK.with(|s| *s.borrow_mut() = None);
+    STATS.with(|s| *s.borrow_mut() = Stats::default());
+}
+
+/// Returns `true` if an analytics sink is set.
+fn is_enabled() -> bool {
+    SINK.with(|s| s.borrow().is_some())
+}
+
+/// Reports an event to the sink, if any.
+pub(crate) fn report(event: AnalyticsEvent) {
+    // the sink may report events itself, so don't hold the borrow while calling it
+    let Some(mut sink) = SINK.with(|s| s.borrow_mut().take()) else {
+        return;
+    };
+    sink(&event);
+    SINK.with(|s| {
+        let mut s = s.borrow_mut();
+        if s.is_none() {
+            *s = Some(sink);
+        }
+    });
+}
+
+/// Counts a key press.
+pub(crate) fn record_key_press() {
+    if is_enabled() {
+        STATS.with(|s| s.borrow_mut().key_presses += 1);
+    }
+}
+
+/// Records the time it took to draw a frame, in milliseconds.
+///
+/// The aggregated statistics are reported every [`FLUSH_INTERVAL_MS`].
+pub(crate) fn record_frame(duration_ms: f64) {
+    if !is_enabled() {
+        return;
+    }
+    let now = clock::now();
+    let events = STATS.with(|s| {
+        let mut stats = s.borrow_mut();
+        stats.buckets[bucket_index(duration_ms)] += 1;
+        match stats.last_flush {
+            Some(last) if now - last < FLUSH_INTERVAL_MS => Vec::new(),
+            _ => {
+                stats.last_flush = Some(now);
+                stats.flush()
+            }
+        }
+    });
+    events.into_iter().for_each(report);
+}
+
+impl Stats {
+    /// Returns the events for the aggregated statistics and resets them.
+    fn flush(&mut self) -> Vec<AnalyticsEvent> {
+        let mut events = Vec::new();
+        if self.key_presses > 0 {
+            events.push(AnalyticsEvent::KeyPresses {
+                count: self.key_presses,
+            });
+        }
+        if self.buckets.iter().any(|&b| b > 0) {
+            events.push(AnalyticsEvent::FrameStats {
+                buckets: self.buckets,
+            });
+        }
+        self.key_presses = 0;
+        self.buckets = [0; 4];
+        events
+    }
+}
+
+/// Returns the index of the frame time bucket for the given duration.
+fn bucket_index(duration_ms: f64) -> usize {
+    FRAME_TIME_BUCKETS
+        .iter()
+        .position(|&bound| duration_ms < bound)
+        .unwrap_or(FRAME_TIME_BUCKETS.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_index() {
+        assert_eq!(bucket_index(5.0), 0);
+        assert_eq!(bucket_index(20.0), 1);
+        assert_eq!(bucket_index(40.0), 2);
+        assert_eq!(bucket_index(120.0), 3);
+    }
+
+    #[test]
+    fn test_stats_flush() {
+        let mut stats = Stats {
+            key_presses: 3,
+            buckets: [1, 0, 0, 2],
+            last_flush: None,
+        };
+        assert_eq!(
+            stats.flush(),
+            vec![
+                AnalyticsEvent::KeyPresses { count: 3 },
+                AnalyticsEvent::FrameStats {
+                    buckets: [1, 0, 0, 2]
+                },
+            ]
+        );
+        assert!(stats.flush().is_empty());
+    }
+}
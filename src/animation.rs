@@ -0,0 +1,296 @@
+//! ## Animation
+//!
+//! An [`Animated`] value moves smoothly to each new target instead of
+//! jumping to it, e.g. a scroll offset, the progress of a bar or a color:
+//!
+//! ```rust no_run
+//! use ratzilla::animation::{Animated, Easing};
+//!
+//! let mut scroll = Animated::new(0u16).duration(150.0).easing(Easing::CubicOut);
+//! // on a key press
+//! scroll.set(10);
+//! // in the render callback
+//! let offset = scroll.value();
+//! ```
+//!
+//! The values are driven by the [clock](crate::clock), so animations are
+//! deterministic with a [`ManualClock`](crate::clock::ManualClock). Reading
+//! a value that is still moving requests a redraw, which keeps the
+//! [on-demand](crate::render_loop::RenderLoopOptions::on_demand) render loop
+//! running until the animation is done.
+//!
+//! Unlike the [effects] of tachyonfx, which transform the cells of a frame,
+//! this animates the state the widgets are rendered from.
+//!
+//! [effects]: https://docs.rs/ratzilla/latest/ratzilla/fx/index.html
+//! [`Animated`]: crate::animation::Animated
+
+use ratatui::style::Color;
+
+use crate::{backend::color::to_rgb, clock, render_loop};
+
+/// Default duration of an animation, in milliseconds.
+const DEFAULT_DURATION_MS: f64 = 200.0;
+
+/// How the progress of an animation accelerates.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Starts slowly.
+    QuadIn,
+    /// Ends slowly.
+    QuadOut,
+    /// Starts and ends slowly.
+    #[default]
+    QuadInOut,
+    /// Starts very slowly.
+    CubicIn,
+    /// Ends very slowly.
+    CubicOut,
+    /// Starts and ends very slowly.
+    CubicInOut,
+}
+
+impl Easing {
+    /// Returns the eased progress, from `0.0` to `1.0`, of a linear progress.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::QuadIn => t * t,
+            Self::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::QuadInOut if t < 0.5 => 2.0 * t * t,
+            Self::QuadInOut => 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0,
+            Self::CubicIn => t * t * t,
+            Self::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Self::CubicInOut if t < 0.5 => 4.0 * t * t * t,
+            Self::CubicInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+/// A value that can be interpolated.
+pub trait Interpolate {
+    /// Returns the value at `progress` between `self` (at `0.0`) and `to`
+    /// (at `1.0`).
+    fn interpolate(&self, to: &Self, progress: f64) -> Self;
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, to: &Self, progress: f64) -> Self {
+        self + (to - self) * progress
+    }
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, to: &Self, progress: f64) -> Self {
+        self + (to - self) * progress as f32
+    }
+}
+
+/// Implements [`Interpolate`] for integers, rounding to the nearest one.
+macro_rules! impl_interpolate_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl Interpolate for $ty {
+                fn interpolate(&self, to: &Self, progress: f64) -> Self {
+                    (*self as f64).interpolate(&(*to as f64), progress).round() as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolate_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Colors are interpolated in RGB. [`Color::Reset`] has no RGB value, so an
+/// animation from or to it switches halfway through.
+impl Interpolate for Color {
+    fn interpolate(&self, to: &Self, progress: f64) -> Self {
+        if *self == Color::Reset || *to == Color::Reset {
+            return if progress < 0.5 { *self } else { *to };
+        }
+        let (from, to) = (to_rgb(*self, 0), to_rgb(*to, 0));
+        let channel = |shift: u32| {
+            let from = ((from >> shift) & 0xff) as u8;
+            let to = ((to >> shift) & 0xff) as u8;
+            from.interpolate(&to, progress)
+        };
+        Color::Rgb(channel(16), channel(8), channel(0))
+    }
+}
+
+impl<A: Interpolate, B: Interpolate> Interpolate for (A, B) {
+    fn interpolate(&self, to: &Self, progress: f64) -> Self {
+        (
+            self.0.interpolate(&to.0, progress),
+            self.1.interpolate(&to.1, progress),
+        )
+    }
+}
+
+/// A value animated to its target over time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animated<T> {
+    /// Value at the start of the animation.
+    from: T,
+    /// Value at the end of the animation.
+    to: T,
+    /// Start time of the animation, in milliseconds.
+    start: f64,
+    /// Duration of the animations, in milliseconds.
+    duration: f64,
+    /// Easing of the animations.
+    easing: Easing,
+}
+
+impl<T: Interpolate + Clone> Animated<T> {
+    /// Constructs a new [`Animated`] value, not moving.
+    pub fn new(value: T) -> Self {
+        Self {
+            from: value.clone(),
+            to: value,
+            start: f64::NEG_INFINITY,
+            duration: DEFAULT_DURATION_MS,
+            easing: Easing::default(),
+        }
+    }
+
+    /// Sets the duration of the animations, in milliseconds.
+    pub fn duration(mut self, ms: f64) -> Self {
+        self.duration = ms.max(0.0);
+        self
+    }
+
+    /// Sets the easing of the animations.
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Starts animating from the current value to the given target.
+    pub fn set(&mut self, target: T) {
+        let now = clock::now();
+        self.from = self.value_at(now);
+        self.to = target;
+        self.start = now;
+        render_loop::request_redraw();
+    }
+
+    /// Sets the value without animating.
+    pub fn jump(&mut self, value: T) {
+        self.from = value.clone();
+        self.to = value;
+        self.start = f64::NEG_INFINITY;
+    }
+
+    /// Returns the current value.
+    ///
+    /// A redraw is requested while the value is moving.
+    pub fn value(&self) -> T {
+        let now = clock::now();
+        if self.is_animating_at(now) {
+            render_loop::request_redraw();
+        }
+        self.value_at(now)
+    }
+
+    /// Returns the value the animation ends at.
+    pub fn target(&self) -> &T {
+        &self.to
+    }
+
+    /// Returns `true` while the value is moving.
+    pub fn is_animating(&self) -> bool {
+        self.is_animating_at(clock::now())
+    }
+
+    /// Returns `true` if the value is moving at the given time.
+    fn is_animating_at(&self, now: f64) -> bool {
+        now - self.start < self.duration
+    }
+
+    /// Returns the value at the given time.
+    fn value_at(&self, now: f64) -> T {
+        if !self.is_animating_at(now) {
+            return self.to.clone();
+        }
+        let progress = self.easing.apply((now - self.start) / self.duration);
+        self.from.interpolate(&self.to, progress)
+    }
+}
+
+impl<T: Interpolate + Clone + Default> Default for Animated<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{reset_clock, set_clock, ManualClock};
+
+    #[test]
+    fn test_easing() {
+        for easing in [
+            Easing::Linear,
+            Easing::QuadIn,
+            Easing::QuadOut,
+            Easing::QuadInOut,
+            Easing::CubicIn,
+            Easing::CubicOut,
+            Easing::CubicInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+        assert_eq!(Easing::QuadInOut.apply(0.5), 0.5);
+        assert_eq!(Easing::QuadIn.apply(0.5), 0.25);
+        assert_eq!(Easing::CubicOut.apply(0.5), 0.875);
+    }
+
+    #[test]
+    fn test_interpolate() {
+        assert_eq!(10u16.interpolate(&0, 0.25), 8);
+        assert_eq!((0.0, 4i32).interpolate(&(1.0, 8), 0.5), (0.5, 6));
+        assert_eq!(
+            Color::Rgb(0, 0, 0).interpolate(&Color::Rgb(200, 100, 50), 0.5),
+            Color::Rgb(100, 50, 25)
+        );
+        assert_eq!(Color::Reset.interpolate(&Color::Red, 0.4), Color::Reset);
+        assert_eq!(Color::Reset.interpolate(&Color::Red, 0.6), Color::Red);
+    }
+
+    #[test]
+    fn test_animated() {
+        let clock = ManualClock::new();
+        set_clock(clock.clone());
+
+        let mut value = Animated::new(0.0).duration(100.0).easing(Easing::Linear);
+        assert!(!value.is_animating());
+        value.set(10.0);
+        assert_eq!(value.value(), 0.0);
+        assert_eq!(*value.target(), 10.0);
+
+        clock.advance(50.0);
+        assert!(value.is_animating());
+        assert_eq!(value.value(), 5.0);
+
+        // a new target starts from the current value
+        value.set(0.0);
+        clock.advance(50.0);
+        assert_eq!(value.value(), 2.5);
+
+        clock.advance(50.0);
+        assert!(!value.is_animating());
+        assert_eq!(value.value(), 0.0);
+
+        value.jump(3.0);
+        assert!(!value.is_animating());
+        assert_eq!(value.value(), 3.0);
+        reset_clock();
+    }
+}
@@ -0,0 +1,153 @@
+//! ## App entry point
+//!
+//! Every app starts with the same setup: installing the panic hook, creating
+//! the backend and the terminal, sharing the state between the event
+//! handlers and the render loop, and starting the loop. Implementing [`App`]
+//! and calling [`run!`](crate::run) does all of it:
+//!
+//! ```rust no_run
+//! use ratzilla::{
+//!     app::App,
+//!     event::{KeyCode, KeyEvent},
+//!     ratatui::{widgets::Paragraph, Frame},
+//! };
+//!
+//! #[derive(Default)]
+//! struct Counter {
+//!     count: u32,
+//! }
+//!
+//! impl App for Counter {
+//!     fn render(&mut self, frame: &mut Frame) {
+//!         frame.render_widget(Paragraph::new(self.count.to_string()), frame.area());
+//!     }
+//!
+//!     fn on_key_event(&mut self, event: KeyEvent) {
+//!         if event.code == KeyCode::Char(' ') {
+//!             self.count += 1;
+//!         }
+//!     }
+//! }
+//!
+//! fn main() -> std::io::Result<()> {
+//!     ratzilla::run!(Counter::default())
+//! }
+//! ```
+//!
+//! The [`DomBackend`] is used unless another backend is given, e.g.
+//! `ratzilla::run!(Counter::default(), CanvasBackend::new())`. Without the
+//! `dom` feature, the backend must always be given.
+//!
+//! [`DomBackend`]: crate::DomBackend
+//! [`App`]: crate::app::App
+
+use std::{cell::RefCell, io, rc::Rc};
+
+use ratatui::{layout::Size, prelude::Backend, Frame, Terminal};
+
+use crate::{
+    event::{ImagePasteEvent, KeyEvent, MouseEvent, TouchEvent, WheelEvent},
+    WebRenderer,
+};
+
+/// An app started with [`run!`](crate::run).
+pub trait App: 'static {
+    /// Renders a frame.
+    fn render(&mut self, frame: &mut Frame);
+
+    /// Handles a key event.
+    fn on_key_event(&mut self, _event: KeyEvent) {}
+
+    /// Handles a mouse event.
+    fn on_mouse_event(&mut self, _event: MouseEvent) {}
+
+    /// Handles a wheel event.
+    fn on_wheel_event(&mut self, _event: WheelEvent) {}
+
+    /// Handles a touch event.
+    fn on_touch_event(&mut self, _event: TouchEvent) {}
+
+    /// Handles text pasted from the clipboard.
+    fn on_paste_event(&mut self, _text: String) {}
+
+    /// Handles an image pasted from the clipboard.
+    fn on_image_paste_event(&mut self, _event: ImagePasteEvent) {}
+
+    /// Handles the page being shown (`true`) or hidden (`false`).
+    fn on_visibility_change(&mut self, _visible: bool) {}
+
+    /// Handles the terminal being resized to the given size, in cells.
+    fn on_resize(&mut self, _size: Size) {}
+}
+
+/// Starts an app with the given backend.
+///
+/// This installs the panic hook, wires the event handlers of the app and
+/// starts the render loop. Prefer the [`run!`](crate::run) macro.
+pub fn run<A, B, E>(app: A, backend: Result<B, E>) -> io::Result<()>
+where
+    A: App,
+    B: Backend<Error = io::Error> + 'static,
+    E: Into<io::Error>,
+{
+    std::panic::set_hook(Box::new(console_error_panic_hook::hook));
+    let terminal = Terminal::new(backend.map_err(Into::into)?)?;
+    let app = Rc::new(RefCell::new(app));
+    terminal.on_key_event({
+        let app = app.clone();
+        move |event| app.borrow_mut().on_key_event(event)
+    });
+    terminal.on_mouse_event({
+        let app = app.clone();
+        move |event| app.borrow_mut().on_mouse_event(event)
+    });
+    terminal.on_wheel_event({
+        let app = app.clone();
+        move |event| app.borrow_mut().on_wheel_event(event)
+    });
+    terminal.on_touch_event({
+        let app = app.clone();
+        move |event| app.borrow_mut().on_touch_event(event)
+    });
+    terminal.on_paste_event({
+        let app = app.clone();
+        move |text| app.borrow_mut().on_paste_event(text)
+    });
+    terminal.on_image_paste_event({
+        let app = app.clone();
+        move |event| app.borrow_mut().on_image_paste_event(event)
+    });
+    terminal.on_visibility_change({
+        let app = app.clone();
+        move |visible| app.borrow_mut().on_visibility_change(visible)
+    });
+    terminal.on_resize({
+        let app = app.clone();
+        move |size| app.borrow_mut().on_resize(size)
+    });
+    terminal.draw_web(move |frame| app.borrow_mut().render(frame));
+    Ok(())
+}
+
+/// Starts an [`App`](crate::app::App).
+///
+/// Takes the app and optionally the result of a backend constructor, which
+/// defaults to [`DomBackend::new`](crate::DomBackend::new):
+///
+/// ```rust no_run
+/// # use ratzilla::{app::App, ratatui::Frame, DomBackend};
+/// # struct MyApp;
+/// # impl App for MyApp { fn render(&mut self, _: &mut Frame) {} }
+/// fn main() -> std::io::Result<()> {
+///     ratzilla::run!(MyApp, DomBackend::new_by_id("grid"))
+/// }
+/// ```
+#[macro_export]
+macro_rules! run {
+    ($app:expr $(,)?) => {
+        $crate::app::run($app, $crate::DomBackend::new())
+    };
+    ($app:expr, $backend:expr $(,)?) => {
+        $crate::app::run($app, $backend)
+    };
+}
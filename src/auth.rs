@@ -0,0 +1,626 @@
+//! ## Authentication
+//!
+//! Enabled with the `auth` feature.
+//!
+//! [`AuthClient`] implements the OAuth 2.0 authorization code flow with PKCE
+//! (RFC 7636), which is the recommended flow for browser apps and also used by
+//! OpenID Connect providers. It supports logging in with a full page redirect
+//! or a popup, stores the tokens in `localStorage`, refreshes them, and
+//! reports changes of the login state as [`AuthEvent`]s.
+//!
+//! ```rust no_run
+//! use ratzilla::auth::{AuthClient, AuthConfig, AuthEvent};
+//! # async fn example() -> Result<(), ratzilla::error::Error> {
+//!
+//! let client = AuthClient::new(
+//!     AuthConfig::new(
+//!         "https://auth.example.com/authorize",
+//!         "https://auth.example.com/token",
+//!         "my-client-id",
+//!         "https://app.example.com/",
+//!     )
+//!     .scopes(&["openid", "profile"]),
+//! );
+//! client.on_auth_event(|event| {
+//!     if let AuthEvent::LoggedIn = event {
+//!         // load the user's data
+//!     }
+//! });
+//!
+//! // completes a login if the page was opened by the provider's redirect
+//! client.handle_redirect().await?;
+//! if !client.is_logged_in() {
+//!     client.login().await?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`AuthClient`]: crate::auth::AuthClient
+//! [`AuthEvent`]: crate::auth::AuthEvent
+
+use std::{cell::RefCell, rc::Rc};
+
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    js_sys::{self, Reflect, JSON},
+    wasm_bindgen::{JsCast, JsValue},
+    Request, RequestInit, Response, Url,
+};
+
+use crate::{
+    backend::utils::get_window,
+    error::Error,
+    utils::{
+        crypto::{random_bytes, sha256},
+        storage::{local_storage, session_storage},
+    },
+};
+
+/// Time before the expiry at which tokens are considered expired, in milliseconds.
+const EXPIRY_MARGIN_MS: f64 = 30_000.0;
+
+/// Interval at which the popup is checked for the redirect, in milliseconds.
+const POPUP_POLL_INTERVAL_MS: i32 = 250;
+
+/// Configuration of an OAuth provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthConfig {
+    /// The authorization endpoint.
+    authorization_endpoint: String,
+    /// The token endpoint.
+    token_endpoint: String,
+    /// The client identifier.
+    client_id: String,
+    /// The redirect URI registered with the provider.
+    redirect_uri: String,
+    /// The requested scopes.
+    scopes: Vec<String>,
+}
+
+impl AuthConfig {
+    /// Constructs a new [`AuthConfig`].
+    pub fn new(
+        authorization_endpoint: &str,
+        token_endpoint: &str,
+        client_id: &str,
+        redirect_uri: &str,
+    ) -> Self {
+        Self {
+            authorization_endpoint: authorization_endpoint.to_string(),
+            token_endpoint: token_endpoint.to_string(),
+            client_id: client_id.to_string(),
+            redirect_uri: redirect_uri.to_string(),
+            scopes: Vec::new(),
+        }
+    }
+
+    /// Sets the requested scopes.
+    pub fn scopes(mut self, scopes: &[&str]) -> Self {
+        self.scopes = scopes.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Returns the authorization URL for the given state and code challenge.
+    fn authorization_url(&self, state: &str, challenge: &str) -> String {
+        let mut params = vec![
+            ("response_type", "code"),
+            ("client_id", self.client_id.as_str()),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("state", state),
+            ("code_challenge", challenge),
+            ("code_challenge_method", "S256"),
+        ];
+        let scope = self.scopes.join(" ");
+        if !scope.is_empty() {
+            params.push(("scope", scope.as_str()));
+        }
+        let separator = if self.authorization_endpoint.contains('?') {
+            '&'
+        } else {
+            '?'
+        };
+        format!(
+            "{}{separator}{}",
+            self.authorization_endpoint,
+            form_encode(&params)
+        )
+    }
+
+    /// Returns the key under which the tokens are stored.
+    fn storage_key(&self) -> String {
+        format!("ratzilla-auth-{}", self.client_id)
+    }
+}
+
+/// Tokens issued by the provider.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tokens {
+    /// The access token.
+    pub access_token: String,
+    /// The refresh token, if issued.
+    pub refresh_token: Option<String>,
+    /// The OpenID Connect ID token, if issued.
+    pub id_token: Option<String>,
+    /// Expiry of the access token, in milliseconds since the Unix epoch.
+    pub expires_at: Option<f64>,
+}
+
+impl Tokens {
+    /// Returns `true` if the access token is expired or about to expire.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expiry| js_sys::Date::now() + EXPIRY_MARGIN_MS >= expiry)
+    }
+
+    /// Parses a token endpoint response, or stored tokens.
+    fn from_json(value: &JsValue, previous: Option<&Tokens>) -> Option<Self> {
+        let field = |name: &str| Reflect::get(value, &name.into()).ok();
+        let string = |name: &str| field(name).and_then(|v| v.as_string());
+        let expires_at = field("expires_at").and_then(|v| v.as_f64()).or_else(|| {
+            field("expires_in")
+                .and_then(|v| v.as_f64())
+                .map(|secs| js_sys::Date::now() + secs * 1000.0)
+        });
+        Some(Self {
+            access_token: string("access_token")?,
+            // providers may omit the refresh token when refreshing
+            refresh_token: string("refresh_token")
+                .or_else(|| previous.and_then(|p| p.refresh_token.clone())),
+            id_token: string("id_token").or_else(|| previous.and_then(|p| p.id_token.clone())),
+            expires_at,
+        })
+    }
+
+    /// Serializes the tokens for storage.
+    fn to_json(&self) -> Option<String> {
+        let object = js_sys::Object::new();
+        let set = |name: &str, value: JsValue| Reflect::set(&object, &name.into(), &value).ok();
+        set("access_token", self.access_token.as_str().into())?;
+        if let Some(token) = &self.refresh_token {
+            set("refresh_token", token.as_str().into())?;
+        }
+        if let Some(token) = &self.id_token {
+            set("id_token", token.as_str().into())?;
+        }
+        if let Some(expiry) = self.expires_at {
+            set("expires_at", expiry.into())?;
+        }
+        JSON::stringify(&object).ok()?.as_string()
+    }
+}
+
+/// A change of the login state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthEvent {
+    /// The user logged in.
+    LoggedIn,
+    /// The user logged out, or the session could not be refreshed.
+    LoggedOut,
+    /// The access token was refreshed.
+    TokenRefreshed,
+    /// The login failed.
+    Error(String),
+}
+
+/// Callback called with authentication events.
+type AuthCallback = Box<dyn FnMut(&AuthEvent)>;
+
+/// The `resolve` and `reject` functions of a promise waiting for a refresh.
+type RefreshWaiter = (js_sys::Function, js_sys::Function);
+
+/// Shared client state.
+struct AuthState {
+    /// The provider configuration.
+    config: AuthConfig,
+    /// The current tokens.
+    tokens: Option<Tokens>,
+    /// Event listeners.
+    listeners: Vec<AuthCallback>,
+    /// Callers waiting for the refresh in progress, if any.
+    refresh_waiters: Option<Vec<RefreshWaiter>>,
+}
+
+/// An OAuth 2.0 client using the authorization code flow with PKCE.
+///
+/// The client is a cheap handle: clones share the same state.
+#[derive(Clone)]
+pub struct AuthClient {
+    state: Rc<RefCell<AuthState>>,
+}
+
+impl std::fmt::Debug for AuthClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthClient")
+            .field("config", &self.state.borrow().config)
+            .field("logged_in", &self.is_logged_in())
+            .finish()
+    }
+}
+
+impl AuthClient {
+    /// Constructs a new [`AuthClient`], restoring the stored tokens if any.
+    pub fn new(config: AuthConfig) -> Self {
+        let tokens = local_storage()
+            .ok()
+            .and_then(|s| s.get_item(&config.storage_key()).ok().flatten())
+            .and_then(|json| JSON::parse(&json).ok())
+            .and_then(|value| Tokens::from_json(&value, None));
+        Self {
+            state: Rc::new(RefCell::new(AuthState {
+                config,
+                tokens,
+                listeners: Vec::new(),
+                refresh_waiters: None,
+            })),
+        }
+    }
+
+    /// Registers a callback that is called whenever the login state changes.
+    pub fn on_auth_event<F>(&self, callback: F)
+    where
+        F: FnMut(&AuthEvent) + 'static,
+    {
+        self.state.borrow_mut().listeners.push(Box::new(callback));
+    }
+
+    /// Returns `true` if the user is logged in.
+    ///
+    /// Expired tokens still count as logged in, as long as they can be
+    /// refreshed.
+    pub fn is_logged_in(&self) -> bool {
+        self.state
+            .borrow()
+            .tokens
+            .as_ref()
+            .is_some_and(|t| !t.is_expired() || t.refresh_token.is_some())
+    }
+
+    /// Returns the current tokens.
+    pub fn tokens(&self) -> Option<Tokens> {
+        self.state.borrow().tokens.clone()
+    }
+
+    /// Returns a valid access token, refreshing it first if it expired.
+    ///
+    /// Concurrent calls share a single refresh.
+    pub async fn access_token(&self) -> Result<Option<String>, Error> {
+        let expired = self.state.borrow().tokens.as_ref().map(Tokens::is_expired);
+        match expired {
+            None => Ok(None),
+            Some(true) => {
+                self.refresh_shared().await?;
+                Ok(self.tokens().map(|t| t.access_token))
+            }
+            Some(false) => Ok(self.tokens().map(|t| t.access_token)),
+        }
+    }
+
+    /// Starts the login by redirecting the page to the provider.
+    ///
+    /// Call [`AuthClient::handle_redirect`] when the app starts to complete the
+    /// login once the provider redirects back.
+    pub async fn login(&self) -> Result<(), Error> {
+        let url = self.prepare_login().await?;
+        get_window()?.location().set_href(&url)?;
+        Ok(())
+    }
+
+    /// Logs in using a popup window, without leaving the app.
+    ///
+    /// The redirect URI must be on the same origin as the app.
+    pub async fn login_popup(&self) -> Result<(), Error> {
+        let url = self.prepare_login().await?;
+        let window = get_window()?;
+        let popup = window
+            .open_with_url_and_target_and_features(&url, "ratzilla-auth", "width=500,height=700")?
+            .ok_or(Error::UnableToRetrieveComponent("popup window"))?;
+
+        let redirect_uri = self.state.borrow().config.redirect_uri.clone();
+        let href = loop {
+            sleep(POPUP_POLL_INTERVAL_MS).await?;
+            if popup.closed().unwrap_or(true) {
+                self.emit(AuthEvent::Error("login popup closed".to_string()));
+                return Ok(());
+            }
+            // reading the location throws while the popup is on the provider's origin
+            match popup.location().href() {
+                Ok(href) if href.starts_with(&redirect_uri) => break href,
+                _ => continue,
+            }
+        };
+        let _ = popup.close();
+        self.complete_login(&href).await.map(|_| ())
+    }
+
+    /// Completes a login if the current URL is the provider's redirect.
+    ///
+    /// Returns `true` if a login was completed. The authorization parameters
+    /// are removed from the URL.
+    pub async fn handle_redirect(&self) -> Result<bool, Error> {
+        let window = get_window()?;
+        let href = window.location().href()?;
+        let completed = self.complete_login(&href).await?;
+        if completed {
+            let url = Url::new(&href)?;
+            for param in ["code", "state", "session_state", "iss"] {
+                url.search_params().delete(param);
+            }
+            window
+                .history()?
+                .replace_state_with_url(&JsValue::NULL, "", Some(&url.href()))?;
+        }
+        Ok(completed)
+    }
+
+    /// Refreshes the access token.
+    ///
+    /// Logs out if the provider rejects the refresh token. Other errors, such
+    /// as network failures, are returned and keep the tokens.
+    pub async fn refresh(&self) -> Result<(), Error> {
+        let Some(refresh_token) = self.tokens().and_then(|t| t.refresh_token) else {
+            self.logout()?;
+            return Ok(());
+        };
+        let client_id = self.state.borrow().config.client_id.clone();
+        let body = form_encode(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.as_str()),
+            ("client_id", client_id.as_str()),
+        ]);
+        match self.request_tokens(&body).await {
+            Ok(()) => {
+                self.emit(AuthEvent::TokenRefreshed);
+                Ok(())
+            }
+            Err(Error::Auth(_)) => self.logout(),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Logs out, removing the stored tokens.
+    pub fn logout(&self) -> Result<(), Error> {
+        let key = {
+            let mut state = self.state.borrow_mut();
+            state.tokens = None;
+            state.config.storage_key()
+        };
+        local_storage()?.remove_item(&key)?;
+        self.emit(AuthEvent::LoggedOut);
+        Ok(())
+    }
+
+    /// Refreshes the access token, or waits for the refresh in progress.
+    async fn refresh_shared(&self) -> Result<(), Error> {
+        let waiting = self
+            .state
+            .borrow_mut()
+            .refresh_waiters
+            .as_mut()
+            .map(|waiters| {
+                js_sys::Promise::new(&mut |resolve, reject| waiters.push((resolve, reject)))
+            });
+        if let Some(promise) = waiting {
+            JsFuture::from(promise).await?;
+            return Ok(());
+        }
+
+        self.state.borrow_mut().refresh_waiters = Some(Vec::new());
+        let mut guard = RefreshGuard {
+            state: &self.state,
+            outcome: None,
+        };
+        let result = self.refresh().await;
+        guard.outcome = Some(match &result {
+            Ok(()) => Ok(()),
+            Err(Error::JsValue(value)) => Err(value.clone()),
+            Err(e) => Err(e.to_string().into()),
+        });
+        result
+    }
+
+    /// Generates the PKCE verifier and state, and returns the authorization URL.
+    async fn prepare_login(&self) -> Result<String, Error> {
+        let verifier = base64_url(&random_bytes(32)?);
+        let state = base64_url(&random_bytes(16)?);
+        let session = session_storage()?;
+        session.set_item("ratzilla-auth-verifier", &verifier)?;
+        session.set_item("ratzilla-auth-state", &state)?;
+
+        let challenge = base64_url(&sha256(verifier.as_bytes()).await?);
+        Ok(self
+            .state
+            .borrow()
+            .config
+            .authorization_url(&state, &challenge))
+    }
+
+    /// Exchanges the authorization code of a redirect URL for tokens.
+    ///
+    /// Returns `false` if the URL is not a redirect from the provider.
+    async fn complete_login(&self, href: &str) -> Result<bool, Error> {
+        let url = Url::new(href)?;
+        let params = url.search_params();
+        if let Some(error) = params.get("error") {
+            self.emit(AuthEvent::Error(error));
+            return Ok(false);
+        }
+        let (Some(code), Some(state)) = (params.get("code"), params.get("state")) else {
+            return Ok(false);
+        };
+
+        let session = session_storage()?;
+        let expected_state = session.get_item("ratzilla-auth-state")?;
+        let verifier = session.get_item("ratzilla-auth-verifier")?;
+        session.remove_item("ratzilla-auth-state")?;
+        session.remove_item("ratzilla-auth-verifier")?;
+        let Some(verifier) = verifier.filter(|_| expected_state.as_deref() == Some(&state)) else {
+            self.emit(AuthEvent::Error("invalid state".to_string()));
+            return Ok(false);
+        };
+
+        let (client_id, redirect_uri) = {
+            let state = self.state.borrow();
+            (
+                state.config.client_id.clone(),
+                state.config.redirect_uri.clone(),
+            )
+        };
+        let body = form_encode(&[
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("client_id", client_id.as_str()),
+            ("code_verifier", verifier.as_str()),
+        ]);
+        if let Err(e) = self.request_tokens(&body).await {
+            self.emit(AuthEvent::Error(e.to_string()));
+            return Err(e);
+        }
+        self.emit(AuthEvent::LoggedIn);
+        Ok(true)
+    }
+
+    /// Posts a form to the token endpoint and stores the returned tokens.
+    async fn request_tokens(&self, body: &str) -> Result<(), Error> {
+        let endpoint = self.state.borrow().config.token_endpoint.clone();
+        let init = RequestInit::new();
+        init.set_method("POST");
+        init.set_body(&body.into());
+        let request = Request::new_with_str_and_init(&endpoint, &init)?;
+        request
+            .headers()
+            .set("Content-Type", "application/x-www-form-urlencoded")?;
+
+        let response: Response = JsFuture::from(get_window()?.fetch_with_request(&request))
+            .await?
+            .dyn_into()?;
+        let json = JsFuture::from(response.json()?).await?;
+        if !response.ok() {
+            let error = Reflect::get(&json, &"error".into())
+                .ok()
+                .and_then(|e| e.as_string())
+                .unwrap_or_else(|| format!("token request failed with {}", response.status()));
+            return Err(Error::Auth(error));
+        }
+
+        let mut state = self.state.borrow_mut();
+        let tokens = Tokens::from_json(&json, state.tokens.as_ref())
+            .ok_or_else(|| Error::Auth("missing access token".to_string()))?;
+        if let Some(json) = tokens.to_json() {
+            local_storage()?.set_item(&state.config.storage_key(), &json)?;
+        }
+        state.tokens = Some(tokens);
+        Ok(())
+    }
+
+    /// Notifies the listeners.
+    fn emit(&self, event: AuthEvent) {
+        let mut listeners = std::mem::take(&mut self.state.borrow_mut().listeners);
+        listeners.iter_mut().for_each(|listener| listener(&event));
+        let mut state = self.state.borrow_mut();
+        listeners.append(&mut state.listeners);
+        state.listeners = listeners;
+    }
+}
+
+/// Settles the promises waiting for a refresh when it ends, or is cancelled.
+struct RefreshGuard<'a> {
+    /// Shared client state.
+    state: &'a RefCell<AuthState>,
+    /// Outcome of the refresh, `None` until it ends.
+    outcome: Option<Result<(), JsValue>>,
+}
+
+impl Drop for RefreshGuard<'_> {
+    fn drop(&mut self) {
+        let waiters = self.state.borrow_mut().refresh_waiters.take();
+        let outcome = self
+            .outcome
+            .take()
+            .unwrap_or_else(|| Err("token refresh cancelled".into()));
+        for (resolve, reject) in waiters.unwrap_or_default() {
+            let _ = match &outcome {
+                Ok(()) => resolve.call0(&JsValue::NULL),
+                Err(error) => reject.call1(&JsValue::NULL, error),
+            };
+        }
+    }
+}
+
+/// Waits for the given duration, in milliseconds.
+async fn sleep(ms: i32) -> Result<(), Error> {
+    let window = get_window()?;
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms);
+    });
+    JsFuture::from(promise).await?;
+    Ok(())
+}
+
+/// Encodes bytes with the URL-safe base64 alphabet, without padding.
+fn base64_url(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = ((chunk[0] as u32) << 16)
+            | ((*chunk.get(1).unwrap_or(&0) as u32) << 8)
+            | (*chunk.get(2).unwrap_or(&0) as u32);
+        for i in 0..=chunk.len() {
+            encoded.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+    encoded
+}
+
+/// Encodes key-value pairs as `application/x-www-form-urlencoded`.
+fn form_encode(params: &[(&str, &str)]) -> String {
+    let encode = |value: &str| {
+        value
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    (b as char).to_string()
+                }
+                b' ' => "+".to_string(),
+                b => format!("%{b:02X}"),
+            })
+            .collect::<String>()
+    };
+    params
+        .iter()
+        .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_url() {
+        assert_eq!(base64_url(b""), "");
+        assert_eq!(base64_url(b"f"), "Zg");
+        assert_eq!(base64_url(b"fo"), "Zm8");
+        assert_eq!(base64_url(b"foo"), "Zm9v");
+        assert_eq!(base64_url(&[0xfb, 0xff]), "-_8");
+    }
+
+    #[test]
+    fn test_authorization_url() {
+        let config = AuthConfig::new(
+            "https://auth.example.com/authorize",
+            "https://auth.example.com/token",
+            "client",
+            "https://app.example.com/",
+        )
+        .scopes(&["openid", "profile"]);
+        assert_eq!(
+            config.authorization_url("xyz", "abc"),
+            "https://auth.example.com/authorize?response_type=code&client_id=client\
+             &redirect_uri=https%3A%2F%2Fapp.example.com%2F&state=xyz&code_challenge=abc\
+             &code_challenge_method=S256&scope=openid+profile"
+        );
+    }
+}
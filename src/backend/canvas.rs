@@ -1,38 +1,140 @@
 use bitvec::{bitvec, prelude::BitVec};
 use ratatui::{backend::ClearType, layout::Rect};
-use std::io::{Error as IoError, Result as IoResult};
+use std::{
+    cell::Cell as StdCell,
+    collections::HashMap,
+    io::{Error as IoError, Result as IoResult},
+    rc::Rc,
+};
 
 use crate::{
+    analytics::{self, AnalyticsEvent},
     backend::{
         color::{actual_bg_color, actual_fg_color},
+        image::{self, ImageElement},
+        metrics::{device_pixel_ratio, on_device_pixel_ratio_change, CellMetrics},
         utils::*,
+        web::{
+            self, buffer_bytes, distinct_glyphs, mirror_cell, CellRect, GridGeometry, MemoryStats,
+            WebBackend,
+        },
     },
+    clock,
     error::Error,
-    CursorShape,
+    images::{self, ImageSource, Placement},
+    render_loop, resize, watchdog, CursorOptions, CursorShape,
 };
 use ratatui::{
     backend::WindowSize,
-    buffer::Cell,
+    buffer::{Buffer, Cell},
     layout::{Position, Size},
     prelude::Backend,
-    style::{Color, Modifier},
+    style::Color,
 };
+use wasm_bindgen_futures::JsFuture;
 use web_sys::{
     js_sys::{Boolean, Map},
     wasm_bindgen::{JsCast, JsValue},
+    window, FontFace,
 };
 
-/// Width of a single cell.
+/// Width of a single cell with the default font.
 ///
 /// This will be used for multiplying the cell's x position to get the actual pixel
-/// position on the canvas.
-const CELL_WIDTH: f64 = 10.0;
+/// position on the canvas, unless a [`FontOptions`] is set.
+pub(crate) const CELL_WIDTH: f64 = 10.0;
 
-/// Height of a single cell.
+/// Height of a single cell with the default font.
 ///
 /// This will be used for multiplying the cell's y position to get the actual pixel
-/// position on the canvas.
-const CELL_HEIGHT: f64 = 19.0;
+/// position on the canvas, unless a [`FontOptions`] is set.
+pub(crate) const CELL_HEIGHT: f64 = 19.0;
+
+/// Font of the [`CanvasBackend`].
+///
+/// The size of the cells is measured from the glyphs of the font, so the
+/// grid stays aligned with any monospace font.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontOptions {
+    /// Font family, or `None` for the default monospace font.
+    family: Option<String>,
+    /// Font size, in pixels.
+    size: f64,
+    /// URL of the font file, loaded with a `@font-face`.
+    url: Option<String>,
+}
+
+impl Default for FontOptions {
+    fn default() -> Self {
+        Self {
+            family: None,
+            size: 16.0,
+            url: None,
+        }
+    }
+}
+
+impl FontOptions {
+    /// Constructs a new [`FontOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the font family, e.g. `Fira Code`.
+    ///
+    /// Glyphs missing from it fall back to the default monospace font.
+    pub fn family(mut self, family: &str) -> Self {
+        self.family = Some(family.to_string());
+        self
+    }
+
+    /// Sets the font size, in pixels.
+    ///
+    /// Defaults to `16.0`.
+    pub fn size(mut self, size: f64) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Loads the font family from the given URL, e.g. a `.woff2` file.
+    ///
+    /// Frames are only drawn once the font is loaded, with the cell size
+    /// measured from it.
+    pub fn url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    /// Returns the CSS `font` shorthand of this font.
+    fn css(&self) -> String {
+        match &self.family {
+            Some(family) => format!("{}px \"{family}\", monospace", self.size),
+            None => format!("{}px monospace", self.size),
+        }
+    }
+
+    /// Loads the font, returning a flag set once it is ready.
+    fn load(&self) -> Result<Rc<StdCell<bool>>, Error> {
+        let fonts = get_document()?.fonts();
+        if let (Some(family), Some(url)) = (&self.family, &self.url) {
+            let face = FontFace::new_with_str(family, &format!("url(\"{url}\")"))?;
+            fonts.add(&face)?;
+        }
+        // resolves once the faces of the font are loaded, including the ones
+        // declared by stylesheets
+        let promise = fonts.load(&self.css());
+        let ready = Rc::new(StdCell::new(false));
+        wasm_bindgen_futures::spawn_local({
+            let ready = ready.clone();
+            async move {
+                // a font failing to load falls back to the default one
+                let _ = JsFuture::from(promise).await;
+                ready.set(true);
+            }
+        });
+        Ok(ready)
+    }
+}
 
 /// Options for the [`CanvasBackend`].
 #[derive(Debug, Default)]
@@ -46,6 +148,14 @@ pub struct CanvasBackendOptions {
     /// this option may cause some performance issues when dealing with large
     /// numbers of simultaneous changes.
     always_clip_cells: bool,
+    /// How the cursor is drawn.
+    cursor: CursorOptions,
+    /// Font of the cells.
+    font: FontOptions,
+    /// Fixed device pixel ratio.
+    pixel_ratio: Option<f64>,
+    /// Hyperlink click callback.
+    hyperlink_callback: Option<HyperlinkCallback>,
 }
 
 impl CanvasBackendOptions {
@@ -65,6 +175,54 @@ impl CanvasBackendOptions {
         self.size = Some(size);
         self
     }
+
+    /// Sets how the cursor is drawn.
+    pub fn cursor_options(mut self, options: CursorOptions) -> Self {
+        self.cursor = options;
+        self
+    }
+
+    /// Sets the font of the cells.
+    pub fn font(mut self, font: FontOptions) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Sets a fixed number of canvas pixels per CSS pixel, e.g. `1.0` for
+    /// output that is the same on every display.
+    ///
+    /// Defaults to the device pixel ratio of the window, following its
+    /// changes, so glyphs are sharp on high density displays.
+    pub fn pixel_ratio(mut self, ratio: f64) -> Self {
+        self.pixel_ratio = Some(ratio);
+        self
+    }
+
+    /// Enables hyperlinks in the canvas.
+    ///
+    /// Sets up a default mouse handler using [`CanvasBackendOptions::on_hyperlink_click`].
+    pub fn enable_hyperlinks(self) -> Self {
+        self.on_hyperlink_click(|url| {
+            if let Some(w) = window() {
+                w.open_with_url_and_target(url, "_blank")
+                    .unwrap_or_default();
+            }
+        })
+    }
+
+    /// Sets a callback for when hyperlinks are clicked.
+    ///
+    /// The links are the regions registered in the
+    /// [`LinkRegistry`](crate::links::LinkRegistry), e.g. by the
+    /// [`Hyperlink`](crate::widgets::Hyperlink) widget. The mouse cursor
+    /// turns into a pointer over them.
+    pub fn on_hyperlink_click<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&str) + 'static,
+    {
+        self.hyperlink_callback = Some(HyperlinkCallback::new(callback));
+        self
+    }
 }
 
 /// Canvas renderer.
@@ -76,6 +234,12 @@ struct Canvas {
     context: web_sys::CanvasRenderingContext2d,
     /// Background color.
     background_color: Color,
+    /// Font of the cells.
+    font: FontOptions,
+    /// Size of a cell.
+    metrics: CellMetrics,
+    /// Size of the canvas, in CSS pixels.
+    size: (u32, u32),
 }
 
 impl Canvas {
@@ -85,6 +249,8 @@ impl Canvas {
         width: u32,
         height: u32,
         background_color: Color,
+        font: FontOptions,
+        pixel_ratio: f64,
     ) -> Result<Self, Error> {
         let canvas = create_canvas_in_element(&parent_element, width, height)?;
 
@@ -99,14 +265,57 @@ impl Canvas {
             .ok_or_else(|| Error::UnableToRetrieveCanvasContext)?
             .dyn_into::<web_sys::CanvasRenderingContext2d>()
             .expect("Unable to cast canvas context");
-        context.set_font("16px monospace");
-        context.set_text_baseline("top");
 
-        Ok(Self {
+        let mut canvas = Self {
             inner: canvas,
             context,
             background_color,
-        })
+            font,
+            metrics: CellMetrics::new(CELL_WIDTH, CELL_HEIGHT),
+            size: (width, height),
+        };
+        canvas.scale(pixel_ratio)?;
+        canvas.apply_font()?;
+        Ok(canvas)
+    }
+
+    /// Sizes the canvas pixels to the given device pixel ratio, keeping its
+    /// size in CSS pixels.
+    ///
+    /// Everything is drawn in CSS pixels, scaled to the canvas pixels, so
+    /// glyphs are rasterized at the resolution of the display.
+    fn scale(&mut self, pixel_ratio: f64) -> Result<(), Error> {
+        let (width, height) = self.size;
+        self.inner
+            .set_width((width as f64 * pixel_ratio).round() as u32);
+        self.inner
+            .set_height((height as f64 * pixel_ratio).round() as u32);
+        let style = self.inner.style();
+        style.set_property("width", &format!("{width}px"))?;
+        style.set_property("height", &format!("{height}px"))?;
+        // resizing the canvas resets the state of the context
+        self.context
+            .set_transform(pixel_ratio, 0.0, 0.0, pixel_ratio, 0.0, 0.0)?;
+        self.context.set_text_baseline("top");
+        self.context.set_font(&self.font.css());
+        self.metrics.device_pixel_ratio = pixel_ratio;
+        Ok(())
+    }
+
+    /// Sets the font of the context and measures the cell size with it.
+    fn apply_font(&mut self) -> Result<(), Error> {
+        self.context.set_font(&self.font.css());
+        // the default font keeps its historical cell size, so layouts and
+        // golden images don't depend on the metrics of the system fonts
+        if self.font == FontOptions::default() {
+            return Ok(());
+        }
+        let pixel_ratio = self.metrics.device_pixel_ratio;
+        self.metrics = CellMetrics {
+            device_pixel_ratio: pixel_ratio,
+            ..CellMetrics::measure(Some(&self.font.css()))?
+        };
+        Ok(())
     }
 }
 
@@ -132,17 +341,35 @@ pub struct CanvasBackend {
     canvas: Canvas,
     /// Cursor position.
     cursor_position: Option<Position>,
-    /// The cursor shape.
-    cursor_shape: CursorShape,
+    /// Position of the cursor on the canvas, if drawn.
+    drawn_cursor: Option<Position>,
+    /// How the cursor is drawn.
+    cursor: CursorOptions,
     /// Draw cell boundaries with specified color.
     debug_mode: Option<String>,
+    /// Set once the font is loaded, if it is loading.
+    font_ready: Option<Rc<StdCell<bool>>>,
+    /// Set when the device pixel ratio changes, unless the ratio is fixed.
+    pixel_ratio_changed: Option<Rc<StdCell<bool>>>,
+    /// How the size of the canvas follows the page.
+    size_source: CanvasSize,
+    /// Set when the window was resized.
+    resized: Rc<StdCell<bool>>,
+    /// Elements of the [`images`] on screen.
+    image_elements: HashMap<ImageSource, ImageElement>,
+    /// Images drawn on the canvas.
+    drawn_images: Vec<Placement>,
+    /// Generation of the images and number of loaded images when drawn.
+    drawn_images_state: Option<(u64, u64)>,
 }
 
 impl CanvasBackend {
     /// Constructs a new [`CanvasBackend`].
     pub fn new() -> Result<Self, Error> {
         let (width, height) = get_raw_window_size();
-        Self::new_with_size(width.into(), height.into())
+        let mut backend = Self::new_with_size(width.into(), height.into())?;
+        backend.size_source = CanvasSize::Window;
+        Ok(backend)
     }
 
     /// Constructs a new [`CanvasBackend`] with the given size.
@@ -158,14 +385,54 @@ impl CanvasBackend {
         // Parent element of canvas (uses <body> unless specified)
         let parent = get_element_by_id_or_body(options.grid_id.as_ref())?;
 
+        let size_source = match options.size {
+            Some(_) => CanvasSize::Fixed,
+            None => CanvasSize::Parent(parent.clone()),
+        };
         let (width, height) = options
             .size
             .unwrap_or_else(|| (parent.client_width() as u32, parent.client_height() as u32));
+        let resized = Rc::new(StdCell::new(false));
+        resize::on_window_resize({
+            let resized = resized.clone();
+            move || resized.set(true)
+        })?;
+        if options.grid_id.is_some() {
+            resize::observe_element(&parent)?;
+        }
 
-        let canvas = Canvas::new(parent, width, height, Color::Black)?;
-        let buffer = get_sized_buffer_from_canvas(&canvas.inner);
+        let font_ready = if options.font != FontOptions::default() {
+            Some(options.font.load()?)
+        } else {
+            None
+        };
+        let pixel_ratio_changed = match options.pixel_ratio {
+            Some(_) => None,
+            None => {
+                let changed = Rc::new(StdCell::new(false));
+                on_device_pixel_ratio_change({
+                    let changed = changed.clone();
+                    move |_| changed.set(true)
+                })?;
+                Some(changed)
+            }
+        };
+        let pixel_ratio = options.pixel_ratio.unwrap_or_else(device_pixel_ratio);
+        let canvas = Canvas::new(
+            parent,
+            width,
+            height,
+            Color::Black,
+            options.font,
+            pixel_ratio,
+        )?;
+        if let Some(callback) = options.hyperlink_callback {
+            handle_hyperlinks(&canvas.inner, callback)?;
+        }
+        let buffer = get_sized_buffer_from_canvas(&canvas.inner, &canvas.metrics);
         let changed_cells = bitvec![0; buffer.len() * buffer[0].len()];
-        Ok(Self {
+        analytics::report(AnalyticsEvent::BackendSelected { backend: "canvas" });
+        let backend = Self {
             prev_buffer: buffer.clone(),
             always_clip_cells: options.always_clip_cells,
             buffer,
@@ -173,9 +440,19 @@ impl CanvasBackend {
             changed_cells,
             canvas,
             cursor_position: None,
-            cursor_shape: CursorShape::SteadyBlock,
+            drawn_cursor: None,
+            cursor: options.cursor,
             debug_mode: None,
-        })
+            font_ready,
+            pixel_ratio_changed,
+            size_source,
+            resized,
+            image_elements: HashMap::new(),
+            drawn_images: vec![],
+            drawn_images_state: None,
+        };
+        backend.set_grid();
+        Ok(backend)
     }
 
     /// Sets the background color of the canvas.
@@ -185,12 +462,23 @@ impl CanvasBackend {
 
     /// Returns the [`CursorShape`].
     pub fn cursor_shape(&self) -> &CursorShape {
-        &self.cursor_shape
+        &self.cursor.shape
     }
 
     /// Set the [`CursorShape`].
     pub fn set_cursor_shape(mut self, shape: CursorShape) -> Self {
-        self.cursor_shape = shape;
+        self.cursor.shape = shape;
+        self
+    }
+
+    /// Returns the [`CursorOptions`].
+    pub fn cursor_options(&self) -> &CursorOptions {
+        &self.cursor
+    }
+
+    /// Set the [`CursorOptions`].
+    pub fn set_cursor_options(mut self, options: CursorOptions) -> Self {
+        self.cursor = options;
         self
     }
 
@@ -214,11 +502,52 @@ impl CanvasBackend {
         self.debug_mode = color.map(Into::into);
     }
 
+    /// Returns `true` once the font is loaded, measuring the cells with it
+    /// then.
+    fn is_font_ready(&mut self) -> Result<bool, Error> {
+        let Some(ready) = &self.font_ready else {
+            return Ok(true);
+        };
+        if !ready.get() {
+            return Ok(false);
+        }
+        self.font_ready = None;
+        self.canvas.apply_font()?;
+        self.reset_buffer();
+        Ok(true)
+    }
+
+    /// Resizes the canvas to follow the page, unless its size is fixed.
+    fn resize_canvas(&mut self) -> Result<(), Error> {
+        let Some(size) = self.size_source.get() else {
+            return Ok(());
+        };
+        if size == self.canvas.size {
+            return Ok(());
+        }
+        self.canvas.size = size;
+        self.canvas.scale(self.canvas.metrics.device_pixel_ratio)?;
+        self.reset_buffer();
+        // the terminal picks up the new size on the next frame
+        render_loop::request_redraw();
+        Ok(())
+    }
+
+    /// Resets the buffer to the size of the canvas, redrawing everything.
+    fn reset_buffer(&mut self) {
+        self.buffer = get_sized_buffer_from_canvas(&self.canvas.inner, &self.canvas.metrics);
+        self.prev_buffer = self.buffer.clone();
+        self.changed_cells = bitvec![0; self.buffer.iter().map(Vec::len).sum()];
+        self.initialized = false;
+        self.set_grid();
+    }
+
     // Compare the current buffer to the previous buffer and updates the canvas
     // accordingly.
     //
     // If `force_redraw` is `true`, the entire canvas will be cleared and redrawn.
-    fn update_grid(&mut self, force_redraw: bool) -> Result<(), Error> {
+    // The cursor is drawn at `cursor`, if any.
+    fn update_grid(&mut self, force_redraw: bool, cursor: Option<Position>) -> Result<(), Error> {
         if force_redraw {
             self.canvas.context.clear_rect(
                 0.0,
@@ -233,9 +562,25 @@ impl CanvasBackend {
         // traversing it once per cell; this is done to reduce the number of
         // WASM calls per cell.
         self.resolve_changed_cells(force_redraw);
+        // redraw the cells under the old and the new cursor
+        for position in self.drawn_cursor.into_iter().chain(cursor) {
+            self.mark_changed(position);
+        }
+        // redraw the cells under the old and the new images
+        let placements = images::placements();
+        for placement in std::mem::take(&mut self.drawn_images)
+            .iter()
+            .chain(&placements)
+        {
+            self.mark_area_changed(placement.area);
+        }
         self.draw_background()?;
         self.draw_symbols()?;
-        self.draw_cursor()?;
+        self.draw_images(&placements)?;
+        self.drawn_images = placements;
+        self.drawn_images_state = Some((images::generation(), image::loads()));
+        self.draw_cursor(cursor)?;
+        self.drawn_cursor = cursor;
         if self.debug_mode.is_some() {
             self.draw_debug()?;
         }
@@ -260,6 +605,77 @@ impl CanvasBackend {
         }
     }
 
+    /// Marks the cell at the given position as changed.
+    fn mark_changed(&mut self, position: Position) {
+        let (x, y) = (position.x as usize, position.y as usize);
+        if self.buffer.get(y).is_none_or(|line| x >= line.len()) {
+            return;
+        }
+        let index = self.buffer[..y].iter().map(Vec::len).sum::<usize>() + x;
+        self.changed_cells.set(index, true);
+    }
+
+    /// Marks the cells of the given area as changed.
+    fn mark_area_changed(&mut self, area: Rect) {
+        let mut index = 0;
+        for (y, line) in self.buffer.iter().enumerate() {
+            if (area.top()..area.bottom()).contains(&(y as u16)) {
+                let start = (area.left() as usize).min(line.len());
+                let end = (area.right() as usize).min(line.len());
+                for x in start..end {
+                    self.changed_cells.set(index + x, true);
+                }
+            }
+            index += line.len();
+        }
+    }
+
+    /// Draws the [`images`] over their cells.
+    ///
+    /// Images loaded from a URL are drawn once loaded.
+    fn draw_images(&mut self, placements: &[Placement]) -> Result<(), Error> {
+        self.image_elements.retain(|source, _| {
+            placements
+                .iter()
+                .any(|placement| placement.source == *source)
+        });
+        if placements.is_empty() {
+            return Ok(());
+        }
+        let document = get_document()?;
+        let (cell_width, cell_height) = self.canvas.metrics.cell_size();
+        for placement in placements {
+            let element = match self.image_elements.get(&placement.source) {
+                Some(element) => element,
+                None => {
+                    let element = ImageElement::new(&document, &placement.source)?;
+                    self.image_elements
+                        .entry(placement.source.clone())
+                        .or_insert(element)
+                }
+            };
+            let Some(size) = element.natural_size() else {
+                continue;
+            };
+            let area = (
+                placement.area.x as f64 * cell_width,
+                placement.area.y as f64 * cell_height,
+                placement.area.width as f64 * cell_width,
+                placement.area.height as f64 * cell_height,
+            );
+            let (source, target) = placement.fit.fit(size, area);
+
+            self.canvas.context.save();
+            self.canvas.context.begin_path();
+            self.canvas.context.rect(area.0, area.1, area.2, area.3);
+            self.canvas.context.clip();
+            let result = element.draw(&self.canvas.context, source, target);
+            self.canvas.context.restore();
+            result?;
+        }
+        Ok(())
+    }
+
     /// Draws the text symbols on the canvas.
     ///
     /// This method renders the textual content of each cell in the buffer, optimizing canvas operations
@@ -273,9 +689,10 @@ impl CanvasBackend {
     /// 1. Only processes cells that have changed since the last render.
     /// 2. Tracks the last foreground color used to avoid unnecessary style changes
     /// 3. Only creates clipping paths for potentially problematic glyphs (non-ASCII)
-    /// or when `always_clip_cells` is enabled.
+    ///    or when `always_clip_cells` is enabled.
     fn draw_symbols(&mut self) -> Result<(), Error> {
         let changed_cells = &self.changed_cells;
+        let (cell_width, cell_height) = self.canvas.metrics.cell_size();
         let mut index = 0;
 
         self.canvas.context.save();
@@ -298,10 +715,10 @@ impl CanvasBackend {
 
                     self.canvas.context.begin_path();
                     self.canvas.context.rect(
-                        x as f64 * CELL_WIDTH,
-                        y as f64 * CELL_HEIGHT,
-                        CELL_WIDTH,
-                        CELL_HEIGHT,
+                        x as f64 * cell_width,
+                        y as f64 * cell_height,
+                        cell_width,
+                        cell_height,
                     );
                     self.canvas.context.clip();
 
@@ -320,8 +737,8 @@ impl CanvasBackend {
 
                 self.canvas.context.fill_text(
                     cell.symbol(),
-                    x as f64 * CELL_WIDTH,
-                    y as f64 * CELL_HEIGHT,
+                    x as f64 * cell_width,
+                    y as f64 * cell_height,
                 )?;
 
                 index += 1;
@@ -341,6 +758,7 @@ impl CanvasBackend {
     /// color, and then it draws the accumulated rectangle.
     fn draw_background(&mut self) -> Result<(), Error> {
         let changed_cells = &self.changed_cells;
+        let (cell_width, cell_height) = self.canvas.metrics.cell_size();
         self.canvas.context.save();
 
         let draw_region = |(rect, color): (Rect, Color)| {
@@ -348,10 +766,10 @@ impl CanvasBackend {
 
             self.canvas.context.set_fill_style_str(&color);
             self.canvas.context.fill_rect(
-                rect.x as f64 * CELL_WIDTH,
-                rect.y as f64 * CELL_HEIGHT,
-                rect.width as f64 * CELL_WIDTH,
-                rect.height as f64 * CELL_HEIGHT,
+                rect.x as f64 * cell_width,
+                rect.y as f64 * cell_height,
+                rect.width as f64 * cell_width,
+                rect.height as f64 * cell_height,
             );
         };
 
@@ -380,40 +798,67 @@ impl CanvasBackend {
         Ok(())
     }
 
-    /// Draws the cursor on the canvas.
-    fn draw_cursor(&mut self) -> Result<(), Error> {
-        if let Some(pos) = self.cursor_position {
-            let cell = &self.buffer[pos.y as usize][pos.x as usize];
-
-            if cell.modifier.contains(Modifier::UNDERLINED) {
-                self.canvas.context.save();
-
-                self.canvas.context.fill_text(
-                    "_",
-                    pos.x as f64 * CELL_WIDTH,
-                    pos.y as f64 * CELL_HEIGHT,
-                )?;
+    /// Draws the cursor on the canvas at the given position.
+    fn draw_cursor(&mut self, cursor: Option<Position>) -> Result<(), Error> {
+        let Some(pos) = cursor else {
+            return Ok(());
+        };
+        let Some(cell) = self
+            .buffer
+            .get(pos.y as usize)
+            .and_then(|line| line.get(pos.x as usize))
+        else {
+            return Ok(());
+        };
+        let (cell_width, cell_height) = self.canvas.metrics.cell_size();
+        let (x, y) = (pos.x as f64 * cell_width, pos.y as f64 * cell_height);
+        let fg = get_canvas_color(actual_fg_color(cell), Color::White);
+        let color = match self.cursor.color {
+            Some(color) => get_canvas_color(color, Color::White),
+            None => fg,
+        };
 
-                self.canvas.context.restore();
+        self.canvas.context.save();
+        self.canvas.context.set_fill_style_str(&color);
+        match self.cursor.shape {
+            CursorShape::SteadyBlock => {
+                self.canvas.context.fill_rect(x, y, cell_width, cell_height);
+                // the symbol in the background color, like reversed cells
+                let bg = get_canvas_color(actual_bg_color(cell), self.canvas.background_color);
+                self.canvas.context.set_fill_style_str(&bg);
+                self.canvas.context.fill_text(cell.symbol(), x, y)?;
+            }
+            CursorShape::SteadyUnderScore => {
+                self.canvas
+                    .context
+                    .fill_rect(x, y + cell_height - 2.0, cell_width, 2.0);
+            }
+            CursorShape::SteadyBar => {
+                self.canvas.context.fill_rect(x, y, 2.0, cell_height);
             }
+            CursorShape::None => {}
         }
+        self.canvas.context.restore();
 
         Ok(())
     }
 
     /// Draws cell boundaries for debugging.
     fn draw_debug(&mut self) -> Result<(), Error> {
+        let Some(color) = self.debug_mode.as_ref() else {
+            return Ok(());
+        };
         self.canvas.context.save();
 
-        let color = self.debug_mode.as_ref().unwrap();
+        let (cell_width, cell_height) = self.canvas.metrics.cell_size();
         for (y, line) in self.buffer.iter().enumerate() {
             for (x, _) in line.iter().enumerate() {
                 self.canvas.context.set_stroke_style_str(color);
                 self.canvas.context.stroke_rect(
-                    x as f64 * CELL_WIDTH,
-                    y as f64 * CELL_HEIGHT,
-                    CELL_WIDTH,
-                    CELL_HEIGHT,
+                    x as f64 * cell_width,
+                    y as f64 * cell_height,
+                    cell_width,
+                    cell_height,
                 );
             }
         }
@@ -424,6 +869,13 @@ impl CanvasBackend {
     }
 }
 
+impl Drop for CanvasBackend {
+    /// Removes the canvas from the page, e.g. on [teardown].
+    fn drop(&mut self) {
+        self.canvas.inner.remove();
+    }
+}
+
 impl Backend for CanvasBackend {
     type Error = IoError;
 
@@ -432,6 +884,7 @@ impl Backend for CanvasBackend {
     where
         I: Iterator<Item = (u16, u16, &'a Cell)>,
     {
+        watchdog::mark_flush_start();
         for (x, y, cell) in content {
             let y = y as usize;
             let x = x as usize;
@@ -440,17 +893,6 @@ impl Backend for CanvasBackend {
             line[x] = cell.clone();
         }
 
-        // Draw the cursor if set
-        if let Some(pos) = self.cursor_position {
-            let y = pos.y as usize;
-            let x = pos.x as usize;
-            let line = &mut self.buffer[y];
-            if x < line.len() {
-                let cursor_style = self.cursor_shape.show(line[x].style());
-                line[x].set_style(cursor_style);
-            }
-        }
-
         Ok(())
     }
 
@@ -459,16 +901,38 @@ impl Backend for CanvasBackend {
     /// This function is called after the [`CanvasBackend::draw`] function to
     /// actually render the content to the screen.
     fn flush(&mut self) -> IoResult<()> {
+        // nothing is drawn until the font is loaded and measured
+        if !self.is_font_ready()? {
+            return Ok(());
+        }
+        if self.resized.replace(false) {
+            self.resize_canvas()?;
+        }
+        if self
+            .pixel_ratio_changed
+            .as_ref()
+            .is_some_and(|changed| changed.replace(false))
+        {
+            // redraw everything at the new resolution
+            self.canvas.scale(device_pixel_ratio())?;
+            self.initialized = false;
+        }
+        let cursor = self
+            .cursor_position
+            .filter(|_| self.cursor.is_visible(clock::now()));
+
         // Only runs once.
         if !self.initialized {
-            self.update_grid(true)?;
+            self.update_grid(true, cursor)?;
             self.prev_buffer = self.buffer.clone();
             self.initialized = true;
             return Ok(());
         }
 
-        if self.buffer != self.prev_buffer {
-            self.update_grid(false)?;
+        let images_changed =
+            self.drawn_images_state != Some((images::generation(), image::loads()));
+        if self.buffer != self.prev_buffer || cursor != self.drawn_cursor || images_changed {
+            self.update_grid(false, cursor)?;
         }
 
         self.prev_buffer = self.buffer.clone();
@@ -477,15 +941,6 @@ impl Backend for CanvasBackend {
     }
 
     fn hide_cursor(&mut self) -> IoResult<()> {
-        if let Some(pos) = self.cursor_position {
-            let y = pos.y as usize;
-            let x = pos.x as usize;
-            let line = &mut self.buffer[y];
-            if x < line.len() {
-                let style = self.cursor_shape.hide(line[x].style());
-                line[x].set_style(style);
-            }
-        }
         self.cursor_position = None;
         Ok(())
     }
@@ -503,7 +958,7 @@ impl Backend for CanvasBackend {
     }
 
     fn clear(&mut self) -> IoResult<()> {
-        self.buffer = get_sized_buffer();
+        self.reset_buffer();
         Ok(())
     }
 
@@ -526,17 +981,7 @@ impl Backend for CanvasBackend {
     }
 
     fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> IoResult<()> {
-        let new_pos = position.into();
-        if let Some(old_pos) = self.cursor_position {
-            let y = old_pos.y as usize;
-            let x = old_pos.x as usize;
-            let line = &mut self.buffer[y];
-            if x < line.len() && old_pos != new_pos {
-                let style = self.cursor_shape.hide(line[x].style());
-                line[x].set_style(style);
-            }
-        }
-        self.cursor_position = Some(new_pos);
+        self.cursor_position = Some(position.into());
         Ok(())
     }
 
@@ -548,6 +993,77 @@ impl Backend for CanvasBackend {
     }
 }
 
+impl WebBackend for CanvasBackend {
+    fn cell_at(&self, x: f64, y: f64) -> Option<Position> {
+        self.geometry()?.cell_at(x, y)
+    }
+
+    fn cell_rect(&self, column: u16, row: u16) -> Option<CellRect> {
+        self.geometry()?.cell_rect(column, row)
+    }
+
+    fn cell_metrics(&self) -> CellMetrics {
+        self.canvas.metrics
+    }
+
+    fn current_buffer(&self) -> Buffer {
+        let size = self.size().unwrap_or_default();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, size.width, size.height));
+        for (y, line) in self.buffer.iter().enumerate() {
+            for (x, cell) in line.iter().enumerate() {
+                mirror_cell(&mut buffer, x as u16, y as u16, cell);
+            }
+        }
+        buffer
+    }
+
+    fn memory_stats(&self) -> MemoryStats {
+        let cells = self.buffer.iter().map(Vec::len).sum();
+        MemoryStats {
+            cells,
+            // the current and the previous frame
+            buffer_bytes: buffer_bytes(cells) * 2,
+            style_cache_entries: 0,
+            distinct_glyphs: distinct_glyphs(self.buffer.iter().flatten()),
+            dom_nodes: None,
+        }
+    }
+}
+
+impl CanvasBackend {
+    /// Returns the geometry of the grid on the page.
+    fn geometry(&self) -> Option<GridGeometry> {
+        Some(measure_grid(
+            &self.canvas.inner,
+            self.canvas.metrics.cell_size(),
+            self.size().ok()?,
+        ))
+    }
+
+    /// Translates the mouse events to the cells of this backend.
+    fn set_grid(&self) {
+        let (canvas, cell_size) = (self.canvas.inner.clone(), self.canvas.metrics.cell_size());
+        if let Ok(size) = self.size() {
+            web::set_grid(move || Some(measure_grid(&canvas, cell_size, size)));
+        }
+    }
+}
+
+/// Measures a grid of the given size drawn on the given canvas.
+fn measure_grid(
+    canvas: &web_sys::HtmlCanvasElement,
+    cell_size: (f64, f64),
+    size: Size,
+) -> GridGeometry {
+    let canvas = canvas.get_bounding_client_rect();
+    GridGeometry {
+        // the cells are drawn with a 5px offset, see `update_grid`
+        origin: (canvas.left() + 5.0, canvas.top() + 5.0),
+        cell_size,
+        size,
+    }
+}
+
 /// Optimizes canvas rendering by batching adjacent cells with the same color into a single rectangle.
 ///
 /// This reduces the number of draw calls to the canvas API by coalescing adjacent cells
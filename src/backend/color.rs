@@ -1,10 +1,10 @@
-use ratatui::{
-    buffer::Cell,
-    prelude::{Color, Modifier},
-};
+use ratatui::prelude::Color;
+
+#[cfg(feature = "canvas")]
+use ratatui::{buffer::Cell, prelude::Modifier};
 
 /// Converts a [`Color`] to an 24-bit RGB value, with a fallback for reset colors.
-pub(super) fn to_rgb(color: Color, reset_fallback_rgb: u32) -> u32 {
+pub(crate) fn to_rgb(color: Color, reset_fallback_rgb: u32) -> u32 {
     match color {
         Color::Rgb(r, g, b) => ((r as u32) << 16) | ((g as u32) << 8) | b as u32,
         Color::Reset => reset_fallback_rgb,
@@ -39,6 +39,7 @@ pub(super) fn ansi_to_rgb(color: Color) -> Option<(u8, u8, u8)> {
 }
 
 /// Returns the actual foreground color of a cell, considering the `REVERSED` modifier.
+#[cfg(feature = "canvas")]
 pub(super) fn actual_fg_color(cell: &Cell) -> Color {
     if cell.modifier.contains(Modifier::REVERSED) {
         cell.bg
@@ -48,6 +49,7 @@ pub(super) fn actual_fg_color(cell: &Cell) -> Color {
 }
 
 /// Returns the actual background color of a cell, considering the `REVERSED` modifier.
+#[cfg(feature = "canvas")]
 pub(super) fn actual_bg_color(cell: &Cell) -> Color {
     if cell.modifier.contains(Modifier::REVERSED) {
         cell.fg
@@ -1,14 +1,28 @@
 use crate::backend::utils::CssAttribute;
-use ratatui::style::Style;
+use ratatui::style::{Color, Style};
+
+#[cfg(any(feature = "webgl2", feature = "webgpu"))]
+use ratatui::{buffer::Cell, style::Modifier};
+
+/// Time the cursor is shown, then hidden, when blinking, in milliseconds.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+pub(crate) const CURSOR_BLINK_MS: f64 = 530.0;
 
 /// Supported cursor shapes.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum CursorShape {
     /// A non blinking block cursor shape (█).
     #[default]
     SteadyBlock,
     /// A non blinking underscore cursor shape (_).
     SteadyUnderScore,
+    /// A non blinking bar cursor shape (▏), on the left edge of the cell.
+    SteadyBar,
     /// This variant is only used to clear cursor.
     None,
 }
@@ -18,16 +32,18 @@ impl CursorShape {
     pub fn hide(&self, style: Style) -> Style {
         match self {
             CursorShape::SteadyBlock => style.not_reversed(),
-            CursorShape::SteadyUnderScore => style.not_underlined(),
+            CursorShape::SteadyUnderScore | CursorShape::SteadyBar => style.not_underlined(),
             CursorShape::None => style,
         }
     }
 
     /// Transforms the given style to show the cursor.
+    ///
+    /// Bars can't be drawn with a style and are shown as underscores.
     pub fn show(&self, style: Style) -> Style {
         match self {
             CursorShape::SteadyBlock => style.reversed(),
-            CursorShape::SteadyUnderScore => style.underlined(),
+            CursorShape::SteadyUnderScore | CursorShape::SteadyBar => style.underlined(),
             CursorShape::None => style,
         }
     }
@@ -43,6 +59,10 @@ impl CursorShape {
                 field: "text-decoration",
                 value: Some("underline"),
             },
+            CursorShape::SteadyBar => CssAttribute {
+                field: "box-shadow",
+                value: Some("inset 2px 0 0 currentColor"),
+            },
             CursorShape::None => CssAttribute {
                 field: "text-decoration",
                 value: None,
@@ -50,3 +70,128 @@ impl CursorShape {
         }
     }
 }
+
+/// Options for drawing the cursor.
+///
+/// The cursor is drawn at the position set with
+/// [`Frame::set_cursor_position`](ratatui::Frame::set_cursor_position), so
+/// editor-style apps can show where the caret is.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CursorOptions {
+    /// The cursor shape.
+    pub(crate) shape: CursorShape,
+    /// Whether the cursor blinks.
+    pub(crate) blink: bool,
+    /// The cursor color, or `None` to use the color of the cell.
+    pub(crate) color: Option<Color>,
+}
+
+impl CursorOptions {
+    /// Constructs a new [`CursorOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the cursor shape.
+    ///
+    /// Defaults to [`CursorShape::SteadyBlock`].
+    pub fn shape(mut self, shape: CursorShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Sets whether the cursor blinks.
+    ///
+    /// Defaults to `false`.
+    pub fn blink(mut self, blink: bool) -> Self {
+        self.blink = blink;
+        self
+    }
+
+    /// Sets the cursor color.
+    ///
+    /// Defaults to the foreground color of the cell under the cursor, with
+    /// block cursors reversing the colors of the cell.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Returns `true` if the cursor is shown at the given time, in
+    /// milliseconds.
+    #[cfg(any(feature = "canvas", feature = "webgl2", feature = "webgpu"))]
+    pub(crate) fn is_visible(&self, time: f64) -> bool {
+        self.shape != CursorShape::None
+            && (!self.blink || ((time / CURSOR_BLINK_MS).floor() as u64).is_multiple_of(2))
+    }
+
+    /// Returns the given cell with the cursor drawn over it with its style.
+    ///
+    /// Bars can't be drawn with a style and are drawn as underscores.
+    #[cfg(any(feature = "webgl2", feature = "webgpu"))]
+    pub(crate) fn apply(&self, cell: &Cell) -> Cell {
+        let mut cell = cell.clone();
+        match (self.shape, self.color) {
+            (CursorShape::SteadyBlock, Some(color)) => {
+                let background = if cell.modifier.contains(Modifier::REVERSED) {
+                    cell.fg
+                } else {
+                    cell.bg
+                };
+                cell.modifier.remove(Modifier::REVERSED);
+                cell.fg = match background {
+                    Color::Reset => Color::Black,
+                    background => background,
+                };
+                cell.bg = color;
+            }
+            (CursorShape::SteadyBlock, None) => cell.modifier.toggle(Modifier::REVERSED),
+            (CursorShape::SteadyUnderScore | CursorShape::SteadyBar, color) => {
+                cell.modifier.insert(Modifier::UNDERLINED);
+                if let Some(color) = color {
+                    cell.fg = color;
+                }
+            }
+            (CursorShape::None, _) => {}
+        }
+        cell
+    }
+}
+
+#[cfg(all(test, any(feature = "canvas", feature = "webgl2", feature = "webgpu")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blink() {
+        let steady = CursorOptions::new();
+        assert!(steady.is_visible(0.0));
+        assert!(steady.is_visible(CURSOR_BLINK_MS));
+        let blinking = steady.blink(true);
+        assert!(blinking.is_visible(CURSOR_BLINK_MS - 1.0));
+        assert!(!blinking.is_visible(CURSOR_BLINK_MS));
+        assert!(blinking.is_visible(CURSOR_BLINK_MS * 2.0));
+        assert!(!CursorOptions::new()
+            .shape(CursorShape::None)
+            .is_visible(0.0));
+    }
+
+    #[test]
+    #[cfg(any(feature = "webgl2", feature = "webgpu"))]
+    fn test_apply() {
+        let mut cell = Cell::new("a");
+        cell.fg = Color::White;
+        let block = CursorOptions::new().apply(&cell);
+        assert!(block.modifier.contains(Modifier::REVERSED));
+
+        let block = CursorOptions::new().color(Color::Red).apply(&cell);
+        assert_eq!((block.fg, block.bg), (Color::Black, Color::Red));
+
+        let bar = CursorOptions::new()
+            .shape(CursorShape::SteadyBar)
+            .color(Color::Green)
+            .apply(&cell);
+        assert!(bar.modifier.contains(Modifier::UNDERLINED));
+        assert_eq!(bar.fg, Color::Green);
+    }
+}
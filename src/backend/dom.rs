@@ -6,26 +6,106 @@ use std::{
 
 use ratatui::{
     backend::WindowSize,
-    buffer::Cell,
-    layout::{Position, Size},
+    buffer::{Buffer, Cell},
+    layout::{Position, Rect, Size},
     prelude::{backend::ClearType, Backend},
+    style::Color,
 };
 use web_sys::{
-    wasm_bindgen::{prelude::Closure, JsCast},
-    window, Document, Element, Window,
+    js_sys::Array,
+    wasm_bindgen::{prelude::Closure, JsCast, JsValue},
+    window, CustomEvent, CustomEventInit, Document, Element, MutationObserver,
+    MutationObserverInit,
 };
 
 use unicode_width::UnicodeWidthStr;
 
-use crate::{backend::utils::*, error::Error, CursorShape};
+use crate::{
+    analytics::{self, AnalyticsEvent},
+    backend::cursor::CURSOR_BLINK_MS,
+    backend::{
+        image::ImageOverlay,
+        metrics::{page_metrics, CellMetrics},
+        stylesheet::StyleSheet,
+        utils::*,
+        web::{
+            self, buffer_bytes, distinct_glyphs, mirror_cell, CellRect, GridGeometry, MemoryStats,
+            WebBackend,
+        },
+    },
+    error::Error,
+    event, links, metadata, render_loop, resize, watchdog, CursorOptions, CursorShape,
+};
+
+/// Attributes set on the cells from their [`metadata`].
+const METADATA_ATTRIBUTES: [&str; 6] = [
+    "id",
+    "data-url",
+    "title",
+    "aria-label",
+    "data-widget",
+    "data-cols",
+];
+
+/// Name of the event dispatched on the grid by [`MutationGuard::Report`].
+pub const MUTATION_EVENT: &str = "ratzilla-dom-mutation";
+
+/// How the [`DomBackend`] reacts to changes of its grid made by someone else.
+///
+/// Browser extensions and page translators (e.g. Google Translate) rewrite
+/// the text of the page, which silently corrupts the grid since the backend
+/// only updates the cells that changed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MutationGuard {
+    /// Changes are not detected.
+    #[default]
+    Off,
+    /// The grid is rebuilt from the cells on screen on the next frame.
+    Repair,
+    /// A [`MUTATION_EVENT`] bubbling `CustomEvent` is dispatched on the grid
+    /// on the next frame, with the number of changes as its `detail`.
+    Report,
+}
+
+/// How the [`DomBackend`] interacts with page translators.
+///
+/// Browser auto-translation rewrites the text of the grid cell by cell,
+/// which destroys box drawing and layouts.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TranslationMode {
+    /// The grid can be translated.
+    #[default]
+    Allow,
+    /// The grid is marked with `translate="no"` and the `notranslate` class.
+    Block,
+    /// The grid is blocked as with [`TranslationMode::Block`] and hidden from
+    /// assistive technologies. Instead, a visually hidden text mirror with
+    /// one paragraph per row is added next to it, which can be translated
+    /// and read by screen readers.
+    Mirror,
+}
+
+/// CSS of the text mirror of [`TranslationMode::Mirror`].
+const TEXT_MIRROR_STYLE: &str = "position: absolute; width: 1px; height: 1px; \
+    overflow: hidden; clip-path: inset(50%); white-space: pre;";
 
 /// Options for the [`DomBackend`].
 #[derive(Debug, Default)]
 pub struct DomBackendOptions {
     /// The element ID.
     grid_id: Option<String>,
-    /// The cursor shape.
-    cursor_shape: CursorShape,
+    /// How the cursor is drawn.
+    cursor: CursorOptions,
+    /// Whether to set stable ids and data attributes on rows and cells.
+    element_ids: bool,
+    /// Reaction to external changes of the grid.
+    mutation_guard: MutationGuard,
+    /// Interaction with page translators.
+    translation_mode: TranslationMode,
+    /// Whether to render without inline styles.
+    csp_safe: bool,
+    /// Whether to only update the parts of the cells that changed.
+    incremental_updates: bool,
 }
 
 impl DomBackendOptions {
@@ -33,15 +113,78 @@ impl DomBackendOptions {
     pub fn new(grid_id: Option<String>, cursor_shape: CursorShape) -> Self {
         Self {
             grid_id,
-            cursor_shape,
+            cursor: CursorOptions::new().shape(cursor_shape),
+            element_ids: false,
+            mutation_guard: MutationGuard::Off,
+            translation_mode: TranslationMode::Allow,
+            csp_safe: false,
+            incremental_updates: false,
         }
     }
 
+    /// Sets whether to only update the parts of the cells that changed.
+    ///
+    /// When enabled, every drawn cell is compared with the cell on screen:
+    /// unchanged cells are not touched, and only the text or the style of a
+    /// cell is updated when the other didn't change. This reduces the DOM
+    /// writes, and the layout work of the browser, for large grids where
+    /// Ratatui redraws cells with the same content.
+    pub fn incremental_updates(mut self, enabled: bool) -> Self {
+        self.incremental_updates = enabled;
+        self
+    }
+
+    /// Sets whether to render without inline styles.
+    ///
+    /// When enabled, the cell styles are classes of a constructable stylesheet
+    /// instead of inline styles, so the app runs under a strict content
+    /// security policy (`style-src 'self'`). The cell contents are always set
+    /// as text nodes, which is also compatible with Trusted Types.
+    pub fn csp_safe(mut self, enabled: bool) -> Self {
+        self.csp_safe = enabled;
+        self
+    }
+
+    /// Sets how the cursor is drawn.
+    pub fn cursor_options(mut self, options: CursorOptions) -> Self {
+        self.cursor = options;
+        self
+    }
+
+    /// Sets how the grid interacts with page translators.
+    pub fn translation_mode(mut self, mode: TranslationMode) -> Self {
+        self.translation_mode = mode;
+        self
+    }
+
+    /// Sets how to react to changes of the grid made by someone else.
+    pub fn mutation_guard(mut self, guard: MutationGuard) -> Self {
+        self.mutation_guard = guard;
+        self
+    }
+
+    /// Sets whether to set stable ids and data attributes on rows and cells.
+    ///
+    /// When enabled, page scripts and stylesheets can target parts of the
+    /// terminal:
+    ///
+    /// - Rows are `<pre class="ratzilla-row" data-row="3">` elements with the
+    ///   id `{grid_id}_row_3`.
+    /// - Cells are `<span class="ratzilla-cell" data-row="3" data-col="7">`
+    ///   elements.
+    /// - Cells in a [`metadata`] region with an id also get `data-widget`
+    ///   set to that id and `data-cols` set to the columns of the region,
+    ///   e.g. `"4-12"`.
+    pub fn element_ids(mut self, enabled: bool) -> Self {
+        self.element_ids = enabled;
+        self
+    }
+
     /// Returns the grid ID.
     ///
     /// - If the grid ID is not set, it returns `"grid"`.
     /// - If the grid ID is set, it returns the grid ID suffixed with
-    ///     `"_ratzilla_grid"`.
+    ///   `"_ratzilla_grid"`.
     pub fn grid_id(&self) -> String {
         match &self.grid_id {
             Some(id) => format!("{id}_ratzilla_grid"),
@@ -51,7 +194,38 @@ impl DomBackendOptions {
 
     /// Returns the [`CursorShape`].
     pub fn cursor_shape(&self) -> &CursorShape {
-        &self.cursor_shape
+        &self.cursor.shape
+    }
+
+    /// Returns the [`CursorOptions`].
+    pub fn get_cursor_options(&self) -> &CursorOptions {
+        &self.cursor
+    }
+
+    /// Returns whether stable ids and data attributes are set on rows and
+    /// cells.
+    pub fn has_element_ids(&self) -> bool {
+        self.element_ids
+    }
+
+    /// Returns the [`MutationGuard`].
+    pub fn get_mutation_guard(&self) -> MutationGuard {
+        self.mutation_guard
+    }
+
+    /// Returns the [`TranslationMode`].
+    pub fn get_translation_mode(&self) -> TranslationMode {
+        self.translation_mode
+    }
+
+    /// Returns whether the backend renders without inline styles.
+    pub fn is_csp_safe(&self) -> bool {
+        self.csp_safe
+    }
+
+    /// Returns whether only the parts of the cells that changed are updated.
+    pub fn has_incremental_updates(&self) -> bool {
+        self.incremental_updates
     }
 }
 
@@ -71,18 +245,40 @@ pub struct DomBackend {
     grid: Element,
     /// The parent of the grid element.
     grid_parent: Element,
-    /// Window.
-    window: Window,
     /// Document.
     document: Document,
     /// Options.
     options: DomBackendOptions,
     /// Cursor position.
     cursor_position: Option<Position>,
-    /// Last Cursor position.
-    last_cursor_position: Option<Position>,
+    /// Position of the cell with the cursor class, if any.
+    drawn_cursor: Option<Position>,
     /// Buffer size to pass to [`ratatui::Terminal`]
     size: Size,
+    /// Copy of the cells on screen.
+    buffer: Buffer,
+    /// Area of the cells updated in the current frame.
+    dirty: Option<Rect>,
+    /// Generation of the cell metadata set on the cells.
+    metadata_generation: Option<u64>,
+    /// Indices of the cells with metadata attributes.
+    metadata_cells: Vec<usize>,
+    /// Generation of the links wrapped around the cells.
+    link_generation: Option<u64>,
+    /// `<a>` elements wrapping the cells of the links.
+    link_anchors: Vec<Element>,
+    /// Elements of the [`images`](crate::images) over the grid.
+    images: ImageOverlay,
+    /// Observer of external changes of the grid, see [`MutationGuard`].
+    mutation_observer: Option<MutationObserver>,
+    /// Number of external changes of the grid since the last frame.
+    mutations: Rc<RefCell<usize>>,
+    /// Text mirror of [`TranslationMode::Mirror`].
+    text_mirror: Option<Element>,
+    /// Paragraphs of the text mirror and their text.
+    text_mirror_rows: Vec<(Element, String)>,
+    /// Stylesheet holding the cell styles in CSP safe mode.
+    stylesheet: Option<StyleSheet>,
 }
 
 impl DomBackend {
@@ -101,10 +297,61 @@ impl DomBackend {
 
     /// Set the [`CursorShape`].
     pub fn set_cursor_shape(mut self, shape: CursorShape) -> Self {
-        self.options.cursor_shape = shape;
+        self.options.cursor.shape = shape;
+        self
+    }
+
+    /// Set the [`CursorOptions`].
+    pub fn set_cursor_options(mut self, options: CursorOptions) -> Self {
+        self.options.cursor = options;
+        self
+    }
+
+    /// Set whether to render without inline styles.
+    ///
+    /// See [`DomBackendOptions::csp_safe`].
+    pub fn set_csp_safe(mut self, enabled: bool) -> Self {
+        self.options.csp_safe = enabled;
+        self
+    }
+
+    /// Set how the grid interacts with page translators.
+    ///
+    /// See [`TranslationMode`].
+    pub fn set_translation_mode(mut self, mode: TranslationMode) -> Self {
+        self.options.translation_mode = mode;
         self
     }
 
+    /// Set how to react to changes of the grid made by someone else.
+    ///
+    /// See [`MutationGuard`].
+    pub fn set_mutation_guard(mut self, guard: MutationGuard) -> Self {
+        self.options.mutation_guard = guard;
+        self
+    }
+
+    /// Set whether to set stable ids and data attributes on rows and cells.
+    ///
+    /// See [`DomBackendOptions::element_ids`].
+    pub fn set_element_ids(mut self, enabled: bool) -> Self {
+        self.options.element_ids = enabled;
+        self
+    }
+
+    /// Set whether to only update the parts of the cells that changed.
+    ///
+    /// See [`DomBackendOptions::incremental_updates`].
+    pub fn set_incremental_updates(mut self, enabled: bool) -> Self {
+        self.options.incremental_updates = enabled;
+        self
+    }
+
+    /// Returns the area of the cells updated in the last frame, if any.
+    pub fn dirty_region(&self) -> Option<Rect> {
+        self.dirty
+    }
+
     /// Constructs a new [`DomBackend`] with the given options.
     pub fn new_with_options(options: DomBackendOptions) -> Result<Self, Error> {
         let window = window().ok_or(Error::UnableToRetrieveWindow)?;
@@ -115,33 +362,315 @@ impl DomBackend {
             grid: document.create_element("div")?,
             grid_parent: get_element_by_id_or_body(options.grid_id.as_ref())?,
             options,
-            window,
             document,
             cursor_position: None,
-            last_cursor_position: None,
-            size: get_size(),
+            drawn_cursor: None,
+            size: Size::default(),
+            buffer: Buffer::default(),
+            dirty: None,
+            metadata_generation: None,
+            metadata_cells: vec![],
+            link_generation: None,
+            link_anchors: vec![],
+            images: ImageOverlay::new(),
+            mutation_observer: None,
+            mutations: Rc::new(RefCell::new(0)),
+            text_mirror: None,
+            text_mirror_rows: vec![],
+            stylesheet: None,
         };
-        backend.add_on_resize_listener();
+        backend.size = backend.container_size();
+        backend.add_on_resize_listener()?;
         backend.reset_grid()?;
+        analytics::report(AnalyticsEvent::BackendSelected { backend: "dom" });
         Ok(backend)
     }
 
-    /// Add a listener to the window resize event.
-    fn add_on_resize_listener(&mut self) {
+    /// Add a listener to the resize events of the window and of the parent
+    /// element, once resizing stopped.
+    fn add_on_resize_listener(&mut self) -> Result<(), Error> {
         let initialized = self.initialized.clone();
-        let closure = Closure::<dyn FnMut(_)>::new(move |_: web_sys::Event| {
+        resize::on_window_resize(move || {
             initialized.replace(false);
-        });
-        self.window
-            .set_onresize(Some(closure.as_ref().unchecked_ref()));
-        closure.forget();
+        })?;
+        if self.options.grid_id.is_some() {
+            resize::observe_element(&self.grid_parent)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of cells fitting in the parent element of the
+    /// grid, or in the window without one.
+    fn container_size(&self) -> Size {
+        if self.options.grid_id.is_none() {
+            return get_size();
+        }
+        page_metrics().grid_size(
+            self.grid_parent.client_width() as f64,
+            self.grid_parent.client_height() as f64,
+        )
+    }
+
+    /// Updates the rows of the text mirror that changed, if enabled.
+    fn update_text_mirror(&mut self) -> Result<(), Error> {
+        if self.options.translation_mode != TranslationMode::Mirror {
+            return Ok(());
+        }
+        // the mirror is removed with the grid on resize
+        let mirror = match &self.text_mirror {
+            Some(mirror) if mirror.is_connected() => mirror.clone(),
+            _ => {
+                let mirror = self.document.create_element("div")?;
+                mirror.set_attribute("class", "ratzilla-text-mirror")?;
+                if !self.options.csp_safe {
+                    mirror.set_attribute("style", TEXT_MIRROR_STYLE)?;
+                }
+                self.grid_parent.append_child(&mirror)?;
+                self.text_mirror = Some(mirror.clone());
+                self.text_mirror_rows.clear();
+                mirror
+            }
+        };
+
+        let area = self.buffer.area;
+        // only the rows with updated cells can change, once all rows exist
+        let rows = match self.dirty {
+            _ if self.text_mirror_rows.len() < area.height as usize => area.top()..area.bottom(),
+            Some(dirty) => dirty.top()..dirty.bottom().min(area.bottom()),
+            None => return Ok(()),
+        };
+        for y in rows {
+            let text = (area.left()..area.right())
+                .filter_map(|x| self.buffer.cell((x, y)))
+                .map(|cell| cell.symbol())
+                .collect::<String>();
+            let text = text.trim_end();
+            let row = (y - area.top()) as usize;
+            if row >= self.text_mirror_rows.len() {
+                let paragraph = self.document.create_element("p")?;
+                mirror.append_child(&paragraph)?;
+                self.text_mirror_rows.push((paragraph, String::new()));
+            }
+            let (paragraph, current) = &mut self.text_mirror_rows[row];
+            // keep the translated text of the rows that didn't change
+            if current.as_str() != text {
+                paragraph.set_text_content(Some(text));
+                *current = text.to_string();
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts watching the grid for external changes, if enabled.
+    ///
+    /// This is called at the end of each frame, so the changes made by the
+    /// backend itself are not observed.
+    fn observe_mutations(&mut self) -> Result<(), Error> {
+        if self.options.mutation_guard == MutationGuard::Off {
+            return Ok(());
+        }
+        if self.mutation_observer.is_none() {
+            let mutations = self.mutations.clone();
+            let closure = Closure::<dyn FnMut(_)>::new(move |records: Array| {
+                *mutations.borrow_mut() += records.length() as usize;
+            });
+            // freed with the observer, which is disconnected when dropped
+            self.mutation_observer = Some(MutationObserver::new(
+                closure.into_js_value().unchecked_ref(),
+            )?);
+        }
+        if let Some(observer) = &self.mutation_observer {
+            let options = MutationObserverInit::new();
+            options.set_attributes(true);
+            options.set_character_data(true);
+            options.set_child_list(true);
+            options.set_subtree(true);
+            observer.observe_with_options(&self.grid, &options)?;
+        }
+        Ok(())
+    }
+
+    /// Stops watching the grid and returns the number of external changes
+    /// since the last frame.
+    fn take_mutations(&mut self) -> usize {
+        let Some(observer) = &self.mutation_observer else {
+            return 0;
+        };
+        let pending = observer.take_records().length() as usize;
+        observer.disconnect();
+        self.mutations.replace(0) + pending
+    }
+
+    /// Reacts to external changes of the grid according to the
+    /// [`MutationGuard`].
+    fn handle_mutations(&mut self, count: usize) -> Result<(), Error> {
+        match self.options.mutation_guard {
+            MutationGuard::Off => {}
+            MutationGuard::Repair => self.repair()?,
+            MutationGuard::Report => {
+                let init = CustomEventInit::new();
+                init.set_bubbles(true);
+                init.set_detail(&JsValue::from(count as u32));
+                let event = CustomEvent::new_with_event_init_dict(MUTATION_EVENT, &init)?;
+                self.grid.dispatch_event(&event)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the grid and redraws the cells on screen.
+    fn repair(&mut self) -> Result<(), Error> {
+        let buffer = std::mem::take(&mut self.buffer);
+        self.grid.remove();
+        self.reset_grid()?;
+        self.grid_parent.append_child(&self.grid)?;
+        self.populate()?;
+        for position in buffer.area.positions() {
+            if let Some(cell) = buffer.cell(position) {
+                self.draw_cell(position.x, position.y, cell)?;
+            }
+        }
+        // the cursor class was lost with the old cells
+        self.drawn_cursor = None;
+        Ok(())
+    }
+
+    /// Draws a cell to the screen.
+    ///
+    /// With incremental updates, only the text or the style of the cell
+    /// element is updated if the other didn't change, and unchanged cells are
+    /// skipped.
+    fn draw_cell(&mut self, x: u16, y: u16, cell: &Cell) -> Result<(), Error> {
+        let cell_position = (y * self.size.width + x) as usize;
+        let (same_symbol, same_style) = match self.buffer.cell((x, y)) {
+            Some(current) if self.options.incremental_updates => (
+                current.symbol() == cell.symbol(),
+                current.style() == cell.style(),
+            ),
+            _ => (false, false),
+        };
+        if same_symbol && same_style {
+            return Ok(());
+        }
+        mirror_cell(&mut self.buffer, x, y, cell);
+        self.mark_dirty(x, y);
+        if same_symbol {
+            return self.set_cell_style(cell_position, cell);
+        } else if same_style {
+            self.cells[cell_position].set_text_content(Some(cell.symbol()));
+        } else {
+            self.set_cell(cell_position, cell)?;
+        }
+
+        // don't display the next cell if a fullwidth glyph preceeds it
+        if cell.symbol().len() > 1
+            && cell.symbol().width() == 2
+            && (cell_position + 1) < self.cells.len()
+        {
+            self.set_cell(cell_position + 1, &Cell::new(""))?;
+            // so that redrawing the next cell is not skipped
+            mirror_cell(&mut self.buffer, x + 1, y, &Cell::new(""));
+        }
+        Ok(())
+    }
+
+    /// Adds a cell to the area of the cells updated in the current frame.
+    fn mark_dirty(&mut self, x: u16, y: u16) {
+        let cell = Rect::new(x, y, 1, 1);
+        self.dirty = Some(self.dirty.map_or(cell, |dirty| dirty.union(cell)));
+    }
+
+    /// Sets the content and the style of the cell element at the given index.
+    fn set_cell(&mut self, index: usize, cell: &Cell) -> Result<(), Error> {
+        self.set_cell_style(index, cell)?;
+        // a text node, so untrusted cell contents can't inject markup
+        self.cells[index].set_text_content(Some(cell.symbol()));
+        Ok(())
+    }
+
+    /// Sets the style of the cell element at the given index.
+    fn set_cell_style(&mut self, index: usize, cell: &Cell) -> Result<(), Error> {
+        let css = get_cell_style_as_css(cell);
+        if self.options.csp_safe {
+            let mut class = self.stylesheet()?.class_for(&css)?.to_string();
+            if self.options.element_ids {
+                class.insert_str(0, "ratzilla-cell ");
+            }
+            self.cells[index].set_attribute("class", &class)?;
+        } else {
+            self.cells[index].set_attribute("style", &css)?;
+        }
+        Ok(())
+    }
+
+    /// Moves the cursor class to the cell at the cursor position.
+    ///
+    /// The cursor is drawn by the rules of the stylesheet, see
+    /// [`cursor_rules`].
+    fn update_cursor(&mut self) -> Result<(), Error> {
+        let cursor = self
+            .cursor_position
+            .filter(|_| self.options.cursor.shape != CursorShape::None);
+        if self.drawn_cursor != cursor {
+            if let Some(element) = self.drawn_cursor.and_then(|pos| self.cell_element(pos)) {
+                element.class_list().remove_1("ratzilla-cursor")?;
+            }
+        }
+        self.drawn_cursor = None;
+        if let Some(pos) = cursor {
+            self.stylesheet()?;
+            // set on every frame, the class of the cells is replaced in CSP
+            // safe mode
+            if let Some(element) = self.cell_element(pos) {
+                element.class_list().add_1("ratzilla-cursor")?;
+                self.drawn_cursor = cursor;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the element of the cell at the given position.
+    fn cell_element(&self, position: Position) -> Option<&Element> {
+        if position.x >= self.size.width {
+            return None;
+        }
+        let index = position.y as usize * self.size.width as usize + position.x as usize;
+        self.cells.get(index)
+    }
+
+    /// Returns the stylesheet of CSP safe mode, creating it on first use.
+    fn stylesheet(&mut self) -> Result<&mut StyleSheet, Error> {
+        if let Some(stylesheet) = self.stylesheet.take() {
+            return Ok(self.stylesheet.insert(stylesheet));
+        }
+        let stylesheet = StyleSheet::new(&self.document)?;
+        let grid_id = self.options.grid_id();
+        stylesheet.insert_rule(&format!("#{grid_id} > pre {{ height: 15px; }}"))?;
+        stylesheet.insert_rule(&format!(".ratzilla-text-mirror {{ {TEXT_MIRROR_STYLE} }}"))?;
+        // the cells of a link keep their own styles
+        stylesheet.insert_rule(".ratzilla-link { color: inherit; text-decoration: none; }")?;
+        for rule in cursor_rules(&self.options.cursor) {
+            stylesheet.insert_rule(&rule)?;
+        }
+        Ok(self.stylesheet.insert(stylesheet))
     }
 
     /// Reset the grid and clear the cells.
     fn reset_grid(&mut self) -> Result<(), Error> {
         self.grid = self.document.create_element("div")?;
         self.grid.set_attribute("id", &self.options.grid_id())?;
+        if self.options.translation_mode != TranslationMode::Allow {
+            self.grid.set_attribute("translate", "no")?;
+            self.grid.set_attribute("class", "notranslate")?;
+        }
+        if self.options.translation_mode == TranslationMode::Mirror {
+            self.grid.set_attribute("aria-hidden", "true")?;
+        }
         self.cells.clear();
+        self.metadata_generation = None;
+        self.metadata_cells.clear();
+        self.link_generation = None;
+        self.link_anchors.clear();
         Ok(())
     }
 
@@ -150,17 +679,41 @@ impl DomBackend {
     /// This function is called from [`draw`] once (or after a resize)
     /// to render the right number of cells to the screen.
     fn populate(&mut self) -> Result<(), Error> {
-        for _y in 0..self.size.height {
+        // the terminal uses one row and column less than the grid, see `size`
+        self.buffer = Buffer::empty(Rect::new(
+            0,
+            0,
+            self.size.width.saturating_sub(1),
+            self.size.height.saturating_sub(1),
+        ));
+        let grid_id = self.options.grid_id();
+        for y in 0..self.size.height {
             let mut line_cells: Vec<Element> = Vec::new();
-            for _x in 0..self.size.width {
-                let span = create_span(&self.document, &Cell::default())?;
+            for x in 0..self.size.width {
+                let span = if self.options.csp_safe {
+                    self.document.create_element("span")?
+                } else {
+                    create_span(&self.document, &Cell::default())?
+                };
+                if self.options.element_ids {
+                    span.set_attribute("class", "ratzilla-cell")?;
+                    span.set_attribute("data-row", &y.to_string())?;
+                    span.set_attribute("data-col", &x.to_string())?;
+                }
                 self.cells.push(span.clone());
                 line_cells.push(span);
             }
 
             // Create a <pre> element for the line
             let pre = self.document.create_element("pre")?;
-            pre.set_attribute("style", "height: 15px;")?;
+            if !self.options.csp_safe {
+                pre.set_attribute("style", "height: 15px;")?;
+            }
+            if self.options.element_ids {
+                pre.set_attribute("id", &format!("{grid_id}_row_{y}"))?;
+                pre.set_attribute("class", "ratzilla-row")?;
+                pre.set_attribute("data-row", &y.to_string())?;
+            }
 
             // Append all elements (spans and anchors) to the <pre>
             for elem in line_cells {
@@ -170,10 +723,153 @@ impl DomBackend {
             // Append the <pre> to the grid
             self.grid.append_child(&pre)?;
         }
+        if self.options.csp_safe {
+            for index in 0..self.cells.len() {
+                self.set_cell(index, &Cell::default())?;
+            }
+        }
+
+        // translate the mouse events to the new cells
+        if let Some(first) = self.cells.first().cloned() {
+            let below = self.cells.get(self.size.width as usize).cloned();
+            let size = self.buffer.area.as_size();
+            web::set_grid(move || Some(measure_grid(&first, below.as_ref(), size)));
+        }
+        Ok(())
+    }
+
+    /// Wraps the cells of each row of the [`links`] of the frame on screen in
+    /// an `<a>` element.
+    fn apply_links(&mut self) -> Result<(), Error> {
+        let generation = links::generation();
+        if self.link_generation == Some(generation) {
+            return Ok(());
+        }
+        self.link_generation = Some(generation);
+        self.stylesheet()?;
+
+        // move the cells of the previous links back into their rows
+        for anchor in self.link_anchors.drain(..) {
+            if let Some(row) = anchor.parent_node() {
+                while let Some(cell) = anchor.first_child() {
+                    row.insert_before(&cell, Some(&anchor))?;
+                }
+                row.remove_child(&anchor)?;
+            }
+        }
+
+        // anchors can't be nested, so the topmost link wins where they overlap
+        let mut linked = vec![false; self.cells.len()];
+        let width = self.size.width as usize;
+        for link in links::links().iter().rev() {
+            if !links::is_safe_url(&link.url) {
+                continue;
+            }
+            let area = link
+                .area
+                .intersection(Rect::from((Position::ORIGIN, self.size)));
+            for y in area.top()..area.bottom() {
+                let start = y as usize * width + area.x as usize;
+                let range = start..start + area.width as usize;
+                if range.end > self.cells.len() || linked[range.clone()].contains(&true) {
+                    continue;
+                }
+                let Some(row) = self.cells[start].parent_node() else {
+                    continue;
+                };
+                let anchor = self.document.create_element("a")?;
+                anchor.set_attribute("href", &link.url)?;
+                anchor.set_attribute("target", "_blank")?;
+                anchor.set_attribute("rel", "noopener noreferrer")?;
+                anchor.set_attribute("class", "ratzilla-link")?;
+                row.insert_before(&anchor, Some(&self.cells[start]))?;
+                for cell in &self.cells[range.clone()] {
+                    anchor.append_child(cell)?;
+                }
+                linked[range].fill(true);
+                self.link_anchors.push(anchor);
+            }
+        }
+        Ok(())
+    }
+
+    /// Places the [`images`](crate::images) of the frame on screen over the
+    /// grid.
+    fn update_images(&mut self) -> Result<(), Error> {
+        let mut images = std::mem::take(&mut self.images);
+        let result = images.update(|| self.geometry());
+        self.images = images;
+        result
+    }
+
+    /// Sets the [`metadata`] of the frame on screen as attributes of the cells.
+    fn apply_metadata(&mut self) -> Result<(), Error> {
+        let generation = metadata::generation();
+        if self.metadata_generation == Some(generation) {
+            return Ok(());
+        }
+        self.metadata_generation = Some(generation);
+
+        for index in self.metadata_cells.drain(..) {
+            if let Some(elem) = self.cells.get(index) {
+                for attribute in METADATA_ATTRIBUTES {
+                    elem.remove_attribute(attribute)?;
+                }
+            }
+        }
+
+        for (area, metadata) in metadata::regions() {
+            for position in area.positions() {
+                let index = (position.y * self.size.width + position.x) as usize;
+                let Some(elem) = self.cells.get(index) else {
+                    continue;
+                };
+                // ids must be unique, so only the first cell gets it
+                if position == area.as_position() {
+                    if let Some(id) = &metadata.id {
+                        elem.set_attribute("id", id)?;
+                    }
+                }
+                if let Some(url) = &metadata.url {
+                    elem.set_attribute("data-url", url)?;
+                }
+                if let Some(tooltip) = &metadata.tooltip {
+                    elem.set_attribute("title", tooltip)?;
+                }
+                if let Some(label) = &metadata.aria_label {
+                    elem.set_attribute("aria-label", label)?;
+                }
+                if let (true, Some(id)) = (self.options.element_ids, &metadata.id) {
+                    elem.set_attribute("data-widget", id)?;
+                    elem.set_attribute(
+                        "data-cols",
+                        &format!("{}-{}", area.left(), area.right() - 1),
+                    )?;
+                }
+                self.metadata_cells.push(index);
+            }
+        }
         Ok(())
     }
 }
 
+impl Drop for DomBackend {
+    /// Removes the grid, the text mirror and the stylesheet from the page,
+    /// e.g. on [teardown](crate::teardown).
+    fn drop(&mut self) {
+        if let Some(observer) = &self.mutation_observer {
+            observer.disconnect();
+        }
+        self.grid.remove();
+        if let Some(mirror) = &self.text_mirror {
+            mirror.remove();
+        }
+        if let Some(stylesheet) = &self.stylesheet {
+            let _ = stylesheet.remove(&self.document);
+        }
+    }
+}
+
 impl Backend for DomBackend {
     type Error = IoError;
 
@@ -185,12 +881,15 @@ impl Backend for DomBackend {
     where
         I: Iterator<Item = (u16, u16, &'a Cell)>,
     {
+        watchdog::mark_flush_start();
+        let mutations = self.take_mutations();
+        self.dirty = None;
         if !*self.initialized.borrow() {
             self.initialized.replace(true);
 
-            // Clear cursor position to avoid modifying css style of a non-existent cell
+            // Clear cursor position to avoid modifying the class of a non-existent cell
             self.cursor_position = None;
-            self.last_cursor_position = None;
+            self.drawn_cursor = None;
 
             // Only runs on resize event.
             if self
@@ -198,37 +897,26 @@ impl Backend for DomBackend {
                 .get_element_by_id(&self.options.grid_id())
                 .is_some()
             {
-                self.grid_parent.set_inner_html("");
+                self.grid_parent.set_text_content(None);
+                // the input of input method editors may have been in it
+                event::restore_capture_element()?;
                 self.reset_grid()?;
 
-                // update size
-                self.size = get_size();
+                // update size, the terminal picks it up on the next frame
+                self.size = self.container_size();
+                render_loop::request_redraw();
             }
 
             self.grid_parent
                 .append_child(&self.grid)
                 .map_err(Error::from)?;
             self.populate()?;
+        } else if mutations > 0 {
+            self.handle_mutations(mutations)?;
         }
 
         for (x, y, cell) in content {
-            let cell_position = (y * self.size.width + x) as usize;
-            let elem = &self.cells[cell_position];
-
-            elem.set_inner_html(cell.symbol());
-            elem.set_attribute("style", &get_cell_style_as_css(cell))
-                .map_err(Error::from)?;
-
-            // don't display the next cell if a fullwidth glyph preceeds it
-            if cell.symbol().len() > 1 && cell.symbol().width() == 2 {
-                if (cell_position + 1) < self.cells.len() {
-                    let next_elem = &self.cells[cell_position + 1];
-                    next_elem.set_inner_html("");
-                    next_elem
-                        .set_attribute("style", &get_cell_style_as_css(&Cell::new("")))
-                        .map_err(Error::from)?;
-                }
-            }
+            self.draw_cell(x, y, cell)?;
         }
 
         Ok(())
@@ -236,49 +924,26 @@ impl Backend for DomBackend {
 
     /// This function is called after the [`DomBackend::draw`] function.
     ///
-    /// This function does nothing because the content is directly
-    /// displayed by the draw function.
+    /// The content is directly displayed by the draw function, so this only
+    /// updates the attributes of the cells from their [`metadata`], the
+    /// [`links`], the images over the grid and the text mirror, and starts
+    /// watching the grid for external changes, see [`MutationGuard`].
     fn flush(&mut self) -> IoResult<()> {
+        self.apply_metadata()?;
+        self.apply_links()?;
+        self.update_images()?;
+        self.update_cursor()?;
+        self.update_text_mirror()?;
+        self.observe_mutations()?;
         Ok(())
     }
 
     fn hide_cursor(&mut self) -> IoResult<()> {
-        if let Some(pos) = self.cursor_position {
-            let cell_position = (pos.y * self.size.width + pos.x) as usize;
-
-            // Use CursorShape::None to clear cursor CSS
-            update_css_field(
-                CursorShape::None.get_css_attribute(),
-                &self.cells[cell_position],
-            )
-            .map_err(Error::from)?;
-        }
-
+        self.cursor_position = None;
         Ok(())
     }
 
     fn show_cursor(&mut self) -> IoResult<()> {
-        // Remove cursor at last position
-        if let Some(pos) = self.last_cursor_position {
-            let cell_position = (pos.y * self.size.width + pos.x) as usize;
-            update_css_field(
-                CursorShape::None.get_css_attribute(),
-                &self.cells[cell_position],
-            )
-            .map_err(Error::from)?;
-        }
-
-        // Show cursor at current position
-        if let Some(pos) = self.cursor_position {
-            let cell_position = (pos.y * self.size.width + pos.x) as usize;
-
-            update_css_field(
-                self.options.cursor_shape.get_css_attribute(),
-                &self.cells[cell_position],
-            )
-            .map_err(Error::from)?;
-        }
-
         Ok(())
     }
 
@@ -312,9 +977,8 @@ impl Backend for DomBackend {
         }
     }
 
-    /// Update cursor_position and last_cursor_position
+    /// Update cursor_position, the cursor is drawn on flush.
     fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> IoResult<()> {
-        self.last_cursor_position = self.cursor_position;
         self.cursor_position = Some(position.into());
 
         Ok(())
@@ -327,3 +991,336 @@ impl Backend for DomBackend {
         }
     }
 }
+
+impl WebBackend for DomBackend {
+    fn cell_at(&self, x: f64, y: f64) -> Option<Position> {
+        self.geometry()?.cell_at(x, y)
+    }
+
+    fn cell_rect(&self, column: u16, row: u16) -> Option<CellRect> {
+        self.geometry()?.cell_rect(column, row)
+    }
+
+    fn cell_metrics(&self) -> CellMetrics {
+        match self.geometry() {
+            Some(geometry) => CellMetrics::new(geometry.cell_size.0, geometry.cell_size.1),
+            // before the grid is populated
+            None => page_metrics(),
+        }
+    }
+
+    fn current_buffer(&self) -> Buffer {
+        self.buffer.clone()
+    }
+
+    fn memory_stats(&self) -> MemoryStats {
+        let cells = self.buffer.content.len();
+        // the grid, its rows and cells, and the text mirror and its rows
+        let mut dom_nodes = 1 + self.size.height as usize + self.cells.len();
+        if self.text_mirror.is_some() {
+            dom_nodes += 1 + self.text_mirror_rows.len();
+        }
+        MemoryStats {
+            cells,
+            buffer_bytes: buffer_bytes(cells),
+            style_cache_entries: self.stylesheet.as_ref().map_or(0, StyleSheet::class_count),
+            distinct_glyphs: distinct_glyphs(&self.buffer.content),
+            dom_nodes: Some(dom_nodes),
+        }
+    }
+}
+
+impl DomBackend {
+    /// Returns the geometry of the grid, measured from its first cells.
+    fn geometry(&self) -> Option<GridGeometry> {
+        Some(measure_grid(
+            self.cells.first()?,
+            self.cells.get(self.size.width as usize),
+            self.size().ok()?,
+        ))
+    }
+}
+
+/// Measures a grid of the given size from its first cell and the cell below.
+fn measure_grid(first: &Element, below: Option<&Element>, size: Size) -> GridGeometry {
+    let first = first.get_bounding_client_rect();
+    // rows are taller than the spans, so measure the distance between two rows
+    let height = match below {
+        Some(below) => below.get_bounding_client_rect().top() - first.top(),
+        None => first.height(),
+    };
+    GridGeometry {
+        origin: (first.left(), first.top()),
+        cell_size: (first.width(), height),
+        size,
+    }
+}
+
+/// Returns the CSS rules drawing the cursor on the cell with the
+/// `ratzilla-cursor` class.
+///
+/// The cursor is a pseudo-element over the cell, so it doesn't change the
+/// styles of the cell and can blink with an animation.
+fn cursor_rules(cursor: &CursorOptions) -> Vec<String> {
+    let color = match cursor.color {
+        Some(color) => get_canvas_color(color, Color::White).to_string(),
+        None => "currentColor".to_string(),
+    };
+    let overlay = match (cursor.shape, cursor.color) {
+        // behind the text of the cell, over its background
+        (CursorShape::SteadyBlock, Some(_)) => {
+            format!("inset: 0; background: {color}; z-index: -1;")
+        }
+        // reverses the colors of the cell
+        (CursorShape::SteadyBlock, None) => {
+            "inset: 0; background: white; mix-blend-mode: difference;".to_string()
+        }
+        (CursorShape::SteadyUnderScore, _) => {
+            format!("left: 0; right: 0; bottom: 0; height: 2px; background: {color};")
+        }
+        (CursorShape::SteadyBar, _) => {
+            format!("left: 0; top: 0; bottom: 0; width: 2px; background: {color};")
+        }
+        (CursorShape::None, _) => return Vec::new(),
+    };
+    let mut rules = vec![
+        ".ratzilla-cursor { position: relative; z-index: 0; }".to_string(),
+        format!(
+            ".ratzilla-cursor::after {{ content: ''; position: absolute; \
+             pointer-events: none; {overlay} }}"
+        ),
+    ];
+    if cursor.blink {
+        rules.push("@keyframes ratzilla-cursor-blink { 50% { opacity: 0; } }".to_string());
+        rules.push(format!(
+            ".ratzilla-cursor::after {{ animation: ratzilla-cursor-blink {}ms step-end infinite; }}",
+            CURSOR_BLINK_MS * 2.0
+        ));
+    }
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Style;
+    use wasm_bindgen_test::*;
+    use web_sys::js_sys::{Function, Reflect};
+
+    use super::*;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Creates the parent element of a grid with the given ID.
+    fn create_parent(id: &str) -> Element {
+        let document = window()
+            .and_then(|window| window.document())
+            .expect("failed to get the document");
+        let parent = document
+            .create_element("div")
+            .expect("failed to create the parent");
+        parent.set_id(id);
+        parent
+            .set_attribute("style", "width: 400px; height: 200px;")
+            .expect("failed to set the size of the parent");
+        document
+            .body()
+            .expect("failed to get the body")
+            .append_child(&parent)
+            .expect("failed to append the parent");
+        parent
+    }
+
+    /// Returns a buffer filling the area of the backend with the given symbol.
+    fn filled(backend: &DomBackend, symbol: &str) -> Buffer {
+        let size = backend.size().expect("failed to get the size");
+        let mut buffer = Buffer::empty(Rect::from((Position::ORIGIN, size)));
+        for cell in buffer.content.iter_mut() {
+            cell.set_symbol(symbol);
+        }
+        buffer
+    }
+
+    /// Draws all the cells of the buffer and flushes the backend.
+    fn draw(backend: &mut DomBackend, buffer: &Buffer) {
+        let content = buffer.area.positions().filter_map(|position| {
+            let cell = buffer.cell(position)?;
+            Some((position.x, position.y, cell))
+        });
+        backend.draw(content).expect("failed to draw");
+        backend.flush().expect("failed to flush");
+    }
+
+    /// Draws the buffer and returns the indices of the cells whose text
+    /// changed and of the cells whose attributes changed.
+    fn draw_observed(backend: &mut DomBackend, buffer: &Buffer) -> (Vec<usize>, Vec<usize>) {
+        let observer =
+            MutationObserver::new(&Function::new_no_args("")).expect("failed to create observer");
+        let options = MutationObserverInit::new();
+        options.set_attributes(true);
+        options.set_child_list(true);
+        options.set_subtree(true);
+        observer
+            .observe_with_options(&backend.grid, &options)
+            .expect("failed to observe the grid");
+        draw(backend, buffer);
+        let (mut text, mut attributes) = (vec![], vec![]);
+        for record in observer.take_records() {
+            let target = Reflect::get(&record, &"target".into()).expect("failed to get target");
+            let kind = Reflect::get(&record, &"type".into()).expect("failed to get the type");
+            let Some(index) = backend
+                .cells
+                .iter()
+                .position(|cell| AsRef::<JsValue>::as_ref(cell) == &target)
+            else {
+                continue;
+            };
+            match kind.as_string().as_deref() {
+                Some("childList") => text.push(index),
+                _ => attributes.push(index),
+            }
+        }
+        observer.disconnect();
+        text.dedup();
+        attributes.dedup();
+        (text, attributes)
+    }
+
+    #[wasm_bindgen_test]
+    fn test_incremental_updates() {
+        create_parent("test-incremental-updates");
+        let options = DomBackendOptions::new(
+            Some("test-incremental-updates".to_string()),
+            CursorShape::None,
+        )
+        .incremental_updates(true);
+        let mut backend = DomBackend::new_with_options(options).expect("failed to create backend");
+        let mut buffer = filled(&backend, "a");
+        draw(&mut backend, &buffer);
+        let width = backend.size.width as usize;
+
+        // nothing changed
+        assert_eq!(draw_observed(&mut backend, &buffer), (vec![], vec![]));
+        assert_eq!(backend.dirty_region(), None);
+
+        // only the text of a cell changed
+        buffer[(1, 0)].set_symbol("b");
+        assert_eq!(draw_observed(&mut backend, &buffer), (vec![1], vec![]));
+        assert_eq!(backend.dirty_region(), Some(Rect::new(1, 0, 1, 1)));
+        assert_eq!(backend.cells[1].text_content().as_deref(), Some("b"));
+
+        // only the style of a cell changed
+        buffer[(2, 1)].set_style(Style::new().red());
+        let index = width + 2;
+        assert_eq!(draw_observed(&mut backend, &buffer), (vec![], vec![index]));
+        assert_eq!(backend.dirty_region(), Some(Rect::new(2, 1, 1, 1)));
+        assert_eq!(backend.cells[index].text_content().as_deref(), Some("a"));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_full_updates() {
+        create_parent("test-full-updates");
+        let mut backend =
+            DomBackend::new_by_id("test-full-updates").expect("failed to create backend");
+        let buffer = filled(&backend, "a");
+        draw(&mut backend, &buffer);
+
+        // every drawn cell is rewritten
+        let (text, attributes) = draw_observed(&mut backend, &buffer);
+        let area = buffer.area;
+        let drawn = area
+            .positions()
+            .map(|position| position.y as usize * backend.size.width as usize + position.x as usize)
+            .collect::<Vec<_>>();
+        assert_eq!(text, drawn);
+        assert_eq!(attributes, drawn);
+        assert_eq!(backend.dirty_region(), Some(area));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_csp_safe() {
+        let parent = create_parent("test-csp-safe");
+        let options =
+            DomBackendOptions::new(Some("test-csp-safe".to_string()), CursorShape::SteadyBlock)
+                .csp_safe(true)
+                .element_ids(true)
+                .translation_mode(TranslationMode::Mirror);
+        let mut backend = DomBackend::new_with_options(options).expect("failed to create backend");
+        let mut buffer = filled(&backend, "a");
+        buffer[(3, 0)].set_style(Style::new().red());
+        draw(&mut backend, &buffer);
+        backend
+            .set_cursor_position((1, 0))
+            .expect("failed to set the cursor");
+        backend.flush().expect("failed to flush");
+
+        // no inline styles, so nothing is blocked by `style-src 'self'`
+        let inline = parent
+            .query_selector("[style]")
+            .expect("failed to query the styled elements");
+        assert_eq!(inline, None);
+
+        // the cells of the same style share a class of the stylesheet
+        let class = |index: usize| {
+            backend.cells[index]
+                .get_attribute("class")
+                .expect("failed to get the class")
+        };
+        assert!(class(0).starts_with("ratzilla-cell "));
+        assert_eq!(class(0), class(2));
+        assert_ne!(class(0), class(3));
+        assert!(backend.cells[1].class_list().contains("ratzilla-cursor"));
+    }
+
+    /// Creates a backend with the given mutation guard and draws a frame.
+    fn guarded_backend(id: &str, guard: MutationGuard) -> DomBackend {
+        create_parent(id);
+        let options =
+            DomBackendOptions::new(Some(id.to_string()), CursorShape::None).mutation_guard(guard);
+        let mut backend = DomBackend::new_with_options(options).expect("failed to create backend");
+        let buffer = filled(&backend, "a");
+        draw(&mut backend, &buffer);
+        backend
+    }
+
+    #[wasm_bindgen_test]
+    fn test_mutation_guard_repair() {
+        let mut backend = guarded_backend("test-mutation-repair", MutationGuard::Repair);
+        let grid = backend.grid.clone();
+        backend.cells[0].set_text_content(Some("x"));
+
+        backend.draw(std::iter::empty()).expect("failed to draw");
+        assert!(!grid.is_connected());
+        assert!(backend.grid.is_connected());
+        assert_eq!(backend.cells[0].text_content().as_deref(), Some("a"));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_mutation_guard_report() {
+        let mut backend = guarded_backend("test-mutation-report", MutationGuard::Report);
+        let reports = Rc::new(RefCell::new(vec![]));
+        let closure = Closure::<dyn FnMut(_)>::new({
+            let reports = reports.clone();
+            move |event: CustomEvent| {
+                reports.borrow_mut().push(event.detail().as_f64());
+            }
+        });
+        backend
+            .grid_parent
+            .add_event_listener_with_callback(MUTATION_EVENT, closure.as_ref().unchecked_ref())
+            .expect("failed to add the listener");
+        backend.cells[0].set_text_content(Some("x"));
+        backend.cells[1].set_text_content(Some("y"));
+
+        // the changes are reported and kept
+        backend.draw(std::iter::empty()).expect("failed to draw");
+        backend.flush().expect("failed to flush");
+        assert_eq!(*reports.borrow(), [Some(2.0)]);
+        assert_eq!(backend.cells[0].text_content().as_deref(), Some("x"));
+
+        // the changes of the backend itself are not reported
+        let buffer = filled(&backend, "b");
+        draw(&mut backend, &buffer);
+        backend.draw(std::iter::empty()).expect("failed to draw");
+        assert_eq!(reports.borrow().len(), 1);
+    }
+}
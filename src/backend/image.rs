@@ -0,0 +1,334 @@
+use std::cell::Cell;
+#[cfg(any(feature = "dom", feature = "webgl2", feature = "webgpu"))]
+use std::collections::{HashMap, VecDeque};
+
+use web_sys::{
+    wasm_bindgen::{prelude::Closure, Clamped, JsCast},
+    CanvasRenderingContext2d, Document, HtmlCanvasElement, HtmlImageElement, ImageData,
+};
+
+use crate::{error::Error, images::ImageSource, render_loop};
+
+#[cfg(any(feature = "dom", feature = "webgl2", feature = "webgpu"))]
+use ratatui::layout::{Position, Rect};
+#[cfg(any(feature = "dom", feature = "webgl2", feature = "webgpu"))]
+use web_sys::{window, HtmlElement};
+
+#[cfg(any(feature = "dom", feature = "webgl2", feature = "webgpu"))]
+use crate::{
+    backend::{utils::get_document, web::GridGeometry},
+    images,
+};
+
+#[cfg(feature = "canvas")]
+use crate::images::PixelRect;
+
+thread_local! {
+    /// Number of images loaded so far.
+    static LOADS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Returns a number that changes whenever an image finishes loading.
+#[cfg(feature = "canvas")]
+pub(crate) fn loads() -> u64 {
+    LOADS.with(Cell::get)
+}
+
+/// An element holding the pixels of an [`ImageSource`].
+#[derive(Debug, Clone)]
+pub(crate) enum ImageElement {
+    /// An `<img>` element loading a URL.
+    Image(HtmlImageElement),
+    /// A `<canvas>` element with the pixels drawn on it.
+    Canvas(HtmlCanvasElement),
+}
+
+impl ImageElement {
+    /// Creates the element of the image.
+    ///
+    /// Images loaded from a URL request a redraw once loaded.
+    pub(crate) fn new(document: &Document, source: &ImageSource) -> Result<Self, Error> {
+        match source {
+            ImageSource::Url(url) => {
+                let image = document
+                    .create_element("img")?
+                    .unchecked_into::<HtmlImageElement>();
+                let onload = Closure::<dyn FnMut()>::new(|| {
+                    LOADS.with(|loads| loads.set(loads.get() + 1));
+                    render_loop::request_redraw();
+                });
+                image.set_onload(Some(onload.into_js_value().unchecked_ref()));
+                image.set_alt("");
+                image.set_src(url);
+                Ok(Self::Image(image))
+            }
+            ImageSource::Pixels(pixels) => {
+                let canvas = document
+                    .create_element("canvas")?
+                    .unchecked_into::<HtmlCanvasElement>();
+                canvas.set_width(pixels.width());
+                canvas.set_height(pixels.height());
+                let context = canvas
+                    .get_context("2d")?
+                    .ok_or(Error::UnableToRetrieveCanvasContext)?
+                    .unchecked_into::<CanvasRenderingContext2d>();
+                let data = ImageData::new_with_u8_clamped_array_and_sh(
+                    Clamped(pixels.data()),
+                    pixels.width(),
+                    pixels.height(),
+                )?;
+                context.put_image_data(&data, 0.0, 0.0)?;
+                Ok(Self::Canvas(canvas))
+            }
+        }
+    }
+
+    /// Returns the element.
+    #[cfg(any(feature = "dom", feature = "webgl2", feature = "webgpu"))]
+    pub(crate) fn element(&self) -> &HtmlElement {
+        match self {
+            Self::Image(image) => image,
+            Self::Canvas(canvas) => canvas,
+        }
+    }
+
+    /// Returns the size of the image in pixels, or `None` until it is loaded.
+    #[cfg(feature = "canvas")]
+    pub(crate) fn natural_size(&self) -> Option<(f64, f64)> {
+        match self {
+            // broken images are complete, but have no size
+            Self::Image(image) if image.complete() && image.natural_width() > 0 => {
+                Some((image.natural_width() as f64, image.natural_height() as f64))
+            }
+            Self::Image(_) => None,
+            Self::Canvas(canvas) => Some((canvas.width() as f64, canvas.height() as f64)),
+        }
+    }
+
+    /// Draws the `source` part of the image to the `target` area of a canvas.
+    #[cfg(feature = "canvas")]
+    pub(crate) fn draw(
+        &self,
+        context: &CanvasRenderingContext2d,
+        source: PixelRect,
+        target: PixelRect,
+    ) -> Result<(), Error> {
+        let (sx, sy, sw, sh) = source;
+        let (dx, dy, dw, dh) = target;
+        match self {
+            Self::Image(image) => context
+                .draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                    image, sx, sy, sw, sh, dx, dy, dw, dh,
+                ),
+            Self::Canvas(canvas) => context
+                .draw_image_with_html_canvas_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                    canvas, sx, sy, sw, sh, dx, dy, dw, dh,
+                ),
+        }?;
+        Ok(())
+    }
+}
+
+/// Elements showing the [`images`] of the frame on screen over the grid, for
+/// the backends that can't draw them with the cells.
+///
+/// The elements are in a container positioned over the page, which lets the
+/// mouse events through to the grid.
+#[cfg(any(feature = "dom", feature = "webgl2", feature = "webgpu"))]
+#[derive(Debug, Default)]
+pub(crate) struct ImageOverlay {
+    /// Container of the elements, once there are images.
+    container: Option<HtmlElement>,
+    /// Elements of the images on screen by source, in placement order.
+    elements: HashMap<ImageSource, VecDeque<ImageElement>>,
+    /// Generation of the images and geometry of the grid they were placed on.
+    state: Option<(u64, Option<GridGeometry>)>,
+}
+
+#[cfg(any(feature = "dom", feature = "webgl2", feature = "webgpu"))]
+impl ImageOverlay {
+    /// Constructs a new, empty [`ImageOverlay`].
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Places the elements of the images on screen over the grid.
+    ///
+    /// The grid is only measured while there are images, and the elements
+    /// are only moved when the images or the grid change. The elements of
+    /// the images that stay on screen are kept, so they aren't loaded again.
+    pub(crate) fn update<F>(&mut self, geometry: F) -> Result<(), Error>
+    where
+        F: FnOnce() -> Option<GridGeometry>,
+    {
+        let generation = images::generation();
+        let placements = images::placements();
+        if placements.is_empty() {
+            if self.state.is_some_and(|(drawn, _)| drawn == generation) {
+                return Ok(());
+            }
+            self.state = Some((generation, None));
+            self.elements.clear();
+            if let Some(container) = self.container.take() {
+                container.remove();
+            }
+            return Ok(());
+        }
+
+        // positions are relative to the document, so scrolling doesn't move them
+        let window = window().ok_or(Error::UnableToRetrieveWindow)?;
+        let geometry = geometry().map(|geometry| GridGeometry {
+            origin: (
+                geometry.origin.0 + window.scroll_x().unwrap_or_default(),
+                geometry.origin.1 + window.scroll_y().unwrap_or_default(),
+            ),
+            ..geometry
+        });
+        let document = get_document()?;
+        let container = match &self.container {
+            // e.g. the DOM backend clears the body on resize
+            Some(container) if !container.is_connected() => {
+                document
+                    .body()
+                    .ok_or(Error::UnableToRetrieveBody)?
+                    .append_child(container)?;
+                container.clone()
+            }
+            Some(container) => {
+                if self.state == Some((generation, geometry)) {
+                    return Ok(());
+                }
+                container.clone()
+            }
+            None => {
+                let container = document
+                    .create_element("div")?
+                    .unchecked_into::<HtmlElement>();
+                container.set_class_name("ratzilla-images");
+                let style = container.style();
+                style.set_property("position", "absolute")?;
+                style.set_property("left", "0")?;
+                style.set_property("top", "0")?;
+                style.set_property("pointer-events", "none")?;
+                document
+                    .body()
+                    .ok_or(Error::UnableToRetrieveBody)?
+                    .append_child(&container)?;
+                self.container.insert(container).clone()
+            }
+        };
+        self.state = Some((generation, geometry));
+
+        let mut previous = std::mem::take(&mut self.elements);
+        if let Some(geometry) = geometry {
+            let (cell_width, cell_height) = geometry.cell_size;
+            let grid = Rect::from((Position::ORIGIN, geometry.size));
+            for placement in placements {
+                let area = placement.area.intersection(grid);
+                if area.is_empty() {
+                    continue;
+                }
+                let image = match previous
+                    .get_mut(&placement.source)
+                    .and_then(VecDeque::pop_front)
+                {
+                    Some(image) => image,
+                    None => ImageElement::new(&document, &placement.source)?,
+                };
+                let style = image.element().style();
+                let px = |value: f64| format!("{value}px");
+                style.set_property("position", "absolute")?;
+                style.set_property("left", &px(geometry.origin.0 + area.x as f64 * cell_width))?;
+                style.set_property("top", &px(geometry.origin.1 + area.y as f64 * cell_height))?;
+                style.set_property("width", &px(area.width as f64 * cell_width))?;
+                style.set_property("height", &px(area.height as f64 * cell_height))?;
+                style.set_property("object-fit", placement.fit.css())?;
+                // appending moves it after the previous images, keeping their order
+                container.append_child(image.element())?;
+                self.elements
+                    .entry(placement.source)
+                    .or_default()
+                    .push_back(image);
+            }
+        }
+        for image in previous.into_values().flatten() {
+            image.element().remove();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(any(feature = "dom", feature = "webgl2", feature = "webgpu"))]
+impl Drop for ImageOverlay {
+    fn drop(&mut self) {
+        if let Some(container) = &self.container {
+            container.remove();
+        }
+    }
+}
+
+#[cfg(all(test, feature = "dom"))]
+mod tests {
+    use ratatui::layout::Size;
+    use wasm_bindgen_test::*;
+
+    use super::*;
+    use crate::images::ImageFit;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    /// A GIF image of a single pixel.
+    const PIXEL: &str =
+        "data:image/gif;base64,R0lGODlhAQABAIAAAAAAAP///yH5BAEAAAAALAAAAAABAAEAAAIBRAA7";
+
+    /// Returns the geometry of a grid of square cells of the given size.
+    fn geometry(cell: f64) -> Option<GridGeometry> {
+        Some(GridGeometry {
+            origin: (0.0, 0.0),
+            cell_size: (cell, cell),
+            size: Size::new(10, 5),
+        })
+    }
+
+    /// Returns the left position of the given element.
+    fn left(element: &web_sys::Element) -> String {
+        element
+            .unchecked_ref::<HtmlElement>()
+            .style()
+            .get_property_value("left")
+            .expect("failed to get the position")
+    }
+
+    #[wasm_bindgen_test]
+    fn test_overlay_survives_resize() {
+        images::register(
+            Rect::new(1, 1, 2, 2),
+            ImageSource::url(PIXEL),
+            ImageFit::Fill,
+        );
+        images::commit();
+        let mut overlay = ImageOverlay::new();
+        overlay
+            .update(|| geometry(10.0))
+            .expect("failed to place the images");
+        let container = overlay.container.clone().expect("no container");
+        let image = container.first_element_child().expect("no image");
+        assert_eq!(left(&image), "10px");
+
+        // the DOM backend clears the body on resize, with the cells resized
+        container.remove();
+        overlay
+            .update(|| geometry(20.0))
+            .expect("failed to place the images");
+        assert!(container.is_connected());
+        assert_eq!(container.child_element_count(), 1);
+        assert_eq!(container.first_element_child(), Some(image.clone()));
+        assert_eq!(left(&image), "20px");
+
+        images::commit();
+        overlay
+            .update(|| geometry(20.0))
+            .expect("failed to place the images");
+        assert!(!container.is_connected());
+    }
+}
@@ -0,0 +1,195 @@
+use ratatui::layout::Size;
+use web_sys::{wasm_bindgen::JsCast, HtmlElement};
+
+use crate::{
+    backend::utils::{get_document, get_window},
+    error::Error,
+};
+
+#[cfg(feature = "canvas")]
+use std::{cell::RefCell, rc::Rc};
+
+#[cfg(feature = "canvas")]
+use web_sys::{wasm_bindgen::prelude::Closure, AddEventListenerOptions};
+
+#[cfg(feature = "canvas")]
+use crate::teardown;
+
+/// Glyph measured to get the size of a cell.
+const PROBE_GLYPH: &str = "M";
+
+/// Number of glyphs of the probe, so the width of a glyph keeps the
+/// sub-pixel precision of the layout.
+const PROBE_LENGTH: usize = 100;
+
+/// Size of a cell, as measured in the browser.
+///
+/// ```rust no_run
+/// use ratzilla::{ratatui::Terminal, DomBackend, WebTerminal};
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+///
+/// let terminal = Terminal::new(DomBackend::new()?)?;
+/// let metrics = terminal.cell_metrics();
+/// // the number of cells in a 640px wide panel
+/// let columns = metrics.grid_size(640.0, 0.0).width;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellMetrics {
+    /// Width of a cell, in CSS pixels.
+    pub width: f64,
+    /// Height of a cell, in CSS pixels.
+    pub height: f64,
+    /// Number of device pixels per CSS pixel.
+    pub device_pixel_ratio: f64,
+}
+
+impl Default for CellMetrics {
+    /// Returns the historical cell size, 10 by 19 pixels, used when a font
+    /// can't be measured.
+    fn default() -> Self {
+        Self {
+            width: 10.0,
+            height: 19.0,
+            device_pixel_ratio: 1.0,
+        }
+    }
+}
+
+impl CellMetrics {
+    /// Constructs a new [`CellMetrics`] of the given cell size, in CSS pixels,
+    /// with the device pixel ratio of the window.
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            device_pixel_ratio: device_pixel_ratio(),
+        }
+    }
+
+    /// Measures a cell of the given CSS font, e.g. `16px monospace`.
+    ///
+    /// A probe glyph is rendered in a hidden `<pre>` element, so the cell is
+    /// as tall as a line of text. Without a font, the probe uses the styles
+    /// of the page, like the cells of the [`DomBackend`](crate::DomBackend).
+    pub fn measure(font: Option<&str>) -> Result<Self, Error> {
+        let document = get_document()?;
+        let body = document.body().ok_or(Error::UnableToRetrieveBody)?;
+        let probe = document
+            .create_element("pre")?
+            .dyn_into::<HtmlElement>()
+            .map_err(|_| Error::UnableToRetrieveComponent("probe"))?;
+        // set through the CSSOM, which the CSP safe mode allows
+        let style = probe.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("visibility", "hidden")?;
+        style.set_property("margin", "0")?;
+        style.set_property("padding", "0")?;
+        if let Some(font) = font {
+            style.set_property("font", font)?;
+        }
+        probe.set_attribute("aria-hidden", "true")?;
+        probe.set_text_content(Some(&PROBE_GLYPH.repeat(PROBE_LENGTH)));
+        body.append_child(&probe)?;
+        let rect = probe.get_bounding_client_rect();
+        probe.remove();
+
+        let (width, height) = (rect.width() / PROBE_LENGTH as f64, rect.height());
+        // e.g. a detached or hidden page
+        if width <= 0.0 || height <= 0.0 {
+            return Ok(Self::default());
+        }
+        Ok(Self::new(width, height))
+    }
+
+    /// Returns the number of cells fitting in the given size, in CSS pixels.
+    pub fn grid_size(&self, width: f64, height: f64) -> Size {
+        let cells = |length: f64, cell: f64| (length / cell).floor().clamp(0.0, u16::MAX as f64);
+        Size::new(
+            cells(width, self.width) as u16,
+            cells(height, self.height) as u16,
+        )
+    }
+
+    /// Returns the size of a cell in device pixels.
+    pub fn physical_size(&self) -> (f64, f64) {
+        (
+            self.width * self.device_pixel_ratio,
+            self.height * self.device_pixel_ratio,
+        )
+    }
+
+    /// Returns the size of a cell in CSS pixels.
+    pub(crate) fn cell_size(&self) -> (f64, f64) {
+        (self.width, self.height)
+    }
+}
+
+/// Measures a cell of the page, falling back to the historical cell size.
+pub(crate) fn page_metrics() -> CellMetrics {
+    CellMetrics::measure(None).unwrap_or_default()
+}
+
+/// Returns the number of device pixels per CSS pixel of the window.
+pub(crate) fn device_pixel_ratio() -> f64 {
+    get_window()
+        .map(|window| window.device_pixel_ratio())
+        .unwrap_or(1.0)
+}
+
+/// Calls the given callback with the new device pixel ratio whenever it
+/// changes, e.g. when the page is zoomed or the window moves to another
+/// screen.
+#[cfg(feature = "canvas")]
+pub(crate) fn on_device_pixel_ratio_change<F>(callback: F) -> Result<(), Error>
+where
+    F: FnMut(f64) + 'static,
+{
+    watch_device_pixel_ratio(Rc::new(RefCell::new(callback)))
+}
+
+/// Waits for the device pixel ratio to change from its current value.
+///
+/// A media query only matches one ratio, so a new one is made after every
+/// change.
+#[cfg(feature = "canvas")]
+fn watch_device_pixel_ratio(callback: Rc<RefCell<dyn FnMut(f64)>>) -> Result<(), Error> {
+    let query = format!("(resolution: {}dppx)", device_pixel_ratio());
+    let Some(media) = get_window()?.match_media(&query)? else {
+        return Ok(());
+    };
+    let on_change = Closure::once_into_js(move || {
+        (callback.borrow_mut())(device_pixel_ratio());
+        let _ = watch_device_pixel_ratio(callback);
+    });
+    let options = AddEventListenerOptions::new();
+    options.set_once(true);
+    media.add_event_listener_with_callback_and_add_event_listener_options(
+        "change",
+        on_change.unchecked_ref(),
+        &options,
+    )?;
+    teardown::on_destroy(move || {
+        let _ = media.remove_event_listener_with_callback("change", on_change.unchecked_ref());
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_size() {
+        let metrics = CellMetrics {
+            width: 9.6,
+            height: 18.5,
+            device_pixel_ratio: 2.0,
+        };
+        assert_eq!(metrics.grid_size(960.0, 370.0), Size::new(100, 20));
+        assert_eq!(metrics.grid_size(959.0, 369.0), Size::new(99, 19));
+        assert_eq!(metrics.grid_size(-1.0, 0.0), Size::new(0, 0));
+        assert_eq!(metrics.physical_size(), (19.2, 37.0));
+    }
+}
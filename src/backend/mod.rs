@@ -1,14 +1,17 @@
 //! ## Backends
 //!
-//! **Ratzilla** provides three backends for rendering terminal UIs in the browser,
+//! **Ratzilla** provides four backends for rendering terminal UIs in the browser,
 //! each with different performance characteristics and trade-offs:
 //!
 //! - [`WebGl2Backend`]: GPU-accelerated rendering powered by [beamterm][beamterm]. Uses prebuilt
 //!   font atlases. Best performance, capable of 60fps on large terminals (300x100+).
 //!
+//! - [`WgpuBackend`]: GPU-accelerated rendering with WebGPU. Rasterizes glyphs into an atlas
+//!   as they are drawn, so it supports dynamic characters, but only in browsers with WebGPU.
+//!
 //! - [`CanvasBackend`]: Canvas 2D API with full Unicode support via browser font rendering.
 //!   Good fallback when WebGL2 isn't available or when dynamic character support is required.
-//!   Does not support hyperlinks or text selection, but can render dynamic Unicode/emoji.
+//!   Does not support text selection, but can render dynamic Unicode/emoji.
 //!
 //! - [`DomBackend`]: Renders cells as HTML elements. Most compatible and accessible,
 //!   supports hyperlinks, but slowest for large terminals.
@@ -17,39 +20,107 @@
 //!
 //! ## Backend Comparison
 //!
-//! | Feature                      | DomBackend | CanvasBackend | WebGl2Backend    |
-//! |------------------------------|------------|---------------|------------------|
-//! | **60fps on large terminals** | ✗          | ✗             | ✓                |
-//! | **Memory Usage**             | Highest    | Medium        | Lowest           |
-//! | **Hyperlinks**               | ✓          | ✗             | ✓                |
-//! | **Text Selection**           | ✓          | ✗             | ✓                |
-//! | **Accessibility**            | ✓          | Limited       | Limited          |
-//! | **Unicode/Emoji Support**    | Full       | Full          | Limited to atlas |
-//! | **Dynamic Characters**       | ✓          | ✓             | ✗                |
-//! | **Font Variants**            | ✓          | Regular only  | ✓                |
-//! | **Underline**                | ✓          | ✗             | ✓                |
-//! | **Strikethrough**            | ✓          | ✗             | ✓                |
-//! | **Browser Support**          | All        | All           | Modern (2017+)   |
+//! | Feature                      | DomBackend | CanvasBackend | WebGl2Backend    | WgpuBackend |
+//! |------------------------------|------------|---------------|------------------|-------------|
+//! | **60fps on large terminals** | ✗          | ✗             | ✓                | ✓           |
+//! | **Memory Usage**             | Highest    | Medium        | Lowest           | Low         |
+//! | **Hyperlinks**               | ✓          | ✓             | ✓                | ✓           |
+//! | **Text Selection**           | ✓          | ✗             | ✓                | ✗           |
+//! | **Accessibility**            | ✓          | Limited       | Limited          | Limited     |
+//! | **Unicode/Emoji Support**    | Full       | Full          | Limited to atlas | Full        |
+//! | **Dynamic Characters**       | ✓          | ✓             | ✗                | ✓           |
+//! | **Font Variants**            | ✓          | Regular only  | ✓                | ✓           |
+//! | **Underline**                | ✓          | ✗             | ✓                | ✓           |
+//! | **Strikethrough**            | ✓          | ✗             | ✓                | ✓           |
+//! | **Images**                   | ✓          | ✓             | ✓                | ✓           |
+//! | **Browser Support**          | All        | All           | Modern (2017+)   | WebGPU only |
 //!
 //! ## Choosing a Backend
 //!
 //! - **WebGl2Backend**: Preferred for most applications - consumes the least amount of resources
 //! - **CanvasBackend**: When you need dynamic Unicode/emoji or must support non-WebGL2 browsers
+//! - **WgpuBackend**: When you need dynamic Unicode/emoji on the GPU, falling back to another
+//!   backend in browsers without WebGPU
 //! - **DomBackend**: When you need better accessibility or CSS styling
+//!
+//! ## Cargo Features
+//!
+//! Each backend is behind its own cargo feature, so an app only pays for the
+//! backends it ships: `dom` (enabled by default), `canvas` and `webgl2`. The
+//! WebGL2 backend pulls in beamterm and accounts for most of the binary size,
+//! so apps using another backend should leave it disabled:
+//!
+//! ```toml
+//! ratzilla = { version = "0.3", default-features = false, features = ["canvas"] }
+//! ```
+//!
+//! The `webgpu` feature enables the `WgpuBackend`. The WebGPU bindings of
+//! `web-sys` are unstable, so it also requires building with
+//! `RUSTFLAGS="--cfg=web_sys_unstable_apis"`.
+//!
+//! `scripts/size-report.sh` prints the wasm size of an app for each backend.
+//!
+//! ### Loading a backend on demand
+//!
+//! A wasm module can't be split into chunks loaded lazily, so every backend
+//! enabled in a build is in the binary fetched on the first paint. To only
+//! fetch the WebGL2 backend when it is used, give the app a feature per
+//! backend forwarding to the ratzilla one, as `examples/size_report` does, and
+//! build it once per feature, as `scripts/size-report.sh` does:
+//!
+//! ```sh
+//! wasm-pack build --target web --out-dir pkg/dom --features dom
+//! wasm-pack build --target web --out-dir pkg/webgl2 --features webgl2
+//! ```
+//!
+//! and pick the bundle to import from the page:
+//!
+//! ```js
+//! const gl = document.createElement("canvas").getContext("webgl2");
+//! const app = await import(gl ? "./pkg/webgl2/app.js" : "./pkg/dom/app.js");
+//! await app.default();
+//! ```
 
 /// Canvas backend.
+#[cfg(feature = "canvas")]
 pub mod canvas;
 
 /// DOM backend.
+#[cfg(feature = "dom")]
 pub mod dom;
 
 /// WebGL2 backend.
+#[cfg(feature = "webgl2")]
 pub mod webgl2;
 
+/// WebGPU backend.
+#[cfg(feature = "webgpu")]
+pub mod webgpu;
+
+#[cfg(all(feature = "webgpu", not(web_sys_unstable_apis)))]
+compile_error!("the `webgpu` feature requires `RUSTFLAGS=\"--cfg=web_sys_unstable_apis\"`");
+
 /// Color handling.
-mod color;
+pub(crate) mod color;
+/// Image elements.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+pub(crate) mod image;
+/// Constructable stylesheets.
+#[cfg(feature = "dom")]
+pub(crate) mod stylesheet;
 /// Backend utilities.
 pub(crate) mod utils;
 
 /// Cursor shapes.
 pub mod cursor;
+
+/// Cell measurement.
+pub mod metrics;
+
+/// Hit testing and coordinate conversion.
+pub mod web;
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use web_sys::{
+    js_sys::{Array, Reflect},
+    wasm_bindgen::JsValue,
+    CssStyleSheet, Document,
+};
+
+use crate::error::Error;
+
+/// A constructable stylesheet adopted by the document.
+///
+/// Unlike inline `style` attributes and `<style>` elements, rules inserted
+/// through the CSSOM are allowed by a strict content security policy
+/// (`style-src 'self'`).
+#[derive(Debug)]
+pub(crate) struct StyleSheet {
+    /// The adopted stylesheet.
+    sheet: CssStyleSheet,
+    /// Class names of the declarations inserted so far.
+    classes: HashMap<String, String>,
+}
+
+impl StyleSheet {
+    /// Constructs a new [`StyleSheet`] and adds it to the adopted stylesheets
+    /// of the document.
+    pub(crate) fn new(document: &Document) -> Result<Self, Error> {
+        let sheet = CssStyleSheet::new()?;
+        let key = JsValue::from_str("adoptedStyleSheets");
+        let adopted = Array::from(&Reflect::get(document, &key)?);
+        adopted.push(&sheet);
+        Reflect::set(document, &key, &adopted)?;
+        Ok(Self {
+            sheet,
+            classes: HashMap::new(),
+        })
+    }
+
+    /// Removes the stylesheet from the adopted stylesheets of the document.
+    pub(crate) fn remove(&self, document: &Document) -> Result<(), Error> {
+        let key = JsValue::from_str("adoptedStyleSheets");
+        let adopted = Array::from(&Reflect::get(document, &key)?);
+        let sheet: &JsValue = self.sheet.as_ref();
+        let remaining: Array = adopted.iter().filter(|s| *s != *sheet).collect();
+        Reflect::set(document, &key, &remaining)?;
+        Ok(())
+    }
+
+    /// Inserts a rule at the end of the stylesheet.
+    pub(crate) fn insert_rule(&self, rule: &str) -> Result<(), Error> {
+        let index = self.sheet.css_rules()?.length();
+        self.sheet.insert_rule_with_index(rule, index)?;
+        Ok(())
+    }
+
+    /// Returns the number of classes inserted so far.
+    pub(crate) fn class_count(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Returns the name of a class with the given declarations, inserting a
+    /// rule for it on first use.
+    pub(crate) fn class_for(&mut self, declarations: &str) -> Result<&str, Error> {
+        if !self.classes.contains_key(declarations) {
+            let class = format!("ratzilla-s{}", self.classes.len());
+            self.insert_rule(&format!(".{class} {{ {declarations} }}"))?;
+            self.classes.insert(declarations.to_string(), class);
+        }
+        Ok(&self.classes[declarations])
+    }
+}
@@ -1,19 +1,36 @@
-use crate::{
-    backend::color::ansi_to_rgb,
-    error::Error,
-    utils::{get_screen_size, get_window_size, is_mobile},
-};
-use compact_str::{format_compact, CompactString};
-use ratatui::{
-    buffer::Cell,
-    layout::Size,
-    style::{Color, Modifier},
-};
+use crate::{backend::color::ansi_to_rgb, error::Error};
+use ratatui::{buffer::Cell, style::Modifier};
 use unicode_width::UnicodeWidthStr;
-use web_sys::{
-    wasm_bindgen::{JsCast, JsValue},
-    window, Document, Element, HtmlCanvasElement, Window,
-};
+use web_sys::{wasm_bindgen::JsValue, window, Document, Window};
+
+#[cfg(any(feature = "canvas", feature = "dom"))]
+use compact_str::{format_compact, CompactString};
+#[cfg(any(feature = "canvas", feature = "dom"))]
+use ratatui::layout::Size;
+#[cfg(any(feature = "canvas", feature = "dom"))]
+use ratatui::style::Color;
+#[cfg(any(feature = "canvas", feature = "webgl2", feature = "webgpu"))]
+use std::{cell::RefCell, rc::Rc};
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+use web_sys::Element;
+#[cfg(any(feature = "canvas", feature = "webgl2", feature = "webgpu"))]
+use web_sys::{wasm_bindgen::JsCast, HtmlCanvasElement};
+
+#[cfg(feature = "canvas")]
+use crate::backend::metrics::CellMetrics;
+#[cfg(feature = "dom")]
+use crate::utils::{get_screen_size, get_window_size, is_mobile};
+#[cfg(any(feature = "canvas", feature = "webgpu"))]
+use crate::{backend::web, links, teardown};
+#[cfg(any(feature = "canvas", feature = "webgpu"))]
+use std::cell::Cell as StdCell;
+#[cfg(any(feature = "canvas", feature = "webgpu"))]
+use web_sys::wasm_bindgen::prelude::Closure;
 
 pub struct CssAttribute {
     pub field: &'static str,
@@ -21,27 +38,16 @@ pub struct CssAttribute {
 }
 
 /// Creates a new `<span>` element with the given cell.
+#[cfg(feature = "dom")]
 pub(crate) fn create_span(document: &Document, cell: &Cell) -> Result<Element, Error> {
     let span = document.create_element("span")?;
-    span.set_inner_html(cell.symbol());
+    span.set_text_content(Some(cell.symbol()));
 
     let style = get_cell_style_as_css(cell);
     span.set_attribute("style", &style)?;
     Ok(span)
 }
 
-/// Creates a new `<a>` element with the given cells.
-#[allow(dead_code)]
-pub(crate) fn create_anchor(document: &Document, cells: &[Cell]) -> Result<Element, Error> {
-    let anchor = document.create_element("a")?;
-    anchor.set_attribute(
-        "href",
-        &cells.iter().map(|c| c.symbol()).collect::<String>(),
-    )?;
-    anchor.set_attribute("style", &get_cell_style_as_css(&cells[0]))?;
-    Ok(anchor)
-}
-
 /// Converts a cell to a CSS style.
 pub(crate) fn get_cell_style_as_css(cell: &Cell) -> String {
     let mut fg = ansi_to_rgb(cell.fg);
@@ -104,88 +110,12 @@ pub(crate) fn get_cell_style_as_css(cell: &Cell) -> String {
     format!("{fg_style} {bg_style} {modifier_style} {braille_style} {sizing}")
 }
 
-/// Parse an inline CSS style string into a Vec of (property, value) pairs.
-fn parse_inline_style(css: &str) -> Vec<(String, String)> {
-    css.split(';')
-        .filter_map(|decl| {
-            let decl = decl.trim();
-            if decl.is_empty() {
-                return None;
-            }
-            let mut parts = decl.splitn(2, ':');
-            let key = parts.next()?.trim();
-            let val = parts.next()?.trim();
-            if key.is_empty() || val.is_empty() {
-                None
-            } else {
-                Some((key.to_string(), val.to_string()))
-            }
-        })
-        .collect()
-}
-
-/// Build a css string from an array of (field, value) css style attributes.
-fn build_inline_style(styles: &[(String, String)]) -> String {
-    let mut s = String::new();
-    for (k, v) in styles {
-        s.push_str(format!("{k}: {v};").as_str());
-    }
-    s
-}
-
-/// Replace the `style` attribute by the given css string in the given Element.
-///
-/// If the css string is empty, removes completly the `style` attribute.
-fn set_or_remove_style_attribute(elem: &Element, css: String) -> Result<(), JsValue> {
-    if css.is_empty() {
-        elem.remove_attribute("style")
-    } else {
-        elem.set_attribute("style", &css)
-    }
-}
-
-/// Update or remove a CSS field in the inline `style` attribute.
-///
-/// - If `attribute.value` is `Some(v)`: sets/updates `attribute.field: v`.
-/// - If `attribute.value` is `None`: removes `attribute.field`.
-/// - If the final style is empty: removes the `style` attribute entirely.
-pub(crate) fn update_css_field(attribute: CssAttribute, elem: &Element) -> Result<(), JsValue> {
-    let field = attribute.field;
-    let value = attribute.value;
-
-    let css = elem.get_attribute("style").unwrap_or_default();
-    let mut styles = parse_inline_style(&css);
-    let target = field.trim().to_string();
-
-    // Either update/add or remove the field
-    match value {
-        Some(new_val) => {
-            let new_val = new_val.trim().to_string();
-            let mut found = false;
-            for (k, v) in styles.iter_mut() {
-                if k.eq_ignore_ascii_case(&target) {
-                    *v = new_val.clone();
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
-                styles.push((target, new_val));
-            }
-        }
-        None => {
-            styles.retain(|(k, _)| !k.eq_ignore_ascii_case(&target));
-        }
-    }
-
-    // Rebuild CSS string
-    let updated_css = build_inline_style(&styles);
-    set_or_remove_style_attribute(elem, updated_css)
-}
-
 /// Converts a Color to a CSS style.
+#[cfg(any(feature = "canvas", feature = "dom"))]
 pub(crate) fn get_canvas_color(color: Color, fallback_color: Color) -> CompactString {
-    let color = ansi_to_rgb(color).unwrap_or_else(|| ansi_to_rgb(fallback_color).unwrap());
+    let color = ansi_to_rgb(color)
+        .or_else(|| ansi_to_rgb(fallback_color))
+        .unwrap_or_default();
 
     format_compact!("rgb({}, {}, {})", color.0, color.1, color.2)
 }
@@ -208,17 +138,14 @@ pub(crate) fn get_raw_window_size() -> (u16, u16) {
 
 /// Returns the number of pixels that can fit in the window.
 pub(crate) fn get_raw_screen_size() -> (i32, i32) {
-    let s = web_sys::window().unwrap().screen().unwrap();
-    (s.width().unwrap(), s.height().unwrap())
-}
-
-/// Returns a buffer based on the screen size.
-pub(crate) fn get_sized_buffer() -> Vec<Vec<Cell>> {
-    let size = get_size();
-    vec![vec![Cell::default(); size.width as usize]; size.height as usize]
+    web_sys::window()
+        .and_then(|w| w.screen().ok())
+        .and_then(|s| s.width().ok().zip(s.height().ok()))
+        .unwrap_or((120, 120))
 }
 
 /// Returns a buffer size based on the screen size.
+#[cfg(feature = "dom")]
 pub(crate) fn get_size() -> Size {
     if is_mobile() {
         get_screen_size()
@@ -227,10 +154,14 @@ pub(crate) fn get_size() -> Size {
     }
 }
 
-/// Returns a buffer based on the canvas size.
-pub(crate) fn get_sized_buffer_from_canvas(canvas: &HtmlCanvasElement) -> Vec<Vec<Cell>> {
-    let width = canvas.client_width() as u16 / 10_u16;
-    let height = canvas.client_height() as u16 / 19_u16;
+/// Returns a buffer based on the canvas size and the size of a cell.
+#[cfg(feature = "canvas")]
+pub(crate) fn get_sized_buffer_from_canvas(
+    canvas: &HtmlCanvasElement,
+    metrics: &CellMetrics,
+) -> Vec<Vec<Cell>> {
+    let Size { width, height } =
+        metrics.grid_size(canvas.client_width() as f64, canvas.client_height() as f64);
     vec![vec![Cell::default(); width as usize]; height as usize]
 }
 
@@ -261,13 +192,14 @@ pub(crate) fn get_element_by_id_or_body(id: Option<&String>) -> Result<web_sys::
 
 /// Returns the performance object from the window.
 pub(crate) fn performance() -> Result<web_sys::Performance, Error> {
-    Ok(get_window()?
+    get_window()?
         .performance()
-        .ok_or(Error::UnableToRetrieveComponent("Performance"))?)
+        .ok_or(Error::UnableToRetrieveComponent("Performance"))
 }
 
 /// Creates a new canvas element in the specified parent element with the
 /// given width and height.
+#[cfg(any(feature = "canvas", feature = "webgl2", feature = "webgpu"))]
 pub(crate) fn create_canvas_in_element(
     parent: &Element,
     width: u32,
@@ -288,6 +220,113 @@ pub(crate) fn create_canvas_in_element(
     Ok(canvas)
 }
 
+/// How the size of a canvas follows the page.
+#[cfg(any(feature = "canvas", feature = "webgl2", feature = "webgpu"))]
+#[derive(Debug, Clone)]
+pub(crate) enum CanvasSize {
+    /// A size set by the app.
+    Fixed,
+    /// The size of the window.
+    Window,
+    /// The size of the parent element.
+    Parent(Element),
+}
+
+#[cfg(any(feature = "canvas", feature = "webgl2", feature = "webgpu"))]
+impl CanvasSize {
+    /// Returns the size the canvas should have, in CSS pixels, or `None` if
+    /// the size is fixed.
+    pub(crate) fn get(&self) -> Option<(u32, u32)> {
+        match self {
+            CanvasSize::Fixed => None,
+            CanvasSize::Window => {
+                let (width, height) = get_raw_window_size();
+                Some((width.into(), height.into()))
+            }
+            CanvasSize::Parent(parent) => {
+                Some((parent.client_width() as u32, parent.client_height() as u32))
+            }
+        }
+    }
+}
+
+/// Callback called with the URL of a clicked hyperlink.
+#[cfg(any(feature = "canvas", feature = "webgl2", feature = "webgpu"))]
+type OnHyperlink = Rc<RefCell<dyn FnMut(&str)>>;
+
+/// A `Debug`-derive friendly convenience wrapper
+#[cfg(any(feature = "canvas", feature = "webgl2", feature = "webgpu"))]
+#[derive(Clone)]
+pub(crate) struct HyperlinkCallback {
+    pub(crate) callback: OnHyperlink,
+}
+
+#[cfg(any(feature = "canvas", feature = "webgl2", feature = "webgpu"))]
+impl HyperlinkCallback {
+    /// Creates a new [`HyperlinkCallback`] with the given callback.
+    pub(crate) fn new<F>(callback: F) -> Self
+    where
+        F: FnMut(&str) + 'static,
+    {
+        Self {
+            callback: Rc::new(RefCell::new(callback)),
+        }
+    }
+}
+
+#[cfg(any(feature = "canvas", feature = "webgl2", feature = "webgpu"))]
+impl std::fmt::Debug for HyperlinkCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallbackWrapper")
+            .field("callback", &"<callback>")
+            .finish()
+    }
+}
+
+/// Calls the callback when a hyperlink on the canvas is clicked, and shows a
+/// pointer while hovering one.
+#[cfg(any(feature = "canvas", feature = "webgpu"))]
+pub(crate) fn handle_hyperlinks(
+    canvas: &web_sys::HtmlCanvasElement,
+    callback: HyperlinkCallback,
+) -> Result<(), Error> {
+    let click = Closure::<dyn FnMut(_)>::new(move |event: web_sys::MouseEvent| {
+        // left mouse button only
+        if event.button() != 0 {
+            return;
+        }
+        if let Some(url) = hyperlink_at(&event) {
+            if let Ok(mut callback) = callback.callback.try_borrow_mut() {
+                callback(&url);
+            }
+        }
+    });
+    teardown::add_event_listener(canvas, "click", click.into_js_value().unchecked_ref())?;
+
+    let style = canvas.style();
+    let over_hyperlink = StdCell::new(false);
+    let hover = Closure::<dyn FnMut(_)>::new(move |event: web_sys::MouseEvent| {
+        let is_over = hyperlink_at(&event).is_some();
+        // only update the cursor style when it changes
+        if over_hyperlink.replace(is_over) != is_over {
+            let _ = if is_over {
+                style.set_property("cursor", "pointer")
+            } else {
+                style.remove_property("cursor").map(drop)
+            };
+        }
+    });
+    teardown::add_event_listener(canvas, "mousemove", hover.into_js_value().unchecked_ref())?;
+    Ok(())
+}
+
+/// Returns the URL of the hyperlink under the mouse, if any.
+#[cfg(any(feature = "canvas", feature = "webgpu"))]
+fn hyperlink_at(event: &web_sys::MouseEvent) -> Option<String> {
+    let cell = web::grid_cell_at(event.client_x() as f64, event.client_y() as f64)?;
+    links::link_at(cell.x, cell.y)
+}
+
 /// Checks if the given cell contains a braille character.
 fn contains_braille(cell: &Cell) -> bool {
     cell.symbol()
@@ -296,67 +335,18 @@ fn contains_braille(cell: &Cell) -> bool {
         .is_some_and(|c| ('\u{2800}'..='\u{28FF}').contains(&c))
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "dom"))]
 mod tests {
     use super::*;
     use wasm_bindgen_test::*;
-    use web_sys::window;
 
     wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
 
-    fn create_elem_with_style(s: &str) -> Element {
-        let doc = window().unwrap().document().unwrap();
-        let el = doc.create_element("div").unwrap();
-        if !s.is_empty() {
-            el.set_attribute("style", s).unwrap();
-        }
-        el
-    }
-
-    #[wasm_bindgen_test]
-    fn test_add_new_field() {
-        let el = create_elem_with_style("color: red;");
-        let attr = CssAttribute {
-            field: "background-color",
-            value: Some("blue"),
-        };
-        update_css_field(attr, &el).unwrap();
-        let got = el.get_attribute("style").unwrap();
-        assert!(got.contains("color: red;"));
-        assert!(got.contains("background-color: blue;"));
-    }
-
-    #[wasm_bindgen_test]
-    fn test_update_existing_field() {
-        let el = create_elem_with_style("color: red;");
-        let attr = CssAttribute {
-            field: "color",
-            value: Some("green"),
-        };
-        update_css_field(attr, &el).unwrap();
-        assert_eq!(el.get_attribute("style").unwrap(), "color: green;");
-    }
-
-    #[wasm_bindgen_test]
-    fn test_remove_field() {
-        let el = create_elem_with_style("color: red; background-color: blue;");
-        let attr = CssAttribute {
-            field: "color",
-            value: None,
-        };
-        update_css_field(attr, &el).unwrap();
-        let got = el.get_attribute("style").unwrap();
-        assert_eq!(got, "background-color: blue;");
-    }
-
     #[wasm_bindgen_test]
-    fn test_remove_last_field_removes_attribute() {
-        let el = create_elem_with_style("color: red;");
-        let attr = CssAttribute {
-            field: "color",
-            value: None,
-        };
-        update_css_field(attr, &el).unwrap();
-        assert!(el.get_attribute("style").is_none());
+    fn test_create_span_escapes_markup() {
+        let doc = get_document().expect("document");
+        let span = create_span(&doc, &Cell::new("<b>")).expect("span");
+        assert_eq!(span.child_element_count(), 0);
+        assert_eq!(span.text_content(), Some("<b>".to_string()));
     }
 }
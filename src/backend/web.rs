@@ -0,0 +1,413 @@
+use std::{cell::RefCell, fmt, mem::size_of};
+
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+use std::{any::Any, collections::HashSet};
+
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::{Position, Size},
+    prelude::Backend,
+    Terminal,
+};
+
+use crate::backend::metrics::CellMetrics;
+
+/// The area of a cell, in CSS pixels relative to the viewport.
+///
+/// The coordinates use the same space as `clientX` and `clientY` of mouse
+/// events, and as [`MouseEvent::x`](crate::event::MouseEvent::x) and
+/// [`MouseEvent::y`](crate::event::MouseEvent::y).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellRect {
+    /// Distance from the left edge of the viewport.
+    pub x: f64,
+    /// Distance from the top edge of the viewport.
+    pub y: f64,
+    /// Width of the cell.
+    pub width: f64,
+    /// Height of the cell.
+    pub height: f64,
+}
+
+impl CellRect {
+    /// Returns `true` if the rectangle contains the given point.
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Approximate memory usage of a terminal, to tune options for low-end
+/// devices.
+///
+/// The sizes only account for the data kept by ratzilla and ratatui, not for
+/// the memory of the browser, e.g. GPU textures or the layout of DOM nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Number of cells of the grid.
+    pub cells: usize,
+    /// Approximate size of the cell buffers, in bytes.
+    pub buffer_bytes: usize,
+    /// Number of cached styles, e.g. the CSS classes of the CSP safe mode of
+    /// the [`DomBackend`](crate::DomBackend).
+    pub style_cache_entries: usize,
+    /// Number of distinct glyphs on screen, i.e. a lower bound of the glyph
+    /// atlas occupancy of the [`WebGl2Backend`](crate::WebGl2Backend).
+    pub distinct_glyphs: usize,
+    /// Number of DOM nodes created by the backend, if it renders to the DOM.
+    pub dom_nodes: Option<usize>,
+}
+
+impl fmt::Display for MemoryStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} cells, {:.1} KiB of buffers, {} cached styles, {} glyphs",
+            self.cells,
+            self.buffer_bytes as f64 / 1024.0,
+            self.style_cache_entries,
+            self.distinct_glyphs
+        )?;
+        if let Some(nodes) = self.dom_nodes {
+            write!(f, ", {nodes} DOM nodes")?;
+        }
+        Ok(())
+    }
+}
+
+/// Functionality shared by the web backends on top of [`Backend`].
+pub trait WebBackend: Backend {
+    /// Returns the cell under the given point, in CSS pixels relative to the
+    /// viewport, or `None` if the point is outside of the grid.
+    fn cell_at(&self, x: f64, y: f64) -> Option<Position>;
+
+    /// Returns the area of the given cell, in CSS pixels relative to the
+    /// viewport, or `None` if the cell is outside of the grid.
+    fn cell_rect(&self, column: u16, row: u16) -> Option<CellRect>;
+
+    /// Returns the size of a cell.
+    fn cell_metrics(&self) -> CellMetrics;
+
+    /// Returns a copy of the cells last drawn to the screen.
+    fn current_buffer(&self) -> Buffer;
+
+    /// Returns the approximate memory usage of the backend.
+    fn memory_stats(&self) -> MemoryStats;
+}
+
+/// Web specific methods of a [`Terminal`] with a [`WebBackend`].
+///
+/// ```rust no_run
+/// use ratzilla::{ratatui::Terminal, DomBackend, WebRenderer, WebTerminal};
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+///
+/// let terminal = Terminal::new(DomBackend::new()?)?;
+/// if let Some(cell) = terminal.cell_at(120.0, 48.0) {
+///     // the point is over the cell at (cell.x, cell.y)
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub trait WebTerminal {
+    /// Returns the cell under the given point, in CSS pixels relative to the
+    /// viewport, or `None` if the point is outside of the grid.
+    fn cell_at(&self, x: f64, y: f64) -> Option<Position>;
+
+    /// Returns the area of the given cell, in CSS pixels relative to the
+    /// viewport, or `None` if the cell is outside of the grid.
+    fn cell_rect(&self, column: u16, row: u16) -> Option<CellRect>;
+
+    /// Returns the size of a cell, to lay out elements over the grid or to
+    /// size the grid itself.
+    fn cell_metrics(&self) -> CellMetrics;
+
+    /// Returns a copy of the cells last drawn to the screen.
+    ///
+    /// Unlike [`Terminal::current_buffer_mut`], which is the buffer of the
+    /// frame being drawn, this is what is actually on screen, e.g. for copy
+    /// modes, search or tests.
+    fn current_buffer(&self) -> Buffer;
+
+    /// Returns the approximate memory usage of the terminal and its backend.
+    fn memory_stats(&self) -> MemoryStats;
+}
+
+impl<B: WebBackend> WebTerminal for Terminal<B> {
+    fn cell_at(&self, x: f64, y: f64) -> Option<Position> {
+        self.backend().cell_at(x, y)
+    }
+
+    fn cell_rect(&self, column: u16, row: u16) -> Option<CellRect> {
+        self.backend().cell_rect(column, row)
+    }
+
+    fn cell_metrics(&self) -> CellMetrics {
+        self.backend().cell_metrics()
+    }
+
+    fn current_buffer(&self) -> Buffer {
+        self.backend().current_buffer()
+    }
+
+    fn memory_stats(&self) -> MemoryStats {
+        let mut stats = self.backend().memory_stats();
+        // the terminal keeps the current and the previous frame
+        stats.buffer_bytes += buffer_bytes(stats.cells) * 2;
+        stats
+    }
+}
+
+/// Returns a copy of the cells last drawn to the screen by a web backend, or
+/// `None` for other backends.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+pub(crate) fn web_backend_buffer(backend: &dyn Any) -> Option<Buffer> {
+    #[cfg(feature = "dom")]
+    if let Some(backend) = backend.downcast_ref::<crate::DomBackend>() {
+        return Some(backend.current_buffer());
+    }
+    #[cfg(feature = "canvas")]
+    if let Some(backend) = backend.downcast_ref::<crate::CanvasBackend>() {
+        return Some(backend.current_buffer());
+    }
+    #[cfg(feature = "webgl2")]
+    if let Some(backend) = backend.downcast_ref::<crate::WebGl2Backend>() {
+        return Some(backend.current_buffer());
+    }
+    #[cfg(feature = "webgpu")]
+    if let Some(backend) = backend.downcast_ref::<crate::WgpuBackend>() {
+        return Some(backend.current_buffer());
+    }
+    None
+}
+
+/// Records a drawn cell in the copy of the screen of a backend.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+pub(crate) fn mirror_cell(buffer: &mut Buffer, x: u16, y: u16, cell: &Cell) {
+    if let Some(target) = buffer.cell_mut((x, y)) {
+        *target = cell.clone();
+    }
+}
+
+/// Returns the approximate size of a buffer of the given number of cells.
+pub(crate) fn buffer_bytes(cells: usize) -> usize {
+    cells * size_of::<Cell>()
+}
+
+/// Returns the number of distinct symbols in a list of cells.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+pub(crate) fn distinct_glyphs<'a>(cells: impl IntoIterator<Item = &'a Cell>) -> usize {
+    cells
+        .into_iter()
+        .map(Cell::symbol)
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Geometry of a grid of equally sized cells.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct GridGeometry {
+    /// Position of the top-left corner of the first cell, in CSS pixels.
+    pub(crate) origin: (f64, f64),
+    /// Size of a cell, in CSS pixels.
+    pub(crate) cell_size: (f64, f64),
+    /// Size of the grid, in cells.
+    pub(crate) size: Size,
+}
+
+impl GridGeometry {
+    /// Returns the cell under the given point.
+    pub(crate) fn cell_at(&self, x: f64, y: f64) -> Option<Position> {
+        let (width, height) = self.cell_size;
+        if width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        let column = ((x - self.origin.0) / width).floor();
+        let row = ((y - self.origin.1) / height).floor();
+        if column < 0.0
+            || row < 0.0
+            || column >= self.size.width as f64
+            || row >= self.size.height as f64
+        {
+            return None;
+        }
+        Some(Position::new(column as u16, row as u16))
+    }
+
+    /// Returns the cell nearest to the given point, clamping points outside
+    /// of the grid to its edges.
+    pub(crate) fn nearest_cell_at(&self, x: f64, y: f64) -> Option<Position> {
+        let (width, height) = self.cell_size;
+        if width <= 0.0 || height <= 0.0 || self.size.width == 0 || self.size.height == 0 {
+            return None;
+        }
+        let column = ((x - self.origin.0) / width).floor();
+        let row = ((y - self.origin.1) / height).floor();
+        Some(Position::new(
+            column.clamp(0.0, (self.size.width - 1) as f64) as u16,
+            row.clamp(0.0, (self.size.height - 1) as f64) as u16,
+        ))
+    }
+
+    /// Returns how far the given height is past the top (negative) or the
+    /// bottom (positive) edge of the grid, in cells.
+    pub(crate) fn overshoot_rows(&self, y: f64) -> f64 {
+        let height = self.cell_size.1;
+        if height <= 0.0 {
+            return 0.0;
+        }
+        let bottom = self.origin.1 + self.size.height as f64 * height;
+        if y < self.origin.1 {
+            (y - self.origin.1) / height
+        } else if y >= bottom {
+            (y - bottom) / height
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the area of the given cell.
+    #[cfg(any(
+        feature = "dom",
+        feature = "canvas",
+        feature = "webgl2",
+        feature = "webgpu"
+    ))]
+    pub(crate) fn cell_rect(&self, column: u16, row: u16) -> Option<CellRect> {
+        if column >= self.size.width || row >= self.size.height {
+            return None;
+        }
+        let (width, height) = self.cell_size;
+        Some(CellRect {
+            x: self.origin.0 + column as f64 * width,
+            y: self.origin.1 + row as f64 * height,
+            width,
+            height,
+        })
+    }
+}
+
+/// Measures the grid of a backend.
+type MeasureGrid = Box<dyn Fn() -> Option<GridGeometry>>;
+
+thread_local! {
+    /// Measures the grid of the backend that set it last.
+    static GRID: RefCell<Option<MeasureGrid>> = RefCell::new(None);
+}
+
+/// Sets how to measure the grid that mouse events are translated to.
+///
+/// Backends call this when they create or resize their grid. The grid is
+/// measured for every event since scrolling moves it.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+pub(crate) fn set_grid<F>(measure: F)
+where
+    F: Fn() -> Option<GridGeometry> + 'static,
+{
+    GRID.with(|grid| *grid.borrow_mut() = Some(Box::new(measure)));
+}
+
+/// Measures the grid set with [`set_grid`].
+pub(crate) fn grid() -> Option<GridGeometry> {
+    GRID.with(|grid| grid.borrow().as_ref()?())
+}
+
+/// Returns the cell under the given point of the grid set with [`set_grid`].
+pub(crate) fn grid_cell_at(x: f64, y: f64) -> Option<Position> {
+    grid()?.cell_at(x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: GridGeometry = GridGeometry {
+        origin: (8.0, 20.0),
+        cell_size: (10.0, 19.0),
+        size: Size::new(80, 24),
+    };
+
+    #[test]
+    fn test_cell_at() {
+        assert_eq!(GRID.cell_at(8.0, 20.0), Some(Position::new(0, 0)));
+        assert_eq!(GRID.cell_at(33.5, 59.0), Some(Position::new(2, 2)));
+        assert_eq!(GRID.cell_at(7.9, 20.0), None);
+        assert_eq!(GRID.cell_at(808.0, 20.0), None);
+    }
+
+    #[test]
+    fn test_nearest_cell_at() {
+        assert_eq!(GRID.nearest_cell_at(33.5, 59.0), Some(Position::new(2, 2)));
+        assert_eq!(GRID.nearest_cell_at(0.0, 0.0), Some(Position::new(0, 0)));
+        assert_eq!(
+            GRID.nearest_cell_at(900.0, 1000.0),
+            Some(Position::new(79, 23))
+        );
+    }
+
+    #[test]
+    fn test_overshoot_rows() {
+        assert_eq!(GRID.overshoot_rows(20.0), 0.0);
+        assert_eq!(GRID.overshoot_rows(1.0), -1.0);
+        // the grid ends at 20 + 24 * 19 = 476
+        assert_eq!(GRID.overshoot_rows(475.0), 0.0);
+        assert_eq!(GRID.overshoot_rows(514.0), 2.0);
+    }
+
+    #[test]
+    #[cfg(any(
+        feature = "dom",
+        feature = "canvas",
+        feature = "webgl2",
+        feature = "webgpu"
+    ))]
+    fn test_distinct_glyphs() {
+        let buffer = Buffer::with_lines(["abca", "  ─ "]);
+        assert_eq!(distinct_glyphs(&buffer.content), 5);
+    }
+
+    #[test]
+    #[cfg(any(
+        feature = "dom",
+        feature = "canvas",
+        feature = "webgl2",
+        feature = "webgpu"
+    ))]
+    fn test_cell_rect() {
+        let rect = GRID.cell_rect(2, 1).expect("cell in the grid");
+        assert_eq!(
+            rect,
+            CellRect {
+                x: 28.0,
+                y: 39.0,
+                width: 10.0,
+                height: 19.0
+            }
+        );
+        assert!(rect.contains(30.0, 40.0));
+        assert_eq!(GRID.cell_rect(80, 0), None);
+    }
+}
@@ -1,22 +1,34 @@
 use crate::{
-    backend::{color::to_rgb, utils::*},
+    analytics::{self, AnalyticsEvent},
+    backend::{
+        color::to_rgb,
+        image::ImageOverlay,
+        metrics::CellMetrics,
+        utils::*,
+        web::{
+            self, buffer_bytes, distinct_glyphs, mirror_cell, CellRect, GridGeometry, MemoryStats,
+            WebBackend,
+        },
+    },
+    clock,
     error::Error,
+    links, render_loop, resize, watchdog,
     widgets::hyperlink::HYPERLINK_MODIFIER,
-    CursorShape,
+    CursorOptions, CursorShape,
 };
 pub use beamterm_renderer::SelectionMode;
-use beamterm_renderer::{mouse::*, select, CellData, GlyphEffect, Terminal as Beamterm, Terminal};
+use beamterm_renderer::{mouse::*, select, CellData, Terminal as Beamterm, Terminal};
 use bitvec::prelude::BitVec;
 use compact_str::CompactString;
 use ratatui::{
     backend::{ClearType, WindowSize},
-    buffer::Cell,
-    layout::{Position, Size},
+    buffer::{Buffer, Cell},
+    layout::{Position, Rect, Size},
     prelude::Backend,
     style::{Color, Modifier},
 };
 use std::{
-    cell::RefCell,
+    cell::{Cell as StdCell, RefCell},
     io::{Error as IoError, Result as IoResult},
     mem::swap,
     rc::Rc,
@@ -67,8 +79,8 @@ pub struct WebGl2BackendOptions {
     font_atlas_config: Option<FontAtlasConfig>,
     /// The canvas padding color.
     canvas_padding_color: Option<Color>,
-    /// The cursor shape.
-    cursor_shape: CursorShape,
+    /// How the cursor is drawn.
+    cursor: CursorOptions,
     /// Hyperlink click callback.
     hyperlink_callback: Option<HyperlinkCallback>,
     /// Mouse selection mode (enables text selection with mouse).
@@ -122,7 +134,16 @@ impl WebGl2BackendOptions {
 
     /// Sets the cursor shape to use when cursor is visible.
     pub fn cursor_shape(mut self, shape: CursorShape) -> Self {
-        self.cursor_shape = shape;
+        self.cursor.shape = shape;
+        self
+    }
+
+    /// Sets how the cursor is drawn.
+    ///
+    /// Bars are drawn as underscores, since the glyphs are drawn from the
+    /// font atlas.
+    pub fn cursor_options(mut self, options: CursorOptions) -> Self {
+        self.cursor = options;
         self
     }
 
@@ -296,13 +317,23 @@ pub struct WebGl2Backend {
     cursor_over_hyperlink: Option<Rc<RefCell<bool>>>,
     /// Hyperlink click callback.
     _hyperlink_callback: Option<HyperlinkCallback>,
+    /// Copy of the cells on screen.
+    buffer: Buffer,
+    /// How the size of the canvas follows the page.
+    size_source: CanvasSize,
+    /// Set when the window was resized.
+    resized: Rc<StdCell<bool>>,
+    /// Elements of the [`images`](crate::images) over the canvas.
+    images: ImageOverlay,
 }
 
 impl WebGl2Backend {
     /// Constructs a new [`WebGl2Backend`].
     pub fn new() -> Result<Self, Error> {
         let (width, height) = get_raw_window_size();
-        Self::new_with_size(width.into(), height.into())
+        let mut backend = Self::new_with_size(width.into(), height.into())?;
+        backend.size_source = CanvasSize::Window;
+        Ok(backend)
     }
 
     /// Constructs a new [`WebGl2Backend`] with the given size.
@@ -325,6 +356,18 @@ impl WebGl2Backend {
         let parent = get_element_by_id_or_body(options.grid_id.as_ref())?;
 
         let beamterm = Self::init_beamterm(&mut options, &parent)?;
+        let size_source = match options.size {
+            Some(_) => CanvasSize::Fixed,
+            None => CanvasSize::Parent(parent.clone()),
+        };
+        let resized = Rc::new(StdCell::new(false));
+        resize::on_window_resize({
+            let resized = resized.clone();
+            move || resized.set(true)
+        })?;
+        if options.grid_id.is_some() {
+            resize::observe_element(&parent)?;
+        }
 
         let hyperlink_cells = if options.hyperlink_callback.is_some() {
             let indices = BitVec::repeat(false, beamterm.cell_count());
@@ -361,7 +404,10 @@ impl WebGl2Backend {
             None
         };
 
-        Ok(Self {
+        let (columns, rows) = beamterm.terminal_size();
+        analytics::report(AnalyticsEvent::BackendSelected { backend: "webgl2" });
+        let backend = Self {
+            buffer: Buffer::empty(Rect::new(0, 0, columns, rows)),
             beamterm,
             cursor_position: None,
             options,
@@ -370,7 +416,12 @@ impl WebGl2Backend {
             performance,
             cursor_over_hyperlink,
             _hyperlink_callback: hyperlink_callback,
-        })
+            size_source,
+            resized,
+            images: ImageOverlay::new(),
+        };
+        backend.set_grid();
+        Ok(backend)
     }
 
     /// Returns the options objects used to create this backend.
@@ -380,12 +431,20 @@ impl WebGl2Backend {
 
     /// Returns the [`CursorShape`].
     pub fn cursor_shape(&self) -> &CursorShape {
-        &self.options.cursor_shape
+        &self.options.cursor.shape
     }
 
     /// Set the [`CursorShape`].
     pub fn set_cursor_shape(mut self, shape: CursorShape) -> Self {
-        self.options.cursor_shape = shape;
+        self.options.cursor.shape = shape;
+        self
+    }
+
+    /// Set the [`CursorOptions`].
+    ///
+    /// See [`WebGl2BackendOptions::cursor_options`].
+    pub fn set_cursor_options(mut self, options: CursorOptions) -> Self {
+        self.options.cursor = options;
         self
     }
 
@@ -395,6 +454,9 @@ impl WebGl2Backend {
 
         // resize the terminal grid and viewport
         self.beamterm.resize(size_px.0, size_px.1)?;
+        let (columns, rows) = self.beamterm.terminal_size();
+        self.buffer.resize(Rect::new(0, 0, columns, rows));
+        self.set_grid();
 
         // clear any hyperlink cells; we'll get them in the next draw call
         if let Some(hyperlink_cells) = &mut self.hyperlink_cells {
@@ -417,6 +479,24 @@ impl WebGl2Backend {
 
     /// Checks if the canvas size matches the display size and resizes it if necessary.
     fn check_canvas_resize(&mut self) -> Result<(), Error> {
+        // follow the page, unless the size is fixed
+        if let Some((width, height)) = self
+            .resized
+            .replace(false)
+            .then(|| self.size_source.get())
+            .flatten()
+        {
+            let canvas = self.beamterm.canvas();
+            if (canvas.width(), canvas.height()) != (width, height) {
+                canvas.set_width(width);
+                canvas.set_height(height);
+                self.resize_canvas()?;
+                // the terminal picks up the new size on the next frame
+                render_loop::request_redraw();
+                return Ok(());
+            }
+        }
+
         let canvas = self.beamterm.canvas();
         let display_width = canvas.client_width() as u32;
         let display_height = canvas.client_height() as u32;
@@ -471,34 +551,23 @@ impl WebGl2Backend {
         Ok(())
     }
 
-    /// Toggles the cursor visibility based on its current position.
-    ///
-    /// If there is no cursor position, it does nothing.
-    fn toggle_cursor(&mut self) {
-        if let Some(pos) = self.cursor_position {
-            self.draw_cursor(pos);
-        }
-    }
-
-    /// Draws the cursor at the specified position.
-    fn draw_cursor(&mut self, pos: Position) {
-        if let Some(c) = self
-            .beamterm
-            .grid()
-            .borrow_mut()
-            .cell_data_mut(pos.x, pos.y)
-        {
-            match self.options.cursor_shape {
-                CursorShape::SteadyBlock => {
-                    c.flip_colors();
-                }
-                CursorShape::SteadyUnderScore => {
-                    // if the overall style is underlined, remove it, otherwise add it
-                    c.style(c.get_style() ^ (GlyphEffect::Underline as u16));
-                }
-                CursorShape::None => (),
-            }
-        }
+    /// Draws the cursor over the cell at the given position, or restores
+    /// the cell.
+    fn draw_cursor(&mut self, pos: Position, shown: bool) -> Result<(), Error> {
+        let Some(cell) = self.buffer.cell(pos) else {
+            return Ok(());
+        };
+        let cell = if shown {
+            self.options.cursor.apply(cell)
+        } else {
+            cell.clone()
+        };
+        self.beamterm.update_cells_by_position(std::iter::once((
+            pos.x,
+            pos.y,
+            cell_data(&cell),
+        )))?;
+        Ok(())
     }
 
     /// Measures the beginning of a performance mark.
@@ -576,12 +645,16 @@ impl WebGl2Backend {
                     MouseEventType::MouseUp => {
                         // Handle hyperlink clicks (left mouse button only)
                         if event.button() == 0 {
-                            if let Some(url) = extract_hyperlink_url(
-                                hyperlink_cells_clone.clone(),
-                                grid,
-                                event.col,
-                                event.row,
-                            ) {
+                            // registered links first, then the cells styled as links
+                            let url = links::link_at(event.col, event.row).or_else(|| {
+                                extract_hyperlink_url(
+                                    hyperlink_cells_clone.clone(),
+                                    grid,
+                                    event.col,
+                                    event.row,
+                                )
+                            });
+                            if let Some(url) = url {
                                 if let Ok(mut cb) = callback.try_borrow_mut() {
                                     cb(&url);
                                 }
@@ -590,12 +663,13 @@ impl WebGl2Backend {
                     }
                     MouseEventType::MouseMove => {
                         // Handle cursor style changes on hover
-                        let is_over_hyperlink = Self::is_over_hyperlink(
-                            hyperlink_cells_move.clone(),
-                            grid,
-                            event.col,
-                            event.row,
-                        );
+                        let is_over_hyperlink = links::link_at(event.col, event.row).is_some()
+                            || Self::is_over_hyperlink(
+                                hyperlink_cells_move.clone(),
+                                grid,
+                                event.col,
+                                event.row,
+                            );
 
                         // Only update cursor style if state has changed
                         if let Ok(mut current_state) = cursor_state_clone.try_borrow_mut() {
@@ -676,6 +750,13 @@ impl WebGl2Backend {
     }
 }
 
+impl Drop for WebGl2Backend {
+    /// Removes the canvas from the page, e.g. on [teardown](crate::teardown).
+    fn drop(&mut self) {
+        self.beamterm.canvas().remove();
+    }
+}
+
 impl Backend for WebGl2Backend {
     type Error = IoError;
 
@@ -684,10 +765,15 @@ impl Backend for WebGl2Backend {
     where
         I: Iterator<Item = (u16, u16, &'a Cell)>,
     {
+        watchdog::mark_flush_start();
         // we only update when we have new cell data or if the mouse selection
         // handler is enabled (otherwise, we fail to update the visualized selection).
         if content.size_hint().1 != Some(0) || self.options.mouse_selection_mode.is_some() {
-            self.update_grid(content)?;
+            let mut buffer = std::mem::take(&mut self.buffer);
+            let content = content.inspect(|(x, y, cell)| mirror_cell(&mut buffer, *x, *y, cell));
+            let result = self.update_grid(content);
+            self.buffer = buffer;
+            result?;
         }
 
         Ok(())
@@ -703,12 +789,25 @@ impl Backend for WebGl2Backend {
         self.measure_begin(WEBGL_RENDER_MARK);
 
         // Flushes GPU buffers and render existing content to the canvas
-        self.toggle_cursor(); // show cursor before rendering
+        let cursor = self
+            .cursor_position
+            .filter(|_| self.options.cursor.is_visible(clock::now()));
+        if let Some(pos) = cursor {
+            self.draw_cursor(pos, true)?; // show cursor before rendering
+        }
         self.beamterm.render_frame().map_err(Error::from)?;
-        self.toggle_cursor(); // restore cell to previous state
+        if let Some(pos) = cursor {
+            self.draw_cursor(pos, false)?; // restore cell to previous state
+        }
 
         self.measure_end(WEBGL_RENDER_MARK);
 
+        // images can't be drawn with the glyphs, they are placed over the canvas
+        let mut images = std::mem::take(&mut self.images);
+        let result = images.update(|| Some(self.geometry()));
+        self.images = images;
+        result?;
+
         Ok(())
     }
 
@@ -728,6 +827,7 @@ impl Backend for WebGl2Backend {
             .take(self.beamterm.cell_count());
 
         self.beamterm.update_cells(cells).map_err(Error::from)?;
+        self.buffer.reset();
 
         if let Some(hyperlink_cells) = &mut self.hyperlink_cells {
             hyperlink_cells.borrow_mut().clear();
@@ -771,6 +871,75 @@ impl Backend for WebGl2Backend {
     }
 }
 
+impl WebBackend for WebGl2Backend {
+    fn cell_at(&self, x: f64, y: f64) -> Option<Position> {
+        self.geometry().cell_at(x, y)
+    }
+
+    fn cell_rect(&self, column: u16, row: u16) -> Option<CellRect> {
+        self.geometry().cell_rect(column, row)
+    }
+
+    fn cell_metrics(&self) -> CellMetrics {
+        let ((width, height), _) = self.grid_size();
+        CellMetrics::new(width, height)
+    }
+
+    fn current_buffer(&self) -> Buffer {
+        self.buffer.clone()
+    }
+
+    fn memory_stats(&self) -> MemoryStats {
+        let cells = self.buffer.content.len();
+        MemoryStats {
+            cells,
+            buffer_bytes: buffer_bytes(cells),
+            style_cache_entries: 0,
+            distinct_glyphs: distinct_glyphs(&self.buffer.content),
+            dom_nodes: None,
+        }
+    }
+}
+
+impl WebGl2Backend {
+    /// Returns the geometry of the grid on the page.
+    fn geometry(&self) -> GridGeometry {
+        let (cell_size, size) = self.grid_size();
+        measure_grid(self.beamterm.canvas(), cell_size, size)
+    }
+
+    /// Translates the mouse events to the cells of this backend.
+    fn set_grid(&self) {
+        let canvas = self.beamterm.canvas().clone();
+        let (cell_size, size) = self.grid_size();
+        web::set_grid(move || Some(measure_grid(&canvas, cell_size, size)));
+    }
+
+    /// Returns the size of a cell in CSS pixels, and the size of the grid.
+    fn grid_size(&self) -> ((f64, f64), Size) {
+        let (cell_width, cell_height) = self.beamterm.cell_size();
+        let (columns, rows) = self.beamterm.terminal_size();
+        (
+            (cell_width as f64, cell_height as f64),
+            Size::new(columns, rows),
+        )
+    }
+}
+
+/// Measures a grid drawn on the given canvas.
+fn measure_grid(
+    canvas: &web_sys::HtmlCanvasElement,
+    cell_size: (f64, f64),
+    size: Size,
+) -> GridGeometry {
+    let canvas = canvas.get_bounding_client_rect();
+    GridGeometry {
+        origin: (canvas.left(), canvas.top()),
+        cell_size,
+        size,
+    }
+}
+
 /// Extracts text from beamterm grid using `[get_text(CellQuery)`].
 fn extract_text_from_grid(
     grid: &beamterm_renderer::TerminalGrid,
@@ -902,32 +1071,6 @@ const fn into_glyph_bits(modifier: Modifier) -> u16 {
     | (m << 6) & (1 << 14) // strikethrough
 }
 
-/// A `Debug`-derive friendly convenience wrapper
-#[derive(Clone)]
-struct HyperlinkCallback {
-    callback: Rc<RefCell<dyn FnMut(&str)>>,
-}
-
-impl HyperlinkCallback {
-    /// Creates a new [`HyperlinkCallback`] with the given callback.
-    pub fn new<F>(callback: F) -> Self
-    where
-        F: FnMut(&str) + 'static,
-    {
-        Self {
-            callback: Rc::new(RefCell::new(callback)),
-        }
-    }
-}
-
-impl std::fmt::Debug for HyperlinkCallback {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("CallbackWrapper")
-            .field("callback", &"<callback>")
-            .finish()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,1262 @@
+use std::{
+    cell::{Cell as StdCell, RefCell},
+    collections::HashMap,
+    io::{Error as IoError, Result as IoResult},
+    mem::swap,
+    ops::Range,
+    rc::Rc,
+};
+
+use compact_str::CompactString;
+use ratatui::{
+    backend::{ClearType, WindowSize},
+    buffer::{Buffer, Cell},
+    layout::{Position, Rect, Size},
+    prelude::Backend,
+    style::Modifier,
+};
+use unicode_width::UnicodeWidthStr;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    gpu_buffer_usage, gpu_texture_usage,
+    js_sys::{Array, Boolean, Map},
+    wasm_bindgen::{JsCast, JsValue},
+    CanvasRenderingContext2d, Gpu, GpuAdapter, GpuBindGroup, GpuBindGroupDescriptor,
+    GpuBindGroupEntry, GpuBuffer, GpuBufferBinding, GpuBufferDescriptor, GpuCanvasAlphaMode,
+    GpuCanvasConfiguration, GpuCanvasContext, GpuColorDict, GpuColorTargetState, GpuDevice,
+    GpuExtent3dDict, GpuFragmentState, GpuLoadOp, GpuOrigin3dDict, GpuPrimitiveState,
+    GpuPrimitiveTopology, GpuQueue, GpuRenderPassColorAttachment, GpuRenderPassDescriptor,
+    GpuRenderPipeline, GpuRenderPipelineDescriptor, GpuShaderModuleDescriptor, GpuStoreOp,
+    GpuTexelCopyBufferLayout, GpuTexelCopyTextureInfo, GpuTexture, GpuTextureDescriptor,
+    GpuTextureFormat, GpuVertexState, HtmlCanvasElement,
+};
+
+use crate::{
+    analytics::{self, AnalyticsEvent},
+    backend::{
+        color::to_rgb,
+        image::ImageOverlay,
+        metrics::{device_pixel_ratio, CellMetrics},
+        utils::*,
+        web::{
+            self, buffer_bytes, distinct_glyphs, mirror_cell, CellRect, GridGeometry, MemoryStats,
+            WebBackend,
+        },
+    },
+    clock,
+    error::Error,
+    render_loop, resize, watchdog, CursorOptions, CursorShape,
+};
+
+/// Width and height of the glyph atlas texture, in texels.
+const ATLAS_SIZE: u32 = 2048;
+
+/// Size of a cell in the cell buffer, see [`encode_cell`].
+const CELL_BYTES: usize = 16;
+
+/// Bit of the glyph of a cell set when it shows the right half of the wide
+/// glyph on its left.
+const RIGHT_HALF: u32 = 1 << 31;
+
+/// Flag of an underlined cell.
+const UNDERLINE_FLAG: u32 = 1;
+
+/// Flag of a crossed out cell.
+const STRIKETHROUGH_FLAG: u32 = 1 << 1;
+
+/// Draws one quad per cell, with the glyph of the cell from the atlas blended
+/// from the background to the foreground color.
+const SHADER: &str = r#"
+struct Uniforms {
+    grid: vec2<u32>,
+    cell: vec2<f32>,
+    viewport: vec2<f32>,
+    atlas_columns: u32,
+    padding: u32,
+}
+
+struct CellData {
+    glyph: u32,
+    fg: u32,
+    bg: u32,
+    flags: u32,
+}
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+@group(0) @binding(1) var<storage, read> cells: array<CellData>;
+@group(0) @binding(2) var atlas: texture_2d<f32>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) local: vec2<f32>,
+    @location(1) @interpolate(flat) cell: u32,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex: u32, @builtin(instance_index) cell: u32) -> VertexOutput {
+    let corner = vec2<f32>(f32(vertex & 1u), f32(vertex >> 1u));
+    let position = vec2<f32>(f32(cell % uniforms.grid.x), f32(cell / uniforms.grid.x));
+    let pixel = (position + corner) * uniforms.cell;
+    var output: VertexOutput;
+    output.position = vec4<f32>(
+        pixel / uniforms.viewport * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0),
+        0.0,
+        1.0,
+    );
+    output.local = corner * uniforms.cell;
+    output.cell = cell;
+    return output;
+}
+
+fn unpack(color: u32) -> vec4<f32> {
+    return vec4<f32>(
+        f32((color >> 16u) & 0xffu),
+        f32((color >> 8u) & 0xffu),
+        f32(color & 0xffu),
+        255.0,
+    ) / 255.0;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let data = cells[input.cell];
+    let slot = data.glyph & 0x7fffffffu;
+    let right = data.glyph >> 31u;
+    let size = vec2<u32>(uniforms.cell);
+    let origin = vec2<u32>(
+        (slot % uniforms.atlas_columns) * 2u + right,
+        slot / uniforms.atlas_columns,
+    ) * size;
+    let texel = origin + min(vec2<u32>(input.local), size - vec2<u32>(1u));
+    var coverage = textureLoad(atlas, texel, 0).a;
+
+    let thickness = max(1.0, floor(uniforms.cell.y / 16.0));
+    let y = input.local.y;
+    let underline = y >= uniforms.cell.y - 2.0 * thickness && y < uniforms.cell.y - thickness;
+    let middle = floor(uniforms.cell.y / 2.0);
+    let strikethrough = y >= middle && y < middle + thickness;
+    if ((data.flags & 1u) != 0u && underline) || ((data.flags & 2u) != 0u && strikethrough) {
+        coverage = 1.0;
+    }
+    return mix(unpack(data.bg), unpack(data.fg), coverage);
+}
+"#;
+
+/// Options for the [`WgpuBackend`].
+#[derive(Debug)]
+pub struct WgpuBackendOptions {
+    /// The element ID.
+    grid_id: Option<String>,
+    /// Override the automatically detected size.
+    size: Option<(u32, u32)>,
+    /// Font families of the glyphs, in order of preference.
+    font_family: Vec<String>,
+    /// Font size, in CSS pixels.
+    font_size: f32,
+    /// How the cursor is drawn.
+    cursor: CursorOptions,
+    /// Hyperlink click callback.
+    hyperlink_callback: Option<HyperlinkCallback>,
+}
+
+impl Default for WgpuBackendOptions {
+    fn default() -> Self {
+        Self {
+            grid_id: None,
+            size: None,
+            font_family: vec!["monospace".to_string()],
+            font_size: 16.0,
+            cursor: CursorOptions::default(),
+            hyperlink_callback: None,
+        }
+    }
+}
+
+impl WgpuBackendOptions {
+    /// Constructs a new [`WgpuBackendOptions`].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the element id of the canvas' parent element.
+    pub fn grid_id(mut self, id: &str) -> Self {
+        self.grid_id = Some(id.to_string());
+        self
+    }
+
+    /// Sets the size of the canvas, in pixels.
+    pub fn size(mut self, size: (u32, u32)) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Sets the font of the glyph atlas, like the dynamic font atlas of the
+    /// [`WebGl2Backend`](crate::WebGl2Backend).
+    ///
+    /// Glyphs are rasterized by the browser the first time they are drawn,
+    /// so any glyph of the fonts can be drawn. Defaults to `16px monospace`.
+    pub fn font(mut self, font_family: &[&str], font_size: f32) -> Self {
+        self.font_family = font_family.iter().map(|s| s.to_string()).collect();
+        self.font_size = font_size;
+        self
+    }
+
+    /// Sets how the cursor is drawn.
+    ///
+    /// Bars are drawn as underscores.
+    pub fn cursor_options(mut self, options: CursorOptions) -> Self {
+        self.cursor = options;
+        self
+    }
+
+    /// Enables hyperlinks in the canvas.
+    ///
+    /// Sets up a default mouse handler using [`WgpuBackendOptions::on_hyperlink_click`].
+    pub fn enable_hyperlinks(self) -> Self {
+        self.on_hyperlink_click(|url| {
+            if let Some(w) = web_sys::window() {
+                w.open_with_url_and_target(url, "_blank")
+                    .unwrap_or_default();
+            }
+        })
+    }
+
+    /// Sets a callback for when hyperlinks are clicked.
+    ///
+    /// The links are the regions registered in the
+    /// [`LinkRegistry`](crate::links::LinkRegistry), e.g. by the
+    /// [`Hyperlink`](crate::widgets::Hyperlink) widget. The mouse cursor
+    /// turns into a pointer over them.
+    pub fn on_hyperlink_click<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&str) + 'static,
+    {
+        self.hyperlink_callback = Some(HyperlinkCallback::new(callback));
+        self
+    }
+
+    /// Returns the CSS `font` shorthand of the given [`GlyphKey::style`],
+    /// at the given size in pixels.
+    fn font_css(&self, style: u8, size: f64) -> String {
+        let italic = if style & ITALIC_STYLE != 0 {
+            "italic "
+        } else {
+            ""
+        };
+        let bold = if style & BOLD_STYLE != 0 { "bold " } else { "" };
+        format!(
+            "{italic}{bold}{size}px {}",
+            css_font_family(&self.font_family)
+        )
+    }
+}
+
+/// State of the GPU device, requested when the backend is created.
+#[derive(Debug)]
+enum DeviceState {
+    /// The device is being requested.
+    Pending,
+    /// The device is ready.
+    Ready(GpuDevice),
+    /// The device couldn't be created, or was lost.
+    Failed(String),
+}
+
+/// WebGPU backend.
+///
+/// This backend renders the buffer onto a HTML canvas element with WebGPU,
+/// drawing every cell as a quad with its glyph from a glyph atlas. Glyphs
+/// are rasterized by the browser into the atlas the first time they are
+/// drawn, so any glyph of the font can be drawn.
+///
+/// The WebGPU bindings of `web-sys` are unstable, so the `webgpu` feature
+/// also requires `RUSTFLAGS="--cfg=web_sys_unstable_apis"`.
+///
+/// The GPU device is requested asynchronously: frames are only drawn once it
+/// is ready. [`WgpuBackend::new`] fails if the browser doesn't support
+/// WebGPU, so apps can fall back to another backend:
+///
+/// ```rust no_run
+/// use ratzilla::{ratatui::Terminal, DomBackend, WgpuBackend};
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+///
+/// match WgpuBackend::new() {
+///     Ok(backend) => {
+///         let terminal = Terminal::new(backend)?;
+///         // ...
+///     }
+///     Err(_) => {
+///         let terminal = Terminal::new(DomBackend::new()?)?;
+///         // ...
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct WgpuBackend {
+    /// The options used to create this backend.
+    options: WgpuBackendOptions,
+    /// Canvas element.
+    canvas: HtmlCanvasElement,
+    /// GPU device, set once ready.
+    device: Rc<RefCell<DeviceState>>,
+    /// GPU resources, created once the device is ready.
+    renderer: Option<Renderer>,
+    /// Size of a cell.
+    metrics: CellMetrics,
+    /// Size of the canvas, in CSS pixels.
+    size: (u32, u32),
+    /// Copy of the cells on screen.
+    buffer: Buffer,
+    /// Indices of the cells changed since the last frame.
+    dirty: Option<Range<usize>>,
+    /// Cursor position.
+    cursor_position: Option<Position>,
+    /// Position of the cursor on the canvas, if drawn.
+    drawn_cursor: Option<Position>,
+    /// How the size of the canvas follows the page.
+    size_source: CanvasSize,
+    /// Set when the window was resized.
+    resized: Rc<StdCell<bool>>,
+    /// Elements of the [`images`](crate::images) over the canvas.
+    images: ImageOverlay,
+}
+
+impl WgpuBackend {
+    /// Constructs a new [`WgpuBackend`].
+    pub fn new() -> Result<Self, Error> {
+        let (width, height) = get_raw_window_size();
+        let mut backend = Self::new_with_size(width.into(), height.into())?;
+        backend.size_source = CanvasSize::Window;
+        Ok(backend)
+    }
+
+    /// Constructs a new [`WgpuBackend`] with the given size.
+    pub fn new_with_size(width: u32, height: u32) -> Result<Self, Error> {
+        Self::new_with_options(WgpuBackendOptions {
+            size: Some((width, height)),
+            ..Default::default()
+        })
+    }
+
+    /// Constructs a new [`WgpuBackend`] with the given options.
+    ///
+    /// Returns an error if the browser doesn't support WebGPU.
+    pub fn new_with_options(mut options: WgpuBackendOptions) -> Result<Self, Error> {
+        let gpu = gpu()?;
+
+        // Parent element of canvas (uses <body> unless specified)
+        let parent = get_element_by_id_or_body(options.grid_id.as_ref())?;
+
+        let size_source = match options.size {
+            Some(_) => CanvasSize::Fixed,
+            None => CanvasSize::Parent(parent.clone()),
+        };
+        let (width, height) = options
+            .size
+            .unwrap_or_else(|| (parent.client_width() as u32, parent.client_height() as u32));
+        let resized = Rc::new(StdCell::new(false));
+        resize::on_window_resize({
+            let resized = resized.clone();
+            move || resized.set(true)
+        })?;
+        if options.grid_id.is_some() {
+            resize::observe_element(&parent)?;
+        }
+
+        let canvas = create_canvas_in_element(&parent, width, height)?;
+        if let Some(callback) = options.hyperlink_callback.take() {
+            handle_hyperlinks(&canvas, callback)?;
+        }
+
+        let device = Rc::new(RefCell::new(DeviceState::Pending));
+        wasm_bindgen_futures::spawn_local({
+            let device = device.clone();
+            async move {
+                match request_device(&gpu).await {
+                    Ok(ready) => {
+                        let lost = ready.lost();
+                        device.replace(DeviceState::Ready(ready));
+                        render_loop::request_redraw();
+                        let _ = JsFuture::from(lost).await;
+                        device.replace(DeviceState::Failed(
+                            "the WebGPU device was lost".to_string(),
+                        ));
+                    }
+                    Err(error) => {
+                        device.replace(DeviceState::Failed(js_error(&error)));
+                    }
+                }
+            }
+        });
+
+        analytics::report(AnalyticsEvent::BackendSelected { backend: "webgpu" });
+        let mut backend = Self {
+            options,
+            canvas,
+            device,
+            renderer: None,
+            metrics: CellMetrics::default(),
+            size: (width, height),
+            buffer: Buffer::default(),
+            dirty: None,
+            cursor_position: None,
+            drawn_cursor: None,
+            size_source,
+            resized,
+            images: ImageOverlay::new(),
+        };
+        backend.resize_canvas((width, height))?;
+        Ok(backend)
+    }
+
+    /// Returns `true` if the browser supports WebGPU.
+    ///
+    /// The GPU may still be unavailable, e.g. if it is blocklisted.
+    pub fn is_supported() -> bool {
+        gpu().is_ok()
+    }
+
+    /// Returns the options objects used to create this backend.
+    pub fn options(&self) -> &WgpuBackendOptions {
+        &self.options
+    }
+
+    /// Returns the [`CursorShape`].
+    pub fn cursor_shape(&self) -> &CursorShape {
+        &self.options.cursor.shape
+    }
+
+    /// Set the [`CursorShape`].
+    pub fn set_cursor_shape(mut self, shape: CursorShape) -> Self {
+        self.options.cursor.shape = shape;
+        self
+    }
+
+    /// Set the [`CursorOptions`].
+    ///
+    /// See [`WgpuBackendOptions::cursor_options`].
+    pub fn set_cursor_options(mut self, options: CursorOptions) -> Self {
+        self.options.cursor = options;
+        self
+    }
+
+    /// Sizes the canvas to the given size in CSS pixels, at the device pixel
+    /// ratio of the window.
+    ///
+    /// The cells are measured again and the GPU resources are recreated on
+    /// the next frame, since the glyph atlas depends on the cell size.
+    fn resize_canvas(&mut self, (width, height): (u32, u32)) -> Result<(), Error> {
+        let pixel_ratio = device_pixel_ratio();
+        self.canvas
+            .set_width((width as f64 * pixel_ratio).round() as u32);
+        self.canvas
+            .set_height((height as f64 * pixel_ratio).round() as u32);
+        let style = self.canvas.style();
+        style.set_property("width", &format!("{width}px"))?;
+        style.set_property("height", &format!("{height}px"))?;
+        self.size = (width, height);
+
+        let font = self.options.font_css(0, self.options.font_size as f64);
+        let measured = CellMetrics::measure(Some(&font))?;
+        // whole texels, so the glyphs are not resampled
+        self.metrics = CellMetrics {
+            width: (measured.width * pixel_ratio).ceil() / pixel_ratio,
+            height: (measured.height * pixel_ratio).ceil() / pixel_ratio,
+            device_pixel_ratio: pixel_ratio,
+        };
+        let size = self.metrics.grid_size(width as f64, height as f64);
+        self.buffer = Buffer::empty(Rect::from((Position::ORIGIN, size)));
+        self.renderer = None;
+        self.drawn_cursor = None;
+        self.set_grid();
+        Ok(())
+    }
+
+    /// Marks the cell at the given position as changed.
+    fn mark_dirty(&mut self, x: u16, y: u16) {
+        if !self.buffer.area.contains(Position::new(x, y)) {
+            return;
+        }
+        let index = self.buffer.index_of(x, y);
+        // the next cell shows the right half of a wide glyph
+        let cells = index..(index + 2).min(self.buffer.content.len());
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => dirty.start.min(cells.start)..dirty.end.max(cells.end),
+            None => cells,
+        });
+    }
+
+    /// Returns the GPU device, or `None` while it is requested.
+    fn device(&mut self) -> Result<Option<GpuDevice>, Error> {
+        match &*self.device.borrow() {
+            DeviceState::Pending => Ok(None),
+            DeviceState::Ready(device) => Ok(Some(device.clone())),
+            DeviceState::Failed(message) => {
+                self.renderer = None;
+                Err(Error::WebGpuError(message.clone()))
+            }
+        }
+    }
+}
+
+impl Drop for WgpuBackend {
+    /// Removes the canvas from the page and releases the GPU, e.g. on
+    /// [teardown](crate::teardown).
+    fn drop(&mut self) {
+        self.canvas.remove();
+        if let DeviceState::Ready(device) = &*self.device.borrow() {
+            device.destroy();
+        }
+    }
+}
+
+impl Backend for WgpuBackend {
+    type Error = IoError;
+
+    // Populates the buffer with the given content.
+    fn draw<'a, I>(&mut self, content: I) -> IoResult<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        watchdog::mark_flush_start();
+        for (x, y, cell) in content {
+            mirror_cell(&mut self.buffer, x, y, cell);
+            self.mark_dirty(x, y);
+        }
+        Ok(())
+    }
+
+    /// Flush the content to the screen.
+    ///
+    /// This function is called after the [`WgpuBackend::draw`] function to
+    /// upload the changed cells to the GPU and render them. Nothing is
+    /// drawn until the GPU device is ready.
+    fn flush(&mut self) -> IoResult<()> {
+        // follow the page, unless the size is fixed
+        if let Some(size) = self
+            .resized
+            .replace(false)
+            .then(|| self.size_source.get())
+            .flatten()
+        {
+            if size != self.size {
+                self.resize_canvas(size)?;
+                // the terminal picks up the new size on the next frame
+                render_loop::request_redraw();
+            }
+        }
+
+        let cursor = self
+            .cursor_position
+            .filter(|_| self.options.cursor.is_visible(clock::now()))
+            .filter(|position| self.buffer.area.contains(*position));
+        if cursor != self.drawn_cursor {
+            for position in self.drawn_cursor.into_iter().chain(cursor) {
+                self.mark_dirty(position.x, position.y);
+            }
+            self.drawn_cursor = cursor;
+        }
+
+        let Some(device) = self.device()? else {
+            return Ok(());
+        };
+        let renderer = match &mut self.renderer {
+            Some(renderer) => renderer,
+            None => {
+                let renderer = Renderer::new(
+                    &device,
+                    &self.canvas,
+                    &self.options,
+                    self.metrics,
+                    self.buffer.area.as_size(),
+                )?;
+                self.dirty = Some(0..self.buffer.content.len());
+                self.renderer.insert(renderer)
+            }
+        };
+
+        if let Some(dirty) = self.dirty.take() {
+            let cursor = cursor.map(|position| {
+                (
+                    self.buffer.index_of(position.x, position.y),
+                    &self.options.cursor,
+                )
+            });
+            renderer.upload(&self.buffer, dirty, cursor)?;
+            renderer.render(self.buffer.content.len())?;
+        }
+
+        // images can't be drawn with the glyphs, they are placed over the canvas
+        let mut images = std::mem::take(&mut self.images);
+        let result = images.update(|| Some(self.geometry()));
+        self.images = images;
+        result?;
+
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> IoResult<()> {
+        self.cursor_position = None;
+        Ok(())
+    }
+
+    fn show_cursor(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+
+    fn clear(&mut self) -> IoResult<()> {
+        self.buffer.reset();
+        self.dirty = Some(0..self.buffer.content.len());
+        Ok(())
+    }
+
+    fn size(&self) -> IoResult<Size> {
+        Ok(self.buffer.area.as_size())
+    }
+
+    fn window_size(&mut self) -> IoResult<WindowSize> {
+        let (width, height) = self.size;
+        Ok(WindowSize {
+            columns_rows: self.buffer.area.as_size(),
+            pixels: Size::new(width as _, height as _),
+        })
+    }
+
+    fn get_cursor_position(&mut self) -> IoResult<Position> {
+        match self.cursor_position {
+            None => Ok((0, 0).into()),
+            Some(position) => Ok(position),
+        }
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> IoResult<()> {
+        self.cursor_position = Some(position.into());
+        Ok(())
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> Result<(), Self::Error> {
+        match clear_type {
+            ClearType::All => self.clear(),
+            _ => Err(IoError::other("unimplemented")),
+        }
+    }
+}
+
+impl WebBackend for WgpuBackend {
+    fn cell_at(&self, x: f64, y: f64) -> Option<Position> {
+        self.geometry().cell_at(x, y)
+    }
+
+    fn cell_rect(&self, column: u16, row: u16) -> Option<CellRect> {
+        self.geometry().cell_rect(column, row)
+    }
+
+    fn cell_metrics(&self) -> CellMetrics {
+        self.metrics
+    }
+
+    fn current_buffer(&self) -> Buffer {
+        self.buffer.clone()
+    }
+
+    fn memory_stats(&self) -> MemoryStats {
+        let cells = self.buffer.content.len();
+        MemoryStats {
+            cells,
+            buffer_bytes: buffer_bytes(cells),
+            style_cache_entries: 0,
+            distinct_glyphs: distinct_glyphs(&self.buffer.content),
+            dom_nodes: None,
+        }
+    }
+}
+
+impl WgpuBackend {
+    /// Returns the geometry of the grid on the page.
+    fn geometry(&self) -> GridGeometry {
+        measure_grid(
+            &self.canvas,
+            self.metrics.cell_size(),
+            self.buffer.area.as_size(),
+        )
+    }
+
+    /// Translates the mouse events to the cells of this backend.
+    fn set_grid(&self) {
+        let canvas = self.canvas.clone();
+        let (cell_size, size) = (self.metrics.cell_size(), self.buffer.area.as_size());
+        web::set_grid(move || Some(measure_grid(&canvas, cell_size, size)));
+    }
+}
+
+/// GPU resources of the [`WgpuBackend`] for a size of the canvas.
+#[derive(Debug)]
+struct Renderer {
+    /// GPU device.
+    device: GpuDevice,
+    /// Queue of the device.
+    queue: GpuQueue,
+    /// WebGPU context of the canvas.
+    context: GpuCanvasContext,
+    /// Pipeline drawing the cells.
+    pipeline: GpuRenderPipeline,
+    /// Cells, see [`encode_cell`].
+    cells: GpuBuffer,
+    /// Bindings of the uniforms, the cells and the atlas.
+    bind_group: GpuBindGroup,
+    /// Glyphs of the cells.
+    atlas: GlyphAtlas,
+}
+
+impl Renderer {
+    /// Creates the resources to draw a grid of the given size.
+    fn new(
+        device: &GpuDevice,
+        canvas: &HtmlCanvasElement,
+        options: &WgpuBackendOptions,
+        metrics: CellMetrics,
+        size: Size,
+    ) -> Result<Self, Error> {
+        let queue = device.queue();
+        let context = canvas
+            .get_context("webgpu")?
+            .ok_or(Error::UnableToRetrieveCanvasContext)?
+            .unchecked_into::<GpuCanvasContext>();
+        let format = gpu()?.get_preferred_canvas_format();
+        let configuration = GpuCanvasConfiguration::new(device, format);
+        configuration.set_alpha_mode(GpuCanvasAlphaMode::Opaque);
+        context.configure(&configuration)?;
+
+        let module = device.create_shader_module(&GpuShaderModuleDescriptor::new(SHADER));
+        let vertex = GpuVertexState::new(&module);
+        vertex.set_entry_point("vs_main");
+        let fragment =
+            GpuFragmentState::new(&module, &Array::of1(&GpuColorTargetState::new(format)));
+        fragment.set_entry_point("fs_main");
+        let primitive = GpuPrimitiveState::new();
+        primitive.set_topology(GpuPrimitiveTopology::TriangleStrip);
+        let descriptor = GpuRenderPipelineDescriptor::new(&JsValue::from_str("auto"), &vertex);
+        descriptor.set_fragment(&fragment);
+        descriptor.set_primitive(&primitive);
+        let pipeline = device.create_render_pipeline(&descriptor)?;
+
+        let (physical_width, physical_height) = metrics.physical_size();
+        let cell = (
+            physical_width.round() as u32,
+            physical_height.round() as u32,
+        );
+        let atlas = GlyphAtlas::new(device, options, cell)?;
+
+        let uniforms = device.create_buffer(&GpuBufferDescriptor::new(
+            32.0,
+            gpu_buffer_usage::UNIFORM | gpu_buffer_usage::COPY_DST,
+        ))?;
+        let data = uniform_bytes(
+            size,
+            cell,
+            (canvas.width(), canvas.height()),
+            atlas.columns(),
+        );
+        queue.write_buffer_with_u32_and_u8_slice(&uniforms, 0, &data)?;
+
+        // a binding can't be empty
+        let cell_count = (size.width as usize * size.height as usize).max(1);
+        let cells = device.create_buffer(&GpuBufferDescriptor::new(
+            (cell_count * CELL_BYTES) as f64,
+            gpu_buffer_usage::STORAGE | gpu_buffer_usage::COPY_DST,
+        ))?;
+
+        let entries = Array::of3(
+            &GpuBindGroupEntry::new(0, &GpuBufferBinding::new(&uniforms)),
+            &GpuBindGroupEntry::new(1, &GpuBufferBinding::new(&cells)),
+            &GpuBindGroupEntry::new(2, &atlas.texture.create_view()?.into()),
+        );
+        let bind_group = device.create_bind_group(&GpuBindGroupDescriptor::new(
+            &entries,
+            &pipeline.get_bind_group_layout(0),
+        ));
+
+        Ok(Self {
+            device: device.clone(),
+            queue,
+            context,
+            pipeline,
+            cells,
+            bind_group,
+            atlas,
+        })
+    }
+
+    /// Uploads the given range of cells, rasterizing their new glyphs.
+    ///
+    /// The cursor is drawn over the cell at the given index, if any. When
+    /// the atlas is full, it is cleared and all the cells are uploaded
+    /// again, so only the glyphs on screen are kept.
+    fn upload(
+        &mut self,
+        buffer: &Buffer,
+        dirty: Range<usize>,
+        cursor: Option<(usize, &CursorOptions)>,
+    ) -> Result<(), Error> {
+        let mut cells = dirty;
+        let mut cleared = false;
+        let data = 'encode: loop {
+            let mut data = Vec::with_capacity(cells.len() * CELL_BYTES);
+            for index in cells.clone() {
+                let Some((cell, right_half)) = shown_cell(buffer, index, cursor) else {
+                    continue;
+                };
+                let slot = match self.atlas.slot(&self.queue, &cell)? {
+                    Some(slot) => slot,
+                    None if !cleared => {
+                        self.atlas.clear();
+                        cleared = true;
+                        cells = 0..buffer.content.len();
+                        continue 'encode;
+                    }
+                    // more distinct glyphs on screen than slots
+                    None => 0,
+                };
+                data.extend(
+                    encode_cell(&cell, slot, right_half)
+                        .iter()
+                        .flat_map(|value| value.to_le_bytes()),
+                );
+            }
+            break data;
+        };
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.queue.write_buffer_with_u32_and_u8_slice(
+            &self.cells,
+            (cells.start * CELL_BYTES) as u32,
+            &data,
+        )?;
+        Ok(())
+    }
+
+    /// Renders the given number of cells to the canvas.
+    fn render(&self, cell_count: usize) -> Result<(), Error> {
+        let view = self.context.get_current_texture()?.create_view()?;
+        let attachment =
+            GpuRenderPassColorAttachment::new(GpuLoadOp::Clear, GpuStoreOp::Store, &view);
+        attachment.set_clear_value(&GpuColorDict::new(1.0, 0.0, 0.0, 0.0));
+        let encoder = self.device.create_command_encoder();
+        let pass =
+            encoder.begin_render_pass(&GpuRenderPassDescriptor::new(&Array::of1(&attachment)))?;
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, Some(&self.bind_group));
+        // a quad per cell
+        pass.draw_with_instance_count(4, cell_count as u32);
+        pass.end();
+        self.queue.submit(&Array::of1(&encoder.finish()));
+        Ok(())
+    }
+}
+
+/// Style of a bold glyph in a [`GlyphKey`].
+const BOLD_STYLE: u8 = 1;
+
+/// Style of an italic glyph in a [`GlyphKey`].
+const ITALIC_STYLE: u8 = 1 << 1;
+
+/// A glyph of the atlas.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    /// Symbol of the cell.
+    symbol: CompactString,
+    /// Font variant, see [`BOLD_STYLE`] and [`ITALIC_STYLE`].
+    style: u8,
+}
+
+impl GlyphKey {
+    /// Returns the glyph of the given cell, or `None` if it is blank.
+    fn of(cell: &Cell) -> Option<Self> {
+        if cell.symbol().trim().is_empty() {
+            return None;
+        }
+        let mut style = 0;
+        if cell.modifier.contains(Modifier::BOLD) {
+            style |= BOLD_STYLE;
+        }
+        if cell.modifier.contains(Modifier::ITALIC) {
+            style |= ITALIC_STYLE;
+        }
+        Some(Self {
+            symbol: cell.symbol().into(),
+            style,
+        })
+    }
+}
+
+/// Slots of the glyph atlas.
+///
+/// The first slot is left empty for blank cells.
+#[derive(Debug)]
+struct AtlasSlots {
+    /// Slots of the glyphs in the atlas.
+    slots: HashMap<GlyphKey, u32>,
+    /// Number of slots of the atlas.
+    capacity: u32,
+}
+
+impl AtlasSlots {
+    /// Constructs new [`AtlasSlots`] with the given number of slots.
+    fn new(capacity: u32) -> Self {
+        Self {
+            slots: HashMap::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the slot of the given glyph and whether it was just added,
+    /// or `None` if the atlas is full.
+    fn get_or_insert(&mut self, key: &GlyphKey) -> Option<(u32, bool)> {
+        if let Some(slot) = self.slots.get(key) {
+            return Some((*slot, false));
+        }
+        let slot = self.slots.len() as u32 + 1;
+        if slot >= self.capacity {
+            return None;
+        }
+        self.slots.insert(key.clone(), slot);
+        Some((slot, true))
+    }
+
+    /// Removes all the glyphs.
+    fn clear(&mut self) {
+        self.slots.clear();
+    }
+}
+
+/// Glyphs rasterized with a 2D canvas into a texture.
+///
+/// The glyphs are in slots two cells wide, so wide glyphs fit.
+#[derive(Debug)]
+struct GlyphAtlas {
+    /// Texture of the glyphs.
+    texture: GpuTexture,
+    /// Canvas the glyphs are rasterized in, one at a time.
+    context: CanvasRenderingContext2d,
+    /// Fonts of each [`GlyphKey::style`].
+    fonts: [String; 4],
+    /// Size of a cell, in texels.
+    cell: (u32, u32),
+    /// Slots of the glyphs.
+    slots: AtlasSlots,
+}
+
+impl GlyphAtlas {
+    /// Constructs a new [`GlyphAtlas`] of the font of the options, for cells
+    /// of the given size in texels.
+    fn new(
+        device: &GpuDevice,
+        options: &WgpuBackendOptions,
+        cell: (u32, u32),
+    ) -> Result<Self, Error> {
+        let size = GpuExtent3dDict::new(ATLAS_SIZE);
+        size.set_height(ATLAS_SIZE);
+        let texture = device.create_texture(&GpuTextureDescriptor::new(
+            GpuTextureFormat::Rgba8unorm,
+            &size,
+            gpu_texture_usage::TEXTURE_BINDING | gpu_texture_usage::COPY_DST,
+        ))?;
+
+        let canvas = get_document()?
+            .create_element("canvas")?
+            .dyn_into::<HtmlCanvasElement>()
+            .map_err(|_| Error::UnableToRetrieveComponent("canvas"))?;
+        canvas.set_width(cell.0 * 2);
+        canvas.set_height(cell.1);
+        let context_options = Map::new();
+        context_options.set(
+            &JsValue::from_str("willReadFrequently"),
+            &Boolean::from(JsValue::TRUE),
+        );
+        let context = canvas
+            .get_context_with_context_options("2d", &context_options)?
+            .ok_or(Error::UnableToRetrieveCanvasContext)?
+            .unchecked_into::<CanvasRenderingContext2d>();
+        context.set_text_baseline("middle");
+        context.set_fill_style_str("#fff");
+
+        let font_size = options.font_size as f64 * device_pixel_ratio();
+        let capacity = (ATLAS_SIZE / (cell.0 * 2).max(1)) * (ATLAS_SIZE / cell.1.max(1));
+        Ok(Self {
+            texture,
+            context,
+            fonts: [0, 1, 2, 3].map(|style| options.font_css(style, font_size)),
+            cell,
+            slots: AtlasSlots::new(capacity),
+        })
+    }
+
+    /// Returns the number of slots in a row of the atlas.
+    fn columns(&self) -> u32 {
+        ATLAS_SIZE / (self.cell.0 * 2).max(1)
+    }
+
+    /// Returns the slot of the glyph of the given cell, rasterizing it if
+    /// needed, or `None` if the atlas is full.
+    fn slot(&mut self, queue: &GpuQueue, cell: &Cell) -> Result<Option<u32>, Error> {
+        let Some(key) = GlyphKey::of(cell) else {
+            return Ok(Some(0));
+        };
+        match self.slots.get_or_insert(&key) {
+            Some((slot, true)) => {
+                self.rasterize(queue, slot, &key)?;
+                Ok(Some(slot))
+            }
+            Some((slot, false)) => Ok(Some(slot)),
+            None => Ok(None),
+        }
+    }
+
+    /// Rasterizes the given glyph into the given slot.
+    fn rasterize(&self, queue: &GpuQueue, slot: u32, key: &GlyphKey) -> Result<(), Error> {
+        let (width, height) = (self.cell.0 * 2, self.cell.1);
+        self.context
+            .clear_rect(0.0, 0.0, width as f64, height as f64);
+        self.context.set_font(&self.fonts[key.style as usize]);
+        self.context
+            .fill_text(&key.symbol, 0.0, height as f64 / 2.0)?;
+        let image = self
+            .context
+            .get_image_data(0.0, 0.0, width as f64, height as f64)?;
+
+        let origin = GpuOrigin3dDict::new();
+        origin.set_x(slot % self.columns() * width);
+        origin.set_y(slot / self.columns() * height);
+        let destination = GpuTexelCopyTextureInfo::new(&self.texture);
+        destination.set_origin(&origin);
+        let layout = GpuTexelCopyBufferLayout::new();
+        layout.set_bytes_per_row(width * 4);
+        let size = GpuExtent3dDict::new(width);
+        size.set_height(height);
+        queue.write_texture_with_u8_slice_and_gpu_extent_3d_dict(
+            &destination,
+            &image.data(),
+            &layout,
+            &size,
+        )?;
+        Ok(())
+    }
+
+    /// Removes all the glyphs.
+    fn clear(&mut self) {
+        self.slots.clear();
+    }
+}
+
+/// Returns the cell shown at the given index of the buffer, with the cursor
+/// drawn over it, and whether it shows the right half of the wide glyph on
+/// its left.
+fn shown_cell(
+    buffer: &Buffer,
+    index: usize,
+    cursor: Option<(usize, &CursorOptions)>,
+) -> Option<(Cell, bool)> {
+    let width = buffer.area.width as usize;
+    let (index, right_half) = match index.checked_sub(1) {
+        Some(left)
+            if !index.is_multiple_of(width) && buffer.content[left].symbol().width() == 2 =>
+        {
+            (left, true)
+        }
+        _ => (index, false),
+    };
+    let cell = buffer.content.get(index)?;
+    let cell = match cursor {
+        Some((cursor, options)) if cursor == index => options.apply(cell),
+        _ => cell.clone(),
+    };
+    Some((cell, right_half))
+}
+
+/// Encodes a cell for the shader: the slot of its glyph, its foreground and
+/// background colors, and its flags.
+fn encode_cell(cell: &Cell, slot: u32, right_half: bool) -> [u32; 4] {
+    let mut fg = to_rgb(cell.fg, 0xffffff);
+    let mut bg = to_rgb(cell.bg, 0x000000);
+    if cell.modifier.contains(Modifier::REVERSED) {
+        swap(&mut fg, &mut bg);
+    }
+    let mut flags = 0;
+    if cell.modifier.contains(Modifier::UNDERLINED) {
+        flags |= UNDERLINE_FLAG;
+    }
+    if cell.modifier.contains(Modifier::CROSSED_OUT) {
+        flags |= STRIKETHROUGH_FLAG;
+    }
+    let glyph = if right_half { slot | RIGHT_HALF } else { slot };
+    [glyph, fg, bg, flags]
+}
+
+/// Encodes the uniforms of the shader: the size of the grid, of a cell and
+/// of the canvas, and the number of slots in a row of the atlas.
+fn uniform_bytes(
+    size: Size,
+    cell: (u32, u32),
+    viewport: (u32, u32),
+    atlas_columns: u32,
+) -> Vec<u8> {
+    [
+        size.width as u32,
+        size.height as u32,
+        (cell.0 as f32).to_bits(),
+        (cell.1 as f32).to_bits(),
+        (viewport.0 as f32).to_bits(),
+        (viewport.1 as f32).to_bits(),
+        atlas_columns,
+        0,
+    ]
+    .iter()
+    .flat_map(|value| value.to_le_bytes())
+    .collect()
+}
+
+/// Returns the CSS `font-family` of the given families, quoting the ones
+/// with spaces.
+fn css_font_family(families: &[String]) -> String {
+    families
+        .iter()
+        .map(|family| {
+            if family.contains(' ') {
+                format!("\"{family}\"")
+            } else {
+                family.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns the WebGPU entry point of the browser, or an error if it doesn't
+/// support WebGPU.
+fn gpu() -> Result<Gpu, Error> {
+    let gpu = get_window()?.navigator().gpu();
+    if gpu.is_undefined() {
+        return Err(Error::WebGpuError(
+            "WebGPU is not supported by the browser".to_string(),
+        ));
+    }
+    Ok(gpu)
+}
+
+/// Requests a GPU device.
+async fn request_device(gpu: &Gpu) -> Result<GpuDevice, JsValue> {
+    let adapter = JsFuture::from(gpu.request_adapter()).await?;
+    if adapter.is_null() {
+        return Err(JsValue::from_str("no WebGPU adapter is available"));
+    }
+    let device = JsFuture::from(adapter.unchecked_into::<GpuAdapter>().request_device()).await?;
+    Ok(device.unchecked_into())
+}
+
+/// Returns the message of a JavaScript error.
+fn js_error(error: &JsValue) -> String {
+    error
+        .as_string()
+        .or_else(|| {
+            error
+                .dyn_ref::<web_sys::js_sys::Error>()
+                .map(|error| error.message().into())
+        })
+        .unwrap_or_else(|| format!("{error:?}"))
+}
+
+/// Measures a grid drawn on the given canvas.
+fn measure_grid(canvas: &HtmlCanvasElement, cell_size: (f64, f64), size: Size) -> GridGeometry {
+    let canvas = canvas.get_bounding_client_rect();
+    GridGeometry {
+        origin: (canvas.left(), canvas.top()),
+        cell_size,
+        size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::{Color, Style};
+
+    use super::*;
+
+    #[test]
+    fn test_encode_cell() {
+        let mut cell = Cell::new("a");
+        cell.set_style(Style::new().red().on_blue().underlined());
+        assert_eq!(encode_cell(&cell, 3, false), [3, 0x800000, 0x000080, 1]);
+
+        cell.set_style(Style::new().reversed().crossed_out());
+        let [glyph, fg, bg, flags] = encode_cell(&cell, 3, true);
+        assert_eq!(glyph, 3 | RIGHT_HALF);
+        assert_eq!((fg, bg), (0x000080, 0x800000));
+        assert_eq!(flags, UNDERLINE_FLAG | STRIKETHROUGH_FLAG);
+
+        let reset = encode_cell(&Cell::new(" "), 0, false);
+        assert_eq!(reset, [0, 0xffffff, 0x000000, 0]);
+    }
+
+    #[test]
+    fn test_glyph_key() {
+        assert_eq!(GlyphKey::of(&Cell::new(" ")), None);
+        assert_eq!(GlyphKey::of(&Cell::new("")), None);
+        let mut cell = Cell::new("a");
+        cell.set_style(Style::new().bold().italic());
+        let key = GlyphKey::of(&cell).expect("failed to get the glyph");
+        assert_eq!(key.symbol, "a");
+        assert_eq!(key.style, BOLD_STYLE | ITALIC_STYLE);
+    }
+
+    #[test]
+    fn test_atlas_slots() {
+        let key = |symbol: &str| GlyphKey {
+            symbol: symbol.into(),
+            style: 0,
+        };
+        let mut slots = AtlasSlots::new(3);
+        // the first slot is for blank cells
+        assert_eq!(slots.get_or_insert(&key("a")), Some((1, true)));
+        assert_eq!(slots.get_or_insert(&key("b")), Some((2, true)));
+        assert_eq!(slots.get_or_insert(&key("a")), Some((1, false)));
+        assert_eq!(slots.get_or_insert(&key("c")), None);
+        slots.clear();
+        assert_eq!(slots.get_or_insert(&key("c")), Some((1, true)));
+    }
+
+    #[test]
+    fn test_shown_cell() {
+        let buffer = Buffer::with_lines(["a界b", "cdef"]);
+        let shown = |index| {
+            shown_cell(&buffer, index, None).map(|(cell, half)| (cell.symbol().to_string(), half))
+        };
+        assert_eq!(shown(1), Some(("界".to_string(), false)));
+        assert_eq!(shown(2), Some(("界".to_string(), true)));
+        assert_eq!(shown(3), Some(("b".to_string(), false)));
+        // the wide glyph doesn't wrap to the next row
+        assert_eq!(shown(4), Some(("c".to_string(), false)));
+        assert_eq!(shown(8), None);
+
+        let cursor = CursorOptions::new().color(Color::Red);
+        let (cell, _) = shown_cell(&buffer, 2, Some((1, &cursor))).expect("failed to get the cell");
+        assert_eq!(cell.bg, Color::Red);
+    }
+
+    #[test]
+    fn test_uniform_bytes() {
+        let bytes = uniform_bytes(Size::new(80, 24), (10, 19), (800, 456), 102);
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(bytes[0..4], 80u32.to_le_bytes());
+        assert_eq!(bytes[8..12], 10.0f32.to_le_bytes());
+        assert_eq!(bytes[24..28], 102u32.to_le_bytes());
+    }
+
+    #[test]
+    fn test_css_font_family() {
+        let families = ["Fira Code".to_string(), "monospace".to_string()];
+        assert_eq!(css_font_family(&families), "\"Fira Code\", monospace");
+    }
+}
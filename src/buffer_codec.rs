@@ -0,0 +1,121 @@
+use std::str::FromStr;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+};
+
+/// Separates the records of an encoded buffer.
+const RECORD_SEPARATOR: char = '\u{1e}';
+
+/// Separates the fields of a record.
+pub(crate) const FIELD_SEPARATOR: char = '\u{1f}';
+
+/// Escapes the separators in the symbols of a buffer.
+const ESCAPE: char = '\\';
+
+/// Largest number of cells of a decoded buffer, so a corrupted buffer can't
+/// allocate a huge one.
+const MAX_CELLS: u32 = u16::MAX as u32;
+
+/// Encodes a buffer.
+pub(crate) fn encode_buffer(buffer: &Buffer) -> String {
+    let mut encoded = format!("{},{}", buffer.area.width, buffer.area.height);
+    for cell in &buffer.content {
+        encoded.push(RECORD_SEPARATOR);
+        escape_symbol(cell.symbol(), &mut encoded);
+        encoded.push(FIELD_SEPARATOR);
+        encoded.push_str(&cell.fg.to_string());
+        encoded.push(FIELD_SEPARATOR);
+        encoded.push_str(&cell.bg.to_string());
+        encoded.push(FIELD_SEPARATOR);
+        encoded.push_str(&cell.modifier.bits().to_string());
+    }
+    encoded
+}
+
+/// Decodes a buffer encoded with [`encode_buffer`].
+///
+/// Returns `None` if the buffer is too large or doesn't have a record for
+/// each of its cells.
+pub(crate) fn decode_buffer(encoded: &str) -> Option<Buffer> {
+    let mut records = encoded.split(RECORD_SEPARATOR);
+    let (width, height) = records.next()?.split_once(',')?;
+    let area = Rect::new(0, 0, width.parse().ok()?, height.parse().ok()?);
+    if area.area() > MAX_CELLS || records.clone().count() != area.area() as usize {
+        return None;
+    }
+    let mut buffer = Buffer::empty(area);
+    for (cell, record) in buffer.content.iter_mut().zip(records) {
+        let mut fields = record.split(FIELD_SEPARATOR);
+        cell.set_symbol(&unescape_symbol(fields.next()?)?);
+        cell.fg = Color::from_str(fields.next()?).ok()?;
+        cell.bg = Color::from_str(fields.next()?).ok()?;
+        cell.modifier = Modifier::from_bits_truncate(fields.next()?.parse().ok()?);
+    }
+    Some(buffer)
+}
+
+/// Appends a symbol to an encoded buffer, escaping the separators.
+fn escape_symbol(symbol: &str, encoded: &mut String) {
+    for c in symbol.chars() {
+        match c {
+            ESCAPE => encoded.push_str("\\\\"),
+            RECORD_SEPARATOR => encoded.push_str("\\r"),
+            FIELD_SEPARATOR => encoded.push_str("\\f"),
+            c => encoded.push(c),
+        }
+    }
+}
+
+/// Unescapes a symbol escaped with [`escape_symbol`].
+fn unescape_symbol(escaped: &str) -> Option<String> {
+    let mut symbol = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != ESCAPE {
+            symbol.push(c);
+            continue;
+        }
+        symbol.push(match chars.next()? {
+            ESCAPE => ESCAPE,
+            'r' => RECORD_SEPARATOR,
+            'f' => FIELD_SEPARATOR,
+            _ => return None,
+        });
+    }
+    Some(symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_roundtrip() {
+        let mut buffer = Buffer::with_lines(["ab", "cd"]);
+        buffer[(1, 0)].set_style(ratatui::style::Style::new().red().on_blue().bold());
+        buffer[(0, 1)].set_fg(Color::Rgb(1, 2, 3));
+        buffer[(1, 1)].set_fg(Color::Indexed(42));
+        assert_eq!(decode_buffer(&encode_buffer(&buffer)), Some(buffer));
+    }
+
+    #[test]
+    fn test_buffer_roundtrip_separators() {
+        let mut buffer = Buffer::with_lines(["abc"]);
+        buffer[(0, 0)].set_symbol("\u{1e}");
+        buffer[(1, 0)].set_symbol("\u{1f}");
+        buffer[(2, 0)].set_symbol("\\r");
+        assert_eq!(decode_buffer(&encode_buffer(&buffer)), Some(buffer));
+    }
+
+    #[test]
+    fn test_decode_invalid_size() {
+        assert_eq!(decode_buffer("65535,65535"), None);
+        let encoded = encode_buffer(&Buffer::with_lines(["ab", "cd"]));
+        let (truncated, _) = encoded.rsplit_once(RECORD_SEPARATOR).expect("four records");
+        assert_eq!(decode_buffer(truncated), None);
+        assert_eq!(decode_buffer(&format!("{encoded}{RECORD_SEPARATOR}")), None);
+    }
+}
@@ -0,0 +1,125 @@
+//! ## Clock
+//!
+//! The time source of ratzilla: the render loop, the [effects], the
+//! [screen transitions], the animated widgets and the timers of the
+//! [leader election] all read the time with [`now`].
+//!
+//! By default this is [`SystemClock`], i.e. `performance.now()`. Replacing
+//! it with a [`ManualClock`] makes the time advance only when told to, for
+//! deterministic animation tests and reproducible recordings:
+//!
+//! ```rust
+//! use ratzilla::clock::{now, set_clock, ManualClock};
+//!
+//! let clock = ManualClock::new();
+//! set_clock(clock.clone());
+//! assert_eq!(now(), 0.0);
+//! clock.advance(16.0);
+//! assert_eq!(now(), 16.0);
+//! ```
+//!
+//! The clock only measures elapsed time. Wall clock times, such as the
+//! expiry of authentication tokens, don't use it.
+//!
+//! [effects]: https://docs.rs/ratzilla/latest/ratzilla/fx/index.html
+//! [screen transitions]: crate::screen
+//! [leader election]: crate::sync::leader
+//! [`ManualClock`]: crate::clock::ManualClock
+//! [`SystemClock`]: crate::clock::SystemClock
+//! [`now`]: crate::clock::now
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use crate::backend::utils::performance;
+
+/// A source of time, in milliseconds.
+pub trait Clock {
+    /// Returns the current time, in milliseconds.
+    ///
+    /// Only the difference between two times is meaningful.
+    fn now(&self) -> f64;
+}
+
+/// The clock of the browser, i.e. `performance.now()`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> f64 {
+        performance().map(|p| p.now()).unwrap_or_default()
+    }
+}
+
+/// A clock that only advances when told to.
+///
+/// Cloning the clock returns a handle to the same time.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    /// Current time, in milliseconds.
+    now: Rc<Cell<f64>>,
+}
+
+impl ManualClock {
+    /// Constructs a new [`ManualClock`] starting at 0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances the clock by the given number of milliseconds.
+    pub fn advance(&self, ms: f64) {
+        self.now.set(self.now.get() + ms);
+    }
+
+    /// Sets the current time, in milliseconds.
+    pub fn set(&self, ms: f64) {
+        self.now.set(ms);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> f64 {
+        self.now.get()
+    }
+}
+
+thread_local! {
+    static CLOCK: RefCell<Option<Rc<dyn Clock>>> = RefCell::new(None);
+}
+
+/// Replaces the clock used by ratzilla.
+pub fn set_clock<C: Clock + 'static>(clock: C) {
+    CLOCK.with(|c| *c.borrow_mut() = Some(Rc::new(clock)));
+}
+
+/// Restores the [`SystemClock`].
+pub fn reset_clock() {
+    CLOCK.with(|c| *c.borrow_mut() = None);
+}
+
+/// Returns the current time of the clock, in milliseconds.
+pub fn now() -> f64 {
+    // the clock may read other thread locals, so don't hold the borrow
+    match CLOCK.with(|c| c.borrow().clone()) {
+        Some(clock) => clock.now(),
+        None => SystemClock.now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = ManualClock::new();
+        set_clock(clock.clone());
+        clock.set(100.0);
+        clock.advance(16.5);
+        assert_eq!(now(), 116.5);
+        reset_clock();
+        assert_eq!(clock.now(), 116.5);
+    }
+}
@@ -0,0 +1,18 @@
+//! ## Compatibility
+//!
+//! Conversions between the [events](crate::event) of ratzilla and the events
+//! of the terminal libraries used by native ratatui apps, so that an app
+//! crate targeting both the terminal and the web can share its input
+//! handling.
+//!
+//! Each library is enabled with the feature of the same name, crossterm
+//! also with `crossterm-compat`.
+
+#[cfg(feature = "crossterm")]
+mod crossterm;
+
+#[cfg(feature = "termion")]
+mod termion;
+
+#[cfg(feature = "termwiz")]
+mod termwiz;
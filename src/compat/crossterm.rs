@@ -0,0 +1,331 @@
+//! Conversions from and to [crossterm] events.
+//!
+//! Key events convert to [`KeyEventKind::Press`], or [`KeyEventKind::Repeat`]
+//! while a key is held down. Releases, reported by [`on_key_release`], are
+//! [`KeyEventKind::Release`] events of an [event stream].
+//!
+//! Mouse, wheel and [input events](InputEvent) convert to crossterm events
+//! with [`TryFrom`], which gives the event back if it has no equivalent:
+//! clicks, which crossterm reports as separate presses and releases, and
+//! events outside of the grid.
+//!
+//! [crossterm]: https://docs.rs/crossterm
+//! [`on_key_release`]: crate::WebRenderer::on_key_release
+//! [event stream]: crate::WebRenderer::event_stream
+
+use crossterm::event::{
+    Event as CtEvent, KeyCode as CtKeyCode, KeyEvent as CtKeyEvent, KeyEventKind, KeyEventState,
+    KeyModifiers, MouseButton as CtMouseButton, MouseEvent as CtMouseEvent,
+    MouseEventKind as CtMouseEventKind,
+};
+
+use crate::event::{
+    InputEvent, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind, WheelEvent,
+};
+
+/// Convert a [`KeyCode`] to a crossterm [`KeyCode`](CtKeyCode).
+impl From<KeyCode> for CtKeyCode {
+    fn from(code: KeyCode) -> Self {
+        match code {
+            KeyCode::Char(c) => CtKeyCode::Char(c),
+            KeyCode::F(n) => CtKeyCode::F(n),
+            KeyCode::Backspace => CtKeyCode::Backspace,
+            KeyCode::Enter => CtKeyCode::Enter,
+            KeyCode::Left => CtKeyCode::Left,
+            KeyCode::Right => CtKeyCode::Right,
+            KeyCode::Up => CtKeyCode::Up,
+            KeyCode::Down => CtKeyCode::Down,
+            KeyCode::Tab => CtKeyCode::Tab,
+            KeyCode::Delete => CtKeyCode::Delete,
+            KeyCode::Home => CtKeyCode::Home,
+            KeyCode::End => CtKeyCode::End,
+            KeyCode::PageUp => CtKeyCode::PageUp,
+            KeyCode::PageDown => CtKeyCode::PageDown,
+            KeyCode::Esc => CtKeyCode::Esc,
+            KeyCode::Unidentified => CtKeyCode::Null,
+        }
+    }
+}
+
+/// Convert a crossterm [`KeyCode`](CtKeyCode) to a [`KeyCode`].
+///
+/// Keys that have no equivalent are [`KeyCode::Unidentified`].
+impl From<CtKeyCode> for KeyCode {
+    fn from(code: CtKeyCode) -> Self {
+        match code {
+            CtKeyCode::Char(c) => KeyCode::Char(c),
+            CtKeyCode::F(n) => KeyCode::F(n),
+            CtKeyCode::Backspace => KeyCode::Backspace,
+            CtKeyCode::Enter => KeyCode::Enter,
+            CtKeyCode::Left => KeyCode::Left,
+            CtKeyCode::Right => KeyCode::Right,
+            CtKeyCode::Up => KeyCode::Up,
+            CtKeyCode::Down => KeyCode::Down,
+            CtKeyCode::Tab | CtKeyCode::BackTab => KeyCode::Tab,
+            CtKeyCode::Delete => KeyCode::Delete,
+            CtKeyCode::Home => KeyCode::Home,
+            CtKeyCode::End => KeyCode::End,
+            CtKeyCode::PageUp => KeyCode::PageUp,
+            CtKeyCode::PageDown => KeyCode::PageDown,
+            CtKeyCode::Esc => KeyCode::Esc,
+            _ => KeyCode::Unidentified,
+        }
+    }
+}
+
+/// Convert a [`KeyEvent`] to a crossterm [`KeyEvent`](CtKeyEvent).
+impl From<KeyEvent> for CtKeyEvent {
+    fn from(event: KeyEvent) -> Self {
+        // crossterm reports shift+tab as a distinct key
+        let code = match event.code {
+            KeyCode::Tab if event.shift => CtKeyCode::BackTab,
+            code => code.into(),
+        };
+        let mut modifiers = modifiers(event.ctrl, event.alt, event.shift);
+        modifiers.set(KeyModifiers::SUPER, event.meta);
+        CtKeyEvent {
+            code,
+            modifiers,
+            kind: if event.repeat {
+                KeyEventKind::Repeat
+            } else {
+                KeyEventKind::Press
+            },
+            state: KeyEventState::NONE,
+        }
+    }
+}
+
+/// Convert a crossterm [`KeyEvent`](CtKeyEvent) to a [`KeyEvent`].
+///
+/// The state of the event is dropped, and releases are converted like
+/// presses.
+impl From<CtKeyEvent> for KeyEvent {
+    fn from(event: CtKeyEvent) -> Self {
+        KeyEvent {
+            code: event.code.into(),
+            ctrl: event.modifiers.contains(KeyModifiers::CONTROL),
+            alt: event.modifiers.contains(KeyModifiers::ALT),
+            shift: event.modifiers.contains(KeyModifiers::SHIFT)
+                || event.code == CtKeyCode::BackTab,
+            meta: event.modifiers.contains(KeyModifiers::SUPER),
+            repeat: event.kind == KeyEventKind::Repeat,
+        }
+    }
+}
+
+/// Returns the crossterm modifiers of the pressed modifier keys.
+fn modifiers(ctrl: bool, alt: bool, shift: bool) -> KeyModifiers {
+    let mut modifiers = KeyModifiers::NONE;
+    modifiers.set(KeyModifiers::CONTROL, ctrl);
+    modifiers.set(KeyModifiers::ALT, alt);
+    modifiers.set(KeyModifiers::SHIFT, shift);
+    modifiers
+}
+
+/// Convert a [`MouseButton`] to a crossterm [`MouseButton`](CtMouseButton).
+///
+/// The back, forward and unidentified buttons have no equivalent.
+impl TryFrom<MouseButton> for CtMouseButton {
+    type Error = MouseButton;
+
+    fn try_from(button: MouseButton) -> Result<Self, Self::Error> {
+        match button {
+            MouseButton::Left => Ok(CtMouseButton::Left),
+            MouseButton::Right => Ok(CtMouseButton::Right),
+            MouseButton::Middle => Ok(CtMouseButton::Middle),
+            button => Err(button),
+        }
+    }
+}
+
+/// Convert a [`MouseEvent`] to a crossterm [`MouseEvent`](CtMouseEvent).
+///
+/// The position is the [cell](MouseEvent::cell) under the mouse.
+impl TryFrom<MouseEvent> for CtMouseEvent {
+    type Error = MouseEvent;
+
+    fn try_from(event: MouseEvent) -> Result<Self, Self::Error> {
+        let Some(cell) = event.cell else {
+            return Err(event);
+        };
+        let button = CtMouseButton::try_from(event.button.clone());
+        let kind = match (&event.event, button) {
+            (MouseEventKind::Moved, _) => CtMouseEventKind::Moved,
+            (MouseEventKind::Dragged, Ok(button)) => CtMouseEventKind::Drag(button),
+            (MouseEventKind::Pressed, Ok(button)) => CtMouseEventKind::Down(button),
+            (MouseEventKind::Released, Ok(button)) => CtMouseEventKind::Up(button),
+            _ => return Err(event),
+        };
+        Ok(CtMouseEvent {
+            kind,
+            column: cell.x,
+            row: cell.y,
+            modifiers: modifiers(event.ctrl, event.alt, event.shift),
+        })
+    }
+}
+
+/// Convert a [`WheelEvent`] to a crossterm [`MouseEvent`](CtMouseEvent).
+///
+/// crossterm reports a scroll event per line, while a wheel event can
+/// scroll several rows or columns at once: only the direction is kept, with
+/// vertical scrolling first.
+impl TryFrom<WheelEvent> for CtMouseEvent {
+    type Error = WheelEvent;
+
+    fn try_from(event: WheelEvent) -> Result<Self, Self::Error> {
+        let kind = match (event.delta_rows.signum(), event.delta_cols.signum()) {
+            (1, _) => CtMouseEventKind::ScrollDown,
+            (-1, _) => CtMouseEventKind::ScrollUp,
+            (_, 1) => CtMouseEventKind::ScrollRight,
+            (_, -1) => CtMouseEventKind::ScrollLeft,
+            _ => return Err(event),
+        };
+        let Some(position) = event.position else {
+            return Err(event);
+        };
+        Ok(CtMouseEvent {
+            kind,
+            column: position.x,
+            row: position.y,
+            modifiers: modifiers(event.ctrl, event.alt, event.shift),
+        })
+    }
+}
+
+/// Convert an [`InputEvent`] to a crossterm [`Event`](CtEvent).
+impl TryFrom<InputEvent> for CtEvent {
+    type Error = InputEvent;
+
+    fn try_from(event: InputEvent) -> Result<Self, Self::Error> {
+        match event {
+            InputEvent::Key(event) => Ok(CtEvent::Key(event.into())),
+            InputEvent::Mouse(event) => CtMouseEvent::try_from(event)
+                .map(CtEvent::Mouse)
+                .map_err(InputEvent::Mouse),
+            InputEvent::Wheel(event) => CtMouseEvent::try_from(event)
+                .map(CtEvent::Mouse)
+                .map_err(InputEvent::Wheel),
+        }
+    }
+}
+
+/// Convert an [`Event`] of an event stream to a crossterm [`Event`](CtEvent).
+///
+/// [`Event`]: crate::event_stream::Event
+#[cfg(feature = "event-stream")]
+impl TryFrom<crate::event_stream::Event> for CtEvent {
+    type Error = crate::event_stream::Event;
+
+    fn try_from(event: crate::event_stream::Event) -> Result<Self, Self::Error> {
+        use crate::event_stream::Event;
+        match event {
+            Event::Key(event) => Ok(CtEvent::Key(event.into())),
+            Event::KeyRelease(event) => Ok(CtEvent::Key(CtKeyEvent {
+                kind: KeyEventKind::Release,
+                ..event.into()
+            })),
+            Event::Mouse(event) => CtMouseEvent::try_from(event)
+                .map(CtEvent::Mouse)
+                .map_err(Event::Mouse),
+            Event::Wheel(event) => CtMouseEvent::try_from(event)
+                .map(CtEvent::Mouse)
+                .map_err(Event::Wheel),
+            Event::Resize(size) => Ok(CtEvent::Resize(size.width, size.height)),
+            Event::Paste(text) => Ok(CtEvent::Paste(text)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::layout::Position;
+
+    use super::*;
+
+    #[test]
+    fn test_key_event_round_trip() {
+        let event = KeyEvent {
+            code: KeyCode::Char('S'),
+            ctrl: true,
+            alt: false,
+            shift: true,
+            meta: false,
+            repeat: false,
+        };
+        let converted = CtKeyEvent::from(event.clone());
+        assert_eq!(converted.code, CtKeyCode::Char('S'));
+        assert_eq!(
+            converted.modifiers,
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT
+        );
+        assert_eq!(converted.kind, KeyEventKind::Press);
+        assert_eq!(KeyEvent::from(converted), event);
+    }
+
+    #[test]
+    fn test_back_tab() {
+        let event = KeyEvent {
+            code: KeyCode::Tab,
+            ctrl: false,
+            alt: false,
+            shift: true,
+            meta: false,
+            repeat: false,
+        };
+        let converted = CtKeyEvent::from(event.clone());
+        assert_eq!(converted.code, CtKeyCode::BackTab);
+        assert_eq!(KeyEvent::from(converted), event);
+        assert_eq!(KeyCode::from(CtKeyCode::Insert), KeyCode::Unidentified);
+    }
+
+    #[test]
+    fn test_mouse_event() {
+        let event = MouseEvent {
+            button: MouseButton::Left,
+            event: MouseEventKind::Pressed,
+            x: 100,
+            y: 40,
+            cell: Some(Position::new(10, 2)),
+            ctrl: false,
+            alt: true,
+            shift: false,
+        };
+        let converted = CtEvent::try_from(InputEvent::Mouse(event.clone()));
+        assert_eq!(
+            converted,
+            Ok(CtEvent::Mouse(CtMouseEvent {
+                kind: CtMouseEventKind::Down(CtMouseButton::Left),
+                column: 10,
+                row: 2,
+                modifiers: KeyModifiers::ALT,
+            }))
+        );
+
+        let clicked = MouseEvent {
+            event: MouseEventKind::Clicked,
+            ..event.clone()
+        };
+        assert_eq!(CtMouseEvent::try_from(clicked.clone()), Err(clicked));
+        let outside = MouseEvent {
+            cell: None,
+            ..event
+        };
+        assert_eq!(CtMouseEvent::try_from(outside.clone()), Err(outside));
+    }
+
+    #[test]
+    fn test_wheel_event() {
+        let event = WheelEvent {
+            delta_rows: -3,
+            delta_cols: 0,
+            position: Some(Position::new(1, 1)),
+            ctrl: false,
+            alt: false,
+            shift: false,
+        };
+        let converted = CtMouseEvent::try_from(event).map(|event| event.kind);
+        assert_eq!(converted, Ok(CtMouseEventKind::ScrollUp));
+    }
+}
@@ -0,0 +1,146 @@
+//! Conversions from and to [termion] keys.
+//!
+//! termion encodes the modifiers in the [`Key`] itself and only for some
+//! keys, e.g. [`Key::Ctrl`] for characters or [`Key::CtrlLeft`] for arrows.
+//! Modifiers termion can't represent are dropped.
+//!
+//! [termion]: https://docs.rs/termion
+
+use termion::event::Key;
+
+use crate::event::{KeyCode, KeyEvent};
+
+/// Convert a [`KeyEvent`] to a termion [`Key`].
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        let KeyEvent {
+            code,
+            ctrl,
+            alt,
+            shift,
+            ..
+        } = event;
+        match code {
+            KeyCode::Char(c) if ctrl => Key::Ctrl(c),
+            KeyCode::Char(c) if alt => Key::Alt(c),
+            KeyCode::Char(c) => Key::Char(c),
+            KeyCode::Enter => Key::Char('\n'),
+            KeyCode::Tab if shift => Key::BackTab,
+            KeyCode::Tab => Key::Char('\t'),
+            KeyCode::Left if ctrl => Key::CtrlLeft,
+            KeyCode::Left if alt => Key::AltLeft,
+            KeyCode::Left if shift => Key::ShiftLeft,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right if ctrl => Key::CtrlRight,
+            KeyCode::Right if alt => Key::AltRight,
+            KeyCode::Right if shift => Key::ShiftRight,
+            KeyCode::Right => Key::Right,
+            KeyCode::Up if ctrl => Key::CtrlUp,
+            KeyCode::Up if alt => Key::AltUp,
+            KeyCode::Up if shift => Key::ShiftUp,
+            KeyCode::Up => Key::Up,
+            KeyCode::Down if ctrl => Key::CtrlDown,
+            KeyCode::Down if alt => Key::AltDown,
+            KeyCode::Down if shift => Key::ShiftDown,
+            KeyCode::Down => Key::Down,
+            KeyCode::Home if ctrl => Key::CtrlHome,
+            KeyCode::Home => Key::Home,
+            KeyCode::End if ctrl => Key::CtrlEnd,
+            KeyCode::End => Key::End,
+            KeyCode::F(n) => Key::F(n),
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Delete => Key::Delete,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::PageDown => Key::PageDown,
+            KeyCode::Esc => Key::Esc,
+            KeyCode::Unidentified => Key::Null,
+        }
+    }
+}
+
+/// Convert a termion [`Key`] to a [`KeyEvent`].
+///
+/// Keys that have no equivalent are [`KeyCode::Unidentified`].
+impl From<Key> for KeyEvent {
+    fn from(key: Key) -> Self {
+        let (code, ctrl, alt, shift) = match key {
+            Key::Char('\n') => (KeyCode::Enter, false, false, false),
+            Key::Char('\t') => (KeyCode::Tab, false, false, false),
+            Key::Char(c) => (KeyCode::Char(c), false, false, false),
+            Key::Ctrl(c) => (KeyCode::Char(c), true, false, false),
+            Key::Alt(c) => (KeyCode::Char(c), false, true, false),
+            Key::BackTab => (KeyCode::Tab, false, false, true),
+            Key::Left => (KeyCode::Left, false, false, false),
+            Key::CtrlLeft => (KeyCode::Left, true, false, false),
+            Key::AltLeft => (KeyCode::Left, false, true, false),
+            Key::ShiftLeft => (KeyCode::Left, false, false, true),
+            Key::Right => (KeyCode::Right, false, false, false),
+            Key::CtrlRight => (KeyCode::Right, true, false, false),
+            Key::AltRight => (KeyCode::Right, false, true, false),
+            Key::ShiftRight => (KeyCode::Right, false, false, true),
+            Key::Up => (KeyCode::Up, false, false, false),
+            Key::CtrlUp => (KeyCode::Up, true, false, false),
+            Key::AltUp => (KeyCode::Up, false, true, false),
+            Key::ShiftUp => (KeyCode::Up, false, false, true),
+            Key::Down => (KeyCode::Down, false, false, false),
+            Key::CtrlDown => (KeyCode::Down, true, false, false),
+            Key::AltDown => (KeyCode::Down, false, true, false),
+            Key::ShiftDown => (KeyCode::Down, false, false, true),
+            Key::Home => (KeyCode::Home, false, false, false),
+            Key::CtrlHome => (KeyCode::Home, true, false, false),
+            Key::End => (KeyCode::End, false, false, false),
+            Key::CtrlEnd => (KeyCode::End, true, false, false),
+            Key::F(n) => (KeyCode::F(n), false, false, false),
+            Key::Backspace => (KeyCode::Backspace, false, false, false),
+            Key::Delete => (KeyCode::Delete, false, false, false),
+            Key::PageUp => (KeyCode::PageUp, false, false, false),
+            Key::PageDown => (KeyCode::PageDown, false, false, false),
+            Key::Esc => (KeyCode::Esc, false, false, false),
+            _ => (KeyCode::Unidentified, false, false, false),
+        };
+        KeyEvent {
+            code,
+            ctrl,
+            alt,
+            shift,
+            meta: false,
+            repeat: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(code: KeyCode, ctrl: bool, alt: bool, shift: bool) -> KeyEvent {
+        KeyEvent {
+            code,
+            ctrl,
+            alt,
+            shift,
+            meta: false,
+            repeat: false,
+        }
+    }
+
+    #[test]
+    fn test_key_round_trip() {
+        for event in [
+            event(KeyCode::Char('a'), true, false, false),
+            event(KeyCode::Char('x'), false, true, false),
+            event(KeyCode::Enter, false, false, false),
+            event(KeyCode::Tab, false, false, true),
+            event(KeyCode::Left, true, false, false),
+            event(KeyCode::Down, false, false, true),
+            event(KeyCode::F(5), false, false, false),
+            event(KeyCode::Unidentified, false, false, false),
+        ] {
+            assert_eq!(KeyEvent::from(Key::from(event.clone())), event);
+        }
+        assert_eq!(
+            Key::from(event(KeyCode::Enter, false, false, false)),
+            Key::Char('\n')
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Conversions from and to [termwiz] key events.
+//!
+//! [termwiz]: https://docs.rs/termwiz
+
+use termwiz::input::{KeyCode as TwKeyCode, KeyEvent as TwKeyEvent, Modifiers};
+
+use crate::event::{KeyCode, KeyEvent};
+
+/// Convert a [`KeyCode`] to a termwiz [`KeyCode`](TwKeyCode).
+impl From<KeyCode> for TwKeyCode {
+    fn from(code: KeyCode) -> Self {
+        match code {
+            KeyCode::Char(c) => TwKeyCode::Char(c),
+            KeyCode::F(n) => TwKeyCode::Function(n),
+            KeyCode::Backspace => TwKeyCode::Backspace,
+            KeyCode::Enter => TwKeyCode::Enter,
+            KeyCode::Left => TwKeyCode::LeftArrow,
+            KeyCode::Right => TwKeyCode::RightArrow,
+            KeyCode::Up => TwKeyCode::UpArrow,
+            KeyCode::Down => TwKeyCode::DownArrow,
+            KeyCode::Tab => TwKeyCode::Tab,
+            KeyCode::Delete => TwKeyCode::Delete,
+            KeyCode::Home => TwKeyCode::Home,
+            KeyCode::End => TwKeyCode::End,
+            KeyCode::PageUp => TwKeyCode::PageUp,
+            KeyCode::PageDown => TwKeyCode::PageDown,
+            KeyCode::Esc => TwKeyCode::Escape,
+            // termwiz has no "null" key
+            KeyCode::Unidentified => TwKeyCode::Char('\0'),
+        }
+    }
+}
+
+/// Convert a termwiz [`KeyCode`](TwKeyCode) to a [`KeyCode`].
+///
+/// Keys that have no equivalent are [`KeyCode::Unidentified`].
+impl From<TwKeyCode> for KeyCode {
+    fn from(code: TwKeyCode) -> Self {
+        match code {
+            TwKeyCode::Char('\0') => KeyCode::Unidentified,
+            TwKeyCode::Char(c) => KeyCode::Char(c),
+            TwKeyCode::Function(n) => KeyCode::F(n),
+            TwKeyCode::Backspace => KeyCode::Backspace,
+            TwKeyCode::Enter => KeyCode::Enter,
+            TwKeyCode::LeftArrow | TwKeyCode::ApplicationLeftArrow => KeyCode::Left,
+            TwKeyCode::RightArrow | TwKeyCode::ApplicationRightArrow => KeyCode::Right,
+            TwKeyCode::UpArrow | TwKeyCode::ApplicationUpArrow => KeyCode::Up,
+            TwKeyCode::DownArrow | TwKeyCode::ApplicationDownArrow => KeyCode::Down,
+            TwKeyCode::Tab => KeyCode::Tab,
+            TwKeyCode::Delete => KeyCode::Delete,
+            TwKeyCode::Home => KeyCode::Home,
+            TwKeyCode::End => KeyCode::End,
+            TwKeyCode::PageUp => KeyCode::PageUp,
+            TwKeyCode::PageDown => KeyCode::PageDown,
+            TwKeyCode::Escape => KeyCode::Esc,
+            _ => KeyCode::Unidentified,
+        }
+    }
+}
+
+/// Convert a [`KeyEvent`] to a termwiz [`KeyEvent`](TwKeyEvent).
+impl From<KeyEvent> for TwKeyEvent {
+    fn from(event: KeyEvent) -> Self {
+        let mut modifiers = Modifiers::NONE;
+        modifiers.set(Modifiers::CTRL, event.ctrl);
+        modifiers.set(Modifiers::ALT, event.alt);
+        modifiers.set(Modifiers::SHIFT, event.shift);
+        modifiers.set(Modifiers::SUPER, event.meta);
+        TwKeyEvent {
+            key: event.code.into(),
+            modifiers,
+        }
+    }
+}
+
+/// Convert a termwiz [`KeyEvent`](TwKeyEvent) to a [`KeyEvent`].
+impl From<TwKeyEvent> for KeyEvent {
+    fn from(event: TwKeyEvent) -> Self {
+        KeyEvent {
+            code: event.key.into(),
+            ctrl: event.modifiers.contains(Modifiers::CTRL),
+            alt: event.modifiers.contains(Modifiers::ALT),
+            shift: event.modifiers.contains(Modifiers::SHIFT),
+            meta: event.modifiers.contains(Modifiers::SUPER),
+            repeat: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_event_round_trip() {
+        let event = KeyEvent {
+            code: KeyCode::Left,
+            ctrl: true,
+            alt: true,
+            shift: false,
+            meta: false,
+            repeat: false,
+        };
+        let converted = TwKeyEvent::from(event.clone());
+        assert_eq!(converted.key, TwKeyCode::LeftArrow);
+        assert_eq!(converted.modifiers, Modifiers::CTRL | Modifiers::ALT);
+        assert_eq!(KeyEvent::from(converted), event);
+        assert_eq!(
+            KeyCode::from(TwKeyCode::from(KeyCode::Unidentified)),
+            KeyCode::Unidentified
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! ## Dioxus
+//!
+//! A [`RatzillaTerminal`] component hosting a terminal in a Dioxus web app.
+//!
+//! Instead of drawing on every animation frame, the terminal is drawn
+//! [on demand](crate::render_loop::RenderLoopOptions::on_demand): every
+//! render of the component requests a frame, so the terminal follows the
+//! state of the app like any other component. The terminal is
+//! [destroyed](crate::teardown::destroy) when the component is dropped.
+//!
+//! ```rust no_run
+//! use dioxus::prelude::*;
+//! use ratzilla::{
+//!     dioxus::{DrawFn, RatzillaTerminal},
+//!     ratatui::widgets::Paragraph,
+//! };
+//!
+//! #[component]
+//! fn App() -> Element {
+//!     let mut count = use_signal(|| 0);
+//!     let draw = DrawFn::new(move |frame| {
+//!         let text = format!("count: {}", count.peek());
+//!         frame.render_widget(Paragraph::new(text), frame.area());
+//!     });
+//!     rsx! {
+//!         button { onclick: move |_| count += 1, "+1" }
+//!         RatzillaTerminal { draw }
+//!     }
+//! }
+//! ```
+//!
+//! Only one terminal can be mounted at a time, as [`teardown`](crate::teardown)
+//! destroys every terminal of the page.
+//!
+//! [`RatzillaTerminal`]: crate::dioxus::RatzillaTerminal
+
+use std::{cell::RefCell, fmt, io, rc::Rc};
+
+use ::dioxus::prelude::*;
+use ratatui::{Frame, Terminal};
+
+use crate::{
+    render_loop::{self, RenderLoopOptions},
+    teardown, DomBackend, WebRenderer,
+};
+
+/// A draw function, called with the frame of the terminal.
+///
+/// Draw functions are equal if they are the same closure, so the component
+/// renders, and redraws the terminal, when the parent passes a new one.
+#[derive(Clone)]
+pub struct DrawFn(Rc<dyn Fn(&mut Frame)>);
+
+impl DrawFn {
+    /// Constructs a new [`DrawFn`].
+    pub fn new<F>(draw: F) -> Self
+    where
+        F: Fn(&mut Frame) + 'static,
+    {
+        Self(Rc::new(draw))
+    }
+}
+
+impl PartialEq for DrawFn {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for DrawFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DrawFn")
+    }
+}
+
+/// A terminal drawn with the [`DomBackend`] in a `<div>`.
+///
+/// The grid fills the `<div>`, so its size is set by the styles of the app.
+#[component]
+pub fn RatzillaTerminal(
+    /// Draws the terminal.
+    draw: DrawFn,
+    /// Id of the `<div>` holding the grid.
+    #[props(default = "ratzilla-terminal".to_string())]
+    id: String,
+    /// Classes of the `<div>` holding the grid.
+    #[props(default)]
+    class: String,
+) -> Element {
+    // the terminal reads the latest props
+    let latest = use_hook(|| Rc::new(RefCell::new(draw.clone())));
+    *latest.borrow_mut() = draw;
+    render_loop::request_redraw();
+
+    use_effect({
+        let id = id.clone();
+        let latest = latest.clone();
+        move || {
+            if let Err(error) = mount(&id, latest.clone()) {
+                web_sys::console::error_1(&format!("unable to mount the terminal: {error}").into());
+            }
+        }
+    });
+    use_drop(teardown::destroy);
+
+    rsx! {
+        div { id: "{id}", class: "{class}" }
+    }
+}
+
+/// Mounts a terminal into the element with the given id.
+fn mount(id: &str, draw: Rc<RefCell<DrawFn>>) -> io::Result<()> {
+    let terminal = Terminal::new(DomBackend::new_by_id(id)?)?;
+    terminal.draw_web_with_options(RenderLoopOptions::new().on_demand(true), move |frame| {
+        let draw = draw.borrow().clone();
+        (draw.0)(frame);
+    });
+    Ok(())
+}
@@ -0,0 +1,405 @@
+//! ## Text editing
+//!
+//! Primitives for editing text one grapheme cluster at a time, so that an
+//! emoji, a ZWJ sequence such as 👩‍👩‍👧 or a letter with combining marks is
+//! moved over, selected and deleted as a whole.
+//!
+//! The free functions work on byte offsets into a `&str`, which always lie on
+//! grapheme boundaries. [`EditBuffer`] builds a single line editor with a
+//! cursor and a selection on top of them:
+//!
+//! ```rust
+//! use ratzilla::editing::EditBuffer;
+//!
+//! let mut buffer = EditBuffer::new("café 👍🏽");
+//! buffer.delete_backward();
+//! assert_eq!(buffer.text(), "café ");
+//! buffer.move_word_left(true);
+//! assert_eq!(buffer.selected_text(), Some("café "));
+//! buffer.insert("tea");
+//! assert_eq!(buffer.text(), "tea");
+//! ```
+//!
+//! [`EditBuffer`]: crate::editing::EditBuffer
+
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::event::{KeyCode, KeyEvent};
+
+/// Returns the offset of the grapheme after the one at `offset`, or the
+/// length of the text at its end.
+pub fn next_boundary(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .graphemes(true)
+        .next()
+        .map_or(text.len(), |g| offset + g.len())
+}
+
+/// Returns the offset of the grapheme before `offset`, or `0` at the start
+/// of the text.
+pub fn prev_boundary(text: &str, offset: usize) -> usize {
+    text[..offset]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+/// Returns the offset of the end of the word after `offset`, skipping
+/// whitespace.
+pub fn next_word_boundary(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .split_word_bound_indices()
+        .find(|(_, word)| !is_whitespace(word))
+        .map_or(text.len(), |(i, word)| offset + i + word.len())
+}
+
+/// Returns the offset of the start of the word before `offset`, skipping
+/// whitespace.
+pub fn prev_word_boundary(text: &str, offset: usize) -> usize {
+    text[..offset]
+        .split_word_bound_indices()
+        .rev()
+        .find(|(_, word)| !is_whitespace(word))
+        .map_or(0, |(i, _)| i)
+}
+
+/// Returns the number of graphemes of the text.
+pub fn grapheme_count(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// Returns the offset of the grapheme with the given index, or the length of
+/// the text if there are fewer graphemes.
+pub fn grapheme_offset(text: &str, index: usize) -> usize {
+    text.grapheme_indices(true)
+        .nth(index)
+        .map_or(text.len(), |(i, _)| i)
+}
+
+/// Returns the column of the offset, in cells.
+pub fn column(text: &str, offset: usize) -> usize {
+    text[..offset]
+        .graphemes(true)
+        .map(UnicodeWidthStr::width)
+        .sum()
+}
+
+/// Returns the offset of the grapheme covering the column, e.g. to place the
+/// cursor on a mouse click, or the length of the text past its end.
+pub fn offset_at_column(text: &str, column: usize) -> usize {
+    let mut start = 0;
+    for (i, grapheme) in text.grapheme_indices(true) {
+        start += grapheme.width();
+        if start > column {
+            return i;
+        }
+    }
+    text.len()
+}
+
+/// Returns `true` if the segment is only whitespace.
+fn is_whitespace(segment: &str) -> bool {
+    segment.chars().all(char::is_whitespace)
+}
+
+/// A single line of text with a cursor and an optional selection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditBuffer {
+    /// The text.
+    text: String,
+    /// Offset of the cursor.
+    cursor: usize,
+    /// Offset where the selection started, the cursor being its other end.
+    anchor: Option<usize>,
+}
+
+impl EditBuffer {
+    /// Constructs a new [`EditBuffer`] with the cursor at the end of the
+    /// text.
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self {
+            cursor: text.len(),
+            text,
+            anchor: None,
+        }
+    }
+
+    /// Returns the text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the offset of the cursor.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns the column of the cursor, in cells.
+    pub fn cursor_column(&self) -> usize {
+        column(&self.text, self.cursor)
+    }
+
+    /// Returns the selected range, if not empty.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        let range = anchor.min(self.cursor)..anchor.max(self.cursor);
+        (!range.is_empty()).then_some(range)
+    }
+
+    /// Returns the selected text, if any.
+    pub fn selected_text(&self) -> Option<&str> {
+        self.selection().map(|range| &self.text[range])
+    }
+
+    /// Selects the whole text.
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.text.len();
+    }
+
+    /// Clears the selection, keeping the cursor in place.
+    pub fn clear_selection(&mut self) {
+        self.anchor = None;
+    }
+
+    /// Replaces the text, moving the cursor to its end.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        *self = Self::new(text);
+    }
+
+    /// Moves the cursor to the offset, extending the selection if `select`
+    /// is `true`.
+    ///
+    /// The offset is clamped to the text and moved back to a grapheme
+    /// boundary.
+    pub fn move_to(&mut self, offset: usize, select: bool) {
+        let offset = if offset >= self.text.len() {
+            self.text.len()
+        } else {
+            self.text
+                .grapheme_indices(true)
+                .map(|(i, _)| i)
+                .take_while(|&i| i <= offset)
+                .last()
+                .unwrap_or(0)
+        };
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = offset;
+    }
+
+    /// Moves the cursor one grapheme to the left.
+    ///
+    /// Without `select`, a selection collapses to its start instead.
+    pub fn move_left(&mut self, select: bool) {
+        match self.selection() {
+            Some(range) if !select => self.move_to(range.start, false),
+            _ => self.move_to(prev_boundary(&self.text, self.cursor), select),
+        }
+    }
+
+    /// Moves the cursor one grapheme to the right.
+    ///
+    /// Without `select`, a selection collapses to its end instead.
+    pub fn move_right(&mut self, select: bool) {
+        match self.selection() {
+            Some(range) if !select => self.move_to(range.end, false),
+            _ => self.move_to(next_boundary(&self.text, self.cursor), select),
+        }
+    }
+
+    /// Moves the cursor to the start of the previous word.
+    pub fn move_word_left(&mut self, select: bool) {
+        self.move_to(prev_word_boundary(&self.text, self.cursor), select);
+    }
+
+    /// Moves the cursor to the end of the next word.
+    pub fn move_word_right(&mut self, select: bool) {
+        self.move_to(next_word_boundary(&self.text, self.cursor), select);
+    }
+
+    /// Moves the cursor to the start of the text.
+    pub fn move_home(&mut self, select: bool) {
+        self.move_to(0, select);
+    }
+
+    /// Moves the cursor to the end of the text.
+    pub fn move_end(&mut self, select: bool) {
+        self.move_to(self.text.len(), select);
+    }
+
+    /// Inserts text at the cursor, replacing the selection.
+    pub fn insert(&mut self, text: &str) {
+        self.delete_selection();
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+
+    /// Deletes the selection, or the grapheme before the cursor.
+    pub fn delete_backward(&mut self) {
+        if !self.delete_selection() {
+            let start = prev_boundary(&self.text, self.cursor);
+            self.delete(start..self.cursor);
+        }
+    }
+
+    /// Deletes the selection, or the grapheme after the cursor.
+    pub fn delete_forward(&mut self) {
+        if !self.delete_selection() {
+            let end = next_boundary(&self.text, self.cursor);
+            self.delete(self.cursor..end);
+        }
+    }
+
+    /// Deletes the selection, or up to the start of the previous word.
+    pub fn delete_word_backward(&mut self) {
+        if !self.delete_selection() {
+            let start = prev_word_boundary(&self.text, self.cursor);
+            self.delete(start..self.cursor);
+        }
+    }
+
+    /// Deletes the selection, or up to the end of the next word.
+    pub fn delete_word_forward(&mut self) {
+        if !self.delete_selection() {
+            let end = next_word_boundary(&self.text, self.cursor);
+            self.delete(self.cursor..end);
+        }
+    }
+
+    /// Handles the usual editing keys: arrows, `Home`, `End`, `Backspace`,
+    /// `Delete` and typed characters.
+    ///
+    /// Shift extends the selection, and Ctrl or Alt (Option on macOS) move or
+    /// delete by words. `Ctrl+A` (`Cmd+A` on macOS) selects all.
+    ///
+    /// Returns `true` if the event was handled.
+    pub fn handle_key_event(&mut self, event: &KeyEvent) -> bool {
+        let by_word = event.ctrl || event.alt;
+        let select = event.shift;
+        match event.code {
+            KeyCode::Left if by_word => self.move_word_left(select),
+            KeyCode::Left => self.move_left(select),
+            KeyCode::Right if by_word => self.move_word_right(select),
+            KeyCode::Right => self.move_right(select),
+            KeyCode::Home => self.move_home(select),
+            KeyCode::End => self.move_end(select),
+            KeyCode::Backspace if by_word => self.delete_word_backward(),
+            KeyCode::Backspace => self.delete_backward(),
+            KeyCode::Delete if by_word => self.delete_word_forward(),
+            KeyCode::Delete => self.delete_forward(),
+            KeyCode::Char('a' | 'A') if (event.ctrl || event.meta) && !event.alt => {
+                self.select_all()
+            }
+            KeyCode::Char(c) if !event.ctrl && !event.meta => {
+                self.insert(c.encode_utf8(&mut [0; 4]))
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Deletes the selection, returning `true` if there was one.
+    fn delete_selection(&mut self) -> bool {
+        let selection = self.selection();
+        self.anchor = None;
+        match selection {
+            Some(range) => {
+                self.delete(range);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Deletes a range and moves the cursor to its start.
+    fn delete(&mut self, range: Range<usize>) {
+        self.cursor = range.start;
+        self.text.replace_range(range, "");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boundaries() {
+        // a ZWJ family, an "e" with a combining acute accent and a flag
+        let text = "a👩‍👩‍👧e\u{301}🇫🇷";
+        let family = "👩‍👩‍👧".len();
+        assert_eq!(grapheme_count(text), 4);
+        assert_eq!(next_boundary(text, 1), 1 + family);
+        assert_eq!(prev_boundary(text, 1 + family), 1);
+        assert_eq!(prev_boundary(text, text.len()), text.len() - "🇫🇷".len());
+        assert_eq!(grapheme_offset(text, 2), 1 + family);
+        assert_eq!(grapheme_offset(text, 9), text.len());
+        assert_eq!(column(text, 1 + family), 3);
+        assert_eq!(offset_at_column(text, 2), 1);
+        assert_eq!(offset_at_column(text, 3), 1 + family);
+    }
+
+    #[test]
+    fn test_words() {
+        let text = "hello,  wide world";
+        assert_eq!(next_word_boundary(text, 0), 5);
+        assert_eq!(next_word_boundary(text, 6), 12);
+        assert_eq!(prev_word_boundary(text, text.len()), 13);
+        assert_eq!(prev_word_boundary(text, 13), 8);
+        assert_eq!(prev_word_boundary(text, 3), 0);
+    }
+
+    #[test]
+    fn test_edit_buffer() {
+        let mut buffer = EditBuffer::new("ne\u{301}e");
+        buffer.move_left(false);
+        buffer.delete_backward();
+        assert_eq!(buffer.text(), "ne");
+        assert_eq!(buffer.cursor(), 1);
+
+        buffer.insert("👍🏽");
+        assert_eq!(buffer.cursor_column(), 3);
+        buffer.move_left(true);
+        assert_eq!(buffer.selected_text(), Some("👍🏽"));
+        buffer.move_right(false);
+        assert_eq!(buffer.selection(), None);
+        assert_eq!(buffer.cursor(), 1 + "👍🏽".len());
+
+        // offsets inside a grapheme move back to its start
+        buffer.move_to(2, false);
+        assert_eq!(buffer.cursor(), 1);
+        buffer.set_text("a👩‍👩‍👧");
+        buffer.move_to(9, false);
+        assert_eq!(buffer.cursor(), 1);
+    }
+
+    #[test]
+    fn test_handle_key_event() {
+        let key = |code, ctrl, shift| KeyEvent {
+            code,
+            ctrl,
+            alt: false,
+            shift,
+            meta: false,
+            repeat: false,
+        };
+        let mut buffer = EditBuffer::default();
+        for c in "one two".chars() {
+            assert!(buffer.handle_key_event(&key(KeyCode::Char(c), false, false)));
+        }
+        assert!(buffer.handle_key_event(&key(KeyCode::Left, true, true)));
+        assert_eq!(buffer.selected_text(), Some("two"));
+        assert!(buffer.handle_key_event(&key(KeyCode::Backspace, false, false)));
+        assert_eq!(buffer.text(), "one ");
+        assert!(buffer.handle_key_event(&key(KeyCode::Backspace, true, false)));
+        assert_eq!(buffer.text(), "");
+        assert!(!buffer.handle_key_event(&key(KeyCode::Char('s'), true, false)));
+    }
+}
@@ -37,12 +37,50 @@ pub enum Error {
     JsValue(wasm_bindgen::JsValue),
 
     /// WebGL2Backend error.
+    #[cfg(feature = "webgl2")]
     #[error("WebGL2Backend error: {0}")]
     WebGl2Error(beamterm_renderer::Error),
 
+    /// WgpuBackend error, e.g. WebGPU is not supported by the browser.
+    #[cfg(feature = "webgpu")]
+    #[error("WgpuBackend error: {0}")]
+    WebGpuError(String),
+
     /// Failed to retrieve a HTML/js component, such as `Performance`.
     #[error("Failed to retrieve component: {0}")]
     UnableToRetrieveComponent(&'static str),
+
+    /// Failed to (de)compress data.
+    #[error("Compression error: {0}")]
+    Compression(String),
+
+    /// Failed to encode or decode a protocol message.
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+
+    /// Authentication failed.
+    #[error("Authentication error: {0}")]
+    Auth(String),
+
+    /// Invalid key binding.
+    #[error("Invalid key binding: {0}")]
+    InvalidKeyBinding(String),
+
+    /// Invalid replay recording.
+    #[error("Invalid recording: {0}")]
+    InvalidRecording(String),
+
+    /// Unable to initialize the terminal.
+    #[error("Unable to initialize terminal: {0}")]
+    UnableToInitializeTerminal(String),
+
+    /// Invalid image.
+    #[error("Invalid image: {0}")]
+    InvalidImage(String),
+
+    /// Invalid session snapshot.
+    #[error("Invalid session: {0}")]
+    InvalidSession(String),
 }
 
 /// Convert [`wasm_bindgen::JsValue`] to [`Error`].
@@ -55,11 +93,12 @@ impl From<wasm_bindgen::JsValue> for Error {
 /// Convert [`Error`] to [`std::io::Error`].
 impl From<Error> for std::io::Error {
     fn from(error: Error) -> Self {
-        std::io::Error::new(std::io::ErrorKind::Other, error.to_string())
+        std::io::Error::other(error.to_string())
     }
 }
 
 /// Converts a [`beamterm_renderer::Error`] into a [`Error`].
+#[cfg(feature = "webgl2")]
 impl From<beamterm_renderer::Error> for Error {
     fn from(value: beamterm_renderer::Error) -> Self {
         Self::WebGl2Error(value)
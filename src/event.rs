@@ -1,3 +1,14 @@
+use std::cell::{Cell, RefCell};
+
+use ratatui::layout::Position;
+use web_sys::{js_sys::Function, wasm_bindgen::prelude::*, HtmlInputElement};
+
+use crate::{
+    backend::{utils::get_document, web},
+    error::Error,
+    teardown,
+};
+
 /// A key event.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct KeyEvent {
@@ -9,6 +20,22 @@ pub struct KeyEvent {
     pub alt: bool,
     /// Whether the shift key is pressed.
     pub shift: bool,
+    /// Whether the meta key is pressed: Cmd on macOS, the Windows key on
+    /// Windows.
+    pub meta: bool,
+    /// Whether the event is repeated because the key is held down.
+    pub repeat: bool,
+}
+
+/// A key, mouse or wheel event.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum InputEvent {
+    /// A key event.
+    Key(KeyEvent),
+    /// A mouse event.
+    Mouse(MouseEvent),
+    /// A wheel event.
+    Wheel(WheelEvent),
 }
 
 /// A mouse movement event.
@@ -22,6 +49,14 @@ pub struct MouseEvent {
     pub x: u32,
     /// The y coordinate of the mouse.
     pub y: u32,
+    /// The cell under the mouse, or `None` if the mouse is outside of the
+    /// grid.
+    ///
+    /// This is translated from [`x`](Self::x) and [`y`](Self::y) with the
+    /// layout of the backend drawn last, see [`WebTerminal::cell_at`].
+    ///
+    /// [`WebTerminal::cell_at`]: crate::WebTerminal::cell_at
+    pub cell: Option<Position>,
     /// Whether the control key is pressed.
     pub ctrl: bool,
     /// Whether the alt key is pressed.
@@ -36,11 +71,15 @@ impl From<web_sys::KeyboardEvent> for KeyEvent {
         let ctrl = event.ctrl_key();
         let alt = event.alt_key();
         let shift = event.shift_key();
+        let meta = event.meta_key();
+        let repeat = event.repeat();
         KeyEvent {
             code: event.into(),
             ctrl,
             alt,
             shift,
+            meta,
+            repeat,
         }
     }
 }
@@ -147,10 +186,16 @@ pub enum MouseButton {
 pub enum MouseEventKind {
     /// Mouse moved
     Moved,
+    /// Mouse moved while a button is pressed
+    Dragged,
     /// Mouse button pressed
     Pressed,
     /// Mouse button released
     Released,
+    /// Mouse button pressed and released on the same element
+    Clicked,
+    /// Mouse button clicked twice in a row
+    DoubleClicked,
     /// Unidentified mouse event
     Unidentified,
 }
@@ -161,17 +206,24 @@ impl From<web_sys::MouseEvent> for MouseEvent {
         let ctrl = event.ctrl_key();
         let alt = event.alt_key();
         let shift = event.shift_key();
-        let event_type = event.type_().into();
+        let mut event_type = event.type_().into();
+        // `button` is only valid for button events, moves report the held
+        // buttons instead
+        let button = if event_type == MouseEventKind::Moved {
+            let button = MouseButton::from_buttons(event.buttons());
+            if button != MouseButton::Unidentified {
+                event_type = MouseEventKind::Dragged;
+            }
+            button
+        } else {
+            event.button().into()
+        };
         MouseEvent {
-            // Button is only valid if it is a mousedown or mouseup event.
-            button: if event_type == MouseEventKind::Moved {
-                MouseButton::Unidentified
-            } else {
-                event.button().into()
-            },
+            button,
             event: event_type,
             x: event.client_x() as u32,
             y: event.client_y() as u32,
+            cell: None,
             ctrl,
             alt,
             shift,
@@ -193,6 +245,23 @@ impl From<i16> for MouseButton {
     }
 }
 
+impl MouseButton {
+    /// Returns the first button of a [`web_sys::MouseEvent::buttons`]
+    /// bitmask, or [`MouseButton::Unidentified`] if no button is held.
+    fn from_buttons(buttons: u16) -> Self {
+        [
+            (1, MouseButton::Left),
+            (2, MouseButton::Right),
+            (4, MouseButton::Middle),
+            (8, MouseButton::Back),
+            (16, MouseButton::Forward),
+        ]
+        .into_iter()
+        .find(|(mask, _)| buttons & mask != 0)
+        .map_or(MouseButton::Unidentified, |(_, button)| button)
+    }
+}
+
 /// Convert a [`web_sys::MouseEvent`] to a [`MouseEventKind`].
 impl From<String> for MouseEventKind {
     fn from(event: String) -> Self {
@@ -201,7 +270,396 @@ impl From<String> for MouseEventKind {
             "mousemove" => MouseEventKind::Moved,
             "mousedown" => MouseEventKind::Pressed,
             "mouseup" => MouseEventKind::Released,
+            "click" | "auxclick" => MouseEventKind::Clicked,
+            "dblclick" => MouseEventKind::DoubleClicked,
             _ => MouseEventKind::Unidentified,
         }
     }
 }
+
+/// A scroll wheel event, in cells.
+///
+/// Browsers report wheel deltas in pixels, lines or pages depending on the
+/// device. They are converted to cells with the size of the cells and of the
+/// grid, and fractions of cells are carried over to the next event, so
+/// trackpads scroll smoothly.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WheelEvent {
+    /// Rows scrolled, positive when scrolling down.
+    pub delta_rows: i32,
+    /// Columns scrolled, positive when scrolling right.
+    pub delta_cols: i32,
+    /// The cell under the mouse, or `None` if the mouse is outside of the
+    /// grid.
+    pub position: Option<Position>,
+    /// Whether the control key is pressed.
+    pub ctrl: bool,
+    /// Whether the alt key is pressed.
+    pub alt: bool,
+    /// Whether the shift key is pressed.
+    pub shift: bool,
+}
+
+/// A touch event.
+///
+/// The position is the one of the touch that changed, and the gestures are
+/// recognized from all touches on screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TouchEvent {
+    /// The triggered event.
+    pub kind: TouchEventKind,
+    /// The x coordinate of the touch.
+    pub x: u32,
+    /// The y coordinate of the touch.
+    pub y: u32,
+    /// The cell under the touch, or `None` if the touch is outside of the
+    /// grid.
+    pub cell: Option<Position>,
+    /// Number of touches on screen.
+    pub touches: usize,
+}
+
+/// A touch event kind.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TouchEventKind {
+    /// A touch started.
+    Started,
+    /// A touch moved.
+    Moved,
+    /// A touch ended.
+    Ended,
+    /// A touch was interrupted, e.g. by a browser gesture.
+    Cancelled,
+    /// A gesture was recognized.
+    Gesture(Gesture),
+}
+
+/// A touch gesture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// A short touch without moving.
+    Tap,
+    /// A touch held without moving.
+    LongPress,
+    /// A quick move in a direction.
+    Swipe(SwipeDirection),
+    /// Two touches moving apart or together, with the ratio of their
+    /// distance to the distance when the second touch started.
+    Pinch(f64),
+}
+
+/// The direction of a swipe.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SwipeDirection {
+    /// Swiped up
+    Up,
+    /// Swiped down
+    Down,
+    /// Swiped left
+    Left,
+    /// Swiped right
+    Right,
+}
+
+/// An image pasted from the clipboard, e.g. a screenshot.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ImagePasteEvent {
+    /// The MIME type of the image, e.g. `image/png`.
+    pub mime_type: String,
+    /// The encoded image, in the format of its MIME type.
+    pub bytes: Vec<u8>,
+}
+
+/// A composition event of an input method editor (IME), e.g. for Chinese,
+/// Japanese or Korean input.
+///
+/// Key events are not reported while composing, the composed text is
+/// reported with [`CompositionEventKind::Ended`] instead.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CompositionEvent {
+    /// The triggered event.
+    pub kind: CompositionEventKind,
+    /// The text being composed, or the composed text once ended.
+    pub data: String,
+}
+
+/// A composition event kind.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CompositionEventKind {
+    /// A composition started.
+    Started,
+    /// The text being composed changed.
+    Updated,
+    /// The composition ended, with the composed text or an empty text if it
+    /// was cancelled.
+    Ended,
+}
+
+impl From<web_sys::CompositionEvent> for CompositionEvent {
+    fn from(event: web_sys::CompositionEvent) -> Self {
+        let kind = match event.type_().as_str() {
+            "compositionstart" => CompositionEventKind::Started,
+            "compositionupdate" => CompositionEventKind::Updated,
+            _ => CompositionEventKind::Ended,
+        };
+        CompositionEvent {
+            kind,
+            data: event.data().unwrap_or_default(),
+        }
+    }
+}
+
+thread_local! {
+    /// Hidden element capturing the input of input method editors.
+    static CAPTURE: RefCell<Option<HtmlInputElement>> = const { RefCell::new(None) };
+    /// Whether an input method editor is composing.
+    static COMPOSING: Cell<bool> = const { Cell::new(false) };
+    /// Whether a composition ended since the last key event.
+    static COMPOSITION_ENDED: Cell<bool> = const { Cell::new(false) };
+    /// Whether the last key press didn't identify the key, so its input is
+    /// reported as key events instead.
+    static PENDING_INPUT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The key code of the key events of input method editors.
+const IME_KEY_CODE: u32 = 229;
+
+/// Returns the hidden element capturing the input of input method editors,
+/// creating it the first time.
+///
+/// Input method editors only compose in editable elements. Key events still
+/// bubble from it to the document. It takes the focus when created, unless
+/// another element has it, and after clicks on the grid, so the other inputs
+/// of the page keep working.
+pub(crate) fn capture_element() -> Result<HtmlInputElement, Error> {
+    if let Some(element) = CAPTURE.with(|capture| capture.borrow().clone()) {
+        if !element.is_connected() {
+            mount(&element)?;
+        }
+        return Ok(element);
+    }
+    let document = get_document()?;
+    let element: HtmlInputElement = document.create_element("input")?.unchecked_into();
+    element.set_attribute("autocomplete", "off")?;
+    element.set_attribute("aria-hidden", "true")?;
+    // set through the CSSOM, which a Content Security Policy allows
+    element.style().set_css_text(
+        "position: fixed; top: 0; left: 0; width: 1px; height: 1px; \
+         opacity: 0; border: 0; padding: 0; pointer-events: none;",
+    );
+
+    let on_composition: Function =
+        Closure::<dyn FnMut(_)>::new(|event: web_sys::CompositionEvent| {
+            let ended = event.type_() == "compositionend";
+            COMPOSING.with(|composing| composing.set(!ended));
+            COMPOSITION_ENDED.with(|composition_ended| composition_ended.set(ended));
+        })
+        .into_js_value()
+        .unchecked_into();
+    for event in ["compositionstart", "compositionend"] {
+        teardown::add_event_listener(&element, event, &on_composition)?;
+    }
+    let on_input = Closure::<dyn FnMut(_)>::new({
+        let element = element.clone();
+        move |event: web_sys::InputEvent| {
+            if is_composing() {
+                return;
+            }
+            if PENDING_INPUT.with(|pending| pending.replace(false)) {
+                let _ = dispatch_input_keys(&element, &event);
+            }
+            // the key events carry the input, don't let it pile up
+            element.set_value("");
+        }
+    });
+    teardown::add_event_listener(&element, "input", on_input.into_js_value().unchecked_ref())?;
+    let on_click = Closure::<dyn FnMut(_)>::new({
+        let element = element.clone();
+        move |event: web_sys::MouseEvent| {
+            let (x, y) = (event.client_x() as f64, event.client_y() as f64);
+            if web::grid_cell_at(x, y).is_some() {
+                if !element.is_connected() {
+                    let _ = mount(&element);
+                }
+                let _ = element.focus();
+            }
+        }
+    });
+    teardown::add_event_listener(&document, "click", on_click.into_js_value().unchecked_ref())?;
+
+    mount(&element)?;
+    CAPTURE.with(|capture| *capture.borrow_mut() = Some(element.clone()));
+    teardown::on_destroy(|| {
+        if let Some(element) = CAPTURE.with(|capture| capture.borrow_mut().take()) {
+            element.remove();
+        }
+        COMPOSING.with(|composing| composing.set(false));
+        COMPOSITION_ENDED.with(|composition_ended| composition_ended.set(false));
+        PENDING_INPUT.with(|pending| pending.set(false));
+    });
+    Ok(element)
+}
+
+/// Puts the hidden input element back into the page if it was detached,
+/// e.g. when the parent of the grid was cleared on resize.
+///
+/// Does nothing if the element was never created.
+#[cfg(feature = "dom")]
+pub(crate) fn restore_capture_element() -> Result<(), Error> {
+    match CAPTURE.with(|capture| capture.borrow().clone()) {
+        Some(element) if !element.is_connected() => mount(&element),
+        _ => Ok(()),
+    }
+}
+
+/// Appends the hidden input element to the body, focusing it unless another
+/// element has the focus.
+fn mount(element: &HtmlInputElement) -> Result<(), Error> {
+    let document = get_document()?;
+    let body = document.body().ok_or(Error::UnableToRetrieveBody)?;
+    body.append_child(element)?;
+    let focused = document
+        .active_element()
+        .is_some_and(|active| active != *body);
+    if !focused {
+        element.focus()?;
+    }
+    Ok(())
+}
+
+/// Reports the text of an input event as key presses, for keyboards that
+/// don't identify the pressed keys, e.g. the virtual keyboards of Android.
+///
+/// The key presses are dispatched as `keydown` events from the hidden input
+/// element, so they reach the key event handlers like the other keys.
+fn dispatch_input_keys(
+    element: &HtmlInputElement,
+    event: &web_sys::InputEvent,
+) -> Result<(), Error> {
+    let keys: Vec<String> = match event.input_type().as_str() {
+        "deleteContentBackward" => vec!["Backspace".to_string()],
+        "deleteContentForward" => vec!["Delete".to_string()],
+        "insertLineBreak" | "insertParagraph" => vec!["Enter".to_string()],
+        _ => event
+            .data()
+            .unwrap_or_default()
+            .chars()
+            .map(String::from)
+            .collect(),
+    };
+    for key in keys {
+        let init = web_sys::KeyboardEventInit::new();
+        init.set_key(&key);
+        init.set_bubbles(true);
+        let event = web_sys::KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init)?;
+        element.dispatch_event(&event)?;
+    }
+    Ok(())
+}
+
+/// Returns `true` if an input method editor is composing.
+pub(crate) fn is_composing() -> bool {
+    COMPOSING.with(Cell::get)
+}
+
+/// Returns `true` if the given key event is part of a composition, or
+/// doesn't identify the pressed key.
+///
+/// Some browsers report the key confirming a composition after the
+/// composition ended, with the key code 229. Virtual keyboards, e.g. on
+/// Android, use the same key code for all keys outside of compositions: the
+/// typed text is then reported as key events once the hidden input element
+/// receives it.
+pub(crate) fn is_composition_key(event: &web_sys::KeyboardEvent) -> bool {
+    let ended = COMPOSITION_ENDED.with(|composition_ended| composition_ended.replace(false));
+    if event.is_composing() || is_composing() {
+        return true;
+    }
+    #[allow(deprecated)]
+    if event.key_code() != IME_KEY_CODE {
+        return false;
+    }
+    if ended {
+        return true;
+    }
+    let unidentified = event.key() == "Unidentified";
+    if unidentified && event.type_() == "keydown" {
+        PENDING_INPUT.with(|pending| pending.set(true));
+    }
+    unidentified
+}
+
+/// Converts a wheel delta to cells.
+///
+/// `delta_mode` is the [`web_sys::WheelEvent::delta_mode`] of the delta,
+/// `cell_size` the size of a cell in pixels and `page` the number of cells in
+/// a page.
+pub(crate) fn wheel_delta_to_cells(delta: f64, delta_mode: u32, cell_size: f64, page: u16) -> f64 {
+    match delta_mode {
+        web_sys::WheelEvent::DOM_DELTA_LINE => delta,
+        web_sys::WheelEvent::DOM_DELTA_PAGE => delta * page.max(1) as f64,
+        _ if cell_size > 0.0 => delta / cell_size,
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Constructs a `keydown` event of the given key and key code.
+    fn keydown(key: &str, key_code: u32, is_composing: bool) -> web_sys::KeyboardEvent {
+        let init = web_sys::KeyboardEventInit::new();
+        init.set_key(key);
+        init.set_key_code(key_code);
+        init.set_is_composing(is_composing);
+        web_sys::KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init)
+            .expect("failed to create the event")
+    }
+
+    #[test]
+    fn test_wheel_delta_to_cells() {
+        assert_eq!(wheel_delta_to_cells(57.0, 0, 19.0, 24), 3.0);
+        assert_eq!(wheel_delta_to_cells(-2.0, 1, 19.0, 24), -2.0);
+        assert_eq!(wheel_delta_to_cells(1.0, 2, 19.0, 24), 24.0);
+        assert_eq!(wheel_delta_to_cells(10.0, 0, 0.0, 24), 0.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_is_composition_key() {
+        assert!(!is_composition_key(&keydown("a", 65, false)));
+        assert!(is_composition_key(&keydown("a", 65, true)));
+
+        // a virtual keyboard outside of a composition
+        assert!(!is_composition_key(&keydown("Enter", IME_KEY_CODE, false)));
+        assert!(is_composition_key(&keydown(
+            "Unidentified",
+            IME_KEY_CODE,
+            false
+        )));
+        assert!(PENDING_INPUT.with(|pending| pending.replace(false)));
+
+        // the key confirming a composition, after it ended
+        COMPOSITION_ENDED.with(|composition_ended| composition_ended.set(true));
+        assert!(is_composition_key(&keydown("Enter", IME_KEY_CODE, false)));
+        assert!(!is_composition_key(&keydown("Enter", IME_KEY_CODE, false)));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_capture_element_is_reattached() {
+        let element = capture_element().expect("failed to create the element");
+        // e.g. the parent of the grid is cleared on resize
+        element.remove();
+        assert!(!element.is_connected());
+
+        let restored = capture_element().expect("failed to get the element");
+        assert_eq!(restored, element);
+        assert!(element.is_connected());
+        teardown::destroy();
+        assert!(!element.is_connected());
+    }
+}
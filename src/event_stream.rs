@@ -0,0 +1,180 @@
+//! ## Event streams
+//!
+//! Besides registering callbacks, the events can be read from an
+//! [`EventStream`], a [`Stream`] unifying key, key release, mouse, wheel,
+//! resize and paste events. Apps structured around an async `select!` loop,
+//! like many crossterm apps with its `event-stream` feature, can port to the
+//! web with few changes:
+//!
+//! ```rust no_run
+//! use ratzilla::{event_stream::Event, shared::Shared, WebRenderer};
+//! # fn example(terminal: ratzilla::ratatui::Terminal<ratzilla::DomBackend>) {
+//!
+//! let mut events = terminal.event_stream();
+//! let last_key = Shared::new(None);
+//! last_key.spawn(|last_key| async move {
+//!     while let Some(event) = events.next().await {
+//!         if let Event::Key(key) = event {
+//!             last_key.set(Some(key.code));
+//!         }
+//!     }
+//! });
+//!
+//! terminal.draw_web(move |frame| {
+//!     // render `last_key`...
+//! });
+//! # }
+//! ```
+//!
+//! The stream ends when the terminal is [destroyed](crate::teardown::destroy).
+//!
+//! [`EventStream`]: crate::event_stream::EventStream
+//! [`Stream`]: crate::event_stream::Stream
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    future::{poll_fn, Future},
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+pub use futures_core::Stream;
+use ratatui::layout::Size;
+
+use crate::{
+    event::{KeyEvent, MouseEvent, WheelEvent},
+    teardown,
+};
+
+/// An event read from an [`EventStream`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// A key event.
+    Key(KeyEvent),
+    /// A key was released.
+    KeyRelease(KeyEvent),
+    /// A mouse event.
+    Mouse(MouseEvent),
+    /// A wheel event.
+    Wheel(WheelEvent),
+    /// The terminal was resized to the given size, in cells.
+    Resize(Size),
+    /// Text was pasted from the clipboard.
+    Paste(String),
+}
+
+/// State of an [`EventStream`].
+#[derive(Debug, Default)]
+struct StreamState {
+    /// Events not yet read.
+    queue: VecDeque<Event>,
+    /// Waker of the task waiting for an event.
+    waker: Option<Waker>,
+    /// Whether the terminal was destroyed.
+    closed: bool,
+}
+
+/// A stream of the events of the terminal.
+///
+/// Constructed with [`WebRenderer::event_stream`](crate::WebRenderer::event_stream).
+#[derive(Debug)]
+pub struct EventStream {
+    state: Rc<RefCell<StreamState>>,
+}
+
+impl EventStream {
+    /// Constructs a new, empty [`EventStream`], which ends on teardown.
+    pub(crate) fn new() -> Self {
+        let state = Rc::new(RefCell::new(StreamState::default()));
+        let closing = Rc::downgrade(&state);
+        teardown::on_destroy(move || {
+            if let Some(state) = closing.upgrade() {
+                let mut state = state.borrow_mut();
+                state.closed = true;
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            }
+        });
+        Self { state }
+    }
+
+    /// Returns a function that pushes events to the stream.
+    pub(crate) fn sender(&self) -> impl Fn(Event) + Clone + 'static {
+        let state = Rc::downgrade(&self.state);
+        move |event| {
+            let Some(state) = state.upgrade() else {
+                return;
+            };
+            let waker = {
+                let mut state = state.borrow_mut();
+                state.queue.push_back(event);
+                state.waker.take()
+            };
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Waits for the next event, or returns `None` once the stream ended.
+    ///
+    /// This is the same as `StreamExt::next` from the `futures` crate.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> impl Future<Output = Option<Event>> + '_ {
+        poll_fn(|cx| Pin::new(&mut *self).poll_next(cx))
+    }
+}
+
+impl Stream for EventStream {
+    type Item = Event;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        let mut state = self.state.borrow_mut();
+        if let Some(event) = state.queue.pop_front() {
+            return Poll::Ready(Some(event));
+        }
+        if state.closed {
+            return Poll::Ready(None);
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, task::Wake};
+
+    use super::*;
+
+    /// A waker that does nothing.
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    #[test]
+    fn test_stream_order() {
+        let mut stream = EventStream::new();
+        let send = stream.sender();
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Pending);
+
+        send(Event::Paste("a".into()));
+        send(Event::Resize(Size::new(80, 24)));
+        assert_eq!(
+            Pin::new(&mut stream).poll_next(&mut cx),
+            Poll::Ready(Some(Event::Paste("a".into())))
+        );
+        assert_eq!(
+            Pin::new(&mut stream).poll_next(&mut cx),
+            Poll::Ready(Some(Event::Resize(Size::new(80, 24))))
+        );
+        assert!(stream.state.borrow().waker.is_none());
+    }
+}
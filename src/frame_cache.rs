@@ -0,0 +1,327 @@
+//! ## Frame cache
+//!
+//! Persists the last frame of a session in `localStorage` when the page is
+//! left, and paints it on the next visit before the app is initialized, so
+//! returning users see their dashboard right away instead of a blank page.
+//!
+//! The frame is stored as its cells, painted with the structure of the
+//! [`DomBackend`](crate::DomBackend) and replaced by the first frame drawn by
+//! the app:
+//!
+//! ```rust no_run
+//! use ratzilla::{
+//!     frame_cache::{self, FrameCacheOptions},
+//!     ratatui::{widgets::Paragraph, Terminal},
+//!     DomBackend, WebRenderer,
+//! };
+//!
+//! fn main() -> std::io::Result<()> {
+//!     let options = FrameCacheOptions::new().key("dashboard-frame");
+//!     // paint the cached frame first, before loading any data
+//!     frame_cache::restore(&options)?;
+//!     frame_cache::enable(options)?;
+//!
+//!     let terminal = Terminal::new(DomBackend::new()?)?;
+//!     terminal.draw_web(|frame| {
+//!         frame.render_widget(Paragraph::new("Hello"), frame.area());
+//!     });
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The cached frame is painted without parsing markup, and its styles are set
+//! through the CSSOM, so it works with a Content Security Policy and with
+//! Trusted Types.
+
+use std::{cell::RefCell, rc::Rc};
+
+use ratatui::buffer::Buffer;
+use unicode_width::UnicodeWidthStr;
+use web_sys::{wasm_bindgen::prelude::*, Element, HtmlElement};
+
+use crate::{
+    backend::utils::{get_cell_style_as_css, get_document, get_element_by_id_or_body, get_window},
+    buffer_codec::{decode_buffer, encode_buffer},
+    error::Error,
+    teardown,
+    utils::storage::local_storage,
+};
+
+/// Options for the frame cache.
+#[derive(Debug, Clone)]
+pub struct FrameCacheOptions {
+    /// Key of the `localStorage` item holding the frame.
+    key: String,
+    /// Id of the element the frame is painted in.
+    parent_id: Option<String>,
+    /// Id of the painted grid element.
+    grid_id: String,
+}
+
+impl Default for FrameCacheOptions {
+    fn default() -> Self {
+        Self {
+            key: "ratzilla-frame".to_string(),
+            parent_id: None,
+            grid_id: "grid".to_string(),
+        }
+    }
+}
+
+impl FrameCacheOptions {
+    /// Constructs a new [`FrameCacheOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the key of the `localStorage` item holding the frame.
+    ///
+    /// Defaults to `ratzilla-frame`.
+    pub fn key(mut self, key: &str) -> Self {
+        self.key = key.to_string();
+        self
+    }
+
+    /// Sets the id of the element the frame is painted in.
+    ///
+    /// Defaults to the `<body>` element.
+    pub fn parent_id(mut self, id: &str) -> Self {
+        self.parent_id = Some(id.to_string());
+        self
+    }
+
+    /// Sets the id of the painted grid element.
+    ///
+    /// This should match the grid id of the [`DomBackend`](crate::DomBackend)
+    /// for it to take over the painted grid, see
+    /// [`DomBackendOptions::grid_id`](crate::backend::dom::DomBackendOptions::grid_id).
+    /// Defaults to `grid`.
+    pub fn grid_id(mut self, id: &str) -> Self {
+        self.grid_id = id.to_string();
+        self
+    }
+}
+
+/// Returns a copy of the cells on screen, if any.
+type FrameSource = Rc<dyn Fn() -> Option<Buffer>>;
+
+/// State of the frame cache.
+#[derive(Default)]
+struct FrameCache {
+    /// Options of the enabled cache.
+    options: Option<FrameCacheOptions>,
+    /// Source of the frame on screen, read when the page is left.
+    source: Option<FrameSource>,
+    /// Painted cached frame, removed once the app draws.
+    preview: Option<Element>,
+    /// Whether the `pagehide` listener is registered.
+    listening: bool,
+}
+
+thread_local! {
+    static CACHE: RefCell<FrameCache> = RefCell::new(FrameCache::default());
+}
+
+/// Paints the cached frame, if any.
+///
+/// Returns `true` if a frame was painted. It is removed once the app draws
+/// its first frame.
+pub fn restore(options: &FrameCacheOptions) -> Result<bool, Error> {
+    // frames stored by older versions don't decode, and are ignored
+    let Some(buffer) = local_storage()?
+        .get_item(&options.key)?
+        .as_deref()
+        .and_then(decode_buffer)
+    else {
+        return Ok(false);
+    };
+    if get_document()?
+        .get_element_by_id(&options.grid_id)
+        .is_some()
+    {
+        // the page is already pre-rendered
+        return Ok(false);
+    }
+    let preview = paint(&buffer, &options.grid_id)?;
+    get_element_by_id_or_body(options.parent_id.as_ref())?.append_child(&preview)?;
+    CACHE.with(|cache| cache.borrow_mut().preview = Some(preview));
+    Ok(true)
+}
+
+/// Enables caching the last frame when the page is left.
+///
+/// [`teardown::destroy`] disables the cache.
+pub fn enable(options: FrameCacheOptions) -> Result<(), Error> {
+    let listening = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        cache.options = Some(options);
+        std::mem::replace(&mut cache.listening, true)
+    });
+    if !listening {
+        let closure = Closure::<dyn FnMut()>::new(|| {
+            // nothing to do about storage errors while the page is left
+            let _ = save();
+        });
+        teardown::add_event_listener(
+            &get_window()?.into(),
+            "pagehide",
+            closure.into_js_value().unchecked_ref(),
+        )?;
+        teardown::on_destroy(reset);
+    }
+    Ok(())
+}
+
+/// Removes the painted cached frame and forgets the options of the
+/// destroyed terminal.
+fn reset() {
+    let cache = CACHE.with(|cache| cache.take());
+    if let Some(preview) = cache.preview {
+        preview.remove();
+    }
+}
+
+/// Disables caching frames, keeping the cached frame.
+pub fn disable() {
+    CACHE.with(|cache| cache.borrow_mut().options = None);
+}
+
+/// Removes the cached frame.
+pub fn clear(options: &FrameCacheOptions) -> Result<(), Error> {
+    local_storage()?.remove_item(&options.key)?;
+    Ok(())
+}
+
+/// Stores the frame on screen.
+///
+/// The frame is only read from its source and encoded here, so drawing
+/// frames doesn't copy them while the cache is enabled.
+fn save() -> Result<(), Error> {
+    let Some((key, source)) = CACHE.with(|cache| {
+        let cache = cache.borrow();
+        Some((cache.options.as_ref()?.key.clone(), cache.source.clone()?))
+    }) else {
+        return Ok(());
+    };
+    // outside of the borrow, the source may be drawing
+    if let Some(frame) = source() {
+        local_storage()?.set_item(&key, &encode_buffer(&frame))?;
+    }
+    Ok(())
+}
+
+/// Creates the grid element of a frame, with the structure of the
+/// [`DomBackend`](crate::DomBackend).
+///
+/// The symbols are set as text, so a tampered frame can't inject markup.
+fn paint(buffer: &Buffer, grid_id: &str) -> Result<Element, Error> {
+    let document = get_document()?;
+    let grid = document.create_element("div")?;
+    grid.set_id(grid_id);
+    let area = buffer.area;
+    for y in area.top()..area.bottom() {
+        let pre = document.create_element("pre")?;
+        pre.unchecked_ref::<HtmlElement>()
+            .style()
+            .set_property("height", "15px")?;
+        let mut skip = false;
+        for x in area.left()..area.right() {
+            let span = document.create_element("span")?;
+            let cell = &buffer[(x, y)];
+            // the cell after a fullwidth glyph stays empty
+            if !std::mem::take(&mut skip) {
+                span.unchecked_ref::<HtmlElement>()
+                    .style()
+                    .set_css_text(&get_cell_style_as_css(cell));
+                span.set_text_content(Some(cell.symbol()));
+                skip = cell.symbol().width() == 2;
+            }
+            pre.append_child(&span)?;
+        }
+        grid.append_child(&pre)?;
+    }
+    Ok(grid)
+}
+
+/// Sets the source of the frame on screen, stored when the page is left.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+pub(crate) fn set_source<F>(source: F)
+where
+    F: Fn() -> Option<Buffer> + 'static,
+{
+    CACHE.with(|cache| cache.borrow_mut().source = Some(Rc::new(source)));
+}
+
+/// Removes the painted cached frame once the app drew a frame.
+pub(crate) fn record_frame() {
+    let preview = CACHE.with(|cache| cache.borrow_mut().preview.take());
+    if let Some(preview) = preview {
+        preview.remove();
+    }
+}
+
+#[cfg(all(test, feature = "dom"))]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Returns the options of a cache painting a grid with the given id.
+    fn options(grid_id: &str) -> FrameCacheOptions {
+        FrameCacheOptions::new()
+            .key(&format!("ratzilla-test-{grid_id}"))
+            .grid_id(grid_id)
+    }
+
+    #[wasm_bindgen_test]
+    fn test_save_restore_and_paint() {
+        let options = options("cached-grid");
+        set_source(|| Some(Buffer::with_lines(["abc", "<i>"])));
+        enable(options.clone()).expect("failed to enable the cache");
+        save().expect("failed to save the frame");
+
+        assert_eq!(restore(&options).ok(), Some(true));
+        let document = get_document().expect("failed to get the document");
+        let grid = document
+            .get_element_by_id("cached-grid")
+            .expect("the frame isn't painted");
+        assert_eq!(grid.child_element_count(), 2);
+        assert_eq!(grid.text_content().as_deref(), Some("abc<i>"));
+        // the symbols are text, not markup
+        assert_eq!(grid.query_selector("i").ok().flatten(), None);
+        // a painted grid isn't painted again
+        assert_eq!(restore(&options).ok(), Some(false));
+
+        record_frame();
+        assert!(document.get_element_by_id("cached-grid").is_none());
+        clear(&options).expect("failed to clear the frame");
+        teardown::destroy();
+    }
+
+    #[wasm_bindgen_test]
+    fn test_restore_ignores_invalid_frames() {
+        let options = options("invalid-grid");
+        let encoded = encode_buffer(&Buffer::with_lines(["ab"]));
+        let tampered = encoded.replacen("Reset", "Plaid", 1);
+        let truncated = &encoded[..encoded.len() / 2];
+        let storage = local_storage().expect("failed to get the storage");
+        for frame in ["garbage", "65535,65535", truncated, &tampered] {
+            storage
+                .set_item(&options.key, frame)
+                .expect("failed to store the frame");
+            assert_eq!(restore(&options).ok(), Some(false), "{frame:?}");
+        }
+        assert!(get_document()
+            .expect("failed to get the document")
+            .get_element_by_id("invalid-grid")
+            .is_none());
+        clear(&options).expect("failed to clear the frame");
+    }
+}
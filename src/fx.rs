@@ -0,0 +1,206 @@
+//! ## Effects
+//!
+//! Integration with [tachyonfx], enabled with the `tachyonfx` feature.
+//!
+//! [`EffectScheduler`] drives an [`EffectManager`] with the real time elapsed
+//! between animation frames, so effects play at the same speed regardless of
+//! the frame rate of the browser. Effects can also be bound to key events or
+//! named triggers, which saves the bookkeeping that every app with effects
+//! ends up writing by hand.
+//!
+//! ```rust no_run
+//! use ratzilla::{event::KeyCode, fx::EffectScheduler, WebRenderer};
+//! use tachyonfx::{fx, Interpolation};
+//! # fn example(terminal: ratzilla::ratatui::Terminal<ratzilla::DomBackend>) {
+//! use std::{cell::RefCell, rc::Rc};
+//!
+//! let scheduler = Rc::new(RefCell::new(EffectScheduler::<u8>::new()));
+//! scheduler
+//!     .borrow_mut()
+//!     .on_key(KeyCode::Enter, || fx::coalesce((500, Interpolation::SineOut)));
+//!
+//! terminal.on_key_event({
+//!     let scheduler = scheduler.clone();
+//!     move |event| scheduler.borrow_mut().handle_key_event(&event)
+//! });
+//! terminal.draw_web(move |frame| {
+//!     // render widgets...
+//!     scheduler.borrow_mut().process(frame);
+//! });
+//! # }
+//! ```
+//!
+//! [tachyonfx]: https://github.com/junkdog/tachyonfx
+
+use std::{collections::HashMap, fmt::Debug};
+
+use ratatui::{layout::Rect, Frame};
+use tachyonfx::{Duration, Effect, EffectManager};
+
+use crate::{
+    adaptive,
+    backend::utils::get_document,
+    clock,
+    event::{KeyCode, KeyEvent},
+};
+
+/// Upper bound for the time passed to effects in a single frame, in milliseconds.
+///
+/// Browsers stop calling `requestAnimationFrame` for background tabs; without
+/// clamping, every effect would jump to its end once the tab is visible again.
+const MAX_FRAME_DELTA_MS: f64 = 100.0;
+
+/// Creates a new effect when triggered.
+type EffectFactory = Box<dyn Fn() -> Effect>;
+
+/// Schedules [tachyonfx] effects using the real frame delta time.
+///
+/// The type parameter `K` is the key type of the underlying [`EffectManager`],
+/// used to replace running effects with [`EffectScheduler::add_unique_effect`].
+///
+/// [tachyonfx]: https://github.com/junkdog/tachyonfx
+pub struct EffectScheduler<K: Clone + Ord + Default + Debug + 'static> {
+    /// The underlying effect manager.
+    manager: EffectManager<K>,
+    /// Timestamp of the last processed frame, in milliseconds.
+    last_frame: Option<f64>,
+    /// Whether the effects are paused.
+    paused: bool,
+    /// Effects triggered by key presses.
+    key_triggers: Vec<(KeyCode, EffectFactory)>,
+    /// Effects triggered by name.
+    named_triggers: HashMap<String, EffectFactory>,
+}
+
+impl<K: Clone + Ord + Default + Debug + 'static> Default for EffectScheduler<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + Ord + Default + Debug + 'static> EffectScheduler<K> {
+    /// Constructs a new [`EffectScheduler`].
+    pub fn new() -> Self {
+        Self {
+            manager: EffectManager::default(),
+            last_frame: None,
+            paused: false,
+            key_triggers: Vec::new(),
+            named_triggers: HashMap::new(),
+        }
+    }
+
+    /// Adds an effect.
+    pub fn add_effect(&mut self, effect: Effect) {
+        self.manager.add_effect(effect);
+    }
+
+    /// Adds an effect, replacing any running effect with the same key.
+    pub fn add_unique_effect(&mut self, key: K, effect: Effect) {
+        self.manager.add_unique_effect(key, effect);
+    }
+
+    /// Returns a mutable reference to the underlying [`EffectManager`].
+    pub fn manager_mut(&mut self) -> &mut EffectManager<K> {
+        &mut self.manager
+    }
+
+    /// Starts a new effect every time the given key is pressed.
+    pub fn on_key<F>(&mut self, code: KeyCode, factory: F)
+    where
+        F: Fn() -> Effect + 'static,
+    {
+        self.key_triggers.push((code, Box::new(factory)));
+    }
+
+    /// Registers an effect that is started by [`EffectScheduler::trigger`].
+    pub fn on_trigger<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn() -> Effect + 'static,
+    {
+        self.named_triggers
+            .insert(name.to_string(), Box::new(factory));
+    }
+
+    /// Starts the effects bound to the code of the given key event.
+    pub fn handle_key_event(&mut self, event: &KeyEvent) {
+        let effects: Vec<Effect> = self
+            .key_triggers
+            .iter()
+            .filter(|(code, _)| *code == event.code)
+            .map(|(_, factory)| factory())
+            .collect();
+        effects.into_iter().for_each(|e| self.manager.add_effect(e));
+    }
+
+    /// Starts the effect registered under the given name.
+    ///
+    /// Returns `false` if there is no such effect.
+    pub fn trigger(&mut self, name: &str) -> bool {
+        match self.named_triggers.get(name) {
+            Some(factory) => {
+                let effect = factory();
+                self.manager.add_effect(effect);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Pauses all effects.
+    ///
+    /// Paused effects keep being rendered in their current state.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the effects.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        // don't count the paused time as elapsed
+        self.last_frame = None;
+    }
+
+    /// Returns `true` if the effects are paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Processes the effects over the whole frame.
+    ///
+    /// Call this at the end of the [`draw_web`] callback, after rendering
+    /// the widgets the effects should be applied to.
+    ///
+    /// [`draw_web`]: crate::WebRenderer::draw_web
+    pub fn process(&mut self, frame: &mut Frame) {
+        let area = frame.area();
+        self.process_area(frame, area);
+    }
+
+    /// Processes the effects over the given area of the frame.
+    ///
+    /// The effects are paused while the [adaptive quality](crate::adaptive)
+    /// level disables them.
+    pub fn process_area(&mut self, frame: &mut Frame, area: Rect) {
+        let elapsed = self.elapsed();
+        if !adaptive::quality().effects() {
+            return;
+        }
+        self.manager
+            .process_effects(elapsed, frame.buffer_mut(), area);
+    }
+
+    /// Returns the time elapsed since the last processed frame.
+    fn elapsed(&mut self) -> Duration {
+        let now = clock::now();
+        let hidden = get_document().map(|d| d.hidden()).unwrap_or(false);
+
+        let delta = match self.last_frame {
+            Some(last) if !self.paused && !hidden => (now - last).clamp(0.0, MAX_FRAME_DELTA_MS),
+            _ => 0.0,
+        };
+        self.last_frame = Some(now);
+
+        Duration::from_millis(delta as u32)
+    }
+}
@@ -0,0 +1,201 @@
+use crate::event::{Gesture, SwipeDirection};
+
+/// Time a touch must be held without moving to be a long press, in
+/// milliseconds.
+pub(crate) const LONG_PRESS_MS: f64 = 500.0;
+
+/// Longest duration of a tap, in milliseconds.
+const TAP_MAX_MS: f64 = 300.0;
+
+/// Distance a touch can move and still be a tap or a long press, in pixels.
+const MOVE_TOLERANCE: f64 = 10.0;
+
+/// Shortest distance of a swipe, in pixels.
+const SWIPE_MIN_DISTANCE: f64 = 30.0;
+
+/// Recognizes gestures from the touches on screen.
+///
+/// A gesture starts with the first touch and ends when the last touch is
+/// lifted. Positions are in pixels.
+#[derive(Debug, Default)]
+pub(crate) struct GestureRecognizer {
+    /// Time and position of the first touch of the gesture.
+    start: Option<(f64, (f64, f64))>,
+    /// Distance between the first two touches when the second one started.
+    pinch_distance: Option<f64>,
+    /// Whether the touches moved beyond the tolerance or were more than one.
+    moved: bool,
+    /// Whether the gesture was a long press.
+    long_pressed: bool,
+    /// Number of gestures started, to match the long press timers.
+    generation: u32,
+}
+
+impl GestureRecognizer {
+    /// Handles touches starting, given all touches on screen.
+    ///
+    /// Returns the generation of the gesture, to pass to
+    /// [`long_press`](Self::long_press) once [`LONG_PRESS_MS`] passed.
+    pub(crate) fn start(&mut self, time: f64, touches: &[(f64, f64)]) -> u32 {
+        if self.start.is_none() {
+            if let Some(&first) = touches.first() {
+                self.generation = self.generation.wrapping_add(1);
+                self.start = Some((time, first));
+                self.moved = false;
+                self.long_pressed = false;
+            }
+        }
+        if touches.len() > 1 {
+            self.moved = true;
+            self.pinch_distance = distance(touches);
+        }
+        self.generation
+    }
+
+    /// Handles touches moving, given all touches on screen.
+    ///
+    /// Returns a pinch gesture if two touches moved.
+    pub(crate) fn moved(&mut self, touches: &[(f64, f64)]) -> Option<Gesture> {
+        let (_, origin) = self.start?;
+        if let Some(&first) = touches.first() {
+            if length(first.0 - origin.0, first.1 - origin.1) > MOVE_TOLERANCE {
+                self.moved = true;
+            }
+        }
+        let start = self.pinch_distance?;
+        let current = distance(touches)?;
+        (start > 0.0).then(|| Gesture::Pinch(current / start))
+    }
+
+    /// Returns a long press if the gesture of the given generation is still
+    /// held without moving.
+    pub(crate) fn long_press(&mut self, generation: u32) -> Option<Gesture> {
+        if self.start.is_none() || self.generation != generation || self.moved {
+            return None;
+        }
+        self.long_pressed = true;
+        Some(Gesture::LongPress)
+    }
+
+    /// Handles touches ending, given the position of the lifted touch and
+    /// the number of touches left on screen.
+    ///
+    /// Returns a tap or a swipe once the last touch is lifted.
+    pub(crate) fn end(
+        &mut self,
+        time: f64,
+        position: (f64, f64),
+        remaining: usize,
+    ) -> Option<Gesture> {
+        if remaining > 0 {
+            self.pinch_distance = None;
+            return None;
+        }
+        let (start_time, origin) = self.start.take()?;
+        self.pinch_distance = None;
+        if self.long_pressed {
+            return None;
+        }
+        let (dx, dy) = (position.0 - origin.0, position.1 - origin.1);
+        if !self.moved && time - start_time <= TAP_MAX_MS {
+            return Some(Gesture::Tap);
+        }
+        if length(dx, dy) < SWIPE_MIN_DISTANCE {
+            return None;
+        }
+        let direction = if dx.abs() > dy.abs() {
+            if dx > 0.0 {
+                SwipeDirection::Right
+            } else {
+                SwipeDirection::Left
+            }
+        } else if dy > 0.0 {
+            SwipeDirection::Down
+        } else {
+            SwipeDirection::Up
+        };
+        Some(Gesture::Swipe(direction))
+    }
+
+    /// Forgets the current gesture, e.g. when the touches are cancelled.
+    pub(crate) fn cancel(&mut self) {
+        self.start = None;
+        self.pinch_distance = None;
+    }
+}
+
+/// Returns the distance between the first two touches.
+fn distance(touches: &[(f64, f64)]) -> Option<f64> {
+    match touches {
+        [a, b, ..] => Some(length(b.0 - a.0, b.1 - a.1)),
+        _ => None,
+    }
+}
+
+/// Returns the length of a vector.
+fn length(dx: f64, dy: f64) -> f64 {
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tap() {
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.start(0.0, &[(10.0, 10.0)]);
+        assert_eq!(recognizer.moved(&[(12.0, 11.0)]), None);
+        assert_eq!(recognizer.end(100.0, (12.0, 11.0), 0), Some(Gesture::Tap));
+        // too slow for a tap
+        recognizer.start(200.0, &[(10.0, 10.0)]);
+        assert_eq!(recognizer.end(600.0, (10.0, 10.0), 0), None);
+    }
+
+    #[test]
+    fn test_long_press() {
+        let mut recognizer = GestureRecognizer::default();
+        let generation = recognizer.start(0.0, &[(10.0, 10.0)]);
+        assert_eq!(recognizer.long_press(generation), Some(Gesture::LongPress));
+        assert_eq!(recognizer.end(800.0, (10.0, 10.0), 0), None);
+        // the timer of an earlier gesture
+        recognizer.start(1000.0, &[(10.0, 10.0)]);
+        assert_eq!(recognizer.long_press(generation), None);
+        // moved away
+        let generation = recognizer.start(2000.0, &[(10.0, 10.0)]);
+        recognizer.moved(&[(40.0, 10.0)]);
+        assert_eq!(recognizer.long_press(generation), None);
+    }
+
+    #[test]
+    fn test_swipe() {
+        let mut recognizer = GestureRecognizer::default();
+        for (end, direction) in [
+            ((100.0, 110.0), SwipeDirection::Right),
+            ((-100.0, 90.0), SwipeDirection::Left),
+            ((10.0, 200.0), SwipeDirection::Down),
+            ((0.0, -50.0), SwipeDirection::Up),
+        ] {
+            recognizer.start(0.0, &[(10.0, 100.0)]);
+            recognizer.moved(&[end]);
+            assert_eq!(
+                recognizer.end(150.0, end, 0),
+                Some(Gesture::Swipe(direction))
+            );
+        }
+    }
+
+    #[test]
+    fn test_pinch() {
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.start(0.0, &[(0.0, 0.0)]);
+        recognizer.start(10.0, &[(0.0, 0.0), (100.0, 0.0)]);
+        assert_eq!(
+            recognizer.moved(&[(0.0, 0.0), (200.0, 0.0)]),
+            Some(Gesture::Pinch(2.0))
+        );
+        assert_eq!(recognizer.end(100.0, (200.0, 0.0), 1), None);
+        assert_eq!(recognizer.moved(&[(0.0, 0.0)]), None);
+        assert_eq!(recognizer.end(200.0, (0.0, 0.0), 0), None);
+    }
+}
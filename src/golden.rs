@@ -0,0 +1,605 @@
+//! ## Golden images
+//!
+//! Enabled with the `golden` feature.
+//!
+//! Helpers to catch rendering regressions of the backends: a frame is drawn
+//! with the [`CanvasBackend`] into an offscreen canvas, read back as an
+//! [`Image`], and compared against a stored golden PNG with a perceptual
+//! color difference, so that small antialiasing differences don't fail the
+//! test.
+//!
+//! ```rust no_run
+//! use ratzilla::{
+//!     golden::{assert_golden, render_to_image, GoldenOptions},
+//!     ratatui::{layout::Size, widgets::Paragraph},
+//! };
+//! # let golden_png: &[u8] = &[];
+//!
+//! let image = render_to_image(Size::new(20, 2), |frame| {
+//!     frame.render_widget(Paragraph::new("hello"), frame.area());
+//! })
+//! .unwrap();
+//! assert_golden(
+//!     "hello",
+//!     &image,
+//!     // e.g. include_bytes!("../tests/golden/hello.png")
+//!     golden_png,
+//!     &GoldenOptions::new(),
+//! );
+//! ```
+//!
+//! On failure, [`assert_golden`] logs the actual image and an image of the
+//! differences to the console as `data:` URLs, which can be opened in the
+//! browser or saved as the new golden image.
+//!
+//! Glyphs are rasterized by the browser with the fonts of the system, so
+//! golden images should be generated on the machine that runs the tests,
+//! e.g. the CI runner.
+//!
+//! [`CanvasBackend`]: crate::CanvasBackend
+//! [`Image`]: crate::golden::Image
+//! [`assert_golden`]: crate::golden::assert_golden
+
+use std::fmt;
+
+use ratatui::{layout::Size, Frame, Terminal};
+use web_sys::{
+    wasm_bindgen::{JsCast, JsValue},
+    CanvasRenderingContext2d, HtmlCanvasElement,
+};
+
+use crate::{
+    backend::{
+        canvas::{CanvasBackendOptions, CELL_HEIGHT, CELL_WIDTH},
+        utils::{get_document, get_window},
+    },
+    error::Error,
+    utils::compression::{compress_sync, crc32, decompress_sync, CompressionFormat},
+    CanvasBackend,
+};
+
+/// Id of the offscreen element the frames are rendered in.
+const GRID_ID: &str = "ratzilla-golden";
+
+/// Signature of PNG files.
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Maximum color difference in the YIQ color space, between black and white.
+const MAX_YIQ_DELTA: f64 = 35215.0;
+
+/// An RGBA image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    /// Width, in pixels.
+    width: u32,
+    /// Height, in pixels.
+    height: u32,
+    /// RGBA pixels, row by row.
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Constructs a new [`Image`] from RGBA pixels, row by row.
+    ///
+    /// Returns `None` if the number of pixels doesn't match the size.
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Option<Self> {
+        (pixels.len() == width as usize * height as usize * 4).then_some(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Returns the width, in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height, in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the RGBA pixels, row by row.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Returns the RGBA color of a pixel.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[index..index + 4].try_into().ok()
+    }
+
+    /// Encodes the image as an 8-bit RGBA PNG.
+    pub fn to_png(&self) -> Vec<u8> {
+        let stride = self.width as usize * 4;
+        let mut scanlines = Vec::with_capacity((stride + 1) * self.height as usize);
+        for row in self.pixels.chunks_exact(stride.max(1)) {
+            // no filtering
+            scanlines.push(0);
+            scanlines.extend_from_slice(row);
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend(self.width.to_be_bytes());
+        header.extend(self.height.to_be_bytes());
+        // bit depth, RGBA, compression, filter and interlace methods
+        header.extend([8, 6, 0, 0, 0]);
+
+        let mut png = PNG_SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(
+            &mut png,
+            b"IDAT",
+            &compress_sync(&scanlines, CompressionFormat::Deflate),
+        );
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    /// Decodes an 8-bit RGB or RGBA PNG without interlacing.
+    pub fn from_png(data: &[u8]) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidImage(reason.to_string());
+        let mut data = data
+            .strip_prefix(&PNG_SIGNATURE)
+            .ok_or_else(|| invalid("not a PNG file"))?;
+
+        let mut header = None;
+        let mut compressed = Vec::new();
+        while data.len() >= 12 {
+            let length = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+            let kind = &data[4..8];
+            let body = data
+                .get(8..)
+                .and_then(|rest| rest.get(..length))
+                .ok_or_else(|| invalid("truncated chunk"))?;
+            match kind {
+                b"IHDR" if body.len() == 13 => header = Some(body),
+                b"IDAT" => compressed.extend_from_slice(body),
+                b"IEND" => break,
+                _ => {}
+            }
+            data = data
+                .get(12 + length..)
+                .ok_or_else(|| invalid("truncated chunk"))?;
+        }
+
+        let header = header.ok_or_else(|| invalid("missing header"))?;
+        let width = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        let height = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+        let channels = match (header[8], header[9], header[12]) {
+            (8, 2, 0) => 3,
+            (8, 6, 0) => 4,
+            _ => return Err(invalid("unsupported format")),
+        };
+
+        let scanlines = decompress_sync(&compressed, CompressionFormat::Deflate)?;
+        let stride = width as usize * channels;
+        if scanlines.len() < (stride + 1) * height as usize {
+            return Err(invalid("truncated image data"));
+        }
+        let mut raw = vec![0; stride * height as usize];
+        for y in 0..height as usize {
+            let line = &scanlines[y * (stride + 1)..(y + 1) * (stride + 1)];
+            let (previous, current) = raw.split_at_mut(y * stride);
+            let previous = (y > 0).then(|| &previous[(y - 1) * stride..]);
+            unfilter(
+                line[0],
+                &line[1..],
+                previous,
+                &mut current[..stride],
+                channels,
+            )
+            .ok_or_else(|| invalid("invalid filter"))?;
+        }
+
+        let pixels = if channels == 4 {
+            raw
+        } else {
+            raw.chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect()
+        };
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Returns the image as a `data:` URL of a PNG.
+    pub fn to_data_url(&self) -> Result<String, Error> {
+        // `btoa` encodes the bytes of a binary string
+        let binary: String = self.to_png().into_iter().map(char::from).collect();
+        Ok(format!(
+            "data:image/png;base64,{}",
+            get_window()?.btoa(&binary)?
+        ))
+    }
+}
+
+/// Appends a PNG chunk.
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], body: &[u8]) {
+    png.extend((body.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(body);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+/// Reverses the filter of a PNG scanline.
+///
+/// Returns `None` if the filter is unknown.
+fn unfilter(
+    filter: u8,
+    line: &[u8],
+    previous: Option<&[u8]>,
+    out: &mut [u8],
+    bpp: usize,
+) -> Option<()> {
+    for i in 0..line.len() {
+        let left = if i >= bpp { out[i - bpp] } else { 0 };
+        let up = previous.map_or(0, |p| p[i]);
+        let up_left = match previous {
+            Some(p) if i >= bpp => p[i - bpp],
+            _ => 0,
+        };
+        let predictor = match filter {
+            0 => 0,
+            1 => left,
+            2 => up,
+            3 => ((left as u16 + up as u16) / 2) as u8,
+            4 => paeth(left, up, up_left),
+            _ => return None,
+        };
+        out[i] = line[i].wrapping_add(predictor);
+    }
+    Some(())
+}
+
+/// The Paeth predictor of PNG.
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Options of the golden image comparison.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoldenOptions {
+    /// Color difference above which pixels differ, from 0 to 1.
+    threshold: f64,
+    /// Ratio of differing pixels above which the images differ.
+    max_diff_ratio: f64,
+}
+
+impl Default for GoldenOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GoldenOptions {
+    /// Constructs new [`GoldenOptions`], tolerating small color differences
+    /// and no differing pixels.
+    pub fn new() -> Self {
+        Self {
+            threshold: 0.1,
+            max_diff_ratio: 0.0,
+        }
+    }
+
+    /// Sets the color difference above which pixels differ, from 0 (exact)
+    /// to 1 (anything goes).
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the ratio of differing pixels tolerated, from 0 to 1.
+    pub fn max_diff_ratio(mut self, ratio: f64) -> Self {
+        self.max_diff_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// The differences between two images of the same size.
+#[derive(Debug, Clone)]
+pub struct ImageDiff {
+    /// Number of differing pixels.
+    pub different_pixels: usize,
+    /// Number of pixels.
+    pub total_pixels: usize,
+    /// The expected image faded out, with the differing pixels in red.
+    pub image: Image,
+}
+
+impl ImageDiff {
+    /// Returns the ratio of differing pixels, from 0 to 1.
+    pub fn ratio(&self) -> f64 {
+        if self.total_pixels == 0 {
+            return 0.0;
+        }
+        self.different_pixels as f64 / self.total_pixels as f64
+    }
+}
+
+/// Compares two images with a perceptual color difference.
+///
+/// Returns `None` if the images have different sizes.
+pub fn compare(actual: &Image, expected: &Image, threshold: f64) -> Option<ImageDiff> {
+    if (actual.width, actual.height) != (expected.width, expected.height) {
+        return None;
+    }
+    let max_delta = MAX_YIQ_DELTA * threshold * threshold;
+    let mut different_pixels = 0;
+    let mut pixels = Vec::with_capacity(expected.pixels.len());
+    for (a, e) in actual
+        .pixels
+        .chunks_exact(4)
+        .zip(expected.pixels.chunks_exact(4))
+    {
+        if yiq_delta(a, e) > max_delta {
+            different_pixels += 1;
+            pixels.extend([255, 0, 0, 255]);
+        } else {
+            let [r, g, b] = blend_white(e);
+            let y = luminance(r, g, b);
+            // keep a faint copy of the image for context
+            let faded = (255.0 + (y - 255.0) * 0.1) as u8;
+            pixels.extend([faded, faded, faded, 255]);
+        }
+    }
+    Some(ImageDiff {
+        different_pixels,
+        total_pixels: pixels.len() / 4,
+        image: Image {
+            width: expected.width,
+            height: expected.height,
+            pixels,
+        },
+    })
+}
+
+/// Blends an RGBA color with a white background.
+fn blend_white(rgba: &[u8]) -> [f64; 3] {
+    let alpha = rgba[3] as f64 / 255.0;
+    [0, 1, 2].map(|i| 255.0 + (rgba[i] as f64 - 255.0) * alpha)
+}
+
+/// Returns the luminance (Y of YIQ) of a color.
+fn luminance(r: f64, g: f64, b: f64) -> f64 {
+    r * 0.298_895_31 + g * 0.586_622_47 + b * 0.114_482_23
+}
+
+/// Returns the squared color difference of two RGBA colors in the YIQ color
+/// space, weighted by the sensitivity of the eye.
+fn yiq_delta(a: &[u8], b: &[u8]) -> f64 {
+    if a == b {
+        return 0.0;
+    }
+    let [r1, g1, b1] = blend_white(a);
+    let [r2, g2, b2] = blend_white(b);
+    let y = luminance(r1, g1, b1) - luminance(r2, g2, b2);
+    let i = (r1 - r2) * 0.595_977_99 - (g1 - g2) * 0.274_176_10 - (b1 - b2) * 0.321_801_89;
+    let q = (r1 - r2) * 0.211_470_17 - (g1 - g2) * 0.522_617_11 + (b1 - b2) * 0.311_146_94;
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
+
+/// A frame that doesn't match its golden image.
+#[derive(Debug, Clone)]
+pub struct GoldenMismatch {
+    /// The rendered image.
+    pub actual: Image,
+    /// The differences, or `None` if the golden image is missing, invalid or
+    /// has a different size.
+    pub diff: Option<ImageDiff>,
+}
+
+impl fmt::Display for GoldenMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.diff {
+            Some(diff) => write!(
+                f,
+                "{} of {} pixels differ ({:.3}%)",
+                diff.different_pixels,
+                diff.total_pixels,
+                diff.ratio() * 100.0
+            ),
+            None => write!(
+                f,
+                "no golden image of {}x{} pixels",
+                self.actual.width, self.actual.height
+            ),
+        }
+    }
+}
+
+/// Compares an image against a golden PNG.
+pub fn check_golden(
+    actual: &Image,
+    golden_png: &[u8],
+    options: &GoldenOptions,
+) -> Result<(), GoldenMismatch> {
+    let diff = Image::from_png(golden_png)
+        .ok()
+        .and_then(|expected| compare(actual, &expected, options.threshold));
+    match diff {
+        Some(diff) if diff.ratio() <= options.max_diff_ratio => Ok(()),
+        diff => Err(GoldenMismatch {
+            actual: actual.clone(),
+            diff,
+        }),
+    }
+}
+
+/// Asserts that an image matches a golden PNG.
+///
+/// On failure, the actual image and the differences are logged to the
+/// console as `data:` URLs before panicking.
+pub fn assert_golden(name: &str, actual: &Image, golden_png: &[u8], options: &GoldenOptions) {
+    let Err(mismatch) = check_golden(actual, golden_png, options) else {
+        return;
+    };
+    let log = |label: &str, image: &Image| {
+        if let Ok(url) = image.to_data_url() {
+            web_sys::console::error_1(&format!("{name}: {label}: {url}").into());
+        }
+    };
+    log("actual image", &mismatch.actual);
+    if let Some(diff) = &mismatch.diff {
+        log("diff image", &diff.image);
+    }
+    panic!("golden image mismatch for {name}: {mismatch}");
+}
+
+/// Renders a frame of the given size, in cells, with the [`CanvasBackend`]
+/// and returns the drawn pixels.
+///
+/// The canvas is drawn in an offscreen element that is removed afterwards.
+pub fn render_to_image<F>(size: Size, render_callback: F) -> Result<Image, Error>
+where
+    F: FnOnce(&mut Frame),
+{
+    let document = get_document()?;
+    let container = document.create_element("div")?;
+    container.set_id(GRID_ID);
+    container.set_attribute("style", "position: fixed; left: -100000px; top: 0")?;
+    document
+        .body()
+        .ok_or(Error::UnableToRetrieveBody)?
+        .append_child(&container)?;
+    let image = render_in(&container, size, render_callback);
+    container.remove();
+    image
+}
+
+/// Renders a frame in the given container element.
+fn render_in<F>(
+    container: &web_sys::Element,
+    size: Size,
+    render_callback: F,
+) -> Result<Image, Error>
+where
+    F: FnOnce(&mut Frame),
+{
+    // the canvas backend leaves a margin of one cell, see `CanvasBackend::size`
+    let width = ((size.width as f64 + 1.0) * CELL_WIDTH) as u32;
+    let height = ((size.height as f64 + 1.0) * CELL_HEIGHT) as u32;
+    let backend = CanvasBackend::new_with_options(
+        CanvasBackendOptions::new()
+            .grid_id(GRID_ID)
+            .size((width, height))
+            // the same pixels on every display
+            .pixel_ratio(1.0),
+    )?;
+    let mut terminal = Terminal::new(backend).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    terminal
+        .draw(render_callback)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    let canvas = container
+        .first_element_child()
+        .and_then(|canvas| canvas.dyn_into::<HtmlCanvasElement>().ok())
+        .ok_or(Error::UnableToRetrieveComponent("canvas"))?;
+    let context = canvas
+        .get_context("2d")?
+        .ok_or(Error::UnableToRetrieveCanvasContext)?
+        .dyn_into::<CanvasRenderingContext2d>()
+        .map_err(JsValue::from)?;
+    let data = context.get_image_data(0.0, 0.0, width as f64, height as f64)?;
+    Image::new(data.width(), data.height(), data.data().0)
+        .ok_or_else(|| Error::InvalidImage("unexpected image data size".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(pixels: &[[u8; 4]], width: u32) -> Image {
+        let height = pixels.len() as u32 / width;
+        Image::new(width, height, pixels.concat()).expect("valid size")
+    }
+
+    #[test]
+    fn test_png_round_trip() {
+        let original = image(
+            &[
+                [0, 0, 0, 255],
+                [255, 0, 0, 255],
+                [0, 255, 0, 128],
+                [10, 20, 30, 0],
+                [1, 2, 3, 4],
+                [255, 255, 255, 255],
+            ],
+            3,
+        );
+        let png = original.to_png();
+        assert!(png.starts_with(&PNG_SIGNATURE));
+        assert_eq!(Image::from_png(&png).expect("valid png"), original);
+        assert!(Image::from_png(&png[..20]).is_err());
+        assert!(Image::from_png(b"GIF89a").is_err());
+    }
+
+    #[test]
+    fn test_unfilter() {
+        let previous = [10, 20, 30, 40];
+        let mut out = [0; 4];
+        // up
+        unfilter(2, &[1, 1, 1, 1], Some(&previous), &mut out, 2).expect("valid filter");
+        assert_eq!(out, [11, 21, 31, 41]);
+        // sub
+        unfilter(1, &[1, 2, 3, 4], None, &mut out, 2).expect("valid filter");
+        assert_eq!(out, [1, 2, 4, 6]);
+        // paeth
+        unfilter(4, &[0, 0, 0, 0], Some(&previous), &mut out, 2).expect("valid filter");
+        assert_eq!(out, [10, 20, 30, 40]);
+        assert!(unfilter(5, &[0], None, &mut out[..1], 1).is_none());
+    }
+
+    #[test]
+    fn test_compare() {
+        let expected = image(&[[0, 0, 0, 255], [255, 255, 255, 255]], 2);
+        let close = image(&[[3, 2, 1, 255], [255, 255, 255, 255]], 2);
+        let far = image(&[[0, 0, 0, 255], [255, 0, 0, 255]], 2);
+
+        assert_eq!(
+            compare(&close, &expected, 0.1)
+                .expect("same size")
+                .different_pixels,
+            0
+        );
+        assert_eq!(
+            compare(&close, &expected, 0.0)
+                .expect("same size")
+                .different_pixels,
+            1
+        );
+        let diff = compare(&far, &expected, 0.1).expect("same size");
+        assert_eq!(diff.different_pixels, 1);
+        assert_eq!(diff.ratio(), 0.5);
+        assert_eq!(diff.image.pixel(1, 0), Some([255, 0, 0, 255]));
+        assert!(compare(&far, &image(&[[0, 0, 0, 255]], 1), 0.1).is_none());
+
+        let options = GoldenOptions::new();
+        assert!(check_golden(&close, &expected.to_png(), &options).is_ok());
+        assert!(check_golden(&far, &expected.to_png(), &options).is_err());
+        assert!(check_golden(&far, &expected.to_png(), &options.max_diff_ratio(0.5)).is_ok());
+        assert!(check_golden(&far, &[], &options).is_err_and(|e| e.diff.is_none()));
+    }
+}
@@ -0,0 +1,267 @@
+//! ## Idle work
+//!
+//! [`IdleScheduler`] runs low priority work (cache warming, prefetching, log
+//! trimming, ...) while the browser is idle between animation frames, using
+//! [`requestIdleCallback`]. The work is split in small steps, and a step is
+//! only started if there is time left before the next frame, so it never
+//! makes the render loop miss a frame.
+//!
+//! A job is a closure called repeatedly with an [`IdleBudget`] until it
+//! returns `true`. Each call should do a bounded amount of work, and can
+//! check [`IdleBudget::time_remaining`] to do more when there is time.
+//!
+//! ```rust no_run
+//! use ratzilla::idle::IdleScheduler;
+//!
+//! let idle = IdleScheduler::new();
+//! let mut lines: Vec<String> = Vec::new();
+//! idle.add("trim-log", move |budget| {
+//!     while lines.len() > 1000 && !budget.should_yield() {
+//!         lines.remove(0);
+//!     }
+//!     lines.len() <= 1000
+//! });
+//! idle.add_once("prefetch", || {
+//!     // fetch the next page...
+//! });
+//! ```
+//!
+//! Browsers without `requestIdleCallback` run the jobs from a timeout, with
+//! a short time budget.
+//!
+//! [`requestIdleCallback`]: https://developer.mozilla.org/en-US/docs/Web/API/Window/requestIdleCallback
+//! [`IdleBudget`]: crate::idle::IdleBudget
+//! [`IdleBudget::time_remaining`]: crate::idle::IdleBudget::time_remaining
+//! [`IdleScheduler`]: crate::idle::IdleScheduler
+
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use web_sys::{
+    js_sys::Reflect,
+    wasm_bindgen::{prelude::Closure, JsCast, JsValue},
+    IdleDeadline,
+};
+
+use crate::backend::utils::{get_window, performance};
+
+/// Time left under which no job is started, in milliseconds.
+const MIN_SLICE_MS: f64 = 1.0;
+
+/// Time budget of the timeout used without `requestIdleCallback`, in
+/// milliseconds.
+const FALLBACK_BUDGET_MS: f64 = 5.0;
+
+/// Delay of the timeout used without `requestIdleCallback`, in milliseconds.
+const FALLBACK_DELAY_MS: i32 = 50;
+
+/// The time available to a job until the browser needs the main thread back.
+#[derive(Debug, Clone, Copy)]
+pub struct IdleBudget {
+    /// End of the idle period, in milliseconds.
+    deadline: f64,
+    /// Returns the current time, in milliseconds.
+    now: fn() -> f64,
+}
+
+impl IdleBudget {
+    /// Returns the time left in the idle period, in milliseconds.
+    pub fn time_remaining(&self) -> f64 {
+        (self.deadline - (self.now)()).max(0.0)
+    }
+
+    /// Returns `true` if the job should return to let the browser render.
+    pub fn should_yield(&self) -> bool {
+        self.time_remaining() < MIN_SLICE_MS
+    }
+}
+
+/// A job run while the browser is idle.
+type IdleJob = Box<dyn FnMut(&IdleBudget) -> bool>;
+
+/// State of the scheduler.
+#[derive(Default)]
+struct Inner {
+    /// Pending jobs and their names, in the order they run.
+    jobs: VecDeque<(String, IdleJob)>,
+    /// Whether an idle callback is requested.
+    requested: bool,
+}
+
+/// Runs low priority jobs while the browser is idle.
+///
+/// Cloning the scheduler returns a handle to the same jobs.
+#[derive(Clone, Default)]
+pub struct IdleScheduler {
+    /// Shared state.
+    inner: Rc<RefCell<Inner>>,
+}
+
+impl IdleScheduler {
+    /// Constructs a new [`IdleScheduler`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a job, called while the browser is idle until it returns `true`.
+    pub fn add<F>(&self, name: &str, job: F)
+    where
+        F: FnMut(&IdleBudget) -> bool + 'static,
+    {
+        self.inner
+            .borrow_mut()
+            .jobs
+            .push_back((name.to_string(), Box::new(job)));
+        self.request();
+    }
+
+    /// Adds a job called once, the next time the browser is idle.
+    pub fn add_once<F>(&self, name: &str, job: F)
+    where
+        F: FnOnce() + 'static,
+    {
+        let mut job = Some(job);
+        self.add(name, move |_| {
+            if let Some(job) = job.take() {
+                job();
+            }
+            true
+        });
+    }
+
+    /// Removes the pending jobs with the given name.
+    ///
+    /// Returns `false` if there is no such job.
+    pub fn cancel(&self, name: &str) -> bool {
+        let mut inner = self.inner.borrow_mut();
+        let count = inner.jobs.len();
+        inner.jobs.retain(|(job, _)| job != name);
+        inner.jobs.len() != count
+    }
+
+    /// Returns the number of pending jobs.
+    pub fn pending(&self) -> usize {
+        self.inner.borrow().jobs.len()
+    }
+
+    /// Runs jobs until they are all done or the budget is spent.
+    fn run(&self, budget: &IdleBudget) {
+        while !budget.should_yield() {
+            // jobs may add other jobs, so don't hold the borrow while running them
+            let Some((name, mut job)) = self.inner.borrow_mut().jobs.pop_front() else {
+                break;
+            };
+            if !job(budget) {
+                self.inner.borrow_mut().jobs.push_back((name, job));
+            }
+        }
+    }
+
+    /// Requests an idle callback to run the pending jobs, if needed.
+    fn request(&self) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            if inner.requested || inner.jobs.is_empty() {
+                return;
+            }
+            inner.requested = true;
+        }
+        let Ok(window) = get_window() else {
+            return;
+        };
+        let supported =
+            Reflect::has(&window, &JsValue::from_str("requestIdleCallback")).unwrap_or(false);
+
+        let scheduler = self.clone();
+        let requested = if supported {
+            let callback = Closure::once_into_js(move |deadline: IdleDeadline| {
+                let budget = IdleBudget {
+                    deadline: now() + deadline.time_remaining(),
+                    now,
+                };
+                scheduler.on_idle(&budget);
+            });
+            window
+                .request_idle_callback(callback.unchecked_ref())
+                .is_ok()
+        } else {
+            let callback = Closure::once_into_js(move || {
+                let budget = IdleBudget {
+                    deadline: now() + FALLBACK_BUDGET_MS,
+                    now,
+                };
+                scheduler.on_idle(&budget);
+            });
+            window
+                .set_timeout_with_callback_and_timeout_and_arguments_0(
+                    callback.unchecked_ref(),
+                    FALLBACK_DELAY_MS,
+                )
+                .is_ok()
+        };
+        self.inner.borrow_mut().requested = requested;
+    }
+
+    /// Runs the jobs in an idle period and requests another one if some are
+    /// left.
+    fn on_idle(&self, budget: &IdleBudget) {
+        self.inner.borrow_mut().requested = false;
+        self.run(budget);
+        self.request();
+    }
+}
+
+/// Returns the current time of the browser, in milliseconds.
+///
+/// Idle periods are given by the browser, so this doesn't use the
+/// [`clock`](crate::clock).
+fn now() -> f64 {
+    performance().map(|p| p.now()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    thread_local! {
+        static CLOCK: Cell<f64> = const { Cell::new(0.0) };
+    }
+
+    /// A clock advancing by 1ms every time it is read.
+    fn fake_now() -> f64 {
+        CLOCK.with(|c| {
+            c.set(c.get() + 1.0);
+            c.get()
+        })
+    }
+
+    #[test]
+    fn test_run_within_budget() {
+        let scheduler = IdleScheduler::new();
+        let steps = Rc::new(Cell::new(0));
+        // don't request idle callbacks, which need a browser
+        scheduler.inner.borrow_mut().requested = true;
+        scheduler.add("count", {
+            let steps = steps.clone();
+            move |_| {
+                steps.set(steps.get() + 1);
+                steps.get() == 10
+            }
+        });
+        scheduler.add_once("once", || {});
+        assert_eq!(scheduler.pending(), 2);
+
+        let budget = IdleBudget {
+            deadline: CLOCK.with(|c| c.get()) + 6.0,
+            now: fake_now,
+        };
+        scheduler.run(&budget);
+        assert_eq!(scheduler.pending(), 1);
+        assert!(steps.get() > 0 && steps.get() < 10);
+
+        assert!(scheduler.cancel("count"));
+        assert!(!scheduler.cancel("count"));
+        assert_eq!(scheduler.pending(), 0);
+    }
+}
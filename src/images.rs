@@ -0,0 +1,347 @@
+//! ## Images
+//!
+//! Widgets place raster images over a region of cells in the
+//! [`ImageRegistry`] of the frame, e.g. with the
+//! [`Image`](crate::widgets::Image) widget. The cells under an image are
+//! blank, and each backend shows the image over them:
+//!
+//! - The [`DomBackend`] and the [`WebGl2Backend`] position an `<img>` element
+//!   (or a `<canvas>` for [`Pixels`]) over the grid.
+//! - The [`CanvasBackend`] draws the image on its canvas.
+//!
+//! ```rust no_run
+//! use ratzilla::{
+//!     images::{self, ImageFit, ImageSource},
+//!     ratatui::layout::Rect,
+//! };
+//!
+//! let area = Rect::new(0, 0, 20, 10);
+//! images::register(area, ImageSource::url("logo.png"), ImageFit::Contain);
+//! ```
+//!
+//! The images of a frame are committed at the end of the [`draw_web`]
+//! callback, and replace the images of the previous frame.
+//!
+//! [`DomBackend`]: crate::DomBackend
+//! [`CanvasBackend`]: crate::CanvasBackend
+//! [`WebGl2Backend`]: crate::WebGl2Backend
+//! [`draw_web`]: crate::WebRenderer::draw_web
+//! [`ImageRegistry`]: crate::images::ImageRegistry
+//! [`Pixels`]: crate::images::Pixels
+
+use std::{
+    cell::{Cell, RefCell},
+    hash::{Hash, Hasher},
+    rc::Rc,
+};
+
+use ratatui::layout::Rect;
+
+use crate::error::Error;
+
+/// Decoded RGBA pixels of an image.
+///
+/// Cloning is cheap, the pixels are shared. Two [`Pixels`] are equal only if
+/// they are clones of each other, so backends upload the pixels once.
+#[derive(Debug, Clone)]
+pub struct Pixels {
+    /// Identifier of the pixels.
+    id: u64,
+    /// Width, in pixels.
+    width: u32,
+    /// Height, in pixels.
+    height: u32,
+    /// Four bytes per pixel, in rows.
+    data: Rc<[u8]>,
+}
+
+thread_local! {
+    static NEXT_PIXELS_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+impl Pixels {
+    /// Constructs new [`Pixels`] from rows of RGBA bytes.
+    ///
+    /// Returns an error if the image is empty or if the size of the data
+    /// doesn't match the size of the image.
+    pub fn new(width: u32, height: u32, data: impl Into<Rc<[u8]>>) -> Result<Self, Error> {
+        let data = data.into();
+        if width == 0 || height == 0 {
+            return Err(Error::InvalidImage("empty image".to_string()));
+        }
+        if data.len() as u64 != width as u64 * height as u64 * 4 {
+            return Err(Error::InvalidImage(format!(
+                "expected {} bytes of RGBA data for a {width}x{height} image, got {}",
+                width as u64 * height as u64 * 4,
+                data.len()
+            )));
+        }
+        let id = NEXT_PIXELS_ID.with(|id| id.replace(id.get() + 1));
+        Ok(Self {
+            id,
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Returns the width of the image, in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the image, in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the RGBA bytes of the image.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl PartialEq for Pixels {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Pixels {}
+
+impl Hash for Pixels {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// Where the pixels of an image come from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ImageSource {
+    /// An image loaded by the browser, in any format it supports.
+    Url(String),
+    /// Decoded pixels.
+    Pixels(Pixels),
+}
+
+impl ImageSource {
+    /// Constructs a new [`ImageSource::Url`].
+    pub fn url(url: impl Into<String>) -> Self {
+        Self::Url(url.into())
+    }
+}
+
+impl From<Pixels> for ImageSource {
+    fn from(pixels: Pixels) -> Self {
+        Self::Pixels(pixels)
+    }
+}
+
+/// A rectangle in pixels, as `(x, y, width, height)`.
+#[cfg(feature = "canvas")]
+pub(crate) type PixelRect = (f64, f64, f64, f64);
+
+/// How an image is scaled to the region of its cells.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFit {
+    /// Scales the image to fit the region, keeping its aspect ratio.
+    #[default]
+    Contain,
+    /// Scales the image to cover the region, keeping its aspect ratio and
+    /// cropping the rest.
+    Cover,
+    /// Stretches the image to the region.
+    Fill,
+}
+
+impl ImageFit {
+    /// Returns the value of the `object-fit` CSS property.
+    #[cfg(any(feature = "dom", feature = "webgl2", feature = "webgpu"))]
+    pub(crate) fn css(self) -> &'static str {
+        match self {
+            Self::Contain => "contain",
+            Self::Cover => "cover",
+            Self::Fill => "fill",
+        }
+    }
+
+    /// Returns the part of an image of the given size to draw, and where to
+    /// draw it in the target.
+    #[cfg(feature = "canvas")]
+    pub(crate) fn fit(self, image: (f64, f64), target: PixelRect) -> (PixelRect, PixelRect) {
+        let (image_width, image_height) = image;
+        let (x, y, width, height) = target;
+        let whole = (0.0, 0.0, image_width, image_height);
+        if image_width <= 0.0 || image_height <= 0.0 {
+            return (whole, target);
+        }
+        match self {
+            Self::Fill => (whole, target),
+            Self::Contain => {
+                let scale = (width / image_width).min(height / image_height);
+                let (fitted_width, fitted_height) = (image_width * scale, image_height * scale);
+                let fitted = (
+                    x + (width - fitted_width) / 2.0,
+                    y + (height - fitted_height) / 2.0,
+                    fitted_width,
+                    fitted_height,
+                );
+                (whole, fitted)
+            }
+            Self::Cover => {
+                let scale = (width / image_width).max(height / image_height);
+                let (source_width, source_height) = (width / scale, height / scale);
+                let source = (
+                    (image_width - source_width) / 2.0,
+                    (image_height - source_height) / 2.0,
+                    source_width,
+                    source_height,
+                );
+                (source, target)
+            }
+        }
+    }
+}
+
+/// An image on screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    /// Region of the cells under the image.
+    pub area: Rect,
+    /// The image.
+    pub source: ImageSource,
+    /// How the image is scaled to the region.
+    pub fit: ImageFit,
+}
+
+/// The images of a frame.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImageRegistry {
+    /// Images placed while rendering the next frame.
+    pending: Vec<Placement>,
+    /// Images of the frame on screen.
+    current: Vec<Placement>,
+    /// Incremented whenever the images on screen change.
+    generation: u64,
+}
+
+impl ImageRegistry {
+    /// Constructs a new, empty [`ImageRegistry`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Places an image for the frame being rendered.
+    ///
+    /// Images placed later are shown above the others where they overlap.
+    pub fn register(&mut self, area: Rect, source: ImageSource, fit: ImageFit) {
+        if !area.is_empty() {
+            self.pending.push(Placement { area, source, fit });
+        }
+    }
+
+    /// Makes the images placed since the last call the images on screen.
+    pub fn commit(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        if pending != self.current {
+            self.current = pending;
+            self.generation += 1;
+        }
+    }
+
+    /// Returns the images on screen.
+    pub fn placements(&self) -> &[Placement] {
+        &self.current
+    }
+
+    /// Returns a number that changes whenever the images on screen change.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+thread_local! {
+    static REGISTRY: RefCell<ImageRegistry> = RefCell::new(ImageRegistry::new());
+}
+
+/// Places an image for the frame being rendered.
+///
+/// See [`ImageRegistry::register`].
+pub fn register(area: Rect, source: ImageSource, fit: ImageFit) {
+    REGISTRY.with(|r| r.borrow_mut().register(area, source, fit));
+}
+
+/// Returns the images on screen.
+pub fn placements() -> Vec<Placement> {
+    REGISTRY.with(|r| r.borrow().placements().to_vec())
+}
+
+/// Makes the images placed since the last call the images on screen.
+pub(crate) fn commit() {
+    REGISTRY.with(|r| r.borrow_mut().commit());
+}
+
+/// Returns a number that changes whenever the images on screen change.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+pub(crate) fn generation() -> u64 {
+    REGISTRY.with(|r| r.borrow().generation())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixels() {
+        let pixels = Pixels::new(2, 1, vec![0; 8]).expect("valid size");
+        assert_eq!((pixels.width(), pixels.height()), (2, 1));
+        assert_eq!(pixels, pixels.clone());
+        assert_ne!(pixels, Pixels::new(2, 1, vec![0; 8]).expect("valid size"));
+        assert!(Pixels::new(2, 2, vec![0; 8]).is_err());
+        assert!(Pixels::new(0, 0, vec![]).is_err());
+    }
+
+    #[test]
+    fn test_commit() {
+        let mut registry = ImageRegistry::new();
+        let source = ImageSource::url("a.png");
+        registry.register(Rect::new(0, 0, 4, 2), source.clone(), ImageFit::Cover);
+        registry.register(Rect::new(0, 0, 0, 2), source.clone(), ImageFit::Cover);
+        assert!(registry.placements().is_empty());
+
+        registry.commit();
+        assert_eq!(registry.generation(), 1);
+        assert_eq!(registry.placements().len(), 1);
+
+        // the same images don't change the generation
+        registry.register(Rect::new(0, 0, 4, 2), source, ImageFit::Cover);
+        registry.commit();
+        assert_eq!(registry.generation(), 1);
+        registry.commit();
+        assert_eq!(registry.generation(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "canvas")]
+    fn test_fit() {
+        let target = (10.0, 0.0, 100.0, 50.0);
+        // a square image in a wide region
+        assert_eq!(
+            ImageFit::Contain.fit((20.0, 20.0), target),
+            ((0.0, 0.0, 20.0, 20.0), (35.0, 0.0, 50.0, 50.0))
+        );
+        assert_eq!(
+            ImageFit::Cover.fit((20.0, 20.0), target),
+            ((0.0, 5.0, 20.0, 10.0), target)
+        );
+        assert_eq!(
+            ImageFit::Fill.fit((20.0, 20.0), target),
+            ((0.0, 0.0, 20.0, 20.0), target)
+        );
+    }
+}
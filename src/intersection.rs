@@ -0,0 +1,208 @@
+//! ## Intersections
+//!
+//! An [`IntersectionTracker`] reports when regions of cells enter or leave
+//! the visible viewport of a scroll view, like the `IntersectionObserver`
+//! of the browser. This enables lazy loading and "mark as read" behaviors
+//! in long feeds:
+//!
+//! ```rust no_run
+//! use ratzilla::{
+//!     intersection::{IntersectionKind, IntersectionTracker},
+//!     ratatui::layout::Rect,
+//! };
+//!
+//! let mut tracker = IntersectionTracker::new().threshold(0.5);
+//! // regions are in the coordinates of the scrolled content
+//! for (index, y) in (0..100).step_by(4).enumerate() {
+//!     tracker.observe(index, Rect::new(0, y, 80, 4));
+//! }
+//!
+//! // after every scroll, with the visible part of the content
+//! let scroll_offset = 20;
+//! for event in tracker.update(Rect::new(0, scroll_offset, 80, 24)) {
+//!     if event.kind == IntersectionKind::Entered {
+//!         // mark the post `event.key` as read
+//!     }
+//! }
+//! ```
+//!
+//! [`IntersectionTracker`]: crate::intersection::IntersectionTracker
+
+use ratatui::layout::Rect;
+
+/// Whether a region entered or left the viewport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntersectionKind {
+    /// The region became visible.
+    Entered,
+    /// The region is no longer visible.
+    Left,
+}
+
+/// A region entering or leaving the viewport.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntersectionEvent<K> {
+    /// Key of the region.
+    pub key: K,
+    /// Whether the region entered or left the viewport.
+    pub kind: IntersectionKind,
+    /// Visible fraction of the region, from `0.0` to `1.0`.
+    pub ratio: f64,
+}
+
+/// A region observed by an [`IntersectionTracker`].
+#[derive(Debug, Clone)]
+struct Region<K> {
+    /// Key of the region.
+    key: K,
+    /// Area of the region, in content coordinates.
+    area: Rect,
+    /// Whether the region was visible at the last update.
+    visible: bool,
+}
+
+/// Tracks the visibility of cell regions in a viewport.
+#[derive(Debug, Clone)]
+pub struct IntersectionTracker<K> {
+    /// Observed regions, in observation order.
+    regions: Vec<Region<K>>,
+    /// Visible fraction above which a region is visible.
+    threshold: f64,
+}
+
+impl<K: Clone + PartialEq> Default for IntersectionTracker<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + PartialEq> IntersectionTracker<K> {
+    /// Constructs a new [`IntersectionTracker`], where a region is visible
+    /// as soon as one of its cells is.
+    pub fn new() -> Self {
+        Self {
+            regions: Vec::new(),
+            threshold: 0.0,
+        }
+    }
+
+    /// Sets the visible fraction of a region, from `0.0` to `1.0`, from
+    /// which it is visible, e.g. `1.0` to only report fully visible regions.
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Observes a region, or moves an observed region, e.g. after the
+    /// content was laid out again.
+    ///
+    /// The area is in the coordinates of the scrolled content, the same as
+    /// the viewport given to [`update`](Self::update).
+    pub fn observe(&mut self, key: K, area: Rect) {
+        match self.regions.iter_mut().find(|region| region.key == key) {
+            Some(region) => region.area = area,
+            None => self.regions.push(Region {
+                key,
+                area,
+                visible: false,
+            }),
+        }
+    }
+
+    /// Stops observing a region.
+    pub fn unobserve(&mut self, key: &K) {
+        self.regions.retain(|region| region.key != *key);
+    }
+
+    /// Stops observing all regions.
+    pub fn clear(&mut self) {
+        self.regions.clear();
+    }
+
+    /// Returns `true` if the region was visible at the last update.
+    pub fn is_visible(&self, key: &K) -> bool {
+        self.regions
+            .iter()
+            .any(|region| region.key == *key && region.visible)
+    }
+
+    /// Updates the visibility of the regions in the given viewport,
+    /// returning the regions that entered or left it, in observation order.
+    pub fn update(&mut self, viewport: Rect) -> Vec<IntersectionEvent<K>> {
+        let mut events = Vec::new();
+        for region in &mut self.regions {
+            let ratio = visible_ratio(region.area, viewport);
+            let visible = ratio > 0.0 && ratio >= self.threshold;
+            if visible == region.visible {
+                continue;
+            }
+            region.visible = visible;
+            events.push(IntersectionEvent {
+                key: region.key.clone(),
+                kind: if visible {
+                    IntersectionKind::Entered
+                } else {
+                    IntersectionKind::Left
+                },
+                ratio,
+            });
+        }
+        events
+    }
+}
+
+/// Returns the fraction of the area that is inside the viewport.
+fn visible_ratio(area: Rect, viewport: Rect) -> f64 {
+    if area.is_empty() {
+        return 0.0;
+    }
+    area.intersection(viewport).area() as f64 / area.area() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enter_and_leave() {
+        let mut tracker = IntersectionTracker::new();
+        tracker.observe("a", Rect::new(0, 0, 10, 4));
+        tracker.observe("b", Rect::new(0, 20, 10, 4));
+
+        let events = tracker.update(Rect::new(0, 0, 10, 10));
+        assert_eq!(
+            events,
+            [IntersectionEvent {
+                key: "a",
+                kind: IntersectionKind::Entered,
+                ratio: 1.0
+            }]
+        );
+        assert!(tracker.update(Rect::new(0, 2, 10, 10)).is_empty());
+
+        let events = tracker.update(Rect::new(0, 15, 10, 10));
+        let kinds: Vec<_> = events.iter().map(|e| (e.key, e.kind)).collect();
+        assert_eq!(
+            kinds,
+            [
+                ("a", IntersectionKind::Left),
+                ("b", IntersectionKind::Entered)
+            ]
+        );
+        assert!(tracker.is_visible(&"b"));
+    }
+
+    #[test]
+    fn test_threshold() {
+        let mut tracker = IntersectionTracker::new().threshold(0.5);
+        tracker.observe(1, Rect::new(0, 8, 10, 4));
+        // a quarter visible
+        assert!(tracker.update(Rect::new(0, 0, 10, 9)).is_empty());
+        let events = tracker.update(Rect::new(0, 0, 10, 10));
+        assert_eq!(events[0].kind, IntersectionKind::Entered);
+        assert_eq!(events[0].ratio, 0.5);
+
+        tracker.unobserve(&1);
+        assert!(tracker.update(Rect::new(0, 100, 10, 10)).is_empty());
+    }
+}
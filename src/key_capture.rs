@@ -0,0 +1,207 @@
+//! ## Key capture
+//!
+//! By default, the browser still handles its own shortcuts while a TUI app
+//! runs: `Ctrl+S` opens the save dialog, `Tab` moves the focus out of the
+//! terminal and the arrow keys scroll the page. [`KeyCaptureOptions`] make
+//! [`on_key_event`] call `preventDefault()` on selected keys, so the app can
+//! own them without breaking every other browser shortcut:
+//!
+//! ```rust no_run
+//! use ratzilla::key_capture::{set_key_capture, BrowserShortcut, KeyCaptureOptions};
+//!
+//! set_key_capture(
+//!     KeyCaptureOptions::new()
+//!         .capture(BrowserShortcut::Save)
+//!         .capture(BrowserShortcut::TabFocus)
+//!         .deny("ctrl+p".parse().unwrap())
+//!         // keep the browser's zoom shortcut working
+//!         .allow("ctrl++".parse().unwrap()),
+//! );
+//! ```
+//!
+//! A key on the allow list is never captured. Otherwise it is captured if it
+//! is on the deny list or belongs to a captured [`BrowserShortcut`].
+//!
+//! [`on_key_event`]: crate::WebRenderer::on_key_event
+//! [`BrowserShortcut`]: crate::key_capture::BrowserShortcut
+//! [`KeyCaptureOptions`]: crate::key_capture::KeyCaptureOptions
+
+use std::cell::RefCell;
+
+use crate::{
+    event::{KeyCode, KeyEvent},
+    keymap::KeyBinding,
+};
+
+/// A group of keys with a default action in the browser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BrowserShortcut {
+    /// `Ctrl+S` (`Cmd+S` on macOS), saving the page.
+    Save,
+    /// `Ctrl+F` (`Cmd+F` on macOS) and `F3`, finding in the page.
+    Find,
+    /// `Tab` and `Shift+Tab`, moving the focus.
+    TabFocus,
+    /// The arrow keys, `PageUp`, `PageDown`, `Home`, `End` and `Space`,
+    /// scrolling the page.
+    Scroll,
+    /// `F5` and `Ctrl+R` (`Cmd+R` on macOS), reloading the page.
+    Reload,
+}
+
+impl BrowserShortcut {
+    /// All the shortcuts.
+    pub const ALL: [BrowserShortcut; 5] = [
+        BrowserShortcut::Save,
+        BrowserShortcut::Find,
+        BrowserShortcut::TabFocus,
+        BrowserShortcut::Scroll,
+        BrowserShortcut::Reload,
+    ];
+
+    /// Returns `true` if the key event triggers the shortcut.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        // the accelerator is Cmd on macOS and Ctrl elsewhere
+        let accel = |c: char| {
+            (event.ctrl || event.meta)
+                && !event.alt
+                && matches!(event.code, KeyCode::Char(k) if k.eq_ignore_ascii_case(&c))
+        };
+        let plain = !event.ctrl && !event.alt && !event.meta;
+        match self {
+            BrowserShortcut::Save => accel('s'),
+            BrowserShortcut::Find => accel('f') || (plain && event.code == KeyCode::F(3)),
+            BrowserShortcut::TabFocus => plain && event.code == KeyCode::Tab,
+            BrowserShortcut::Scroll => {
+                plain
+                    && matches!(
+                        event.code,
+                        KeyCode::Left
+                            | KeyCode::Right
+                            | KeyCode::Up
+                            | KeyCode::Down
+                            | KeyCode::PageUp
+                            | KeyCode::PageDown
+                            | KeyCode::Home
+                            | KeyCode::End
+                            | KeyCode::Char(' ')
+                    )
+            }
+            BrowserShortcut::Reload => accel('r') || (plain && event.code == KeyCode::F(5)),
+        }
+    }
+}
+
+/// Options selecting the keys whose browser default is prevented.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyCaptureOptions {
+    /// Captured shortcuts.
+    shortcuts: Vec<BrowserShortcut>,
+    /// Keys that are always captured.
+    deny: Vec<KeyBinding>,
+    /// Keys that are never captured.
+    allow: Vec<KeyBinding>,
+}
+
+impl KeyCaptureOptions {
+    /// Constructs new [`KeyCaptureOptions`] capturing no keys.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs new [`KeyCaptureOptions`] capturing all the
+    /// [`BrowserShortcut`]s.
+    pub fn all() -> Self {
+        Self {
+            shortcuts: BrowserShortcut::ALL.to_vec(),
+            ..Self::default()
+        }
+    }
+
+    /// Captures the keys of a browser shortcut.
+    pub fn capture(mut self, shortcut: BrowserShortcut) -> Self {
+        if !self.shortcuts.contains(&shortcut) {
+            self.shortcuts.push(shortcut);
+        }
+        self
+    }
+
+    /// Always captures a key.
+    pub fn deny(mut self, key: KeyBinding) -> Self {
+        self.deny.push(key);
+        self
+    }
+
+    /// Never captures a key, even if it belongs to a captured shortcut.
+    pub fn allow(mut self, key: KeyBinding) -> Self {
+        self.allow.push(key);
+        self
+    }
+
+    /// Returns `true` if the browser default of the key event is prevented.
+    pub fn captures(&self, event: &KeyEvent) -> bool {
+        if self.allow.iter().any(|key| key.matches(event)) {
+            return false;
+        }
+        self.deny.iter().any(|key| key.matches(event))
+            || self.shortcuts.iter().any(|s| s.matches(event))
+    }
+}
+
+thread_local! {
+    static OPTIONS: RefCell<KeyCaptureOptions> = RefCell::new(KeyCaptureOptions::new());
+}
+
+/// Sets the keys whose browser default is prevented.
+pub fn set_key_capture(options: KeyCaptureOptions) {
+    OPTIONS.with(|o| *o.borrow_mut() = options);
+}
+
+/// Returns `true` if the browser default of the key event is prevented.
+pub(crate) fn captures(event: &KeyEvent) -> bool {
+    OPTIONS.with(|o| o.borrow().captures(event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(binding: &str) -> KeyEvent {
+        let binding: KeyBinding = binding.parse().expect("valid key binding");
+        KeyEvent {
+            code: binding.code,
+            ctrl: binding.ctrl,
+            alt: binding.alt,
+            shift: binding.shift,
+            meta: binding.meta,
+            repeat: false,
+        }
+    }
+
+    #[test]
+    fn test_shortcuts() {
+        let options = KeyCaptureOptions::new()
+            .capture(BrowserShortcut::Save)
+            .capture(BrowserShortcut::TabFocus);
+        assert!(options.captures(&key("ctrl+s")));
+        assert!(options.captures(&key("cmd+s")));
+        assert!(options.captures(&key("tab")));
+        assert!(options.captures(&key("shift+tab")));
+        assert!(!options.captures(&key("s")));
+        assert!(!options.captures(&key("ctrl+f")));
+        assert!(!options.captures(&key("down")));
+        assert!(!KeyCaptureOptions::new().captures(&key("ctrl+s")));
+    }
+
+    #[test]
+    fn test_allow_and_deny() {
+        let options = KeyCaptureOptions::all()
+            .allow("f5".parse().expect("valid key binding"))
+            .deny("ctrl+p".parse().expect("valid key binding"));
+        assert!(options.captures(&key("ctrl+r")));
+        assert!(options.captures(&key("ctrl+p")));
+        assert!(options.captures(&key("space")));
+        assert!(!options.captures(&key("f5")));
+        assert!(!options.captures(&key("ctrl+k")));
+    }
+}
@@ -0,0 +1,408 @@
+//! ## Keymaps
+//!
+//! A [`Keymap`] maps key bindings to app actions, grouped by context (e.g.
+//! the screen or panel that has the focus). Since the keymap knows every
+//! binding and its description, it can also render a help overlay listing
+//! them with [`KeymapHelp`], toggled with `?` out of the box.
+//!
+//! ```rust no_run
+//! use ratzilla::{keymap::{Keymap, GLOBAL}, widgets::KeymapHelp, WebRenderer};
+//! # fn example(terminal: ratzilla::ratatui::Terminal<ratzilla::DomBackend>) {
+//! use std::{cell::RefCell, rc::Rc};
+//!
+//! #[derive(Clone)]
+//! enum Action {
+//!     Quit,
+//!     Save,
+//! }
+//!
+//! let mut keymap = Keymap::new();
+//! keymap.bind(GLOBAL, "ctrl+q", Action::Quit, "Quit").unwrap();
+//! keymap.bind("editor", "ctrl+s", Action::Save, "Save the file").unwrap();
+//! let keymap = Rc::new(RefCell::new(keymap));
+//!
+//! terminal.on_key_event({
+//!     let keymap = keymap.clone();
+//!     move |event| match keymap.borrow_mut().handle_key_event(&event, "editor") {
+//!         Some(Action::Save) => { /* ... */ }
+//!         Some(Action::Quit) => { /* ... */ }
+//!         None => {}
+//!     }
+//! });
+//! terminal.draw_web(move |frame| {
+//!     // render widgets...
+//!     frame.render_widget(KeymapHelp::new(&keymap.borrow()), frame.area());
+//! });
+//! # }
+//! ```
+//!
+//! [`KeymapHelp`]: crate::widgets::KeymapHelp
+//! [`Keymap`]: crate::keymap::Keymap
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    error::Error,
+    event::{KeyCode, KeyEvent},
+};
+
+/// The context of the bindings that are active everywhere.
+pub const GLOBAL: &str = "Global";
+
+/// A key combination, e.g. `Ctrl+S`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    /// The key code.
+    pub code: KeyCode,
+    /// Whether the control key must be pressed.
+    pub ctrl: bool,
+    /// Whether the alt key must be pressed.
+    pub alt: bool,
+    /// Whether the shift key must be pressed.
+    pub shift: bool,
+    /// Whether the meta key (Cmd or Windows) must be pressed.
+    pub meta: bool,
+}
+
+impl KeyBinding {
+    /// Constructs a new [`KeyBinding`] without modifiers.
+    pub fn new(code: KeyCode) -> Self {
+        Self {
+            code,
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+        }
+    }
+
+    /// Returns `true` if the key event matches the binding.
+    ///
+    /// The shift key is ignored for characters, since it is already part of
+    /// the character, e.g. `?` is typed with shift on most layouts.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let shift_matches = matches!(self.code, KeyCode::Char(_)) || self.shift == event.shift;
+        self.code == event.code
+            && self.ctrl == event.ctrl
+            && self.alt == event.alt
+            && self.meta == event.meta
+            && shift_matches
+    }
+}
+
+impl From<KeyCode> for KeyBinding {
+    fn from(code: KeyCode) -> Self {
+        Self::new(code)
+    }
+}
+
+/// Parses bindings such as `"ctrl+s"`, `"alt+shift+left"`, `"cmd+k"`,
+/// `"f5"` or `"?"`.
+impl FromStr for KeyBinding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidKeyBinding(s.to_string());
+        let mut binding = KeyBinding::new(KeyCode::Unidentified);
+        // `+` itself is a valid key, so split from the right
+        let (modifiers, key) = match s.rsplit_once('+') {
+            Some((modifiers, "")) => (modifiers.strip_suffix('+').ok_or_else(invalid)?, "+"),
+            Some((modifiers, key)) => (modifiers, key),
+            None => ("", s),
+        };
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => binding.ctrl = true,
+                "alt" | "option" => binding.alt = true,
+                "shift" => binding.shift = true,
+                "meta" | "cmd" | "command" | "super" | "win" => binding.meta = true,
+                _ => return Err(invalid()),
+            }
+        }
+        binding.code = match key.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "space" => KeyCode::Char(' '),
+            lower => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => match lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                        Some(n @ 1..=12) => KeyCode::F(n),
+                        _ => return Err(invalid()),
+                    },
+                }
+            }
+        };
+        Ok(binding)
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            f.write_str("Ctrl+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+        if self.meta {
+            f.write_str("Meta+")?;
+        }
+        match &self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) if self.ctrl || self.alt || self.meta => {
+                write!(f, "{}", c.to_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::Delete => f.write_str("Del"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Unidentified => f.write_str("?"),
+        }
+    }
+}
+
+/// A binding registered in a [`Keymap`].
+#[derive(Debug, Clone)]
+pub struct Binding<A> {
+    /// The context the binding is active in.
+    pub context: String,
+    /// The key combination.
+    pub key: KeyBinding,
+    /// The action triggered by the binding.
+    pub action: A,
+    /// Human readable description of the action.
+    pub description: String,
+}
+
+/// Maps key bindings to actions, grouped by context.
+#[derive(Debug, Clone)]
+pub struct Keymap<A> {
+    /// Registered bindings, in registration order.
+    bindings: Vec<Binding<A>>,
+    /// The key toggling the help overlay, if any.
+    help_key: Option<KeyBinding>,
+    /// Whether the help overlay is visible.
+    help_visible: bool,
+}
+
+impl<A: Clone> Default for Keymap<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Clone> Keymap<A> {
+    /// Constructs a new, empty [`Keymap`] with `?` toggling the help overlay.
+    pub fn new() -> Self {
+        Self {
+            bindings: Vec::new(),
+            help_key: Some(KeyBinding::new(KeyCode::Char('?'))),
+            help_visible: false,
+        }
+    }
+
+    /// Sets the key toggling the help overlay, or disables it with `None`.
+    pub fn help_key(mut self, key: Option<KeyBinding>) -> Self {
+        self.help_key = key;
+        self
+    }
+
+    /// Binds a key, given as a string such as `"ctrl+s"`, to an action in
+    /// the given context.
+    ///
+    /// Bindings of the [`GLOBAL`] context are active in every context, unless
+    /// the context binds the same key. See [`KeyBinding`] for the syntax.
+    pub fn bind(
+        &mut self,
+        context: &str,
+        key: &str,
+        action: A,
+        description: &str,
+    ) -> Result<(), Error> {
+        self.bind_key(context, key.parse()?, action, description);
+        Ok(())
+    }
+
+    /// Binds a key to an action in the given context.
+    pub fn bind_key(&mut self, context: &str, key: KeyBinding, action: A, description: &str) {
+        self.bindings.push(Binding {
+            context: context.to_string(),
+            key,
+            action,
+            description: description.to_string(),
+        });
+    }
+
+    /// Removes all bindings of the given context.
+    pub fn clear_context(&mut self, context: &str) {
+        self.bindings.retain(|b| b.context != context);
+    }
+
+    /// Returns the action bound to the key event in the given context.
+    pub fn resolve(&self, event: &KeyEvent, context: &str) -> Option<&A> {
+        let find = |context: &str| {
+            self.bindings
+                .iter()
+                .find(|b| b.context == context && b.key.matches(event))
+                .map(|b| &b.action)
+        };
+        find(context).or_else(|| find(GLOBAL))
+    }
+
+    /// Handles a key event, returning the bound action.
+    ///
+    /// The help key toggles the help overlay, and `Esc` closes it. While the
+    /// overlay is visible, other keys are ignored.
+    pub fn handle_key_event(&mut self, event: &KeyEvent, context: &str) -> Option<A> {
+        if self.help_key.as_ref().is_some_and(|key| key.matches(event)) {
+            self.help_visible = !self.help_visible;
+            return None;
+        }
+        if self.help_visible {
+            if event.code == KeyCode::Esc {
+                self.help_visible = false;
+            }
+            return None;
+        }
+        self.resolve(event, context).cloned()
+    }
+
+    /// Returns `true` if the help overlay is visible.
+    pub fn help_visible(&self) -> bool {
+        self.help_visible
+    }
+
+    /// Shows or hides the help overlay.
+    pub fn set_help_visible(&mut self, visible: bool) {
+        self.help_visible = visible;
+    }
+
+    /// Returns all bindings, in registration order.
+    pub fn bindings(&self) -> &[Binding<A>] {
+        &self.bindings
+    }
+
+    /// Returns the bindings grouped by context, in registration order.
+    ///
+    /// The [`GLOBAL`] context comes first.
+    pub fn groups(&self) -> Vec<(&str, Vec<&Binding<A>>)> {
+        let mut groups: Vec<(&str, Vec<&Binding<A>>)> = Vec::new();
+        for binding in &self.bindings {
+            match groups.iter_mut().find(|(c, _)| *c == binding.context) {
+                Some((_, bindings)) => bindings.push(binding),
+                None => groups.push((&binding.context, vec![binding])),
+            }
+        }
+        groups.sort_by_key(|(context, _)| *context != GLOBAL);
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, ctrl: bool) -> KeyEvent {
+        KeyEvent {
+            code,
+            ctrl,
+            alt: false,
+            shift: false,
+            meta: false,
+            repeat: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_and_display() -> Result<(), Error> {
+        let binding: KeyBinding = "ctrl+s".parse()?;
+        assert_eq!(binding.to_string(), "Ctrl+S");
+        assert_eq!(
+            "alt+shift+left".parse::<KeyBinding>()?.to_string(),
+            "Alt+Shift+←"
+        );
+        assert_eq!("ctrl++".parse::<KeyBinding>()?.code, KeyCode::Char('+'));
+        assert_eq!("f5".parse::<KeyBinding>()?.code, KeyCode::F(5));
+        assert_eq!("?".parse::<KeyBinding>()?.code, KeyCode::Char('?'));
+        assert!("hyper+x".parse::<KeyBinding>().is_err());
+        assert!("f13".parse::<KeyBinding>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_meta() -> Result<(), Error> {
+        let binding: KeyBinding = "cmd+k".parse()?;
+        assert_eq!(binding.to_string(), "Meta+K");
+        let mut event = key(KeyCode::Char('k'), false);
+        assert!(!binding.matches(&event));
+        event.meta = true;
+        assert!(binding.matches(&event));
+        Ok(())
+    }
+
+    #[test]
+    fn test_context_overrides_global() -> Result<(), Error> {
+        let mut keymap = Keymap::new();
+        keymap.bind(GLOBAL, "ctrl+s", 1, "Global save")?;
+        keymap.bind("editor", "ctrl+s", 2, "Editor save")?;
+
+        let event = key(KeyCode::Char('s'), true);
+        assert_eq!(keymap.resolve(&event, "editor"), Some(&2));
+        assert_eq!(keymap.resolve(&event, "list"), Some(&1));
+        assert_eq!(
+            keymap.resolve(&key(KeyCode::Char('s'), false), "editor"),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_help_toggle() -> Result<(), Error> {
+        let mut keymap = Keymap::new();
+        keymap.bind(GLOBAL, "q", 1, "Quit")?;
+
+        assert_eq!(
+            keymap.handle_key_event(&key(KeyCode::Char('?'), false), GLOBAL),
+            None
+        );
+        assert!(keymap.help_visible());
+        assert_eq!(
+            keymap.handle_key_event(&key(KeyCode::Char('q'), false), GLOBAL),
+            None
+        );
+        keymap.handle_key_event(&key(KeyCode::Esc, false), GLOBAL);
+        assert!(!keymap.help_visible());
+        assert_eq!(
+            keymap.handle_key_event(&key(KeyCode::Char('q'), false), GLOBAL),
+            Some(1)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! ## Leptos
+//!
+//! A [`RatzillaView`] component and a [`use_ratzilla`] hook mounting a
+//! terminal into a node of a Leptos app. The terminal is mounted once the
+//! node is, and [destroyed](crate::teardown::destroy) when the owner of the
+//! view is cleaned up:
+//!
+//! ```rust no_run
+//! use leptos::prelude::*;
+//! use ratzilla::{
+//!     leptos::RatzillaView,
+//!     ratatui::{widgets::Paragraph, Frame},
+//! };
+//!
+//! #[component]
+//! fn App() -> impl IntoView {
+//!     let (count, set_count) = signal(0);
+//!     view! {
+//!         <button on:click=move |_| set_count.update(|count| *count += 1)>"+1"</button>
+//!         <RatzillaView draw=move |frame: &mut Frame| {
+//!             let text = format!("count: {}", count.get_untracked());
+//!             frame.render_widget(Paragraph::new(text), frame.area());
+//!         } />
+//!     }
+//! }
+//! ```
+//!
+//! The draw function runs on every frame of the render loop, so it shows
+//! the latest value of the signals it reads. In
+//! [on-demand](crate::render_loop::RenderLoopOptions::on_demand) mode,
+//! [`redraw_on`] requests a frame when signals change.
+//!
+//! Only one terminal can be mounted at a time, as [`teardown`](crate::teardown)
+//! destroys every terminal of the page.
+
+use std::io;
+
+use ::leptos::{html::Div, prelude::*};
+use ratatui::{Frame, Terminal};
+use web_sys::HtmlElement;
+
+use crate::{render_loop, teardown, DomBackend, WebRenderer};
+
+/// Id given to the node of the terminal if it has none.
+const DEFAULT_ID: &str = "ratzilla-terminal";
+
+/// A terminal drawn with the [`DomBackend`] in a `<div>`.
+///
+/// The grid fills the `<div>`, so its size is set by the styles of the app.
+#[component]
+pub fn RatzillaView<F>(
+    /// Draws the terminal.
+    draw: F,
+    /// Classes of the `<div>` holding the grid.
+    #[prop(optional, into)]
+    class: String,
+) -> impl IntoView
+where
+    F: Fn(&mut Frame) + 'static,
+{
+    let node_ref = NodeRef::<Div>::new();
+    use_ratzilla(node_ref, draw);
+    view! { <div node_ref=node_ref class=class /> }
+}
+
+/// Mounts a terminal into the given node once it is mounted, and destroys
+/// it on cleanup.
+pub fn use_ratzilla<F>(node_ref: NodeRef<Div>, draw: F)
+where
+    F: Fn(&mut Frame) + 'static,
+{
+    let mut draw = Some(draw);
+    Effect::new(move |_| {
+        let (Some(element), Some(draw)) = (node_ref.get(), draw.take()) else {
+            return;
+        };
+        if let Err(error) = mount(&element, draw) {
+            web_sys::console::error_1(&format!("unable to mount the terminal: {error}").into());
+        }
+    });
+    on_cleanup(teardown::destroy);
+}
+
+/// Requests a frame of the render loop whenever the signals read by the
+/// given function change.
+pub fn redraw_on<F>(signals: F)
+where
+    F: Fn() + 'static,
+{
+    Effect::new(move |_| {
+        signals();
+        render_loop::request_redraw();
+    });
+}
+
+/// Mounts a terminal into the given element.
+fn mount<F>(element: &HtmlElement, draw: F) -> io::Result<()>
+where
+    F: Fn(&mut Frame) + 'static,
+{
+    if element.id().is_empty() {
+        element.set_id(DEFAULT_ID);
+    }
+    let terminal = Terminal::new(DomBackend::new_by_id(&element.id())?)?;
+    terminal.draw_web(draw);
+    Ok(())
+}
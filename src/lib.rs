@@ -1,6 +1,21 @@
 #![warn(missing_docs, clippy::unwrap_used)]
 #![doc = include_str!("../README.md")]
 
+/// Adaptive quality scaling.
+pub mod adaptive;
+
+/// Analytics hook points.
+pub mod analytics;
+
+/// Time source.
+pub mod clock;
+
+/// Animated values.
+pub mod animation;
+
+/// Kinetic scrolling.
+pub mod scroll;
+
 /// Custom error type.
 pub mod error;
 
@@ -13,22 +28,165 @@ pub mod utils;
 /// Widgets.
 pub mod widgets;
 
+/// Cell metadata for widgets.
+pub mod metadata;
+
+/// Clickable links.
+pub mod links;
+
+/// Raster images over the grid.
+pub mod images;
+
+/// Slow frame detection.
+pub mod watchdog;
+
+/// Frame by frame stepping.
+pub mod stepping;
+
+/// Browser shortcut interception.
+pub mod key_capture;
+
+/// Input recording and replay.
+pub mod replay;
+
 /// Backend.
 pub mod backend;
 
+/// Keymaps and key bindings.
+pub mod keymap;
+
+/// Routing.
+pub mod router;
+
+/// Screen management and transitions.
+pub mod screen;
+
+/// Multi-tab synchronization.
+pub mod sync;
+
+/// Background computation.
+pub mod threads;
+
+/// Background tasks.
+pub mod tasks;
+
+/// State shared with async tasks.
+pub mod shared;
+
+/// Idle time scheduling.
+pub mod idle;
+
+/// Server-side rendering and static site generation.
+pub mod ssr;
+
+/// Cached frames for instant startup.
+pub mod frame_cache;
+
+/// Mouse text selection.
+pub mod selection;
+
+/// Render loop frame rate control.
+pub mod render_loop;
+
+/// Built-in terminal actions.
+pub mod actions;
+
+/// Terminal teardown.
+pub mod teardown;
+
+/// Persisted user settings.
+pub mod settings;
+
+/// Layout of wrapped rich text.
+pub mod text_layout;
+
+/// Grapheme aware text editing.
+pub mod editing;
+
+/// Visibility of regions in a viewport.
+pub mod intersection;
+
+/// Mouse reporting for remote terminals.
+pub mod mouse_report;
+
 /// Rendering.
 mod render;
 
+/// Window resize handling.
+mod resize;
+
+/// Text encoding of buffers.
+mod buffer_codec;
+
+/// Touch gesture recognition.
+mod gesture;
+
+/// Platform abstraction for native and web apps.
+pub mod platform;
+
+/// App entry point.
+pub mod app;
+
+/// Conversions from and to the events of terminal libraries.
+mod compat;
+
+/// Effects.
+#[cfg(feature = "tachyonfx")]
+pub mod fx;
+
+/// Authentication.
+#[cfg(feature = "auth")]
+pub mod auth;
+
+/// Binary protocol helpers.
+#[cfg(feature = "protocol")]
+pub mod protocol;
+
+/// Session restore.
+#[cfg(feature = "session")]
+pub mod session;
+
+/// Golden image testing.
+#[cfg(feature = "golden")]
+pub mod golden;
+
+/// Remote debugging.
+#[cfg(feature = "remote-debug")]
+pub mod remote_debug;
+
+/// Async event streams.
+#[cfg(feature = "event-stream")]
+pub mod event_stream;
+
+/// Yew integration.
+#[cfg(feature = "yew")]
+pub mod yew;
+
+/// Leptos integration.
+#[cfg(feature = "leptos")]
+pub mod leptos;
+
+/// Dioxus integration.
+#[cfg(feature = "dioxus")]
+pub mod dioxus;
+
 // Re-export ratatui crate.
 pub use ratatui;
 
 // Re-export web_sys crate.
 pub use web_sys;
 
+#[cfg(feature = "canvas")]
+pub use backend::canvas::CanvasBackend;
+#[cfg(feature = "dom")]
+pub use backend::dom::DomBackend;
+#[cfg(feature = "webgl2")]
+pub use backend::webgl2::{FontAtlasConfig, SelectionMode, WebGl2Backend};
+#[cfg(feature = "webgpu")]
+pub use backend::webgpu::WgpuBackend;
 pub use backend::{
-    canvas::CanvasBackend,
-    cursor::CursorShape,
-    dom::DomBackend,
-    webgl2::{FontAtlasConfig, SelectionMode, WebGl2Backend},
+    cursor::{CursorOptions, CursorShape},
+    metrics::CellMetrics,
+    web::{CellRect, MemoryStats, WebBackend, WebTerminal},
 };
 pub use render::WebRenderer;
@@ -0,0 +1,191 @@
+//! ## Links
+//!
+//! Widgets register the links they render, as a region of cells and a URL,
+//! in the [`LinkRegistry`] of the frame. The cells keep their own styles, and
+//! each backend makes the regions clickable:
+//!
+//! - The [`DomBackend`] wraps the cells of each row of a link in an `<a>`
+//!   element, so the links work like any other link of the page.
+//! - The [`CanvasBackend`] and [`WebGl2Backend`] hit-test the regions on
+//!   click and show a pointer over them, once hyperlinks are enabled in their
+//!   options.
+//!
+//! ```rust no_run
+//! use ratzilla::{links, ratatui::{layout::Rect, widgets::Paragraph}};
+//!
+//! let area = Rect::new(0, 0, 20, 1);
+//! // frame.render_widget(Paragraph::new("Read the docs"), area);
+//! links::register(area, "https://docs.rs/ratzilla");
+//! ```
+//!
+//! The links of a frame are committed at the end of the [`draw_web`]
+//! callback, and replace the links of the previous frame. The
+//! [`Hyperlink`](crate::widgets::Hyperlink) widget registers its URL.
+//!
+//! [`DomBackend`]: crate::DomBackend
+//! [`CanvasBackend`]: crate::CanvasBackend
+//! [`WebGl2Backend`]: crate::WebGl2Backend
+//! [`draw_web`]: crate::WebRenderer::draw_web
+//! [`LinkRegistry`]: crate::links::LinkRegistry
+
+use std::cell::RefCell;
+
+use ratatui::layout::{Position, Rect};
+
+/// A link on screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    /// Region of the cells of the link.
+    pub area: Rect,
+    /// URL of the link.
+    pub url: String,
+}
+
+/// The links of a frame.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LinkRegistry {
+    /// Links registered while rendering the next frame.
+    pending: Vec<Link>,
+    /// Links of the frame on screen.
+    current: Vec<Link>,
+    /// Incremented whenever the links on screen change.
+    generation: u64,
+}
+
+impl LinkRegistry {
+    /// Constructs a new, empty [`LinkRegistry`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a link for the frame being rendered.
+    ///
+    /// Links registered later take precedence where they overlap.
+    pub fn register(&mut self, area: Rect, url: &str) {
+        if !area.is_empty() && !url.is_empty() {
+            self.pending.push(Link {
+                area,
+                url: url.to_string(),
+            });
+        }
+    }
+
+    /// Makes the links registered since the last call the links on screen.
+    pub fn commit(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        if pending != self.current {
+            self.current = pending;
+            self.generation += 1;
+        }
+    }
+
+    /// Returns the topmost link on screen containing the cell, if any.
+    pub fn link_at(&self, column: u16, row: u16) -> Option<&Link> {
+        let position = Position::new(column, row);
+        self.current
+            .iter()
+            .rev()
+            .find(|link| link.area.contains(position))
+    }
+
+    /// Returns the links on screen.
+    pub fn links(&self) -> &[Link] {
+        &self.current
+    }
+
+    /// Returns a number that changes whenever the links on screen change.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+thread_local! {
+    static REGISTRY: RefCell<LinkRegistry> = RefCell::new(LinkRegistry::new());
+}
+
+/// Registers a link for the frame being rendered.
+///
+/// See [`LinkRegistry::register`].
+pub fn register(area: Rect, url: &str) {
+    REGISTRY.with(|r| r.borrow_mut().register(area, url));
+}
+
+/// Returns the URL of the link on screen at the given cell, if any.
+pub fn link_at(column: u16, row: u16) -> Option<String> {
+    REGISTRY.with(|r| r.borrow().link_at(column, row).map(|link| link.url.clone()))
+}
+
+/// Returns the links on screen.
+pub fn links() -> Vec<Link> {
+    REGISTRY.with(|r| r.borrow().links().to_vec())
+}
+
+/// Makes the links registered since the last call the links on screen.
+pub(crate) fn commit() {
+    REGISTRY.with(|r| r.borrow_mut().commit());
+}
+
+/// Returns a number that changes whenever the links on screen change.
+#[cfg(feature = "dom")]
+pub(crate) fn generation() -> u64 {
+    REGISTRY.with(|r| r.borrow().generation())
+}
+
+/// Returns `true` if the URL can be the `href` of a link: a relative URL or
+/// an `http`, `https` or `mailto` one, but not e.g. a `javascript:` URL.
+pub fn is_safe_url(url: &str) -> bool {
+    let url = url.trim_start();
+    match url.split_once(':') {
+        Some((scheme, _)) if !scheme.contains(['/', '?', '#']) => ["http", "https", "mailto"]
+            .iter()
+            .any(|safe| scheme.eq_ignore_ascii_case(safe)),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_and_hit_test() {
+        let mut registry = LinkRegistry::new();
+        registry.register(Rect::new(0, 0, 10, 1), "https://a.example");
+        registry.register(Rect::new(5, 0, 10, 1), "https://b.example");
+        registry.register(Rect::new(0, 1, 0, 1), "https://empty.example");
+        assert_eq!(registry.link_at(0, 0), None);
+
+        registry.commit();
+        assert_eq!(registry.generation(), 1);
+        assert_eq!(registry.links().len(), 2);
+        assert_eq!(
+            registry.link_at(2, 0).map(|link| link.url.as_str()),
+            Some("https://a.example")
+        );
+        assert_eq!(
+            registry.link_at(7, 0).map(|link| link.url.as_str()),
+            Some("https://b.example")
+        );
+        assert_eq!(registry.link_at(0, 1), None);
+
+        // the same links don't change the generation
+        registry.register(Rect::new(0, 0, 10, 1), "https://a.example");
+        registry.register(Rect::new(5, 0, 10, 1), "https://b.example");
+        registry.commit();
+        assert_eq!(registry.generation(), 1);
+        registry.commit();
+        assert_eq!(registry.generation(), 2);
+        assert!(registry.links().is_empty());
+    }
+
+    #[test]
+    fn test_safe_urls() {
+        assert!(is_safe_url("https://ratatui.rs"));
+        assert!(is_safe_url("mailto:someone@example.com"));
+        assert!(is_safe_url("/docs?page=a:b"));
+        assert!(is_safe_url("#section"));
+        assert!(!is_safe_url("javascript:alert(1)"));
+        assert!(!is_safe_url(" JavaScript:alert(1)"));
+        assert!(!is_safe_url("data:text/html,hi"));
+    }
+}
@@ -0,0 +1,218 @@
+//! ## Cell metadata
+//!
+//! Widgets can attach metadata to the cells they render, such as an element
+//! id, a hyperlink URL, a tooltip or an ARIA label. The metadata is kept next
+//! to the buffer for the frame, so it doesn't change how cells are drawn:
+//!
+//! - The [`DomBackend`] sets it as attributes of the cell elements (`id`,
+//!   `data-url`, `title` and `aria-label`).
+//! - [`metadata_at`] returns it for a cell, e.g. for hit testing with
+//!   [`WebTerminal::cell_at`].
+//!
+//! ```rust no_run
+//! use ratzilla::{
+//!     metadata::{CellMetadata, WithMetadata},
+//!     ratatui::widgets::Paragraph,
+//! };
+//!
+//! let button = WithMetadata::new(
+//!     Paragraph::new("[ Save ]"),
+//!     CellMetadata::new().id("save-button").tooltip("Save the file (Ctrl+S)"),
+//! );
+//! // frame.render_widget(button, area);
+//! ```
+//!
+//! The metadata of a frame is committed at the end of the [`draw_web`]
+//! callback, and replaces the metadata of the previous frame.
+//!
+//! [`DomBackend`]: crate::DomBackend
+//! [`WebTerminal::cell_at`]: crate::WebTerminal::cell_at
+//! [`draw_web`]: crate::WebRenderer::draw_web
+//! [`metadata_at`]: crate::metadata::metadata_at
+
+use std::cell::RefCell;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    widgets::Widget,
+};
+
+/// Metadata attached to a region of cells.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CellMetadata {
+    /// Element id, set on the first cell of the region.
+    pub id: Option<String>,
+    /// Hyperlink URL.
+    pub url: Option<String>,
+    /// Tooltip text.
+    pub tooltip: Option<String>,
+    /// Accessible label.
+    pub aria_label: Option<String>,
+}
+
+impl CellMetadata {
+    /// Constructs a new, empty [`CellMetadata`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the element id.
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Sets the hyperlink URL.
+    pub fn url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    /// Sets the tooltip text.
+    pub fn tooltip(mut self, tooltip: &str) -> Self {
+        self.tooltip = Some(tooltip.to_string());
+        self
+    }
+
+    /// Sets the accessible label.
+    pub fn aria_label(mut self, label: &str) -> Self {
+        self.aria_label = Some(label.to_string());
+        self
+    }
+}
+
+/// Metadata of the frame being rendered and of the frame on screen.
+#[derive(Debug, Default)]
+struct MetadataLayer {
+    /// Regions attached while rendering the next frame.
+    pending: Vec<(Rect, CellMetadata)>,
+    /// Regions of the frame on screen.
+    current: Vec<(Rect, CellMetadata)>,
+    /// Incremented whenever the regions on screen change.
+    generation: u64,
+}
+
+impl MetadataLayer {
+    /// Makes the pending regions the regions on screen.
+    fn commit(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        if pending != self.current {
+            self.current = pending;
+            self.generation += 1;
+        }
+    }
+
+    /// Returns the metadata of the topmost region containing the cell.
+    fn at(&self, position: Position) -> Option<&CellMetadata> {
+        self.current
+            .iter()
+            .rev()
+            .find(|(area, _)| area.contains(position))
+            .map(|(_, metadata)| metadata)
+    }
+}
+
+thread_local! {
+    static LAYER: RefCell<MetadataLayer> = RefCell::new(MetadataLayer::default());
+}
+
+/// Attaches metadata to a region of cells of the frame being rendered.
+///
+/// Regions attached later take precedence where they overlap.
+pub fn attach(area: Rect, metadata: CellMetadata) {
+    if !area.is_empty() {
+        LAYER.with(|l| l.borrow_mut().pending.push((area, metadata)));
+    }
+}
+
+/// Returns the metadata of the cell on screen at the given position, if any.
+pub fn metadata_at(column: u16, row: u16) -> Option<CellMetadata> {
+    LAYER.with(|l| l.borrow().at(Position::new(column, row)).cloned())
+}
+
+/// Returns the regions of the frame on screen and their metadata.
+pub fn regions() -> Vec<(Rect, CellMetadata)> {
+    LAYER.with(|l| l.borrow().current.clone())
+}
+
+/// Makes the metadata attached since the last call the metadata on screen.
+pub(crate) fn commit() {
+    LAYER.with(|l| l.borrow_mut().commit());
+}
+
+/// Returns a number that changes whenever the metadata on screen changes.
+#[cfg(feature = "dom")]
+pub(crate) fn generation() -> u64 {
+    LAYER.with(|l| l.borrow().generation)
+}
+
+/// A widget that attaches metadata to the area of another widget.
+#[derive(Debug, Clone)]
+pub struct WithMetadata<W> {
+    /// The wrapped widget.
+    widget: W,
+    /// The metadata of its area.
+    metadata: CellMetadata,
+}
+
+impl<W> WithMetadata<W> {
+    /// Constructs a new [`WithMetadata`] attaching the metadata to the area
+    /// the widget is rendered in.
+    pub fn new(widget: W, metadata: CellMetadata) -> Self {
+        Self { widget, metadata }
+    }
+}
+
+impl<W: Widget> Widget for WithMetadata<W> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.widget.render(area, buf);
+        attach(area.intersection(buf.area), self.metadata);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_and_lookup() {
+        let mut layer = MetadataLayer::default();
+        layer
+            .pending
+            .push((Rect::new(0, 0, 10, 1), CellMetadata::new().id("bar")));
+        layer
+            .pending
+            .push((Rect::new(2, 0, 3, 1), CellMetadata::new().tooltip("tip")));
+        assert_eq!(layer.at(Position::new(3, 0)), None);
+
+        layer.commit();
+        assert_eq!(layer.generation, 1);
+        assert_eq!(
+            layer
+                .at(Position::new(3, 0))
+                .and_then(|m| m.tooltip.as_deref()),
+            Some("tip")
+        );
+        assert_eq!(
+            layer.at(Position::new(8, 0)).and_then(|m| m.id.as_deref()),
+            Some("bar")
+        );
+        assert_eq!(layer.at(Position::new(0, 1)), None);
+    }
+
+    #[test]
+    fn test_unchanged_frame_keeps_generation() {
+        let mut layer = MetadataLayer::default();
+        let region = (Rect::new(0, 0, 1, 1), CellMetadata::new().url("/"));
+        layer.pending.push(region.clone());
+        layer.commit();
+        layer.pending.push(region);
+        layer.commit();
+        assert_eq!(layer.generation, 1);
+
+        layer.commit();
+        assert_eq!(layer.generation, 2);
+        assert!(layer.current.is_empty());
+    }
+}
@@ -0,0 +1,441 @@
+//! ## Mouse reporting
+//!
+//! Encodes mouse events into the escape sequences a terminal sends to the
+//! app running in it, e.g. a remote TUI behind a PTY bridged over a
+//! `WebSocket`. Apps like `htop` or `vim` enable mouse reporting with
+//! private modes (`DECSET`), and only receive the events they asked for:
+//!
+//! | Mode   | Reported events                                   |
+//! |--------|---------------------------------------------------|
+//! | `9`    | Button presses (X10)                              |
+//! | `1000` | Button presses and releases, and the wheel        |
+//! | `1002` | The above, and moves while a button is held       |
+//! | `1003` | The above, and every move                         |
+//! | `1006` | Encodes the events as SGR sequences               |
+//!
+//! [`MouseReporter`] tracks these modes from the output of the remote app,
+//! and encodes the local events accordingly:
+//!
+//! ```rust
+//! use ratzilla::{
+//!     event::{MouseButton, MouseEvent, MouseEventKind},
+//!     mouse_report::MouseReporter,
+//!     ratatui::layout::Position,
+//! };
+//!
+//! let mut reporter = MouseReporter::new();
+//! // output of the remote app, enabling mouse reporting with SGR encoding
+//! reporter.observe(b"\x1b[?1000h\x1b[?1006h");
+//!
+//! let event = MouseEvent {
+//!     button: MouseButton::Left,
+//!     event: MouseEventKind::Pressed,
+//!     x: 0,
+//!     y: 0,
+//!     cell: Some(Position::new(4, 2)),
+//!     ctrl: false,
+//!     alt: false,
+//!     shift: false,
+//! };
+//! assert_eq!(reporter.encode_mouse(&event), Some(b"\x1b[<0;5;3M".to_vec()));
+//! ```
+//!
+//! The default encoding can't report cells past the 223rd column or row;
+//! such events are dropped, as xterm does.
+//!
+//! [`MouseReporter`]: crate::mouse_report::MouseReporter
+
+use ratatui::layout::Position;
+
+use crate::event::{MouseButton, MouseEvent, MouseEventKind, WheelEvent};
+
+/// Longest parameter list of a tracked private mode sequence, in bytes.
+const MAX_PARAMS_LEN: usize = 32;
+
+/// Largest coordinate of the default encoding, 1-based.
+const MAX_DEFAULT_COORDINATE: u16 = 255 - 32;
+
+/// Button code of a move without any button held.
+const NO_BUTTON: u16 = 3;
+
+/// Added to the button code of moves.
+const MOTION: u16 = 32;
+
+/// Button code of scrolling up, followed by down, left and right.
+const WHEEL_UP: u16 = 64;
+
+/// Which mouse events are reported.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MouseTracking {
+    /// No events, the default.
+    #[default]
+    Off,
+    /// Button presses only, without modifiers (mode `9`).
+    X10,
+    /// Button presses and releases, and the wheel (mode `1000`).
+    Normal,
+    /// The normal events, and moves while a button is held (mode `1002`).
+    ButtonEvent,
+    /// The normal events, and every move (mode `1003`).
+    AnyEvent,
+}
+
+impl MouseTracking {
+    /// Returns the tracking enabled by the given private mode, if any.
+    fn from_mode(mode: u16) -> Option<Self> {
+        match mode {
+            9 => Some(Self::X10),
+            1000 => Some(Self::Normal),
+            1002 => Some(Self::ButtonEvent),
+            1003 => Some(Self::AnyEvent),
+            _ => None,
+        }
+    }
+}
+
+/// How mouse events are encoded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MouseEncoding {
+    /// `CSI M` followed by three bytes, the default.
+    #[default]
+    Default,
+    /// `CSI <` followed by decimal parameters (mode `1006`).
+    Sgr,
+}
+
+/// State of the scan for private mode sequences.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+enum Scan {
+    /// Outside of an escape sequence.
+    #[default]
+    Ground,
+    /// After `ESC`.
+    Escape,
+    /// After `CSI`, with whether it is a private sequence and its
+    /// parameters so far.
+    Csi { private: bool, params: Vec<u8> },
+}
+
+/// Encodes mouse events for the modes enabled by a remote app.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MouseReporter {
+    /// Which events are reported.
+    tracking: MouseTracking,
+    /// How events are encoded.
+    encoding: MouseEncoding,
+    /// Cell of the last reported move, to only report moves between cells.
+    last_cell: Option<Position>,
+    /// Scan of the output, kept between chunks.
+    scan: Scan,
+}
+
+impl MouseReporter {
+    /// Constructs a new [`MouseReporter`] with mouse reporting disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns which events are reported.
+    pub fn tracking(&self) -> MouseTracking {
+        self.tracking
+    }
+
+    /// Returns how events are encoded.
+    pub fn encoding(&self) -> MouseEncoding {
+        self.encoding
+    }
+
+    /// Disables mouse reporting, e.g. when the remote app exits.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Sets or resets a private mode, as with `DECSET` and `DECRST`.
+    ///
+    /// Returns `true` if the mode is a mouse reporting mode.
+    pub fn set_mode(&mut self, mode: u16, enabled: bool) -> bool {
+        if let Some(tracking) = MouseTracking::from_mode(mode) {
+            if enabled {
+                self.tracking = tracking;
+            } else if self.tracking == tracking {
+                self.tracking = MouseTracking::Off;
+            }
+            self.last_cell = None;
+            true
+        } else if mode == 1006 {
+            self.encoding = if enabled {
+                MouseEncoding::Sgr
+            } else {
+                MouseEncoding::Default
+            };
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Tracks the mouse reporting modes set in the output of the remote app.
+    ///
+    /// The output can be passed in chunks of any size, sequences split
+    /// between two chunks are recognized.
+    pub fn observe(&mut self, output: &[u8]) {
+        for &byte in output {
+            self.scan = match (std::mem::take(&mut self.scan), byte) {
+                (_, 0x1b) => Scan::Escape,
+                (Scan::Escape, b'[') => Scan::Csi {
+                    private: false,
+                    params: Vec::new(),
+                },
+                (
+                    Scan::Csi {
+                        private: false,
+                        params,
+                    },
+                    b'?',
+                ) if params.is_empty() => Scan::Csi {
+                    private: true,
+                    params,
+                },
+                (
+                    Scan::Csi {
+                        private,
+                        mut params,
+                    },
+                    b'0'..=b'9' | b';',
+                ) if params.len() < MAX_PARAMS_LEN => {
+                    params.push(byte);
+                    Scan::Csi { private, params }
+                }
+                (
+                    Scan::Csi {
+                        private: true,
+                        params,
+                    },
+                    b'h' | b'l',
+                ) => {
+                    for mode in params.split(|&b| b == b';') {
+                        let mode = std::str::from_utf8(mode).ok().and_then(|m| m.parse().ok());
+                        if let Some(mode) = mode {
+                            self.set_mode(mode, byte == b'h');
+                        }
+                    }
+                    Scan::Ground
+                }
+                _ => Scan::Ground,
+            };
+        }
+    }
+
+    /// Encodes a mouse event, or returns `None` if it isn't reported.
+    pub fn encode_mouse(&mut self, event: &MouseEvent) -> Option<Vec<u8>> {
+        let cell = event.cell?;
+        let button = match event.button {
+            MouseButton::Left => 0,
+            MouseButton::Middle => 1,
+            MouseButton::Right => 2,
+            MouseButton::Back => 128,
+            MouseButton::Forward => 129,
+            MouseButton::Unidentified => NO_BUTTON,
+        };
+        let (code, release) = match (&event.event, self.tracking) {
+            (_, MouseTracking::Off) => return None,
+            (MouseEventKind::Pressed, _) if button != NO_BUTTON => (button, false),
+            (MouseEventKind::Released, tracking) if tracking != MouseTracking::X10 => {
+                // the default encoding doesn't tell which button was released
+                match self.encoding {
+                    MouseEncoding::Default => (NO_BUTTON, true),
+                    MouseEncoding::Sgr => (button, true),
+                }
+            }
+            (MouseEventKind::Dragged, MouseTracking::ButtonEvent | MouseTracking::AnyEvent)
+            | (MouseEventKind::Moved, MouseTracking::AnyEvent) => {
+                if self.last_cell == Some(cell) {
+                    return None;
+                }
+                self.last_cell = Some(cell);
+                (button + MOTION, false)
+            }
+            _ => return None,
+        };
+        let code = match self.tracking {
+            MouseTracking::X10 => code,
+            _ => code + modifiers(event.shift, event.alt, event.ctrl),
+        };
+        self.encode(code, cell, release)
+    }
+
+    /// Encodes a wheel event, with a report for each row and column
+    /// scrolled, or returns `None` if it isn't reported.
+    pub fn encode_wheel(&self, event: &WheelEvent) -> Option<Vec<u8>> {
+        let cell = event.position?;
+        if matches!(self.tracking, MouseTracking::Off | MouseTracking::X10) {
+            return None;
+        }
+        let modifiers = modifiers(event.shift, event.alt, event.ctrl);
+        let rows = (WHEEL_UP + u16::from(event.delta_rows > 0), event.delta_rows);
+        let columns = (
+            WHEEL_UP + 2 + u16::from(event.delta_cols > 0),
+            event.delta_cols,
+        );
+        let mut encoded = Vec::new();
+        for (code, delta) in [rows, columns] {
+            for _ in 0..delta.unsigned_abs() {
+                encoded.extend(self.encode(code + modifiers, cell, false)?);
+            }
+        }
+        (!encoded.is_empty()).then_some(encoded)
+    }
+
+    /// Encodes a report of the given button code at the given cell.
+    fn encode(&self, code: u16, cell: Position, release: bool) -> Option<Vec<u8>> {
+        // coordinates are 1-based
+        let (x, y) = (cell.x.saturating_add(1), cell.y.saturating_add(1));
+        match self.encoding {
+            MouseEncoding::Sgr => {
+                let end = if release { 'm' } else { 'M' };
+                Some(format!("\x1b[<{code};{x};{y}{end}").into_bytes())
+            }
+            MouseEncoding::Default => {
+                if x > MAX_DEFAULT_COORDINATE || y > MAX_DEFAULT_COORDINATE {
+                    return None;
+                }
+                let code = u8::try_from(code + 32).ok()?;
+                Some(vec![0x1b, b'[', b'M', code, x as u8 + 32, y as u8 + 32])
+            }
+        }
+    }
+}
+
+/// Returns the bits of the given modifiers in a button code.
+fn modifiers(shift: bool, alt: bool, ctrl: bool) -> u16 {
+    u16::from(shift) * 4 + u16::from(alt) * 8 + u16::from(ctrl) * 16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mouse(button: MouseButton, event: MouseEventKind, x: u16, y: u16) -> MouseEvent {
+        MouseEvent {
+            button,
+            event,
+            x: 0,
+            y: 0,
+            cell: Some(Position::new(x, y)),
+            ctrl: false,
+            alt: false,
+            shift: false,
+        }
+    }
+
+    fn wheel(delta_rows: i32, delta_cols: i32) -> WheelEvent {
+        WheelEvent {
+            delta_rows,
+            delta_cols,
+            position: Some(Position::new(0, 0)),
+            ctrl: false,
+            alt: false,
+            shift: false,
+        }
+    }
+
+    #[test]
+    fn test_observe_modes() {
+        let mut reporter = MouseReporter::new();
+        reporter.observe(b"text\x1b[?1002;10");
+        assert_eq!(reporter.tracking(), MouseTracking::Off);
+        reporter.observe(b"06hmore text\x1b[?25l");
+        assert_eq!(reporter.tracking(), MouseTracking::ButtonEvent);
+        assert_eq!(reporter.encoding(), MouseEncoding::Sgr);
+
+        // not a private mode
+        reporter.observe(b"\x1b[1002l");
+        assert_eq!(reporter.tracking(), MouseTracking::ButtonEvent);
+        reporter.observe(b"\x1b[?1002l\x1b[?1006l");
+        assert_eq!(reporter.tracking(), MouseTracking::Off);
+        assert_eq!(reporter.encoding(), MouseEncoding::Default);
+    }
+
+    #[test]
+    fn test_encode_default() {
+        let mut reporter = MouseReporter::new();
+        let press = mouse(MouseButton::Left, MouseEventKind::Pressed, 4, 2);
+        assert_eq!(reporter.encode_mouse(&press), None);
+
+        reporter.set_mode(1000, true);
+        assert_eq!(reporter.encode_mouse(&press), Some(b"\x1b[M %#".to_vec()));
+        let release = mouse(MouseButton::Left, MouseEventKind::Released, 4, 2);
+        assert_eq!(reporter.encode_mouse(&release), Some(b"\x1b[M#%#".to_vec()));
+        let far = mouse(MouseButton::Left, MouseEventKind::Pressed, 300, 2);
+        assert_eq!(reporter.encode_mouse(&far), None);
+    }
+
+    #[test]
+    fn test_encode_sgr() {
+        let mut reporter = MouseReporter::new();
+        reporter.set_mode(1000, true);
+        reporter.set_mode(1006, true);
+        let mut press = mouse(MouseButton::Right, MouseEventKind::Pressed, 299, 0);
+        press.ctrl = true;
+        assert_eq!(
+            reporter.encode_mouse(&press),
+            Some(b"\x1b[<18;300;1M".to_vec())
+        );
+        let release = mouse(MouseButton::Right, MouseEventKind::Released, 299, 0);
+        assert_eq!(
+            reporter.encode_mouse(&release),
+            Some(b"\x1b[<2;300;1m".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_x10() {
+        let mut reporter = MouseReporter::new();
+        reporter.set_mode(9, true);
+        let mut press = mouse(MouseButton::Middle, MouseEventKind::Pressed, 0, 0);
+        press.shift = true;
+        assert_eq!(reporter.encode_mouse(&press), Some(b"\x1b[M!!!".to_vec()));
+        let release = mouse(MouseButton::Middle, MouseEventKind::Released, 0, 0);
+        assert_eq!(reporter.encode_mouse(&release), None);
+    }
+
+    #[test]
+    fn test_motion() {
+        let mut reporter = MouseReporter::new();
+        reporter.set_mode(1006, true);
+        reporter.set_mode(1002, true);
+        let moved = mouse(MouseButton::Unidentified, MouseEventKind::Moved, 1, 1);
+        assert_eq!(reporter.encode_mouse(&moved), None);
+        let drag = mouse(MouseButton::Left, MouseEventKind::Dragged, 1, 1);
+        assert_eq!(
+            reporter.encode_mouse(&drag),
+            Some(b"\x1b[<32;2;2M".to_vec())
+        );
+        // same cell
+        assert_eq!(reporter.encode_mouse(&drag), None);
+
+        reporter.set_mode(1003, true);
+        let moved = mouse(MouseButton::Unidentified, MouseEventKind::Moved, 2, 1);
+        assert_eq!(
+            reporter.encode_mouse(&moved),
+            Some(b"\x1b[<35;3;2M".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_encode_wheel() {
+        let mut reporter = MouseReporter::new();
+        assert_eq!(reporter.encode_wheel(&wheel(1, 0)), None);
+        reporter.set_mode(1000, true);
+        reporter.set_mode(1006, true);
+        assert_eq!(
+            reporter.encode_wheel(&wheel(-2, 0)),
+            Some(b"\x1b[<64;1;1M\x1b[<64;1;1M".to_vec())
+        );
+        assert_eq!(
+            reporter.encode_wheel(&wheel(1, 1)),
+            Some(b"\x1b[<65;1;1M\x1b[<67;1;1M".to_vec())
+        );
+        assert_eq!(reporter.encode_wheel(&wheel(0, 0)), None);
+    }
+}
@@ -0,0 +1,282 @@
+//! ## Platform
+//!
+//! The [`Platform`] trait gathers what differs between running an app in a
+//! terminal and in a browser: creating the terminal, receiving input
+//! events, running the render loop, spawning async work, persisting small
+//! values and opening links. An app written against the trait compiles for
+//! both targets, with [`WebPlatform`] on the web and a native
+//! implementation in the terminal.
+//!
+//! ```rust no_run
+//! use ratzilla::{
+//!     event::{InputEvent, KeyCode},
+//!     platform::{Platform, WebPlatform},
+//!     ratatui::widgets::Paragraph,
+//! };
+//! use std::{cell::Cell, rc::Rc};
+//!
+//! fn start<P: Platform>(platform: P) -> Result<(), P::Error> {
+//!     let terminal = platform.init_terminal()?;
+//!     let count = Rc::new(Cell::new(platform.storage_get("count").map_or(0, |c| c.parse().unwrap_or(0))));
+//!     platform.on_event(&terminal, {
+//!         let count = count.clone();
+//!         move |event| {
+//!             if let InputEvent::Key(key) = event {
+//!                 if key.code == KeyCode::Enter {
+//!                     count.set(count.get() + 1);
+//!                 }
+//!             }
+//!         }
+//!     });
+//!     platform.run(terminal, move |frame| {
+//!         frame.render_widget(Paragraph::new(count.get().to_string()), frame.area());
+//!     })
+//! }
+//!
+//! start(WebPlatform::dom()).unwrap();
+//! ```
+//!
+//! ### Native counterpart
+//!
+//! ratzilla only runs in the browser, so the native implementation lives in
+//! the app, e.g. with crossterm and the `crossterm` feature for the key
+//! conversions:
+//!
+//! ```rust ignore
+//! struct NativePlatform {
+//!     events: RefCell<Vec<Box<dyn FnMut(InputEvent)>>>,
+//! }
+//!
+//! impl Platform for NativePlatform {
+//!     type Backend = CrosstermBackend<Stdout>;
+//!     type Error = io::Error;
+//!
+//!     fn init_terminal(&self) -> io::Result<Terminal<Self::Backend>> {
+//!         Ok(ratatui::init())
+//!     }
+//!
+//!     fn on_event<F: FnMut(InputEvent) + 'static>(&self, _: &Terminal<Self::Backend>, callback: F) {
+//!         self.events.borrow_mut().push(Box::new(callback));
+//!     }
+//!
+//!     fn run<F: FnMut(&mut Frame) + 'static>(
+//!         &self,
+//!         mut terminal: Terminal<Self::Backend>,
+//!         mut render_callback: F,
+//!     ) -> io::Result<()> {
+//!         loop {
+//!             terminal.draw(&mut render_callback)?;
+//!             if event::poll(Duration::from_millis(16))? {
+//!                 if let event::Event::Key(key) = event::read()? {
+//!                     if key.code == event::KeyCode::Char('q') {
+//!                         break;
+//!                     }
+//!                     for callback in self.events.borrow_mut().iter_mut() {
+//!                         callback(InputEvent::Key(key.into()));
+//!                     }
+//!                 }
+//!             }
+//!         }
+//!         ratatui::restore();
+//!         Ok(())
+//!     }
+//!
+//!     fn spawn<F: Future<Output = ()> + 'static>(&self, future: F) {
+//!         // e.g. a `tokio::task::LocalSet` or a thread with `futures::executor::block_on`
+//!     }
+//!
+//!     fn storage_get(&self, key: &str) -> Option<String> {
+//!         fs::read_to_string(config_dir().join(key)).ok()
+//!     }
+//!
+//!     fn storage_set(&self, key: &str, value: &str) -> io::Result<()> {
+//!         fs::write(config_dir().join(key), value)
+//!     }
+//!
+//!     fn storage_remove(&self, key: &str) -> io::Result<()> {
+//!         fs::remove_file(config_dir().join(key))
+//!     }
+//!
+//!     fn open_url(&self, url: &str) -> io::Result<()> {
+//!         open::that(url)
+//!     }
+//! }
+//! ```
+//!
+//! [`Platform`]: crate::platform::Platform
+//! [`WebPlatform`]: crate::platform::WebPlatform
+
+use std::{cell::RefCell, future::Future, rc::Rc};
+
+use ratatui::{prelude::Backend, Frame, Terminal};
+
+#[cfg(feature = "canvas")]
+use crate::CanvasBackend;
+#[cfg(feature = "dom")]
+use crate::DomBackend;
+#[cfg(feature = "webgl2")]
+use crate::WebGl2Backend;
+#[cfg(feature = "webgpu")]
+use crate::WgpuBackend;
+use crate::{
+    error::Error,
+    event::InputEvent,
+    utils::{self, storage},
+    WebRenderer,
+};
+
+/// What an app needs from the platform it runs on.
+pub trait Platform {
+    /// The backend of the terminal.
+    type Backend: Backend + 'static;
+
+    /// The error returned by the platform.
+    type Error: std::error::Error + 'static;
+
+    /// Creates the terminal.
+    fn init_terminal(&self) -> Result<Terminal<Self::Backend>, Self::Error>;
+
+    /// Registers a callback called with every input event.
+    fn on_event<F>(&self, terminal: &Terminal<Self::Backend>, callback: F)
+    where
+        F: FnMut(InputEvent) + 'static;
+
+    /// Runs the render loop, calling the callback to draw every frame.
+    ///
+    /// Depending on the platform, this returns when the app quits or right
+    /// away, leaving the loop running.
+    fn run<F>(
+        &self,
+        terminal: Terminal<Self::Backend>,
+        render_callback: F,
+    ) -> Result<(), Self::Error>
+    where
+        F: FnMut(&mut Frame) + 'static;
+
+    /// Runs a future in the background.
+    fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + 'static;
+
+    /// Returns a persisted value.
+    fn storage_get(&self, key: &str) -> Option<String>;
+
+    /// Persists a value.
+    fn storage_set(&self, key: &str, value: &str) -> Result<(), Self::Error>;
+
+    /// Removes a persisted value.
+    fn storage_remove(&self, key: &str) -> Result<(), Self::Error>;
+
+    /// Opens a URL, in a new tab on the web.
+    fn open_url(&self, url: &str) -> Result<(), Self::Error>;
+}
+
+/// The browser [`Platform`].
+///
+/// Values are persisted in `localStorage`, and [`run`](Platform::run)
+/// returns right away, leaving the render loop running on animation frames.
+#[derive(Debug)]
+pub struct WebPlatform<B> {
+    /// Constructs the backend.
+    backend: fn() -> Result<B, Error>,
+}
+
+#[cfg(feature = "dom")]
+impl WebPlatform<DomBackend> {
+    /// Constructs a new [`WebPlatform`] with the [`DomBackend`].
+    pub fn dom() -> Self {
+        Self::with_backend(DomBackend::new)
+    }
+}
+
+#[cfg(feature = "canvas")]
+impl WebPlatform<CanvasBackend> {
+    /// Constructs a new [`WebPlatform`] with the [`CanvasBackend`].
+    pub fn canvas() -> Self {
+        Self::with_backend(CanvasBackend::new)
+    }
+}
+
+#[cfg(feature = "webgl2")]
+impl WebPlatform<WebGl2Backend> {
+    /// Constructs a new [`WebPlatform`] with the [`WebGl2Backend`].
+    pub fn webgl2() -> Self {
+        Self::with_backend(WebGl2Backend::new)
+    }
+}
+
+#[cfg(feature = "webgpu")]
+impl WebPlatform<WgpuBackend> {
+    /// Constructs a new [`WebPlatform`] with the [`WgpuBackend`].
+    pub fn webgpu() -> Self {
+        Self::with_backend(WgpuBackend::new)
+    }
+}
+
+impl<B> WebPlatform<B> {
+    /// Constructs a new [`WebPlatform`] creating the backend with the given
+    /// function.
+    pub fn with_backend(backend: fn() -> Result<B, Error>) -> Self {
+        Self { backend }
+    }
+}
+
+impl<B> Platform for WebPlatform<B>
+where
+    B: Backend + 'static,
+{
+    type Backend = B;
+    type Error = Error;
+
+    fn init_terminal(&self) -> Result<Terminal<B>, Error> {
+        let backend = (self.backend)()?;
+        Terminal::new(backend).map_err(|e| Error::UnableToInitializeTerminal(e.to_string()))
+    }
+
+    fn on_event<F>(&self, terminal: &Terminal<B>, callback: F)
+    where
+        F: FnMut(InputEvent) + 'static,
+    {
+        let callback = Rc::new(RefCell::new(callback));
+        terminal.on_key_event({
+            let callback = callback.clone();
+            move |event| (*callback.borrow_mut())(InputEvent::Key(event))
+        });
+        terminal.on_mouse_event({
+            let callback = callback.clone();
+            move |event| (*callback.borrow_mut())(InputEvent::Mouse(event))
+        });
+        terminal.on_wheel_event(move |event| (*callback.borrow_mut())(InputEvent::Wheel(event)));
+    }
+
+    fn run<F>(&self, terminal: Terminal<B>, render_callback: F) -> Result<(), Error>
+    where
+        F: FnMut(&mut Frame) + 'static,
+    {
+        terminal.draw_web(render_callback);
+        Ok(())
+    }
+
+    fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        wasm_bindgen_futures::spawn_local(future);
+    }
+
+    fn storage_get(&self, key: &str) -> Option<String> {
+        storage::get_item(key).ok().flatten()
+    }
+
+    fn storage_set(&self, key: &str, value: &str) -> Result<(), Error> {
+        storage::set_item(key, value)
+    }
+
+    fn storage_remove(&self, key: &str) -> Result<(), Error> {
+        storage::remove_item(key)
+    }
+
+    fn open_url(&self, url: &str) -> Result<(), Error> {
+        utils::open_url(url, true)
+    }
+}
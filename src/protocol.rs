@@ -0,0 +1,227 @@
+//! ## Binary protocol
+//!
+//! Enabled with the `protocol` feature.
+//!
+//! Thin helpers around [serde] and [postcard] for apps exchanging binary
+//! messages with a Rust server, e.g. over a `WebSocket`. Every message is
+//! wrapped in a small frame:
+//!
+//! | Bytes | Content                                  |
+//! |-------|------------------------------------------|
+//! | 1     | Protocol version                         |
+//! | 4     | Payload length, little endian            |
+//! | n     | Payload, serialized with [postcard]      |
+//!
+//! The version lets both sides reject messages from incompatible clients
+//! instead of misinterpreting them, and the length makes it possible to
+//! split messages out of byte streams with [`FrameDecoder`].
+//!
+//! ```rust no_run
+//! use ratzilla::protocol::Codec;
+//! # fn example() -> Result<(), ratzilla::error::Error> {
+//!
+//! let codec = Codec::new(1);
+//! let bytes = codec.encode(&("move", 3u8, 4u8))?;
+//! let message: (String, u8, u8) = codec.decode(&bytes)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [serde]: https://serde.rs
+//! [postcard]: https://docs.rs/postcard
+//! [`FrameDecoder`]: crate::protocol::FrameDecoder
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::Error;
+
+/// Length of the frame header, in bytes.
+pub const HEADER_LEN: usize = 5;
+
+/// Default maximum payload length, in bytes.
+const DEFAULT_MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+/// Encodes and decodes framed messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Codec {
+    /// Protocol version written into and expected from every frame.
+    version: u8,
+    /// Maximum payload length, in bytes.
+    max_frame_len: usize,
+}
+
+impl Codec {
+    /// Constructs a new [`Codec`] for the given protocol version.
+    pub fn new(version: u8) -> Self {
+        Self {
+            version,
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+        }
+    }
+
+    /// Sets the maximum payload length, in bytes.
+    ///
+    /// Larger frames are rejected when decoding, which protects against
+    /// buffering unbounded amounts of data from a misbehaving peer.
+    pub fn max_frame_len(mut self, len: usize) -> Self {
+        self.max_frame_len = len;
+        self
+    }
+
+    /// Returns the protocol version.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Serializes a message into a frame.
+    pub fn encode<T: Serialize>(&self, message: &T) -> Result<Vec<u8>, Error> {
+        let payload = postcard::to_allocvec(message).map_err(protocol_error)?;
+        if payload.len() > self.max_frame_len {
+            return Err(Error::Protocol(format!(
+                "frame of {} bytes exceeds the limit of {} bytes",
+                payload.len(),
+                self.max_frame_len
+            )));
+        }
+        let mut frame = Vec::with_capacity(HEADER_LEN + payload.len());
+        frame.push(self.version);
+        frame.extend((payload.len() as u32).to_le_bytes());
+        frame.extend(payload);
+        Ok(frame)
+    }
+
+    /// Deserializes a message from a single, complete frame.
+    pub fn decode<T: DeserializeOwned>(&self, frame: &[u8]) -> Result<T, Error> {
+        match self.frame_len(frame)? {
+            Some(len) if len == frame.len() => self.decode_payload(&frame[HEADER_LEN..]),
+            _ => Err(Error::Protocol(format!(
+                "frame length doesn't match the {} received bytes",
+                frame.len()
+            ))),
+        }
+    }
+
+    /// Returns the total length of the frame starting at the given bytes, or
+    /// `None` if the header is incomplete.
+    fn frame_len(&self, bytes: &[u8]) -> Result<Option<usize>, Error> {
+        if bytes.len() < HEADER_LEN {
+            return Ok(None);
+        }
+        if bytes[0] != self.version {
+            return Err(Error::Protocol(format!(
+                "unsupported protocol version {}, expected {}",
+                bytes[0], self.version
+            )));
+        }
+        let len = u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]) as usize;
+        if len > self.max_frame_len {
+            return Err(Error::Protocol(format!(
+                "frame of {len} bytes exceeds the limit of {} bytes",
+                self.max_frame_len
+            )));
+        }
+        Ok(Some(HEADER_LEN + len))
+    }
+
+    /// Deserializes a payload.
+    fn decode_payload<T: DeserializeOwned>(&self, payload: &[u8]) -> Result<T, Error> {
+        postcard::from_bytes(payload).map_err(protocol_error)
+    }
+}
+
+/// Splits a byte stream into messages.
+///
+/// Useful for transports that don't preserve message boundaries, such as
+/// WebTransport streams.
+#[derive(Debug, Clone)]
+pub struct FrameDecoder {
+    /// The codec.
+    codec: Codec,
+    /// Bytes received but not decoded yet.
+    buffer: Vec<u8>,
+}
+
+impl FrameDecoder {
+    /// Constructs a new [`FrameDecoder`] using the given codec.
+    pub fn new(codec: Codec) -> Self {
+        Self {
+            codec,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Appends received bytes.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Decodes the next complete message, if any.
+    ///
+    /// After an error the stream is out of sync and should be closed.
+    pub fn decode_next<T: DeserializeOwned>(&mut self) -> Result<Option<T>, Error> {
+        let Some(len) = self.codec.frame_len(&self.buffer)? else {
+            return Ok(None);
+        };
+        if self.buffer.len() < len {
+            return Ok(None);
+        }
+        let message = self.codec.decode_payload(&self.buffer[HEADER_LEN..len]);
+        self.buffer.drain(..len);
+        message.map(Some)
+    }
+
+    /// Returns the number of buffered bytes.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+}
+
+/// Converts a postcard error into a [`Error`].
+fn protocol_error(error: postcard::Error) -> Error {
+    Error::Protocol(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() -> Result<(), Error> {
+        let codec = Codec::new(3);
+        let bytes = codec.encode(&("hello".to_string(), 42u32))?;
+        assert_eq!(bytes[0], 3);
+        let message: (String, u32) = codec.decode(&bytes)?;
+        assert_eq!(message, ("hello".to_string(), 42));
+        Ok(())
+    }
+
+    #[test]
+    fn test_version_mismatch() -> Result<(), Error> {
+        let bytes = Codec::new(1).encode(&7u8)?;
+        assert!(Codec::new(2).decode::<u8>(&bytes).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_frame_limit() {
+        let codec = Codec::new(1).max_frame_len(4);
+        assert!(codec.encode(&vec![0u8; 10]).is_err());
+    }
+
+    #[test]
+    fn test_frame_decoder() -> Result<(), Error> {
+        let codec = Codec::new(1);
+        let mut stream = codec.encode(&1u16)?;
+        stream.extend(codec.encode(&"two")?);
+
+        let mut decoder = FrameDecoder::new(codec);
+        let (head, tail) = stream.split_at(4);
+        decoder.push(head);
+        assert_eq!(decoder.decode_next::<u16>()?, None);
+        decoder.push(tail);
+        assert_eq!(decoder.decode_next::<u16>()?, Some(1));
+        assert_eq!(decoder.decode_next::<String>()?, Some("two".to_string()));
+        assert_eq!(decoder.buffered(), 0);
+        Ok(())
+    }
+}
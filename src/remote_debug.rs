@@ -0,0 +1,264 @@
+//! ## Remote debugging
+//!
+//! Enabled with the `remote-debug` feature.
+//!
+//! [`RemoteDebugger`] streams the live buffer, a log of the input events and
+//! frame statistics of a running app as JSON messages, either over a
+//! [`WebSocket`] or a [`BroadcastChannel`]. This makes it possible to inspect
+//! an app running on a phone from a desktop (through a small relay server),
+//! or from a devtools tab of the same browser.
+//!
+//! ```rust no_run
+//! use std::{cell::RefCell, rc::Rc};
+//! use ratzilla::{remote_debug::RemoteDebugger, WebRenderer};
+//! # fn example(terminal: ratzilla::ratatui::Terminal<ratzilla::DomBackend>) {
+//!
+//! let debugger = Rc::new(RefCell::new(
+//!     RemoteDebugger::websocket("ws://192.168.1.10:9000").unwrap(),
+//! ));
+//! terminal.on_key_event({
+//!     let debugger = debugger.clone();
+//!     move |event| debugger.borrow_mut().log_key_event(&event)
+//! });
+//! terminal.draw_web(move |frame| {
+//!     // render widgets...
+//!     debugger.borrow_mut().capture(frame);
+//! });
+//! # }
+//! ```
+//!
+//! Every message is a JSON object with a `type` field:
+//!
+//! - `frame`: `{"type":"frame","width":80,"height":24,"lines":["..."]}`
+//! - `event`: `{"type":"event","time":1234.5,"event":"KeyEvent { .. }"}`
+//! - `stats`: `{"type":"stats","fps":60.0,"frame_time":1.2}`
+//!
+//! [`WebSocket`]: https://developer.mozilla.org/en-US/docs/Web/API/WebSocket
+//! [`BroadcastChannel`]: https://developer.mozilla.org/en-US/docs/Web/API/BroadcastChannel
+//! [`RemoteDebugger`]: crate::remote_debug::RemoteDebugger
+
+use std::{collections::VecDeque, fmt::Write};
+
+use ratatui::{buffer::Buffer, Frame};
+use web_sys::{BroadcastChannel, WebSocket};
+
+use crate::{
+    clock::now,
+    error::Error,
+    event::{KeyEvent, MouseEvent},
+};
+
+/// Default interval between two buffer snapshots, in milliseconds.
+const DEFAULT_SNAPSHOT_INTERVAL_MS: f64 = 250.0;
+
+/// Maximum number of events kept while the connection is not open.
+const MAX_PENDING_EVENTS: usize = 256;
+
+/// The transport used to send debug messages.
+#[derive(Debug)]
+enum Transport {
+    /// A WebSocket connection.
+    WebSocket(WebSocket),
+    /// A BroadcastChannel.
+    BroadcastChannel(BroadcastChannel),
+}
+
+impl Transport {
+    /// Returns `true` if messages can be sent.
+    fn is_open(&self) -> bool {
+        match self {
+            Transport::WebSocket(socket) => socket.ready_state() == WebSocket::OPEN,
+            Transport::BroadcastChannel(_) => true,
+        }
+    }
+
+    /// Sends a message.
+    fn send(&self, message: &str) -> Result<(), Error> {
+        match self {
+            Transport::WebSocket(socket) => socket.send_with_str(message)?,
+            Transport::BroadcastChannel(channel) => channel.post_message(&message.into())?,
+        }
+        Ok(())
+    }
+}
+
+/// Streams the state of a running app to a remote inspector.
+#[derive(Debug)]
+pub struct RemoteDebugger {
+    /// The transport.
+    transport: Transport,
+    /// Interval between two buffer snapshots, in milliseconds.
+    snapshot_interval: f64,
+    /// Timestamp of the last snapshot, in milliseconds.
+    last_snapshot: Option<f64>,
+    /// Timestamp of the last captured frame, in milliseconds.
+    last_frame: Option<f64>,
+    /// Frames captured since the last snapshot.
+    frames: u32,
+    /// Events waiting for the connection to open.
+    pending: VecDeque<String>,
+}
+
+impl RemoteDebugger {
+    /// Constructs a new [`RemoteDebugger`] sending messages to the given WebSocket URL.
+    pub fn websocket(url: &str) -> Result<Self, Error> {
+        Ok(Self::new(Transport::WebSocket(WebSocket::new(url)?)))
+    }
+
+    /// Constructs a new [`RemoteDebugger`] posting messages to the
+    /// BroadcastChannel with the given name.
+    pub fn broadcast_channel(name: &str) -> Result<Self, Error> {
+        Ok(Self::new(Transport::BroadcastChannel(
+            BroadcastChannel::new(name)?,
+        )))
+    }
+
+    fn new(transport: Transport) -> Self {
+        Self {
+            transport,
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL_MS,
+            last_snapshot: None,
+            last_frame: None,
+            frames: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Sets the interval between two buffer snapshots, in milliseconds.
+    pub fn snapshot_interval(mut self, interval_ms: u32) -> Self {
+        self.snapshot_interval = interval_ms as f64;
+        self
+    }
+
+    /// Logs a key event.
+    pub fn log_key_event(&mut self, event: &KeyEvent) {
+        self.log_event(&format!("{event:?}"));
+    }
+
+    /// Logs a mouse event.
+    pub fn log_mouse_event(&mut self, event: &MouseEvent) {
+        self.log_event(&format!("{event:?}"));
+    }
+
+    /// Logs an arbitrary event.
+    pub fn log_event(&mut self, event: &str) {
+        let message = format!(
+            "{{\"type\":\"event\",\"time\":{},\"event\":{}}}",
+            now(),
+            json_string(event)
+        );
+        if self.pending.len() == MAX_PENDING_EVENTS {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(message);
+        self.flush();
+    }
+
+    /// Captures the frame.
+    ///
+    /// Call this at the end of the [`draw_web`] callback. The buffer and the
+    /// frame statistics are sent at most once per snapshot interval.
+    ///
+    /// [`draw_web`]: crate::WebRenderer::draw_web
+    pub fn capture(&mut self, frame: &mut Frame) {
+        let now = now();
+        let frame_time = self.last_frame.map(|last| now - last).unwrap_or_default();
+        self.last_frame = Some(now);
+        self.frames += 1;
+
+        if let Some(last) = self.last_snapshot {
+            if now - last < self.snapshot_interval {
+                return;
+            }
+        }
+        if !self.transport.is_open() {
+            return;
+        }
+
+        let elapsed = self.last_snapshot.map(|last| now - last);
+        self.last_snapshot = Some(now);
+        if let Some(elapsed) = elapsed.filter(|e| *e > 0.0) {
+            let fps = self.frames as f64 * 1000.0 / elapsed;
+            let _ = self.transport.send(&format!(
+                "{{\"type\":\"stats\",\"fps\":{fps:.1},\"frame_time\":{frame_time:.1}}}"
+            ));
+        }
+        self.frames = 0;
+
+        let _ = self.transport.send(&buffer_message(frame.buffer_mut()));
+        self.flush();
+    }
+
+    /// Sends the pending events if the connection is open.
+    fn flush(&mut self) {
+        if !self.transport.is_open() {
+            return;
+        }
+        while let Some(message) = self.pending.pop_front() {
+            if self.transport.send(&message).is_err() {
+                self.pending.push_front(message);
+                break;
+            }
+        }
+    }
+}
+
+/// Serializes the content of a buffer into a `frame` message.
+fn buffer_message(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut message = format!(
+        "{{\"type\":\"frame\",\"width\":{},\"height\":{},\"lines\":[",
+        area.width, area.height
+    );
+    for y in area.top()..area.bottom() {
+        if y > area.top() {
+            message.push(',');
+        }
+        let line: String = (area.left()..area.right())
+            .map(|x| buffer[(x, y)].symbol())
+            .collect();
+        message.push_str(&json_string(&line));
+    }
+    message.push_str("]}");
+    message
+}
+
+/// Encodes a string as a JSON string literal.
+fn json_string(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len() + 2);
+    encoded.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => encoded.push_str("\\\""),
+            '\\' => encoded.push_str("\\\\"),
+            '\n' => encoded.push_str("\\n"),
+            '\r' => encoded.push_str("\\r"),
+            '\t' => encoded.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(encoded, "\\u{:04x}", c as u32);
+            }
+            c => encoded.push(c),
+        }
+    }
+    encoded.push('"');
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\"b\\c\n\u{1}"), r#""a\"b\\c\n\u0001""#);
+    }
+
+    #[test]
+    fn test_buffer_message() {
+        let buffer = Buffer::with_lines(["ab", "c\""]);
+        assert_eq!(
+            buffer_message(&buffer),
+            r#"{"type":"frame","width":2,"height":2,"lines":["ab","c\""]}"#
+        );
+    }
+}
@@ -1,8 +1,41 @@
-use ratatui::{prelude::Backend, Frame, Terminal};
-use std::{cell::RefCell, rc::Rc};
-use web_sys::{wasm_bindgen::prelude::*, window};
+use ratatui::{layout::Size, prelude::Backend, Frame, Terminal};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    js_sys::{self, Uint8Array},
+    wasm_bindgen::prelude::*,
+    window,
+};
 
-use crate::event::{KeyEvent, MouseEvent};
+#[cfg(feature = "event-stream")]
+use crate::event_stream::{Event, EventStream};
+use crate::{
+    actions, adaptive,
+    analytics::{self, AnalyticsEvent},
+    backend::{
+        metrics::CellMetrics,
+        utils::{get_document, get_window},
+        web,
+    },
+    clock,
+    event::{
+        self, wheel_delta_to_cells, CompositionEvent, ImagePasteEvent, InputEvent, KeyEvent,
+        MouseEvent, TouchEvent, TouchEventKind, WheelEvent,
+    },
+    frame_cache,
+    gesture::{self, GestureRecognizer},
+    images, key_capture, links, metadata,
+    render_loop::{self, RenderLoopOptions},
+    replay, resize, selection, stepping, teardown,
+    watchdog::{self, FrameTimings},
+};
+
+/// Size of the grid in cells, used for wheel events before a backend measured
+/// its grid.
+const FALLBACK_GRID_SIZE: Size = Size::new(80, 24);
 
 /// Trait for rendering on the web.
 ///
@@ -21,59 +54,482 @@ pub trait WebRenderer {
     where
         F: FnMut(&mut Frame) + 'static;
 
+    /// Renders the terminal on the web with the given render loop options.
+    ///
+    /// See [`render_loop`](crate::render_loop) to cap the frame rate or only
+    /// draw when [`request_redraw`](crate::render_loop::request_redraw) is
+    /// called.
+    fn draw_web_with_options<F>(self, options: RenderLoopOptions, render_callback: F)
+    where
+        Self: Sized,
+        F: FnMut(&mut Frame) + 'static,
+    {
+        render_loop::set_options(options);
+        self.draw_web(render_callback);
+    }
+
     /// Handles key events.
     ///
     /// This method takes a closure that will be called on every `keydown`
-    /// event.
+    /// event. The browser default of the keys selected with
+    /// [`set_key_capture`](crate::key_capture::set_key_capture) is prevented.
     fn on_key_event<F>(&self, mut callback: F)
     where
         F: FnMut(KeyEvent) + 'static,
     {
         let closure = Closure::<dyn FnMut(_)>::new(move |event: web_sys::KeyboardEvent| {
+            if event::is_composition_key(&event) {
+                // composed text is reported by composition events
+                return;
+            }
+            analytics::record_key_press();
+            let key_event = KeyEvent::from(event.clone());
+            if key_capture::captures(&key_event) {
+                event.prevent_default();
+            }
+            if !stepping::handle_key_event(&key_event) {
+                replay::record(InputEvent::Key(key_event.clone()));
+                callback(key_event);
+            }
+        });
+        add_event_listeners(
+            &document(),
+            &["keydown"],
+            closure.into_js_value().unchecked_ref(),
+        );
+    }
+
+    /// Handles key releases.
+    ///
+    /// This method takes a closure that will be called on every `keyup`
+    /// event, e.g. for games that track which keys are held down. Key
+    /// presses, including the ones repeated while a key is held, are
+    /// reported by [`on_key_event`](Self::on_key_event).
+    fn on_key_release<F>(&self, mut callback: F)
+    where
+        F: FnMut(KeyEvent) + 'static,
+    {
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: web_sys::KeyboardEvent| {
+            if event::is_composition_key(&event) {
+                return;
+            }
             callback(event.into());
         });
-        let window = window().unwrap();
-        let document = window.document().unwrap();
-        document
-            .add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref())
-            .unwrap();
-        closure.forget();
+        add_event_listeners(
+            &document(),
+            &["keyup"],
+            closure.into_js_value().unchecked_ref(),
+        );
     }
 
     /// Handles mouse events.
     ///
-    /// This method takes a closure that will be called on every `mousemove`, 'mousedown', and `mouseup`
-    /// event.
+    /// This method takes a closure that will be called on every `mousemove`,
+    /// `mousedown`, `mouseup`, `click` (and `auxclick`) and `dblclick` event.
+    /// Moves while a button is held are reported as
+    /// [`MouseEventKind::Dragged`], and every event carries the cell under
+    /// the mouse in [`MouseEvent::cell`].
+    ///
+    /// [`MouseEventKind::Dragged`]: crate::event::MouseEventKind::Dragged
     fn on_mouse_event<F>(&self, mut callback: F)
     where
         F: FnMut(MouseEvent) + 'static,
     {
         let closure = Closure::<dyn FnMut(_)>::new(move |event: web_sys::MouseEvent| {
+            let mut event: MouseEvent = event.into();
+            event.cell = web::grid_cell_at(event.x as f64, event.y as f64);
+            replay::record(InputEvent::Mouse(event.clone()));
+            callback(event);
+        });
+        add_event_listeners(
+            &document(),
+            &[
+                "mousemove",
+                "mousedown",
+                "mouseup",
+                "click",
+                "auxclick",
+                "dblclick",
+            ],
+            closure.into_js_value().unchecked_ref(),
+        );
+    }
+
+    /// Handles wheel events.
+    ///
+    /// This method takes a closure that will be called on every `wheel`
+    /// event that scrolls by at least one cell, see [`WheelEvent`].
+    fn on_wheel_event<F>(&self, mut callback: F)
+    where
+        F: FnMut(WheelEvent) + 'static,
+    {
+        // fractions of cells carried over to the next event
+        let mut remainder = (0.0, 0.0);
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: web_sys::WheelEvent| {
+            let grid = web::grid();
+            // before a backend measured its grid
+            let (cell_width, cell_height) =
+                grid.map_or_else(|| CellMetrics::default().cell_size(), |grid| grid.cell_size);
+            let size = grid.map_or(FALLBACK_GRID_SIZE, |grid| grid.size);
+            let mode = event.delta_mode();
+            remainder.0 += wheel_delta_to_cells(event.delta_x(), mode, cell_width, size.width);
+            remainder.1 += wheel_delta_to_cells(event.delta_y(), mode, cell_height, size.height);
+            let (cols, rows) = (remainder.0.trunc(), remainder.1.trunc());
+            remainder = (remainder.0 - cols, remainder.1 - rows);
+            if cols == 0.0 && rows == 0.0 {
+                return;
+            }
+            let event = WheelEvent {
+                delta_rows: rows as i32,
+                delta_cols: cols as i32,
+                position: grid.and_then(|grid| {
+                    grid.cell_at(event.client_x() as f64, event.client_y() as f64)
+                }),
+                ctrl: event.ctrl_key(),
+                alt: event.alt_key(),
+                shift: event.shift_key(),
+            };
+            replay::record(InputEvent::Wheel(event.clone()));
+            callback(event);
+        });
+        add_event_listeners(
+            &document(),
+            &["wheel"],
+            closure.into_js_value().unchecked_ref(),
+        );
+    }
+
+    /// Handles touch events.
+    ///
+    /// This method takes a closure that will be called on every
+    /// `touchstart`, `touchmove`, `touchend` and `touchcancel` event, and
+    /// for every recognized [`Gesture`]: taps, long presses, swipes and
+    /// pinches.
+    ///
+    /// [`Gesture`]: crate::event::Gesture
+    fn on_touch_event<F>(&self, callback: F)
+    where
+        F: FnMut(TouchEvent) + 'static,
+    {
+        let callback = Rc::new(RefCell::new(callback));
+        let recognizer = Rc::new(RefCell::new(GestureRecognizer::default()));
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: web_sys::TouchEvent| {
+            let Some(changed) = event.changed_touches().get(0) else {
+                return;
+            };
+            let touches = touch_positions(&event.touches());
+            let (x, y) = (changed.client_x() as f64, changed.client_y() as f64);
+            let touch_event = TouchEvent {
+                kind: TouchEventKind::Started,
+                x: x as u32,
+                y: y as u32,
+                cell: web::grid_cell_at(x, y),
+                touches: touches.len(),
+            };
+            let time = clock::now();
+            let (kind, gesture) = match event.type_().as_str() {
+                "touchstart" => {
+                    let generation = recognizer.borrow_mut().start(time, &touches);
+                    let long_press = Closure::once_into_js({
+                        let callback = callback.clone();
+                        let recognizer = recognizer.clone();
+                        let touch_event = touch_event.clone();
+                        move || {
+                            if let Some(gesture) = recognizer.borrow_mut().long_press(generation) {
+                                (*callback.borrow_mut())(TouchEvent {
+                                    kind: TouchEventKind::Gesture(gesture),
+                                    ..touch_event
+                                });
+                            }
+                        }
+                    });
+                    if let Some(window) = window() {
+                        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                            long_press.unchecked_ref(),
+                            gesture::LONG_PRESS_MS as i32,
+                        );
+                    }
+                    (TouchEventKind::Started, None)
+                }
+                "touchmove" => (
+                    TouchEventKind::Moved,
+                    recognizer.borrow_mut().moved(&touches),
+                ),
+                "touchend" => (
+                    TouchEventKind::Ended,
+                    recognizer.borrow_mut().end(time, (x, y), touches.len()),
+                ),
+                _ => {
+                    recognizer.borrow_mut().cancel();
+                    (TouchEventKind::Cancelled, None)
+                }
+            };
+            let mut callback = callback.borrow_mut();
+            callback(TouchEvent {
+                kind,
+                ..touch_event.clone()
+            });
+            if let Some(gesture) = gesture {
+                callback(TouchEvent {
+                    kind: TouchEventKind::Gesture(gesture),
+                    ..touch_event
+                });
+            }
+        });
+        add_event_listeners(
+            &document(),
+            &["touchstart", "touchmove", "touchend", "touchcancel"],
+            closure.into_js_value().unchecked_ref(),
+        );
+    }
+
+    /// Handles composition events of input method editors (IME).
+    ///
+    /// This method takes a closure that will be called on every
+    /// `compositionstart`, `compositionupdate` and `compositionend` event,
+    /// so text inputs can accept Chinese, Japanese or Korean input. It
+    /// creates a hidden input element to compose in, which takes the focus
+    /// when the grid is clicked. The text typed outside of compositions on
+    /// virtual keyboards that don't identify their keys, e.g. on Android, is
+    /// reported to [`on_key_event`](Self::on_key_event) as key presses.
+    fn on_composition_event<F>(&self, mut callback: F)
+    where
+        F: FnMut(CompositionEvent) + 'static,
+    {
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: web_sys::CompositionEvent| {
             callback(event.into());
         });
-        let window = window().unwrap();
-        let document = window.document().unwrap();
-        document
-            .add_event_listener_with_callback("mousemove", closure.as_ref().unchecked_ref())
-            .unwrap();
-        document
-            .add_event_listener_with_callback("mousedown", closure.as_ref().unchecked_ref())
-            .unwrap();
-        document
-            .add_event_listener_with_callback("mouseup", closure.as_ref().unchecked_ref())
-            .unwrap();
-        closure.forget();
+        let element = event::capture_element().expect("Unable to create composition input");
+        add_event_listeners(
+            &element,
+            &["compositionstart", "compositionupdate", "compositionend"],
+            closure.into_js_value().unchecked_ref(),
+        );
+    }
+
+    /// Handles terminal resizes.
+    ///
+    /// This method takes a closure that will be called with the new size
+    /// of the terminal, in cells, after the window is resized. The terminal
+    /// is resized before the frame that calls it, so the closure can
+    /// recompute layouts that don't depend on the frame area.
+    fn on_resize<F>(&self, callback: F)
+    where
+        F: FnMut(Size) + 'static,
+    {
+        resize::on_resize(callback);
+    }
+
+    /// Handles page visibility changes.
+    ///
+    /// This method takes a closure that will be called with `true` when the
+    /// page is shown and `false` when it is hidden, e.g. in a background
+    /// tab. The render loop pauses by itself while the page is hidden, this
+    /// lets apps pause their own timers too.
+    fn on_visibility_change<F>(&self, mut callback: F)
+    where
+        F: FnMut(bool) + 'static,
+    {
+        let closure = Closure::<dyn FnMut()>::new(move || {
+            callback(!render_loop::is_hidden());
+        });
+        add_event_listeners(
+            &document(),
+            &["visibilitychange"],
+            closure.into_js_value().unchecked_ref(),
+        );
+    }
+
+    /// Handles text pasted from the clipboard.
+    ///
+    /// This method takes a closure that will be called with the text of
+    /// every `paste` event. If Ctrl+V (or Cmd+V) doesn't lead to a `paste`
+    /// event, e.g. because another key handler prevented it, the text is
+    /// read with the Clipboard API instead, which asks for the clipboard
+    /// read permission.
+    ///
+    /// The closure is also called by the [paste action](crate::actions::PASTE).
+    fn on_paste_event<F>(&self, callback: F)
+    where
+        F: FnMut(String) + 'static,
+    {
+        let callback = Rc::new(RefCell::new(callback));
+        actions::on_paste(callback.clone());
+        // whether a paste shortcut is waiting for its `paste` event
+        let pending = Rc::new(Cell::new(false));
+        let on_paste = Closure::<dyn FnMut(_)>::new({
+            let callback = callback.clone();
+            let pending = pending.clone();
+            move |event: web_sys::ClipboardEvent| {
+                pending.set(false);
+                let text = event
+                    .clipboard_data()
+                    .and_then(|data| data.get_data("text/plain").ok())
+                    .unwrap_or_default();
+                if !text.is_empty() {
+                    callback.borrow_mut()(text);
+                }
+            }
+        });
+        let on_key = Closure::<dyn FnMut(_)>::new(move |event: web_sys::KeyboardEvent| {
+            let shortcut = event.ctrl_key() || event.meta_key();
+            if !shortcut || !event.key().eq_ignore_ascii_case("v") || event.alt_key() {
+                return;
+            }
+            pending.set(true);
+            let fallback = Closure::once_into_js({
+                let callback = callback.clone();
+                let pending = pending.clone();
+                move || {
+                    if !pending.replace(false) {
+                        return;
+                    }
+                    let Some(window) = window() else {
+                        return;
+                    };
+                    let promise = window.navigator().clipboard().read_text();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        // denied permission or empty clipboard
+                        let Ok(text) = JsFuture::from(promise).await else {
+                            return;
+                        };
+                        if let Some(text) = text.as_string().filter(|text| !text.is_empty()) {
+                            callback.borrow_mut()(text);
+                        }
+                    });
+                }
+            });
+            // the `paste` event follows the key event before any timeout
+            if let Some(window) = window() {
+                let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                    fallback.unchecked_ref(),
+                    0,
+                );
+            }
+        });
+        let document = document();
+        add_event_listeners(
+            &document,
+            &["paste"],
+            on_paste.into_js_value().unchecked_ref(),
+        );
+        add_event_listeners(
+            &document,
+            &["keydown"],
+            on_key.into_js_value().unchecked_ref(),
+        );
+    }
+
+    /// Handles images pasted from the clipboard.
+    ///
+    /// This method takes a closure that will be called for every image of a
+    /// `paste` event, e.g. a screenshot pasted with Ctrl+V. Pastes with
+    /// images don't reach the page otherwise.
+    fn on_image_paste_event<F>(&self, callback: F)
+    where
+        F: FnMut(ImagePasteEvent) + 'static,
+    {
+        let callback = Rc::new(RefCell::new(callback));
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: web_sys::ClipboardEvent| {
+            let Some(items) = event.clipboard_data().map(|data| data.items()) else {
+                return;
+            };
+            let images: Vec<_> = (0..items.length())
+                .filter_map(|index| items.get(index))
+                .filter(|item| item.kind() == "file" && item.type_().starts_with("image/"))
+                .filter_map(|item| item.get_as_file().ok().flatten())
+                .collect();
+            if images.is_empty() {
+                return;
+            }
+            event.prevent_default();
+            for image in images {
+                let callback = callback.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let Ok(buffer) = JsFuture::from(image.array_buffer()).await else {
+                        return;
+                    };
+                    let event = ImagePasteEvent {
+                        mime_type: image.type_(),
+                        bytes: Uint8Array::new(&buffer).to_vec(),
+                    };
+                    callback.borrow_mut()(event);
+                });
+            }
+        });
+        add_event_listeners(
+            &document(),
+            &["paste"],
+            closure.into_js_value().unchecked_ref(),
+        );
+    }
+
+    /// Returns a stream of the key, key release, mouse, wheel, resize and
+    /// paste events.
+    ///
+    /// This registers the same listeners as the `on_*` methods, see
+    /// [`event_stream`](crate::event_stream).
+    #[cfg(feature = "event-stream")]
+    fn event_stream(&self) -> EventStream {
+        let stream = EventStream::new();
+        let send = stream.sender();
+        self.on_key_event({
+            let send = send.clone();
+            move |event| send(Event::Key(event))
+        });
+        self.on_key_release({
+            let send = send.clone();
+            move |event| send(Event::KeyRelease(event))
+        });
+        self.on_mouse_event({
+            let send = send.clone();
+            move |event| send(Event::Mouse(event))
+        });
+        self.on_wheel_event({
+            let send = send.clone();
+            move |event| send(Event::Wheel(event))
+        });
+        self.on_resize({
+            let send = send.clone();
+            move |size| send(Event::Resize(size))
+        });
+        self.on_paste_event(move |text| send(Event::Paste(text)));
+        stream
     }
 
     /// Requests an animation frame.
     fn request_animation_frame(f: &Closure<dyn FnMut()>) {
-        window()
-            .unwrap()
-            .request_animation_frame(f.as_ref().unchecked_ref())
-            .unwrap();
+        get_window()
+            .and_then(|window| Ok(window.request_animation_frame(f.as_ref().unchecked_ref())?))
+            .expect("Unable to request animation frame");
+    }
+}
+
+/// Returns the document, without which the app can't run.
+fn document() -> web_sys::Document {
+    get_document().expect("Unable to retrieve document")
+}
+
+/// Adds a listener for the given events, removed on [`teardown`](crate::teardown).
+fn add_event_listeners(
+    target: &web_sys::EventTarget,
+    events: &[&'static str],
+    listener: &js_sys::Function,
+) {
+    for event in events {
+        teardown::add_event_listener(target, event, listener)
+            .expect("Unable to add event listener");
     }
 }
 
+/// Returns the positions of the given touches, in CSS pixels.
+fn touch_positions(touches: &web_sys::TouchList) -> Vec<(f64, f64)> {
+    (0..touches.length())
+        .filter_map(|index| touches.get(index))
+        .map(|touch| (touch.client_x() as f64, touch.client_y() as f64))
+        .collect()
+}
+
 /// Implement [`WebRenderer`] for Ratatui's [`Terminal`].
 ///
 /// This implementation creates a loop that calls the [`Terminal::draw`] method.
@@ -81,21 +537,90 @@ impl<T> WebRenderer for Terminal<T>
 where
     T: Backend + 'static,
 {
-    fn draw_web<F>(mut self, mut render_callback: F)
+    fn draw_web<F>(self, mut render_callback: F)
     where
         F: FnMut(&mut Frame) + 'static,
     {
-        let callback = Rc::new(RefCell::new(None));
-        *callback.borrow_mut() = Some(Closure::wrap(Box::new({
-            let cb = callback.clone();
+        analytics::report(AnalyticsEvent::AppStarted);
+        let terminal = Rc::new(RefCell::new(self));
+        // the frame cache reads the frame on screen when the page is left
+        #[cfg(any(
+            feature = "dom",
+            feature = "canvas",
+            feature = "webgl2",
+            feature = "webgpu"
+        ))]
+        frame_cache::set_source({
+            let terminal = Rc::downgrade(&terminal);
             move || {
-                self.draw(|frame| {
+                let terminal = terminal.upgrade()?;
+                let terminal = terminal.try_borrow().ok()?;
+                web::web_backend_buffer(terminal.backend())
+            }
+        });
+        let callback = Closure::<dyn FnMut()>::new(move || {
+            // while paused by frame stepping, only draw the requested steps
+            let step = stepping::should_draw();
+            let start = clock::now();
+            // otherwise skip frames until requested or to stay under the frame rate caps
+            let draw =
+                step.unwrap_or_else(|| render_loop::is_due(start) && adaptive::should_draw(start));
+            if !draw {
+                // in on-demand mode, the loop stops until a redraw is requested
+                if step.is_some() || render_loop::keeps_running() {
+                    render_loop::request_frame();
+                }
+                return;
+            }
+            render_loop::begin_frame();
+            // end of the render phase, and changed cells
+            let mut phases = None;
+            let mut terminal = terminal.borrow_mut();
+            let completed = terminal
+                .draw(|frame| {
                     render_callback(frame);
+                    selection::highlight(frame.buffer_mut());
+                    metadata::commit();
+                    links::commit();
+                    images::commit();
+                    if watchdog::is_enabled() {
+                        let render_end = clock::now();
+                        let changed_cells = watchdog::changed_cells(frame.buffer_mut());
+                        phases = Some((render_end, changed_cells));
+                    }
                 })
-                .unwrap();
-                Self::request_animation_frame(cb.borrow().as_ref().unwrap());
+                .expect("Unable to draw the terminal");
+            let end = clock::now();
+            resize::record_size(completed.area.as_size());
+            frame_cache::record_frame();
+            selection::record(completed.buffer);
+            analytics::record_frame(end - start);
+            adaptive::record_frame(end - start);
+            render_loop::record_frame(start, end);
+            if let Some((render_end, changed_cells)) = phases {
+                // the diff ends when the backend starts drawing the changed cells
+                let diff_end = watchdog::take_flush_start().unwrap_or(render_end);
+                let timings = FrameTimings {
+                    render_ms: render_end - start,
+                    diff_ms: diff_end - render_end,
+                    flush_ms: end - diff_end,
+                    changed_cells,
+                };
+                watchdog::record(timings, end);
             }
-        }) as Box<dyn FnMut()>));
-        Self::request_animation_frame(callback.borrow().as_ref().unwrap());
+            if step.is_some() || render_loop::keeps_running() {
+                render_loop::request_frame();
+            }
+        });
+        render_loop::start(
+            callback
+                .as_ref()
+                .unchecked_ref::<js_sys::Function>()
+                .clone(),
+        );
+        render_loop::request_frame();
+        // dropping the closure drops the terminal, after the current frame if
+        // destroyed while drawing
+        teardown::on_destroy(move || drop(callback));
     }
 }
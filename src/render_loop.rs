@@ -0,0 +1,353 @@
+//! ## Render loop
+//!
+//! By default, [`draw_web`] draws a frame on every animation frame of the
+//! browser, usually 60 times per second. Mostly static apps, like
+//! dashboards updated every few seconds, can save battery by capping the
+//! frame rate or by only drawing when something changed:
+//!
+//! ```rust no_run
+//! use ratzilla::{
+//!     ratatui::{widgets::Paragraph, Terminal},
+//!     render_loop::{self, RenderLoopOptions},
+//!     DomBackend, WebRenderer,
+//! };
+//!
+//! fn main() -> std::io::Result<()> {
+//!     let terminal = Terminal::new(DomBackend::new()?)?;
+//!     terminal.on_key_event(|_| {
+//!         // ... update the state, then draw it
+//!         render_loop::request_redraw();
+//!     });
+//!     let options = RenderLoopOptions::new().on_demand(true);
+//!     terminal.draw_web_with_options(options, |frame| {
+//!         frame.render_widget(Paragraph::new("Hello"), frame.area());
+//!     });
+//!     Ok(())
+//! }
+//! ```
+//!
+//! In on-demand mode, the first frame is always drawn and the loop stops
+//! requesting animation frames until [`request_redraw`] is called.
+//!
+//! In every mode, the loop is paused while the page is hidden, e.g. in a
+//! background tab, and a frame is drawn when it is shown again. Apps can
+//! pause their own timers with
+//! [`on_visibility_change`](crate::WebRenderer::on_visibility_change).
+//!
+//! [`draw_web`]: crate::WebRenderer::draw_web
+//! [`request_redraw`]: crate::render_loop::request_redraw
+
+use std::cell::RefCell;
+
+use web_sys::{js_sys::Function, wasm_bindgen::prelude::*, VisibilityState};
+
+use crate::{
+    backend::utils::{get_document, get_window},
+    teardown,
+};
+
+/// Options of the render loop.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderLoopOptions {
+    /// Frame rate cap, in frames per second.
+    target_fps: Option<f64>,
+    /// Whether frames are only drawn when requested.
+    on_demand: bool,
+    /// Longest time a frame should take, in milliseconds.
+    frame_budget_ms: Option<f64>,
+}
+
+impl RenderLoopOptions {
+    /// Constructs a new [`RenderLoopOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Caps the frame rate at the given frames per second, e.g. `30.0`.
+    ///
+    /// Defaults to the frame rate of the browser.
+    pub fn target_fps(mut self, fps: f64) -> Self {
+        self.target_fps = (fps > 0.0).then_some(fps);
+        self
+    }
+
+    /// Sets whether frames are only drawn when [`request_redraw`] is
+    /// called.
+    ///
+    /// Defaults to `false`.
+    pub fn on_demand(mut self, on_demand: bool) -> Self {
+        self.on_demand = on_demand;
+        self
+    }
+
+    /// Sets the longest time a frame should take, in milliseconds.
+    ///
+    /// A frame taking longer delays the next one by the time over budget,
+    /// so slow frames don't keep the main thread busy.
+    pub fn frame_budget(mut self, budget_ms: f64) -> Self {
+        self.frame_budget_ms = Some(budget_ms);
+        self
+    }
+}
+
+/// State of the render loop.
+#[derive(Debug)]
+struct RenderLoop {
+    /// Options.
+    options: RenderLoopOptions,
+    /// Whether a frame was requested in on-demand mode.
+    pending: bool,
+    /// Time of the last drawn frame.
+    last_draw: Option<f64>,
+    /// Time before which no frame is drawn, after a frame over budget.
+    resume_at: f64,
+    /// Callback of the animation frames, to restart the loop.
+    frame_callback: Option<Function>,
+    /// Id of the requested animation frame, to cancel it.
+    frame_id: Option<i32>,
+    /// Whether the loop requests animation frames.
+    running: bool,
+    /// Whether the page is hidden.
+    hidden: bool,
+    /// Whether the visibility listener is registered.
+    listening: bool,
+}
+
+impl Default for RenderLoop {
+    fn default() -> Self {
+        Self {
+            options: RenderLoopOptions::default(),
+            // the first frame is always drawn
+            pending: true,
+            last_draw: None,
+            resume_at: 0.0,
+            frame_callback: None,
+            frame_id: None,
+            running: false,
+            hidden: false,
+            listening: false,
+        }
+    }
+}
+
+impl RenderLoop {
+    /// Returns `true` if a frame is due at the given time.
+    fn is_due(&self, now_ms: f64) -> bool {
+        if self.options.on_demand && !self.pending {
+            return false;
+        }
+        if now_ms < self.resume_at {
+            return false;
+        }
+        match (self.options.target_fps, self.last_draw) {
+            // a small tolerance, since animation frames aren't exactly periodic
+            (Some(fps), Some(last)) => now_ms - last >= 1000.0 / fps - 1.0,
+            _ => true,
+        }
+    }
+
+    /// Consumes the requested frame, before drawing it.
+    ///
+    /// Redraws requested while drawing, e.g. by animations, are kept for the
+    /// next frame.
+    fn begin_frame(&mut self) {
+        self.pending = false;
+    }
+
+    /// Records a frame drawn from the given time to the given end.
+    fn record_frame(&mut self, start_ms: f64, end_ms: f64) {
+        self.last_draw = Some(start_ms);
+        if let Some(budget) = self.options.frame_budget_ms {
+            let overrun = end_ms - start_ms - budget;
+            self.resume_at = if overrun > 0.0 { end_ms + overrun } else { 0.0 };
+        }
+    }
+
+    /// Returns `true` if the loop should request the next animation frame.
+    fn keeps_running(&mut self) -> bool {
+        self.running = !self.hidden && (!self.options.on_demand || self.pending);
+        self.running
+    }
+}
+
+thread_local! {
+    static RENDER_LOOP: RefCell<RenderLoop> = RefCell::new(RenderLoop::default());
+}
+
+/// Sets the options of the render loop.
+///
+/// Prefer [`draw_web_with_options`](crate::WebRenderer::draw_web_with_options).
+pub fn set_options(options: RenderLoopOptions) {
+    RENDER_LOOP.with(|state| state.borrow_mut().options = options);
+    // the loop may be waiting for a request that isn't needed anymore
+    request_redraw();
+}
+
+/// Caps the frame rate of the render loop, keeping the other options.
+pub(crate) fn set_target_fps(fps: Option<f64>) {
+    RENDER_LOOP.with(|state| state.borrow_mut().options.target_fps = fps);
+}
+
+/// Requests a frame to be drawn.
+///
+/// This is only needed in [on-demand](RenderLoopOptions::on_demand) mode,
+/// after changing the state shown by the app.
+pub fn request_redraw() {
+    let restart = RENDER_LOOP.with(|state| {
+        let mut state = state.borrow_mut();
+        state.pending = true;
+        if state.running || state.hidden || state.frame_callback.is_none() {
+            return false;
+        }
+        state.running = true;
+        true
+    });
+    if restart {
+        request_frame();
+    }
+}
+
+/// Sets the callback of the animation frames, once the loop is started.
+pub(crate) fn start(callback: Function) {
+    let listening = RENDER_LOOP.with(|state| {
+        let mut state = state.borrow_mut();
+        state.frame_callback = Some(callback);
+        state.running = true;
+        state.hidden = is_hidden();
+        std::mem::replace(&mut state.listening, true)
+    });
+    if listening {
+        return;
+    }
+    // the visibility listener is removed on teardown
+    teardown::on_destroy(stop);
+    let on_visibility_change = Closure::<dyn FnMut()>::new(|| {
+        let hidden = is_hidden();
+        RENDER_LOOP.with(|state| state.borrow_mut().hidden = hidden);
+        if !hidden {
+            // the page may have been resized or restored from the cache
+            request_redraw();
+        }
+    });
+    if let Ok(document) = get_document() {
+        let _ = teardown::add_event_listener(
+            &document,
+            "visibilitychange",
+            on_visibility_change.into_js_value().unchecked_ref(),
+        );
+    }
+}
+
+/// Requests the next animation frame of the loop.
+pub(crate) fn request_frame() {
+    let Some(callback) = RENDER_LOOP.with(|state| state.borrow().frame_callback.clone()) else {
+        return;
+    };
+    let Ok(window) = get_window() else {
+        return;
+    };
+    let frame_id = window.request_animation_frame(&callback).ok();
+    RENDER_LOOP.with(|state| state.borrow_mut().frame_id = frame_id);
+}
+
+/// Cancels the requested animation frame and resets the loop.
+fn stop() {
+    let state = RENDER_LOOP.with(|state| state.take());
+    if let (Some(frame_id), Ok(window)) = (state.frame_id, get_window()) {
+        let _ = window.cancel_animation_frame(frame_id);
+    }
+}
+
+/// Returns `true` if the page is hidden.
+pub(crate) fn is_hidden() -> bool {
+    get_document()
+        .map(|document| document.visibility_state() == VisibilityState::Hidden)
+        .unwrap_or(false)
+}
+
+/// Returns `true` if a frame is due at the given time.
+pub(crate) fn is_due(now_ms: f64) -> bool {
+    RENDER_LOOP.with(|state| state.borrow().is_due(now_ms))
+}
+
+/// Consumes the requested frame, before drawing it.
+pub(crate) fn begin_frame() {
+    RENDER_LOOP.with(|state| state.borrow_mut().begin_frame());
+}
+
+/// Records a frame drawn from the given time to the given end.
+pub(crate) fn record_frame(start_ms: f64, end_ms: f64) {
+    RENDER_LOOP.with(|state| state.borrow_mut().record_frame(start_ms, end_ms));
+}
+
+/// Returns `true` if the loop should request the next animation frame, or
+/// stops it until the next [`request_redraw`].
+pub(crate) fn keeps_running() -> bool {
+    RENDER_LOOP.with(|state| state.borrow_mut().keeps_running())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_loop(options: RenderLoopOptions) -> RenderLoop {
+        RenderLoop {
+            options,
+            ..RenderLoop::default()
+        }
+    }
+
+    #[test]
+    fn test_target_fps() {
+        let mut state = render_loop(RenderLoopOptions::new().target_fps(30.0));
+        assert!(state.is_due(0.0));
+        state.record_frame(0.0, 5.0);
+        assert!(!state.is_due(16.7));
+        assert!(state.is_due(33.3));
+    }
+
+    #[test]
+    fn test_on_demand() {
+        let mut state = render_loop(RenderLoopOptions::new().on_demand(true));
+        assert!(state.is_due(0.0));
+        state.begin_frame();
+        state.record_frame(0.0, 5.0);
+        assert!(!state.is_due(16.7));
+        assert!(!state.keeps_running());
+        state.pending = true;
+        assert!(state.keeps_running());
+        assert!(state.is_due(33.3));
+    }
+
+    #[test]
+    fn test_redraw_requested_while_drawing() {
+        let mut state = render_loop(RenderLoopOptions::new().on_demand(true));
+        assert!(state.is_due(0.0));
+        state.begin_frame();
+        // e.g. an animation requesting its next frame
+        state.pending = true;
+        state.record_frame(0.0, 5.0);
+        assert!(state.keeps_running());
+        assert!(state.is_due(16.7));
+    }
+
+    #[test]
+    fn test_hidden() {
+        let mut state = render_loop(RenderLoopOptions::new());
+        state.hidden = true;
+        assert!(!state.keeps_running());
+        state.hidden = false;
+        assert!(state.keeps_running());
+    }
+
+    #[test]
+    fn test_frame_budget() {
+        let mut state = render_loop(RenderLoopOptions::new().frame_budget(10.0));
+        state.record_frame(0.0, 30.0);
+        // 20ms over budget
+        assert!(!state.is_due(40.0));
+        assert!(state.is_due(50.0));
+        state.record_frame(50.0, 55.0);
+        assert!(state.is_due(60.0));
+    }
+}
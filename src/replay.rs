@@ -0,0 +1,580 @@
+//! ## Replay
+//!
+//! Records the input events of a session with their timestamps, and re-runs
+//! them against a fresh instance of the app with a [`ManualClock`], so every
+//! frame sees the same time as in the recorded session. This makes bug
+//! reports reproducible and turns recorded sessions into UI regression
+//! tests.
+//!
+//! While recording, every key and mouse event handled by
+//! [`on_key_event`] and [`on_mouse_event`] is recorded:
+//!
+//! ```rust no_run
+//! use ratzilla::replay;
+//!
+//! replay::start_recording();
+//! // ... later, e.g. from a "report a bug" button
+//! if let Some(recording) = replay::stop_recording() {
+//!     let text = recording.to_string();
+//! }
+//! ```
+//!
+//! A [`Replay`] then feeds the events to the app as the clock advances, one
+//! frame at a time, e.g. in a test with a [`TestBackend`]:
+//!
+//! ```rust
+//! use ratzilla::{
+//!     ratatui::{backend::TestBackend, widgets::Paragraph, Terminal},
+//!     replay::{InputEvent, Recording, Replay},
+//! };
+//!
+//! let recording: Recording = "ratzilla-replay 1\n20 key a\n40 key ctrl+b\n"
+//!     .parse()
+//!     .unwrap();
+//! let mut terminal = Terminal::new(TestBackend::new(10, 1)).unwrap();
+//! let mut keys = 0;
+//! Replay::new(recording)
+//!     .run(&mut terminal, 1000.0 / 60.0, |event| {
+//!         if let InputEvent::Key(_) = event {
+//!             keys += 1;
+//!         }
+//!     }, |frame| {
+//!         frame.render_widget(Paragraph::new("hello"), frame.area());
+//!     })
+//!     .unwrap();
+//! assert_eq!(keys, 2);
+//! ```
+//!
+//! The app must start from the same state as the recorded session, e.g. by
+//! being constructed the same way with the same random seeds.
+//!
+//! [`on_key_event`]: crate::WebRenderer::on_key_event
+//! [`on_mouse_event`]: crate::WebRenderer::on_mouse_event
+//! [`TestBackend`]: ratatui::backend::TestBackend
+//! [`ManualClock`]: crate::clock::ManualClock
+//! [`Replay`]: crate::replay::Replay
+
+use std::{cell::RefCell, fmt, str::FromStr};
+
+use ratatui::{layout::Position, prelude::Backend, Frame, Terminal};
+
+use crate::{
+    clock::{self, ManualClock},
+    error::Error,
+    event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind, WheelEvent},
+    keymap::KeyBinding,
+};
+
+pub use crate::event::InputEvent;
+
+/// First line of the text format of recordings.
+const HEADER: &str = "ratzilla-replay 1";
+
+/// Input events and the time they happened at, in milliseconds since the
+/// start of the recording.
+///
+/// The text format has a header line followed by one event per line:
+///
+/// ```text
+/// ratzilla-replay 1
+/// 120.5 key ctrl+s
+/// 301 mouse pressed left 12 4 shift
+/// 302 mouse clicked left 12 4 cell 0 0
+/// 450 wheel 3 0 cell 0 0
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    /// Recorded events, in chronological order.
+    events: Vec<(f64, InputEvent)>,
+}
+
+impl Recording {
+    /// Constructs a new empty [`Recording`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an event at the given time, in milliseconds.
+    ///
+    /// Events added out of order are sorted by time.
+    pub fn push(&mut self, time: f64, event: InputEvent) {
+        let index = self.events.partition_point(|(t, _)| *t <= time);
+        self.events.insert(index, (time, event));
+    }
+
+    /// Returns the recorded events and their times.
+    pub fn events(&self) -> &[(f64, InputEvent)] {
+        &self.events
+    }
+
+    /// Returns the time of the last event, in milliseconds.
+    pub fn duration(&self) -> f64 {
+        self.events.last().map_or(0.0, |(time, _)| *time)
+    }
+}
+
+impl fmt::Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        for (time, event) in &self.events {
+            write!(f, "{time} ")?;
+            match event {
+                InputEvent::Key(key) => {
+                    f.write_str("key ")?;
+                    write_modifiers(f, key.ctrl, key.alt, key.shift, "+")?;
+                    if key.meta {
+                        f.write_str("meta+")?;
+                    }
+                    writeln!(f, "{}", key_name(&key.code))?;
+                }
+                InputEvent::Mouse(mouse) => {
+                    let kind = match mouse.event {
+                        MouseEventKind::Moved => "moved",
+                        MouseEventKind::Dragged => "dragged",
+                        MouseEventKind::Pressed => "pressed",
+                        MouseEventKind::Released => "released",
+                        MouseEventKind::Clicked => "clicked",
+                        MouseEventKind::DoubleClicked => "double-clicked",
+                        MouseEventKind::Unidentified => "unidentified",
+                    };
+                    let button = match mouse.button {
+                        MouseButton::Left => "left",
+                        MouseButton::Right => "right",
+                        MouseButton::Middle => "middle",
+                        MouseButton::Back => "back",
+                        MouseButton::Forward => "forward",
+                        MouseButton::Unidentified => "unidentified",
+                    };
+                    write!(f, "mouse {kind} {button} {} {}", mouse.x, mouse.y)?;
+                    write_cell(f, mouse.cell)?;
+                    write_modifiers(f, mouse.ctrl, mouse.alt, mouse.shift, "")?;
+                    writeln!(f)?;
+                }
+                InputEvent::Wheel(wheel) => {
+                    write!(f, "wheel {} {}", wheel.delta_rows, wheel.delta_cols)?;
+                    write_cell(f, wheel.position)?;
+                    write_modifiers(f, wheel.ctrl, wheel.alt, wheel.shift, "")?;
+                    writeln!(f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recording {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().filter(|line| !line.trim().is_empty());
+        if lines.next().map(str::trim) != Some(HEADER) {
+            return Err(Error::InvalidRecording("missing header".to_string()));
+        }
+        let mut recording = Recording::new();
+        for line in lines {
+            let invalid = || Error::InvalidRecording(line.to_string());
+            let mut fields = line.split_whitespace();
+            let time = fields
+                .next()
+                .and_then(|time| time.parse::<f64>().ok())
+                .filter(|time| time.is_finite())
+                .ok_or_else(invalid)?;
+            let event = match fields.next() {
+                Some("key") => {
+                    let key = fields.next().ok_or_else(invalid)?;
+                    InputEvent::Key(parse_key(key).ok_or_else(invalid)?)
+                }
+                Some("mouse") => {
+                    let event = match fields.next() {
+                        Some("moved") => MouseEventKind::Moved,
+                        Some("dragged") => MouseEventKind::Dragged,
+                        Some("pressed") => MouseEventKind::Pressed,
+                        Some("released") => MouseEventKind::Released,
+                        Some("clicked") => MouseEventKind::Clicked,
+                        Some("double-clicked") => MouseEventKind::DoubleClicked,
+                        Some("unidentified") => MouseEventKind::Unidentified,
+                        _ => return Err(invalid()),
+                    };
+                    let button = match fields.next() {
+                        Some("left") => MouseButton::Left,
+                        Some("right") => MouseButton::Right,
+                        Some("middle") => MouseButton::Middle,
+                        Some("back") => MouseButton::Back,
+                        Some("forward") => MouseButton::Forward,
+                        Some("unidentified") => MouseButton::Unidentified,
+                        _ => return Err(invalid()),
+                    };
+                    let mut coordinate = || fields.next()?.parse().ok();
+                    let (Some(x), Some(y)) = (coordinate(), coordinate()) else {
+                        return Err(invalid());
+                    };
+                    let (cell, ctrl, alt, shift) =
+                        parse_cell_and_modifiers(&mut fields).ok_or_else(invalid)?;
+                    InputEvent::Mouse(MouseEvent {
+                        button,
+                        event,
+                        x,
+                        y,
+                        cell,
+                        ctrl,
+                        alt,
+                        shift,
+                    })
+                }
+                Some("wheel") => {
+                    let mut delta = || fields.next()?.parse().ok();
+                    let (Some(delta_rows), Some(delta_cols)) = (delta(), delta()) else {
+                        return Err(invalid());
+                    };
+                    let (position, ctrl, alt, shift) =
+                        parse_cell_and_modifiers(&mut fields).ok_or_else(invalid)?;
+                    InputEvent::Wheel(WheelEvent {
+                        delta_rows,
+                        delta_cols,
+                        position,
+                        ctrl,
+                        alt,
+                        shift,
+                    })
+                }
+                _ => return Err(invalid()),
+            };
+            if fields.next().is_some() {
+                return Err(invalid());
+            }
+            recording.push(time, event);
+        }
+        Ok(recording)
+    }
+}
+
+/// Writes the cell of a mouse or wheel event, if any.
+fn write_cell(f: &mut fmt::Formatter<'_>, cell: Option<Position>) -> fmt::Result {
+    match cell {
+        Some(cell) => write!(f, " cell {} {}", cell.x, cell.y),
+        None => Ok(()),
+    }
+}
+
+/// Parses the optional cell and the modifiers ending a mouse or wheel event.
+fn parse_cell_and_modifiers<'a>(
+    fields: &mut impl Iterator<Item = &'a str>,
+) -> Option<(Option<Position>, bool, bool, bool)> {
+    let (mut cell, mut ctrl, mut alt, mut shift) = (None, false, false, false);
+    while let Some(field) = fields.next() {
+        match field {
+            "cell" if cell.is_none() => {
+                let x = fields.next()?.parse().ok()?;
+                let y = fields.next()?.parse().ok()?;
+                cell = Some(Position::new(x, y));
+            }
+            "ctrl" => ctrl = true,
+            "alt" => alt = true,
+            "shift" => shift = true,
+            _ => return None,
+        }
+    }
+    Some((cell, ctrl, alt, shift))
+}
+
+/// Writes the enabled modifiers, each followed by the separator.
+///
+/// Modifiers are preceded by a space when the separator is empty.
+fn write_modifiers(
+    f: &mut fmt::Formatter<'_>,
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    separator: &str,
+) -> fmt::Result {
+    for (enabled, name) in [(ctrl, "ctrl"), (alt, "alt"), (shift, "shift")] {
+        if enabled {
+            if separator.is_empty() {
+                f.write_str(" ")?;
+            }
+            write!(f, "{name}{separator}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the name of a key code, as parsed by [`KeyBinding`].
+fn key_name(code: &KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("f{n}"),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Left => "left".to_string(),
+        KeyCode::Right => "right".to_string(),
+        KeyCode::Up => "up".to_string(),
+        KeyCode::Down => "down".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::Delete => "delete".to_string(),
+        KeyCode::Home => "home".to_string(),
+        KeyCode::End => "end".to_string(),
+        KeyCode::PageUp => "pageup".to_string(),
+        KeyCode::PageDown => "pagedown".to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::Unidentified => "unidentified".to_string(),
+    }
+}
+
+/// Parses a key event written by [`key_name`], with its modifiers.
+fn parse_key(s: &str) -> Option<KeyEvent> {
+    let binding = match s.strip_suffix("unidentified") {
+        Some(modifiers) => KeyBinding {
+            code: KeyCode::Unidentified,
+            ..format!("{modifiers}a").parse().ok()?
+        },
+        None => s.parse::<KeyBinding>().ok()?,
+    };
+    Some(KeyEvent {
+        code: binding.code,
+        ctrl: binding.ctrl,
+        alt: binding.alt,
+        shift: binding.shift,
+        meta: binding.meta,
+        repeat: false,
+    })
+}
+
+/// State of an ongoing recording.
+#[derive(Debug)]
+struct Recorder {
+    /// Time the recording started at, in milliseconds.
+    start: f64,
+    /// Events recorded so far.
+    recording: Recording,
+}
+
+thread_local! {
+    static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+/// Starts recording the input events, discarding any ongoing recording.
+pub fn start_recording() {
+    let recorder = Recorder {
+        start: clock::now(),
+        recording: Recording::new(),
+    };
+    RECORDER.with(|r| *r.borrow_mut() = Some(recorder));
+}
+
+/// Stops recording and returns the recorded events, if recording.
+pub fn stop_recording() -> Option<Recording> {
+    RECORDER.with(|r| r.borrow_mut().take().map(|r| r.recording))
+}
+
+/// Returns `true` if the input events are being recorded.
+pub fn is_recording() -> bool {
+    RECORDER.with(|r| r.borrow().is_some())
+}
+
+/// Records an input event, if recording.
+pub(crate) fn record(event: InputEvent) {
+    if !is_recording() {
+        return;
+    }
+    let now = clock::now();
+    RECORDER.with(|r| {
+        if let Some(recorder) = r.borrow_mut().as_mut() {
+            let time = now - recorder.start;
+            recorder.recording.push(time, event);
+        }
+    });
+}
+
+/// Re-runs a [`Recording`] with a [`ManualClock`].
+///
+/// Constructing a replay installs its clock, and dropping it restores the
+/// [`SystemClock`](crate::clock::SystemClock).
+#[derive(Debug)]
+pub struct Replay {
+    /// The replayed recording.
+    recording: Recording,
+    /// The clock driven by the replay, starting at 0.
+    clock: ManualClock,
+    /// Index of the next event to replay.
+    next: usize,
+}
+
+impl Replay {
+    /// Constructs a new [`Replay`] of the given recording and installs its
+    /// clock.
+    pub fn new(recording: Recording) -> Self {
+        let clock = ManualClock::new();
+        clock::set_clock(clock.clone());
+        Self {
+            recording,
+            clock,
+            next: 0,
+        }
+    }
+
+    /// Returns the clock driven by the replay.
+    pub fn clock(&self) -> &ManualClock {
+        &self.clock
+    }
+
+    /// Returns the current time of the replay, in milliseconds.
+    pub fn time(&self) -> f64 {
+        clock::Clock::now(&self.clock)
+    }
+
+    /// Returns `true` if all the events were replayed.
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.events.len()
+    }
+
+    /// Advances the clock by the given number of milliseconds and returns the
+    /// events that happened meanwhile.
+    pub fn advance(&mut self, ms: f64) -> Vec<InputEvent> {
+        self.clock.advance(ms);
+        let time = self.time();
+        let due = self.recording.events[self.next..]
+            .iter()
+            .take_while(|(t, _)| *t <= time)
+            .map(|(_, event)| event.clone())
+            .collect::<Vec<_>>();
+        self.next += due.len();
+        due
+    }
+
+    /// Replays the whole recording, one frame of the given duration at a time.
+    ///
+    /// Before each frame, the events that happened during the frame are
+    /// passed to `on_event`. Then the frame is drawn with `render_callback`,
+    /// like [`draw_web`] does.
+    ///
+    /// [`draw_web`]: crate::WebRenderer::draw_web
+    pub fn run<B, E, R>(
+        mut self,
+        terminal: &mut Terminal<B>,
+        frame_ms: f64,
+        mut on_event: E,
+        mut render_callback: R,
+    ) -> Result<(), B::Error>
+    where
+        B: Backend,
+        E: FnMut(InputEvent),
+        R: FnMut(&mut Frame),
+    {
+        terminal.draw(&mut render_callback)?;
+        while !self.is_finished() {
+            self.advance(frame_ms.max(f64::EPSILON))
+                .into_iter()
+                .for_each(&mut on_event);
+            terminal.draw(&mut render_callback)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Replay {
+    fn drop(&mut self) {
+        clock::reset_clock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+
+    fn key(code: KeyCode, ctrl: bool) -> InputEvent {
+        InputEvent::Key(KeyEvent {
+            code,
+            ctrl,
+            alt: false,
+            shift: false,
+            meta: false,
+            repeat: false,
+        })
+    }
+
+    #[test]
+    fn test_text_format() {
+        let mut recording = Recording::new();
+        recording.push(10.5, key(KeyCode::Char('+'), true));
+        recording.push(20.0, key(KeyCode::Char(' '), false));
+        recording.push(30.0, key(KeyCode::Unidentified, true));
+        recording.push(
+            5.0,
+            InputEvent::Mouse(MouseEvent {
+                button: MouseButton::Left,
+                event: MouseEventKind::Pressed,
+                x: 3,
+                y: 4,
+                cell: Some(Position::new(0, 1)),
+                ctrl: false,
+                alt: true,
+                shift: true,
+            }),
+        );
+        recording.push(
+            40.0,
+            InputEvent::Wheel(WheelEvent {
+                delta_rows: -3,
+                delta_cols: 0,
+                position: None,
+                ctrl: true,
+                alt: false,
+                shift: false,
+            }),
+        );
+        let text = recording.to_string();
+        assert_eq!(
+            text,
+            "ratzilla-replay 1\n\
+             5 mouse pressed left 3 4 cell 0 1 alt shift\n\
+             10.5 key ctrl++\n\
+             20 key space\n\
+             30 key ctrl+unidentified\n\
+             40 wheel -3 0 ctrl\n"
+        );
+        assert_eq!(
+            text.parse::<Recording>().expect("valid recording"),
+            recording
+        );
+        assert!("10 key a".parse::<Recording>().is_err());
+        assert!("ratzilla-replay 1\n10 key a b"
+            .parse::<Recording>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut recording = Recording::new();
+        recording.push(15.0, key(KeyCode::Enter, false));
+        recording.push(40.0, key(KeyCode::Esc, false));
+
+        let mut replay = Replay::new(recording.clone());
+        assert_eq!(replay.advance(10.0), vec![]);
+        assert_eq!(replay.advance(10.0), vec![key(KeyCode::Enter, false)]);
+        assert_eq!(clock::now(), 20.0);
+        drop(replay);
+
+        let mut terminal =
+            Terminal::new(TestBackend::new(4, 1)).expect("failed to create the terminal");
+        let (mut events, mut frames) = (Vec::new(), Vec::new());
+        Replay::new(recording)
+            .run(
+                &mut terminal,
+                10.0,
+                |event| events.push((clock::now(), event)),
+                |_| frames.push(clock::now()),
+            )
+            .expect("failed to replay the recording");
+        assert_eq!(
+            events,
+            vec![
+                (20.0, key(KeyCode::Enter, false)),
+                (40.0, key(KeyCode::Esc, false)),
+            ]
+        );
+        assert_eq!(frames, vec![0.0, 10.0, 20.0, 30.0, 40.0]);
+    }
+}
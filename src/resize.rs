@@ -0,0 +1,351 @@
+use std::cell::RefCell;
+
+use ratatui::layout::Size;
+
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+use web_sys::{js_sys::Function, wasm_bindgen::prelude::*, Element, ResizeObserver};
+
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+use crate::{backend::utils::get_window, error::Error, render_loop, teardown};
+
+/// Time without `resize` events before the backends are resized, in
+/// milliseconds.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+const RESIZE_DEBOUNCE_MS: i32 = 100;
+
+/// State of the resize handling.
+#[derive(Default)]
+struct Resize {
+    /// Backend listeners, called once the window stopped resizing.
+    #[cfg(any(
+        feature = "dom",
+        feature = "canvas",
+        feature = "webgl2",
+        feature = "webgpu"
+    ))]
+    listeners: Vec<Box<dyn FnMut()>>,
+    /// App callbacks, called when the size of the terminal changed.
+    callbacks: Vec<Box<dyn FnMut(Size)>>,
+    /// Size of the last drawn frame.
+    size: Option<Size>,
+    /// Debounced step, called by the timer.
+    #[cfg(any(
+        feature = "dom",
+        feature = "canvas",
+        feature = "webgl2",
+        feature = "webgpu"
+    ))]
+    tick: Option<Function>,
+    /// Id of the running debounce timer.
+    #[cfg(any(
+        feature = "dom",
+        feature = "canvas",
+        feature = "webgl2",
+        feature = "webgpu"
+    ))]
+    timer: Option<i32>,
+    /// Observers of the [observed](observe_element) elements.
+    #[cfg(any(
+        feature = "dom",
+        feature = "canvas",
+        feature = "webgl2",
+        feature = "webgpu"
+    ))]
+    observers: Vec<ResizeObserver>,
+    /// Whether the `resize` listener of the window is registered.
+    #[cfg(any(
+        feature = "dom",
+        feature = "canvas",
+        feature = "webgl2",
+        feature = "webgpu"
+    ))]
+    listening: bool,
+}
+
+thread_local! {
+    static RESIZE: RefCell<Resize> = RefCell::new(Resize::default());
+}
+
+/// Calls the given listener once the window, or an
+/// [observed](observe_element) element, stopped resizing.
+///
+/// A frame is requested after the listeners are called, so backends can
+/// pick up their new size on the next draw.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+pub(crate) fn on_window_resize<F>(listener: F) -> Result<(), Error>
+where
+    F: FnMut() + 'static,
+{
+    RESIZE.with(|state| state.borrow_mut().listeners.push(Box::new(listener)));
+    listen()
+}
+
+/// Tracks the size of the given element, e.g. the container of an embedded
+/// terminal, calling the listeners once it stopped resizing.
+///
+/// Unlike the window, the element may be resized by the layout of the page,
+/// e.g. a resizable panel or a flexbox. Browsers without `ResizeObserver`
+/// only follow the size of the window.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+pub(crate) fn observe_element(element: &Element) -> Result<(), Error> {
+    listen()?;
+    let on_resize = Closure::<dyn FnMut()>::new(schedule);
+    let Ok(observer) = ResizeObserver::new(on_resize.into_js_value().unchecked_ref()) else {
+        return Ok(());
+    };
+    observer.observe(element);
+    RESIZE.with(|state| state.borrow_mut().observers.push(observer));
+    Ok(())
+}
+
+/// Registers the `resize` listener of the window, once.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+fn listen() -> Result<(), Error> {
+    let listening = RESIZE.with(|state| std::mem::replace(&mut state.borrow_mut().listening, true));
+    if listening {
+        return Ok(());
+    }
+    let tick = Closure::<dyn FnMut()>::new(|| {
+        let mut listeners = RESIZE.with(|state| {
+            let mut state = state.borrow_mut();
+            state.timer = None;
+            std::mem::take(&mut state.listeners)
+        });
+        // outside of the borrow, listeners may register other listeners
+        for listener in &mut listeners {
+            listener();
+        }
+        RESIZE.with(|state| {
+            let mut state = state.borrow_mut();
+            listeners.append(&mut state.listeners);
+            state.listeners = listeners;
+        });
+        render_loop::request_redraw();
+    });
+    RESIZE.with(|state| state.borrow_mut().tick = Some(tick.into_js_value().unchecked_into()));
+    let on_resize = Closure::<dyn FnMut()>::new(schedule);
+    teardown::add_event_listener(
+        &get_window()?.into(),
+        "resize",
+        on_resize.into_js_value().unchecked_ref(),
+    )?;
+    teardown::on_destroy(reset);
+    Ok(())
+}
+
+/// Stops the timer and the observers, and forgets the listeners and
+/// callbacks of the destroyed terminal.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+fn reset() {
+    let state = RESIZE.with(|state| state.take());
+    if let (Some(timer), Ok(window)) = (state.timer, get_window()) {
+        window.clear_timeout_with_handle(timer);
+    }
+    for observer in state.observers {
+        observer.disconnect();
+    }
+}
+
+/// Restarts the debounce timer of the listeners.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+fn schedule() {
+    let Ok(window) = get_window() else {
+        return;
+    };
+    RESIZE.with(|state| {
+        let mut state = state.borrow_mut();
+        if let Some(timer) = state.timer.take() {
+            window.clear_timeout_with_handle(timer);
+        }
+        state.timer = state.tick.as_ref().and_then(|tick| {
+            window
+                .set_timeout_with_callback_and_timeout_and_arguments_0(tick, RESIZE_DEBOUNCE_MS)
+                .ok()
+        });
+    });
+}
+
+/// Calls the given callback with the new size of the terminal whenever it
+/// changes.
+pub(crate) fn on_resize<F>(callback: F)
+where
+    F: FnMut(Size) + 'static,
+{
+    RESIZE.with(|state| state.borrow_mut().callbacks.push(Box::new(callback)));
+}
+
+/// Records the size of a drawn frame, calling the callbacks if it changed.
+pub(crate) fn record_size(size: Size) {
+    let callbacks = RESIZE.with(|state| {
+        let mut state = state.borrow_mut();
+        let previous = state.size.replace(size);
+        // the first frame isn't a resize
+        match previous {
+            Some(previous) if previous != size => Some(std::mem::take(&mut state.callbacks)),
+            _ => None,
+        }
+    });
+    let Some(mut callbacks) = callbacks else {
+        return;
+    };
+    for callback in &mut callbacks {
+        callback(size);
+    }
+    RESIZE.with(|state| {
+        let mut state = state.borrow_mut();
+        callbacks.append(&mut state.callbacks);
+        state.callbacks = callbacks;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn test_record_size() {
+        let sizes = Rc::new(RefCell::new(Vec::new()));
+        on_resize({
+            let sizes = sizes.clone();
+            move |size| sizes.borrow_mut().push(size)
+        });
+
+        record_size(Size::new(80, 24));
+        record_size(Size::new(80, 24));
+        record_size(Size::new(100, 30));
+        record_size(Size::new(100, 30));
+        record_size(Size::new(80, 24));
+        assert_eq!(*sizes.borrow(), vec![Size::new(100, 30), Size::new(80, 24)]);
+    }
+
+    #[cfg(any(
+        feature = "dom",
+        feature = "canvas",
+        feature = "webgl2",
+        feature = "webgpu"
+    ))]
+    mod web {
+        use std::cell::Cell;
+
+        use wasm_bindgen_test::*;
+        use web_sys::{
+            js_sys::{Promise, Reflect},
+            wasm_bindgen::JsValue,
+            Event,
+        };
+
+        use super::*;
+        use crate::backend::utils::get_document;
+
+        wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+        /// Waits for the given duration, in milliseconds.
+        async fn sleep(ms: i32) {
+            let window = get_window().expect("failed to get the window");
+            let promise = Promise::new(&mut |resolve, _| {
+                let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms);
+            });
+            wasm_bindgen_futures::JsFuture::from(promise)
+                .await
+                .expect("failed to wait");
+        }
+
+        /// Dispatches a `resize` event on the window.
+        fn resize_window() {
+            let event = Event::new("resize").expect("failed to create the event");
+            get_window()
+                .expect("failed to get the window")
+                .dispatch_event(&event)
+                .expect("failed to dispatch");
+        }
+
+        /// Returns a counter of the calls of a resize listener.
+        fn count_calls() -> Rc<Cell<u32>> {
+            let calls = Rc::new(Cell::new(0));
+            on_window_resize({
+                let calls = calls.clone();
+                move || calls.set(calls.get() + 1)
+            })
+            .expect("failed to listen");
+            calls
+        }
+
+        #[wasm_bindgen_test]
+        async fn test_debounce() {
+            let calls = count_calls();
+            for _ in 0..3 {
+                resize_window();
+                sleep(RESIZE_DEBOUNCE_MS / 4).await;
+            }
+            assert_eq!(calls.get(), 0);
+
+            sleep(RESIZE_DEBOUNCE_MS * 2).await;
+            assert_eq!(calls.get(), 1);
+            teardown::destroy();
+        }
+
+        #[wasm_bindgen_test]
+        async fn test_observer_fallback() {
+            let window = get_window().expect("failed to get the window");
+            let observer = Reflect::get(&window, &"ResizeObserver".into()).expect("failed to get");
+            Reflect::set(&window, &"ResizeObserver".into(), &JsValue::UNDEFINED)
+                .expect("failed to set");
+            let element = get_document()
+                .expect("failed to get the document")
+                .create_element("div")
+                .expect("failed to create the element");
+            let observed = observe_element(&element);
+            Reflect::set(&window, &"ResizeObserver".into(), &observer).expect("failed to set");
+
+            assert!(observed.is_ok());
+            assert!(RESIZE.with(|state| state.borrow().observers.is_empty()));
+            let calls = count_calls();
+            resize_window();
+            sleep(RESIZE_DEBOUNCE_MS * 2).await;
+            assert_eq!(calls.get(), 1);
+            teardown::destroy();
+        }
+    }
+}
@@ -0,0 +1,457 @@
+//! ## Routing
+//!
+//! A small router based on the URL fragment (`#/path`), which works with any
+//! static file server and doesn't require server-side rewrites.
+//!
+//! ```rust no_run
+//! use ratzilla::router::Router;
+//!
+//! let router = Router::new().unwrap();
+//! router.on_route_change(|route| {
+//!     web_sys::console::log_1(&format!("navigated to {route}").into());
+//! });
+//! router.navigate("/settings");
+//! ```
+//!
+//! Navigation guards can block route changes, e.g. while a form has unsaved
+//! changes:
+//!
+//! ```rust no_run
+//! use ratzilla::router::Router;
+//!
+//! let router = Router::new().unwrap();
+//! router.add_guard(|from, _to| from != "/checkout/payment");
+//! router.set_unsaved_changes(Some("Discard your changes?"));
+//! ```
+//!
+//! Like the scroll position of regular web pages, the [`ViewState`] of
+//! lists and tables can be restored when navigating back to a route:
+//!
+//! ```rust no_run
+//! use ratzilla::{ratatui::widgets::ListState, router::Router};
+//! use std::{cell::RefCell, rc::Rc};
+//!
+//! let router = Router::new().unwrap();
+//! let list = Rc::new(RefCell::new(ListState::default()));
+//! router.on_route_change({
+//!     let router = router.clone();
+//!     let list = list.clone();
+//!     move |_| {
+//!         let view = router.view_state("files").unwrap_or_default();
+//!         view.restore_list(&mut list.borrow_mut());
+//!     }
+//! });
+//! // after rendering the list:
+//! router.save_view_state("files", (&*list.borrow()).into());
+//! ```
+//!
+//! [`ViewState`]: crate::router::ViewState
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use ratatui::widgets::{ListState, TableState};
+use web_sys::wasm_bindgen::{prelude::Closure, JsCast};
+
+use crate::{
+    analytics::{self, AnalyticsEvent},
+    backend::utils::get_window,
+    error::Error,
+    ssr::ROUTE_ATTRIBUTE,
+    teardown,
+    utils::storage::session_storage,
+};
+
+/// Prefix of the `sessionStorage` keys of the view states.
+const VIEW_STATE_PREFIX: &str = "ratzilla.view.";
+
+/// Callback called with the new route.
+type RouteCallback = Box<dyn FnMut(&str)>;
+
+/// Callback deciding whether navigating from a route to another is allowed.
+type GuardCallback = Box<dyn FnMut(&str, &str) -> bool>;
+
+/// The scroll offset and the selection of a viewport, e.g. a list.
+///
+/// Saved per route with [`Router::save_view_state`], in memory and in the
+/// `sessionStorage`, so it survives reloads of the tab like the scroll
+/// position of regular web pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ViewState {
+    /// Index of the first visible item.
+    pub offset: usize,
+    /// Index of the selected (focused) item, if any.
+    pub selected: Option<usize>,
+}
+
+impl ViewState {
+    /// Restores the state of a list.
+    pub fn restore_list(&self, state: &mut ListState) {
+        *state.offset_mut() = self.offset;
+        state.select(self.selected);
+    }
+
+    /// Restores the state of a table.
+    pub fn restore_table(&self, state: &mut TableState) {
+        *state.offset_mut() = self.offset;
+        state.select(self.selected);
+    }
+
+    /// Returns the stored form of the state, e.g. `3,5`.
+    fn to_stored(self) -> String {
+        match self.selected {
+            Some(selected) => format!("{},{selected}", self.offset),
+            None => self.offset.to_string(),
+        }
+    }
+
+    /// Parses the stored form of a state.
+    fn from_stored(value: &str) -> Option<Self> {
+        let (offset, selected) = match value.split_once(',') {
+            Some((offset, selected)) => (offset, Some(selected.parse().ok()?)),
+            None => (value, None),
+        };
+        Some(Self {
+            offset: offset.parse().ok()?,
+            selected,
+        })
+    }
+}
+
+impl From<&ListState> for ViewState {
+    fn from(state: &ListState) -> Self {
+        Self {
+            offset: state.offset(),
+            selected: state.selected(),
+        }
+    }
+}
+
+impl From<&TableState> for ViewState {
+    fn from(state: &TableState) -> Self {
+        Self {
+            offset: state.offset(),
+            selected: state.selected(),
+        }
+    }
+}
+
+/// Identifier of a navigation guard, used to remove it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GuardId(u64);
+
+/// Shared router state.
+struct RouterState {
+    /// The current route.
+    route: String,
+    /// Route change listeners.
+    listeners: Vec<RouteCallback>,
+    /// Navigation guards.
+    guards: Vec<(GuardId, GuardCallback)>,
+    /// Identifier of the next guard.
+    next_guard_id: u64,
+    /// Message shown before leaving while there are unsaved changes.
+    unsaved_changes: Option<String>,
+    /// View states, by route and viewport.
+    views: HashMap<(String, String), ViewState>,
+}
+
+impl RouterState {
+    /// Calls the route change listeners.
+    fn notify(state: &Rc<RefCell<Self>>, route: &str) {
+        // listeners may navigate, so don't hold the borrow while calling them
+        let mut listeners = std::mem::take(&mut state.borrow_mut().listeners);
+        for listener in listeners.iter_mut() {
+            listener(route);
+        }
+        let mut state = state.borrow_mut();
+        listeners.append(&mut state.listeners);
+        state.listeners = listeners;
+    }
+
+    /// Returns `true` if the guards allow navigating to the given route.
+    fn allows(state: &Rc<RefCell<Self>>, to: &str) -> bool {
+        let from = state.borrow().route.clone();
+        // guards may add guards, so don't hold the borrow while calling them
+        let mut guards = std::mem::take(&mut state.borrow_mut().guards);
+        let allowed = guards.iter_mut().all(|(_, guard)| guard(&from, to));
+        {
+            let mut state = state.borrow_mut();
+            guards.append(&mut state.guards);
+            state.guards = guards;
+        }
+        if !allowed {
+            return false;
+        }
+
+        let message = state.borrow().unsaved_changes.clone();
+        match (message, get_window()) {
+            (Some(message), Ok(window)) => window.confirm_with_message(&message).unwrap_or(true),
+            _ => true,
+        }
+    }
+}
+
+/// A router based on the URL fragment.
+///
+/// Routes are the fragment of the URL without the leading `#`, e.g. the route
+/// of `https://example.com/#/about` is `/about`. An empty fragment is the `/`
+/// route.
+///
+/// The router is a cheap handle: clones share the same state.
+#[derive(Clone)]
+pub struct Router {
+    state: Rc<RefCell<RouterState>>,
+}
+
+impl std::fmt::Debug for Router {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Router")
+            .field("route", &self.state.borrow().route)
+            .finish()
+    }
+}
+
+impl Router {
+    /// Constructs a new [`Router`] and starts listening to `hashchange` events.
+    ///
+    /// The listeners are removed by [`teardown::destroy`].
+    pub fn new() -> Result<Self, Error> {
+        let window = get_window()?;
+        let hash = window.location().hash()?;
+        // pages generated by `StaticSite` carry their route when there is no fragment
+        let route = match window
+            .document()
+            .and_then(|d| d.document_element())
+            .and_then(|e| e.get_attribute(ROUTE_ATTRIBUTE))
+        {
+            Some(route) if hash.trim_start_matches('#').is_empty() => route,
+            _ => route_from_hash(&hash),
+        };
+        let state = Rc::new(RefCell::new(RouterState {
+            route,
+            listeners: Vec::new(),
+            guards: Vec::new(),
+            next_guard_id: 0,
+            unsaved_changes: None,
+            views: HashMap::new(),
+        }));
+
+        let closure = Closure::<dyn FnMut(_)>::new({
+            let state = state.clone();
+            move |_: web_sys::Event| {
+                let Some(hash) = web_sys::window().and_then(|w| w.location().hash().ok()) else {
+                    return;
+                };
+                let route = route_from_hash(&hash);
+                if state.borrow().route == route {
+                    return;
+                }
+                if !RouterState::allows(&state, &route) {
+                    // the URL already changed, so restore it without adding a history entry
+                    let previous = format!("#{}", state.borrow().route);
+                    if let Some(history) = web_sys::window().and_then(|w| w.history().ok()) {
+                        let _ = history.replace_state_with_url(
+                            &web_sys::wasm_bindgen::JsValue::NULL,
+                            "",
+                            Some(&previous),
+                        );
+                    }
+                    return;
+                }
+                state.borrow_mut().route = route.clone();
+                analytics::report(AnalyticsEvent::RouteChanged {
+                    route: route.clone(),
+                });
+                RouterState::notify(&state, &route);
+            }
+        });
+        teardown::add_event_listener(
+            &window,
+            "hashchange",
+            closure.into_js_value().unchecked_ref(),
+        )?;
+
+        let closure = Closure::<dyn FnMut(_)>::new({
+            let state = state.clone();
+            move |event: web_sys::BeforeUnloadEvent| {
+                if let Some(message) = &state.borrow().unsaved_changes {
+                    event.prevent_default();
+                    // browsers show their own message, but some still require one to be set
+                    event.set_return_value(message);
+                }
+            }
+        });
+        teardown::add_event_listener(
+            &window,
+            "beforeunload",
+            closure.into_js_value().unchecked_ref(),
+        )?;
+
+        Ok(Self { state })
+    }
+
+    /// Returns the current route.
+    pub fn route(&self) -> String {
+        self.state.borrow().route.clone()
+    }
+
+    /// Navigates to the given route.
+    ///
+    /// This adds a new entry to the browser history, so the back button
+    /// returns to the previous route.
+    ///
+    /// Does nothing if a navigation guard rejects the route.
+    pub fn navigate(&self, route: &str) {
+        let route = route_from_hash(route);
+        if self.state.borrow().route == route || !RouterState::allows(&self.state, &route) {
+            return;
+        }
+        // the `hashchange` handler must not ask the guards again
+        self.state.borrow_mut().route = route.clone();
+        analytics::report(AnalyticsEvent::RouteChanged {
+            route: route.clone(),
+        });
+        RouterState::notify(&self.state, &route);
+        if let Ok(window) = get_window() {
+            let _ = window.location().set_hash(&route);
+        }
+    }
+
+    /// Goes back to the previous entry of the browser history.
+    pub fn back(&self) {
+        if let Ok(history) = get_window().and_then(|w| w.history().map_err(Error::from)) {
+            let _ = history.back();
+        }
+    }
+
+    /// Registers a callback that is called whenever the route changes.
+    pub fn on_route_change<F>(&self, callback: F)
+    where
+        F: FnMut(&str) + 'static,
+    {
+        self.state.borrow_mut().listeners.push(Box::new(callback));
+    }
+
+    /// Adds a navigation guard.
+    ///
+    /// The guard is called with the current and the requested route before
+    /// every route change, including the ones triggered by the back and
+    /// forward buttons. Returning `false` cancels the navigation.
+    pub fn add_guard<F>(&self, guard: F) -> GuardId
+    where
+        F: FnMut(&str, &str) -> bool + 'static,
+    {
+        let mut state = self.state.borrow_mut();
+        let id = GuardId(state.next_guard_id);
+        state.next_guard_id += 1;
+        state.guards.push((id, Box::new(guard)));
+        id
+    }
+
+    /// Removes a navigation guard.
+    pub fn remove_guard(&self, id: GuardId) {
+        self.state.borrow_mut().guards.retain(|(i, _)| *i != id);
+    }
+
+    /// Marks the app as having unsaved changes, or clears the mark with `None`.
+    ///
+    /// While marked, the user is asked to confirm with the given message
+    /// before the route changes, and the browser asks for confirmation
+    /// before the page is closed or reloaded. Browsers show their own message
+    /// in the latter case.
+    pub fn set_unsaved_changes(&self, message: Option<&str>) {
+        self.state.borrow_mut().unsaved_changes = message.map(str::to_string);
+    }
+
+    /// Returns `true` if the app is marked as having unsaved changes.
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.state.borrow().unsaved_changes.is_some()
+    }
+
+    /// Saves the state of the given viewport for the current route.
+    ///
+    /// This is cheap to call after every frame: the `sessionStorage` is only
+    /// written when the state changed.
+    pub fn save_view_state(&self, viewport: &str, view: ViewState) {
+        let mut state = self.state.borrow_mut();
+        let key = (state.route.clone(), viewport.to_string());
+        if state.views.get(&key) == Some(&view) {
+            return;
+        }
+        if let Ok(storage) = session_storage() {
+            let _ = storage.set_item(&view_state_key(&key.0, &key.1), &view.to_stored());
+        }
+        state.views.insert(key, view);
+    }
+
+    /// Returns the saved state of the given viewport for the current route,
+    /// e.g. to restore it after navigating back.
+    pub fn view_state(&self, viewport: &str) -> Option<ViewState> {
+        let mut state = self.state.borrow_mut();
+        let key = (state.route.clone(), viewport.to_string());
+        if let Some(view) = state.views.get(&key) {
+            return Some(*view);
+        }
+        // saved before the tab was reloaded
+        let view = session_storage()
+            .ok()?
+            .get_item(&view_state_key(&key.0, &key.1))
+            .ok()
+            .flatten()
+            .and_then(|value| ViewState::from_stored(&value))?;
+        state.views.insert(key, view);
+        Some(view)
+    }
+}
+
+/// Returns the `sessionStorage` key of the state of a viewport.
+fn view_state_key(route: &str, viewport: &str) -> String {
+    format!("{VIEW_STATE_PREFIX}{route}#{viewport}")
+}
+
+/// Converts a URL fragment into a route.
+fn route_from_hash(hash: &str) -> String {
+    let route = hash.trim_start_matches('#');
+    if route.is_empty() {
+        "/".to_string()
+    } else if route.starts_with('/') {
+        route.to_string()
+    } else {
+        format!("/{route}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_from_hash() {
+        assert_eq!(route_from_hash(""), "/");
+        assert_eq!(route_from_hash("#"), "/");
+        assert_eq!(route_from_hash("#/about"), "/about");
+        assert_eq!(route_from_hash("#about"), "/about");
+    }
+
+    #[test]
+    fn test_view_state() {
+        let view = ViewState {
+            offset: 3,
+            selected: Some(5),
+        };
+        assert_eq!(view.to_stored(), "3,5");
+        assert_eq!(ViewState::from_stored("3,5"), Some(view));
+        assert_eq!(
+            ViewState::from_stored("7"),
+            Some(ViewState {
+                offset: 7,
+                selected: None
+            })
+        );
+        assert_eq!(ViewState::from_stored("x"), None);
+
+        let mut list = ListState::default();
+        view.restore_list(&mut list);
+        assert_eq!(ViewState::from(&list), view);
+    }
+}
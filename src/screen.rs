@@ -0,0 +1,412 @@
+//! ## Screens
+//!
+//! [`ScreenManager`] owns a set of screens, renders the active one and plays a
+//! [`Transition`] when switching between them. Screens can be bound to
+//! [`Router`] routes, in which case route changes switch screens (and animate)
+//! automatically.
+//!
+//! ```rust no_run
+//! use ratzilla::{
+//!     ratatui::{buffer::Buffer, layout::Rect, widgets::{Paragraph, Widget}},
+//!     router::Router,
+//!     screen::{ScreenManager, Transition},
+//! };
+//!
+//! let mut screens = ScreenManager::new()
+//!     .transition(Transition::Slide(300))
+//!     .with_router(Router::new().unwrap());
+//!
+//! screens.register("home", |area: Rect, buf: &mut Buffer| {
+//!     Paragraph::new("Home").render(area, buf);
+//! });
+//! screens.register("about", |area: Rect, buf: &mut Buffer| {
+//!     Paragraph::new("About").render(area, buf);
+//! });
+//! screens.bind_route("/", "home");
+//! screens.bind_route("/about", "about");
+//!
+//! // In the draw callback:
+//! // screens.render(frame.area(), frame.buffer_mut());
+//! ```
+//!
+//! The screens can also be shown as tabs with a [`TabBar`], applying its
+//! actions with [`ScreenManager::apply_tab_action`].
+//!
+//! [`TabBar`]: crate::widgets::TabBar
+//! [`Router`]: crate::router::Router
+//! [`ScreenManager`]: crate::screen::ScreenManager
+//! [`ScreenManager::apply_tab_action`]: crate::screen::ScreenManager::apply_tab_action
+//! [`Transition`]: crate::screen::Transition
+
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::Rect,
+    style::Color,
+};
+
+use crate::{backend::color::to_rgb, clock::now, router::Router, widgets::TabAction};
+
+/// A screen that can be managed by the [`ScreenManager`].
+pub trait Screen {
+    /// Renders the screen into the given area of the buffer.
+    fn render(&mut self, area: Rect, buf: &mut Buffer);
+}
+
+impl<F> Screen for F
+where
+    F: FnMut(Rect, &mut Buffer),
+{
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self(area, buf)
+    }
+}
+
+/// Animation played when switching screens.
+///
+/// The value of each variant is the duration of the transition, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Transition {
+    /// Switch immediately.
+    #[default]
+    None,
+    /// Cross-fade the colors of the old screen into the new one.
+    Fade(u32),
+    /// Slide the new screen in from the right.
+    Slide(u32),
+    /// Replace the cells of the old screen in random order.
+    Dissolve(u32),
+}
+
+impl Transition {
+    /// Returns the duration of the transition, in milliseconds.
+    pub fn duration(&self) -> u32 {
+        match self {
+            Transition::None => 0,
+            Transition::Fade(ms) | Transition::Slide(ms) | Transition::Dissolve(ms) => *ms,
+        }
+    }
+}
+
+/// A transition that is currently playing.
+struct ActiveTransition<K> {
+    /// The screen being replaced.
+    from: K,
+    /// The transition.
+    transition: Transition,
+    /// Start time, in milliseconds.
+    start: f64,
+}
+
+/// Manages screens and the transitions between them.
+pub struct ScreenManager<K> {
+    /// Registered screens.
+    screens: Vec<(K, Box<dyn Screen>)>,
+    /// The active screen.
+    current: Option<K>,
+    /// Default transition.
+    transition: Transition,
+    /// The transition currently playing.
+    active: Option<ActiveTransition<K>>,
+    /// Router driving the screens.
+    router: Option<Router>,
+    /// Routes bound to screens.
+    routes: Vec<(String, K)>,
+    /// The last route seen by the manager.
+    last_route: Option<String>,
+}
+
+impl<K: Clone + PartialEq> Default for ScreenManager<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + PartialEq> ScreenManager<K> {
+    /// Constructs a new, empty [`ScreenManager`].
+    pub fn new() -> Self {
+        Self {
+            screens: Vec::new(),
+            current: None,
+            transition: Transition::default(),
+            active: None,
+            router: None,
+            routes: Vec::new(),
+            last_route: None,
+        }
+    }
+
+    /// Sets the transition used by [`ScreenManager::switch_to`] and route changes.
+    pub fn transition(mut self, transition: Transition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// Drives the screens with the given router.
+    ///
+    /// Use [`ScreenManager::bind_route`] to map routes to screens.
+    pub fn with_router(mut self, router: Router) -> Self {
+        self.router = Some(router);
+        self
+    }
+
+    /// Registers a screen.
+    ///
+    /// The first registered screen becomes the active screen.
+    pub fn register<S>(&mut self, key: K, screen: S)
+    where
+        S: Screen + 'static,
+    {
+        if self.current.is_none() {
+            self.current = Some(key.clone());
+        }
+        self.screens.retain(|(k, _)| *k != key);
+        self.screens.push((key, Box::new(screen)));
+    }
+
+    /// Removes a screen.
+    ///
+    /// If it was the active screen, the previous screen (or the next one, if
+    /// it was the first) becomes active.
+    pub fn unregister(&mut self, key: &K) {
+        let Some(index) = self.screens.iter().position(|(k, _)| k == key) else {
+            return;
+        };
+        self.screens.remove(index);
+        if self.current.as_ref() == Some(key) {
+            let next = index.min(self.screens.len()).saturating_sub(1);
+            self.current = self.screens.get(next).map(|(k, _)| k.clone());
+            self.active = None;
+        }
+    }
+
+    /// Returns the keys of the screens, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.screens.iter().map(|(k, _)| k)
+    }
+
+    /// Returns the index of the active screen.
+    pub fn current_index(&self) -> Option<usize> {
+        let current = self.current.as_ref()?;
+        self.screens.iter().position(|(k, _)| k == current)
+    }
+
+    /// Moves the screen at index `from` to index `to`.
+    pub fn reorder(&mut self, from: usize, to: usize) {
+        if from < self.screens.len() && to < self.screens.len() {
+            let screen = self.screens.remove(from);
+            self.screens.insert(to, screen);
+        }
+    }
+
+    /// Applies an action of a [`TabBar`] showing the screens in order.
+    ///
+    /// [`TabBar`]: crate::widgets::TabBar
+    pub fn apply_tab_action(&mut self, action: TabAction) {
+        match action {
+            TabAction::Select(index) => {
+                if let Some((key, _)) = self.screens.get(index) {
+                    self.switch_to(key.clone());
+                }
+            }
+            TabAction::Close(index) => {
+                if let Some(key) = self.screens.get(index).map(|(k, _)| k.clone()) {
+                    self.unregister(&key);
+                }
+            }
+            TabAction::Move { from, to } => self.reorder(from, to),
+        }
+    }
+
+    /// Binds a route to a screen.
+    pub fn bind_route(&mut self, route: &str, key: K) {
+        self.routes.push((route.to_string(), key));
+    }
+
+    /// Returns the active screen.
+    pub fn current(&self) -> Option<&K> {
+        self.current.as_ref()
+    }
+
+    /// Returns `true` while a transition is playing.
+    pub fn is_transitioning(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// Switches to the given screen using the default transition.
+    pub fn switch_to(&mut self, key: K) {
+        self.switch_to_with(key, self.transition);
+    }
+
+    /// Switches to the given screen using the given transition.
+    pub fn switch_to_with(&mut self, key: K, transition: Transition) {
+        if self.current.as_ref() == Some(&key) {
+            return;
+        }
+        let previous = self.current.replace(key);
+        self.active = match (previous, transition) {
+            (_, Transition::None) | (None, _) => None,
+            (Some(from), transition) => Some(ActiveTransition {
+                from,
+                transition,
+                start: now(),
+            }),
+        };
+    }
+
+    /// Renders the active screen, playing the current transition if any.
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        self.sync_route();
+
+        let Some(current) = self.current.clone() else {
+            return;
+        };
+
+        let progress = self.active.as_ref().map(|active| {
+            let duration = active.transition.duration().max(1) as f64;
+            ((now() - active.start) / duration).clamp(0.0, 1.0) as f32
+        });
+
+        match (progress, self.active.take()) {
+            (Some(progress), Some(active)) if progress < 1.0 => {
+                let mut old = Buffer::empty(area);
+                let mut new = Buffer::empty(area);
+                self.render_screen(&active.from, area, &mut old);
+                self.render_screen(&current, area, &mut new);
+                composite(active.transition, progress, &old, &new, area, buf);
+                self.active = Some(active);
+            }
+            _ => self.render_screen(&current, area, buf),
+        }
+    }
+
+    /// Renders the screen with the given key.
+    fn render_screen(&mut self, key: &K, area: Rect, buf: &mut Buffer) {
+        if let Some((_, screen)) = self.screens.iter_mut().find(|(k, _)| k == key) {
+            screen.render(area, buf);
+        }
+    }
+
+    /// Switches screens if the route changed since the last frame.
+    fn sync_route(&mut self) {
+        let Some(route) = self.router.as_ref().map(Router::route) else {
+            return;
+        };
+        if self.last_route.as_ref() == Some(&route) {
+            return;
+        }
+
+        let key = self
+            .routes
+            .iter()
+            .find(|(r, _)| *r == route)
+            .map(|(_, key)| key.clone());
+        if let Some(key) = key {
+            // don't animate the initial route
+            if self.last_route.is_some() {
+                self.switch_to(key);
+            } else {
+                self.current = Some(key);
+            }
+        }
+        self.last_route = Some(route);
+    }
+}
+
+/// Blends the old and new screens into the output buffer.
+fn composite(
+    transition: Transition,
+    progress: f32,
+    old: &Buffer,
+    new: &Buffer,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            let cell = match transition {
+                Transition::None => new[(x, y)].clone(),
+                Transition::Fade(_) => fade_cell(&old[(x, y)], &new[(x, y)], progress),
+                Transition::Slide(_) => {
+                    let offset = (area.width as f32 * (1.0 - progress)) as u16;
+                    let rel_x = x - area.x;
+                    if rel_x < offset {
+                        old[(x + area.width - offset, y)].clone()
+                    } else {
+                        new[(x - offset, y)].clone()
+                    }
+                }
+                Transition::Dissolve(_) => {
+                    if dissolve_threshold(x, y) < progress {
+                        new[(x, y)].clone()
+                    } else {
+                        old[(x, y)].clone()
+                    }
+                }
+            };
+            if let Some(target) = buf.cell_mut((x, y)) {
+                *target = cell;
+            }
+        }
+    }
+}
+
+/// Interpolates the colors of two cells, switching symbols halfway through.
+fn fade_cell(old: &Cell, new: &Cell, progress: f32) -> Cell {
+    let mut cell = if progress < 0.5 {
+        old.clone()
+    } else {
+        new.clone()
+    };
+    cell.fg = lerp_color(old.fg, new.fg, Color::White, progress);
+    cell.bg = lerp_color(old.bg, new.bg, Color::Black, progress);
+    cell
+}
+
+/// Linearly interpolates between two colors.
+fn lerp_color(from: Color, to: Color, reset: Color, progress: f32) -> Color {
+    let reset = to_rgb(reset, 0);
+    let (from, to) = (to_rgb(from, reset), to_rgb(to, reset));
+    let channel = |shift: u32| {
+        let a = ((from >> shift) & 0xff) as f32;
+        let b = ((to >> shift) & 0xff) as f32;
+        (a + (b - a) * progress) as u8
+    };
+    Color::Rgb(channel(16), channel(8), channel(0))
+}
+
+/// Returns a pseudo random, but stable, value in `0.0..1.0` for a cell.
+fn dissolve_threshold(x: u16, y: u16) -> f32 {
+    let mut h = (x as u32).wrapping_mul(0x9E37_79B9) ^ (y as u32).wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2C1B_3C6D);
+    h ^= h >> 12;
+    (h & 0xffff) as f32 / 65536.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lerp_color() {
+        let from = Color::Rgb(0, 0, 0);
+        let to = Color::Rgb(200, 100, 50);
+        assert_eq!(lerp_color(from, to, Color::Black, 0.0), from);
+        assert_eq!(
+            lerp_color(from, to, Color::Black, 0.5),
+            Color::Rgb(100, 50, 25)
+        );
+        assert_eq!(lerp_color(from, to, Color::Black, 1.0), to);
+    }
+
+    #[test]
+    fn test_slide_composite() {
+        let area = Rect::new(0, 0, 4, 1);
+        let old = Buffer::with_lines(["abcd"]);
+        let new = Buffer::with_lines(["wxyz"]);
+        let mut buf = Buffer::empty(area);
+
+        composite(Transition::Slide(100), 0.5, &old, &new, area, &mut buf);
+        assert_eq!(buf, Buffer::with_lines(["cdwx"]));
+    }
+}
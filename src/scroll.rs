@@ -0,0 +1,413 @@
+//! ## Kinetic scrolling
+//!
+//! [`KineticScroll`] turns touch drags into a scroll offset that follows
+//! the finger, keeps moving after it is lifted and slows down, like the
+//! native scrolling of mobile browsers. With rubber-banding, dragging or
+//! flinging past an edge stretches beyond it and springs back.
+//!
+//! ```rust no_run
+//! use ratzilla::{scroll::KineticScroll, WebRenderer};
+//! # fn example(terminal: ratzilla::ratatui::Terminal<ratzilla::DomBackend>) {
+//! use std::{cell::RefCell, rc::Rc};
+//!
+//! let scroll = Rc::new(RefCell::new(KineticScroll::new().rubber_band(true)));
+//! terminal.on_touch_event({
+//!     let scroll = scroll.clone();
+//!     move |event| {
+//!         scroll.borrow_mut().handle_touch_event(&event);
+//!     }
+//! });
+//! terminal.draw_web(move |frame| {
+//!     let mut scroll = scroll.borrow_mut();
+//!     scroll.set_max_offset(100);
+//!     let first_row = scroll.offset();
+//!     // render the rows from `first_row`, shifted by `scroll.overscroll()`
+//! });
+//! # }
+//! ```
+//!
+//! The motion is driven by the [clock](crate::clock), and reading the
+//! offset while it moves requests a redraw, so it also works with the
+//! [on-demand](crate::render_loop::RenderLoopOptions::on_demand) render
+//! loop.
+//!
+//! [`KineticScroll`]: crate::scroll::KineticScroll
+
+use crate::{
+    backend::{metrics::CellMetrics, web},
+    clock,
+    event::{TouchEvent, TouchEventKind},
+    render_loop,
+};
+
+/// Default fraction of the velocity kept after each millisecond, the
+/// deceleration of native scroll views.
+const DEFAULT_DECELERATION_RATE: f64 = 0.998;
+
+/// Velocity below which the scroll stops, in rows per millisecond.
+const MIN_VELOCITY: f64 = 0.001;
+
+/// Highest velocity of a fling, in rows per millisecond.
+const MAX_VELOCITY: f64 = 0.5;
+
+/// Time without moving after which lifting the finger doesn't fling, in
+/// milliseconds.
+const FLING_TIMEOUT_MS: f64 = 100.0;
+
+/// How much a drag past an edge moves the content.
+const RUBBER_BAND_RESISTANCE: f64 = 0.5;
+
+/// Angular frequency of the spring pulling back past an edge, per
+/// millisecond.
+const SPRING_FREQUENCY: f64 = 0.02;
+
+/// Longest step of the simulation, in milliseconds.
+const MAX_STEP_MS: f64 = 4.0;
+
+/// A finger dragging the content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Drag {
+    /// Last position of the finger, in rows.
+    position: f64,
+    /// Time of the last move, in milliseconds.
+    time: f64,
+}
+
+/// A scroll offset moved by touch drags, with momentum.
+///
+/// Offsets are in rows, from `0` to the [maximum
+/// offset](Self::set_max_offset).
+#[derive(Debug, Clone, PartialEq)]
+pub struct KineticScroll {
+    /// Current offset, past the edges while rubber-banding.
+    offset: f64,
+    /// Largest offset.
+    max_offset: f64,
+    /// Velocity, in rows per millisecond.
+    velocity: f64,
+    /// The finger dragging the content, if any.
+    drag: Option<Drag>,
+    /// Time the motion was last simulated to, in milliseconds.
+    updated: f64,
+    /// Whether the content can be pulled past the edges.
+    rubber_band: bool,
+    /// Fraction of the velocity kept after each millisecond.
+    deceleration_rate: f64,
+}
+
+impl Default for KineticScroll {
+    fn default() -> Self {
+        Self {
+            offset: 0.0,
+            max_offset: 0.0,
+            velocity: 0.0,
+            drag: None,
+            updated: 0.0,
+            rubber_band: false,
+            deceleration_rate: DEFAULT_DECELERATION_RATE,
+        }
+    }
+}
+
+impl KineticScroll {
+    /// Constructs a new [`KineticScroll`] at the top.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the content can be pulled past the edges, springing
+    /// back when released.
+    pub fn rubber_band(mut self, enabled: bool) -> Self {
+        self.rubber_band = enabled;
+        self
+    }
+
+    /// Sets the fraction of the velocity kept after each millisecond, from
+    /// `0.0` (no momentum) to below `1.0`. Defaults to `0.998`.
+    pub fn deceleration_rate(mut self, rate: f64) -> Self {
+        self.deceleration_rate = rate.clamp(0.0, 0.9999);
+        self
+    }
+
+    /// Sets the largest offset, e.g. the number of rows of the content
+    /// minus the number of visible rows.
+    pub fn set_max_offset(&mut self, max_offset: usize) {
+        self.max_offset = max_offset as f64;
+        if !self.rubber_band {
+            self.offset = self.offset.clamp(0.0, self.max_offset);
+        }
+    }
+
+    /// Scrolls to the given offset, stopping any motion.
+    pub fn scroll_to(&mut self, offset: usize) {
+        self.offset = (offset as f64).min(self.max_offset);
+        self.velocity = 0.0;
+        self.drag = None;
+    }
+
+    /// Stops any motion, moving back inside the edges.
+    pub fn stop(&mut self) {
+        self.update(clock::now());
+        self.velocity = 0.0;
+        self.drag = None;
+        self.offset = self.offset.clamp(0.0, self.max_offset);
+    }
+
+    /// Returns the first visible row.
+    pub fn offset(&mut self) -> usize {
+        self.position().round().clamp(0.0, self.max_offset) as usize
+    }
+
+    /// Returns by how many rows the content is pulled past an edge, negative
+    /// above the top, while rubber-banding.
+    pub fn overscroll(&mut self) -> i32 {
+        let position = self.position();
+        (position - position.clamp(0.0, self.max_offset)).round() as i32
+    }
+
+    /// Returns `true` while the content is dragged or moving.
+    pub fn is_moving(&self) -> bool {
+        self.drag.is_some() || self.velocity != 0.0 || self.is_past_edge()
+    }
+
+    /// Handles a touch event, returning `true` if it scrolled.
+    ///
+    /// A single finger drags the content. Lifting it flings the content
+    /// with the velocity of the last moves. A second finger, e.g. a pinch,
+    /// stops dragging.
+    pub fn handle_touch_event(&mut self, event: &TouchEvent) -> bool {
+        // before a backend measured its grid
+        let row_height = web::grid().map_or_else(
+            || CellMetrics::default().cell_size().1,
+            |grid| grid.cell_size.1,
+        );
+        let position = event.y as f64 / row_height;
+        let time = clock::now();
+        match event.kind {
+            TouchEventKind::Started if event.touches == 1 => self.press(time, position),
+            TouchEventKind::Started => self.cancel(time),
+            TouchEventKind::Moved if event.touches == 1 => self.drag_to(time, position),
+            TouchEventKind::Ended | TouchEventKind::Cancelled if event.touches == 0 => {
+                self.release(time)
+            }
+            _ => false,
+        }
+    }
+
+    /// Starts dragging the content at the given position, in rows.
+    fn press(&mut self, time: f64, position: f64) -> bool {
+        self.update(time);
+        self.velocity = 0.0;
+        self.drag = Some(Drag { position, time });
+        false
+    }
+
+    /// Drags the content to the given position, in rows.
+    fn drag_to(&mut self, time: f64, position: f64) -> bool {
+        let Some(drag) = self.drag else {
+            return false;
+        };
+        // moving the finger up moves the content up, i.e. scrolls down
+        let delta = drag.position - position;
+        let resistance = if self.is_past_edge() {
+            RUBBER_BAND_RESISTANCE
+        } else {
+            1.0
+        };
+        self.offset += delta * resistance;
+        if !self.rubber_band {
+            self.offset = self.offset.clamp(0.0, self.max_offset);
+        }
+        let elapsed = time - drag.time;
+        if elapsed > 0.0 {
+            // smooth the velocity over the last moves
+            self.velocity = 0.8 * (delta / elapsed) + 0.2 * self.velocity;
+        }
+        self.drag = Some(Drag { position, time });
+        self.updated = time;
+        render_loop::request_redraw();
+        true
+    }
+
+    /// Releases the content, flinging it with the velocity of the drag.
+    fn release(&mut self, time: f64) -> bool {
+        let Some(drag) = self.drag.take() else {
+            return false;
+        };
+        if time - drag.time > FLING_TIMEOUT_MS {
+            self.velocity = 0.0;
+        }
+        self.velocity = self.velocity.clamp(-MAX_VELOCITY, MAX_VELOCITY);
+        self.updated = time;
+        render_loop::request_redraw();
+        true
+    }
+
+    /// Stops dragging without flinging.
+    fn cancel(&mut self, time: f64) -> bool {
+        self.velocity = 0.0;
+        self.drag = None;
+        self.updated = time;
+        false
+    }
+
+    /// Returns the current position, requesting a redraw while moving.
+    fn position(&mut self) -> f64 {
+        self.update(clock::now());
+        if self.is_moving() {
+            render_loop::request_redraw();
+        }
+        self.offset
+    }
+
+    /// Returns `true` if the content is pulled past an edge.
+    fn is_past_edge(&self) -> bool {
+        self.offset < 0.0 || self.offset > self.max_offset
+    }
+
+    /// Simulates the motion until the given time.
+    fn update(&mut self, now: f64) {
+        // the motion stops by itself, so this ends even after a long time
+        let mut elapsed = (now - self.updated).max(0.0);
+        self.updated = now;
+        if self.drag.is_some() {
+            return;
+        }
+        while elapsed > 0.0 && (self.velocity != 0.0 || self.is_past_edge()) {
+            let step = elapsed.min(MAX_STEP_MS);
+            elapsed -= step;
+            self.step(step);
+        }
+    }
+
+    /// Advances the motion by a step, in milliseconds.
+    fn step(&mut self, step: f64) {
+        let edge = self.offset.clamp(0.0, self.max_offset);
+        if self.rubber_band && self.offset != edge {
+            // a critically damped spring pulls the content back to the edge
+            let stretch = self.offset - edge;
+            let acceleration = -SPRING_FREQUENCY * SPRING_FREQUENCY * stretch
+                - 2.0 * SPRING_FREQUENCY * self.velocity;
+            self.velocity += acceleration * step;
+            self.offset += self.velocity * step;
+            if stretch.abs() < 0.01 && self.velocity.abs() < MIN_VELOCITY {
+                self.offset = edge;
+                self.velocity = 0.0;
+            }
+            return;
+        }
+
+        self.velocity *= self.deceleration_rate.powf(step);
+        self.offset += self.velocity * step;
+        if self.velocity.abs() < MIN_VELOCITY {
+            self.velocity = 0.0;
+        }
+        if !self.rubber_band && self.is_past_edge() {
+            self.offset = self.offset.clamp(0.0, self.max_offset);
+            self.velocity = 0.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{reset_clock, set_clock, ManualClock};
+
+    /// Returns a touch event at the given row, with the default cell size.
+    fn touch(kind: TouchEventKind, row: u32, touches: usize) -> TouchEvent {
+        TouchEvent {
+            kind,
+            x: 0,
+            y: row * 19,
+            cell: None,
+            touches,
+        }
+    }
+
+    #[test]
+    fn test_drag_and_fling() {
+        let clock = ManualClock::new();
+        set_clock(clock.clone());
+        let mut scroll = KineticScroll::new();
+        scroll.set_max_offset(100);
+
+        scroll.handle_touch_event(&touch(TouchEventKind::Started, 20, 1));
+        for row in [18, 16, 14, 12, 10] {
+            clock.advance(16.0);
+            assert!(scroll.handle_touch_event(&touch(TouchEventKind::Moved, row, 1)));
+        }
+        assert_eq!(scroll.offset(), 10);
+        assert!(scroll.handle_touch_event(&touch(TouchEventKind::Ended, 10, 0)));
+
+        // keeps moving, slower and slower
+        clock.advance(100.0);
+        let first = scroll.offset();
+        clock.advance(100.0);
+        let second = scroll.offset();
+        assert!(first > 10 && second - first < first - 10);
+        clock.advance(5000.0);
+        assert!(scroll.offset() > second);
+        assert!(!scroll.is_moving());
+        reset_clock();
+    }
+
+    #[test]
+    fn test_no_fling_after_holding() {
+        let clock = ManualClock::new();
+        set_clock(clock.clone());
+        let mut scroll = KineticScroll::new();
+        scroll.set_max_offset(100);
+        scroll.handle_touch_event(&touch(TouchEventKind::Started, 20, 1));
+        clock.advance(16.0);
+        scroll.handle_touch_event(&touch(TouchEventKind::Moved, 15, 1));
+        clock.advance(500.0);
+        scroll.handle_touch_event(&touch(TouchEventKind::Ended, 15, 0));
+        assert!(!scroll.is_moving());
+        assert_eq!(scroll.offset(), 5);
+        reset_clock();
+    }
+
+    #[test]
+    fn test_edges() {
+        let clock = ManualClock::new();
+        set_clock(clock.clone());
+
+        // without rubber-banding, the offset stops at the edges
+        let mut scroll = KineticScroll::new();
+        scroll.set_max_offset(10);
+        scroll.handle_touch_event(&touch(TouchEventKind::Started, 0, 1));
+        clock.advance(16.0);
+        scroll.handle_touch_event(&touch(TouchEventKind::Moved, 4, 1));
+        assert_eq!((scroll.offset(), scroll.overscroll()), (0, 0));
+
+        // with rubber-banding, it stretches past them and springs back
+        let mut scroll = KineticScroll::new().rubber_band(true);
+        scroll.set_max_offset(10);
+        scroll.handle_touch_event(&touch(TouchEventKind::Started, 0, 1));
+        clock.advance(16.0);
+        scroll.handle_touch_event(&touch(TouchEventKind::Moved, 4, 1));
+        clock.advance(16.0);
+        scroll.handle_touch_event(&touch(TouchEventKind::Moved, 8, 1));
+        assert_eq!((scroll.offset(), scroll.overscroll()), (0, -6));
+        scroll.handle_touch_event(&touch(TouchEventKind::Ended, 8, 0));
+        clock.advance(1000.0);
+        assert_eq!((scroll.offset(), scroll.overscroll()), (0, 0));
+        assert!(!scroll.is_moving());
+        reset_clock();
+    }
+
+    #[test]
+    fn test_pinch_cancels_drag() {
+        let clock = ManualClock::new();
+        set_clock(clock.clone());
+        let mut scroll = KineticScroll::new();
+        scroll.set_max_offset(100);
+        scroll.handle_touch_event(&touch(TouchEventKind::Started, 20, 1));
+        scroll.handle_touch_event(&touch(TouchEventKind::Started, 30, 2));
+        clock.advance(16.0);
+        assert!(!scroll.handle_touch_event(&touch(TouchEventKind::Moved, 10, 2)));
+        assert_eq!(scroll.offset(), 0);
+        reset_clock();
+    }
+}
@@ -0,0 +1,469 @@
+//! ## Selection
+//!
+//! Mouse text selection for every backend. Dragging over the grid selects
+//! cells, highlighted by reversing their style, and the selected text is
+//! copied to the clipboard when the mouse button is released:
+//!
+//! ```rust no_run
+//! use ratzilla::selection::{self, SelectionMode, SelectionOptions};
+//! # fn example() -> Result<(), ratzilla::error::Error> {
+//!
+//! selection::enable(SelectionOptions::new().mode(SelectionMode::Linear))?;
+//! // ... later, e.g. from a key binding
+//! if let Some(text) = selection::selected_text() {
+//!     // search for the selected text
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Dragging past the top or the bottom of the grid scrolls the content of
+//! the app, if it [handles auto-scrolling](crate::selection::on_auto_scroll),
+//! at a speed proportional to the distance past the edge.
+//!
+//! The [`WebGl2Backend`] can also select text on the GPU, see
+//! [`WebGl2BackendOptions::enable_mouse_selection_with_mode`]. Only the
+//! selections made with this module can be queried.
+//!
+//! [`WebGl2Backend`]: crate::WebGl2Backend
+//! [`WebGl2BackendOptions::enable_mouse_selection_with_mode`]: crate::backend::webgl2::WebGl2BackendOptions::enable_mouse_selection_with_mode
+
+use std::cell::RefCell;
+
+use ratatui::{buffer::Buffer, layout::Position, style::Modifier};
+use web_sys::{js_sys::Function, wasm_bindgen::prelude::*};
+
+use crate::{
+    backend::{utils::get_window, web},
+    error::Error,
+    teardown,
+};
+
+/// Interval between auto-scrolling steps, in milliseconds.
+const AUTO_SCROLL_INTERVAL_MS: i32 = 50;
+
+/// Rows auto-scrolled per second for every row the mouse is past the edge of
+/// the grid.
+const AUTO_SCROLL_SPEED: f64 = 10.0;
+
+/// How the cells between the start and the end of a selection are selected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// The rectangle between the start and the end.
+    #[default]
+    Block,
+    /// The cells between the start and the end in reading order, like the
+    /// selection of a text editor.
+    Linear,
+}
+
+/// A selection of cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    /// Cell where the selection started.
+    pub start: Position,
+    /// Cell where the selection ends.
+    pub end: Position,
+    /// How the cells in between are selected.
+    pub mode: SelectionMode,
+}
+
+impl Selection {
+    /// Constructs a new [`Selection`].
+    pub fn new(start: Position, end: Position, mode: SelectionMode) -> Self {
+        Self { start, end, mode }
+    }
+
+    /// Returns the first and the last selected cells in reading order.
+    fn bounds(&self) -> (Position, Position) {
+        let (start, end) = if (self.start.y, self.start.x) <= (self.end.y, self.end.x) {
+            (self.start, self.end)
+        } else {
+            (self.end, self.start)
+        };
+        match self.mode {
+            SelectionMode::Block => (
+                Position::new(start.x.min(end.x), start.y),
+                Position::new(start.x.max(end.x), end.y),
+            ),
+            SelectionMode::Linear => (start, end),
+        }
+    }
+
+    /// Returns the selected columns of the given row, as an inclusive range
+    /// ending at `last_column`.
+    fn columns(&self, row: u16, last_column: u16) -> Option<(u16, u16)> {
+        let (start, end) = self.bounds();
+        if row < start.y || row > end.y {
+            return None;
+        }
+        match self.mode {
+            SelectionMode::Block => Some((start.x, end.x.min(last_column))),
+            SelectionMode::Linear => {
+                let first = if row == start.y { start.x } else { 0 };
+                let last = if row == end.y { end.x } else { last_column };
+                Some((first, last.min(last_column)))
+            }
+        }
+    }
+
+    /// Returns `true` if the given cell is selected.
+    pub fn contains(&self, position: Position) -> bool {
+        self.columns(position.y, u16::MAX)
+            .is_some_and(|(first, last)| (first..=last).contains(&position.x))
+    }
+
+    /// Returns the text of the selected cells of the given buffer.
+    ///
+    /// Rows are separated by newlines, without their trailing whitespace.
+    pub fn text(&self, buffer: &Buffer) -> String {
+        let area = buffer.area;
+        let mut lines = Vec::new();
+        for y in area.top()..area.bottom() {
+            let Some((first, last)) = self.columns(y, area.right().saturating_sub(1)) else {
+                continue;
+            };
+            let line: String = (first.max(area.left())..=last)
+                .filter_map(|x| buffer.cell((x, y)))
+                .map(|cell| cell.symbol())
+                .collect();
+            lines.push(line.trim_end().to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+/// Options for mouse text selection.
+#[derive(Debug, Clone)]
+pub struct SelectionOptions {
+    /// How the cells are selected.
+    mode: SelectionMode,
+    /// Whether the selected text is copied when the mouse button is released.
+    copy_on_select: bool,
+}
+
+impl Default for SelectionOptions {
+    fn default() -> Self {
+        Self {
+            mode: SelectionMode::default(),
+            copy_on_select: true,
+        }
+    }
+}
+
+impl SelectionOptions {
+    /// Constructs a new [`SelectionOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how the cells are selected.
+    ///
+    /// Defaults to [`SelectionMode::Block`].
+    pub fn mode(mut self, mode: SelectionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets whether the selected text is copied to the clipboard when the
+    /// mouse button is released.
+    ///
+    /// Defaults to `true`.
+    pub fn copy_on_select(mut self, copy: bool) -> Self {
+        self.copy_on_select = copy;
+        self
+    }
+}
+
+/// State of the mouse selection.
+#[derive(Default)]
+struct SelectionState {
+    /// Options of the enabled selection.
+    options: Option<SelectionOptions>,
+    /// Current selection.
+    selection: Option<Selection>,
+    /// Whether the selection is being dragged.
+    dragging: bool,
+    /// Cells on screen.
+    buffer: Option<Buffer>,
+    /// Scrolls the content of the app by the given rows.
+    auto_scroll: Option<Box<dyn FnMut(i32)>>,
+    /// Rows the mouse is past the top (negative) or the bottom (positive)
+    /// edge of the grid.
+    overshoot: f64,
+    /// Fractions of rows carried over to the next auto-scrolling step.
+    scrolled: f64,
+    /// Auto-scrolling step, called by the interval timer.
+    tick: Option<Function>,
+    /// Id of the running auto-scrolling interval timer.
+    timer: Option<i32>,
+    /// Whether the mouse listeners are registered.
+    listening: bool,
+}
+
+thread_local! {
+    static STATE: RefCell<SelectionState> = RefCell::new(SelectionState::default());
+}
+
+/// Enables mouse text selection.
+pub fn enable(options: SelectionOptions) -> Result<(), Error> {
+    let listening = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.options = Some(options);
+        std::mem::replace(&mut state.listening, true)
+    });
+    if listening {
+        return Ok(());
+    }
+    let closure = Closure::<dyn FnMut(_)>::new(on_mouse_event);
+    let document = get_window()?
+        .document()
+        .ok_or(Error::UnableToRetrieveDocument)?;
+    let listener: Function = closure.into_js_value().unchecked_into();
+    for event in ["mousedown", "mousemove", "mouseup"] {
+        teardown::add_event_listener(&document, event, &listener)?;
+    }
+    let tick = Closure::<dyn FnMut()>::new(auto_scroll_step);
+    STATE.with(|state| state.borrow_mut().tick = Some(tick.into_js_value().unchecked_into()));
+    teardown::on_destroy(|| {
+        disable();
+        STATE.with(|state| state.take());
+    });
+    Ok(())
+}
+
+/// Disables mouse text selection and clears the selection.
+pub fn disable() {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.options = None;
+        state.selection = None;
+        state.dragging = false;
+        state.buffer = None;
+        stop_auto_scroll(&mut state);
+    });
+}
+
+/// Sets how to scroll the content of the app while a selection is dragged
+/// past the top or the bottom of the grid.
+///
+/// The callback is given the rows to scroll, positive to show later rows.
+/// The start of the selection moves with the content, staying within the
+/// grid.
+pub fn on_auto_scroll<F>(callback: F)
+where
+    F: FnMut(i32) + 'static,
+{
+    STATE.with(|state| state.borrow_mut().auto_scroll = Some(Box::new(callback)));
+}
+
+/// Returns the current selection.
+pub fn selection() -> Option<Selection> {
+    STATE.with(|state| state.borrow().selection)
+}
+
+/// Returns the text of the current selection.
+pub fn selected_text() -> Option<String> {
+    STATE.with(|state| {
+        let state = state.borrow();
+        Some(state.selection?.text(state.buffer.as_ref()?))
+    })
+}
+
+/// Clears the current selection.
+pub fn clear() {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.selection = None;
+        state.dragging = false;
+        stop_auto_scroll(&mut state);
+    });
+}
+
+/// Copies the text of the current selection to the clipboard.
+pub fn copy() -> Result<(), Error> {
+    if let Some(text) = selected_text() {
+        // the promise only fails without clipboard permission
+        let _ = get_window()?.navigator().clipboard().write_text(&text);
+    }
+    Ok(())
+}
+
+/// Updates the selection from a mouse event.
+fn on_mouse_event(event: web_sys::MouseEvent) {
+    let (x, y) = (event.client_x() as f64, event.client_y() as f64);
+    let grid = web::grid();
+    let cell = grid.and_then(|grid| grid.cell_at(x, y));
+    let copy = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let mode = state.options.as_ref()?.mode;
+        match event.type_().as_str() {
+            "mousedown" if event.button() == 0 => {
+                state.selection = cell.map(|cell| Selection::new(cell, cell, mode));
+                state.dragging = state.selection.is_some();
+                if state.dragging {
+                    // keep the browser from selecting text as well
+                    event.prevent_default();
+                }
+            }
+            "mousemove" if state.dragging => {
+                let nearest = grid.and_then(|grid| grid.nearest_cell_at(x, y));
+                if let (Some(selection), Some(cell)) = (&mut state.selection, nearest) {
+                    selection.end = cell;
+                }
+                state.overshoot = grid.map_or(0.0, |grid| grid.overshoot_rows(y));
+                if state.overshoot == 0.0 {
+                    stop_auto_scroll(&mut state);
+                } else if state.timer.is_none() && state.auto_scroll.is_some() {
+                    state.timer = state.tick.as_ref().and_then(|tick| {
+                        get_window()
+                            .ok()?
+                            .set_interval_with_callback_and_timeout_and_arguments_0(
+                                tick,
+                                AUTO_SCROLL_INTERVAL_MS,
+                            )
+                            .ok()
+                    });
+                }
+            }
+            "mouseup" if state.dragging => {
+                state.dragging = false;
+                stop_auto_scroll(&mut state);
+                let selection = state.selection?;
+                if selection.start == selection.end {
+                    // a click, not a selection
+                    state.selection = None;
+                    return None;
+                }
+                return state.options.as_ref().map(|options| options.copy_on_select);
+            }
+            _ => {}
+        }
+        None
+    });
+    if copy == Some(true) {
+        let _ = self::copy();
+    }
+}
+
+/// Scrolls the content of the app by a step, while the selection is
+/// dragged past the edge of the grid.
+fn auto_scroll_step() {
+    let rows = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let overshoot = state.overshoot;
+        state.scrolled += auto_scroll_rows(overshoot, AUTO_SCROLL_INTERVAL_MS as f64);
+        let rows = state.scrolled.trunc();
+        state.scrolled -= rows;
+        let height = web::grid().map_or(0, |grid| grid.size.height);
+        if let Some(selection) = &mut state.selection {
+            let start = selection.start.y as f64 - rows;
+            selection.start.y = start.clamp(0.0, height.saturating_sub(1) as f64) as u16;
+        }
+        rows as i32
+    });
+    if rows == 0 {
+        return;
+    }
+    // call the app without borrowing the state, it may query the selection
+    let callback = STATE.with(|state| state.borrow_mut().auto_scroll.take());
+    if let Some(mut callback) = callback {
+        callback(rows);
+        STATE.with(|state| {
+            state.borrow_mut().auto_scroll.get_or_insert(callback);
+        });
+    }
+}
+
+/// Stops auto-scrolling.
+fn stop_auto_scroll(state: &mut SelectionState) {
+    if let Some(timer) = state.timer.take() {
+        if let Ok(window) = get_window() {
+            window.clear_interval_with_handle(timer);
+        }
+    }
+    state.overshoot = 0.0;
+    state.scrolled = 0.0;
+}
+
+/// Returns the rows to auto-scroll in the given time, for a mouse the given
+/// rows past the edge of the grid.
+fn auto_scroll_rows(overshoot: f64, elapsed_ms: f64) -> f64 {
+    overshoot * AUTO_SCROLL_SPEED * elapsed_ms / 1000.0
+}
+
+/// Highlights the selected cells of a frame.
+pub(crate) fn highlight(buffer: &mut Buffer) {
+    STATE.with(|state| {
+        let state = state.borrow();
+        let Some(selection) = state.selection.filter(|_| state.options.is_some()) else {
+            return;
+        };
+        let area = buffer.area;
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                if selection.contains(Position::new(x, y)) {
+                    if let Some(cell) = buffer.cell_mut((x, y)) {
+                        cell.modifier.toggle(Modifier::REVERSED);
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Records the cells on screen, to get the text of the selection.
+pub(crate) fn record(buffer: &Buffer) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if state.options.is_none() {
+            return;
+        }
+        match &mut state.buffer {
+            Some(last) => last.clone_from(buffer),
+            None => state.buffer = Some(buffer.clone()),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer() -> Buffer {
+        Buffer::with_lines(["hello world", "foo bar baz", "last line  "])
+    }
+
+    #[test]
+    fn test_block_selection() {
+        let selection = Selection::new(
+            Position::new(8, 1),
+            Position::new(4, 0),
+            SelectionMode::Block,
+        );
+        assert!(selection.contains(Position::new(4, 1)));
+        assert!(!selection.contains(Position::new(9, 0)));
+        assert!(!selection.contains(Position::new(4, 2)));
+        assert_eq!(selection.text(&buffer()), "o wor\nbar b");
+    }
+
+    #[test]
+    fn test_linear_selection() {
+        let selection = Selection::new(
+            Position::new(6, 0),
+            Position::new(3, 2),
+            SelectionMode::Linear,
+        );
+        assert!(selection.contains(Position::new(0, 1)));
+        assert!(!selection.contains(Position::new(5, 0)));
+        assert!(!selection.contains(Position::new(4, 2)));
+        assert_eq!(selection.text(&buffer()), "world\nfoo bar baz\nlast");
+    }
+
+    #[test]
+    fn test_auto_scroll_rows() {
+        assert_eq!(auto_scroll_rows(0.0, 50.0), 0.0);
+        assert_eq!(auto_scroll_rows(2.0, 50.0), 1.0);
+        assert_eq!(auto_scroll_rows(-1.0, 100.0), -1.0);
+    }
+}
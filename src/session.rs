@@ -0,0 +1,314 @@
+//! ## Session restore
+//!
+//! Enabled with the `session` feature.
+//!
+//! Snapshots registered app state to `localStorage` when the page is hidden
+//! or left, and restores it on the next visit. States are serialized with
+//! [serde] into a single JSON document tagged with a version, and
+//! migrations upgrade snapshots of older versions:
+//!
+//! ```rust no_run
+//! use ratzilla::session::{self, SessionOptions};
+//! use serde::{Deserialize, Serialize};
+//! # fn example() -> Result<(), ratzilla::error::Error> {
+//!
+//! #[derive(Default, Serialize, Deserialize)]
+//! struct Tabs {
+//!     open: Vec<String>,
+//!     active: usize,
+//! }
+//!
+//! session::enable(
+//!     SessionOptions::new("my-app")
+//!         .version(2)
+//!         // version 1 stored the active tab as `selected`
+//!         .migration(1, |state| {
+//!             if let Some(tabs) = state.get_mut("tabs").and_then(|t| t.as_object_mut()) {
+//!                 if let Some(selected) = tabs.remove("selected") {
+//!                     tabs.insert("active".to_string(), selected);
+//!                 }
+//!             }
+//!             Ok(())
+//!         }),
+//! )?;
+//! let tabs = session::register("tabs", Tabs::default());
+//! tabs.borrow_mut().active = 1;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Snapshots that can't be migrated or deserialized are discarded, and the
+//! states start from their default.
+//!
+//! [serde]: https://serde.rs
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use web_sys::{js_sys::Function, wasm_bindgen::prelude::*, VisibilityState};
+
+use crate::{
+    backend::utils::{get_document, get_window},
+    error::Error,
+    teardown,
+    utils::storage::local_storage,
+};
+
+/// Upgrades the states of a snapshot by one version.
+type Migration = Box<dyn Fn(&mut Map<String, Value>) -> Result<(), Error>>;
+
+/// Serializes a registered state.
+type Snapshot = Box<dyn Fn() -> Result<Value, Error>>;
+
+/// Options for the session.
+pub struct SessionOptions {
+    /// Key of the `localStorage` item holding the snapshot.
+    key: String,
+    /// Version of the states.
+    version: u32,
+    /// Migrations from each older version to the next one.
+    migrations: BTreeMap<u32, Migration>,
+}
+
+impl std::fmt::Debug for SessionOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionOptions")
+            .field("key", &self.key)
+            .field("version", &self.version)
+            .field("migrations", &self.migrations.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl SessionOptions {
+    /// Constructs a new [`SessionOptions`] storing the snapshot under the
+    /// given `localStorage` key.
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            version: 1,
+            migrations: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the version of the states.
+    ///
+    /// Increase it when a state changes in a way older snapshots can't be
+    /// deserialized into, and add a [`migration`](Self::migration) from the
+    /// previous version. Defaults to `1`.
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Adds a migration upgrading snapshots from the given version to the
+    /// next one.
+    ///
+    /// The migration receives the states of the snapshot by name, as JSON
+    /// values.
+    pub fn migration<F>(mut self, from_version: u32, migration: F) -> Self
+    where
+        F: Fn(&mut Map<String, Value>) -> Result<(), Error> + 'static,
+    {
+        self.migrations.insert(from_version, Box::new(migration));
+        self
+    }
+}
+
+/// State of the session.
+#[derive(Default)]
+struct Session {
+    /// Options of the enabled session.
+    options: Option<SessionOptions>,
+    /// States of the restored snapshot, taken when they are registered.
+    restored: Map<String, Value>,
+    /// Registered states.
+    states: Vec<(String, Snapshot)>,
+    /// Whether the page listeners are registered.
+    listening: bool,
+}
+
+thread_local! {
+    static SESSION: RefCell<Session> = RefCell::new(Session::default());
+}
+
+/// Enables the session, restoring the last snapshot.
+///
+/// States must be [registered](register) after this. [`teardown::destroy`]
+/// saves the states and disables the session.
+pub fn enable(options: SessionOptions) -> Result<(), Error> {
+    let stored = local_storage()?.get_item(&options.key)?;
+    let restored = stored
+        .and_then(|snapshot| serde_json::from_str(&snapshot).ok())
+        .and_then(|snapshot| migrate(snapshot, options.version, &options.migrations))
+        .unwrap_or_default();
+    let listening = SESSION.with(|session| {
+        let mut session = session.borrow_mut();
+        session.options = Some(options);
+        session.restored = restored;
+        std::mem::replace(&mut session.listening, true)
+    });
+    if !listening {
+        let on_hidden: Function = Closure::<dyn FnMut()>::new(|| {
+            let hidden = get_document()
+                .map(|document| document.visibility_state() == VisibilityState::Hidden)
+                .unwrap_or(true);
+            if hidden {
+                // nothing to do about storage errors while the page is hidden
+                let _ = save();
+            }
+        })
+        .into_js_value()
+        .unchecked_into();
+        teardown::add_event_listener(&get_document()?.into(), "visibilitychange", &on_hidden)?;
+        teardown::add_event_listener(&get_window()?.into(), "pagehide", &on_hidden)?;
+        teardown::on_destroy(reset);
+    }
+    Ok(())
+}
+
+/// Saves the states of the destroyed terminal and disables the session.
+fn reset() {
+    // nothing to do about storage errors while the terminal is destroyed
+    let _ = save();
+    SESSION.with(|session| session.take());
+}
+
+/// Registers a state under the given name, restoring it from the snapshot.
+///
+/// Returns the state from the snapshot, or the given default if the
+/// session is disabled or the snapshot has no state with this name.
+pub fn register<T>(name: &str, default: T) -> Rc<RefCell<T>>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    SESSION.with(|session| {
+        let mut session = session.borrow_mut();
+        let state = session
+            .restored
+            .remove(name)
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or(default);
+        let state = Rc::new(RefCell::new(state));
+        let snapshot = {
+            let state = state.clone();
+            move || {
+                serde_json::to_value(&*state.borrow())
+                    .map_err(|e| Error::InvalidSession(e.to_string()))
+            }
+        };
+        session.states.retain(|(registered, _)| registered != name);
+        session.states.push((name.to_string(), Box::new(snapshot)));
+        state
+    })
+}
+
+/// Saves a snapshot of the registered states.
+///
+/// This happens automatically when the page is hidden or left.
+pub fn save() -> Result<(), Error> {
+    let snapshot = SESSION.with(|session| {
+        let session = session.borrow();
+        let Some(options) = &session.options else {
+            return Ok(None);
+        };
+        let mut states = Map::new();
+        for (name, snapshot) in &session.states {
+            states.insert(name.clone(), snapshot()?);
+        }
+        let snapshot = serde_json::json!({ "version": options.version, "states": states });
+        Ok::<_, Error>(Some((options.key.clone(), snapshot.to_string())))
+    })?;
+    if let Some((key, snapshot)) = snapshot {
+        local_storage()?.set_item(&key, &snapshot)?;
+    }
+    Ok(())
+}
+
+/// Removes the snapshot, e.g. on logout.
+///
+/// The registered states are still saved when the page is hidden, unless
+/// the session is [disabled](disable).
+pub fn clear() -> Result<(), Error> {
+    let key = SESSION.with(|session| Some(session.borrow().options.as_ref()?.key.clone()));
+    if let Some(key) = key {
+        local_storage()?.remove_item(&key)?;
+    }
+    Ok(())
+}
+
+/// Disables the session, forgetting the registered states.
+pub fn disable() {
+    SESSION.with(|session| {
+        let mut session = session.borrow_mut();
+        session.options = None;
+        session.restored.clear();
+        session.states.clear();
+    });
+}
+
+/// Upgrades a snapshot to the given version, returning its states.
+///
+/// Returns `None` if the snapshot is invalid, newer than the version or
+/// misses a migration.
+fn migrate(
+    snapshot: Value,
+    version: u32,
+    migrations: &BTreeMap<u32, Migration>,
+) -> Option<Map<String, Value>> {
+    let Value::Object(mut snapshot) = snapshot else {
+        return None;
+    };
+    let mut from = u32::try_from(snapshot.get("version")?.as_u64()?).ok()?;
+    let Value::Object(mut states) = snapshot.remove("states")? else {
+        return None;
+    };
+    while from < version {
+        migrations.get(&from)?(&mut states).ok()?;
+        from += 1;
+    }
+    (from == version).then_some(states)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn rename(from: &'static str, to: &'static str) -> Migration {
+        Box::new(move |states| {
+            let value = states
+                .remove(from)
+                .ok_or(Error::InvalidSession(from.into()))?;
+            states.insert(to.to_string(), value);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_migrate() {
+        let migrations = BTreeMap::from([(1, rename("a", "b")), (2, rename("b", "c"))]);
+        let snapshot = json!({ "version": 1, "states": { "a": 42 } });
+        let states = migrate(snapshot.clone(), 3, &migrations).expect("migrated");
+        assert_eq!(Value::Object(states), json!({ "c": 42 }));
+        assert_eq!(
+            migrate(snapshot.clone(), 1, &migrations).map(Value::Object),
+            Some(json!({ "a": 42 }))
+        );
+        // newer than the app
+        assert_eq!(
+            migrate(json!({ "version": 4, "states": {} }), 3, &migrations),
+            None
+        );
+        // missing migration
+        assert_eq!(migrate(snapshot, 4, &migrations), None);
+        // failing migration
+        assert_eq!(
+            migrate(json!({ "version": 2, "states": {} }), 3, &migrations),
+            None
+        );
+        assert_eq!(migrate(json!("invalid"), 1, &migrations), None);
+    }
+}
@@ -0,0 +1,228 @@
+//! ## Settings
+//!
+//! User preferences for the built-in features, persisted in the
+//! `localStorage` of the window: the font size, the theme, reduced motion,
+//! the frame rate cap and the bell. Combined with the [`SettingsScreen`]
+//! widget, apps get a preferences screen for free:
+//!
+//! ```rust no_run
+//! use ratzilla::{
+//!     settings::Settings,
+//!     widgets::{SettingsScreen, SettingsScreenState},
+//!     WebRenderer,
+//! };
+//! # fn example(terminal: ratzilla::ratatui::Terminal<ratzilla::DomBackend>) {
+//! use std::{cell::RefCell, rc::Rc};
+//!
+//! let settings = Settings::load();
+//! settings.apply();
+//! let state = Rc::new(RefCell::new((
+//!     settings,
+//!     SettingsScreenState::new().themes(["dark", "light"]),
+//! )));
+//!
+//! terminal.on_key_event({
+//!     let state = state.clone();
+//!     move |event| {
+//!         let (settings, screen) = &mut *state.borrow_mut();
+//!         if screen.handle_key_event(&event, settings) {
+//!             let _ = settings.save();
+//!             settings.apply();
+//!         }
+//!     }
+//! });
+//! terminal.draw_web(move |frame| {
+//!     let (settings, screen) = &mut *state.borrow_mut();
+//!     frame.render_stateful_widget(SettingsScreen::new(settings), frame.area(), screen);
+//! });
+//! # }
+//! ```
+//!
+//! The font size, the theme and the bell depend on the app, e.g. the font
+//! size is given to the font options of the `CanvasBackend`.
+//!
+//! [`SettingsScreen`]: crate::widgets::SettingsScreen
+
+use crate::{
+    backend::utils::get_window,
+    error::Error,
+    render_loop,
+    utils::storage::{get_item, remove_item, set_item},
+};
+
+/// Prefix of the `localStorage` keys of the settings.
+const KEY_PREFIX: &str = "ratzilla.settings.";
+
+/// Stored value of a disabled frame rate cap.
+const FPS_CAP_OFF: &str = "off";
+
+/// User preferences for the built-in features.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// Font size, in CSS pixels.
+    pub font_size: f64,
+    /// Name of the theme, if one was chosen.
+    pub theme: Option<String>,
+    /// Whether animations should be reduced.
+    pub reduced_motion: bool,
+    /// Maximum number of frames per second, if capped.
+    pub fps_cap: Option<f64>,
+    /// Whether the bell is audible.
+    pub bell: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            font_size: 16.0,
+            theme: None,
+            reduced_motion: false,
+            fps_cap: None,
+            bell: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Constructs new default [`Settings`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the stored settings.
+    ///
+    /// Settings that were never stored or are invalid, or all of them if the
+    /// storage is unavailable, keep their default. Reduced motion defaults to the
+    /// `prefers-reduced-motion` preference of the browser.
+    pub fn load() -> Self {
+        let defaults = Self {
+            reduced_motion: prefers_reduced_motion(),
+            ..Self::default()
+        };
+        let load = || -> Result<Self, Error> {
+            Ok(Self {
+                font_size: get_item(&key("font-size"))?
+                    .filter(is_positive)
+                    .unwrap_or(defaults.font_size),
+                theme: get_item(&key("theme"))?,
+                reduced_motion: get_item(&key("reduced-motion"))?
+                    .unwrap_or(defaults.reduced_motion),
+                // `off` doesn't parse, which keeps the frame rate uncapped
+                fps_cap: get_item(&key("fps-cap"))?.filter(is_positive),
+                bell: get_item(&key("bell"))?.unwrap_or(defaults.bell),
+            })
+        };
+        load().unwrap_or(defaults)
+    }
+
+    /// Stores the settings.
+    pub fn save(&self) -> Result<(), Error> {
+        set_item(&key("font-size"), self.font_size)?;
+        match &self.theme {
+            Some(theme) => set_item(&key("theme"), theme)?,
+            None => remove_item(&key("theme"))?,
+        }
+        set_item(&key("reduced-motion"), self.reduced_motion)?;
+        match self.fps_cap {
+            Some(fps) => set_item(&key("fps-cap"), fps)?,
+            None => set_item(&key("fps-cap"), FPS_CAP_OFF)?,
+        }
+        set_item(&key("bell"), self.bell)
+    }
+
+    /// Removes the stored settings.
+    pub fn clear() -> Result<(), Error> {
+        for name in ["font-size", "theme", "reduced-motion", "fps-cap", "bell"] {
+            remove_item(&key(name))?;
+        }
+        Ok(())
+    }
+
+    /// Applies the settings of the built-in features, i.e. caps the frame
+    /// rate of the [render loop](crate::render_loop).
+    pub fn apply(&self) {
+        render_loop::set_target_fps(self.fps_cap);
+    }
+}
+
+/// Returns the `localStorage` key of the given setting.
+fn key(name: &str) -> String {
+    format!("{KEY_PREFIX}{name}")
+}
+
+/// Returns `true` if the stored number is usable as a size or a rate, i.e.
+/// not `NaN`, infinite, zero or negative.
+fn is_positive(value: &f64) -> bool {
+    value.is_finite() && *value > 0.0
+}
+
+/// Returns `true` if the user asked the browser to minimize animations.
+fn prefers_reduced_motion() -> bool {
+    get_window()
+        .ok()
+        .and_then(|window| window.match_media("(prefers-reduced-motion: reduce)").ok())
+        .flatten()
+        .is_some_and(|media| media.matches())
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+    use crate::utils::storage::local_storage;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn test_is_positive() {
+        assert!(is_positive(&14.5));
+        for value in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(!is_positive(&value), "{value}");
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_save_and_load() {
+        let settings = Settings {
+            font_size: 20.0,
+            theme: Some("light".to_string()),
+            reduced_motion: true,
+            fps_cap: Some(30.0),
+            bell: false,
+        };
+        settings.save().expect("failed to save the settings");
+        assert_eq!(Settings::load(), settings);
+
+        let uncapped = Settings {
+            theme: None,
+            fps_cap: None,
+            ..settings
+        };
+        uncapped.save().expect("failed to save the settings");
+        assert_eq!(Settings::load(), uncapped);
+        Settings::clear().expect("failed to clear the settings");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_load_corrupt_storage() {
+        let storage = local_storage().expect("failed to get the storage");
+        for (name, value) in [
+            ("font-size", "NaN"),
+            ("reduced-motion", "maybe"),
+            ("fps-cap", "-60"),
+            ("bell", "loud"),
+        ] {
+            storage
+                .set_item(&key(name), value)
+                .expect("failed to corrupt the storage");
+        }
+
+        let defaults = Settings {
+            reduced_motion: prefers_reduced_motion(),
+            ..Settings::default()
+        };
+        assert_eq!(Settings::load(), defaults);
+        Settings::clear().expect("failed to clear the settings");
+    }
+}
@@ -0,0 +1,160 @@
+//! ## Shared state
+//!
+//! A frame is drawn synchronously, so the render callback can't `.await`
+//! a `fetch` or any other future. Instead, async tasks write their results
+//! to a [`Shared`] cell and the next frame reads it:
+//!
+//! ```rust no_run
+//! use ratzilla::{ratatui::widgets::Paragraph, shared::Shared, WebRenderer};
+//! # async fn fetch_motd() -> String { String::new() }
+//! # fn example(terminal: ratzilla::ratatui::Terminal<ratzilla::DomBackend>) {
+//!
+//! let motd = Shared::new(String::from("Loading..."));
+//! // runs on the browser event loop and redraws when done
+//! motd.load(fetch_motd());
+//!
+//! terminal.draw_web(move |frame| {
+//!     motd.with(|motd| frame.render_widget(Paragraph::new(motd.as_str()), frame.area()));
+//! });
+//! # }
+//! ```
+//!
+//! Every write requests a redraw, so the cell also works with the
+//! [on-demand](crate::render_loop::RenderLoopOptions::on_demand) render loop.
+//! Tasks that need progress reports or cancellation can use the
+//! [`TaskScheduler`](crate::tasks::TaskScheduler) and write to a cell
+//! from their job.
+//!
+//! [`Shared`]: crate::shared::Shared
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    future::Future,
+    rc::Rc,
+};
+
+use crate::render_loop;
+
+/// A value shared between the render callback and async tasks.
+///
+/// Clones of a cell share the same value.
+pub struct Shared<T> {
+    /// The value.
+    value: Rc<RefCell<T>>,
+    /// Number of writes to the value.
+    version: Rc<Cell<u64>>,
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            version: self.version.clone(),
+        }
+    }
+}
+
+impl<T: Default> Default for Shared<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shared")
+            .field("value", &self.value.borrow())
+            .field("version", &self.version.get())
+            .finish()
+    }
+}
+
+impl<T> Shared<T> {
+    /// Constructs a new [`Shared`] cell with the given value.
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Rc::new(RefCell::new(value)),
+            version: Rc::new(Cell::new(0)),
+        }
+    }
+
+    /// Returns a copy of the value.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.value.borrow().clone()
+    }
+
+    /// Calls the closure with a reference to the value.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.value.borrow())
+    }
+
+    /// Replaces the value and requests a redraw, returning the previous one.
+    pub fn set(&self, value: T) -> T {
+        let previous = self.value.replace(value);
+        self.changed();
+        previous
+    }
+
+    /// Modifies the value in place and requests a redraw.
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let result = f(&mut self.value.borrow_mut());
+        self.changed();
+        result
+    }
+
+    /// Returns the number of writes to the value.
+    ///
+    /// Comparing it with the version of the previous frame tells if the
+    /// value changed in between, e.g. to recompute a layout.
+    pub fn version(&self) -> u64 {
+        self.version.get()
+    }
+
+    /// Spawns a task on the browser event loop, passing it a clone of the
+    /// cell to write its results to.
+    pub fn spawn<F, Fut>(&self, task: F)
+    where
+        F: FnOnce(Shared<T>) -> Fut,
+        Fut: Future<Output = ()> + 'static,
+    {
+        wasm_bindgen_futures::spawn_local(task(self.clone()));
+    }
+
+    /// Spawns a future on the browser event loop and sets the value to its
+    /// output.
+    pub fn load<Fut>(&self, future: Fut)
+    where
+        T: 'static,
+        Fut: Future<Output = T> + 'static,
+    {
+        self.spawn(|shared| async move {
+            shared.set(future.await);
+        });
+    }
+
+    /// Records a write and requests a redraw.
+    fn changed(&self) {
+        self.version.set(self.version.get() + 1);
+        render_loop::request_redraw();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_value() {
+        let shared = Shared::new(vec![1]);
+        let clone = shared.clone();
+        assert_eq!(clone.set(vec![2]), vec![1]);
+        clone.update(|value| value.push(3));
+        assert_eq!(shared.get(), vec![2, 3]);
+        assert_eq!(shared.with(Vec::len), 2);
+        assert_eq!(shared.version(), 2);
+    }
+}
@@ -0,0 +1,343 @@
+//! ## Server-side rendering
+//!
+//! Renders buffers into the same HTML structure as the [`DomBackend`], so a
+//! page can show meaningful content before the WebAssembly module is loaded.
+//! Once the app starts, the [`DomBackend`] replaces the pre-rendered grid.
+//!
+//! On non-wasm targets, [`StaticSite`] renders a set of routes into static
+//! HTML pages at build time, e.g. from a `build.rs` script or a small binary:
+//!
+//! ```rust no_run
+//! # #[cfg(not(target_arch = "wasm32"))]
+//! # fn main() -> std::io::Result<()> {
+//! use ratzilla::{
+//!     ratatui::widgets::Paragraph,
+//!     ssr::StaticSite,
+//! };
+//!
+//! StaticSite::new(80, 24)
+//!     .title("My docs")
+//!     .bundle("/app.js", "/app_bg.wasm")
+//!     .page("/", |frame| frame.render_widget(Paragraph::new("Home"), frame.area()))
+//!     .page("/about", |frame| frame.render_widget(Paragraph::new("About"), frame.area()))
+//!     .build("dist")?;
+//! # Ok(())
+//! # }
+//! # #[cfg(target_arch = "wasm32")]
+//! # fn main() {}
+//! ```
+//!
+//! [`DomBackend`]: crate::DomBackend
+//! [`StaticSite`]: crate::ssr::StaticSite
+
+use std::fmt::Write;
+
+use ratatui::buffer::Buffer;
+use unicode_width::UnicodeWidthStr;
+
+use crate::backend::utils::get_cell_style_as_css;
+
+/// The attribute on the `<html>` element holding the route of a static page.
+///
+/// The [`Router`](crate::router::Router) uses it as the initial route when the
+/// URL has no fragment.
+pub const ROUTE_ATTRIBUTE: &str = "data-ratzilla-route";
+
+/// Renders a buffer into HTML using the structure of the [`DomBackend`].
+///
+/// `grid_id` is the id of the generated grid element, which must match the
+/// grid id of the [`DomBackend`] for it to take over the pre-rendered grid.
+///
+/// [`DomBackend`]: crate::DomBackend
+pub fn buffer_to_html(buffer: &Buffer, grid_id: &str) -> String {
+    let mut html = String::new();
+    let _ = write!(html, "<div id=\"{}\">", escape_html(grid_id));
+
+    let area = buffer.area;
+    for y in area.top()..area.bottom() {
+        html.push_str("<pre style=\"height: 15px;\">");
+        let mut skip = false;
+        for x in area.left()..area.right() {
+            let cell = &buffer[(x, y)];
+            if skip {
+                skip = false;
+                html.push_str("<span></span>");
+                continue;
+            }
+            let _ = write!(
+                html,
+                "<span style=\"{}\">{}</span>",
+                get_cell_style_as_css(cell),
+                escape_html(cell.symbol())
+            );
+            skip = cell.symbol().width() == 2;
+        }
+        html.push_str("</pre>");
+    }
+
+    html.push_str("</div>");
+    html
+}
+
+/// Escapes the characters that have a special meaning in HTML.
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use static_site::StaticSite;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod static_site {
+    use std::{
+        fs, io,
+        path::{Component, Path, PathBuf},
+    };
+
+    use ratatui::{backend::TestBackend, Frame, Terminal};
+
+    use super::{buffer_to_html, escape_html, ROUTE_ATTRIBUTE};
+
+    /// Renders a page into a frame.
+    type PageRenderer = Box<dyn FnMut(&mut Frame)>;
+
+    /// Generates static HTML pages from ratzilla views.
+    ///
+    /// Each page is written to `<out_dir>/<route>/index.html` with the
+    /// pre-rendered grid and the script tag loading the app, which takes over
+    /// the page once it is initialized.
+    pub struct StaticSite {
+        /// Terminal size, in cells.
+        size: (u16, u16),
+        /// Page title.
+        title: String,
+        /// Grid element id.
+        grid_id: String,
+        /// Paths of the JavaScript glue and WebAssembly module.
+        bundle: Option<(String, String)>,
+        /// Extra markup for the `<head>` element.
+        head: String,
+        /// Routes and their renderers.
+        pages: Vec<(String, PageRenderer)>,
+    }
+
+    impl StaticSite {
+        /// Constructs a new [`StaticSite`] rendering pages with the given size, in cells.
+        pub fn new(width: u16, height: u16) -> Self {
+            Self {
+                size: (width, height),
+                title: "Ratzilla".to_string(),
+                grid_id: "grid".to_string(),
+                bundle: None,
+                head: String::new(),
+                pages: Vec::new(),
+            }
+        }
+
+        /// Sets the title of the pages.
+        pub fn title(mut self, title: &str) -> Self {
+            self.title = title.to_string();
+            self
+        }
+
+        /// Sets the id of the grid element.
+        ///
+        /// This must match the grid id of the [`DomBackend`](crate::DomBackend)
+        /// used by the app, see [`DomBackendOptions::grid_id`](crate::backend::dom::DomBackendOptions::grid_id).
+        pub fn grid_id(mut self, id: &str) -> Self {
+            self.grid_id = id.to_string();
+            self
+        }
+
+        /// Sets the paths of the JavaScript glue and the WebAssembly module
+        /// generated by `wasm-bindgen`, used to hydrate the pages.
+        pub fn bundle(mut self, js_path: &str, wasm_path: &str) -> Self {
+            self.bundle = Some((js_path.to_string(), wasm_path.to_string()));
+            self
+        }
+
+        /// Adds extra markup to the `<head>` element, such as stylesheets.
+        pub fn head(mut self, markup: &str) -> Self {
+            self.head.push_str(markup);
+            self
+        }
+
+        /// Adds a page for the given route.
+        pub fn page<F>(mut self, route: &str, render: F) -> Self
+        where
+            F: FnMut(&mut Frame) + 'static,
+        {
+            self.pages.push((route.to_string(), Box::new(render)));
+            self
+        }
+
+        /// Renders all pages into the given directory.
+        ///
+        /// Returns an [`io::ErrorKind::InvalidInput`] error, without writing
+        /// any page, if a route would leave the directory, e.g. `/../x`.
+        pub fn build<P: AsRef<Path>>(mut self, out_dir: P) -> io::Result<()> {
+            let out_dir = out_dir.as_ref();
+            let pages = std::mem::take(&mut self.pages)
+                .into_iter()
+                .map(|(route, render)| Ok((route_dir(out_dir, &route)?, route, render)))
+                .collect::<io::Result<Vec<_>>>()?;
+            for (dir, route, mut render) in pages {
+                let html = self.render_page(&route, &mut render)?;
+                fs::create_dir_all(&dir)?;
+                fs::write(dir.join("index.html"), html)?;
+            }
+            Ok(())
+        }
+
+        /// Renders a single page into a HTML document.
+        fn render_page(&self, route: &str, render: &mut PageRenderer) -> io::Result<String> {
+            let mut terminal = Terminal::new(TestBackend::new(self.size.0, self.size.1))
+                .map_err(io::Error::other)?;
+            terminal
+                .draw(|frame| render(frame))
+                .map_err(io::Error::other)?;
+            let grid = buffer_to_html(terminal.backend().buffer(), &self.grid_id);
+
+            let script = match &self.bundle {
+                Some((js, wasm)) => format!(
+                    "<script type=\"module\">import init from \"{}\"; init(\"{}\");</script>",
+                    escape_html(js),
+                    escape_html(wasm)
+                ),
+                None => String::new(),
+            };
+
+            Ok(format!(
+                "<!doctype html>\n\
+                 <html lang=\"en\" {ROUTE_ATTRIBUTE}=\"{route}\">\n\
+                 <head>\n\
+                 <meta charset=\"UTF-8\" />\n\
+                 <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\" />\n\
+                 <title>{title}</title>\n\
+                 {head}\n\
+                 </head>\n\
+                 <body>{grid}{script}</body>\n\
+                 </html>\n",
+                route = escape_html(route),
+                title = escape_html(&self.title),
+                head = self.head,
+            ))
+        }
+    }
+
+    /// Returns the directory of the page for the given route.
+    ///
+    /// Only plain path segments are allowed, so the page can't be written
+    /// outside of `out_dir`.
+    fn route_dir(out_dir: &Path, route: &str) -> io::Result<PathBuf> {
+        let path = Path::new(route.trim_matches('/'));
+        if !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid route: {route}"),
+            ));
+        }
+        Ok(out_dir.join(path))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ratatui::widgets::Paragraph;
+
+        #[test]
+        fn test_route_dir() {
+            let out_dir = Path::new("dist");
+            assert_eq!(route_dir(out_dir, "/").ok(), Some(PathBuf::from("dist")));
+            assert_eq!(
+                route_dir(out_dir, "/docs/intro/").ok(),
+                Some(Path::new("dist").join("docs").join("intro"))
+            );
+            for route in ["/../x", "/docs/../../x", "/./x", "x/.."] {
+                assert_eq!(
+                    route_dir(out_dir, route).map_err(|e| e.kind()),
+                    Err(io::ErrorKind::InvalidInput),
+                    "{route}"
+                );
+            }
+        }
+
+        #[test]
+        fn test_build() -> io::Result<()> {
+            let out_dir = std::env::temp_dir().join(format!("ratzilla-ssr-{}", std::process::id()));
+            let result = StaticSite::new(10, 2)
+                .title("Docs")
+                .bundle("/app.js", "/app_bg.wasm")
+                .page("/", |frame| {
+                    frame.render_widget(Paragraph::new("Home"), frame.area())
+                })
+                .page("/about", |frame| {
+                    frame.render_widget(Paragraph::new("About"), frame.area())
+                })
+                .build(&out_dir)
+                .and_then(|_| {
+                    Ok((
+                        fs::read_to_string(out_dir.join("index.html"))?,
+                        fs::read_to_string(out_dir.join("about").join("index.html"))?,
+                    ))
+                });
+            let rejected = StaticSite::new(10, 2)
+                .page("/", |_| {})
+                .page("/../escaped", |_| {})
+                .build(out_dir.join("rejected"));
+            let rejected_written = out_dir.join("rejected").exists();
+            fs::remove_dir_all(&out_dir)?;
+
+            let (home, about) = result?;
+            assert!(home.contains("<title>Docs</title>"));
+            assert!(home.contains(&format!("{ROUTE_ATTRIBUTE}=\"/\"")));
+            assert!(home.contains(">H</span>"));
+            assert!(home.contains("init(\"/app_bg.wasm\")"));
+            assert!(about.contains(&format!("{ROUTE_ATTRIBUTE}=\"/about\"")));
+            assert!(about.contains(">A</span>"));
+            assert_eq!(
+                rejected.map_err(|e| e.kind()),
+                Err(io::ErrorKind::InvalidInput)
+            );
+            assert!(!rejected_written);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("<a href=\"x\">&'"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&#39;"
+        );
+    }
+
+    #[test]
+    fn test_buffer_to_html() {
+        let buffer = Buffer::with_lines(["a<", "b "]);
+        let html = buffer_to_html(&buffer, "grid");
+        assert!(html.starts_with("<div id=\"grid\">"));
+        assert_eq!(html.matches("<pre").count(), 2);
+        assert_eq!(html.matches("<span").count(), 4);
+        assert!(html.contains(">&lt;</span>"));
+    }
+}
@@ -0,0 +1,265 @@
+//! ## Frame stepping
+//!
+//! A debug mode where the render loop of [`draw_web`] can be paused and
+//! advanced one frame at a time, e.g. to inspect an effect sequence or an
+//! animation glitch frame by frame.
+//!
+//! While stepping is enabled, it drives the [`clock`](crate::clock): the time
+//! stands still while paused and advances by one frame duration per step, so
+//! the effects, transitions and animated widgets advance exactly one frame
+//! too. After resuming, the time continues from where it was paused.
+//!
+//! Rendering is paused and stepped with [`pause`], [`step`] and [`resume`],
+//! or with keyboard chords handled by [`on_key_event`] (`Ctrl+Alt+P` toggles
+//! the pause and `Ctrl+Alt+N` steps by default).
+//!
+//! ```rust no_run
+//! use ratzilla::stepping::{enable_stepping, SteppingOptions};
+//!
+//! enable_stepping(SteppingOptions::new());
+//! ```
+//!
+//! [`draw_web`]: crate::WebRenderer::draw_web
+//! [`on_key_event`]: crate::WebRenderer::on_key_event
+//! [`pause`]: crate::stepping::pause
+//! [`resume`]: crate::stepping::resume
+//! [`step`]: crate::stepping::step
+
+use std::cell::RefCell;
+
+use crate::{
+    clock::{self, Clock, SystemClock},
+    event::{KeyCode, KeyEvent},
+    keymap::KeyBinding,
+};
+
+/// Default duration of a frame step, in milliseconds.
+const DEFAULT_FRAME_MS: f64 = 1000.0 / 60.0;
+
+/// Options of the frame stepping mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SteppingOptions {
+    /// Key toggling the pause.
+    pause_key: Option<KeyBinding>,
+    /// Key advancing one frame.
+    step_key: Option<KeyBinding>,
+    /// Duration of a step, in milliseconds.
+    frame_ms: f64,
+}
+
+impl Default for SteppingOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SteppingOptions {
+    /// Constructs new [`SteppingOptions`] with the default keys and 60 frames
+    /// per second.
+    pub fn new() -> Self {
+        let chord = |c| KeyBinding {
+            ctrl: true,
+            alt: true,
+            ..KeyBinding::new(KeyCode::Char(c))
+        };
+        Self {
+            pause_key: Some(chord('p')),
+            step_key: Some(chord('n')),
+            frame_ms: DEFAULT_FRAME_MS,
+        }
+    }
+
+    /// Sets the key toggling the pause, or disables it with `None`.
+    pub fn pause_key(mut self, key: Option<KeyBinding>) -> Self {
+        self.pause_key = key;
+        self
+    }
+
+    /// Sets the key advancing one frame, or disables it with `None`.
+    pub fn step_key(mut self, key: Option<KeyBinding>) -> Self {
+        self.step_key = key;
+        self
+    }
+
+    /// Sets the duration of a step, in milliseconds.
+    pub fn frame_duration(mut self, ms: f64) -> Self {
+        self.frame_ms = ms;
+        self
+    }
+}
+
+/// State of the frame stepping mode.
+#[derive(Debug)]
+struct Stepper {
+    /// Options.
+    options: SteppingOptions,
+    /// Time of the clock while paused, in milliseconds.
+    frozen: Option<f64>,
+    /// Difference between the system time and the time of the clock.
+    offset: f64,
+    /// Frames to draw before pausing again.
+    pending_steps: u32,
+}
+
+impl Stepper {
+    /// Constructs a new [`Stepper`].
+    fn new(options: SteppingOptions) -> Self {
+        Self {
+            options,
+            frozen: None,
+            offset: 0.0,
+            pending_steps: 0,
+        }
+    }
+
+    /// Returns the time of the clock for the given system time.
+    fn now(&self, system_now: f64) -> f64 {
+        self.frozen.unwrap_or(system_now - self.offset)
+    }
+
+    /// Pauses rendering.
+    fn pause(&mut self, system_now: f64) {
+        if self.frozen.is_none() {
+            self.frozen = Some(self.now(system_now));
+        }
+    }
+
+    /// Resumes rendering, continuing from the time of the clock.
+    fn resume(&mut self, system_now: f64) {
+        if let Some(frozen) = self.frozen.take() {
+            self.offset = system_now - frozen;
+        }
+        self.pending_steps = 0;
+    }
+
+    /// Returns `Some(true)` if a frame should be drawn while paused, advancing
+    /// the clock by one frame, or `None` if not paused.
+    fn should_draw(&mut self) -> Option<bool> {
+        let frozen = self.frozen.as_mut()?;
+        if self.pending_steps == 0 {
+            return Some(false);
+        }
+        self.pending_steps -= 1;
+        *frozen += self.options.frame_ms;
+        Some(true)
+    }
+}
+
+thread_local! {
+    static STEPPER: RefCell<Option<Stepper>> = const { RefCell::new(None) };
+}
+
+/// The clock driven by the frame stepping mode.
+#[derive(Debug, Clone, Copy)]
+struct SteppingClock;
+
+impl Clock for SteppingClock {
+    fn now(&self) -> f64 {
+        let system_now = SystemClock.now();
+        STEPPER.with(|s| {
+            s.borrow()
+                .as_ref()
+                .map_or(system_now, |s| s.now(system_now))
+        })
+    }
+}
+
+/// Runs a function on the stepper, if enabled.
+fn with_stepper<T>(f: impl FnOnce(&mut Stepper) -> T) -> Option<T> {
+    STEPPER.with(|s| s.borrow_mut().as_mut().map(f))
+}
+
+/// Enables the frame stepping mode and makes it drive the clock.
+pub fn enable_stepping(options: SteppingOptions) {
+    STEPPER.with(|s| *s.borrow_mut() = Some(Stepper::new(options)));
+    clock::set_clock(SteppingClock);
+}
+
+/// Disables the frame stepping mode and restores the system clock.
+pub fn disable_stepping() {
+    STEPPER.with(|s| *s.borrow_mut() = None);
+    clock::reset_clock();
+}
+
+/// Pauses rendering.
+pub fn pause() {
+    let system_now = SystemClock.now();
+    with_stepper(|s| s.pause(system_now));
+}
+
+/// Resumes rendering.
+pub fn resume() {
+    let system_now = SystemClock.now();
+    with_stepper(|s| s.resume(system_now));
+}
+
+/// Draws one more frame while paused.
+pub fn step() {
+    with_stepper(|s| {
+        if s.frozen.is_some() {
+            s.pending_steps += 1;
+        }
+    });
+}
+
+/// Returns `true` if rendering is paused.
+pub fn is_paused() -> bool {
+    with_stepper(|s| s.frozen.is_some()).unwrap_or(false)
+}
+
+/// Returns `Some(true)` if a frame should be drawn while paused, or `None`
+/// if rendering isn't paused.
+pub(crate) fn should_draw() -> Option<bool> {
+    with_stepper(Stepper::should_draw).flatten()
+}
+
+/// Handles the keyboard chords of the frame stepping mode.
+///
+/// Returns `true` if the event was consumed.
+pub(crate) fn handle_key_event(event: &KeyEvent) -> bool {
+    let Some((pause_key, step_key)) =
+        with_stepper(|s| (s.options.pause_key.clone(), s.options.step_key.clone()))
+    else {
+        return false;
+    };
+    if pause_key.is_some_and(|key| key.matches(event)) {
+        if is_paused() {
+            resume();
+        } else {
+            pause();
+        }
+        true
+    } else if step_key.is_some_and(|key| key.matches(event)) {
+        step();
+        true
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pause_step_resume() {
+        let mut stepper = Stepper::new(SteppingOptions::new().frame_duration(10.0));
+        assert_eq!(stepper.should_draw(), None);
+        assert_eq!(stepper.now(100.0), 100.0);
+
+        stepper.pause(100.0);
+        assert_eq!(stepper.should_draw(), Some(false));
+        assert_eq!(stepper.now(500.0), 100.0);
+
+        stepper.pending_steps = 2;
+        assert_eq!(stepper.should_draw(), Some(true));
+        assert_eq!(stepper.should_draw(), Some(true));
+        assert_eq!(stepper.should_draw(), Some(false));
+        assert_eq!(stepper.now(500.0), 120.0);
+
+        // the time continues from where it was paused
+        stepper.resume(1000.0);
+        assert_eq!(stepper.should_draw(), None);
+        assert_eq!(stepper.now(1005.0), 125.0);
+    }
+}
@@ -0,0 +1,76 @@
+use web_sys::{
+    wasm_bindgen::{prelude::Closure, JsCast},
+    BroadcastChannel, MessageEvent,
+};
+
+use crate::error::Error;
+
+/// Delivers messages between the tabs running the same app.
+///
+/// Messages are strings, so apps are free to pick their own serialization
+/// format. A tab never receives its own messages.
+///
+/// ```rust no_run
+/// use ratzilla::sync::BroadcastSync;
+///
+/// let mut sync = BroadcastSync::new("counter").unwrap();
+/// sync.on_message(|message| {
+///     web_sys::console::log_1(&format!("other tab says {message}").into());
+/// });
+/// sync.post("42").unwrap();
+/// ```
+#[derive(Debug)]
+pub struct BroadcastSync {
+    /// The underlying channel.
+    channel: BroadcastChannel,
+    /// Message listeners, kept alive as long as the channel.
+    listeners: Vec<Closure<dyn FnMut(MessageEvent)>>,
+}
+
+impl BroadcastSync {
+    /// Constructs a new [`BroadcastSync`] using the channel with the given name.
+    ///
+    /// Tabs using the same name receive each other's messages.
+    pub fn new(name: &str) -> Result<Self, Error> {
+        Ok(Self {
+            channel: BroadcastChannel::new(name)?,
+            listeners: Vec::new(),
+        })
+    }
+
+    /// Returns the name of the channel.
+    pub fn name(&self) -> String {
+        self.channel.name()
+    }
+
+    /// Sends a message to the other tabs.
+    pub fn post(&self, message: &str) -> Result<(), Error> {
+        self.channel.post_message(&message.into())?;
+        Ok(())
+    }
+
+    /// Registers a callback that is called with every message sent by the
+    /// other tabs.
+    ///
+    /// Messages that are not strings are ignored.
+    pub fn on_message<F>(&mut self, mut callback: F)
+    where
+        F: FnMut(String) + 'static,
+    {
+        let closure = Closure::<dyn FnMut(_)>::new(move |event: MessageEvent| {
+            if let Some(message) = event.data().as_string() {
+                callback(message);
+            }
+        });
+        let _ = self
+            .channel
+            .add_event_listener_with_callback("message", closure.as_ref().unchecked_ref());
+        self.listeners.push(closure);
+    }
+}
+
+impl Drop for BroadcastSync {
+    fn drop(&mut self) {
+        self.channel.close();
+    }
+}
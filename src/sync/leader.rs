@@ -0,0 +1,368 @@
+use std::{cell::RefCell, rc::Rc};
+
+use web_sys::{
+    wasm_bindgen::{prelude::Closure, JsCast},
+    BroadcastChannel, MessageEvent,
+};
+
+use crate::{backend::utils::get_window, clock::now, error::Error, widgets::rng::Rng};
+
+/// Interval between two election rounds, in milliseconds.
+const TICK_INTERVAL_MS: i32 = 500;
+
+/// Time without a heartbeat after which the leader is considered gone, in milliseconds.
+const LEADER_TIMEOUT_MS: f64 = 2_000.0;
+
+/// Callback called when the leadership of this tab changes.
+type LeadershipCallback = Box<dyn FnMut(bool)>;
+
+/// A message exchanged between the tabs taking part in the election.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Message {
+    /// Sent periodically by the leader.
+    Heartbeat(u64),
+    /// Sent by a tab that wants to become the leader.
+    Claim(u64),
+    /// Sent by the leader when its tab is closed or hidden.
+    Resign(u64),
+}
+
+impl Message {
+    /// Encodes the message.
+    fn encode(&self) -> String {
+        match self {
+            Message::Heartbeat(id) => format!("heartbeat:{id}"),
+            Message::Claim(id) => format!("claim:{id}"),
+            Message::Resign(id) => format!("resign:{id}"),
+        }
+    }
+
+    /// Decodes a message.
+    fn decode(message: &str) -> Option<Self> {
+        let (kind, id) = message.split_once(':')?;
+        let id = id.parse().ok()?;
+        match kind {
+            "heartbeat" => Some(Message::Heartbeat(id)),
+            "claim" => Some(Message::Claim(id)),
+            "resign" => Some(Message::Resign(id)),
+            _ => None,
+        }
+    }
+}
+
+/// The election state of a tab.
+///
+/// When the leader disappears, the remaining tabs claim the leadership and
+/// the tab with the lowest id wins.
+#[derive(Debug)]
+struct Election {
+    /// Id of this tab.
+    id: u64,
+    /// Whether this tab is the leader.
+    leader: bool,
+    /// Timestamp of the last heartbeat from another leader, in milliseconds.
+    last_heartbeat: Option<f64>,
+    /// Timestamp at which this tab claimed the leadership, in milliseconds.
+    claimed_at: Option<f64>,
+}
+
+impl Election {
+    fn new(id: u64, now: f64) -> Self {
+        Self {
+            id,
+            leader: false,
+            // give an existing leader the chance to announce itself
+            last_heartbeat: Some(now),
+            claimed_at: None,
+        }
+    }
+
+    /// Advances the election, returning the messages to send.
+    fn tick(&mut self, now: f64) -> Option<Message> {
+        if self.leader {
+            return Some(Message::Heartbeat(self.id));
+        }
+        let leader_gone = self
+            .last_heartbeat
+            .is_none_or(|last| now - last > LEADER_TIMEOUT_MS);
+        if !leader_gone {
+            return None;
+        }
+        match self.claimed_at {
+            None => {
+                self.claimed_at = Some(now);
+                Some(Message::Claim(self.id))
+            }
+            // nobody with a lower id objected during a whole round
+            Some(_) => {
+                self.leader = true;
+                self.claimed_at = None;
+                Some(Message::Heartbeat(self.id))
+            }
+        }
+    }
+
+    /// Gives up the leadership, returning the message to send.
+    fn resign(&mut self) -> Option<Message> {
+        if !self.leader {
+            return None;
+        }
+        self.leader = false;
+        self.last_heartbeat = None;
+        Some(Message::Resign(self.id))
+    }
+
+    /// Handles a message from another tab, returning the message to answer with.
+    fn handle(&mut self, message: Message, now: f64) -> Option<Message> {
+        match message {
+            Message::Heartbeat(id) if id != self.id => {
+                if self.leader && id > self.id {
+                    // two leaders, the other one steps down on our heartbeat
+                    return Some(Message::Heartbeat(self.id));
+                }
+                self.leader = false;
+                self.claimed_at = None;
+                self.last_heartbeat = Some(now);
+                None
+            }
+            Message::Claim(id) if id != self.id => {
+                if self.leader {
+                    Some(Message::Heartbeat(self.id))
+                } else {
+                    if id < self.id {
+                        self.claimed_at = None;
+                        self.last_heartbeat = Some(now);
+                    }
+                    None
+                }
+            }
+            Message::Resign(id) if id != self.id => {
+                self.last_heartbeat = None;
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Shared state of the [`LeaderElection`].
+struct State {
+    /// The election.
+    election: Election,
+    /// Leadership change listeners.
+    listeners: Vec<LeadershipCallback>,
+}
+
+impl State {
+    /// Runs an election step, notifying the listeners if the leadership changed.
+    ///
+    /// The listeners are called outside of the borrow of the state, so they
+    /// can use the election, e.g. to register other listeners.
+    fn step(
+        state: &RefCell<Self>,
+        channel: &BroadcastChannel,
+        step: impl FnOnce(&mut Election) -> Option<Message>,
+    ) {
+        let (was_leader, is_leader) = {
+            let mut state = state.borrow_mut();
+            let was_leader = state.election.leader;
+            if let Some(message) = step(&mut state.election) {
+                let _ = channel.post_message(&message.encode().into());
+            }
+            (was_leader, state.election.leader)
+        };
+        if was_leader == is_leader {
+            return;
+        }
+        let mut listeners = std::mem::take(&mut state.borrow_mut().listeners);
+        listeners
+            .iter_mut()
+            .for_each(|listener| listener(is_leader));
+        let mut state = state.borrow_mut();
+        listeners.append(&mut state.listeners);
+        state.listeners = listeners;
+    }
+}
+
+/// Elects a single leader among the tabs running the same app.
+///
+/// The leader sends a heartbeat twice per second. When its tab is closed or
+/// hidden, it resigns and the remaining tabs elect a new leader right away;
+/// when it stops otherwise, they do within a few seconds.
+///
+/// ```rust no_run
+/// use ratzilla::sync::LeaderElection;
+///
+/// let election = LeaderElection::new("dashboard").unwrap();
+/// election.on_leadership_change(|leader| {
+///     if leader {
+///         // start polling the server and share the results with
+///         // `BroadcastSync`
+///     }
+/// });
+/// ```
+pub struct LeaderElection {
+    /// The election channel.
+    channel: BroadcastChannel,
+    /// Shared state.
+    state: Rc<RefCell<State>>,
+    /// Id of the tick interval.
+    interval: i32,
+    /// Message handler, kept alive as long as the election.
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    /// Tick handler, kept alive as long as the election.
+    _on_tick: Closure<dyn FnMut()>,
+    /// `pagehide` handler, removed when the election is dropped.
+    on_pagehide: Closure<dyn FnMut()>,
+}
+
+impl std::fmt::Debug for LeaderElection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LeaderElection")
+            .field("channel", &self.channel.name())
+            .field("leader", &self.is_leader())
+            .finish()
+    }
+}
+
+impl LeaderElection {
+    /// Constructs a new [`LeaderElection`] among the tabs using the given name.
+    pub fn new(name: &str) -> Result<Self, Error> {
+        let channel = BroadcastChannel::new(&format!("ratzilla-leader-{name}"))?;
+        let state = Rc::new(RefCell::new(State {
+            election: Election::new(Rng::from_entropy().next_u64(), now()),
+            listeners: Vec::new(),
+        }));
+
+        let on_message = Closure::<dyn FnMut(_)>::new({
+            let state = state.clone();
+            let channel = channel.clone();
+            move |event: MessageEvent| {
+                let Some(message) = event.data().as_string().and_then(|m| Message::decode(&m))
+                else {
+                    return;
+                };
+                State::step(&state, &channel, |election| election.handle(message, now()));
+            }
+        });
+        channel.add_event_listener_with_callback("message", on_message.as_ref().unchecked_ref())?;
+
+        let on_tick = Closure::<dyn FnMut()>::new({
+            let state = state.clone();
+            let channel = channel.clone();
+            move || State::step(&state, &channel, |election| election.tick(now()))
+        });
+        let window = get_window()?;
+        let interval = window.set_interval_with_callback_and_timeout_and_arguments_0(
+            on_tick.as_ref().unchecked_ref(),
+            TICK_INTERVAL_MS,
+        )?;
+
+        // the other tabs take over right away instead of waiting for the
+        // heartbeats to time out
+        let on_pagehide = Closure::<dyn FnMut()>::new({
+            let state = state.clone();
+            let channel = channel.clone();
+            move || State::step(&state, &channel, Election::resign)
+        });
+        window
+            .add_event_listener_with_callback("pagehide", on_pagehide.as_ref().unchecked_ref())?;
+
+        Ok(Self {
+            channel,
+            state,
+            interval,
+            _on_message: on_message,
+            _on_tick: on_tick,
+            on_pagehide,
+        })
+    }
+
+    /// Returns `true` if this tab is the leader.
+    pub fn is_leader(&self) -> bool {
+        self.state.borrow().election.leader
+    }
+
+    /// Registers a callback that is called with `true` when this tab becomes
+    /// the leader, and with `false` when it loses the leadership.
+    pub fn on_leadership_change<F>(&self, callback: F)
+    where
+        F: FnMut(bool) + 'static,
+    {
+        self.state.borrow_mut().listeners.push(Box::new(callback));
+    }
+}
+
+impl Drop for LeaderElection {
+    fn drop(&mut self) {
+        if let Ok(window) = get_window() {
+            window.clear_interval_with_handle(self.interval);
+            let _ = window.remove_event_listener_with_callback(
+                "pagehide",
+                self.on_pagehide.as_ref().unchecked_ref(),
+            );
+        }
+        if let Some(message) = self.state.borrow_mut().election.resign() {
+            let _ = self.channel.post_message(&message.encode().into());
+        }
+        self.channel.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_roundtrip() {
+        for message in [Message::Heartbeat(1), Message::Claim(2), Message::Resign(3)] {
+            assert_eq!(Message::decode(&message.encode()), Some(message));
+        }
+        assert_eq!(Message::decode("hello"), None);
+    }
+
+    #[test]
+    fn test_single_tab_becomes_leader() {
+        let mut election = Election::new(7, 0.0);
+        assert_eq!(election.tick(500.0), None);
+        assert_eq!(election.tick(2_500.0), Some(Message::Claim(7)));
+        assert_eq!(election.tick(3_000.0), Some(Message::Heartbeat(7)));
+        assert!(election.leader);
+    }
+
+    #[test]
+    fn test_lowest_id_wins() {
+        let mut low = Election::new(1, 0.0);
+        let mut high = Election::new(2, 0.0);
+        assert_eq!(high.tick(2_500.0), Some(Message::Claim(2)));
+        assert_eq!(low.tick(2_500.0), Some(Message::Claim(1)));
+        assert_eq!(low.handle(Message::Claim(2), 2_500.0), None);
+        assert_eq!(high.handle(Message::Claim(1), 2_500.0), None);
+
+        assert_eq!(low.tick(3_000.0), Some(Message::Heartbeat(1)));
+        assert_eq!(high.tick(3_000.0), None);
+        assert!(low.leader && !high.leader);
+    }
+
+    #[test]
+    fn test_resign_triggers_new_election() {
+        let mut election = Election::new(5, 0.0);
+        election.handle(Message::Heartbeat(1), 100.0);
+        assert_eq!(election.tick(600.0), None);
+        election.handle(Message::Resign(1), 700.0);
+        assert_eq!(election.tick(800.0), Some(Message::Claim(5)));
+    }
+
+    #[test]
+    fn test_resign() {
+        let mut election = Election::new(3, 0.0);
+        assert_eq!(election.resign(), None);
+        election.tick(2_500.0);
+        election.tick(3_000.0);
+        assert!(election.leader);
+        assert_eq!(election.resign(), Some(Message::Resign(3)));
+        assert!(!election.leader);
+        // a restored tab claims the leadership again
+        assert_eq!(election.tick(3_500.0), Some(Message::Claim(3)));
+    }
+}
@@ -0,0 +1,30 @@
+//! ## Multi-tab synchronization
+//!
+//! Helpers for apps running in several tabs of the same browser:
+//!
+//! - [`BroadcastSync`] delivers messages to the other tabs running the app,
+//!   e.g. to share state between several dashboards.
+//! - [`LeaderElection`] elects a single tab among them, e.g. to avoid
+//!   polling the same server from every open tab.
+//! - With the `shared-worker` feature, the `worker` module runs the app
+//!   core in a shared worker, with every tab acting as a thin renderer.
+//!
+//! Both are built on the [`BroadcastChannel`] API, which only reaches tabs of
+//! the same origin.
+//!
+//! [`BroadcastChannel`]: https://developer.mozilla.org/en-US/docs/Web/API/BroadcastChannel
+//! [`BroadcastSync`]: crate::sync::broadcast::BroadcastSync
+//! [`LeaderElection`]: crate::sync::leader::LeaderElection
+
+/// Message broadcasting.
+pub mod broadcast;
+
+/// Leader election.
+pub mod leader;
+
+/// SharedWorker-hosted app core.
+#[cfg(feature = "shared-worker")]
+pub mod worker;
+
+pub use broadcast::BroadcastSync;
+pub use leader::LeaderElection;
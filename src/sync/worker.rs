@@ -0,0 +1,487 @@
+//! Running the app core inside a [`SharedWorker`].
+//!
+//! The [`SharedWorkerHost`] runs in the worker: it owns the application
+//! state, renders into an in-memory terminal and broadcasts the resulting
+//! buffer to every connected tab. Each tab uses a [`SharedWorkerClient`] as a
+//! thin renderer, copying the latest buffer into its own terminal and
+//! forwarding its input events to the worker.
+//!
+//! Since there is a single worker per origin and name, all tabs share the
+//! same session, and network connections opened by the worker survive as
+//! long as one tab is open.
+//!
+//! A tab disconnects when its page is hidden for good or its client is
+//! dropped. A tab restored from the back/forward cache must connect again.
+//!
+//! [`SharedWorker`]: https://developer.mozilla.org/en-US/docs/Web/API/SharedWorker
+//! [`SharedWorkerClient`]: crate::sync::worker::SharedWorkerClient
+//! [`SharedWorkerHost`]: crate::sync::worker::SharedWorkerHost
+
+use std::{cell::RefCell, rc::Rc};
+
+use ratatui::{backend::TestBackend, buffer::Buffer, layout::Rect, Frame, Terminal};
+use web_sys::{
+    js_sys,
+    wasm_bindgen::{prelude::Closure, JsCast},
+    MessageEvent, MessagePort, SharedWorker, SharedWorkerGlobalScope,
+};
+
+use crate::{
+    backend::utils::get_window,
+    buffer_codec::{decode_buffer, encode_buffer, FIELD_SEPARATOR},
+    error::Error,
+    event::{KeyCode, KeyEvent},
+};
+
+/// Callback called with application messages.
+type MessageCallback = Box<dyn FnMut(String)>;
+
+/// Callback called with key events forwarded by the tabs.
+type KeyCallback = Box<dyn FnMut(KeyEvent)>;
+
+/// State shared by the tab side.
+#[derive(Default)]
+struct ClientState {
+    /// The latest buffer received from the worker.
+    buffer: Option<Buffer>,
+    /// Application message listeners.
+    listeners: Vec<MessageCallback>,
+}
+
+/// Connects a tab to the app core running in a [`SharedWorkerHost`].
+///
+/// ```rust no_run
+/// use std::rc::Rc;
+/// use ratzilla::{sync::worker::SharedWorkerClient, WebRenderer};
+/// # fn example(terminal: ratzilla::ratatui::Terminal<ratzilla::DomBackend>) {
+///
+/// let client = Rc::new(SharedWorkerClient::connect("./worker.js", "app").unwrap());
+/// terminal.on_key_event({
+///     let client = client.clone();
+///     move |event| client.send_key_event(&event)
+/// });
+/// terminal.draw_web(move |frame| client.render(frame));
+/// # }
+/// ```
+pub struct SharedWorkerClient {
+    /// The port connected to the worker.
+    port: MessagePort,
+    /// Shared state.
+    state: Rc<RefCell<ClientState>>,
+    /// The last size reported to the worker.
+    size: RefCell<Option<Rect>>,
+    /// Message handler, kept alive as long as the client.
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    /// `pagehide` handler, removed when the client is dropped.
+    on_pagehide: Closure<dyn FnMut()>,
+}
+
+impl std::fmt::Debug for SharedWorkerClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedWorkerClient")
+            .field("size", &self.size.borrow())
+            .finish()
+    }
+}
+
+impl SharedWorkerClient {
+    /// Connects to the shared worker with the given script URL and name,
+    /// starting it if no other tab did.
+    pub fn connect(script_url: &str, name: &str) -> Result<Self, Error> {
+        let worker = SharedWorker::new_with_str(script_url, name)?;
+        let port = worker.port();
+        let state = Rc::new(RefCell::new(ClientState::default()));
+
+        let on_message = Closure::<dyn FnMut(_)>::new({
+            let state = state.clone();
+            move |event: MessageEvent| {
+                let Some(message) = event.data().as_string() else {
+                    return;
+                };
+                if let Some(frame) = message.strip_prefix("frame:") {
+                    if let Some(buffer) = decode_buffer(frame) {
+                        state.borrow_mut().buffer = Some(buffer);
+                    }
+                } else if let Some(payload) = message.strip_prefix("app:") {
+                    let mut listeners = std::mem::take(&mut state.borrow_mut().listeners);
+                    listeners
+                        .iter_mut()
+                        .for_each(|listener| listener(payload.to_string()));
+                    let mut state = state.borrow_mut();
+                    listeners.append(&mut state.listeners);
+                    state.listeners = listeners;
+                }
+            }
+        });
+        port.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        port.start();
+
+        // the worker stops sending frames to closed tabs
+        let on_pagehide = Closure::<dyn FnMut()>::new({
+            let port = port.clone();
+            move || {
+                let _ = port.post_message(&"close".into());
+            }
+        });
+        get_window()?
+            .add_event_listener_with_callback("pagehide", on_pagehide.as_ref().unchecked_ref())?;
+
+        Ok(Self {
+            port,
+            state,
+            size: RefCell::new(None),
+            _on_message: on_message,
+            on_pagehide,
+        })
+    }
+
+    /// Forwards a key event to the worker.
+    pub fn send_key_event(&self, event: &KeyEvent) {
+        self.send(&format!("key:{}", encode_key_event(event)));
+    }
+
+    /// Sends an application message to the worker.
+    pub fn post(&self, message: &str) {
+        self.send(&format!("app:{message}"));
+    }
+
+    /// Registers a callback that is called with the application messages
+    /// sent by the worker.
+    pub fn on_message<F>(&self, callback: F)
+    where
+        F: FnMut(String) + 'static,
+    {
+        self.state.borrow_mut().listeners.push(Box::new(callback));
+    }
+
+    /// Renders the latest buffer received from the worker into the frame.
+    ///
+    /// This also reports the size of the frame to the worker when it changes.
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        if *self.size.borrow() != Some(area) {
+            self.size.replace(Some(area));
+            self.send(&format!("resize:{},{}", area.width, area.height));
+        }
+
+        let state = self.state.borrow();
+        let Some(remote) = &state.buffer else {
+            return;
+        };
+        let buf = frame.buffer_mut();
+        let area = area.intersection(remote.area);
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                buf[(x, y)] = remote[(x, y)].clone();
+            }
+        }
+    }
+
+    /// Sends a message to the worker.
+    fn send(&self, message: &str) {
+        let _ = self.port.post_message(&message.into());
+    }
+}
+
+impl Drop for SharedWorkerClient {
+    fn drop(&mut self) {
+        self.send("close");
+        if let Ok(window) = get_window() {
+            let _ = window.remove_event_listener_with_callback(
+                "pagehide",
+                self.on_pagehide.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
+
+/// A tab connected to the worker.
+struct Connection {
+    /// The port connected to the tab.
+    port: MessagePort,
+    /// Message handler of the port, dropped when the tab disconnects.
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+}
+
+/// State shared by the worker side.
+struct HostState {
+    /// The connected tabs.
+    connections: Vec<Connection>,
+    /// The last broadcast frame.
+    last_frame: Option<String>,
+    /// Key event listeners.
+    key_listeners: Vec<KeyCallback>,
+    /// Application message listeners.
+    listeners: Vec<MessageCallback>,
+}
+
+/// Hosts the app core inside a [`SharedWorker`].
+///
+/// The app renders with [`SharedWorkerHost::draw`] whenever its state
+/// changes; the resulting buffer is sent to all connected tabs. The terminal
+/// takes the size of the tab that resized last.
+///
+/// Must be constructed inside a shared worker.
+///
+/// [`SharedWorker`]: https://developer.mozilla.org/en-US/docs/Web/API/SharedWorker
+pub struct SharedWorkerHost {
+    /// Shared state.
+    state: Rc<RefCell<HostState>>,
+    /// The in-memory terminal the app renders into.
+    ///
+    /// Kept out of the shared state so the render callback can use the host.
+    terminal: Rc<RefCell<Terminal<TestBackend>>>,
+    /// Connection handler, kept alive as long as the host.
+    _on_connect: Closure<dyn FnMut(MessageEvent)>,
+}
+
+impl std::fmt::Debug for SharedWorkerHost {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedWorkerHost")
+            .field("connections", &self.state.borrow().connections.len())
+            .finish()
+    }
+}
+
+impl SharedWorkerHost {
+    /// Constructs a new [`SharedWorkerHost`] and starts accepting connections.
+    pub fn new() -> Result<Self, Error> {
+        let scope: SharedWorkerGlobalScope = js_sys::global()
+            .dyn_into()
+            .map_err(|_| Error::UnableToRetrieveComponent("SharedWorkerGlobalScope"))?;
+        let terminal = Terminal::new(TestBackend::new(80, 24))
+            .map_err(|e| Error::UnableToInitializeTerminal(e.to_string()))?;
+        let terminal = Rc::new(RefCell::new(terminal));
+        let state = Rc::new(RefCell::new(HostState {
+            connections: Vec::new(),
+            last_frame: None,
+            key_listeners: Vec::new(),
+            listeners: Vec::new(),
+        }));
+
+        let on_connect = Closure::<dyn FnMut(_)>::new({
+            let state = state.clone();
+            let terminal = terminal.clone();
+            move |event: MessageEvent| {
+                let Ok(port) = event.ports().get(0).dyn_into::<MessagePort>() else {
+                    return;
+                };
+                Self::accept(&state, &terminal, port);
+            }
+        });
+        scope.set_onconnect(Some(on_connect.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            state,
+            terminal,
+            _on_connect: on_connect,
+        })
+    }
+
+    /// Registers a callback that is called with the key events of all tabs.
+    pub fn on_key_event<F>(&self, callback: F)
+    where
+        F: FnMut(KeyEvent) + 'static,
+    {
+        self.state
+            .borrow_mut()
+            .key_listeners
+            .push(Box::new(callback));
+    }
+
+    /// Registers a callback that is called with the application messages
+    /// sent by the tabs.
+    pub fn on_message<F>(&self, callback: F)
+    where
+        F: FnMut(String) + 'static,
+    {
+        self.state.borrow_mut().listeners.push(Box::new(callback));
+    }
+
+    /// Sends an application message to all tabs.
+    pub fn broadcast(&self, message: &str) {
+        let message = format!("app:{message}").into();
+        for connection in &self.state.borrow().connections {
+            let _ = connection.port.post_message(&message);
+        }
+    }
+
+    /// Renders a frame and sends it to all tabs, unless it didn't change.
+    pub fn draw<F>(&self, render_callback: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Frame),
+    {
+        let mut terminal = self.terminal.borrow_mut();
+        terminal
+            .draw(render_callback)
+            .map_err(|e| Error::UnableToInitializeTerminal(e.to_string()))?;
+        let frame = encode_buffer(terminal.backend().buffer());
+        let mut state = self.state.borrow_mut();
+        if state.last_frame.as_ref() != Some(&frame) {
+            let message = format!("frame:{frame}").into();
+            for connection in &state.connections {
+                let _ = connection.port.post_message(&message);
+            }
+            state.last_frame = Some(frame);
+        }
+        Ok(())
+    }
+
+    /// Accepts a new tab.
+    fn accept(
+        state: &Rc<RefCell<HostState>>,
+        terminal: &Rc<RefCell<Terminal<TestBackend>>>,
+        port: MessagePort,
+    ) {
+        let on_message = Closure::<dyn FnMut(_)>::new({
+            let state = state.clone();
+            let terminal = terminal.clone();
+            let port = port.clone();
+            move |event: MessageEvent| {
+                if let Some(message) = event.data().as_string() {
+                    Self::handle_message(&state, &terminal, &port, &message);
+                }
+            }
+        });
+        port.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        port.start();
+
+        let mut state = state.borrow_mut();
+        // bring the new tab up to date
+        if let Some(frame) = &state.last_frame {
+            let _ = port.post_message(&format!("frame:{frame}").into());
+        }
+        state.connections.push(Connection {
+            port,
+            _on_message: on_message,
+        });
+    }
+
+    /// Handles a message from the tab connected to the given port.
+    fn handle_message(
+        state: &Rc<RefCell<HostState>>,
+        terminal: &Rc<RefCell<Terminal<TestBackend>>>,
+        port: &MessagePort,
+        message: &str,
+    ) {
+        if message == "close" {
+            let connection = {
+                let mut state = state.borrow_mut();
+                let Some(index) = state.connections.iter().position(|c| &c.port == port) else {
+                    return;
+                };
+                state.connections.remove(index)
+            };
+            connection.port.set_onmessage(None);
+            connection.port.close();
+            // the handler running this is only freed once it returns
+            drop(connection);
+        } else if let Some(size) = message.strip_prefix("resize:") {
+            let Some((width, height)) = size.split_once(',') else {
+                return;
+            };
+            if let (Ok(width), Ok(height)) = (width.parse(), height.parse()) {
+                terminal.borrow_mut().backend_mut().resize(width, height);
+            }
+        } else if let Some(event) = message.strip_prefix("key:").and_then(decode_key_event) {
+            let mut listeners = std::mem::take(&mut state.borrow_mut().key_listeners);
+            listeners
+                .iter_mut()
+                .for_each(|listener| listener(event.clone()));
+            let mut state = state.borrow_mut();
+            listeners.append(&mut state.key_listeners);
+            state.key_listeners = listeners;
+        } else if let Some(payload) = message.strip_prefix("app:") {
+            let mut listeners = std::mem::take(&mut state.borrow_mut().listeners);
+            listeners
+                .iter_mut()
+                .for_each(|listener| listener(payload.to_string()));
+            let mut state = state.borrow_mut();
+            listeners.append(&mut state.listeners);
+            state.listeners = listeners;
+        }
+    }
+}
+
+/// Encodes a key event.
+fn encode_key_event(event: &KeyEvent) -> String {
+    let code = match &event.code {
+        KeyCode::Char(c) => format!("c{c}"),
+        KeyCode::F(n) => format!("f{n}"),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Delete => "Delete".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Unidentified => "Unidentified".to_string(),
+    };
+    let flags = event.ctrl as u8
+        | (event.alt as u8) << 1
+        | (event.shift as u8) << 2
+        | (event.meta as u8) << 3
+        | (event.repeat as u8) << 4;
+    format!("{flags}{FIELD_SEPARATOR}{code}")
+}
+
+/// Decodes a key event encoded with [`encode_key_event`].
+fn decode_key_event(encoded: &str) -> Option<KeyEvent> {
+    let (flags, code) = encoded.split_once(FIELD_SEPARATOR)?;
+    let flags: u8 = flags.parse().ok()?;
+    let code = match code {
+        "Backspace" => KeyCode::Backspace,
+        "Enter" => KeyCode::Enter,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Tab" => KeyCode::Tab,
+        "Delete" => KeyCode::Delete,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Esc" => KeyCode::Esc,
+        "Unidentified" => KeyCode::Unidentified,
+        code => match code.split_at_checked(1)? {
+            ("c", c) => KeyCode::Char(c.chars().next()?),
+            ("f", n) => KeyCode::F(n.parse().ok()?),
+            _ => return None,
+        },
+    };
+    Some(KeyEvent {
+        code,
+        ctrl: flags & 1 != 0,
+        alt: flags & 2 != 0,
+        shift: flags & 4 != 0,
+        meta: flags & 8 != 0,
+        repeat: flags & 16 != 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_event_roundtrip() {
+        for code in [KeyCode::Char('x'), KeyCode::F(11), KeyCode::PageDown] {
+            let event = KeyEvent {
+                code,
+                ctrl: true,
+                alt: false,
+                shift: true,
+                meta: true,
+                repeat: true,
+            };
+            assert_eq!(decode_key_event(&encode_key_event(&event)), Some(event));
+        }
+    }
+}
@@ -0,0 +1,492 @@
+//! ## Background tasks
+//!
+//! [`TaskScheduler`] spawns long-running async jobs (network requests, file
+//! processing, awaiting a Web Worker, ...) on the browser event loop, and
+//! reports their progress and completion as [`TaskEvent`]s that the app
+//! drains from its render loop.
+//!
+//! Jobs can be cancelled through their [`TaskHandle`] or the
+//! [`CancellationToken`] passed to them. A cancelled job is dropped at its
+//! next `.await` point, so it doesn't need to check for cancellation itself.
+//!
+//! The tasks can be shown with the [`TaskList`] and [`ProgressBar`] widgets.
+//!
+//! ```rust no_run
+//! use ratzilla::{tasks::{TaskEvent, TaskScheduler}, WebRenderer};
+//! # fn example(terminal: ratzilla::ratatui::Terminal<ratzilla::DomBackend>) {
+//!
+//! let scheduler = TaskScheduler::new();
+//! let handle = scheduler.spawn("download", |ctx| async move {
+//!     for chunk in 0..10 {
+//!         // fetch the chunk...
+//!         ctx.report_progress(chunk as f32 / 10.0);
+//!     }
+//!     Ok::<(), String>(())
+//! });
+//!
+//! terminal.draw_web(move |frame| {
+//!     for event in scheduler.poll_events() {
+//!         if let TaskEvent::Completed { id } = event {
+//!             // ...
+//!         }
+//!     }
+//!     // render widgets...
+//! });
+//! # }
+//! ```
+//!
+//! [`CancellationToken`]: crate::tasks::CancellationToken
+//! [`TaskEvent`]: crate::tasks::TaskEvent
+//! [`TaskHandle`]: crate::tasks::TaskHandle
+//! [`TaskScheduler`]: crate::tasks::TaskScheduler
+//! [`TaskList`]: crate::widgets::TaskList
+//! [`ProgressBar`]: crate::widgets::ProgressBar
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fmt::Display,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// Identifier of a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TaskId(u64);
+
+/// Status of a task.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskStatus {
+    /// The task is running.
+    Running,
+    /// The task completed successfully.
+    Completed,
+    /// The task failed with the given error.
+    Failed(String),
+    /// The task was cancelled.
+    Cancelled,
+}
+
+impl TaskStatus {
+    /// Returns `true` if the task is no longer running.
+    pub fn is_finished(&self) -> bool {
+        *self != TaskStatus::Running
+    }
+}
+
+/// A snapshot of a task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskInfo {
+    /// The task identifier.
+    pub id: TaskId,
+    /// The task name.
+    pub name: String,
+    /// The progress of the task, from `0.0` to `1.0`, if reported.
+    pub progress: Option<f32>,
+    /// The last status message of the task, if any.
+    pub message: Option<String>,
+    /// The status of the task.
+    pub status: TaskStatus,
+}
+
+/// An event reported by a task.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskEvent {
+    /// A task was spawned.
+    Started {
+        /// The task identifier.
+        id: TaskId,
+        /// The task name.
+        name: String,
+    },
+    /// A task reported its progress.
+    Progress {
+        /// The task identifier.
+        id: TaskId,
+        /// The progress, from `0.0` to `1.0`.
+        progress: f32,
+    },
+    /// A task reported a status message.
+    Message {
+        /// The task identifier.
+        id: TaskId,
+        /// The message.
+        message: String,
+    },
+    /// A task completed successfully.
+    Completed {
+        /// The task identifier.
+        id: TaskId,
+    },
+    /// A task failed.
+    Failed {
+        /// The task identifier.
+        id: TaskId,
+        /// The error message.
+        error: String,
+    },
+    /// A task was cancelled.
+    Cancelled {
+        /// The task identifier.
+        id: TaskId,
+    },
+}
+
+/// State of a [`CancellationToken`].
+#[derive(Debug, Default)]
+struct TokenState {
+    /// Whether the token was cancelled.
+    cancelled: bool,
+    /// Waker of the task waiting on the token.
+    waker: Option<Waker>,
+}
+
+/// A token used to cancel a task.
+///
+/// Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Rc<RefCell<TokenState>>,
+}
+
+impl CancellationToken {
+    /// Constructs a new [`CancellationToken`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token.
+    pub fn cancel(&self) {
+        let waker = {
+            let mut state = self.state.borrow_mut();
+            state.cancelled = true;
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Returns `true` if the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.borrow().cancelled
+    }
+}
+
+/// Shared scheduler state.
+#[derive(Debug, Default)]
+struct SchedulerState {
+    /// Identifier of the next task.
+    next_id: u64,
+    /// Known tasks.
+    tasks: Vec<TaskInfo>,
+    /// Events not yet polled by the app.
+    events: VecDeque<TaskEvent>,
+}
+
+impl SchedulerState {
+    /// Records an event and updates the matching task.
+    fn push(&mut self, event: TaskEvent) {
+        match &event {
+            TaskEvent::Started { id, name } => self.tasks.push(TaskInfo {
+                id: *id,
+                name: name.clone(),
+                progress: None,
+                message: None,
+                status: TaskStatus::Running,
+            }),
+            TaskEvent::Progress { id, progress } => {
+                self.update(*id, |t| t.progress = Some(*progress))
+            }
+            TaskEvent::Message { id, message } => {
+                self.update(*id, |t| t.message = Some(message.clone()))
+            }
+            TaskEvent::Completed { id } => self.update(*id, |t| {
+                t.status = TaskStatus::Completed;
+                t.progress = Some(1.0);
+            }),
+            TaskEvent::Failed { id, error } => {
+                self.update(*id, |t| t.status = TaskStatus::Failed(error.clone()))
+            }
+            TaskEvent::Cancelled { id } => self.update(*id, |t| t.status = TaskStatus::Cancelled),
+        }
+        self.events.push_back(event);
+    }
+
+    /// Updates the task with the given identifier.
+    fn update(&mut self, id: TaskId, f: impl FnOnce(&mut TaskInfo)) {
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
+            f(task);
+        }
+    }
+}
+
+/// Context passed to a running task.
+#[derive(Debug, Clone)]
+pub struct TaskContext {
+    /// The task identifier.
+    id: TaskId,
+    /// The cancellation token of the task.
+    token: CancellationToken,
+    /// Scheduler state.
+    state: Rc<RefCell<SchedulerState>>,
+}
+
+impl TaskContext {
+    /// Returns the identifier of the task.
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// Returns the cancellation token of the task.
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Returns `true` if the task was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Reports the progress of the task, from `0.0` to `1.0`.
+    pub fn report_progress(&self, progress: f32) {
+        self.state.borrow_mut().push(TaskEvent::Progress {
+            id: self.id,
+            progress: progress.clamp(0.0, 1.0),
+        });
+    }
+
+    /// Reports a status message, e.g. `"Downloading chunk 3/10"`.
+    pub fn report_message(&self, message: &str) {
+        self.state.borrow_mut().push(TaskEvent::Message {
+            id: self.id,
+            message: message.to_string(),
+        });
+    }
+}
+
+/// A handle to a spawned task.
+#[derive(Debug, Clone)]
+pub struct TaskHandle {
+    /// The task identifier.
+    id: TaskId,
+    /// The cancellation token of the task.
+    token: CancellationToken,
+}
+
+impl TaskHandle {
+    /// Returns the identifier of the task.
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// Cancels the task.
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+}
+
+/// Drops the inner future when the token is cancelled.
+struct Cancellable<F> {
+    future: Pin<Box<F>>,
+    token: CancellationToken,
+}
+
+impl<F: Future> Future for Cancellable<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.token.is_cancelled() {
+            return Poll::Ready(None);
+        }
+        self.token.state.borrow_mut().waker = Some(cx.waker().clone());
+        self.future.as_mut().poll(cx).map(Some)
+    }
+}
+
+/// Spawns async tasks and collects their events.
+///
+/// The scheduler is a cheap handle: clones share the same tasks.
+#[derive(Debug, Clone, Default)]
+pub struct TaskScheduler {
+    state: Rc<RefCell<SchedulerState>>,
+}
+
+impl TaskScheduler {
+    /// Constructs a new [`TaskScheduler`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns a task on the browser event loop.
+    ///
+    /// The closure receives the [`TaskContext`] of the task, used to report
+    /// its progress, and returns the future to run. Returning an error marks
+    /// the task as failed.
+    pub fn spawn<F, Fut, E>(&self, name: &str, task: F) -> TaskHandle
+    where
+        F: FnOnce(TaskContext) -> Fut + 'static,
+        Fut: Future<Output = Result<(), E>> + 'static,
+        E: Display + 'static,
+    {
+        let (handle, future) = self.prepare(name, task);
+        wasm_bindgen_futures::spawn_local(future);
+        handle
+    }
+
+    /// Registers a task and wraps its future with the event reporting.
+    pub(crate) fn prepare<F, Fut, E>(
+        &self,
+        name: &str,
+        task: F,
+    ) -> (TaskHandle, impl Future<Output = ()>)
+    where
+        F: FnOnce(TaskContext) -> Fut,
+        Fut: Future<Output = Result<(), E>> + 'static,
+        E: Display,
+    {
+        let id = {
+            let mut state = self.state.borrow_mut();
+            let id = TaskId(state.next_id);
+            state.next_id += 1;
+            state.push(TaskEvent::Started {
+                id,
+                name: name.to_string(),
+            });
+            id
+        };
+        let token = CancellationToken::new();
+        let context = TaskContext {
+            id,
+            token: token.clone(),
+            state: self.state.clone(),
+        };
+        let future = Cancellable {
+            future: Box::pin(task(context)),
+            token: token.clone(),
+        };
+
+        let state = self.state.clone();
+        let run = async move {
+            let event = match future.await {
+                Some(Ok(())) => TaskEvent::Completed { id },
+                Some(Err(e)) => TaskEvent::Failed {
+                    id,
+                    error: e.to_string(),
+                },
+                None => TaskEvent::Cancelled { id },
+            };
+            state.borrow_mut().push(event);
+        };
+        (TaskHandle { id, token }, run)
+    }
+
+    /// Returns the events reported since the last call.
+    pub fn poll_events(&self) -> Vec<TaskEvent> {
+        self.state.borrow_mut().events.drain(..).collect()
+    }
+
+    /// Returns a snapshot of all known tasks, in spawn order.
+    pub fn tasks(&self) -> Vec<TaskInfo> {
+        self.state.borrow().tasks.clone()
+    }
+
+    /// Returns a snapshot of the given task.
+    pub fn task(&self, id: TaskId) -> Option<TaskInfo> {
+        self.state
+            .borrow()
+            .tasks
+            .iter()
+            .find(|t| t.id == id)
+            .cloned()
+    }
+
+    /// Returns `true` if any task is running.
+    pub fn is_busy(&self) -> bool {
+        self.state
+            .borrow()
+            .tasks
+            .iter()
+            .any(|t| !t.status.is_finished())
+    }
+
+    /// Forgets the tasks that are no longer running.
+    pub fn clear_finished(&self) {
+        self.state
+            .borrow_mut()
+            .tasks
+            .retain(|t| !t.status.is_finished());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Wake;
+
+    use super::*;
+
+    /// A waker that does nothing.
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: std::sync::Arc<Self>) {}
+    }
+
+    /// Polls a future once.
+    fn poll_once<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+        let waker = Waker::from(std::sync::Arc::new(NoopWaker));
+        future.poll(&mut Context::from_waker(&waker))
+    }
+
+    #[test]
+    fn test_task_events() {
+        let scheduler = TaskScheduler::new();
+        let (handle, future) = scheduler.prepare("job", |ctx| async move {
+            ctx.report_progress(0.5);
+            Ok::<(), String>(())
+        });
+        let mut future = Box::pin(future);
+        assert!(poll_once(future.as_mut()).is_ready());
+
+        assert_eq!(
+            scheduler.poll_events(),
+            vec![
+                TaskEvent::Started {
+                    id: handle.id(),
+                    name: "job".to_string()
+                },
+                TaskEvent::Progress {
+                    id: handle.id(),
+                    progress: 0.5
+                },
+                TaskEvent::Completed { id: handle.id() },
+            ]
+        );
+        assert_eq!(
+            scheduler.task(handle.id()).map(|t| t.status),
+            Some(TaskStatus::Completed)
+        );
+    }
+
+    #[test]
+    fn test_cancelled_task() {
+        let scheduler = TaskScheduler::new();
+        let (handle, future) = scheduler.prepare("job", |_| async {
+            std::future::pending::<()>().await;
+            Ok::<(), String>(())
+        });
+        let mut future = Box::pin(future);
+        assert!(poll_once(future.as_mut()).is_pending());
+        assert!(scheduler.is_busy());
+
+        handle.cancel();
+        assert!(poll_once(future.as_mut()).is_ready());
+        assert_eq!(
+            scheduler.task(handle.id()).map(|t| t.status),
+            Some(TaskStatus::Cancelled)
+        );
+        assert!(!scheduler.is_busy());
+    }
+}
@@ -0,0 +1,172 @@
+//! ## Teardown
+//!
+//! Single page apps built with frameworks such as Yew, Leptos or Dioxus
+//! mount and unmount components, and the terminal with them. [`destroy`]
+//! stops the render loop started by
+//! [`draw_web`](crate::WebRenderer::draw_web), removes the event listeners
+//! registered by the terminal and drops the terminal, whose backend then
+//! deletes the elements it created:
+//!
+//! ```rust no_run
+//! use ratzilla::{teardown, WebRenderer};
+//! # fn example(terminal: ratzilla::ratatui::Terminal<ratzilla::DomBackend>) {
+//!
+//! terminal.on_key_event(|_| {});
+//! terminal.draw_web(|frame| { /* ... */ });
+//! // ... later, when the component is unmounted
+//! teardown::destroy();
+//! # }
+//! ```
+//!
+//! A new terminal can be drawn after [`destroy`], e.g. when the component is
+//! mounted again.
+//!
+//! The [`Router`](crate::router::Router), the session and the
+//! [frame cache](crate::frame_cache) stop listening to the page too: create a
+//! new router, or enable them again, for the new terminal. Objects owning
+//! their listeners, such as
+//! [`LeaderElection`](crate::sync::LeaderElection) and
+//! [`BroadcastSync`](crate::sync::BroadcastSync), remove them when dropped.
+//!
+//! [`destroy`]: crate::teardown::destroy
+
+use std::cell::RefCell;
+
+use web_sys::{js_sys::Function, EventTarget};
+
+use crate::error::Error;
+
+/// State of the teardown.
+#[derive(Default)]
+struct Teardown {
+    /// Registered event listeners: target, event type and listener.
+    listeners: Vec<(EventTarget, &'static str, Function)>,
+    /// Hooks called on [`destroy`].
+    hooks: Vec<Box<dyn FnOnce()>>,
+}
+
+thread_local! {
+    static TEARDOWN: RefCell<Teardown> = RefCell::new(Teardown::default());
+}
+
+/// Adds an event listener to the given target, removed on [`destroy`].
+///
+/// The listener is usually a closure converted with
+/// [`Closure::into_js_value`](web_sys::wasm_bindgen::closure::Closure::into_js_value),
+/// which frees it once removed.
+pub(crate) fn add_event_listener(
+    target: &EventTarget,
+    event: &'static str,
+    listener: &Function,
+) -> Result<(), Error> {
+    target.add_event_listener_with_callback(event, listener)?;
+    TEARDOWN.with(|state| {
+        state
+            .borrow_mut()
+            .listeners
+            .push((target.clone(), event, listener.clone()))
+    });
+    Ok(())
+}
+
+/// Calls the given hook on [`destroy`], e.g. to reset the state of a module.
+pub(crate) fn on_destroy<F>(hook: F)
+where
+    F: FnOnce() + 'static,
+{
+    TEARDOWN.with(|state| state.borrow_mut().hooks.push(Box::new(hook)));
+}
+
+/// Destroys the terminal.
+///
+/// This cancels the render loop, removes the event listeners of the
+/// terminal, e.g. the ones of [`on_key_event`](crate::WebRenderer::on_key_event),
+/// and drops the terminal. Dropping a backend removes its grid or canvas
+/// from the page.
+///
+/// It's safe to call this from the render callback or an event handler: the
+/// terminal is dropped once the current frame is done.
+pub fn destroy() {
+    let (listeners, hooks) = TEARDOWN.with(|state| {
+        let mut state = state.borrow_mut();
+        (
+            std::mem::take(&mut state.listeners),
+            std::mem::take(&mut state.hooks),
+        )
+    });
+    for (target, event, listener) in listeners {
+        let _ = target.remove_event_listener_with_callback(event, &listener);
+    }
+    // outside of the borrow, hooks may register listeners of a new terminal
+    for hook in hooks {
+        hook();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use wasm_bindgen_test::*;
+    use web_sys::{
+        wasm_bindgen::{prelude::Closure, JsCast},
+        Event,
+    };
+
+    use super::*;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn test_hooks_run_once_in_order() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        for i in 0..3 {
+            let calls = calls.clone();
+            on_destroy(move || calls.borrow_mut().push(i));
+        }
+
+        destroy();
+        assert_eq!(*calls.borrow(), vec![0, 1, 2]);
+        destroy();
+        assert_eq!(*calls.borrow(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_hooks_registered_by_hooks_run_on_next_destroy() {
+        let calls = Rc::new(Cell::new(0));
+        on_destroy({
+            let calls = calls.clone();
+            move || {
+                calls.set(calls.get() + 1);
+                on_destroy(move || calls.set(calls.get() + 10));
+            }
+        });
+
+        destroy();
+        assert_eq!(calls.get(), 1);
+        destroy();
+        assert_eq!(calls.get(), 11);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_destroy_removes_listeners() {
+        let target = EventTarget::new().expect("failed to create the target");
+        let calls = Rc::new(Cell::new(0));
+        let listener = Closure::<dyn FnMut()>::new({
+            let calls = calls.clone();
+            move || calls.set(calls.get() + 1)
+        })
+        .into_js_value();
+        add_event_listener(&target, "ping", listener.unchecked_ref())
+            .expect("failed to add the listener");
+
+        let event = Event::new("ping").expect("failed to create the event");
+        target.dispatch_event(&event).expect("failed to dispatch");
+        destroy();
+        target.dispatch_event(&event).expect("failed to dispatch");
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -0,0 +1,434 @@
+//! ## Text layout
+//!
+//! [`TextLayout`] wraps styled text to a width, for widgets showing rich
+//! text such as Markdown, chat messages, code or logs:
+//!
+//! - Lines are wrapped at word boundaries, and words longer than the width
+//!   are broken between graphemes, so that combining characters and emoji
+//!   sequences are never split.
+//! - Wide characters (e.g. CJK) that don't fit at the end of a line move to
+//!   the next one instead of overflowing it.
+//! - Where words can break is configured with a [`WordBreak`] policy, e.g.
+//!   to break long URLs anywhere, and soft hyphens (`U+00AD`) are shown as
+//!   a hyphen when a line wraps at them.
+//! - Tabs are expanded to the next tab stop.
+//! - Wrapped lines can be indented, e.g. to align them after a list bullet.
+//! - The style of every span is kept.
+//!
+//! ```rust
+//! use ratzilla::{ratatui::text::Text, text_layout::TextLayout};
+//!
+//! let text = Text::from("- a list item long enough to be wrapped");
+//! let lines = TextLayout::new(16).hanging_indent(2).layout(&text);
+//! assert_eq!(lines[0].to_string(), "- a list item");
+//! assert_eq!(lines[1].to_string(), "  long enough to");
+//! ```
+//!
+//! [`TextLayout`]: crate::text_layout::TextLayout
+//! [`WordBreak`]: crate::text_layout::WordBreak
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::Style,
+    text::{Line, Span, Text},
+    widgets::Widget,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Default number of columns between tab stops.
+const DEFAULT_TAB_WIDTH: u16 = 4;
+
+/// The soft hyphen, an invisible break opportunity.
+const SOFT_HYPHEN: &str = "\u{ad}";
+
+/// Where lines can break inside words, like the CSS `word-break` property.
+///
+/// Lines can always break at whitespace and soft hyphens, and words longer
+/// than a line are always broken between graphemes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WordBreak {
+    /// Lines also break after hyphens and between CJK characters.
+    #[default]
+    Normal,
+    /// Lines break between any two graphemes, e.g. for URLs, hashes or
+    /// other long tokens.
+    BreakAll,
+    /// Lines don't break between CJK characters, only at whitespace and
+    /// hyphens, e.g. for Korean text that separates words with spaces.
+    KeepAll,
+}
+
+/// A grapheme of a line being laid out.
+#[derive(Debug, Clone)]
+struct Grapheme {
+    /// The grapheme.
+    symbol: String,
+    /// Style of the grapheme.
+    style: Style,
+    /// Width of the grapheme, in cells.
+    width: u16,
+}
+
+impl Grapheme {
+    /// Returns `true` if the grapheme is a whitespace, where lines can wrap.
+    fn is_whitespace(&self) -> bool {
+        self.symbol.chars().all(char::is_whitespace)
+    }
+
+    /// Returns `true` if the grapheme is a soft hyphen.
+    fn is_soft_hyphen(&self) -> bool {
+        self.symbol == SOFT_HYPHEN
+    }
+
+    /// Returns `true` if lines can break after the grapheme, inside a word.
+    fn is_hyphen(&self) -> bool {
+        self.symbol == "-" || self.is_soft_hyphen()
+    }
+
+    /// Returns `true` if the grapheme is a CJK character.
+    fn is_cjk(&self) -> bool {
+        self.symbol.chars().next().is_some_and(|c| {
+            matches!(
+                c as u32,
+                0x2E80..=0x9FFF
+                    | 0xAC00..=0xD7AF
+                    | 0xF900..=0xFAFF
+                    | 0xFF00..=0xFFEF
+                    | 0x20000..=0x2FFFF
+            )
+        })
+    }
+}
+
+/// Lays out styled text in lines of a given width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextLayout {
+    /// Width of the lines, in cells.
+    width: u16,
+    /// Alignment of the lines without an alignment of their own.
+    alignment: Alignment,
+    /// Number of columns between tab stops.
+    tab_width: u16,
+    /// Indentation of the wrapped lines, in cells.
+    hanging_indent: u16,
+    /// Whether whitespace at the start of wrapped lines is removed.
+    trim: bool,
+    /// Where lines can break inside words.
+    word_break: WordBreak,
+}
+
+impl TextLayout {
+    /// Constructs a new [`TextLayout`] wrapping lines to the given width.
+    pub fn new(width: u16) -> Self {
+        Self {
+            width,
+            alignment: Alignment::Left,
+            tab_width: DEFAULT_TAB_WIDTH,
+            hanging_indent: 0,
+            trim: true,
+            word_break: WordBreak::Normal,
+        }
+    }
+
+    /// Sets the alignment of the lines without an alignment of their own.
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Sets the number of columns between tab stops.
+    pub fn tab_width(mut self, tab_width: u16) -> Self {
+        self.tab_width = tab_width.max(1);
+        self
+    }
+
+    /// Sets the indentation of the wrapped lines, after the first line of
+    /// every source line.
+    ///
+    /// The indentation is limited to half the width.
+    pub fn hanging_indent(mut self, indent: u16) -> Self {
+        self.hanging_indent = indent;
+        self
+    }
+
+    /// Sets whether whitespace at the start of wrapped lines is removed,
+    /// which is the default.
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Sets where lines can break inside words.
+    pub fn word_break(mut self, word_break: WordBreak) -> Self {
+        self.word_break = word_break;
+        self
+    }
+
+    /// Wraps the text, returning its lines.
+    ///
+    /// Every line has the alignment of its source line, or the one of the
+    /// layout.
+    pub fn layout(&self, text: &Text) -> Vec<Line<'static>> {
+        text.lines
+            .iter()
+            .flat_map(|line| {
+                let style = text.style.patch(line.style);
+                let alignment = line.alignment.or(text.alignment).unwrap_or(self.alignment);
+                self.wrap(self.graphemes(line, style))
+                    .into_iter()
+                    .map(move |line| line.alignment(alignment))
+            })
+            .collect()
+    }
+
+    /// Returns the number of lines of the wrapped text.
+    pub fn line_count(&self, text: &Text) -> usize {
+        self.layout(text).len()
+    }
+
+    /// Wraps the text and renders it in the area, clipping the lines below
+    /// it.
+    pub fn render(&self, text: &Text, area: Rect, buf: &mut Buffer) {
+        let rows = area.rows();
+        for (line, row) in self.layout(text).iter().zip(rows) {
+            line.render(row, buf);
+        }
+    }
+
+    /// Returns the graphemes of the line, with expanded tabs.
+    fn graphemes(&self, line: &Line, style: Style) -> Vec<Grapheme> {
+        let mut graphemes = Vec::new();
+        let mut column = 0;
+        for span in &line.spans {
+            let style = style.patch(span.style);
+            // tabs are control characters, which aren't graphemes of spans
+            for (index, piece) in span.content.split('\t').enumerate() {
+                if index > 0 {
+                    let spaces = self.tab_width - column % self.tab_width;
+                    graphemes.extend((0..spaces).map(|_| Grapheme {
+                        symbol: String::from(" "),
+                        style,
+                        width: 1,
+                    }));
+                    column += spaces;
+                }
+                for grapheme in Span::raw(piece).styled_graphemes(style) {
+                    let width = match grapheme.symbol {
+                        // only shown when a line wraps at it
+                        SOFT_HYPHEN => 0,
+                        symbol => symbol.width() as u16,
+                    };
+                    column += width;
+                    graphemes.push(Grapheme {
+                        symbol: grapheme.symbol.to_string(),
+                        style: grapheme.style,
+                        width,
+                    });
+                }
+            }
+        }
+        graphemes
+    }
+
+    /// Wraps the graphemes of a source line.
+    fn wrap(&self, graphemes: Vec<Grapheme>) -> Vec<Line<'static>> {
+        let width = self.width.max(1);
+        let indent = self.hanging_indent.min(width / 2);
+        let mut lines = Vec::new();
+        let mut current: Vec<Grapheme> = Vec::new();
+        let mut current_width = 0;
+        let mut available = width;
+        for word in words(graphemes, self.word_break) {
+            // the width without the trailing whitespace, which can overflow,
+            // and with the hyphen shown if the line wraps at a soft hyphen
+            let word_width: u16 = word
+                .iter()
+                .filter(|grapheme| !grapheme.is_whitespace())
+                .map(|grapheme| grapheme.width)
+                .sum::<u16>()
+                + u16::from(word.last().is_some_and(Grapheme::is_soft_hyphen));
+            if current_width + word_width > available && !current.is_empty() {
+                lines.push(finish(&mut current, lines.is_empty(), indent, true));
+                current_width = 0;
+                available = width - indent;
+            }
+            for grapheme in word {
+                if current.is_empty() && self.trim && !lines.is_empty() && grapheme.is_whitespace()
+                {
+                    continue;
+                }
+                if current_width + grapheme.width > available
+                    && !current.is_empty()
+                    && !grapheme.is_whitespace()
+                {
+                    // breaking a word longer than the line
+                    lines.push(finish(&mut current, lines.is_empty(), indent, true));
+                    current_width = 0;
+                    available = width - indent;
+                }
+                current_width += grapheme.width;
+                current.push(grapheme);
+            }
+        }
+        if !current.is_empty() || lines.is_empty() {
+            lines.push(finish(&mut current, lines.is_empty(), indent, false));
+        }
+        lines
+    }
+}
+
+/// Splits graphemes into words, each followed by its trailing whitespace.
+///
+/// A word is the part of the text between two break opportunities.
+fn words(graphemes: Vec<Grapheme>, word_break: WordBreak) -> Vec<Vec<Grapheme>> {
+    let mut words: Vec<Vec<Grapheme>> = Vec::new();
+    for grapheme in graphemes {
+        let breaks = match words.last().and_then(|word| word.last()) {
+            None => true,
+            Some(_) if grapheme.is_whitespace() => false,
+            Some(previous) => {
+                previous.is_whitespace()
+                    || word_break == WordBreak::BreakAll
+                    || previous.is_hyphen()
+                    || (word_break == WordBreak::Normal && (previous.is_cjk() || grapheme.is_cjk()))
+            }
+        };
+        match words.last_mut() {
+            Some(word) if !breaks => word.push(grapheme),
+            _ => words.push(vec![grapheme]),
+        }
+    }
+    words
+}
+
+/// Builds a line from the graphemes, merging graphemes of the same style
+/// into spans, and drops its trailing whitespace.
+///
+/// The soft hyphen ending a wrapped line is shown as a hyphen, the others
+/// are removed.
+fn finish(graphemes: &mut Vec<Grapheme>, first: bool, indent: u16, wrapped: bool) -> Line<'static> {
+    while graphemes.last().is_some_and(Grapheme::is_whitespace) {
+        graphemes.pop();
+    }
+    if wrapped {
+        if let Some(last) = graphemes.last_mut().filter(|last| last.is_soft_hyphen()) {
+            last.symbol = String::from("-");
+            last.width = 1;
+        }
+    }
+    graphemes.retain(|grapheme| !grapheme.is_soft_hyphen());
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    if !first && indent > 0 {
+        spans.push(Span::raw(" ".repeat(indent as usize)));
+    }
+    let mut content = String::new();
+    let mut style = None;
+    for grapheme in graphemes.drain(..) {
+        if style.is_some_and(|style| style != grapheme.style) {
+            spans.push(Span::styled(
+                std::mem::take(&mut content),
+                style.unwrap_or_default(),
+            ));
+        }
+        style = Some(grapheme.style);
+        content.push_str(&grapheme.symbol);
+    }
+    if let Some(style) = style {
+        spans.push(Span::styled(content, style));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Stylize;
+
+    use super::*;
+
+    /// Returns the content of the lines.
+    fn contents(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_wrap_words() {
+        let text = Text::from("the quick brown fox\n\njumps");
+        let lines = TextLayout::new(10).layout(&text);
+        assert_eq!(contents(&lines), ["the quick", "brown fox", "", "jumps"]);
+    }
+
+    #[test]
+    fn test_break_long_words() {
+        let text = Text::from("abcdefgh ij");
+        let lines = TextLayout::new(3).layout(&text);
+        assert_eq!(contents(&lines), ["abc", "def", "gh", "ij"]);
+    }
+
+    #[test]
+    fn test_wide_characters() {
+        // a wide character never straddles the edge
+        let text = Text::from("a日本語");
+        let lines = TextLayout::new(4).layout(&text);
+        assert_eq!(contents(&lines), ["a日", "本語"]);
+        // combining characters stay with their base
+        let lines = TextLayout::new(2).layout(&Text::from("e\u{301}e\u{301}e\u{301}"));
+        assert_eq!(contents(&lines), ["e\u{301}e\u{301}", "e\u{301}"]);
+    }
+
+    #[test]
+    fn test_tabs_and_indent() {
+        let lines = TextLayout::new(20).tab_width(4).layout(&Text::from("a\tb"));
+        assert_eq!(contents(&lines), ["a   b"]);
+
+        let lines = TextLayout::new(8)
+            .hanging_indent(2)
+            .layout(&Text::from("* one two three"));
+        assert_eq!(contents(&lines), ["* one", "  two", "  three"]);
+    }
+
+    #[test]
+    fn test_word_break() {
+        let url = Text::from("see https://example.com/a/long/path");
+        let lines = TextLayout::new(12).layout(&url);
+        assert_eq!(
+            contents(&lines),
+            ["see", "https://exam", "ple.com/a/lo", "ng/path"]
+        );
+        let lines = TextLayout::new(12)
+            .word_break(WordBreak::BreakAll)
+            .layout(&url);
+        assert_eq!(
+            contents(&lines),
+            ["see https://", "example.com/", "a/long/path"]
+        );
+
+        let cjk = Text::from("한국어 문장");
+        let lines = TextLayout::new(8).layout(&cjk);
+        assert_eq!(contents(&lines), ["한국어", "문장"]);
+        let lines = TextLayout::new(5).layout(&cjk);
+        assert_eq!(contents(&lines), ["한국", "어 문", "장"]);
+        let lines = TextLayout::new(5)
+            .word_break(WordBreak::KeepAll)
+            .layout(&cjk);
+        assert_eq!(contents(&lines), ["한국", "어", "문장"]);
+    }
+
+    #[test]
+    fn test_hyphens() {
+        let text = Text::from("hyphen\u{ad}ation well-known");
+        let lines = TextLayout::new(8).layout(&text);
+        assert_eq!(contents(&lines), ["hyphen-", "ation", "well-", "known"]);
+        let lines = TextLayout::new(30).layout(&text);
+        assert_eq!(contents(&lines), ["hyphenation well-known"]);
+    }
+
+    #[test]
+    fn test_styles_and_alignment() {
+        let text = Text::from(Line::from(vec!["red ".red(), "blue words".blue()]).centered());
+        let lines = TextLayout::new(7).layout(&text);
+        assert_eq!(lines[0].spans, [Span::from("red").red()]);
+        assert_eq!(lines[1].spans, [Span::from("blue").blue()]);
+        assert_eq!(lines[1].alignment, Some(Alignment::Center));
+    }
+}
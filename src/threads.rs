@@ -0,0 +1,136 @@
+//! ## Background computation
+//!
+//! [`ComputeQueue`] runs heavy work (simulations, parsing, searching large
+//! datasets, ...) away from the render loop and delivers the results back
+//! to it, so the app stays responsive.
+//!
+//! With the `threads` feature, work runs on a [rayon] thread pool backed by
+//! Web Workers and `SharedArrayBuffer`. Without it, work runs inline when it
+//! is spawned, so apps can use the same code whether threads are available or
+//! not.
+//!
+//! Enabling threads requires:
+//!
+//! - a nightly toolchain building the standard library with atomics, e.g.
+//!   `RUSTFLAGS="-C target-feature=+atomics,+bulk-memory"` and
+//!   `-Z build-std=panic_abort,std`,
+//! - serving the page with the `Cross-Origin-Opener-Policy: same-origin` and
+//!   `Cross-Origin-Embedder-Policy: require-corp` headers,
+//! - calling the exported `initThreadPool` function from JavaScript before
+//!   spawning work, e.g. `await initThreadPool(navigator.hardwareConcurrency)`.
+//!
+//! ```rust no_run
+//! use std::{cell::RefCell, rc::Rc};
+//! use ratzilla::{threads::ComputeQueue, WebRenderer};
+//! # fn example(terminal: ratzilla::ratatui::Terminal<ratzilla::DomBackend>) {
+//!
+//! let queue = Rc::new(RefCell::new(ComputeQueue::new()));
+//! queue.borrow_mut().spawn(|| (1..=20u64).product::<u64>());
+//!
+//! terminal.draw_web(move |frame| {
+//!     for result in queue.borrow_mut().poll() {
+//!         // update the app state with the result
+//!     }
+//!     // render widgets...
+//! });
+//! # }
+//! ```
+//!
+//! [rayon]: https://github.com/rayon-rs/rayon
+//! [`ComputeQueue`]: crate::threads::ComputeQueue
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+#[cfg(feature = "threads")]
+pub use rayon;
+#[cfg(feature = "threads")]
+pub use wasm_bindgen_rayon::init_thread_pool;
+
+/// Runs work in the background and collects the results.
+#[derive(Debug)]
+pub struct ComputeQueue<T> {
+    /// Sends results from the workers.
+    sender: Sender<T>,
+    /// Receives results on the render thread.
+    receiver: Receiver<T>,
+    /// Number of spawned jobs that haven't been polled yet.
+    pending: usize,
+}
+
+impl<T: Send + 'static> Default for ComputeQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send + 'static> ComputeQueue<T> {
+    /// Constructs a new, empty [`ComputeQueue`].
+    pub fn new() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            sender,
+            receiver,
+            pending: 0,
+        }
+    }
+
+    /// Spawns a job.
+    ///
+    /// With the `threads` feature the job runs on the thread pool, otherwise
+    /// it runs immediately. In both cases, its result is returned by the
+    /// next calls to [`ComputeQueue::poll`].
+    pub fn spawn<F>(&mut self, job: F)
+    where
+        F: FnOnce() -> T + Send + 'static,
+    {
+        self.pending += 1;
+        let sender = self.sender.clone();
+        let run = move || {
+            // the queue may have been dropped in the meantime
+            let _ = sender.send(job());
+        };
+        #[cfg(feature = "threads")]
+        rayon::spawn(run);
+        #[cfg(not(feature = "threads"))]
+        run();
+    }
+
+    /// Returns the results of the jobs that finished since the last call.
+    ///
+    /// This never blocks, so it is safe to call it on every frame.
+    pub fn poll(&mut self) -> Vec<T> {
+        let results: Vec<T> = self.receiver.try_iter().collect();
+        self.pending = self.pending.saturating_sub(results.len());
+        results
+    }
+
+    /// Returns the number of jobs whose results haven't been polled yet.
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Returns `true` if all results have been polled.
+    pub fn is_idle(&self) -> bool {
+        self.pending == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_queue() {
+        let mut queue = ComputeQueue::new();
+        queue.spawn(|| 1 + 1);
+        queue.spawn(|| 2 * 3);
+
+        let mut results = Vec::new();
+        while !queue.is_idle() {
+            results.extend(queue.poll());
+        }
+        results.sort();
+        assert_eq!(results, vec![2, 6]);
+        assert!(queue.poll().is_empty());
+    }
+}
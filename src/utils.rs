@@ -1,7 +1,31 @@
+/// Compression helpers.
+pub mod compression;
+
+/// Cookie access.
+pub mod cookies;
+
+/// Hashing and Web Crypto wrappers.
+pub mod crypto;
+
+/// Fuzzy matching.
+pub mod fuzzy;
+
+/// Sanitization of untrusted text.
+pub mod sanitize;
+
+/// Spell checking.
+pub mod spellcheck;
+
+/// Web storage access.
+pub mod storage;
+
 use ratatui::layout::Size;
 
 use crate::{
-    backend::utils::{get_raw_screen_size, get_raw_window_size},
+    backend::{
+        metrics::page_metrics,
+        utils::{get_raw_screen_size, get_raw_window_size},
+    },
     error::Error,
 };
 
@@ -43,17 +67,21 @@ pub fn is_mobile() -> bool {
 }
 
 /// Returns the number of characters that can fit in the window (viewport of the browser or terminal).
+///
+/// The size of a character is measured with the styles of the page, see
+/// [`CellMetrics::measure`](crate::CellMetrics::measure).
 pub fn get_window_size() -> Size {
     let (w, h) = get_raw_window_size();
-    // TODO: These are mildly magical numbers... make them more precise
-    (w / 10, h / 20).into()
+    page_metrics().grid_size(w as f64, h as f64)
 }
 
 /// Returns the number of characters that can fit in the screen (entire physical display).
+///
+/// The size of a character is measured with the styles of the page, see
+/// [`CellMetrics::measure`](crate::CellMetrics::measure).
 pub fn get_screen_size() -> Size {
     let (w, h) = get_raw_screen_size();
-    // TODO: These are mildly magical numbers... make them more precise
-    (w as u16 / 10, h as u16 / 19).into()
+    page_metrics().grid_size(w as f64, h as f64)
 }
 
 /// Calls a global JavaScript function by name, with a custom `this` context and an arbitrary number of arguments.
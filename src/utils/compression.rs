@@ -0,0 +1,226 @@
+//! ## Compression
+//!
+//! Helpers to (de)compress payloads before sending them over the network or
+//! writing them to storage.
+//!
+//! The async functions use the browser's native [`CompressionStream`] and
+//! [`DecompressionStream`] when available, and fall back to a pure Rust
+//! implementation otherwise. The `_sync` variants always use the Rust
+//! implementation, which is handy outside of async contexts and produces
+//! the same output formats.
+//!
+//! Brotli is not offered since browsers only support it for decoding HTTP
+//! responses, not through these streams.
+//!
+//! ```rust no_run
+//! use ratzilla::utils::compression::{compress, decompress, CompressionFormat};
+//! # async fn example() -> Result<(), ratzilla::error::Error> {
+//!
+//! let payload = b"hello hello hello hello";
+//! let compressed = compress(payload, CompressionFormat::Gzip).await?;
+//! let restored = decompress(&compressed, CompressionFormat::Gzip).await?;
+//! assert_eq!(restored, payload);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`CompressionStream`]: https://developer.mozilla.org/en-US/docs/Web/API/CompressionStream
+//! [`DecompressionStream`]: https://developer.mozilla.org/en-US/docs/Web/API/DecompressionStream
+
+use miniz_oxide::{deflate, inflate};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    js_sys::{self, Array, Function, Reflect, Uint8Array},
+    wasm_bindgen::JsCast,
+    Blob, ReadableWritablePair, Response,
+};
+
+use crate::error::Error;
+
+/// Compression level used by the Rust implementation.
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// A compression format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompressionFormat {
+    /// The gzip format (RFC 1952).
+    #[default]
+    Gzip,
+    /// The zlib format (RFC 1950), called `deflate` by the browser.
+    Deflate,
+    /// Raw deflate data (RFC 1951), without header or checksum.
+    DeflateRaw,
+}
+
+impl CompressionFormat {
+    /// Returns the name of the format for the browser streams.
+    fn as_str(self) -> &'static str {
+        match self {
+            CompressionFormat::Gzip => "gzip",
+            CompressionFormat::Deflate => "deflate",
+            CompressionFormat::DeflateRaw => "deflate-raw",
+        }
+    }
+}
+
+/// Compresses data, using the native `CompressionStream` if available.
+pub async fn compress(data: &[u8], format: CompressionFormat) -> Result<Vec<u8>, Error> {
+    if !has_native_streams() {
+        return Ok(compress_sync(data, format));
+    }
+    let pair = native_stream("CompressionStream", format)?;
+    pipe_through(data, &pair).await
+}
+
+/// Decompresses data, using the native `DecompressionStream` if available.
+pub async fn decompress(data: &[u8], format: CompressionFormat) -> Result<Vec<u8>, Error> {
+    if !has_native_streams() {
+        return decompress_sync(data, format);
+    }
+    let pair = native_stream("DecompressionStream", format)?;
+    pipe_through(data, &pair).await
+}
+
+/// Compresses data using the Rust implementation.
+pub fn compress_sync(data: &[u8], format: CompressionFormat) -> Vec<u8> {
+    match format {
+        CompressionFormat::Gzip => {
+            let mut out = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+            out.extend(deflate::compress_to_vec(data, COMPRESSION_LEVEL));
+            out.extend(crc32(data).to_le_bytes());
+            out.extend((data.len() as u32).to_le_bytes());
+            out
+        }
+        CompressionFormat::Deflate => deflate::compress_to_vec_zlib(data, COMPRESSION_LEVEL),
+        CompressionFormat::DeflateRaw => deflate::compress_to_vec(data, COMPRESSION_LEVEL),
+    }
+}
+
+/// Decompresses data using the Rust implementation.
+pub fn decompress_sync(data: &[u8], format: CompressionFormat) -> Result<Vec<u8>, Error> {
+    let invalid = |reason: &str| Error::Compression(reason.to_string());
+    match format {
+        CompressionFormat::Gzip => {
+            let body = gzip_body(data).ok_or_else(|| invalid("invalid gzip header"))?;
+            let (deflated, trailer) = body.split_at(body.len() - 8);
+            let out = inflate::decompress_to_vec(deflated)
+                .map_err(|e| invalid(&format!("{:?}", e.status)))?;
+            let expected = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+            if crc32(&out) != expected {
+                return Err(invalid("gzip checksum mismatch"));
+            }
+            Ok(out)
+        }
+        CompressionFormat::Deflate => {
+            inflate::decompress_to_vec_zlib(data).map_err(|e| invalid(&format!("{:?}", e.status)))
+        }
+        CompressionFormat::DeflateRaw => {
+            inflate::decompress_to_vec(data).map_err(|e| invalid(&format!("{:?}", e.status)))
+        }
+    }
+}
+
+/// Returns `true` if the browser supports compression streams.
+fn has_native_streams() -> bool {
+    Reflect::has(&js_sys::global(), &"CompressionStream".into()).unwrap_or(false)
+}
+
+/// Constructs a native `CompressionStream` or `DecompressionStream`.
+///
+/// The streams are constructed dynamically since their `web-sys` bindings
+/// are behind `web_sys_unstable_apis`.
+fn native_stream(name: &str, format: CompressionFormat) -> Result<ReadableWritablePair, Error> {
+    let constructor: Function = Reflect::get(&js_sys::global(), &name.into())?.dyn_into()?;
+    let stream = Reflect::construct(&constructor, &Array::of1(&format.as_str().into()))?;
+    Ok(stream.unchecked_into())
+}
+
+/// Pipes data through a transform stream and collects the output.
+async fn pipe_through(data: &[u8], pair: &ReadableWritablePair) -> Result<Vec<u8>, Error> {
+    let parts = Array::of1(&Uint8Array::from(data));
+    let blob = Blob::new_with_u8_array_sequence(&parts)?;
+    let stream = blob.stream().pipe_through(pair);
+    let response = Response::new_with_opt_readable_stream(Some(&stream))?;
+    let buffer = JsFuture::from(response.array_buffer()?).await?;
+    Ok(Uint8Array::new(&buffer).to_vec())
+}
+
+/// Returns the deflate data and trailer of a gzip member, skipping its header.
+fn gzip_body(data: &[u8]) -> Option<&[u8]> {
+    const FHCRC: u8 = 0x02;
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+    const FCOMMENT: u8 = 0x10;
+
+    if data.len() < 18 || data[0..3] != [0x1f, 0x8b, 8] {
+        return None;
+    }
+    let flags = data[3];
+    let mut pos = 10;
+    if flags & FEXTRA != 0 {
+        let len = u16::from_le_bytes([*data.get(pos)?, *data.get(pos + 1)?]) as usize;
+        pos += 2 + len;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            pos += data.get(pos..)?.iter().position(|&b| b == 0)? + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        pos += 2;
+    }
+    let body = data.get(pos..)?;
+    (body.len() >= 8).then_some(body)
+}
+
+/// Computes the CRC-32 checksum used by gzip and PNG.
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let data = b"ratzilla ratzilla ratzilla ratzilla".repeat(10);
+        for format in [
+            CompressionFormat::Gzip,
+            CompressionFormat::Deflate,
+            CompressionFormat::DeflateRaw,
+        ] {
+            let compressed = compress_sync(&data, format);
+            assert!(compressed.len() < data.len());
+            assert_eq!(
+                decompress_sync(&compressed, format).expect("valid data"),
+                data
+            );
+        }
+    }
+
+    #[test]
+    fn test_gzip_rejects_corrupted_data() {
+        let mut compressed = compress_sync(b"hello", CompressionFormat::Gzip);
+        let len = compressed.len();
+        compressed[len - 5] ^= 0xff;
+        assert!(decompress_sync(&compressed, CompressionFormat::Gzip).is_err());
+        assert!(decompress_sync(b"nope", CompressionFormat::Gzip).is_err());
+    }
+}
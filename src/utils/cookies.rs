@@ -0,0 +1,246 @@
+//! ## Cookies
+//!
+//! Typed access to the cookies of the document, e.g. to interoperate with
+//! session cookies set by the server.
+//!
+//! Names and values are percent-encoded when written and decoded when read,
+//! so they can contain any character. Cookies set by the server with the
+//! `HttpOnly` attribute are not visible from the app.
+//!
+//! ```rust no_run
+//! use std::time::Duration;
+//! use ratzilla::utils::cookies::{get_cookie, set_cookie, CookieOptions, SameSite};
+//! # fn example() -> Result<(), ratzilla::error::Error> {
+//!
+//! set_cookie(
+//!     "theme",
+//!     "dark",
+//!     &CookieOptions::new()
+//!         .max_age(Duration::from_secs(60 * 60 * 24 * 365))
+//!         .same_site(SameSite::Lax),
+//! )?;
+//! let visits: Option<u32> = get_cookie("visits")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use web_sys::{wasm_bindgen::JsCast, HtmlDocument};
+
+use crate::{backend::utils::get_document, error::Error};
+
+/// The `SameSite` attribute of a cookie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    /// Only sent with same-site requests.
+    Strict,
+    /// Also sent when navigating to the site from another site.
+    Lax,
+    /// Sent with all requests. Requires [`CookieOptions::secure`].
+    None,
+}
+
+/// Attributes of a cookie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CookieOptions {
+    /// The path the cookie is visible to.
+    path: Option<String>,
+    /// The domain the cookie is visible to.
+    domain: Option<String>,
+    /// Lifetime of the cookie. Session cookie if not set.
+    max_age: Option<Duration>,
+    /// Whether the cookie is only sent over HTTPS.
+    secure: bool,
+    /// The `SameSite` attribute.
+    same_site: Option<SameSite>,
+}
+
+impl Default for CookieOptions {
+    fn default() -> Self {
+        Self {
+            path: Some("/".to_string()),
+            domain: None,
+            max_age: None,
+            secure: false,
+            same_site: None,
+        }
+    }
+}
+
+impl CookieOptions {
+    /// Constructs a new [`CookieOptions`] for a session cookie visible to the whole site.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the path the cookie is visible to. Defaults to `/`.
+    pub fn path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+
+    /// Sets the domain the cookie is visible to, including its subdomains.
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.domain = Some(domain.to_string());
+        self
+    }
+
+    /// Sets the lifetime of the cookie.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Only sends the cookie over HTTPS.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Sets the `SameSite` attribute.
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Formats a cookie with these options, as assigned to `document.cookie`.
+    fn format(&self, name: &str, value: &str) -> String {
+        let mut cookie = format!("{}={}", encode(name), encode(value));
+        if let Some(path) = &self.path {
+            cookie.push_str(&format!("; path={path}"));
+        }
+        if let Some(domain) = &self.domain {
+            cookie.push_str(&format!("; domain={domain}"));
+        }
+        if let Some(max_age) = self.max_age {
+            cookie.push_str(&format!("; max-age={}", max_age.as_secs()));
+        }
+        if self.secure {
+            cookie.push_str("; secure");
+        }
+        match self.same_site {
+            Some(SameSite::Strict) => cookie.push_str("; samesite=strict"),
+            Some(SameSite::Lax) => cookie.push_str("; samesite=lax"),
+            Some(SameSite::None) => cookie.push_str("; samesite=none"),
+            None => {}
+        }
+        cookie
+    }
+}
+
+/// Returns all cookies visible to the document.
+pub fn cookies() -> Result<Vec<(String, String)>, Error> {
+    Ok(parse_cookies(&document()?.cookie()?))
+}
+
+/// Returns the value of a cookie, parsed into the given type.
+///
+/// Returns `None` if the cookie doesn't exist or can't be parsed.
+pub fn get_cookie<T: FromStr>(name: &str) -> Result<Option<T>, Error> {
+    Ok(cookies()?
+        .into_iter()
+        .find(|(n, _)| n == name)
+        .and_then(|(_, value)| value.parse().ok()))
+}
+
+/// Sets a cookie.
+pub fn set_cookie<T: Display>(name: &str, value: T, options: &CookieOptions) -> Result<(), Error> {
+    document()?.set_cookie(&options.format(name, &value.to_string()))?;
+    Ok(())
+}
+
+/// Deletes a cookie.
+///
+/// The path and domain of the options must match the ones the cookie was
+/// set with.
+pub fn delete_cookie(name: &str, options: &CookieOptions) -> Result<(), Error> {
+    let options = options.clone().max_age(Duration::ZERO);
+    document()?.set_cookie(&options.format(name, ""))?;
+    Ok(())
+}
+
+/// Returns the document as a [`HtmlDocument`].
+fn document() -> Result<HtmlDocument, Error> {
+    get_document()?
+        .dyn_into()
+        .map_err(|_| Error::UnableToRetrieveDocument)
+}
+
+/// Parses the value of `document.cookie`.
+fn parse_cookies(cookies: &str) -> Vec<(String, String)> {
+    cookies
+        .split(';')
+        .filter_map(|cookie| {
+            let (name, value) = cookie.split_once('=')?;
+            Some((decode(name.trim()), decode(value.trim())))
+        })
+        .collect()
+}
+
+/// Percent-encodes the characters that are not allowed in cookies.
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Decodes a percent-encoded value, keeping invalid sequences as is.
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        let options = CookieOptions::new()
+            .max_age(Duration::from_secs(60))
+            .secure(true)
+            .same_site(SameSite::Strict);
+        assert_eq!(
+            options.format("a b", "ü;"),
+            "a%20b=%C3%BC%3B; path=/; max-age=60; secure; samesite=strict"
+        );
+    }
+
+    #[test]
+    fn test_parse_cookies() {
+        assert_eq!(
+            parse_cookies("a=1; b%20c=%C3%BC; broken; d=%zz"),
+            vec![
+                ("a".to_string(), "1".to_string()),
+                ("b c".to_string(), "ü".to_string()),
+                ("d".to_string(), "%zz".to_string()),
+            ]
+        );
+        assert!(parse_cookies("").is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+//! ## Cryptography
+//!
+//! Wrappers around the [Web Crypto API] for hashing, message authentication
+//! and random byte generation, e.g. for auth tokens, cache keys or content
+//! hashes.
+//!
+//! The functions use the `crypto` object of the global scope, so they work in
+//! workers too.
+//!
+//! ```rust no_run
+//! use ratzilla::utils::crypto::{hmac_sha256, random_bytes, sha256, to_hex};
+//! # async fn example() -> Result<(), ratzilla::error::Error> {
+//!
+//! let digest = sha256(b"hello").await?;
+//! let key = random_bytes(32)?;
+//! let signature = hmac_sha256(&key, b"message").await?;
+//! web_sys::console::log_1(&to_hex(&digest).into());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [Web Crypto API]: https://developer.mozilla.org/en-US/docs/Web/API/Web_Crypto_API
+
+use std::fmt::Write;
+
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    js_sys::{self, Array, ArrayBuffer, Object, Reflect, Uint8Array},
+    wasm_bindgen::{JsCast, JsValue},
+    Crypto, CryptoKey, SubtleCrypto,
+};
+
+use crate::error::Error;
+
+/// Maximum number of bytes `getRandomValues` fills in a single call.
+const MAX_RANDOM_CHUNK: usize = 65_536;
+
+/// Fills a new buffer with cryptographically secure random bytes.
+pub fn random_bytes(len: usize) -> Result<Vec<u8>, Error> {
+    let crypto = crypto()?;
+    let mut bytes = vec![0; len];
+    for chunk in bytes.chunks_mut(MAX_RANDOM_CHUNK) {
+        crypto.get_random_values_with_u8_array(chunk)?;
+    }
+    Ok(bytes)
+}
+
+/// Computes the SHA-256 digest of the data.
+pub async fn sha256(data: &[u8]) -> Result<[u8; 32], Error> {
+    let promise =
+        subtle()?.digest_with_str_and_buffer_source("SHA-256", &Uint8Array::from(data))?;
+    digest_from(JsFuture::from(promise).await?)
+}
+
+/// Computes the HMAC-SHA-256 of the data with the given key.
+pub async fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<[u8; 32], Error> {
+    let key = import_hmac_key(key, "sign").await?;
+    let promise =
+        subtle()?.sign_with_str_and_buffer_source("HMAC", &key, &Uint8Array::from(data))?;
+    digest_from(JsFuture::from(promise).await?)
+}
+
+/// Verifies an HMAC-SHA-256 signature in constant time.
+pub async fn hmac_sha256_verify(key: &[u8], data: &[u8], signature: &[u8]) -> Result<bool, Error> {
+    let key = import_hmac_key(key, "verify").await?;
+    let promise = subtle()?.verify_with_str_and_buffer_source_and_buffer_source(
+        "HMAC",
+        &key,
+        &Uint8Array::from(signature),
+        &Uint8Array::from(data),
+    )?;
+    Ok(JsFuture::from(promise).await?.is_truthy())
+}
+
+/// Encodes bytes as a lowercase hexadecimal string.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// Returns the `crypto` object of the global scope.
+fn crypto() -> Result<Crypto, Error> {
+    Reflect::get(&js_sys::global(), &"crypto".into())?
+        .dyn_into()
+        .map_err(|_| Error::UnableToRetrieveComponent("Crypto"))
+}
+
+/// Returns the `SubtleCrypto` interface.
+///
+/// It is only available in secure contexts (HTTPS or localhost).
+fn subtle() -> Result<SubtleCrypto, Error> {
+    let subtle = crypto()?.subtle();
+    if subtle.is_undefined() {
+        return Err(Error::UnableToRetrieveComponent("SubtleCrypto"));
+    }
+    Ok(subtle)
+}
+
+/// Imports a raw HMAC-SHA-256 key for the given usage.
+async fn import_hmac_key(key: &[u8], usage: &str) -> Result<CryptoKey, Error> {
+    let algorithm = Object::new();
+    Reflect::set(&algorithm, &"name".into(), &"HMAC".into())?;
+    Reflect::set(&algorithm, &"hash".into(), &"SHA-256".into())?;
+    let promise = subtle()?.import_key_with_object(
+        "raw",
+        &Uint8Array::from(key),
+        &algorithm,
+        false,
+        &Array::of1(&usage.into()),
+    )?;
+    JsFuture::from(promise)
+        .await?
+        .dyn_into()
+        .map_err(|_| Error::UnableToRetrieveComponent("CryptoKey"))
+}
+
+/// Converts the `ArrayBuffer` returned by a digest into bytes.
+fn digest_from(value: JsValue) -> Result<[u8; 32], Error> {
+    let buffer: ArrayBuffer = value.dyn_into()?;
+    let mut digest = [0; 32];
+    Uint8Array::new(&buffer).copy_to(&mut digest);
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_hex() {
+        assert_eq!(to_hex(&[0x00, 0x0f, 0xa5, 0xff]), "000fa5ff");
+        assert_eq!(to_hex(&[]), "");
+    }
+}
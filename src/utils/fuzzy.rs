@@ -0,0 +1,311 @@
+//! ## Fuzzy matching
+//!
+//! fzf-like fuzzy matching: a pattern matches a candidate if its characters
+//! appear in order in the candidate, e.g. `fbr` matches `foo_bar`. Matches
+//! are scored so that the characters at the start of words and consecutive
+//! characters rank first, and report the matched characters to highlight
+//! them.
+//!
+//! ```rust
+//! use ratzilla::utils::fuzzy::FuzzyMatcher;
+//!
+//! let matcher = FuzzyMatcher::new();
+//! let results = matcher.filter("of", ["Copy file", "Open file", "Fold"]);
+//! // the words starting with the pattern characters come first
+//! assert_eq!(results[0].0, 1);
+//! assert_eq!(results[0].1.indices, [0, 5]);
+//! ```
+//!
+//! The matching is case-insensitive unless the pattern contains an
+//! uppercase character.
+
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+
+/// Score of a matched character.
+const SCORE_MATCH: i64 = 16;
+/// Penalty for the first unmatched character between two matched ones.
+const PENALTY_GAP_START: i64 = 3;
+/// Penalty for the next unmatched characters between two matched ones.
+const PENALTY_GAP_EXTENSION: i64 = 1;
+/// Bonus for a match after a whitespace, or at the start of the candidate.
+const BONUS_WHITESPACE: i64 = 10;
+/// Bonus for a match after a delimiter such as `/` or `_`.
+const BONUS_DELIMITER: i64 = 9;
+/// Bonus for a match at a camel case or digit boundary.
+const BONUS_CAMEL: i64 = 7;
+/// Bonus for a match right after the previous one.
+const BONUS_CONSECUTIVE: i64 = 4;
+/// Multiplier of the bonus of the first pattern character.
+const FIRST_CHAR_MULTIPLIER: i64 = 2;
+
+/// How a [`FuzzyMatcher`] compares the case of characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaseMatching {
+    /// Case-insensitive, unless the pattern contains an uppercase character.
+    #[default]
+    Smart,
+    /// Case-sensitive.
+    Respect,
+    /// Case-insensitive.
+    Ignore,
+}
+
+/// A fuzzy match of a pattern in a candidate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Score of the match, higher is better.
+    pub score: i64,
+    /// Indices of the matched characters in the candidate, in `char`s.
+    pub indices: Vec<usize>,
+}
+
+/// A fuzzy matcher.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FuzzyMatcher {
+    /// How the case of characters is compared.
+    case: CaseMatching,
+}
+
+impl FuzzyMatcher {
+    /// Constructs a new [`FuzzyMatcher`] with smart case matching.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how the case of characters is compared.
+    pub fn case_matching(mut self, case: CaseMatching) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Matches the pattern in the candidate, returning the best match if
+    /// any.
+    ///
+    /// An empty pattern matches every candidate with a score of `0`.
+    pub fn fuzzy_match(&self, pattern: &str, candidate: &str) -> Option<FuzzyMatch> {
+        let ignore_case = match self.case {
+            CaseMatching::Smart => !pattern.chars().any(char::is_uppercase),
+            CaseMatching::Respect => false,
+            CaseMatching::Ignore => true,
+        };
+        let fold = |c: char| {
+            if ignore_case {
+                c.to_lowercase().next().unwrap_or(c)
+            } else {
+                c
+            }
+        };
+        let pattern: Vec<char> = pattern.chars().map(fold).collect();
+        let chars: Vec<char> = candidate.chars().collect();
+        if pattern.is_empty() {
+            return Some(FuzzyMatch {
+                score: 0,
+                indices: Vec::new(),
+            });
+        }
+        let folded: Vec<char> = chars.iter().copied().map(fold).collect();
+        if !is_subsequence(&pattern, &folded) {
+            return None;
+        }
+        let bonuses: Vec<i64> = (0..chars.len())
+            .map(|index| bonus(index.checked_sub(1).map(|index| chars[index]), chars[index]))
+            .collect();
+        best_match(&pattern, &folded, &bonuses)
+    }
+
+    /// Matches the pattern in every candidate, returning the indices of the
+    /// matching candidates with their match, best first.
+    ///
+    /// Candidates with the same score keep their order.
+    pub fn filter<I, S>(&self, pattern: &str, candidates: I) -> Vec<(usize, FuzzyMatch)>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut results: Vec<_> = candidates
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, candidate)| {
+                self.fuzzy_match(pattern, candidate.as_ref())
+                    .map(|found| (index, found))
+            })
+            .collect();
+        results.sort_by_key(|(_, found)| std::cmp::Reverse(found.score));
+        results
+    }
+}
+
+/// Matches the pattern in the candidate with a [`FuzzyMatcher`] with smart
+/// case matching.
+pub fn fuzzy_match(pattern: &str, candidate: &str) -> Option<FuzzyMatch> {
+    FuzzyMatcher::new().fuzzy_match(pattern, candidate)
+}
+
+/// Returns a line of the text with the characters at the given indices
+/// highlighted, e.g. the [`indices`](FuzzyMatch::indices) of a match.
+pub fn highlight<'a>(text: &'a str, indices: &[usize], style: Style) -> Line<'a> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut highlighted = false;
+    for (index, (offset, _)) in text.char_indices().enumerate() {
+        let matched = indices.binary_search(&index).is_ok();
+        if matched != highlighted {
+            push_span(&mut spans, &text[start..offset], highlighted, style);
+            start = offset;
+            highlighted = matched;
+        }
+    }
+    push_span(&mut spans, &text[start..], highlighted, style);
+    Line::from(spans)
+}
+
+/// Pushes a span of the text, styled if highlighted.
+fn push_span<'a>(spans: &mut Vec<Span<'a>>, text: &'a str, highlighted: bool, style: Style) {
+    if text.is_empty() {
+        return;
+    }
+    spans.push(if highlighted {
+        Span::styled(text, style)
+    } else {
+        Span::raw(text)
+    });
+}
+
+/// Returns `true` if the pattern characters appear in order in the
+/// candidate.
+fn is_subsequence(pattern: &[char], candidate: &[char]) -> bool {
+    let mut chars = candidate.iter();
+    pattern.iter().all(|p| chars.any(|c| c == p))
+}
+
+/// Returns the bonus of a match of the character, given the previous one.
+fn bonus(previous: Option<char>, c: char) -> i64 {
+    let Some(previous) = previous else {
+        return BONUS_WHITESPACE;
+    };
+    if previous.is_whitespace() {
+        BONUS_WHITESPACE
+    } else if matches!(
+        previous,
+        '/' | '\\' | '_' | '-' | '.' | ',' | ':' | ';' | '|'
+    ) {
+        BONUS_DELIMITER
+    } else if (previous.is_lowercase() && c.is_uppercase())
+        || (!previous.is_numeric() && c.is_numeric())
+    {
+        BONUS_CAMEL
+    } else {
+        0
+    }
+}
+
+/// Finds the best scoring alignment of the pattern in the candidate.
+///
+/// `scores[i][j]` is the best score of the first `i + 1` pattern characters
+/// with the last one matched at `j`, computed in `O(pattern × candidate)`
+/// by carrying the best gapped predecessor along each row.
+fn best_match(pattern: &[char], candidate: &[char], bonuses: &[i64]) -> Option<FuzzyMatch> {
+    let (rows, cols) = (pattern.len(), candidate.len());
+    let mut scores = vec![None; rows * cols];
+    // the index of the previous match of every cell
+    let mut previous = vec![0; rows * cols];
+    for (j, &c) in candidate.iter().enumerate() {
+        if c == pattern[0] {
+            scores[j] = Some(SCORE_MATCH + bonuses[j] * FIRST_CHAR_MULTIPLIER);
+        }
+    }
+    for i in 1..rows {
+        let (above, row) = scores.split_at_mut(i * cols);
+        let above = &above[(i - 1) * cols..];
+        // best predecessor with at least one unmatched character in between
+        let mut gapped: Option<(i64, usize)> = None;
+        for j in 1..cols {
+            if j >= 2 {
+                let start = above[j - 2].map(|score| (score - PENALTY_GAP_START, j - 2));
+                let extended = gapped.map(|(score, k)| (score - PENALTY_GAP_EXTENSION, k));
+                gapped = match (start, extended) {
+                    (Some(a), Some(b)) => Some(if b.0 > a.0 { b } else { a }),
+                    (a, b) => a.or(b),
+                };
+            }
+            if candidate[j] != pattern[i] {
+                continue;
+            }
+            let consecutive = above[j - 1].map(|score| (score + BONUS_CONSECUTIVE, j - 1));
+            let best = match (consecutive, gapped) {
+                (Some(a), Some(b)) => Some(if b.0 > a.0 { b } else { a }),
+                (a, b) => a.or(b),
+            };
+            if let Some((score, k)) = best {
+                row[j] = Some(score + SCORE_MATCH + bonuses[j]);
+                previous[i * cols + j] = k;
+            }
+        }
+    }
+    let last = &scores[(rows - 1) * cols..];
+    let (mut j, score) = last
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .fold(None, |best: Option<(usize, i64)>, (j, score)| match best {
+            Some((_, best_score)) if best_score >= score => best,
+            _ => Some((j, score)),
+        })?;
+    let mut indices = vec![0; rows];
+    for i in (0..rows).rev() {
+        indices[i] = j;
+        j = previous[i * cols + j];
+    }
+    Some(FuzzyMatch { score, indices })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match() {
+        let found = fuzzy_match("fbr", "foo_bar").expect("should match");
+        assert_eq!(found.indices, [0, 4, 6]);
+        assert_eq!(fuzzy_match("rbf", "foo_bar"), None);
+        assert_eq!(fuzzy_match("", "foo").map(|m| m.score), Some(0));
+    }
+
+    #[test]
+    fn test_prefers_boundaries_and_consecutive() {
+        // `b` at the word start rather than the first `b`
+        let found = fuzzy_match("bar", "abar foo_bar").expect("should match");
+        assert_eq!(found.indices, [9, 10, 11]);
+        let consecutive = fuzzy_match("ab", "xabx").expect("should match");
+        let gapped = fuzzy_match("ab", "xaxb").expect("should match");
+        assert!(consecutive.score > gapped.score);
+    }
+
+    #[test]
+    fn test_case_matching() {
+        assert!(fuzzy_match("foo", "FooBar").is_some());
+        assert!(fuzzy_match("Foo", "foobar").is_none());
+        let matcher = FuzzyMatcher::new().case_matching(CaseMatching::Ignore);
+        assert!(matcher.fuzzy_match("Foo", "foobar").is_some());
+    }
+
+    #[test]
+    fn test_filter_order() {
+        let results = FuzzyMatcher::new().filter("sv", ["save", "reserve", "Save as", "open"]);
+        let order: Vec<_> = results.iter().map(|(index, _)| *index).collect();
+        assert_eq!(order, [0, 2, 1]);
+    }
+
+    #[test]
+    fn test_highlight() {
+        let style = Style::new().bold();
+        let line = highlight("héllo", &[1, 2], style);
+        assert_eq!(
+            line.spans,
+            [Span::raw("h"), Span::styled("él", style), Span::raw("lo")]
+        );
+    }
+}
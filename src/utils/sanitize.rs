@@ -0,0 +1,114 @@
+//! ## Sanitization
+//!
+//! Helpers to make untrusted text safe to display, e.g. user generated or
+//! remote content such as logs and chat messages.
+//!
+//! Such text can contain characters that don't render as what they are:
+//!
+//! - Control characters, e.g. escape sequences or carriage returns that
+//!   overwrite what was displayed before them.
+//! - Bidirectional overrides and isolates, which reorder the text around them
+//!   (e.g. `invoice\u{202E}fdp.exe` displays as `invoiceexe.pdf`).
+//! - Invisible characters such as zero-width spaces, which make two strings
+//!   that look equal differ.
+//!
+//! [`strip`] removes them, while [`escape`] replaces them with a visible
+//! `\u{..}` notation. Both keep line feeds, so the result can still be split
+//! into lines, and replace tabs with a space. Zero-width joiners and
+//! non-joiners are kept since emoji sequences and some scripts need them.
+//!
+//! ```rust
+//! use ratzilla::utils::sanitize::{escape, strip};
+//!
+//! assert_eq!(strip("invoice\u{202E}fdp.exe"), "invoicefdp.exe");
+//! assert_eq!(escape("\x1b[2J"), "\\u{1b}[2J");
+//! ```
+//!
+//! [`escape`]: crate::utils::sanitize::escape
+//! [`strip`]: crate::utils::sanitize::strip
+
+use std::{borrow::Cow, fmt::Write};
+
+/// Returns `true` if the character is hidden or altered by [`strip`] and
+/// [`escape`].
+pub fn is_unsafe(c: char) -> bool {
+    (c.is_control() && c != '\n')
+        || matches!(
+            c,
+            // bidi marks, embeddings, overrides and isolates
+            '\u{061C}'
+                | '\u{200E}'
+                | '\u{200F}'
+                | '\u{202A}'..='\u{202E}'
+                | '\u{2066}'..='\u{2069}'
+                // zero-width and invisible characters
+                | '\u{00AD}'
+                | '\u{180E}'
+                | '\u{200B}'
+                | '\u{2060}'..='\u{2064}'
+                | '\u{FEFF}'
+        )
+}
+
+/// Removes the control, bidirectional and invisible characters of a string.
+///
+/// Tabs are replaced with a space. The string is borrowed if it contains no
+/// such character.
+pub fn strip(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(is_unsafe) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.chars()
+            .filter_map(|c| match c {
+                '\t' => Some(' '),
+                c if is_unsafe(c) => None,
+                c => Some(c),
+            })
+            .collect(),
+    )
+}
+
+/// Replaces the control, bidirectional and invisible characters of a string
+/// with their `\u{..}` notation.
+///
+/// Tabs are replaced with a space. The string is borrowed if it contains no
+/// such character.
+pub fn escape(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(is_unsafe) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\t' => escaped.push(' '),
+            c if is_unsafe(c) => {
+                let _ = write!(escaped, "\\u{{{:x}}}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip() {
+        assert!(matches!(strip("plain text"), Cow::Borrowed(_)));
+        assert_eq!(strip("a\x1b[31mb\rc"), "a[31mbc");
+        assert_eq!(strip("one\ttwo\nthree"), "one two\nthree");
+        assert_eq!(strip("pay\u{200B}pal\u{2066}\u{2069}"), "paypal");
+        assert_eq!(strip("👩\u{200D}💻"), "👩\u{200D}💻");
+    }
+
+    #[test]
+    fn test_escape() {
+        assert!(matches!(escape("plain text"), Cow::Borrowed(_)));
+        assert_eq!(escape("a\u{202E}b"), "a\\u{202e}b");
+        assert_eq!(escape("\u{7f}\u{85}"), "\\u{7f}\\u{85}");
+        assert_eq!(escape("x\ty\n"), "x y\n");
+    }
+}
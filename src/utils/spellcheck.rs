@@ -0,0 +1,183 @@
+//! ## Spell checking
+//!
+//! Finds the misspelled words of a text, e.g. the text of an
+//! [`EditBuffer`](crate::editing::EditBuffer), and highlights them when
+//! rendering it.
+//!
+//! Browsers don't expose the results of their own spell checker to scripts,
+//! so the words are checked by a [`SpellChecker`]: a [`Dictionary`] of known
+//! words, or any `Fn(&str) -> bool` bridging to another checker.
+//!
+//! ```rust
+//! use ratzilla::{
+//!     ratatui::style::{Style, Stylize},
+//!     utils::spellcheck::{highlight, misspellings, Dictionary},
+//! };
+//!
+//! let dictionary = Dictionary::from_iter(["the", "quick", "fox"]);
+//! assert_eq!(misspellings("The quikc fox", &dictionary), [4..9]);
+//!
+//! let line = highlight("The quikc fox", &dictionary, Style::new().red().underlined());
+//! // frame.render_widget(line, area);
+//! ```
+//!
+//! [`Dictionary`]: crate::utils::spellcheck::Dictionary
+//! [`SpellChecker`]: crate::utils::spellcheck::SpellChecker
+
+use std::{collections::HashSet, ops::Range};
+
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Checks the spelling of words.
+pub trait SpellChecker {
+    /// Returns `true` if the word is spelled correctly.
+    fn check(&self, word: &str) -> bool;
+}
+
+impl<F> SpellChecker for F
+where
+    F: Fn(&str) -> bool,
+{
+    fn check(&self, word: &str) -> bool {
+        self(word)
+    }
+}
+
+/// A set of correctly spelled words.
+///
+/// A word is correct if it is in the dictionary as is or in lowercase, so
+/// that capitalized words at the start of a sentence are correct.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dictionary {
+    /// The words.
+    words: HashSet<String>,
+}
+
+impl Dictionary {
+    /// Constructs a new, empty [`Dictionary`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a dictionary with a word per line, e.g. a word list file.
+    ///
+    /// Empty lines and lines starting with `#` are skipped.
+    pub fn parse(words: &str) -> Self {
+        words
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect()
+    }
+
+    /// Adds a word, e.g. when the user chooses to ignore a misspelling.
+    pub fn add(&mut self, word: impl Into<String>) {
+        self.words.insert(word.into());
+    }
+
+    /// Returns the number of words.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns `true` if the dictionary has no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Dictionary {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self {
+            words: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<S: Into<String>> Extend<S> for Dictionary {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        self.words.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+impl SpellChecker for Dictionary {
+    fn check(&self, word: &str) -> bool {
+        self.words.contains(word) || self.words.contains(&word.to_lowercase())
+    }
+}
+
+/// Returns the byte ranges of the misspelled words of the text.
+///
+/// Only words made of letters are checked, so numbers, identifiers such as
+/// `v2` and punctuation are never reported.
+pub fn misspellings<C>(text: &str, checker: &C) -> Vec<Range<usize>>
+where
+    C: SpellChecker + ?Sized,
+{
+    text.split_word_bound_indices()
+        .filter(|(_, word)| {
+            word.chars().all(|c| c.is_alphabetic() || c == '\'')
+                && word.chars().any(char::is_alphabetic)
+                && !checker.check(word)
+        })
+        .map(|(start, word)| start..start + word.len())
+        .collect()
+}
+
+/// Returns a [`Line`] of the text with the misspelled words styled.
+pub fn highlight<'a, C>(text: &'a str, checker: &C, style: Style) -> Line<'a>
+where
+    C: SpellChecker + ?Sized,
+{
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for range in misspellings(text, checker) {
+        if start < range.start {
+            spans.push(Span::raw(&text[start..range.start]));
+        }
+        start = range.end;
+        spans.push(Span::styled(&text[range], style));
+    }
+    if start < text.len() {
+        spans.push(Span::raw(&text[start..]));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_misspellings() {
+        let mut dictionary = Dictionary::parse("# words\nhello\n\nworld\ndon't\n");
+        assert_eq!(dictionary.len(), 3);
+        let text = "Hello, wrld! I don't see v2 in 2024.";
+        assert_eq!(
+            misspellings(text, &dictionary),
+            [7..11, 13..14, 21..24, 28..30]
+        );
+
+        dictionary.extend(["I", "see", "in"]);
+        assert_eq!(
+            misspellings(text, &dictionary),
+            [Range { start: 7, end: 11 }]
+        );
+        assert!(misspellings("naïve café", &|word: &str| word.len() > 3).is_empty());
+    }
+
+    #[test]
+    fn test_highlight() {
+        let dictionary = Dictionary::from_iter(["a", "word"]);
+        let style = Style::new().red().underlined();
+        assert_eq!(
+            highlight("a wrod", &dictionary, style),
+            Line::from(vec![Span::raw("a "), Span::styled("wrod", style)])
+        );
+        assert_eq!(highlight("", &dictionary, style), Line::default());
+    }
+}
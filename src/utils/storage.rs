@@ -0,0 +1,55 @@
+//! ## Web storage
+//!
+//! Typed access to the `localStorage` of the window, e.g. to persist user
+//! preferences across sessions.
+//!
+//! ```rust no_run
+//! use ratzilla::utils::storage::{get_item, set_item};
+//! # fn example() -> Result<(), ratzilla::error::Error> {
+//!
+//! set_item("font-size", 16)?;
+//! let font_size: Option<u32> = get_item("font-size")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+use web_sys::Storage;
+
+use crate::{backend::utils::get_window, error::Error};
+
+/// Returns the `localStorage` of the window.
+pub fn local_storage() -> Result<Storage, Error> {
+    get_window()?
+        .local_storage()?
+        .ok_or(Error::UnableToRetrieveComponent("localStorage"))
+}
+
+/// Returns the `sessionStorage` of the window.
+pub fn session_storage() -> Result<Storage, Error> {
+    get_window()?
+        .session_storage()?
+        .ok_or(Error::UnableToRetrieveComponent("sessionStorage"))
+}
+
+/// Returns the value of a `localStorage` item, parsed into the given type.
+///
+/// Returns `None` if the item doesn't exist or can't be parsed.
+pub fn get_item<T: FromStr>(key: &str) -> Result<Option<T>, Error> {
+    Ok(local_storage()?
+        .get_item(key)?
+        .and_then(|value| value.parse().ok()))
+}
+
+/// Sets a `localStorage` item.
+pub fn set_item<T: Display>(key: &str, value: T) -> Result<(), Error> {
+    local_storage()?.set_item(key, &value.to_string())?;
+    Ok(())
+}
+
+/// Removes a `localStorage` item.
+pub fn remove_item(key: &str) -> Result<(), Error> {
+    local_storage()?.remove_item(key)?;
+    Ok(())
+}
@@ -0,0 +1,309 @@
+//! ## Frame watchdog
+//!
+//! A development aid detecting frames that take longer than a budget. For
+//! each slow frame, it measures which phase of the frame overran:
+//!
+//! - **render**: the callback passed to [`draw_web`], i.e. the app's widgets.
+//! - **diff**: the comparison of the new frame with the previous one, which
+//!   also counts the changed cells.
+//! - **flush**: the backend drawing the changed cells to the screen.
+//!
+//! The backends of ratzilla mark the end of the diff when they start
+//! drawing. With other backends, the diff is counted in the flush phase.
+//!
+//! Slow frames are logged to the console, at most once per log interval so
+//! a janky app doesn't flood it, and the last one is available with
+//! [`last_slow_frame`], e.g. to display it in the app.
+//!
+//! ```rust no_run
+//! use ratzilla::watchdog::{enable_watchdog, WatchdogOptions};
+//!
+//! // warn about frames missing 60fps
+//! enable_watchdog(WatchdogOptions::new(16.7));
+//! ```
+//!
+//! The diff is measured on a copy of the previous frame kept by the
+//! watchdog, so enabling it adds the cost of a copy and a diff to each
+//! frame.
+//!
+//! [`draw_web`]: crate::WebRenderer::draw_web
+//! [`last_slow_frame`]: crate::watchdog::last_slow_frame
+
+use std::cell::RefCell;
+
+use ratatui::buffer::Buffer;
+
+/// Default minimum interval between two logged slow frames, in milliseconds.
+const DEFAULT_LOG_INTERVAL_MS: f64 = 1000.0;
+
+/// Options of the frame watchdog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchdogOptions {
+    /// Frame budget, in milliseconds.
+    budget_ms: f64,
+    /// Minimum interval between two logged slow frames, in milliseconds.
+    log_interval_ms: f64,
+    /// Whether slow frames are logged to the console.
+    log: bool,
+}
+
+impl WatchdogOptions {
+    /// Constructs new [`WatchdogOptions`] with the given frame budget, in
+    /// milliseconds.
+    pub fn new(budget_ms: f64) -> Self {
+        Self {
+            budget_ms,
+            log_interval_ms: DEFAULT_LOG_INTERVAL_MS,
+            log: true,
+        }
+    }
+
+    /// Sets the minimum interval between two logged slow frames, in
+    /// milliseconds.
+    pub fn log_interval(mut self, interval_ms: f64) -> Self {
+        self.log_interval_ms = interval_ms;
+        self
+    }
+
+    /// Sets whether slow frames are logged to the console.
+    pub fn log(mut self, log: bool) -> Self {
+        self.log = log;
+        self
+    }
+}
+
+/// Timings of a frame, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameTimings {
+    /// Time spent in the render callback.
+    pub render_ms: f64,
+    /// Time spent comparing the frame with the previous one.
+    pub diff_ms: f64,
+    /// Time spent drawing the changed cells to the screen.
+    pub flush_ms: f64,
+    /// Number of cells that changed since the previous frame.
+    pub changed_cells: usize,
+}
+
+impl FrameTimings {
+    /// Returns the total time of the frame, in milliseconds.
+    pub fn total_ms(&self) -> f64 {
+        self.render_ms + self.diff_ms + self.flush_ms
+    }
+
+    /// Returns the name of the slowest phase.
+    pub fn slowest_phase(&self) -> &'static str {
+        if self.render_ms >= self.diff_ms && self.render_ms >= self.flush_ms {
+            "render"
+        } else if self.diff_ms >= self.flush_ms {
+            "diff"
+        } else {
+            "flush"
+        }
+    }
+}
+
+/// State of the watchdog.
+#[derive(Debug)]
+struct Watchdog {
+    /// Options.
+    options: WatchdogOptions,
+    /// Copy of the previous frame.
+    previous: Option<Buffer>,
+    /// The last slow frame.
+    last_slow: Option<FrameTimings>,
+    /// Timestamp of the last logged slow frame, in milliseconds.
+    last_log: Option<f64>,
+    /// Slow frames not logged since the last logged one.
+    suppressed: u32,
+    /// Time the backend started drawing the current frame, in milliseconds.
+    flush_start: Option<f64>,
+}
+
+impl Watchdog {
+    /// Constructs a new [`Watchdog`].
+    fn new(options: WatchdogOptions) -> Self {
+        Self {
+            options,
+            previous: None,
+            last_slow: None,
+            last_log: None,
+            suppressed: 0,
+            flush_start: None,
+        }
+    }
+
+    /// Returns the number of cells that changed since the previous frame and
+    /// keeps a copy of the frame.
+    fn changed_cells(&mut self, current: &Buffer) -> usize {
+        let changed = match &self.previous {
+            Some(previous) if previous.area == current.area => previous.diff(current).len(),
+            _ => current.content.len(),
+        };
+        match &mut self.previous {
+            Some(previous) if previous.area == current.area => {
+                previous.content.clone_from(&current.content)
+            }
+            previous => *previous = Some(current.clone()),
+        }
+        changed
+    }
+
+    /// Records the timings of a frame and returns the message to log, if the
+    /// frame is slow and the log interval elapsed.
+    fn record(&mut self, timings: FrameTimings, now_ms: f64) -> Option<String> {
+        if timings.total_ms() <= self.options.budget_ms {
+            return None;
+        }
+        self.last_slow = Some(timings);
+        if !self.options.log {
+            return None;
+        }
+        if let Some(last) = self.last_log {
+            if now_ms - last < self.options.log_interval_ms {
+                self.suppressed += 1;
+                return None;
+            }
+        }
+        self.last_log = Some(now_ms);
+        let mut message = format!(
+            "slow frame: {:.1}ms over a {:.1}ms budget, {} overran \
+             (render {:.1}ms, diff {:.1}ms, flush {:.1}ms, {} changed cells)",
+            timings.total_ms(),
+            self.options.budget_ms,
+            timings.slowest_phase(),
+            timings.render_ms,
+            timings.diff_ms,
+            timings.flush_ms,
+            timings.changed_cells,
+        );
+        if self.suppressed > 0 {
+            message.push_str(&format!(
+                ", {} more slow frames since the last report",
+                self.suppressed
+            ));
+            self.suppressed = 0;
+        }
+        Some(message)
+    }
+}
+
+thread_local! {
+    static WATCHDOG: RefCell<Option<Watchdog>> = const { RefCell::new(None) };
+}
+
+/// Enables the frame watchdog, replacing its previous options.
+pub fn enable_watchdog(options: WatchdogOptions) {
+    WATCHDOG.with(|w| *w.borrow_mut() = Some(Watchdog::new(options)));
+}
+
+/// Disables the frame watchdog.
+pub fn disable_watchdog() {
+    WATCHDOG.with(|w| *w.borrow_mut() = None);
+}
+
+/// Returns the timings of the last frame over the budget, if any.
+pub fn last_slow_frame() -> Option<FrameTimings> {
+    WATCHDOG.with(|w| w.borrow().as_ref().and_then(|w| w.last_slow))
+}
+
+/// Returns `true` if the frame watchdog is enabled.
+pub(crate) fn is_enabled() -> bool {
+    WATCHDOG.with(|w| w.borrow().is_some())
+}
+
+/// Returns the number of cells that changed since the previous frame.
+pub(crate) fn changed_cells(current: &Buffer) -> usize {
+    WATCHDOG.with(|w| {
+        w.borrow_mut()
+            .as_mut()
+            .map_or(0, |w| w.changed_cells(current))
+    })
+}
+
+/// Marks the start of the backend drawing the current frame, i.e. the end of
+/// its diff.
+#[cfg(any(
+    feature = "dom",
+    feature = "canvas",
+    feature = "webgl2",
+    feature = "webgpu"
+))]
+pub(crate) fn mark_flush_start() {
+    if !is_enabled() {
+        return;
+    }
+    // the clock may read other thread locals, so don't hold the borrow
+    let now = crate::clock::now();
+    WATCHDOG.with(|w| {
+        if let Some(w) = w.borrow_mut().as_mut() {
+            w.flush_start = Some(now);
+        }
+    });
+}
+
+/// Returns the start of the backend drawing the current frame, if the
+/// backend marked it.
+pub(crate) fn take_flush_start() -> Option<f64> {
+    WATCHDOG.with(|w| w.borrow_mut().as_mut()?.flush_start.take())
+}
+
+/// Records the timings of a frame, logging it if it is slow.
+pub(crate) fn record(timings: FrameTimings, now_ms: f64) {
+    let message = WATCHDOG.with(|w| w.borrow_mut().as_mut()?.record(timings, now_ms));
+    if let Some(message) = message {
+        web_sys::console::warn_1(&message.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{layout::Rect, style::Style};
+
+    use super::*;
+
+    fn timings(render_ms: f64) -> FrameTimings {
+        FrameTimings {
+            render_ms,
+            diff_ms: 1.0,
+            flush_ms: 2.0,
+            changed_cells: 12,
+        }
+    }
+
+    #[test]
+    fn test_changed_cells() {
+        let mut watchdog = Watchdog::new(WatchdogOptions::new(16.0));
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        assert_eq!(watchdog.changed_cells(&buffer), 8);
+        buffer.set_string(0, 0, "ab", Style::default());
+        assert_eq!(watchdog.changed_cells(&buffer), 2);
+        assert_eq!(watchdog.changed_cells(&buffer), 0);
+    }
+
+    #[test]
+    fn test_record_rate_limited() {
+        let mut watchdog = Watchdog::new(WatchdogOptions::new(16.0));
+        assert_eq!(watchdog.record(timings(5.0), 0.0), None);
+        assert_eq!(watchdog.last_slow, None);
+
+        let message = watchdog.record(timings(30.0), 100.0).expect("slow frame");
+        assert!(message.starts_with("slow frame: 33.0ms over a 16.0ms budget, render overran"));
+        assert_eq!(watchdog.record(timings(40.0), 500.0), None);
+        assert_eq!(watchdog.last_slow, Some(timings(40.0)));
+
+        let message = watchdog.record(timings(30.0), 1200.0).expect("slow frame");
+        assert!(message.ends_with("1 more slow frames since the last report"));
+    }
+
+    #[test]
+    fn test_slowest_phase() {
+        let frame = FrameTimings {
+            render_ms: 1.0,
+            diff_ms: 0.5,
+            flush_ms: 9.0,
+            changed_cells: 0,
+        };
+        assert_eq!(frame.slowest_phase(), "flush");
+    }
+}
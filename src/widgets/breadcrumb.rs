@@ -0,0 +1,269 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+    text::Span,
+    widgets::StatefulWidget,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::event::{KeyCode, KeyEvent};
+
+/// Glyph shown in place of the segments that don't fit.
+const ELLIPSIS: &str = "…";
+
+/// State of a [`Breadcrumb`]: the focused segment and the clickable areas of
+/// the last render.
+#[derive(Debug, Default, Clone)]
+pub struct BreadcrumbState {
+    /// Index of the focused segment.
+    focused: Option<usize>,
+    /// Number of segments of the last render.
+    len: usize,
+    /// Areas of the rendered segments.
+    hits: Vec<(usize, Rect)>,
+}
+
+impl BreadcrumbState {
+    /// Constructs a new [`BreadcrumbState`] without focus.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the index of the focused segment.
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    /// Focuses the segment at the given index, or removes the focus with `None`.
+    pub fn focus(&mut self, index: Option<usize>) {
+        self.focused = index;
+    }
+
+    /// Returns the index of the segment at the given cell position, if any.
+    ///
+    /// Use this to navigate when the breadcrumb is clicked.
+    pub fn click(&self, column: u16, row: u16) -> Option<usize> {
+        let position = Position::new(column, row);
+        self.hits
+            .iter()
+            .find(|(_, area)| area.contains(position))
+            .map(|(index, _)| *index)
+    }
+
+    /// Handles a key event, returning the index of the activated segment.
+    ///
+    /// The left and right arrows (and `Home` and `End`) move the focus
+    /// between the segments, and `Enter` activates the focused segment.
+    pub fn handle_key_event(&mut self, event: &KeyEvent) -> Option<usize> {
+        let last = self.len.checked_sub(1)?;
+        let focused = self.focused.map(|i| i.min(last));
+        self.focused = match event.code {
+            KeyCode::Left => Some(focused.map_or(last, |i| i.saturating_sub(1))),
+            KeyCode::Right => Some(focused.map_or(last, |i| (i + 1).min(last))),
+            KeyCode::Home => Some(0),
+            KeyCode::End => Some(last),
+            KeyCode::Esc => None,
+            KeyCode::Enter => return focused,
+            _ => return None,
+        };
+        None
+    }
+}
+
+/// A breadcrumb trail showing the path to the current location, e.g.
+/// `Home › Docs › API`.
+///
+/// The last segment is the current location. When the trail doesn't fit, the
+/// leading segments are replaced with an ellipsis. Segments can be activated
+/// with the mouse or the keyboard through the [`BreadcrumbState`], which
+/// returns the index of the segment to navigate to.
+///
+/// ```rust no_run
+/// use ratzilla::widgets::{Breadcrumb, BreadcrumbState};
+///
+/// let breadcrumb = Breadcrumb::new(["Home", "Docs", "API"]);
+/// let mut state = BreadcrumbState::new();
+///
+/// // frame.render_stateful_widget(breadcrumb, area, &mut state);
+/// // and on mouse events: state.click(column, row);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Breadcrumb<'a> {
+    /// The segments, from the root.
+    segments: Vec<Span<'a>>,
+    /// Separator between segments.
+    separator: Span<'a>,
+    /// Style of the trail.
+    style: Style,
+    /// Style of the last segment.
+    current_style: Style,
+    /// Style of the focused segment.
+    highlight_style: Style,
+}
+
+impl<'a> Breadcrumb<'a> {
+    /// Constructs a new [`Breadcrumb`] with the given segments, from the root.
+    pub fn new<I, T>(segments: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Span<'a>>,
+    {
+        Self {
+            segments: segments.into_iter().map(Into::into).collect(),
+            separator: Span::raw(" › "),
+            style: Style::default(),
+            current_style: Style::new().add_modifier(Modifier::BOLD),
+            highlight_style: Style::new().add_modifier(Modifier::REVERSED),
+        }
+    }
+
+    /// Sets the separator between segments.
+    pub fn separator<T>(mut self, separator: T) -> Self
+    where
+        T: Into<Span<'a>>,
+    {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Sets the style of the trail.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the last segment.
+    pub fn current_style(mut self, style: Style) -> Self {
+        self.current_style = style;
+        self
+    }
+
+    /// Sets the style of the focused segment.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = style;
+        self
+    }
+
+    /// Returns the index of the first segment to show so the trail fits into
+    /// `width` cells.
+    fn first_visible(&self, width: u16) -> usize {
+        let separator = self.separator.content.width();
+        let mut used = self
+            .segments
+            .iter()
+            .map(|s| s.content.width() + separator)
+            .sum::<usize>()
+            - separator;
+        if used <= width as usize {
+            return 0;
+        }
+        // the ellipsis takes the place of the hidden segments
+        used += ELLIPSIS.width() + separator;
+        let mut first = 0;
+        while first + 1 < self.segments.len() && used > width as usize {
+            used -= self.segments[first].content.width() + separator;
+            first += 1;
+        }
+        first
+    }
+}
+
+impl StatefulWidget for Breadcrumb<'_> {
+    type State = BreadcrumbState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        state.hits.clear();
+        state.len = self.segments.len();
+        let area = area.intersection(buf.area);
+        if area.is_empty() || self.segments.is_empty() {
+            return;
+        }
+        buf.set_style(area, self.style);
+
+        let first = self.first_visible(area.width);
+        let (mut x, y) = (area.x, area.y);
+        if first > 0 {
+            x = buf
+                .set_stringn(x, y, ELLIPSIS, (area.right() - x) as usize, self.style)
+                .0;
+            x = buf.set_span(x, y, &self.separator, area.right() - x).0;
+        }
+        let last = self.segments.len() - 1;
+        for (index, segment) in self.segments.iter().enumerate().skip(first) {
+            if x >= area.right() {
+                break;
+            }
+            let mut style = self.style.patch(segment.style);
+            if index == last {
+                style = style.patch(self.current_style);
+            }
+            if state.focused == Some(index) {
+                style = style.patch(self.highlight_style);
+            }
+            let start = x;
+            x = buf
+                .set_stringn(x, y, &segment.content, (area.right() - x) as usize, style)
+                .0;
+            state.hits.push((index, Rect::new(start, y, x - start, 1)));
+            if index < last && x < area.right() {
+                x = buf.set_span(x, y, &self.separator, area.right() - x).0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(width: u16, state: &mut BreadcrumbState) -> Buffer {
+        let area = Rect::new(0, 0, width, 1);
+        let mut buf = Buffer::empty(area);
+        Breadcrumb::new(["Home", "Docs", "API"])
+            .current_style(Style::new())
+            .highlight_style(Style::new())
+            .render(area, &mut buf, state);
+        buf
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent {
+            code,
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+            repeat: false,
+        }
+    }
+
+    #[test]
+    fn test_render_and_click() {
+        let mut state = BreadcrumbState::new();
+        let buf = render(20, &mut state);
+        assert_eq!(buf, Buffer::with_lines(["Home › Docs › API   "]));
+        assert_eq!(state.click(8, 0), Some(1));
+        assert_eq!(state.click(5, 0), None);
+    }
+
+    #[test]
+    fn test_truncates_leading_segments() {
+        let mut state = BreadcrumbState::new();
+        let buf = render(12, &mut state);
+        assert_eq!(buf, Buffer::with_lines(["… › API     "]));
+        assert_eq!(state.click(4, 0), Some(2));
+    }
+
+    #[test]
+    fn test_keyboard_navigation() {
+        let mut state = BreadcrumbState::new();
+        render(20, &mut state);
+        assert_eq!(state.handle_key_event(&key(KeyCode::Left)), None);
+        assert_eq!(state.focused(), Some(2));
+        state.handle_key_event(&key(KeyCode::Left));
+        assert_eq!(state.handle_key_event(&key(KeyCode::Enter)), Some(1));
+        state.handle_key_event(&key(KeyCode::Home));
+        assert_eq!(state.focused(), Some(0));
+    }
+}
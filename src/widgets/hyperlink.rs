@@ -1,5 +1,7 @@
 use ratatui::{buffer::Buffer, layout::Rect, style::Modifier, text::Span, widgets::Widget};
 
+use crate::links;
+
 /// Hyperlink modifier.
 ///
 /// When added as a modifier to a style, the styled element is marked as
@@ -38,6 +40,12 @@ impl Widget for Hyperlink<'_> {
     where
         Self: Sized,
     {
+        // make the rendered cells a link in every backend
+        let width = (self.line.width() as u16).min(area.width);
+        links::register(
+            Rect::new(area.x, area.y, width, 1.min(area.height)).intersection(buf.area),
+            &self.line.content,
+        );
         self.line.render(area, buf);
     }
 }
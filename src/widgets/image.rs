@@ -0,0 +1,108 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Styled},
+    widgets::Widget,
+};
+
+use crate::images::{self, ImageFit, ImageSource};
+
+/// A widget that shows a raster image over its area.
+///
+/// The cells of the area are blank, in the style of the widget, and the
+/// backend shows the image over them, scaled to the area. See
+/// [`images`](crate::images).
+///
+/// ```rust no_run
+/// use ratzilla::{images::ImageFit, ratatui::Frame, widgets::Image};
+///
+/// fn render(frame: &mut Frame) {
+///     let image = Image::url("logo.png").fit(ImageFit::Cover);
+///     frame.render_widget(&image, frame.area());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    /// The image.
+    source: ImageSource,
+    /// How the image is scaled to the area.
+    fit: ImageFit,
+    /// Style of the cells under the image.
+    style: Style,
+}
+
+impl Image {
+    /// Constructs a new [`Image`] widget.
+    pub fn new(source: impl Into<ImageSource>) -> Self {
+        Self {
+            source: source.into(),
+            fit: ImageFit::default(),
+            style: Style::default(),
+        }
+    }
+
+    /// Constructs a new [`Image`] widget of an image loaded from a URL.
+    pub fn url(url: impl Into<String>) -> Self {
+        Self::new(ImageSource::url(url))
+    }
+
+    /// Sets how the image is scaled to the area.
+    pub fn fit(mut self, fit: ImageFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Sets the style of the cells under the image, e.g. the background
+    /// around an image that doesn't cover its area.
+    pub fn style<S: Into<Style>>(mut self, style: S) -> Self {
+        self.style = style.into();
+        self
+    }
+}
+
+impl Styled for Image {
+    type Item = Self;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style<S: Into<Style>>(self, style: S) -> Self::Item {
+        self.style(style)
+    }
+}
+
+impl Widget for &Image {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        for position in area.positions() {
+            buf[position].reset();
+        }
+        buf.set_style(area, self.style);
+        images::register(area, self.source.clone(), self.fit);
+    }
+}
+
+impl Widget for Image {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        (&self).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::{Color, Stylize};
+
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut buf = Buffer::with_lines(["abcd", "efgh"]);
+        Image::url("a.png")
+            .on_blue()
+            .render(Rect::new(1, 0, 10, 1), &mut buf);
+        let mut expected = Buffer::with_lines(["a   ", "efgh"]);
+        expected.set_style(Rect::new(1, 0, 3, 1), Style::new().bg(Color::Blue));
+        assert_eq!(buf, expected);
+    }
+}
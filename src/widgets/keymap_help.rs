@@ -0,0 +1,121 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, Paragraph, Widget},
+};
+
+use crate::keymap::Keymap;
+
+/// An overlay listing the bindings of a [`Keymap`], grouped by context.
+///
+/// Renders nothing unless the help of the keymap is visible, so it can be
+/// rendered unconditionally at the end of the [`draw_web`] callback.
+///
+/// ```rust no_run
+/// use ratzilla::{keymap::Keymap, widgets::KeymapHelp};
+///
+/// let keymap: Keymap<()> = Keymap::new();
+///
+/// // frame.render_widget(KeymapHelp::new(&keymap), frame.area());
+/// ```
+///
+/// [`draw_web`]: crate::WebRenderer::draw_web
+pub struct KeymapHelp<'a, A> {
+    /// The keymap.
+    keymap: &'a Keymap<A>,
+    /// Title of the overlay.
+    title: &'a str,
+    /// Style of the keys.
+    key_style: Style,
+    /// Style of the context headers.
+    header_style: Style,
+}
+
+impl<'a, A: Clone> KeymapHelp<'a, A> {
+    /// Constructs a new [`KeymapHelp`] widget.
+    pub fn new(keymap: &'a Keymap<A>) -> Self {
+        Self {
+            keymap,
+            title: " Keyboard shortcuts ",
+            key_style: Style::new().fg(Color::Yellow),
+            header_style: Style::new().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        }
+    }
+
+    /// Sets the title of the overlay.
+    pub fn title(mut self, title: &'a str) -> Self {
+        self.title = title;
+        self
+    }
+
+    /// Sets the style of the keys.
+    pub fn key_style(mut self, style: Style) -> Self {
+        self.key_style = style;
+        self
+    }
+
+    /// Sets the style of the context headers.
+    pub fn header_style(mut self, style: Style) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    /// Returns the lines of the overlay.
+    fn lines(&self) -> Vec<Line<'a>> {
+        let groups = self.keymap.groups();
+        let key_width = groups
+            .iter()
+            .flat_map(|(_, bindings)| bindings.iter())
+            .map(|b| b.key.to_string().chars().count())
+            .max()
+            .unwrap_or_default();
+
+        let mut lines = Vec::new();
+        for (i, (context, bindings)) in groups.into_iter().enumerate() {
+            if i > 0 {
+                lines.push(Line::default());
+            }
+            lines.push(Line::styled(context.to_string(), self.header_style));
+            for binding in bindings {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  {:<key_width$}  ", binding.key.to_string()),
+                        self.key_style,
+                    ),
+                    Span::raw(binding.description.clone()),
+                ]));
+            }
+        }
+        lines
+    }
+}
+
+impl<A: Clone> Widget for KeymapHelp<'_, A> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if !self.keymap.help_visible() {
+            return;
+        }
+        let lines = self.lines();
+        let width = lines.iter().map(Line::width).max().unwrap_or_default() as u16 + 4;
+        let height = lines.len() as u16 + 2;
+
+        let [area] = Layout::horizontal([Constraint::Length(width)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title(self.title)
+                    .title_bottom(Line::from(" Esc to close ").right_aligned()),
+            )
+            .render(area, buf);
+    }
+}
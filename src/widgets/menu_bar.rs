@@ -0,0 +1,631 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Clear, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    event::{KeyCode, KeyEvent},
+    keymap::KeyBinding,
+};
+
+/// Glyph marking the items that open a submenu.
+const SUBMENU_GLYPH: &str = "▸";
+
+/// What a [`MenuItem`] does.
+#[derive(Debug, Clone)]
+enum MenuItemKind<A> {
+    /// Returns an action.
+    Action(A),
+    /// Opens a submenu.
+    Submenu(Vec<MenuItem<A>>),
+    /// Separates groups of items.
+    Separator,
+}
+
+/// An item of a [`MenuBar`] dropdown.
+#[derive(Debug, Clone)]
+pub struct MenuItem<A> {
+    /// Label of the item.
+    label: String,
+    /// What the item does.
+    kind: MenuItemKind<A>,
+    /// Keyboard accelerator.
+    shortcut: Option<KeyBinding>,
+    /// Whether the item can be activated.
+    enabled: bool,
+}
+
+impl<A> MenuItem<A> {
+    /// Constructs a new [`MenuItem`] returning the given action when activated.
+    pub fn new(label: &str, action: A) -> Self {
+        Self::with_kind(label, MenuItemKind::Action(action))
+    }
+
+    /// Constructs a new [`MenuItem`] opening a submenu.
+    pub fn submenu(label: &str, items: Vec<MenuItem<A>>) -> Self {
+        Self::with_kind(label, MenuItemKind::Submenu(items))
+    }
+
+    /// Constructs a separator line.
+    pub fn separator() -> Self {
+        Self::with_kind("", MenuItemKind::Separator)
+    }
+
+    fn with_kind(label: &str, kind: MenuItemKind<A>) -> Self {
+        Self {
+            label: label.to_string(),
+            kind,
+            shortcut: None,
+            enabled: true,
+        }
+    }
+
+    /// Sets the keyboard accelerator of the item.
+    ///
+    /// The accelerator is shown next to the label and activates the item
+    /// while the menus are closed.
+    pub fn shortcut(mut self, key: KeyBinding) -> Self {
+        self.shortcut = Some(key);
+        self
+    }
+
+    /// Sets whether the item can be activated.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Returns `true` if the item can be highlighted.
+    fn is_selectable(&self) -> bool {
+        self.enabled && !matches!(self.kind, MenuItemKind::Separator)
+    }
+
+    /// Returns the items of the submenu, if any.
+    fn children(&self) -> &[MenuItem<A>] {
+        match &self.kind {
+            MenuItemKind::Submenu(items) => items,
+            _ => &[],
+        }
+    }
+
+    /// Returns the text shown on the right side of the item.
+    fn hint(&self) -> String {
+        match (&self.kind, &self.shortcut) {
+            (MenuItemKind::Submenu(_), _) => SUBMENU_GLYPH.to_string(),
+            (_, Some(shortcut)) => shortcut.to_string(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// A classic desktop menu bar with dropdown menus and submenus.
+///
+/// The menu bar owns its menus and their navigation state. Key and mouse
+/// events are forwarded to it, and it returns the action of the activated
+/// item:
+///
+/// - `F10` opens the first menu and `Alt` with the first letter of a title
+///   opens that menu.
+/// - The arrow keys move through the items and menus, `Enter` activates the
+///   highlighted item and `Esc` closes the innermost menu.
+/// - Hovering with the mouse highlights items and switches between open
+///   menus, and clicking activates them.
+/// - While the menus are closed, the item accelerators are active.
+///
+/// The first row of the area holds the titles. Dropdowns are drawn below it,
+/// over whatever was rendered there, so render the menu bar last.
+///
+/// ```rust no_run
+/// use ratzilla::widgets::{MenuBar, MenuItem};
+///
+/// #[derive(Clone)]
+/// enum Action {
+///     Open,
+///     Quit,
+///     Zoom(u8),
+/// }
+///
+/// let mut menu_bar = MenuBar::new()
+///     .menu(
+///         "File",
+///         vec![
+///             MenuItem::new("Open", Action::Open).shortcut("ctrl+o".parse().unwrap()),
+///             MenuItem::separator(),
+///             MenuItem::new("Quit", Action::Quit),
+///         ],
+///     )
+///     .menu(
+///         "View",
+///         vec![MenuItem::submenu(
+///             "Zoom",
+///             vec![MenuItem::new("100%", Action::Zoom(1)), MenuItem::new("200%", Action::Zoom(2))],
+///         )],
+///     );
+///
+/// // frame.render_widget(&mut menu_bar, frame.area());
+/// // and on key events: menu_bar.handle_key_event(&event);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MenuBar<A> {
+    /// The menus, as submenu items.
+    menus: Vec<MenuItem<A>>,
+    /// Path of the open menus: the index of the open menu, followed by the
+    /// index of the item of each open submenu.
+    open: Vec<usize>,
+    /// Index of the highlighted item of the innermost open menu.
+    highlight: Option<usize>,
+    /// Style of the bar and the dropdowns.
+    style: Style,
+    /// Style of the open menu and the highlighted items.
+    highlight_style: Style,
+    /// Style of the disabled items.
+    disabled_style: Style,
+    /// Areas of the rendered titles.
+    titles: Vec<Rect>,
+    /// Areas of the rendered dropdowns and their items, from the outermost.
+    dropdowns: Vec<(Rect, Vec<Rect>)>,
+}
+
+impl<A> Default for MenuBar<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> MenuBar<A> {
+    /// Constructs a new, empty [`MenuBar`].
+    pub fn new() -> Self {
+        Self {
+            menus: Vec::new(),
+            open: Vec::new(),
+            highlight: None,
+            style: Style::default(),
+            highlight_style: Style::new().add_modifier(Modifier::REVERSED),
+            disabled_style: Style::new().add_modifier(Modifier::DIM),
+            titles: Vec::new(),
+            dropdowns: Vec::new(),
+        }
+    }
+
+    /// Adds a menu with the given title and items.
+    pub fn menu(mut self, title: &str, items: Vec<MenuItem<A>>) -> Self {
+        self.menus.push(MenuItem::submenu(title, items));
+        self
+    }
+
+    /// Sets the style of the bar and the dropdowns.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the open menu and the highlighted items.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = style;
+        self
+    }
+
+    /// Sets the style of the disabled items.
+    pub fn disabled_style(mut self, style: Style) -> Self {
+        self.disabled_style = style;
+        self
+    }
+
+    /// Returns `true` if a menu is open.
+    pub fn is_open(&self) -> bool {
+        !self.open.is_empty()
+    }
+
+    /// Opens the menu at the given index.
+    pub fn open_menu(&mut self, index: usize) {
+        if let Some(menu) = self.menus.get(index) {
+            self.highlight = first_selectable(menu.children());
+            self.open = vec![index];
+        }
+    }
+
+    /// Closes all menus.
+    pub fn close(&mut self) {
+        self.open.clear();
+        self.highlight = None;
+    }
+
+    /// Returns the items of the open menu at the given depth.
+    fn items_at(&self, level: usize) -> &[MenuItem<A>] {
+        let mut items = self.menus[self.open[0]].children();
+        for &index in &self.open[1..=level] {
+            items = items[index].children();
+        }
+        items
+    }
+
+    /// Moves the highlight of the innermost menu by `step` selectable items.
+    fn move_highlight(&mut self, step: isize) {
+        let items = self.items_at(self.open.len() - 1);
+        let len = items.len() as isize;
+        let mut index = match self.highlight {
+            Some(index) => index as isize,
+            None if step > 0 => -1,
+            None => len,
+        };
+        for _ in 0..len {
+            index = (index + step).rem_euclid(len);
+            if items[index as usize].is_selectable() {
+                self.highlight = Some(index as usize);
+                return;
+            }
+        }
+    }
+
+    /// Opens the menu next to the open one, `step` menus away.
+    fn switch_menu(&mut self, step: isize) {
+        let len = self.menus.len() as isize;
+        self.open_menu((self.open[0] as isize + step).rem_euclid(len) as usize);
+    }
+}
+
+impl<A: Clone> MenuBar<A> {
+    /// Handles a key event, returning the action of the activated item.
+    pub fn handle_key_event(&mut self, event: &KeyEvent) -> Option<A> {
+        if !self.is_open() {
+            let index = match event.code {
+                KeyCode::F(10) => Some(0),
+                KeyCode::Char(c) if event.alt => self.menus.iter().position(|menu| {
+                    menu.label
+                        .chars()
+                        .next()
+                        .is_some_and(|first| first.eq_ignore_ascii_case(&c))
+                }),
+                _ => None,
+            };
+            match index {
+                Some(index) => self.open_menu(index),
+                None => return find_shortcut(&self.menus, event).cloned(),
+            }
+            return None;
+        }
+
+        match event.code {
+            KeyCode::F(10) => self.close(),
+            KeyCode::Esc | KeyCode::Left if self.open.len() > 1 => {
+                self.highlight = self.open.pop();
+            }
+            KeyCode::Esc => self.close(),
+            KeyCode::Left => self.switch_menu(-1),
+            KeyCode::Up => self.move_highlight(-1),
+            KeyCode::Down => self.move_highlight(1),
+            KeyCode::Right => {
+                let opens_submenu = self.highlight.is_some_and(|index| {
+                    let item = &self.items_at(self.open.len() - 1)[index];
+                    item.enabled && matches!(item.kind, MenuItemKind::Submenu(_))
+                });
+                if opens_submenu {
+                    return self.activate();
+                }
+                self.switch_menu(1);
+            }
+            KeyCode::Enter => return self.activate(),
+            _ => {}
+        }
+        None
+    }
+
+    /// Handles a mouse button press at the given cell position, returning
+    /// the action of the clicked item.
+    ///
+    /// Clicking a title toggles its menu and clicking outside of the menus
+    /// closes them.
+    pub fn mouse_down(&mut self, column: u16, row: u16) -> Option<A> {
+        let position = Position::new(column, row);
+        if let Some(index) = self.titles.iter().position(|r| r.contains(position)) {
+            if self.open.first() == Some(&index) {
+                self.close();
+            } else {
+                self.open_menu(index);
+            }
+            return None;
+        }
+        for level in (0..self.dropdowns.len().min(self.open.len())).rev() {
+            let (area, items) = &self.dropdowns[level];
+            if let Some(index) = items.iter().position(|r| r.contains(position)) {
+                self.open.truncate(level + 1);
+                self.highlight = Some(index);
+                return self.activate();
+            }
+            if area.contains(position) {
+                return None;
+            }
+        }
+        self.close();
+        None
+    }
+
+    /// Handles a mouse movement to the given cell position.
+    ///
+    /// While a menu is open, hovering another title opens its menu and
+    /// hovering an item highlights it, opening its submenu if it has one.
+    pub fn mouse_move(&mut self, column: u16, row: u16) {
+        if !self.is_open() {
+            return;
+        }
+        let position = Position::new(column, row);
+        if let Some(index) = self.titles.iter().position(|r| r.contains(position)) {
+            if self.open[0] != index {
+                self.open_menu(index);
+            }
+            return;
+        }
+        for level in (0..self.dropdowns.len().min(self.open.len())).rev() {
+            let Some(index) = self.dropdowns[level]
+                .1
+                .iter()
+                .position(|r| r.contains(position))
+            else {
+                continue;
+            };
+            if self.open.len() > level + 1 && self.open[level + 1] == index {
+                // the submenu of this item is already open
+                return;
+            }
+            self.open.truncate(level + 1);
+            let item = &self.items_at(level)[index];
+            let is_submenu = item.enabled && matches!(item.kind, MenuItemKind::Submenu(_));
+            self.highlight = item.is_selectable().then_some(index);
+            if is_submenu {
+                self.open.push(index);
+                self.highlight = None;
+            }
+            return;
+        }
+    }
+
+    /// Activates the highlighted item of the innermost menu.
+    fn activate(&mut self) -> Option<A> {
+        let index = self.highlight?;
+        let item = &self.items_at(self.open.len() - 1)[index];
+        if !item.enabled {
+            return None;
+        }
+        match &item.kind {
+            MenuItemKind::Action(action) => {
+                let action = action.clone();
+                self.close();
+                Some(action)
+            }
+            MenuItemKind::Submenu(items) => {
+                self.highlight = first_selectable(items);
+                self.open.push(index);
+                None
+            }
+            MenuItemKind::Separator => None,
+        }
+    }
+}
+
+impl<A> Widget for &mut MenuBar<A> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        let mut titles = Vec::new();
+        let mut dropdowns = Vec::new();
+        if !area.is_empty() {
+            let bar = Rect { height: 1, ..area };
+            buf.set_style(bar, self.style);
+            let mut x = bar.x;
+            for (index, menu) in self.menus.iter().enumerate() {
+                let width = menu.label.width() as u16 + 2;
+                if x + width > bar.right() {
+                    break;
+                }
+                let title = Rect::new(x, bar.y, width, 1);
+                let style = if self.open.first() == Some(&index) {
+                    self.style.patch(self.highlight_style)
+                } else {
+                    self.style
+                };
+                buf.set_style(title, style);
+                buf.set_string(x + 1, bar.y, &menu.label, style);
+                titles.push(title);
+                x += width;
+            }
+
+            if let Some(title) = self.open.first().and_then(|&i| titles.get(i)) {
+                let mut anchor = Position::new(title.x, bar.y + 1);
+                for level in 0..self.open.len() {
+                    let highlighted = match self.open.get(level + 1) {
+                        Some(&index) => Some(index),
+                        None => self.highlight,
+                    };
+                    let (dropdown, items) =
+                        self.render_dropdown(self.items_at(level), anchor, highlighted, buf);
+                    let Some(parent) = self.open.get(level + 1).and_then(|&i| items.get(i)) else {
+                        dropdowns.push((dropdown, items));
+                        break;
+                    };
+                    // align the first item of the submenu with its parent item
+                    anchor = Position::new(dropdown.right(), parent.y.saturating_sub(1));
+                    dropdowns.push((dropdown, items));
+                }
+            }
+        }
+        self.titles = titles;
+        self.dropdowns = dropdowns;
+    }
+}
+
+impl<A> MenuBar<A> {
+    /// Renders a dropdown at the given position, returning its area and the
+    /// areas of its items.
+    fn render_dropdown(
+        &self,
+        items: &[MenuItem<A>],
+        anchor: Position,
+        highlighted: Option<usize>,
+        buf: &mut Buffer,
+    ) -> (Rect, Vec<Rect>) {
+        let label_width = items.iter().map(|i| i.label.width()).max().unwrap_or(0);
+        let hint_width = items.iter().map(|i| i.hint().width()).max().unwrap_or(0);
+        let inner_width = 1 + label_width + if hint_width > 0 { 2 + hint_width } else { 0 } + 1;
+        let width = (inner_width as u16 + 2).min(buf.area.width);
+        let height = (items.len() as u16 + 2).min(buf.area.height);
+        // keep the dropdown on screen
+        let x = anchor.x.min(buf.area.right().saturating_sub(width));
+        let y = anchor.y.min(buf.area.bottom().saturating_sub(height));
+        let area = Rect::new(x, y, width, height).intersection(buf.area);
+
+        Clear.render(area, buf);
+        Block::bordered().style(self.style).render(area, buf);
+        let mut hits = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            let y = area.y + 1 + index as u16;
+            if y + 1 >= area.bottom() || area.width < 2 {
+                break;
+            }
+            let row = Rect::new(area.x + 1, y, area.width - 2, 1);
+            hits.push(row);
+            if let MenuItemKind::Separator = item.kind {
+                buf.set_string(area.x, y, "├", self.style);
+                buf.set_string(row.x, y, "─".repeat(row.width as usize), self.style);
+                buf.set_string(row.right(), y, "┤", self.style);
+                continue;
+            }
+            let mut style = self.style;
+            if !item.enabled {
+                style = style.patch(self.disabled_style);
+            } else if highlighted == Some(index) {
+                style = style.patch(self.highlight_style);
+            }
+            buf.set_style(row, style);
+            buf.set_stringn(
+                row.x + 1,
+                y,
+                &item.label,
+                row.width.saturating_sub(1) as usize,
+                style,
+            );
+            let hint = item.hint();
+            let hint_x = row.right().saturating_sub(1 + hint.width() as u16);
+            if hint_x > row.x {
+                buf.set_string(hint_x, y, hint, style);
+            }
+        }
+        (area, hits)
+    }
+}
+
+/// Returns the index of the first item that can be highlighted.
+fn first_selectable<A>(items: &[MenuItem<A>]) -> Option<usize> {
+    items.iter().position(MenuItem::is_selectable)
+}
+
+/// Returns the action of the enabled item whose accelerator matches the key event.
+fn find_shortcut<'a, A>(items: &'a [MenuItem<A>], event: &KeyEvent) -> Option<&'a A> {
+    items
+        .iter()
+        .filter(|item| item.enabled)
+        .find_map(|item| match &item.kind {
+            MenuItemKind::Action(action)
+                if item.shortcut.as_ref().is_some_and(|s| s.matches(event)) =>
+            {
+                Some(action)
+            }
+            MenuItemKind::Submenu(items) => find_shortcut(items, event),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent {
+            code,
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+            repeat: false,
+        }
+    }
+
+    fn menu_bar() -> MenuBar<&'static str> {
+        MenuBar::new()
+            .highlight_style(Style::new())
+            .menu(
+                "File",
+                vec![
+                    MenuItem::new("New", "new").shortcut("ctrl+n".parse().expect("valid shortcut")),
+                    MenuItem::separator(),
+                    MenuItem::submenu("Recent", vec![MenuItem::new("a.txt", "a.txt")]),
+                    MenuItem::new("Quit", "quit"),
+                ],
+            )
+            .menu("Edit", vec![MenuItem::new("Undo", "undo")])
+    }
+
+    #[test]
+    fn test_keyboard_navigation() {
+        let mut menu_bar = menu_bar();
+        assert_eq!(menu_bar.handle_key_event(&key(KeyCode::F(10))), None);
+        assert!(menu_bar.is_open());
+        assert_eq!(menu_bar.handle_key_event(&key(KeyCode::Down)), None);
+        assert_eq!(menu_bar.highlight, Some(2));
+        assert_eq!(menu_bar.handle_key_event(&key(KeyCode::Right)), None);
+        assert_eq!(menu_bar.open, vec![0, 2]);
+        assert_eq!(
+            menu_bar.handle_key_event(&key(KeyCode::Enter)),
+            Some("a.txt")
+        );
+        assert!(!menu_bar.is_open());
+
+        menu_bar.open_menu(0);
+        assert_eq!(menu_bar.handle_key_event(&key(KeyCode::Right)), None);
+        assert_eq!(menu_bar.open, vec![1]);
+        assert_eq!(menu_bar.handle_key_event(&key(KeyCode::Esc)), None);
+        assert!(!menu_bar.is_open());
+    }
+
+    #[test]
+    fn test_shortcuts() {
+        let mut menu_bar = menu_bar();
+        let mut event = key(KeyCode::Char('n'));
+        event.ctrl = true;
+        assert_eq!(menu_bar.handle_key_event(&event), Some("new"));
+
+        let mut event = key(KeyCode::Char('e'));
+        event.alt = true;
+        assert_eq!(menu_bar.handle_key_event(&event), None);
+        assert_eq!(menu_bar.open, vec![1]);
+    }
+
+    #[test]
+    fn test_render_and_mouse() {
+        let mut menu_bar = menu_bar();
+        let area = Rect::new(0, 0, 20, 7);
+        let mut buf = Buffer::empty(area);
+        menu_bar.render(area, &mut buf);
+        assert_eq!(menu_bar.mouse_down(2, 0), None);
+        menu_bar.render(area, &mut buf);
+        assert_eq!(
+            buf,
+            Buffer::with_lines([
+                " File  Edit         ",
+                "┌────────────────┐  ",
+                "│ New     Ctrl+N │  ",
+                "├────────────────┤  ",
+                "│ Recent       ▸ │  ",
+                "│ Quit           │  ",
+                "└────────────────┘  ",
+            ])
+        );
+
+        menu_bar.mouse_move(3, 4);
+        assert_eq!(menu_bar.open, vec![0, 2]);
+        menu_bar.render(area, &mut buf);
+        assert_eq!(menu_bar.mouse_down(3, 5), Some("quit"));
+        assert!(!menu_bar.is_open());
+    }
+}
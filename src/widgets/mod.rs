@@ -2,6 +2,37 @@
 //!
 //! **Ratzilla** provides web-only widgets that you can use while building TUIs.
 
+pub(crate) mod breadcrumb;
 pub(crate) mod hyperlink;
+pub(crate) mod image;
+pub(crate) mod keymap_help;
+pub(crate) mod menu_bar;
+pub(crate) mod particles;
+pub(crate) mod progress;
+pub(crate) mod screensaver;
+pub(crate) mod settings_screen;
+pub(crate) mod sixel;
+pub(crate) mod splitter;
+pub(crate) mod status_bar;
+pub(crate) mod suggestions;
+pub(crate) mod tab_bar;
+pub(crate) mod virtual_list;
 
+/// Random number generation for animated widgets.
+pub(crate) mod rng;
+
+pub use breadcrumb::{Breadcrumb, BreadcrumbState};
 pub use hyperlink::Hyperlink;
+pub use image::Image;
+pub use keymap_help::KeymapHelp;
+pub use menu_bar::{MenuBar, MenuItem};
+pub use particles::{ParticleEmitter, ParticleSystem};
+pub use progress::{ProgressBar, TaskList};
+pub use screensaver::{Screensaver, ScreensaverKind, ScreensaverState};
+pub use settings_screen::{SettingsScreen, SettingsScreenState};
+pub use sixel::SixelImage;
+pub use splitter::{Splitter, SplitterState};
+pub use status_bar::{Segment, StatusBar, StatusBarState};
+pub use suggestions::{SuggestionEvent, SuggestionPopup, SuggestionState};
+pub use tab_bar::{TabAction, TabBar, TabBarState};
+pub use virtual_list::{KeyedList, ListChange, VirtualList, VirtualListState};
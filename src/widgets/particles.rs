@@ -0,0 +1,350 @@
+use std::{f32::consts::PI, rc::Rc, time::Duration};
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+
+use crate::widgets::rng::Rng;
+
+/// Describes how newly spawned particles look and move.
+///
+/// Glyphs and colors are ramps: a particle walks through them from the first
+/// to the last entry over the course of its lifetime.
+///
+/// ```rust no_run
+/// use ratzilla::widgets::ParticleEmitter;
+/// use ratzilla::ratatui::style::Color;
+///
+/// let sparks = ParticleEmitter::new()
+///     .glyphs(&['*', '+', '.'])
+///     .colors(&[Color::Yellow, Color::LightRed, Color::DarkGray])
+///     .lifetime(0.3, 0.8)
+///     .speed(10.0, 25.0)
+///     .gravity(20.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParticleEmitter {
+    /// Glyph ramp.
+    glyphs: Rc<[char]>,
+    /// Color ramp.
+    colors: Rc<[Color]>,
+    /// Lifetime range, in seconds.
+    lifetime: (f32, f32),
+    /// Initial speed range, in cells per second.
+    speed: (f32, f32),
+    /// Emission angle range, in radians. `0` points right, `PI / 2` points down.
+    angle: (f32, f32),
+    /// Downwards acceleration, in cells per second squared.
+    gravity: f32,
+}
+
+impl Default for ParticleEmitter {
+    fn default() -> Self {
+        Self {
+            glyphs: Rc::from(['*', '+', '·'].as_slice()),
+            colors: Rc::from([Color::White, Color::Gray, Color::DarkGray].as_slice()),
+            lifetime: (0.5, 1.5),
+            speed: (5.0, 15.0),
+            angle: (0.0, 2.0 * PI),
+            gravity: 0.0,
+        }
+    }
+}
+
+impl ParticleEmitter {
+    /// Constructs a new [`ParticleEmitter`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Confetti bursting upwards and falling down.
+    pub fn confetti() -> Self {
+        Self::new()
+            .glyphs(&['■', '▪', '▴', '•'])
+            .colors(&[
+                Color::LightRed,
+                Color::LightYellow,
+                Color::LightGreen,
+                Color::LightCyan,
+                Color::LightMagenta,
+            ])
+            .lifetime(1.0, 2.5)
+            .speed(10.0, 30.0)
+            .angle(1.25 * PI, 1.75 * PI)
+            .gravity(25.0)
+    }
+
+    /// Short lived sparks flying in every direction.
+    pub fn sparks() -> Self {
+        Self::new()
+            .glyphs(&['*', '+', '.'])
+            .colors(&[
+                Color::Rgb(255, 240, 160),
+                Color::Yellow,
+                Color::Rgb(120, 40, 0),
+            ])
+            .lifetime(0.2, 0.6)
+            .speed(15.0, 40.0)
+    }
+
+    /// Slowly falling snow flakes.
+    pub fn snow() -> Self {
+        Self::new()
+            .glyphs(&['❄', '*', '·'])
+            .colors(&[Color::White, Color::Gray])
+            .lifetime(4.0, 8.0)
+            .speed(1.0, 3.0)
+            .angle(0.4 * PI, 0.6 * PI)
+    }
+
+    /// Sets the glyph ramp.
+    pub fn glyphs(mut self, glyphs: &[char]) -> Self {
+        self.glyphs = Rc::from(glyphs);
+        self
+    }
+
+    /// Sets the color ramp.
+    pub fn colors(mut self, colors: &[Color]) -> Self {
+        self.colors = Rc::from(colors);
+        self
+    }
+
+    /// Sets the lifetime range of the particles, in seconds.
+    pub fn lifetime(mut self, min: f32, max: f32) -> Self {
+        self.lifetime = (min, max);
+        self
+    }
+
+    /// Sets the initial speed range of the particles, in cells per second.
+    pub fn speed(mut self, min: f32, max: f32) -> Self {
+        self.speed = (min, max);
+        self
+    }
+
+    /// Sets the emission angle range, in radians.
+    ///
+    /// `0` points to the right and angles grow clockwise, as the y axis
+    /// points down in terminal coordinates.
+    pub fn angle(mut self, min: f32, max: f32) -> Self {
+        self.angle = (min, max);
+        self
+    }
+
+    /// Sets the downwards acceleration, in cells per second squared.
+    pub fn gravity(mut self, gravity: f32) -> Self {
+        self.gravity = gravity;
+        self
+    }
+}
+
+/// A single particle.
+#[derive(Debug, Clone)]
+struct Particle {
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    gravity: f32,
+    age: f32,
+    lifetime: f32,
+    glyphs: Rc<[char]>,
+    colors: Rc<[Color]>,
+}
+
+impl Particle {
+    /// Returns how far the particle is through its lifetime, from `0.0` to `1.0`.
+    fn progress(&self) -> f32 {
+        (self.age / self.lifetime).clamp(0.0, 1.0)
+    }
+}
+
+/// Picks the ramp entry matching the given progress.
+fn ramp<T: Copy>(ramp: &[T], progress: f32) -> Option<T> {
+    let index = (progress * ramp.len() as f32) as usize;
+    ramp.get(index.min(ramp.len().saturating_sub(1))).copied()
+}
+
+/// A lightweight particle system.
+///
+/// Particles are positioned in cell coordinates relative to the area the
+/// system is rendered into. Rendering only touches the cells occupied by
+/// particles, so the system can be drawn on top of the rest of the UI.
+///
+/// ```rust no_run
+/// use std::time::Duration;
+/// use ratzilla::widgets::{ParticleEmitter, ParticleSystem};
+///
+/// let mut particles = ParticleSystem::new();
+/// particles.emit(&ParticleEmitter::confetti(), 40.0, 12.0, 50);
+///
+/// // On every frame:
+/// particles.update(Duration::from_millis(16));
+/// // frame.render_widget(&particles, frame.area());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParticleSystem {
+    /// Live particles.
+    particles: Vec<Particle>,
+    /// Maximum number of live particles.
+    capacity: usize,
+    /// Random number generator.
+    rng: Rng,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParticleSystem {
+    /// Default maximum number of live particles.
+    const DEFAULT_CAPACITY: usize = 2048;
+
+    /// Constructs a new, empty [`ParticleSystem`].
+    pub fn new() -> Self {
+        Self::with_rng(Rng::from_entropy())
+    }
+
+    /// Constructs a new [`ParticleSystem`] with a fixed random seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(Rng::with_seed(seed))
+    }
+
+    fn with_rng(rng: Rng) -> Self {
+        Self {
+            particles: Vec::new(),
+            capacity: Self::DEFAULT_CAPACITY,
+            rng,
+        }
+    }
+
+    /// Sets the maximum number of live particles.
+    ///
+    /// Particles emitted while the system is full are dropped.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Spawns `count` particles at the given position.
+    pub fn emit(&mut self, emitter: &ParticleEmitter, x: f32, y: f32, count: usize) {
+        let count = count.min(self.capacity.saturating_sub(self.particles.len()));
+        for _ in 0..count {
+            let angle = self.rng.range_f32(emitter.angle.0, emitter.angle.1);
+            let speed = self.rng.range_f32(emitter.speed.0, emitter.speed.1);
+            self.particles.push(Particle {
+                x,
+                y,
+                // cells are roughly twice as high as they are wide
+                vx: angle.cos() * speed,
+                vy: angle.sin() * speed * 0.5,
+                gravity: emitter.gravity,
+                age: 0.0,
+                lifetime: self.rng.range_f32(emitter.lifetime.0, emitter.lifetime.1),
+                glyphs: emitter.glyphs.clone(),
+                colors: emitter.colors.clone(),
+            });
+        }
+    }
+
+    /// Advances the simulation and removes expired particles.
+    pub fn update(&mut self, elapsed: Duration) {
+        let dt = elapsed.as_secs_f32();
+        self.particles.retain_mut(|p| {
+            p.age += dt;
+            p.vy += p.gravity * dt;
+            p.x += p.vx * dt;
+            p.y += p.vy * dt;
+            p.age < p.lifetime
+        });
+    }
+
+    /// Returns the number of live particles.
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Returns `true` if there are no live particles.
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Removes all particles.
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+}
+
+impl Widget for &ParticleSystem {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        for particle in &self.particles {
+            if particle.x < 0.0 || particle.y < 0.0 {
+                continue;
+            }
+            let (x, y) = (particle.x as u16, particle.y as u16);
+            if x >= area.width || y >= area.height {
+                continue;
+            }
+
+            let progress = particle.progress();
+            let cell = &mut buf[(area.x + x, area.y + y)];
+            if let Some(glyph) = ramp(&particle.glyphs, progress) {
+                cell.set_char(glyph);
+            }
+            if let Some(color) = ramp(&particle.colors, progress) {
+                cell.set_fg(color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramp() {
+        let glyphs = ['a', 'b', 'c'];
+        assert_eq!(ramp(&glyphs, 0.0), Some('a'));
+        assert_eq!(ramp(&glyphs, 0.5), Some('b'));
+        assert_eq!(ramp(&glyphs, 1.0), Some('c'));
+        assert_eq!(ramp::<char>(&[], 0.5), None);
+    }
+
+    #[test]
+    fn test_particles_expire() {
+        let emitter = ParticleEmitter::new().lifetime(1.0, 1.0);
+        let mut system = ParticleSystem::with_seed(42);
+        system.emit(&emitter, 5.0, 5.0, 10);
+        assert_eq!(system.len(), 10);
+
+        system.update(Duration::from_millis(500));
+        assert_eq!(system.len(), 10);
+
+        system.update(Duration::from_millis(600));
+        assert!(system.is_empty());
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut system = ParticleSystem::with_seed(7).capacity(4);
+        system.emit(&ParticleEmitter::sparks(), 0.0, 0.0, 10);
+        assert_eq!(system.len(), 4);
+    }
+
+    #[test]
+    fn test_render_only_touches_particle_cells() {
+        let emitter = ParticleEmitter::new()
+            .glyphs(&['x'])
+            .colors(&[Color::Red])
+            .speed(0.0, 0.0);
+        let mut system = ParticleSystem::with_seed(1);
+        system.emit(&emitter, 2.0, 1.0, 1);
+
+        let mut buf = Buffer::with_lines(["....", "...."]);
+        (&system).render(buf.area, &mut buf);
+        assert_eq!(buf[(2, 1)].symbol(), "x");
+        assert_eq!(buf[(2, 1)].fg, Color::Red);
+        assert_eq!(buf[(1, 1)].symbol(), ".");
+    }
+}
@@ -0,0 +1,364 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Widget,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    clock::now,
+    tasks::{TaskInfo, TaskScheduler, TaskStatus},
+};
+
+/// Partially filled cells, by eighths.
+const EIGHTHS: [&str; 8] = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+
+/// Frames of the spinner shown next to running tasks.
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Duration of a spinner frame, in milliseconds.
+const SPINNER_FRAME_MS: f64 = 80.0;
+
+/// Duration of a back and forth sweep of an indeterminate bar, in milliseconds.
+const SWEEP_PERIOD_MS: f64 = 2000.0;
+
+/// A single line progress bar.
+///
+/// A determinate bar fills up with the progress, with a precision of an
+/// eighth of a cell. An indeterminate bar (without progress) shows a block
+/// sweeping back and forth, animated with the current time.
+///
+/// ```rust no_run
+/// use ratzilla::widgets::ProgressBar;
+///
+/// let download = ProgressBar::new(Some(0.42)).label("Downloading");
+/// let connecting = ProgressBar::new(None).label("Connecting");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProgressBar<'a> {
+    /// Progress, from `0.0` to `1.0`, or `None` if indeterminate.
+    progress: Option<f32>,
+    /// Label shown before the bar.
+    label: Option<&'a str>,
+    /// Whether the percentage is shown after a determinate bar.
+    show_percentage: bool,
+    /// Style of the label, the percentage and the empty part of the bar.
+    style: Style,
+    /// Style of the filled part of the bar.
+    filled_style: Style,
+    /// Animation time, in milliseconds.
+    phase: Option<f64>,
+}
+
+impl<'a> ProgressBar<'a> {
+    /// Constructs a new [`ProgressBar`], indeterminate if `progress` is `None`.
+    pub fn new(progress: Option<f32>) -> Self {
+        Self {
+            progress: progress.map(|p| p.clamp(0.0, 1.0)),
+            label: None,
+            show_percentage: true,
+            style: Style::default(),
+            filled_style: Style::new().fg(Color::Cyan),
+            phase: None,
+        }
+    }
+
+    /// Constructs a new [`ProgressBar`] showing the progress of a task.
+    ///
+    /// Running tasks that didn't report their progress are indeterminate.
+    pub fn for_task(task: &'a TaskInfo) -> Self {
+        let progress = match task.status {
+            TaskStatus::Completed => Some(1.0),
+            _ => task.progress,
+        };
+        Self::new(progress).label(task.message.as_deref().unwrap_or(&task.name))
+    }
+
+    /// Sets the label shown before the bar.
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Sets whether the percentage is shown after a determinate bar.
+    pub fn show_percentage(mut self, show: bool) -> Self {
+        self.show_percentage = show;
+        self
+    }
+
+    /// Sets the style of the label, the percentage and the empty part of the bar.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the filled part of the bar.
+    pub fn filled_style(mut self, style: Style) -> Self {
+        self.filled_style = style;
+        self
+    }
+
+    /// Sets the animation time of an indeterminate bar, in milliseconds.
+    ///
+    /// Defaults to the current time.
+    pub fn phase(mut self, phase_ms: f64) -> Self {
+        self.phase = Some(phase_ms);
+        self
+    }
+}
+
+impl Widget for ProgressBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        if area.is_empty() {
+            return;
+        }
+        buf.set_style(area, self.style);
+        let (mut x, y) = (area.x, area.y);
+        if let Some(label) = self.label {
+            x = buf
+                .set_stringn(x, y, label, area.width as usize, self.style)
+                .0;
+            if x < area.right() {
+                x += 1;
+            }
+        }
+        let mut width = area.right() - x;
+
+        let Some(progress) = self.progress else {
+            let block = (width / 4).max(1);
+            let travel = width.saturating_sub(block) as f64;
+            let t = self.phase.unwrap_or_else(now).rem_euclid(SWEEP_PERIOD_MS) / SWEEP_PERIOD_MS;
+            let position = if t < 0.5 { t * 2.0 } else { 2.0 - t * 2.0 };
+            let start = x + (position * travel).round() as u16;
+            buf.set_string(
+                start,
+                y,
+                "█".repeat(block.min(area.right() - start) as usize),
+                self.filled_style,
+            );
+            return;
+        };
+
+        if self.show_percentage && width > 5 {
+            width -= 5;
+            let percentage = format!(" {:>3}%", (progress * 100.0).round() as u8);
+            buf.set_string(x + width, y, percentage, self.style);
+        }
+        let eighths = (progress * width as f32 * 8.0).round() as usize;
+        let filled = format!("{}{}", "█".repeat(eighths / 8), EIGHTHS[eighths % 8]);
+        buf.set_stringn(x, y, filled, width as usize, self.filled_style);
+    }
+}
+
+/// A list of the tasks of a [`TaskScheduler`], with their status, progress
+/// and last message.
+///
+/// The list reads the tasks from the scheduler when it is constructed, so
+/// building it in the [`draw_web`] callback keeps it up to date without any
+/// bookkeeping.
+///
+/// ```rust no_run
+/// use ratzilla::{tasks::TaskScheduler, widgets::TaskList};
+///
+/// let scheduler = TaskScheduler::new();
+/// // in the draw callback:
+/// // frame.render_widget(TaskList::new(&scheduler), area);
+/// ```
+///
+/// [`draw_web`]: crate::WebRenderer::draw_web
+#[derive(Debug, Clone)]
+pub struct TaskList {
+    /// The tasks to show.
+    tasks: Vec<TaskInfo>,
+    /// Width of the progress bars, including the percentage.
+    bar_width: u16,
+    /// Style of the list.
+    style: Style,
+    /// Style of the filled part of the progress bars.
+    filled_style: Style,
+    /// Style of the failed tasks.
+    failed_style: Style,
+    /// Animation time, in milliseconds.
+    phase: Option<f64>,
+}
+
+impl TaskList {
+    /// Constructs a new [`TaskList`] showing the tasks of the scheduler.
+    pub fn new(scheduler: &TaskScheduler) -> Self {
+        Self {
+            tasks: scheduler.tasks(),
+            bar_width: 20,
+            style: Style::default(),
+            filled_style: Style::new().fg(Color::Cyan),
+            failed_style: Style::new().fg(Color::Red),
+            phase: None,
+        }
+    }
+
+    /// Hides the tasks that are no longer running.
+    pub fn hide_finished(mut self, hide: bool) -> Self {
+        if hide {
+            self.tasks.retain(|t| !t.status.is_finished());
+        }
+        self
+    }
+
+    /// Sets the width of the progress bars, including the percentage.
+    pub fn bar_width(mut self, width: u16) -> Self {
+        self.bar_width = width;
+        self
+    }
+
+    /// Sets the style of the list.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the filled part of the progress bars.
+    pub fn filled_style(mut self, style: Style) -> Self {
+        self.filled_style = style;
+        self
+    }
+
+    /// Sets the style of the failed tasks.
+    pub fn failed_style(mut self, style: Style) -> Self {
+        self.failed_style = style;
+        self
+    }
+
+    /// Sets the animation time of the spinners and indeterminate bars, in
+    /// milliseconds.
+    ///
+    /// Defaults to the current time.
+    pub fn phase(mut self, phase_ms: f64) -> Self {
+        self.phase = Some(phase_ms);
+        self
+    }
+}
+
+impl Widget for TaskList {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        if area.is_empty() {
+            return;
+        }
+        buf.set_style(area, self.style);
+        let phase = self.phase.unwrap_or_else(now);
+        let spinner = SPINNER[(phase / SPINNER_FRAME_MS) as usize % SPINNER.len()];
+        let name_width = self
+            .tasks
+            .iter()
+            .map(|t| t.name.width() as u16)
+            .max()
+            .unwrap_or(0)
+            .min(area.width / 3);
+
+        for (task, y) in self.tasks.iter().zip(area.top()..area.bottom()) {
+            let (icon, style, message) = match &task.status {
+                TaskStatus::Running => (spinner, self.style, task.message.as_deref()),
+                TaskStatus::Completed => ("✓", self.style, task.message.as_deref()),
+                TaskStatus::Failed(error) => ("✗", self.failed_style, Some(error.as_str())),
+                TaskStatus::Cancelled => ("⊘", self.style, Some("cancelled")),
+            };
+            buf.set_stringn(area.x, y, icon, area.width as usize, style);
+            let name_x = area.x + 2;
+            if name_x >= area.right() {
+                continue;
+            }
+            buf.set_stringn(name_x, y, &task.name, name_width as usize, style);
+
+            let bar_x = name_x + name_width + 1;
+            if bar_x >= area.right() {
+                continue;
+            }
+            let bar_width = self.bar_width.min(area.right() - bar_x);
+            let progress = match task.status {
+                TaskStatus::Completed => Some(1.0),
+                _ => task.progress,
+            };
+            let filled_style = if let TaskStatus::Failed(_) = task.status {
+                self.failed_style
+            } else {
+                self.filled_style
+            };
+            ProgressBar::new(progress)
+                .style(self.style)
+                .filled_style(filled_style)
+                .phase(phase)
+                .render(Rect::new(bar_x, y, bar_width, 1), buf);
+
+            let message_x = bar_x + bar_width + 1;
+            if let Some(message) = message.filter(|_| message_x < area.right()) {
+                buf.set_stringn(
+                    message_x,
+                    y,
+                    message,
+                    (area.right() - message_x) as usize,
+                    style,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    fn render(widget: impl Widget, width: u16, height: u16) -> Buffer {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        widget.render(area, &mut buf);
+        buf
+    }
+
+    #[test]
+    fn test_determinate_bar() {
+        let bar = ProgressBar::new(Some(0.5)).filled_style(Style::new());
+        assert_eq!(render(bar, 15, 1), Buffer::with_lines(["█████       50%"]));
+
+        let bar = ProgressBar::new(Some(0.55))
+            .show_percentage(false)
+            .filled_style(Style::new());
+        assert_eq!(render(bar, 10, 1), Buffer::with_lines(["█████▌    "]));
+    }
+
+    #[test]
+    fn test_indeterminate_bar() {
+        let bar = ProgressBar::new(None)
+            .filled_style(Style::new())
+            .phase(500.0);
+        assert_eq!(render(bar, 8, 1), Buffer::with_lines(["   ██   "]));
+    }
+
+    #[test]
+    fn test_task_list() {
+        let scheduler = TaskScheduler::new();
+        let context = Rc::new(RefCell::new(None));
+        let _ = scheduler.prepare("a", |ctx| {
+            *context.borrow_mut() = Some(ctx);
+            std::future::pending::<Result<(), String>>()
+        });
+        let _ = scheduler.prepare("bb", |_| std::future::pending::<Result<(), String>>());
+        let ctx = context.borrow_mut().take().expect("task started");
+        ctx.report_progress(0.5);
+        ctx.report_message("chunk");
+
+        let list = TaskList::new(&scheduler)
+            .bar_width(10)
+            .filled_style(Style::new())
+            .phase(0.0);
+        assert_eq!(
+            render(list, 30, 2),
+            Buffer::with_lines([
+                "⠋ a  ██▌    50% chunk         ",
+                "⠋ bb ██                       ",
+            ])
+        );
+    }
+}
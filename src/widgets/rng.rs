@@ -0,0 +1,59 @@
+/// A small xorshift pseudo random number generator.
+///
+/// Animations don't need cryptographic quality randomness, so this avoids
+/// pulling in an extra dependency just to scatter some glyphs around.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Constructs a new [`Rng`] with the given seed.
+    pub(crate) fn with_seed(seed: u64) -> Self {
+        Self {
+            // xorshift gets stuck on zero
+            state: seed.max(1),
+        }
+    }
+
+    /// Constructs a new [`Rng`] seeded from `Math.random()`.
+    pub(crate) fn from_entropy() -> Self {
+        let seed = web_sys::js_sys::Math::random() * u64::MAX as f64;
+        Self::with_seed(seed as u64)
+    }
+
+    /// Returns the next random `u64`.
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    /// Returns a random value in `0..max`.
+    ///
+    /// Returns `0` if `max` is `0`.
+    pub(crate) fn below(&mut self, max: u32) -> u32 {
+        if max == 0 {
+            return 0;
+        }
+        (self.next_u64() % max as u64) as u32
+    }
+
+    /// Returns a random value in `0.0..1.0`.
+    pub(crate) fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Returns a random value in `min..max`.
+    pub(crate) fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// Returns `true` with the given probability.
+    pub(crate) fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+}
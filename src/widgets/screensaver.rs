@@ -0,0 +1,681 @@
+use std::time::Duration;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::StatefulWidget,
+};
+
+use crate::{clock, widgets::rng::Rng};
+
+/// Glyphs used by the matrix rain animation.
+const MATRIX_GLYPHS: &[char] = &[
+    'ｱ', 'ｲ', 'ｳ', 'ｴ', 'ｵ', 'ｶ', 'ｷ', 'ｸ', 'ｹ', 'ｺ', 'ｻ', 'ｼ', 'ｽ', 'ｾ', 'ｿ', 'ﾀ', 'ﾁ', 'ﾂ', 'ﾃ',
+    'ﾄ', '0', '1', '2', '3', '4', '5', '7', '8', '9', 'Z', ':', '=', '*', '+', '<', '>',
+];
+
+/// Number of frames between two generations of the game of life.
+const LIFE_FRAMES_PER_GENERATION: u32 = 4;
+
+/// Number of unchanged generations before the game of life is reseeded.
+const LIFE_STAGNATION_LIMIT: u32 = 30;
+
+/// Default inactivity before the screensaver starts, in milliseconds.
+const DEFAULT_IDLE_TIMEOUT_MS: f64 = 60_000.0;
+
+/// The available idle animations.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ScreensaverKind {
+    /// Falling columns of glyphs.
+    #[default]
+    MatrixRain,
+    /// Stars flying towards the viewer.
+    Starfield,
+    /// Randomly growing pipes drawn with box-drawing characters.
+    Pipes,
+    /// Conway's game of life.
+    GameOfLife,
+}
+
+/// A widget that renders an idle animation.
+///
+/// Every render advances the animation by one step, so rendering it from the
+/// [`draw_web`] callback animates it at the frame rate of the browser.
+///
+/// The animation state lives in [`ScreensaverState`], which is reset
+/// automatically when the rendered area changes size. The state also tracks
+/// the inactivity of the user: record the input events with
+/// [`ScreensaverState::record_activity`] and show the screensaver while
+/// [`ScreensaverState::is_idle`] is `true`.
+///
+/// ```rust no_run
+/// use std::{cell::RefCell, rc::Rc, time::Duration};
+///
+/// use ratzilla::{
+///     widgets::{Screensaver, ScreensaverKind, ScreensaverState},
+///     DomBackend, WebRenderer,
+/// };
+/// use ratatui::{widgets::Paragraph, Terminal};
+///
+/// let terminal = Terminal::new(DomBackend::new()?)?;
+/// let state = Rc::new(RefCell::new(
+///     ScreensaverState::new(ScreensaverKind::Starfield).idle_after(Duration::from_secs(30)),
+/// ));
+///
+/// terminal.on_key_event({
+///     let state = state.clone();
+///     move |_| state.borrow_mut().record_activity()
+/// });
+/// terminal.on_mouse_event({
+///     let state = state.clone();
+///     move |_| state.borrow_mut().record_activity()
+/// });
+/// terminal.draw_web(move |frame| {
+///     let mut state = state.borrow_mut();
+///     if state.is_idle() {
+///         frame.render_stateful_widget(Screensaver::new(), frame.area(), &mut state);
+///     } else {
+///         frame.render_widget(Paragraph::new("Hello"), frame.area());
+///     }
+/// });
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// Inactivity is measured with the [clock](crate::clock), unlike the
+/// [idle work](crate::idle) which runs while the browser has nothing to render.
+///
+/// [`draw_web`]: crate::WebRenderer::draw_web
+#[derive(Debug, Clone, Copy)]
+pub struct Screensaver {
+    /// Background color of the animation.
+    background: Color,
+}
+
+impl Default for Screensaver {
+    fn default() -> Self {
+        Self {
+            background: Color::Black,
+        }
+    }
+}
+
+impl Screensaver {
+    /// Constructs a new [`Screensaver`] widget.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the background color of the animation.
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = color;
+        self
+    }
+}
+
+/// State of the [`Screensaver`] widget.
+#[derive(Debug, Clone)]
+pub struct ScreensaverState {
+    /// The current animation.
+    kind: ScreensaverKind,
+    /// Random number generator.
+    rng: Rng,
+    /// The area the animation was initialized for.
+    area: Rect,
+    /// Animation specific state.
+    animation: Animation,
+    /// Inactivity before the screensaver starts, in milliseconds.
+    idle_timeout: f64,
+    /// Time of the last user activity, in milliseconds.
+    last_activity: f64,
+}
+
+impl ScreensaverState {
+    /// Constructs a new [`ScreensaverState`] for the given animation.
+    pub fn new(kind: ScreensaverKind) -> Self {
+        Self::with_rng(kind, Rng::from_entropy())
+    }
+
+    /// Constructs a new [`ScreensaverState`] with a fixed random seed.
+    ///
+    /// This is useful to get a reproducible animation.
+    pub fn with_seed(kind: ScreensaverKind, seed: u64) -> Self {
+        Self::with_rng(kind, Rng::with_seed(seed))
+    }
+
+    fn with_rng(kind: ScreensaverKind, rng: Rng) -> Self {
+        Self {
+            kind,
+            rng,
+            area: Rect::default(),
+            animation: Animation::Empty,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT_MS,
+            last_activity: 0.0,
+        }
+    }
+
+    /// Sets the inactivity after which [`ScreensaverState::is_idle`] returns
+    /// `true`.
+    ///
+    /// The default is one minute.
+    pub fn idle_after(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout.as_secs_f64() * 1000.0;
+        self
+    }
+
+    /// Records a user activity, e.g. a key press or a mouse move.
+    ///
+    /// The animation restarts the next time the user becomes idle.
+    pub fn record_activity(&mut self) {
+        if self.is_idle() {
+            self.reset();
+        }
+        self.last_activity = clock::now();
+    }
+
+    /// Returns `true` if there was no user activity for the idle timeout.
+    ///
+    /// Without any recorded activity, the inactivity is counted from the
+    /// page load.
+    pub fn is_idle(&self) -> bool {
+        clock::now() - self.last_activity >= self.idle_timeout
+    }
+
+    /// Returns the current animation.
+    pub fn kind(&self) -> ScreensaverKind {
+        self.kind
+    }
+
+    /// Switches to another animation.
+    pub fn set_kind(&mut self, kind: ScreensaverKind) {
+        self.kind = kind;
+        self.reset();
+    }
+
+    /// Restarts the animation from scratch.
+    pub fn reset(&mut self) {
+        self.animation = Animation::new(self.kind, self.area, &mut self.rng);
+    }
+
+    /// Advances the animation by one step.
+    fn step(&mut self, area: Rect) {
+        if self.area != area {
+            self.area = area;
+            self.reset();
+        }
+        self.animation.step(self.area, &mut self.rng);
+    }
+}
+
+impl StatefulWidget for Screensaver {
+    type State = ScreensaverState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let area = area.intersection(buf.area);
+        buf.set_style(area, Style::default().bg(self.background));
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                buf[(x, y)].set_symbol(" ");
+            }
+        }
+
+        state.step(area);
+        state.animation.render(area, buf, &mut state.rng);
+    }
+}
+
+/// Animation specific state.
+#[derive(Debug, Clone)]
+enum Animation {
+    /// Nothing to animate (yet).
+    Empty,
+    /// One falling drop per column.
+    MatrixRain(Vec<RainDrop>),
+    /// Stars in normalized 3D space.
+    Starfield(Vec<Star>),
+    /// Pipes and the trail they left behind.
+    Pipes(Pipes),
+    /// Game of life cells.
+    GameOfLife(Life),
+}
+
+impl Animation {
+    fn new(kind: ScreensaverKind, area: Rect, rng: &mut Rng) -> Self {
+        if area.is_empty() {
+            return Self::Empty;
+        }
+        match kind {
+            ScreensaverKind::MatrixRain => Self::MatrixRain(
+                (0..area.width)
+                    .map(|_| RainDrop::new(area.height, rng))
+                    .collect(),
+            ),
+            ScreensaverKind::Starfield => {
+                let count = (area.width as usize * area.height as usize / 12).max(16);
+                Self::Starfield((0..count).map(|_| Star::new(rng)).collect())
+            }
+            ScreensaverKind::Pipes => Self::Pipes(Pipes::new(area, rng)),
+            ScreensaverKind::GameOfLife => Self::GameOfLife(Life::new(area, rng)),
+        }
+    }
+
+    fn step(&mut self, area: Rect, rng: &mut Rng) {
+        match self {
+            Self::Empty => {}
+            Self::MatrixRain(drops) => {
+                for drop in drops {
+                    drop.y += drop.speed;
+                    if drop.y - drop.length as f32 > area.height as f32 {
+                        *drop = RainDrop::new(area.height, rng);
+                    }
+                }
+            }
+            Self::Starfield(stars) => {
+                for star in stars {
+                    star.z -= 0.01;
+                    if star.z <= 0.05 {
+                        *star = Star::new(rng);
+                    }
+                }
+            }
+            Self::Pipes(pipes) => pipes.step(area, rng),
+            Self::GameOfLife(life) => life.step(area, rng),
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, rng: &mut Rng) {
+        match self {
+            Self::Empty => {}
+            Self::MatrixRain(drops) => {
+                for (x, drop) in drops.iter().enumerate() {
+                    let head = drop.y as i32;
+                    for i in 0..drop.length as i32 {
+                        let y = head - i;
+                        if y < 0 || y >= area.height as i32 {
+                            continue;
+                        }
+                        let color = if i == 0 {
+                            Color::Rgb(220, 255, 220)
+                        } else {
+                            let fade = 1.0 - i as f32 / drop.length as f32;
+                            Color::Rgb(0, (80.0 + 175.0 * fade) as u8, (40.0 * fade) as u8)
+                        };
+                        let glyph = MATRIX_GLYPHS[rng.below(MATRIX_GLYPHS.len() as u32) as usize];
+                        buf[(area.x + x as u16, area.y + y as u16)]
+                            .set_char(glyph)
+                            .set_fg(color);
+                    }
+                }
+            }
+            Self::Starfield(stars) => {
+                let cx = area.width as f32 / 2.0;
+                let cy = area.height as f32 / 2.0;
+                for star in stars {
+                    let x = cx + star.x / star.z * cx;
+                    let y = cy + star.y / star.z * cy;
+                    if x < 0.0 || y < 0.0 || x >= area.width as f32 || y >= area.height as f32 {
+                        continue;
+                    }
+                    let (glyph, brightness) = match star.z {
+                        z if z < 0.3 => ('@', 255),
+                        z if z < 0.6 => ('*', 180),
+                        _ => ('.', 110),
+                    };
+                    buf[(area.x + x as u16, area.y + y as u16)]
+                        .set_char(glyph)
+                        .set_fg(Color::Rgb(brightness, brightness, brightness));
+                }
+            }
+            Self::Pipes(pipes) => {
+                for (i, cell) in pipes.trail.iter().enumerate() {
+                    if let Some((glyph, color)) = cell {
+                        let x = (i % area.width as usize) as u16;
+                        let y = (i / area.width as usize) as u16;
+                        buf[(area.x + x, area.y + y)]
+                            .set_char(*glyph)
+                            .set_fg(*color);
+                    }
+                }
+            }
+            Self::GameOfLife(life) => {
+                for (i, alive) in life.cells.iter().enumerate() {
+                    if *alive {
+                        let x = (i % area.width as usize) as u16;
+                        let y = (i / area.width as usize) as u16;
+                        buf[(area.x + x, area.y + y)]
+                            .set_char('●')
+                            .set_fg(Color::Rgb(120, 200, 255));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A single column of the matrix rain.
+#[derive(Debug, Clone)]
+struct RainDrop {
+    /// Row of the head of the drop.
+    y: f32,
+    /// Rows per step.
+    speed: f32,
+    /// Length of the tail.
+    length: u16,
+}
+
+impl RainDrop {
+    fn new(height: u16, rng: &mut Rng) -> Self {
+        Self {
+            y: -(rng.below(height as u32 + 1) as f32),
+            speed: rng.range_f32(0.2, 0.9),
+            length: 4 + rng.below(height as u32 / 2 + 1) as u16,
+        }
+    }
+}
+
+/// A star of the starfield.
+#[derive(Debug, Clone)]
+struct Star {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+impl Star {
+    fn new(rng: &mut Rng) -> Self {
+        Self {
+            x: rng.range_f32(-1.0, 1.0),
+            y: rng.range_f32(-1.0, 1.0),
+            z: rng.range_f32(0.5, 1.0),
+        }
+    }
+}
+
+/// Direction a pipe is growing in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    fn opposite(self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    /// Returns the box-drawing glyph connecting the two sides of a cell.
+    fn glyph(from: Direction, to: Direction) -> char {
+        use Direction::*;
+        match (from, to) {
+            (Up, Down) | (Down, Up) => '│',
+            (Left, Right) | (Right, Left) => '─',
+            (Left, Up) | (Up, Left) => '┘',
+            (Left, Down) | (Down, Left) => '┐',
+            (Right, Up) | (Up, Right) => '└',
+            (Right, Down) | (Down, Right) => '┌',
+            (Up, Up) | (Down, Down) => '│',
+            (Left, Left) | (Right, Right) => '─',
+        }
+    }
+}
+
+/// The head of a growing pipe.
+#[derive(Debug, Clone)]
+struct PipeHead {
+    x: u16,
+    y: u16,
+    direction: Direction,
+    color: Color,
+}
+
+/// State of the pipes animation.
+#[derive(Debug, Clone)]
+struct Pipes {
+    heads: Vec<PipeHead>,
+    trail: Vec<Option<(char, Color)>>,
+    steps: usize,
+}
+
+impl Pipes {
+    const COLORS: [Color; 6] = [
+        Color::LightRed,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightBlue,
+        Color::LightMagenta,
+        Color::LightCyan,
+    ];
+
+    fn new(area: Rect, rng: &mut Rng) -> Self {
+        let heads = (0..3)
+            .map(|_| PipeHead {
+                x: rng.below(area.width as u32) as u16,
+                y: rng.below(area.height as u32) as u16,
+                direction: Direction::ALL[rng.below(4) as usize],
+                color: Self::COLORS[rng.below(Self::COLORS.len() as u32) as usize],
+            })
+            .collect();
+        Self {
+            heads,
+            trail: vec![None; area.area() as usize],
+            steps: 0,
+        }
+    }
+
+    fn step(&mut self, area: Rect, rng: &mut Rng) {
+        // start over once the screen is reasonably filled
+        self.steps += 1;
+        if self.steps > area.area() as usize * 2 {
+            *self = Self::new(area, rng);
+        }
+
+        for head in &mut self.heads {
+            let previous = head.direction;
+            if rng.chance(0.15) {
+                let turn = Direction::ALL[rng.below(4) as usize];
+                if turn != previous.opposite() {
+                    head.direction = turn;
+                }
+            }
+
+            let index = head.y as usize * area.width as usize + head.x as usize;
+            let glyph = Direction::glyph(previous.opposite(), head.direction);
+            self.trail[index] = Some((glyph, head.color));
+
+            // wrap around the edges, switching color when doing so
+            let (x, y) = (head.x as i32, head.y as i32);
+            let (x, y) = match head.direction {
+                Direction::Up => (x, y - 1),
+                Direction::Down => (x, y + 1),
+                Direction::Left => (x - 1, y),
+                Direction::Right => (x + 1, y),
+            };
+            if x < 0 || y < 0 || x >= area.width as i32 || y >= area.height as i32 {
+                head.color = Self::COLORS[rng.below(Self::COLORS.len() as u32) as usize];
+            }
+            head.x = x.rem_euclid(area.width as i32) as u16;
+            head.y = y.rem_euclid(area.height as i32) as u16;
+        }
+    }
+}
+
+/// State of the game of life.
+#[derive(Debug, Clone)]
+struct Life {
+    cells: Vec<bool>,
+    frame: u32,
+    population: usize,
+    stagnant_generations: u32,
+}
+
+impl Life {
+    fn new(area: Rect, rng: &mut Rng) -> Self {
+        let cells: Vec<bool> = (0..area.area()).map(|_| rng.chance(0.3)).collect();
+        Self {
+            population: cells.iter().filter(|c| **c).count(),
+            cells,
+            frame: 0,
+            stagnant_generations: 0,
+        }
+    }
+
+    fn step(&mut self, area: Rect, rng: &mut Rng) {
+        self.frame += 1;
+        if !self.frame.is_multiple_of(LIFE_FRAMES_PER_GENERATION) {
+            return;
+        }
+
+        let (w, h) = (area.width as i32, area.height as i32);
+        let alive = |x: i32, y: i32| self.cells[(y.rem_euclid(h) * w + x.rem_euclid(w)) as usize];
+        let next: Vec<bool> = (0..h)
+            .flat_map(|y| (0..w).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let neighbors = [
+                    (-1, -1),
+                    (0, -1),
+                    (1, -1),
+                    (-1, 0),
+                    (1, 0),
+                    (-1, 1),
+                    (0, 1),
+                    (1, 1),
+                ]
+                .iter()
+                .filter(|(dx, dy)| alive(x + dx, y + dy))
+                .count();
+                matches!((alive(x, y), neighbors), (true, 2) | (_, 3))
+            })
+            .collect();
+
+        let population = next.iter().filter(|c| **c).count();
+        if population == self.population {
+            self.stagnant_generations += 1;
+        } else {
+            self.stagnant_generations = 0;
+        }
+        self.cells = next;
+        self.population = population;
+
+        if population == 0 || self.stagnant_generations > LIFE_STAGNATION_LIMIT {
+            *self = Self::new(area, rng);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{reset_clock, set_clock, ManualClock};
+
+    const KINDS: [ScreensaverKind; 4] = [
+        ScreensaverKind::MatrixRain,
+        ScreensaverKind::Starfield,
+        ScreensaverKind::Pipes,
+        ScreensaverKind::GameOfLife,
+    ];
+
+    /// Returns the number of cells the animation drew on.
+    fn drawn_cells(buf: &Buffer) -> usize {
+        buf.content()
+            .iter()
+            .filter(|cell| cell.symbol() != " ")
+            .count()
+    }
+
+    /// Returns the number of elements in the animation state.
+    fn animation_len(state: &ScreensaverState) -> usize {
+        match &state.animation {
+            Animation::Empty => 0,
+            Animation::MatrixRain(drops) => drops.len(),
+            Animation::Starfield(stars) => stars.len(),
+            Animation::Pipes(pipes) => pipes.trail.len(),
+            Animation::GameOfLife(life) => life.cells.len(),
+        }
+    }
+
+    #[test]
+    fn test_render_each_kind() {
+        for kind in KINDS {
+            let area = Rect::new(0, 0, 24, 12);
+            let mut buf = Buffer::empty(area);
+            let mut state = ScreensaverState::with_seed(kind, 42);
+            let mut drawn = 0;
+            for _ in 0..60 {
+                Screensaver::new()
+                    .background(Color::Blue)
+                    .render(area, &mut buf, &mut state);
+                drawn = drawn.max(drawn_cells(&buf));
+            }
+            assert!(drawn > 0, "{kind:?} drew nothing");
+            assert!(buf.content().iter().all(|cell| cell.bg == Color::Blue));
+        }
+    }
+
+    #[test]
+    fn test_render_is_reproducible() {
+        let area = Rect::new(0, 0, 16, 8);
+        let render = || {
+            let mut buf = Buffer::empty(area);
+            let mut state = ScreensaverState::with_seed(ScreensaverKind::Pipes, 7);
+            for _ in 0..10 {
+                Screensaver::new().render(area, &mut buf, &mut state);
+            }
+            buf
+        };
+        assert_eq!(render(), render());
+    }
+
+    #[test]
+    fn test_reset_on_area_change() {
+        for kind in KINDS {
+            let mut state = ScreensaverState::with_seed(kind, 1);
+            let mut buf = Buffer::empty(Rect::new(0, 0, 48, 24));
+            Screensaver::new().render(Rect::new(0, 0, 48, 24), &mut buf, &mut state);
+            assert_eq!(state.area, Rect::new(0, 0, 48, 24));
+            let before = animation_len(&state);
+
+            Screensaver::new().render(Rect::new(2, 1, 8, 4), &mut buf, &mut state);
+            assert_eq!(state.area, Rect::new(2, 1, 8, 4));
+            assert_ne!(animation_len(&state), before, "{kind:?} was not reset");
+        }
+    }
+
+    #[test]
+    fn test_set_kind() {
+        let mut state = ScreensaverState::with_seed(ScreensaverKind::MatrixRain, 3);
+        let area = Rect::new(0, 0, 10, 5);
+        Screensaver::new().render(area, &mut Buffer::empty(area), &mut state);
+        state.set_kind(ScreensaverKind::GameOfLife);
+        assert_eq!(state.kind(), ScreensaverKind::GameOfLife);
+        assert!(matches!(state.animation, Animation::GameOfLife(_)));
+    }
+
+    #[test]
+    fn test_idle() {
+        let clock = ManualClock::new();
+        set_clock(clock.clone());
+        let mut state = ScreensaverState::with_seed(ScreensaverKind::Starfield, 5)
+            .idle_after(Duration::from_secs(10));
+        assert!(!state.is_idle());
+
+        clock.advance(10_000.0);
+        assert!(state.is_idle());
+
+        state.record_activity();
+        assert!(!state.is_idle());
+        clock.advance(9_999.0);
+        assert!(!state.is_idle());
+        clock.advance(1.0);
+        assert!(state.is_idle());
+        reset_clock();
+    }
+}
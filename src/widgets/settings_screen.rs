@@ -0,0 +1,294 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, BorderType, Paragraph, StatefulWidget, Widget},
+};
+
+use crate::{
+    event::{KeyCode, KeyEvent},
+    settings::Settings,
+};
+
+/// Range of the font size, in CSS pixels.
+const FONT_SIZE_RANGE: (f64, f64) = (8.0, 48.0);
+
+/// Frame rate caps to choose from, `None` being uncapped.
+const FPS_CAPS: [Option<f64>; 4] = [None, Some(30.0), Some(60.0), Some(120.0)];
+
+/// Labels of the settings, in display order.
+const LABELS: [&str; 5] = ["Font size", "Theme", "Reduced motion", "Frame rate", "Bell"];
+
+/// State of a [`SettingsScreen`]: the selected setting and the themes to
+/// choose from.
+#[derive(Debug, Default, Clone)]
+pub struct SettingsScreenState {
+    /// Index of the selected setting.
+    selected: usize,
+    /// Names of the themes.
+    themes: Vec<String>,
+}
+
+impl SettingsScreenState {
+    /// Constructs a new [`SettingsScreenState`] with the first setting
+    /// selected.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the names of the themes to choose from.
+    ///
+    /// The theme setting is hidden without themes.
+    pub fn themes<I, S>(mut self, themes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.themes = themes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the index of the selected setting.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Handles a key event, changing the given settings.
+    ///
+    /// `↑`/`↓` select a setting, `←`/`→` change it and `Enter` or `Space`
+    /// toggle it. Returns `true` if the settings changed, so the app can
+    /// [save](Settings::save) and [apply](Settings::apply) them.
+    pub fn handle_key_event(&mut self, event: &KeyEvent, settings: &mut Settings) -> bool {
+        let rows = self.rows();
+        self.selected = self.selected.min(rows.len() - 1);
+        let step = match event.code {
+            KeyCode::Up => {
+                self.selected = self.selected.saturating_sub(1);
+                return false;
+            }
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(rows.len() - 1);
+                return false;
+            }
+            KeyCode::Left => -1,
+            KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') => 1,
+            _ => return false,
+        };
+        let previous = settings.clone();
+        match rows[self.selected] {
+            0 => {
+                let (min, max) = FONT_SIZE_RANGE;
+                settings.font_size = (settings.font_size + step as f64).clamp(min, max);
+            }
+            1 => {
+                let current = settings
+                    .theme
+                    .as_ref()
+                    .and_then(|theme| self.themes.iter().position(|t| t == theme));
+                let index = cycle(current, step, self.themes.len());
+                settings.theme = Some(self.themes[index].clone());
+            }
+            2 => settings.reduced_motion = !settings.reduced_motion,
+            3 => {
+                let current = FPS_CAPS.iter().position(|fps| *fps == settings.fps_cap);
+                settings.fps_cap = FPS_CAPS[cycle(current, step, FPS_CAPS.len())];
+            }
+            _ => settings.bell = !settings.bell,
+        }
+        *settings != previous
+    }
+
+    /// Returns the indices of the shown settings in [`LABELS`].
+    fn rows(&self) -> Vec<usize> {
+        (0..LABELS.len())
+            .filter(|&row| row != 1 || !self.themes.is_empty())
+            .collect()
+    }
+}
+
+/// Returns the index after moving by the given step, wrapping around.
+fn cycle(current: Option<usize>, step: i32, len: usize) -> usize {
+    match current {
+        Some(current) => (current as i64 + step as i64).rem_euclid(len as i64) as usize,
+        None => 0,
+    }
+}
+
+/// A screen editing the [`Settings`] of the built-in features.
+///
+/// Key events are forwarded to the [`SettingsScreenState`], which changes
+/// the settings. See [`settings`](crate::settings) for a complete example.
+///
+/// ```rust no_run
+/// use ratzilla::{settings::Settings, widgets::{SettingsScreen, SettingsScreenState}};
+///
+/// let settings = Settings::load();
+/// let mut state = SettingsScreenState::new().themes(["dark", "light"]);
+///
+/// // frame.render_stateful_widget(SettingsScreen::new(&settings), area, &mut state);
+/// // and on key events: state.handle_key_event(&event, &mut settings);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SettingsScreen<'a> {
+    /// The settings.
+    settings: &'a Settings,
+    /// Title of the screen.
+    title: &'a str,
+    /// Style of the selected setting.
+    highlight_style: Style,
+}
+
+impl<'a> SettingsScreen<'a> {
+    /// Constructs a new [`SettingsScreen`] widget.
+    pub fn new(settings: &'a Settings) -> Self {
+        Self {
+            settings,
+            title: " Settings ",
+            highlight_style: Style::new().add_modifier(Modifier::REVERSED),
+        }
+    }
+
+    /// Sets the title of the screen.
+    pub fn title(mut self, title: &'a str) -> Self {
+        self.title = title;
+        self
+    }
+
+    /// Sets the style of the selected setting.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = style;
+        self
+    }
+
+    /// Returns the displayed value of the setting at the given index of
+    /// [`LABELS`].
+    fn value(&self, row: usize) -> String {
+        let toggle = |enabled: bool| if enabled { "[x]" } else { "[ ]" }.to_string();
+        match row {
+            0 => format!("‹ {} px ›", self.settings.font_size),
+            1 => format!(
+                "‹ {} ›",
+                self.settings.theme.as_deref().unwrap_or("default")
+            ),
+            2 => toggle(self.settings.reduced_motion),
+            3 => match self.settings.fps_cap {
+                Some(fps) => format!("‹ {fps} fps ›"),
+                None => "‹ unlimited ›".to_string(),
+            },
+            _ => toggle(self.settings.bell),
+        }
+    }
+}
+
+impl StatefulWidget for SettingsScreen<'_> {
+    type State = SettingsScreenState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let rows = state.rows();
+        state.selected = state.selected.min(rows.len() - 1);
+        let label_width = LABELS
+            .iter()
+            .map(|label| label.len())
+            .max()
+            .unwrap_or_default();
+        let lines: Vec<Line> = rows
+            .iter()
+            .enumerate()
+            .map(|(index, &row)| {
+                let line = Line::raw(format!(
+                    " {:<label_width$}  {} ",
+                    LABELS[row],
+                    self.value(row)
+                ));
+                if index == state.selected {
+                    line.style(self.highlight_style)
+                } else {
+                    line
+                }
+            })
+            .collect();
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title(self.title)
+                    .title_bottom(Line::from(" ↑↓ select  ←→ change ").right_aligned()),
+            )
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent {
+            code,
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+            repeat: false,
+        }
+    }
+
+    #[test]
+    fn test_change_settings() {
+        let mut settings = Settings::new();
+        let mut state = SettingsScreenState::new();
+        assert!(state.handle_key_event(&key(KeyCode::Right), &mut settings));
+        assert_eq!(settings.font_size, 17.0);
+
+        // without themes, the theme setting is skipped
+        state.handle_key_event(&key(KeyCode::Down), &mut settings);
+        assert!(state.handle_key_event(&key(KeyCode::Enter), &mut settings));
+        assert!(settings.reduced_motion);
+
+        state.handle_key_event(&key(KeyCode::Down), &mut settings);
+        state.handle_key_event(&key(KeyCode::Left), &mut settings);
+        assert_eq!(settings.fps_cap, Some(120.0));
+        state.handle_key_event(&key(KeyCode::Right), &mut settings);
+        assert_eq!(settings.fps_cap, None);
+
+        state.handle_key_event(&key(KeyCode::Down), &mut settings);
+        state.handle_key_event(&key(KeyCode::Down), &mut settings);
+        assert_eq!(state.selected(), 3);
+        assert!(state.handle_key_event(&key(KeyCode::Char(' ')), &mut settings));
+        assert!(!settings.bell);
+        assert!(!state.handle_key_event(&key(KeyCode::Esc), &mut settings));
+    }
+
+    #[test]
+    fn test_themes_and_limits() {
+        let mut settings = Settings {
+            font_size: 48.0,
+            ..Settings::new()
+        };
+        let mut state = SettingsScreenState::new().themes(["dark", "light"]);
+        assert!(!state.handle_key_event(&key(KeyCode::Right), &mut settings));
+
+        state.handle_key_event(&key(KeyCode::Down), &mut settings);
+        state.handle_key_event(&key(KeyCode::Right), &mut settings);
+        assert_eq!(settings.theme.as_deref(), Some("dark"));
+        state.handle_key_event(&key(KeyCode::Left), &mut settings);
+        assert_eq!(settings.theme.as_deref(), Some("light"));
+    }
+
+    #[test]
+    fn test_render() {
+        let settings = Settings::new();
+        let mut state = SettingsScreenState::new();
+        let mut buf = Buffer::empty(Rect::new(0, 0, 36, 7));
+        SettingsScreen::new(&settings).render(buf.area, &mut buf, &mut state);
+        let line = |y: u16| {
+            (0..buf.area.width)
+                .map(|x| buf[(x, y)].symbol())
+                .collect::<String>()
+        };
+        assert_eq!(line(1), "│ Font size       ‹ 16 px ›        │");
+        assert_eq!(line(3), "│ Frame rate      ‹ unlimited ›    │");
+        assert!(buf[(1, 1)].modifier.contains(Modifier::REVERSED));
+    }
+}
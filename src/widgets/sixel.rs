@@ -0,0 +1,337 @@
+use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+
+use crate::error::Error;
+
+/// Largest width or height of a decoded image, in pixels.
+///
+/// Sixel data often comes from remote byte streams, this keeps a malicious
+/// sequence from allocating an unbounded image.
+const MAX_SIZE: usize = 4096;
+
+/// Number of color registers.
+const REGISTERS: usize = 256;
+
+/// Default colors of the first registers, the ones of the VT340.
+const DEFAULT_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (51, 51, 204),
+    (204, 36, 36),
+    (51, 204, 51),
+    (204, 51, 204),
+    (51, 204, 204),
+    (204, 204, 51),
+    (120, 120, 120),
+    (69, 69, 69),
+    (87, 87, 153),
+    (153, 69, 69),
+    (87, 153, 87),
+    (153, 87, 153),
+    (87, 153, 153),
+    (153, 153, 87),
+    (204, 204, 204),
+];
+
+/// An image decoded from sixel data.
+///
+/// It renders in the cells of its area with half blocks (`▀`), two pixels
+/// per cell, scaled down or up to fit the area while keeping its aspect
+/// ratio. This works with every backend.
+///
+/// ```rust no_run
+/// use ratzilla::{ratatui::Frame, widgets::SixelImage};
+///
+/// fn render(frame: &mut Frame, sequence: &[u8]) {
+///     if let Ok(image) = SixelImage::decode(sequence) {
+///         frame.render_widget(&image, frame.area());
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SixelImage {
+    /// Width of the image, in pixels.
+    width: usize,
+    /// Height of the image, in pixels.
+    height: usize,
+    /// Pixels in rows, `None` where no color was drawn.
+    pixels: Vec<Option<Color>>,
+}
+
+impl SixelImage {
+    /// Decodes sixel data.
+    ///
+    /// The data is either a whole device control string (`ESC P ... q ...
+    /// ESC \`), e.g. from a VT emulator, or the sixels following the `q`.
+    pub fn decode(data: &[u8]) -> Result<Self, Error> {
+        let data = strip_introducer(data);
+        let mut image = Self {
+            width: 0,
+            height: 0,
+            pixels: Vec::new(),
+        };
+        let mut palette: Vec<Color> = (0..REGISTERS)
+            .map(|index| {
+                let (r, g, b) = DEFAULT_PALETTE.get(index).copied().unwrap_or_default();
+                Color::Rgb(r, g, b)
+            })
+            .collect();
+        let mut color = palette[0];
+        let (mut x, mut band) = (0, 0);
+        let mut bytes = data.iter().copied().peekable();
+        while let Some(byte) = bytes.next() {
+            match byte {
+                // string terminator, `ESC \` or 8-bit
+                0x1b | 0x9c => break,
+                b'"' => {
+                    // raster attributes: aspect ratio and size
+                    let params = parse_params(&mut bytes);
+                    if let [_, _, width, height, ..] = params[..] {
+                        image.grow(width, height)?;
+                    }
+                }
+                b'#' => {
+                    let params = parse_params(&mut bytes);
+                    let register = params.first().copied().unwrap_or(0) % REGISTERS;
+                    if let [_, system, a, b, c, ..] = params[..] {
+                        palette[register] = match system {
+                            1 => hls_to_rgb(a, b, c),
+                            _ => Color::Rgb(percent(a), percent(b), percent(c)),
+                        };
+                    }
+                    color = palette[register];
+                }
+                b'!' => {
+                    let count = parse_params(&mut bytes)
+                        .first()
+                        .copied()
+                        .unwrap_or(1)
+                        .max(1);
+                    if let Some(sixel) = bytes.next_if(|byte| (b'?'..=b'~').contains(byte)) {
+                        image.draw(x, band, sixel - b'?', count, color)?;
+                        x += count;
+                    }
+                }
+                b'$' => x = 0,
+                b'-' => {
+                    x = 0;
+                    band += 1;
+                }
+                b'?'..=b'~' => {
+                    image.draw(x, band, byte - b'?', 1, color)?;
+                    x += 1;
+                }
+                // line breaks and other characters are ignored
+                _ => {}
+            }
+        }
+        Ok(image)
+    }
+
+    /// Returns the width of the image, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the image, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the color of a pixel, or `None` if no color was drawn there.
+    pub fn pixel(&self, x: usize, y: usize) -> Option<Color> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.pixels[y * self.width + x]
+    }
+
+    /// Grows the image to at least the given size.
+    fn grow(&mut self, width: usize, height: usize) -> Result<(), Error> {
+        if width <= self.width && height <= self.height {
+            return Ok(());
+        }
+        if width > MAX_SIZE || height > MAX_SIZE {
+            return Err(Error::InvalidImage(format!(
+                "sixel image larger than {MAX_SIZE} pixels"
+            )));
+        }
+        let (new_width, new_height) = (width.max(self.width), height.max(self.height));
+        let mut pixels = vec![None; new_width * new_height];
+        for y in 0..self.height {
+            let row = &self.pixels[y * self.width..(y + 1) * self.width];
+            pixels[y * new_width..y * new_width + self.width].copy_from_slice(row);
+        }
+        self.width = new_width;
+        self.height = new_height;
+        self.pixels = pixels;
+        Ok(())
+    }
+
+    /// Draws a sixel `count` times from the given column of a band.
+    fn draw(
+        &mut self,
+        x: usize,
+        band: usize,
+        sixel: u8,
+        count: usize,
+        color: Color,
+    ) -> Result<(), Error> {
+        let top = band * 6;
+        self.grow(x.saturating_add(count), top + 6)?;
+        for bit in 0..6 {
+            if sixel & (1 << bit) != 0 {
+                let row = (top + bit) * self.width;
+                self.pixels[row + x..row + x + count].fill(Some(color));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Widget for &SixelImage {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.width == 0 || self.height == 0 || area.is_empty() {
+            return;
+        }
+        // a cell is about twice as tall as wide, so it holds two square pixels
+        let scale = (area.width as f64 / self.width as f64)
+            .min(area.height as f64 * 2.0 / self.height as f64);
+        let columns = ((self.width as f64 * scale).round() as u16).clamp(1, area.width);
+        let rows = ((self.height as f64 * scale / 2.0).round() as u16).clamp(1, area.height);
+        let sample = |column: u16, pixel_row: u16| {
+            let x = ((column as f64 + 0.5) / scale) as usize;
+            let y = ((pixel_row as f64 + 0.5) / scale) as usize;
+            self.pixel(x.min(self.width - 1), y.min(self.height - 1))
+        };
+        for row in 0..rows {
+            for column in 0..columns {
+                let (top, bottom) = (sample(column, row * 2), sample(column, row * 2 + 1));
+                if top.is_none() && bottom.is_none() {
+                    continue;
+                }
+                if let Some(cell) = buf.cell_mut((area.x + column, area.y + row)) {
+                    cell.set_symbol("▀")
+                        .set_fg(top.unwrap_or(Color::Reset))
+                        .set_bg(bottom.unwrap_or(Color::Reset));
+                }
+            }
+        }
+    }
+}
+
+impl Widget for SixelImage {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        (&self).render(area, buf);
+    }
+}
+
+/// Skips the device control string introducer, up to the `q` final byte.
+fn strip_introducer(data: &[u8]) -> &[u8] {
+    let start = match data {
+        [0x1b, b'P', rest @ ..] => data.len() - rest.len(),
+        [0x90, rest @ ..] => data.len() - rest.len(),
+        _ => return data,
+    };
+    match data[start..].iter().position(|&byte| byte == b'q') {
+        Some(q) => &data[start + q + 1..],
+        None => &[],
+    }
+}
+
+/// Parses numeric parameters separated by `;`.
+fn parse_params(bytes: &mut std::iter::Peekable<impl Iterator<Item = u8>>) -> Vec<usize> {
+    let mut params = vec![0usize];
+    while let Some(byte) = bytes.next_if(|byte| byte.is_ascii_digit() || *byte == b';') {
+        match byte {
+            b';' => params.push(0),
+            digit => {
+                let param = params.last_mut().expect("params are never empty");
+                *param = param
+                    .saturating_mul(10)
+                    .saturating_add((digit - b'0') as usize);
+            }
+        }
+    }
+    params
+}
+
+/// Converts a percentage to a color component.
+fn percent(value: usize) -> u8 {
+    (value.min(100) * 255 / 100) as u8
+}
+
+/// Converts a sixel HLS color to RGB.
+///
+/// The hue of sixel colors starts at blue, with red at 120 degrees.
+fn hls_to_rgb(hue: usize, lightness: usize, saturation: usize) -> Color {
+    let hue = ((hue + 240) % 360) as f64;
+    let lightness = lightness.min(100) as f64 / 100.0;
+    let saturation = saturation.min(100) as f64 / 100.0;
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let component = |value: f64| ((value + m) * 255.0).round() as u8;
+    Color::Rgb(component(r), component(g), component(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color::Rgb(255, 0, 0);
+    const BLUE: Color = Color::Rgb(0, 0, 255);
+
+    #[test]
+    fn test_decode() {
+        // 3x12 pixels: a red top band, then a blue column in the second band
+        let image = SixelImage::decode(b"\x1bPq\"1;1;3;12#1;2;100;0;0#1!3~-#2;2;0;0;100#2?~\x1b\\")
+            .expect("valid sixel data");
+        assert_eq!((image.width(), image.height()), (3, 12));
+        assert_eq!(image.pixel(2, 5), Some(RED));
+        assert_eq!(image.pixel(0, 6), None);
+        assert_eq!(image.pixel(1, 11), Some(BLUE));
+    }
+
+    #[test]
+    fn test_overwrite_line() {
+        // `$` draws the same band again, with another color
+        let image =
+            SixelImage::decode(b"#1;2;100;0;0#1@$#2;2;0;0;100#2A").expect("valid sixel data");
+        assert_eq!(image.pixel(0, 0), Some(RED));
+        assert_eq!(image.pixel(0, 1), Some(BLUE));
+    }
+
+    #[test]
+    fn test_hls() {
+        assert_eq!(hls_to_rgb(120, 50, 100), RED);
+        assert_eq!(hls_to_rgb(0, 50, 100), BLUE);
+        assert_eq!(hls_to_rgb(0, 100, 0), Color::Rgb(255, 255, 255));
+    }
+
+    #[test]
+    fn test_too_large() {
+        assert!(SixelImage::decode(b"\"1;1;5000;1").is_err());
+        assert!(SixelImage::decode(b"!99999~").is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let image =
+            SixelImage::decode(b"#1;2;100;0;0#1!2~-#2;2;0;0;100#2!2~").expect("valid sixel data");
+        let area = Rect::new(0, 0, 4, 4);
+        let mut buf = Buffer::empty(area);
+        (&image).render(area, &mut buf);
+        // 2x12 pixels fit in 1x4 cells
+        let cell = &buf[(0, 0)];
+        assert_eq!((cell.symbol(), cell.fg, cell.bg), ("▀", RED, RED));
+        assert_eq!((buf[(0, 3)].fg, buf[(0, 3)].bg), (BLUE, BLUE));
+        assert_eq!(buf[(1, 0)].symbol(), " ");
+    }
+}
@@ -0,0 +1,283 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::{Color, Style},
+    widgets::StatefulWidget,
+};
+
+use crate::utils::storage;
+
+/// State of a [`Splitter`]: the split ratio and the drag state.
+///
+/// The ratio is the size of the first pane relative to the whole area. When
+/// a storage key is set with [`SplitterState::persist`], the ratio is loaded
+/// from `localStorage` and saved there after every drag.
+#[derive(Debug, Clone)]
+pub struct SplitterState {
+    /// Direction in which the area is split.
+    direction: Direction,
+    /// Size of the first pane relative to the whole area.
+    ratio: f32,
+    /// Minimum size of each pane, in cells.
+    min_size: u16,
+    /// The `localStorage` key the ratio is persisted to.
+    storage_key: Option<String>,
+    /// The area of the last render.
+    area: Rect,
+    /// The divider of the last render.
+    divider: Rect,
+    /// Whether the divider is being dragged.
+    dragging: bool,
+}
+
+impl SplitterState {
+    /// Constructs a new [`SplitterState`] splitting an area in half.
+    ///
+    /// [`Direction::Horizontal`] puts the panes side by side, and
+    /// [`Direction::Vertical`] puts them on top of each other.
+    pub fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            ratio: 0.5,
+            min_size: 1,
+            storage_key: None,
+            area: Rect::default(),
+            divider: Rect::default(),
+            dragging: false,
+        }
+    }
+
+    /// Sets the size of the first pane relative to the whole area, from `0.0` to `1.0`.
+    pub fn ratio(mut self, ratio: f32) -> Self {
+        self.ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the minimum size of each pane, in cells.
+    pub fn min_size(mut self, min_size: u16) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Persists the ratio to `localStorage` under the given key, restoring
+    /// the saved ratio if there is one.
+    pub fn persist(mut self, key: &str) -> Self {
+        if let Ok(Some(ratio)) = storage::get_item::<f32>(key) {
+            self.ratio = ratio.clamp(0.0, 1.0);
+        }
+        self.storage_key = Some(key.to_string());
+        self
+    }
+
+    /// Returns the size of the first pane relative to the whole area.
+    pub fn current_ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Returns the constraints of the first pane, the divider and the second
+    /// pane for the given area.
+    pub fn constraints(&self, area: Rect) -> [Constraint; 3] {
+        let total = self.total(area);
+        let available = total.saturating_sub(1);
+        let mut first = (self.ratio * total as f32).round() as u16;
+        if available >= self.min_size * 2 {
+            first = first.clamp(self.min_size, available - self.min_size);
+        }
+        [
+            Constraint::Length(first.min(available)),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ]
+    }
+
+    /// Splits the area into the two panes, leaving a cell for the divider.
+    pub fn split(&self, area: Rect) -> [Rect; 2] {
+        let [first, _, second] = self.layout(area);
+        [first, second]
+    }
+
+    /// Handles a mouse button press at the given cell position.
+    ///
+    /// Returns `true` if the press started dragging the divider.
+    pub fn mouse_down(&mut self, column: u16, row: u16) -> bool {
+        self.dragging = self.divider.contains(Position::new(column, row));
+        self.dragging
+    }
+
+    /// Handles a mouse movement to the given cell position.
+    ///
+    /// Returns `true` if the divider was moved.
+    pub fn mouse_move(&mut self, column: u16, row: u16) -> bool {
+        let total = self.total(self.area);
+        if !self.dragging || total == 0 {
+            return false;
+        }
+        let offset = match self.direction {
+            Direction::Horizontal => column.saturating_sub(self.area.x),
+            Direction::Vertical => row.saturating_sub(self.area.y),
+        };
+        let ratio = (offset as f32 / total as f32).clamp(0.0, 1.0);
+        let moved = ratio != self.ratio;
+        self.ratio = ratio;
+        moved
+    }
+
+    /// Handles a mouse button release, saving the ratio if a drag ended.
+    pub fn mouse_up(&mut self) {
+        if !std::mem::take(&mut self.dragging) {
+            return;
+        }
+        if let Some(key) = &self.storage_key {
+            let _ = storage::set_item(key, self.ratio);
+        }
+    }
+
+    /// Returns `true` while the divider is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    /// Returns the size of the area in the split direction.
+    fn total(&self, area: Rect) -> u16 {
+        match self.direction {
+            Direction::Horizontal => area.width,
+            Direction::Vertical => area.height,
+        }
+    }
+
+    /// Returns the areas of the first pane, the divider and the second pane.
+    fn layout(&self, area: Rect) -> [Rect; 3] {
+        Layout::new(self.direction, self.constraints(area)).areas(area)
+    }
+}
+
+/// A draggable divider between two panes.
+///
+/// The [`SplitterState`] splits the area into the two panes, and the
+/// splitter renders the divider between them. Mouse events are forwarded to
+/// the state to drag the divider.
+///
+/// ```rust no_run
+/// use ratzilla::{
+///     ratatui::layout::Direction,
+///     widgets::{Splitter, SplitterState},
+/// };
+///
+/// let mut state = SplitterState::new(Direction::Horizontal)
+///     .min_size(10)
+///     .persist("sidebar");
+///
+/// // in the draw callback:
+/// // let [sidebar, main] = state.split(frame.area());
+/// // frame.render_stateful_widget(Splitter::new(), frame.area(), &mut state);
+/// // and on mouse events: state.mouse_down(column, row);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Splitter<'a> {
+    /// Symbol of the divider, if not the default for the direction.
+    symbol: Option<&'a str>,
+    /// Style of the divider.
+    style: Style,
+    /// Style of the divider while it is dragged.
+    drag_style: Style,
+}
+
+impl Default for Splitter<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Splitter<'a> {
+    /// Constructs a new [`Splitter`].
+    pub fn new() -> Self {
+        Self {
+            symbol: None,
+            style: Style::new().fg(Color::DarkGray),
+            drag_style: Style::new().fg(Color::Yellow),
+        }
+    }
+
+    /// Sets the symbol of the divider.
+    ///
+    /// Defaults to `│` for side by side panes and `─` for stacked panes.
+    pub fn symbol(mut self, symbol: &'a str) -> Self {
+        self.symbol = Some(symbol);
+        self
+    }
+
+    /// Sets the style of the divider.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the divider while it is dragged.
+    pub fn drag_style(mut self, style: Style) -> Self {
+        self.drag_style = style;
+        self
+    }
+}
+
+impl StatefulWidget for Splitter<'_> {
+    type State = SplitterState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let [_, divider, _] = state.layout(area);
+        state.area = area;
+        state.divider = divider;
+        let symbol = self.symbol.unwrap_or(match state.direction {
+            Direction::Horizontal => "│",
+            Direction::Vertical => "─",
+        });
+        let style = if state.dragging {
+            self.style.patch(self.drag_style)
+        } else {
+            self.style
+        };
+        for position in divider.intersection(buf.area).positions() {
+            buf[position].set_symbol(symbol).set_style(style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let state = SplitterState::new(Direction::Horizontal);
+        let [left, right] = state.split(Rect::new(0, 0, 20, 5));
+        assert_eq!(left, Rect::new(0, 0, 10, 5));
+        assert_eq!(right, Rect::new(11, 0, 9, 5));
+
+        let state = SplitterState::new(Direction::Vertical)
+            .ratio(0.0)
+            .min_size(2);
+        let [top, bottom] = state.split(Rect::new(0, 0, 10, 10));
+        assert_eq!(top, Rect::new(0, 0, 10, 2));
+        assert_eq!(bottom, Rect::new(0, 3, 10, 7));
+    }
+
+    #[test]
+    fn test_drag() {
+        let area = Rect::new(0, 0, 20, 2);
+        let mut buf = Buffer::empty(area);
+        let mut state = SplitterState::new(Direction::Horizontal).min_size(3);
+        Splitter::new()
+            .style(Style::new())
+            .render(area, &mut buf, &mut state);
+        assert_eq!(buf[(10, 1)].symbol(), "│");
+
+        assert!(!state.mouse_down(4, 0));
+        assert!(state.mouse_down(10, 0));
+        assert!(state.mouse_move(5, 0));
+        assert_eq!(state.split(area)[0].width, 5);
+        assert!(state.mouse_move(0, 0));
+        assert_eq!(state.split(area)[0].width, 3);
+        state.mouse_up();
+        assert!(!state.is_dragging());
+        assert!(!state.mouse_move(8, 0));
+    }
+}
@@ -0,0 +1,359 @@
+use std::{fmt, rc::Rc};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::Span,
+    widgets::StatefulWidget,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Callback called when a segment is clicked.
+type ClickHandler = Rc<dyn Fn()>;
+
+/// A segment of a [`StatusBar`].
+#[derive(Clone)]
+pub struct Segment<'a> {
+    /// Content of the segment.
+    content: Span<'a>,
+    /// Click handler.
+    on_click: Option<ClickHandler>,
+}
+
+impl fmt::Debug for Segment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Segment")
+            .field("content", &self.content)
+            .field("clickable", &self.on_click.is_some())
+            .finish()
+    }
+}
+
+impl<'a> Segment<'a> {
+    /// Constructs a new [`Segment`].
+    pub fn new<T>(content: T) -> Self
+    where
+        T: Into<Span<'a>>,
+    {
+        Self {
+            content: content.into(),
+            on_click: None,
+        }
+    }
+
+    /// Sets the style of the segment.
+    pub fn style(mut self, style: Style) -> Self {
+        self.content = self.content.style(style);
+        self
+    }
+
+    /// Sets the callback called when the segment is clicked.
+    ///
+    /// See [`StatusBarState::click`].
+    pub fn on_click<F>(mut self, callback: F) -> Self
+    where
+        F: Fn() + 'static,
+    {
+        self.on_click = Some(Rc::new(callback));
+        self
+    }
+
+    /// Returns the width of the segment, in cells.
+    fn width(&self) -> usize {
+        self.content.content.width()
+    }
+}
+
+impl<'a, T: Into<Span<'a>>> From<T> for Segment<'a> {
+    fn from(content: T) -> Self {
+        Self::new(content)
+    }
+}
+
+/// The clickable areas of a rendered [`StatusBar`].
+#[derive(Default, Clone)]
+pub struct StatusBarState {
+    /// Areas of the clickable segments.
+    targets: Vec<(Rect, ClickHandler)>,
+}
+
+impl fmt::Debug for StatusBarState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatusBarState")
+            .field(
+                "targets",
+                &self.targets.iter().map(|(r, _)| r).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl StatusBarState {
+    /// Constructs a new [`StatusBarState`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls the click handler of the segment at the given cell position.
+    ///
+    /// Returns `true` if a segment handled the click.
+    pub fn click(&self, column: u16, row: u16) -> bool {
+        let target = self
+            .targets
+            .iter()
+            .find(|(area, _)| area.contains((column, row).into()));
+        match target {
+            Some((_, handler)) => {
+                handler();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A status bar with left, center and right aligned segments.
+///
+/// When the bar is too narrow, the right segments are kept, then the left
+/// ones, and the center segments are truncated first. Truncated segments end
+/// with `…`.
+///
+/// ```rust no_run
+/// use ratzilla::widgets::{Segment, StatusBar, StatusBarState};
+///
+/// let bar = StatusBar::new()
+///     .left([Segment::new(" NORMAL ")])
+///     .center(["main.rs"])
+///     .right([Segment::new("Ln 1, Col 1").on_click(|| {
+///         // open the "go to line" prompt
+///     })]);
+/// let mut state = StatusBarState::new();
+///
+/// // frame.render_stateful_widget(bar, area, &mut state);
+/// // and on click: state.click(column, row);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StatusBar<'a> {
+    /// Left aligned segments.
+    left: Vec<Segment<'a>>,
+    /// Centered segments.
+    center: Vec<Segment<'a>>,
+    /// Right aligned segments.
+    right: Vec<Segment<'a>>,
+    /// Separator between segments.
+    separator: Span<'a>,
+    /// Style of the whole bar.
+    style: Style,
+}
+
+impl Default for StatusBar<'_> {
+    fn default() -> Self {
+        Self {
+            left: Vec::new(),
+            center: Vec::new(),
+            right: Vec::new(),
+            separator: Span::raw(" "),
+            style: Style::new().fg(Color::Black).bg(Color::Gray),
+        }
+    }
+}
+
+impl<'a> StatusBar<'a> {
+    /// Constructs a new, empty [`StatusBar`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the left aligned segments.
+    pub fn left<I, S>(mut self, segments: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Segment<'a>>,
+    {
+        self.left = segments.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the centered segments.
+    pub fn center<I, S>(mut self, segments: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Segment<'a>>,
+    {
+        self.center = segments.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the right aligned segments.
+    pub fn right<I, S>(mut self, segments: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Segment<'a>>,
+    {
+        self.right = segments.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets the separator between segments. Defaults to a space.
+    pub fn separator<T>(mut self, separator: T) -> Self
+    where
+        T: Into<Span<'a>>,
+    {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Sets the style of the bar.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Returns the width of a group of segments, including the separators.
+    fn group_width(&self, segments: &[Segment<'a>]) -> usize {
+        let separators = segments.len().saturating_sub(1) * self.separator.content.width();
+        segments.iter().map(Segment::width).sum::<usize>() + separators
+    }
+
+    /// Renders a group of segments, truncated to `max_width` cells.
+    fn render_group(
+        &self,
+        segments: &[Segment<'a>],
+        x: u16,
+        y: u16,
+        max_width: u16,
+        buf: &mut Buffer,
+        state: &mut StatusBarState,
+    ) {
+        let end = x + max_width;
+        let mut x = x;
+        for (i, segment) in segments.iter().enumerate() {
+            if i > 0 {
+                x = write_truncated(buf, x, y, end, &self.separator);
+            }
+            let start = x;
+            x = write_truncated(buf, x, y, end, &segment.content);
+            if let Some(handler) = &segment.on_click {
+                if x > start {
+                    state
+                        .targets
+                        .push((Rect::new(start, y, x - start, 1), handler.clone()));
+                }
+            }
+            if x >= end {
+                break;
+            }
+        }
+    }
+}
+
+/// Writes a span, ending with `…` if it doesn't fit before `end`.
+///
+/// Returns the column after the written content.
+fn write_truncated(buf: &mut Buffer, x: u16, y: u16, end: u16, span: &Span) -> u16 {
+    let available = end.saturating_sub(x) as usize;
+    if available == 0 {
+        return x;
+    }
+    if span.content.width() <= available {
+        let (x, _) = buf.set_span(x, y, span, available as u16);
+        return x;
+    }
+    let (x, _) = buf.set_stringn(x, y, &span.content, available - 1, span.style);
+    buf.set_stringn(x, y, "…", 1, span.style);
+    x + 1
+}
+
+impl StatefulWidget for StatusBar<'_> {
+    type State = StatusBarState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        state.targets.clear();
+        let area = area.intersection(buf.area);
+        if area.is_empty() {
+            return;
+        }
+        buf.set_style(area, self.style);
+        let y = area.y;
+        let width = area.width as usize;
+
+        let right_width = self.group_width(&self.right).min(width) as u16;
+        let left_width = self
+            .group_width(&self.left)
+            .min(width - right_width as usize) as u16;
+        let right_x = area.right() - right_width;
+        self.render_group(&self.left, area.x, y, left_width, buf, state);
+        self.render_group(&self.right, right_x, y, right_width, buf, state);
+
+        // center in the whole bar if possible, otherwise in the gap between the sides
+        let gap_start = area.x + left_width + 1;
+        let gap_end = right_x.saturating_sub(1);
+        if gap_start >= gap_end {
+            return;
+        }
+        let center_width = self.group_width(&self.center) as u16;
+        let centered = area.x + area.width.saturating_sub(center_width) / 2;
+        let center_x = if centered >= gap_start && centered + center_width <= gap_end {
+            centered
+        } else {
+            gap_start + (gap_end - gap_start).saturating_sub(center_width) / 2
+        };
+        self.render_group(&self.center, center_x, y, gap_end - center_x, buf, state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    fn render(bar: StatusBar, width: u16) -> (Buffer, StatusBarState) {
+        let area = Rect::new(0, 0, width, 1);
+        let mut buf = Buffer::empty(area);
+        let mut state = StatusBarState::new();
+        bar.style(Style::new()).render(area, &mut buf, &mut state);
+        (buf, state)
+    }
+
+    #[test]
+    fn test_layout() {
+        let bar = StatusBar::new()
+            .left(["left"])
+            .center(["mid"])
+            .right(["right"]);
+        let (buf, _) = render(bar, 20);
+        assert_eq!(buf, Buffer::with_lines(["left    mid    right"]));
+    }
+
+    #[test]
+    fn test_truncation() {
+        let bar = StatusBar::new()
+            .left(["a long left segment"])
+            .center(["center"])
+            .right(["right"]);
+        let (buf, _) = render(bar, 12);
+        assert_eq!(buf, Buffer::with_lines(["a long…right"]));
+
+        let bar = StatusBar::new()
+            .left(["a long left segment"])
+            .right(["right"]);
+        let (buf, _) = render(bar, 14);
+        assert_eq!(buf, Buffer::with_lines(["a long l…right"]));
+    }
+
+    #[test]
+    fn test_click() {
+        let clicked = Rc::new(Cell::new(false));
+        let bar = StatusBar::new().right([Segment::new("btn").on_click({
+            let clicked = clicked.clone();
+            move || clicked.set(true)
+        })]);
+        let (_, state) = render(bar, 10);
+        assert!(!state.click(0, 0));
+        assert!(!clicked.get());
+        assert!(state.click(8, 0));
+        assert!(clicked.get());
+    }
+}
@@ -0,0 +1,464 @@
+use std::future::Future;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, StatefulWidget, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    event::{KeyCode, KeyEvent},
+    shared::Shared,
+};
+
+/// Suggestions received for a request.
+#[derive(Debug, Default)]
+struct Results {
+    /// Request the suggestions answer.
+    generation: u64,
+    /// The suggestions.
+    items: Vec<String>,
+}
+
+/// Result of [`SuggestionState::handle_key_event`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SuggestionEvent {
+    /// The key is not for the popup and should go to the input.
+    Ignored,
+    /// The key moved the selection or closed the popup.
+    Handled,
+    /// The suggestion was accepted and should replace the input.
+    Accepted(String),
+}
+
+/// State of a [`SuggestionPopup`]: the suggestions, the selected one and the
+/// clickable areas of the last render.
+#[derive(Debug, Default, Clone)]
+pub struct SuggestionState {
+    /// Suggestions, written by the provider tasks.
+    results: Shared<Results>,
+    /// Last request.
+    generation: u64,
+    /// Version of the results the selection refers to.
+    seen: u64,
+    /// Index of the selected suggestion.
+    selected: Option<usize>,
+    /// Whether the popup is shown.
+    open: bool,
+    /// Index of the first visible suggestion.
+    offset: usize,
+    /// Areas of the rendered suggestions.
+    hits: Vec<(usize, Rect)>,
+}
+
+impl SuggestionState {
+    /// Constructs a new, closed [`SuggestionState`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests suggestions for the query, e.g. when the input changed.
+    ///
+    /// The provider runs on the browser event loop. Its suggestions are shown
+    /// when ready, unless a newer request was made in the meantime.
+    pub fn request<F, Fut>(&mut self, query: &str, provider: F)
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: Future<Output = Vec<String>> + 'static,
+    {
+        let generation = self.next_generation();
+        let future = provider(query.to_string());
+        self.results.spawn(move |results| async move {
+            let items = future.await;
+            apply(&results, generation, items);
+        });
+    }
+
+    /// Sets the suggestions directly, e.g. from a list filtered in place.
+    pub fn set_suggestions(&mut self, items: Vec<String>) {
+        let generation = self.next_generation();
+        apply(&self.results, generation, items);
+    }
+
+    /// Returns the suggestions.
+    pub fn suggestions(&self) -> Vec<String> {
+        self.results.with(|r| r.items.clone())
+    }
+
+    /// Returns the index of the selected suggestion.
+    pub fn selected(&mut self) -> Option<usize> {
+        self.sync();
+        self.selected
+    }
+
+    /// Returns `true` if the popup is shown.
+    pub fn is_open(&self) -> bool {
+        self.open && self.results.with(|r| !r.items.is_empty())
+    }
+
+    /// Closes the popup until the next request.
+    pub fn close(&mut self) {
+        self.open = false;
+        self.selected = None;
+    }
+
+    /// Selects the next suggestion, wrapping around.
+    pub fn select_next(&mut self) {
+        self.sync();
+        let len = self.results.with(|r| r.items.len());
+        if len > 0 {
+            self.selected = Some(self.selected.map_or(0, |i| (i + 1) % len));
+        }
+    }
+
+    /// Selects the previous suggestion, wrapping around.
+    pub fn select_previous(&mut self) {
+        self.sync();
+        let len = self.results.with(|r| r.items.len());
+        if len > 0 {
+            self.selected = Some(self.selected.map_or(len - 1, |i| (i + len - 1) % len));
+        }
+    }
+
+    /// Returns the rest of the selected suggestion, or of the first one, if
+    /// it starts with the input, to show as ghost text after the input.
+    ///
+    /// The input is matched case-insensitively.
+    pub fn completion(&mut self, input: &str) -> Option<String> {
+        if !self.is_open() || input.is_empty() {
+            return None;
+        }
+        let index = self.selected().unwrap_or(0);
+        self.results.with(|r| {
+            let candidate = r.items.get(index)?;
+            let mut chars = candidate.char_indices();
+            for c in input.chars() {
+                let (_, d) = chars.next()?;
+                if !c.to_lowercase().eq(d.to_lowercase()) {
+                    return None;
+                }
+            }
+            let rest = chars.next().map(|(i, _)| &candidate[i..])?;
+            Some(rest.to_string())
+        })
+    }
+
+    /// Handles a key event while the popup is shown.
+    ///
+    /// The up and down arrows move the selection, `Tab` accepts the selected
+    /// suggestion or the first one, `Enter` accepts the selected suggestion
+    /// and `Esc` closes the popup. The other keys are
+    /// [ignored](SuggestionEvent::Ignored).
+    pub fn handle_key_event(&mut self, event: &KeyEvent) -> SuggestionEvent {
+        if !self.is_open() {
+            return SuggestionEvent::Ignored;
+        }
+        match event.code {
+            KeyCode::Down => self.select_next(),
+            KeyCode::Up => self.select_previous(),
+            KeyCode::Tab if !event.shift => {
+                let index = self.selected().unwrap_or(0);
+                return self.accept(index);
+            }
+            KeyCode::Enter => match self.selected() {
+                Some(index) => return self.accept(index),
+                None => return SuggestionEvent::Ignored,
+            },
+            KeyCode::Esc => self.close(),
+            _ => return SuggestionEvent::Ignored,
+        }
+        SuggestionEvent::Handled
+    }
+
+    /// Returns the suggestion at the given cell position, if any, accepting
+    /// it.
+    ///
+    /// Use this when the popup is clicked.
+    pub fn click(&mut self, column: u16, row: u16) -> Option<String> {
+        let position = Position::new(column, row);
+        let index = self
+            .hits
+            .iter()
+            .find(|(_, area)| area.contains(position))
+            .map(|(index, _)| *index)?;
+        match self.accept(index) {
+            SuggestionEvent::Accepted(suggestion) => Some(suggestion),
+            _ => None,
+        }
+    }
+
+    /// Starts a new request and opens the popup.
+    fn next_generation(&mut self) -> u64 {
+        self.generation += 1;
+        self.open = true;
+        self.generation
+    }
+
+    /// Accepts the suggestion at the given index and closes the popup.
+    fn accept(&mut self, index: usize) -> SuggestionEvent {
+        let suggestion = self.results.with(|r| r.items.get(index).cloned());
+        match suggestion {
+            Some(suggestion) => {
+                self.close();
+                SuggestionEvent::Accepted(suggestion)
+            }
+            None => SuggestionEvent::Ignored,
+        }
+    }
+
+    /// Clears the selection when new suggestions arrived.
+    fn sync(&mut self) {
+        let version = self.results.version();
+        if self.seen != version {
+            self.seen = version;
+            self.selected = None;
+            self.offset = 0;
+        }
+    }
+}
+
+/// Sets the suggestions of a request, unless they are older than the shown
+/// ones.
+fn apply(results: &Shared<Results>, generation: u64, items: Vec<String>) {
+    if results.with(|r| r.generation) < generation {
+        results.set(Results { generation, items });
+    }
+}
+
+/// A popup listing suggestions below a text input, e.g. for a search box or
+/// a command input.
+///
+/// The popup is rendered below the area of the input, or above it when there
+/// is no room, on top of what was rendered before. The suggestions are
+/// provided by the [`SuggestionState`], which also returns the ghost text
+/// completing the input:
+///
+/// ```rust no_run
+/// use ratzilla::{
+///     ratatui::{style::Stylize, text::Line},
+///     widgets::{SuggestionPopup, SuggestionState},
+/// };
+/// # async fn search(query: String) -> Vec<String> { vec![query] }
+///
+/// let mut state = SuggestionState::new();
+/// let input = "ra";
+/// // when the input changes:
+/// state.request(input, search);
+///
+/// let mut line = Line::from(input);
+/// if let Some(completion) = state.completion(input) {
+///     line.push_span(completion.dim());
+/// }
+/// // frame.render_widget(line, input_area);
+/// // frame.render_stateful_widget(SuggestionPopup::new(), input_area, &mut state);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SuggestionPopup<'a> {
+    /// Block around the suggestions.
+    block: Option<Block<'a>>,
+    /// Style of the popup.
+    style: Style,
+    /// Style of the selected suggestion.
+    highlight_style: Style,
+    /// Maximum number of visible suggestions.
+    max_visible: u16,
+}
+
+impl Default for SuggestionPopup<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> SuggestionPopup<'a> {
+    /// Constructs a new [`SuggestionPopup`] showing up to 8 suggestions.
+    pub fn new() -> Self {
+        Self {
+            block: Some(Block::new().borders(Borders::ALL)),
+            style: Style::default(),
+            highlight_style: Style::new().add_modifier(Modifier::REVERSED),
+            max_visible: 8,
+        }
+    }
+
+    /// Sets the block around the suggestions, or removes it with `None`.
+    pub fn block(mut self, block: Option<Block<'a>>) -> Self {
+        self.block = block;
+        self
+    }
+
+    /// Sets the style of the popup.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the selected suggestion.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = style;
+        self
+    }
+
+    /// Sets the maximum number of visible suggestions.
+    pub fn max_visible(mut self, max: u16) -> Self {
+        self.max_visible = max.max(1);
+        self
+    }
+
+    /// Returns the area of the popup for the given input area.
+    fn popup_area(&self, input: Rect, bounds: Rect, items: &[String]) -> Rect {
+        let border = if self.block.is_some() { 2 } else { 0 };
+        let rows = (items.len() as u16).min(self.max_visible);
+        let height = rows + border;
+        let width = items.iter().map(|s| s.width() as u16).max().unwrap_or(0) + border;
+        let width = width.max(input.width).min(bounds.width);
+        let x = input.x.min(bounds.right().saturating_sub(width));
+        let below = input.bottom();
+        let y = if below + height <= bounds.bottom() || input.y.saturating_sub(bounds.y) < height {
+            below
+        } else {
+            input.y - height
+        };
+        Rect::new(x, y, width, height).intersection(bounds)
+    }
+}
+
+impl StatefulWidget for SuggestionPopup<'_> {
+    type State = SuggestionState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        state.hits.clear();
+        state.sync();
+        if !state.is_open() {
+            return;
+        }
+        let items = state.suggestions();
+        let popup = self.popup_area(area, buf.area, &items);
+        if popup.is_empty() {
+            return;
+        }
+        Clear.render(popup, buf);
+        buf.set_style(popup, self.style);
+        let inner = match &self.block {
+            Some(block) => {
+                let inner = block.inner(popup);
+                block.clone().render(popup, buf);
+                inner
+            }
+            None => popup,
+        };
+        let rows = inner.height as usize;
+        if rows == 0 {
+            return;
+        }
+
+        // keep the selected suggestion visible
+        if let Some(selected) = state.selected {
+            if selected < state.offset {
+                state.offset = selected;
+            } else if selected >= state.offset + rows {
+                state.offset = selected + 1 - rows;
+            }
+        }
+        for (row, (index, item)) in items
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .take(rows)
+            .enumerate()
+        {
+            let line = Rect::new(inner.x, inner.y + row as u16, inner.width, 1);
+            let style = if state.selected == Some(index) {
+                self.style.patch(self.highlight_style)
+            } else {
+                self.style
+            };
+            buf.set_style(line, style);
+            buf.set_stringn(line.x, line.y, item, line.width as usize, style);
+            state.hits.push((index, line));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent {
+            code,
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+            repeat: false,
+        }
+    }
+
+    fn state(items: &[&str]) -> SuggestionState {
+        let mut state = SuggestionState::new();
+        state.set_suggestions(items.iter().map(|s| s.to_string()).collect());
+        state
+    }
+
+    #[test]
+    fn test_navigation_and_accept() {
+        let mut state = state(&["ratatui", "ratzilla", "rust"]);
+        assert_eq!(state.completion("RA"), Some("tatui".into()));
+        assert_eq!(
+            state.handle_key_event(&key(KeyCode::Up)),
+            SuggestionEvent::Handled
+        );
+        assert_eq!(state.selected(), Some(2));
+        assert_eq!(state.completion("ra"), None);
+        state.select_next();
+        state.select_next();
+        assert_eq!(state.completion("ra"), Some("tzilla".into()));
+        assert_eq!(
+            state.handle_key_event(&key(KeyCode::Enter)),
+            SuggestionEvent::Accepted("ratzilla".into())
+        );
+        assert!(!state.is_open());
+        assert_eq!(
+            state.handle_key_event(&key(KeyCode::Down)),
+            SuggestionEvent::Ignored
+        );
+    }
+
+    #[test]
+    fn test_stale_results() {
+        let mut state = state(&["old"]);
+        let stale = state.generation;
+        state.set_suggestions(vec!["new".into()]);
+        apply(&state.results, stale, vec!["late".into()]);
+        assert_eq!(state.suggestions(), ["new"]);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut state = state(&["alpha", "beta", "gamma"]);
+        state.select_previous();
+        let mut buf = Buffer::empty(Rect::new(0, 0, 10, 6));
+        let input = Rect::new(0, 4, 10, 1);
+        SuggestionPopup::new()
+            .block(None)
+            .highlight_style(Style::new())
+            .max_visible(2)
+            .render(input, &mut buf, &mut state);
+        // no room below the input, so the popup is above it
+        assert_eq!(
+            buf,
+            Buffer::with_lines([
+                "          ",
+                "          ",
+                "beta      ",
+                "gamma     ",
+                "          ",
+                "          "
+            ])
+        );
+        assert_eq!(state.click(0, 3), Some("gamma".into()));
+    }
+}
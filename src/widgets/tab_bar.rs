@@ -0,0 +1,365 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+    text::Span,
+    widgets::StatefulWidget,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Glyph of the close buttons.
+const CLOSE_GLYPH: &str = "×";
+
+/// An action requested through a [`TabBar`].
+///
+/// The tab bar doesn't own the tabs, so the app applies the actions to its
+/// own list, e.g. with [`ScreenManager::apply_tab_action`].
+///
+/// [`ScreenManager::apply_tab_action`]: crate::screen::ScreenManager::apply_tab_action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabAction {
+    /// The tab at the given index was selected.
+    Select(usize),
+    /// The close button of the tab at the given index was clicked.
+    Close(usize),
+    /// The tab at index `from` was dragged to index `to`.
+    Move {
+        /// The original index.
+        from: usize,
+        /// The new index.
+        to: usize,
+    },
+}
+
+/// The clickable area of a rendered tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TabHit {
+    /// Index of the tab.
+    index: usize,
+    /// Area of the whole tab.
+    area: Rect,
+    /// Area of the close button.
+    close: Option<Rect>,
+}
+
+/// State of a [`TabBar`]: the selected tab, the scroll offset and the
+/// clickable areas of the last render.
+#[derive(Debug, Default, Clone)]
+pub struct TabBarState {
+    /// Index of the selected tab.
+    selected: usize,
+    /// Index of the first visible tab.
+    offset: usize,
+    /// Areas of the rendered tabs.
+    hits: Vec<TabHit>,
+    /// Area of the "scroll left" arrow.
+    scroll_left: Option<Rect>,
+    /// Area of the "scroll right" arrow.
+    scroll_right: Option<Rect>,
+    /// Index of the tab being dragged.
+    dragging: Option<usize>,
+    /// The selected tab that was last scrolled into view.
+    scrolled_to: Option<usize>,
+}
+
+impl TabBarState {
+    /// Constructs a new [`TabBarState`] with the first tab selected.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the index of the selected tab.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Selects the tab at the given index, scrolling it into view on the next render.
+    pub fn select(&mut self, index: usize) {
+        self.selected = index;
+    }
+
+    /// Returns the index of the tab at the given cell position.
+    pub fn tab_at(&self, column: u16, row: u16) -> Option<usize> {
+        let position = Position::new(column, row);
+        self.hits
+            .iter()
+            .find(|hit| hit.area.contains(position))
+            .map(|hit| hit.index)
+    }
+
+    /// Handles a mouse button press at the given cell position.
+    ///
+    /// Pressing a tab selects it and starts dragging it, pressing a close
+    /// button requests closing its tab, and pressing an arrow scrolls the
+    /// tabs.
+    pub fn mouse_down(&mut self, column: u16, row: u16) -> Option<TabAction> {
+        let position = Position::new(column, row);
+        if self.scroll_left.is_some_and(|r| r.contains(position)) {
+            self.offset = self.offset.saturating_sub(1);
+            return None;
+        }
+        if self.scroll_right.is_some_and(|r| r.contains(position)) {
+            self.offset += 1;
+            return None;
+        }
+        let hit = self.hits.iter().find(|hit| hit.area.contains(position))?;
+        if hit.close.is_some_and(|r| r.contains(position)) {
+            return Some(TabAction::Close(hit.index));
+        }
+        self.selected = hit.index;
+        self.dragging = Some(hit.index);
+        Some(TabAction::Select(hit.index))
+    }
+
+    /// Handles a mouse button release at the given cell position.
+    ///
+    /// Releasing a dragged tab over another one requests moving it there.
+    pub fn mouse_up(&mut self, column: u16, row: u16) -> Option<TabAction> {
+        let from = self.dragging.take()?;
+        let to = self.tab_at(column, row)?;
+        if from == to {
+            return None;
+        }
+        self.selected = to;
+        Some(TabAction::Move { from, to })
+    }
+
+    /// Returns `true` while a tab is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.dragging.is_some()
+    }
+}
+
+/// A tab bar with close buttons, drag-to-reorder and overflow scrolling.
+///
+/// When the tabs don't fit, arrows are shown on both sides to scroll them,
+/// and the selected tab is always kept in view. Mouse events are forwarded to
+/// the [`TabBarState`], which returns the [`TabAction`]s to apply.
+///
+/// ```rust no_run
+/// use ratzilla::widgets::{TabBar, TabBarState};
+///
+/// let tabs = TabBar::new(["main.rs", "lib.rs", "Cargo.toml"]).closable(true);
+/// let mut state = TabBarState::new();
+///
+/// // frame.render_stateful_widget(tabs, area, &mut state);
+/// // and on mouse events: state.mouse_down(column, row);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TabBar<'a> {
+    /// Tab titles.
+    titles: Vec<Span<'a>>,
+    /// Whether the tabs have close buttons.
+    closable: bool,
+    /// Style of the bar.
+    style: Style,
+    /// Style of the selected tab.
+    highlight_style: Style,
+    /// Separator between tabs.
+    divider: Span<'a>,
+}
+
+impl<'a> TabBar<'a> {
+    /// Constructs a new [`TabBar`] with the given titles.
+    pub fn new<I, T>(titles: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Span<'a>>,
+    {
+        Self {
+            titles: titles.into_iter().map(Into::into).collect(),
+            closable: false,
+            style: Style::default(),
+            highlight_style: Style::new().add_modifier(Modifier::REVERSED),
+            divider: Span::raw("│"),
+        }
+    }
+
+    /// Shows a close button on every tab.
+    pub fn closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+
+    /// Sets the style of the bar.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the selected tab.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = style;
+        self
+    }
+
+    /// Sets the separator between tabs.
+    pub fn divider<T>(mut self, divider: T) -> Self
+    where
+        T: Into<Span<'a>>,
+    {
+        self.divider = divider.into();
+        self
+    }
+
+    /// Returns the width of the tab at the given index.
+    fn tab_width(&self, index: usize) -> u16 {
+        let close = if self.closable {
+            CLOSE_GLYPH.width()
+        } else {
+            0
+        };
+        (self.titles[index].content.width() + 2 + close) as u16
+    }
+
+    /// Returns the index of the first visible tab, so the selected tab fits
+    /// into `width` cells.
+    fn scroll_offset(&self, offset: usize, selected: usize, width: u16) -> usize {
+        let mut offset = offset.min(selected);
+        let divider = self.divider.content.width() as u16;
+        loop {
+            let used: u16 = (offset..=selected)
+                .map(|i| self.tab_width(i) + divider)
+                .sum::<u16>()
+                - divider;
+            if used <= width || offset == selected {
+                return offset;
+            }
+            offset += 1;
+        }
+    }
+}
+
+impl StatefulWidget for TabBar<'_> {
+    type State = TabBarState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        state.hits.clear();
+        state.scroll_left = None;
+        state.scroll_right = None;
+        let area = area.intersection(buf.area);
+        if area.is_empty() || self.titles.is_empty() {
+            return;
+        }
+        buf.set_style(area, self.style);
+        state.selected = state.selected.min(self.titles.len() - 1);
+
+        let divider = self.divider.content.width() as u16;
+        let total: u16 = (0..self.titles.len())
+            .map(|i| self.tab_width(i) + divider)
+            .sum::<u16>()
+            - divider;
+        let overflow = total > area.width && area.width > 2;
+        let (mut x, end) = if overflow {
+            (area.x + 1, area.right() - 1)
+        } else {
+            state.offset = 0;
+            (area.x, area.right())
+        };
+        if overflow {
+            // only follow the selection when it changes, so the arrows can scroll freely
+            if state.scrolled_to != Some(state.selected) {
+                state.offset = self.scroll_offset(state.offset, state.selected, end - x);
+                state.scrolled_to = Some(state.selected);
+            }
+            state.offset = state.offset.min(self.titles.len() - 1);
+        }
+
+        let y = area.y;
+        for index in state.offset..self.titles.len() {
+            let width = self.tab_width(index);
+            if x + width > end {
+                break;
+            }
+            let style = if index == state.selected {
+                self.style.patch(self.highlight_style)
+            } else {
+                self.style
+            };
+            let tab = Rect::new(x, y, width, 1);
+            buf.set_style(tab, style);
+            let (title_end, _) = buf.set_span(x + 1, y, &self.titles[index], width - 2);
+            let close = self.closable.then(|| {
+                let close = Rect::new(title_end + 1, y, CLOSE_GLYPH.width() as u16, 1);
+                buf.set_string(close.x, y, CLOSE_GLYPH, style);
+                close
+            });
+            state.hits.push(TabHit {
+                index,
+                area: tab,
+                close,
+            });
+            x += width;
+            if index + 1 < self.titles.len() && x + divider <= end {
+                buf.set_span(x, y, &self.divider, divider);
+                x += divider;
+            }
+        }
+
+        if overflow {
+            if state.offset > 0 {
+                buf.set_string(area.x, y, "‹", self.style);
+                state.scroll_left = Some(Rect::new(area.x, y, 1, 1));
+            }
+            if state
+                .hits
+                .last()
+                .is_some_and(|hit| hit.index + 1 < self.titles.len())
+            {
+                buf.set_string(end, y, "›", self.style);
+                state.scroll_right = Some(Rect::new(end, y, 1, 1));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(tabs: TabBar, width: u16, state: &mut TabBarState) -> Buffer {
+        let area = Rect::new(0, 0, width, 1);
+        let mut buf = Buffer::empty(area);
+        tabs.highlight_style(Style::new())
+            .render(area, &mut buf, state);
+        buf
+    }
+
+    #[test]
+    fn test_render_and_click() {
+        let mut state = TabBarState::new();
+        let buf = render(TabBar::new(["a", "bb"]).closable(true), 12, &mut state);
+        assert_eq!(buf, Buffer::with_lines([" a ×│ bb ×  "]));
+
+        assert_eq!(state.mouse_down(5, 0), Some(TabAction::Select(1)));
+        assert_eq!(state.selected(), 1);
+        assert_eq!(state.mouse_up(5, 0), None);
+        assert_eq!(state.mouse_down(3, 0), Some(TabAction::Close(0)));
+    }
+
+    #[test]
+    fn test_drag_to_reorder() {
+        let mut state = TabBarState::new();
+        render(TabBar::new(["a", "b", "c"]), 12, &mut state);
+        assert_eq!(state.mouse_down(1, 0), Some(TabAction::Select(0)));
+        assert!(state.is_dragging());
+        assert_eq!(
+            state.mouse_up(9, 0),
+            Some(TabAction::Move { from: 0, to: 2 })
+        );
+        assert_eq!(state.selected(), 2);
+    }
+
+    #[test]
+    fn test_overflow_keeps_selected_visible() {
+        let mut state = TabBarState::new();
+        state.select(3);
+        let buf = render(TabBar::new(["one", "two", "three", "four"]), 14, &mut state);
+        assert_eq!(buf, Buffer::with_lines(["‹ four        "]));
+        assert_eq!(state.tab_at(2, 0), Some(3));
+
+        assert_eq!(state.mouse_down(0, 0), None);
+        render(TabBar::new(["one", "two", "three", "four"]), 14, &mut state);
+        assert_eq!(state.tab_at(2, 0), Some(2));
+    }
+}
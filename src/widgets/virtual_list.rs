@@ -0,0 +1,539 @@
+use std::{collections::HashMap, hash::Hash};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{StatefulWidget, Widget},
+};
+
+/// A change made to a [`KeyedList`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListChange<K> {
+    /// An item was inserted at the given index.
+    Inserted {
+        /// Key of the item.
+        key: K,
+        /// Index of the item after the change.
+        index: usize,
+    },
+    /// An item was removed from the given index.
+    Removed {
+        /// Key of the item.
+        key: K,
+        /// Index of the item before the change.
+        index: usize,
+    },
+    /// An item was moved to another index.
+    Moved {
+        /// Key of the item.
+        key: K,
+        /// Index of the item before the change.
+        from: usize,
+        /// Index of the item after the change.
+        to: usize,
+    },
+    /// An item was replaced with a different value.
+    Updated {
+        /// Key of the item.
+        key: K,
+        /// Index of the item after the change.
+        index: usize,
+    },
+}
+
+/// A list of items with stable keys, recording the changes made to it.
+///
+/// Items are looked up by key in constant time, so a [`VirtualList`] keeps
+/// its selection and scroll position on the same items while a streaming
+/// data source inserts, removes and moves them.
+///
+/// ```rust
+/// use ratzilla::widgets::{KeyedList, ListChange};
+///
+/// let mut list = KeyedList::new();
+/// list.push(1, "first");
+/// list.push(2, "second");
+///
+/// // a new snapshot of the data source
+/// let changes = list.sync([(2, "second"), (3, "third")]);
+/// assert_eq!(changes[0], ListChange::Removed { key: 1, index: 0 });
+/// assert_eq!(changes[1], ListChange::Inserted { key: 3, index: 1 });
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyedList<K, T> {
+    /// Keys and items, in order.
+    entries: Vec<(K, T)>,
+    /// Index of every key.
+    positions: HashMap<K, usize>,
+    /// Changes since the last call to `take_changes`.
+    changes: Vec<ListChange<K>>,
+}
+
+impl<K: Clone + Eq + Hash, T> Default for KeyedList<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + Eq + Hash, T> KeyedList<K, T> {
+    /// Constructs a new, empty [`KeyedList`].
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            positions: HashMap::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    /// Returns the number of items.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the list has no items.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the item with the given key.
+    pub fn get(&self, key: &K) -> Option<&T> {
+        self.index_of(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Returns the key and item at the given index.
+    pub fn get_index(&self, index: usize) -> Option<(&K, &T)> {
+        self.entries.get(index).map(|(key, item)| (key, item))
+    }
+
+    /// Returns the index of the item with the given key.
+    pub fn index_of(&self, key: &K) -> Option<usize> {
+        self.positions.get(key).copied()
+    }
+
+    /// Returns an iterator over the keys and items, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &T)> {
+        self.entries.iter().map(|(key, item)| (key, item))
+    }
+
+    /// Appends an item, replacing the item with the same key if any.
+    pub fn push(&mut self, key: K, item: T) {
+        self.insert(self.len(), key, item);
+    }
+
+    /// Inserts an item at the given index, replacing the item with the same
+    /// key if any.
+    ///
+    /// The index is clamped to the length of the list.
+    pub fn insert(&mut self, index: usize, key: K, item: T) {
+        if let Some(current) = self.index_of(&key) {
+            self.entries[current].1 = item;
+            self.changes.push(ListChange::Updated {
+                key: key.clone(),
+                index: current,
+            });
+            self.move_to(&key, index);
+            return;
+        }
+        let index = index.min(self.len());
+        self.entries.insert(index, (key.clone(), item));
+        self.reindex(index);
+        self.changes.push(ListChange::Inserted { key, index });
+    }
+
+    /// Removes the item with the given key.
+    pub fn remove(&mut self, key: &K) -> Option<T> {
+        let index = self.positions.remove(key)?;
+        let (key, item) = self.entries.remove(index);
+        self.reindex(index);
+        self.changes.push(ListChange::Removed { key, index });
+        Some(item)
+    }
+
+    /// Moves the item with the given key to the given index.
+    ///
+    /// The index is clamped to the last index of the list.
+    pub fn move_to(&mut self, key: &K, index: usize) {
+        let Some(from) = self.index_of(key) else {
+            return;
+        };
+        let to = index.min(self.len() - 1);
+        if from == to {
+            return;
+        }
+        let entry = self.entries.remove(from);
+        self.entries.insert(to, entry);
+        self.reindex(from.min(to));
+        self.changes.push(ListChange::Moved {
+            key: key.clone(),
+            from,
+            to,
+        });
+    }
+
+    /// Replaces the items with a new snapshot of the data source, returning
+    /// the changes between both.
+    ///
+    /// Removals are reported first, then insertions, moves and updates. Only
+    /// the smallest set of items is reported as moved, e.g. a single item
+    /// moved to the top doesn't move all the others.
+    pub fn sync<I>(&mut self, items: I) -> Vec<ListChange<K>>
+    where
+        I: IntoIterator<Item = (K, T)>,
+        T: PartialEq,
+    {
+        let entries: Vec<(K, T)> = items.into_iter().collect();
+        let positions: HashMap<K, usize> = entries
+            .iter()
+            .enumerate()
+            .map(|(index, (key, _))| (key.clone(), index))
+            .collect();
+
+        let mut changes: Vec<_> = self
+            .entries
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, (key, _))| !positions.contains_key(key))
+            .map(|(index, (key, _))| ListChange::Removed {
+                key: key.clone(),
+                index,
+            })
+            .collect();
+        // old indices of the kept items, in their new order
+        let kept: Vec<usize> = entries
+            .iter()
+            .filter_map(|(key, _)| self.index_of(key))
+            .collect();
+        let stable = longest_increasing(&kept);
+        let mut moves = Vec::new();
+        let mut updates = Vec::new();
+        for (to, (key, item)) in entries.iter().enumerate() {
+            let Some(from) = self.index_of(key) else {
+                changes.push(ListChange::Inserted {
+                    key: key.clone(),
+                    index: to,
+                });
+                continue;
+            };
+            if stable.binary_search(&from).is_err() {
+                moves.push(ListChange::Moved {
+                    key: key.clone(),
+                    from,
+                    to,
+                });
+            }
+            if self.entries[from].1 != *item {
+                updates.push(ListChange::Updated {
+                    key: key.clone(),
+                    index: to,
+                });
+            }
+        }
+        changes.extend(moves);
+        changes.extend(updates);
+
+        self.entries = entries;
+        self.positions = positions;
+        self.changes.extend(changes.iter().cloned());
+        changes
+    }
+
+    /// Returns and clears the changes made since the last call.
+    pub fn take_changes(&mut self) -> Vec<ListChange<K>> {
+        std::mem::take(&mut self.changes)
+    }
+
+    /// Updates the index of the keys from the given index.
+    fn reindex(&mut self, from: usize) {
+        for (index, (key, _)) in self.entries.iter().enumerate().skip(from) {
+            self.positions.insert(key.clone(), index);
+        }
+    }
+}
+
+/// Returns the old indices of a longest run of items that kept their
+/// relative order, given the old indices in the new order.
+fn longest_increasing(indices: &[usize]) -> Vec<usize> {
+    // smallest tail of the increasing runs of every length
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![None; indices.len()];
+    for (position, &old) in indices.iter().enumerate() {
+        let length = tails.partition_point(|&tail| indices[tail] < old);
+        if length > 0 {
+            previous[position] = Some(tails[length - 1]);
+        }
+        if length == tails.len() {
+            tails.push(position);
+        } else {
+            tails[length] = position;
+        }
+    }
+    let mut stable = Vec::with_capacity(tails.len());
+    let mut position = tails.last().copied();
+    while let Some(current) = position {
+        stable.push(indices[current]);
+        position = previous[current];
+    }
+    stable.sort_unstable();
+    stable
+}
+
+/// State of a [`VirtualList`]: the selected item and the scroll position.
+///
+/// Both are kept by key, so they stay on the same items when the list
+/// changes.
+#[derive(Debug, Clone)]
+pub struct VirtualListState<K> {
+    /// Key of the selected item.
+    selected: Option<K>,
+    /// Key of the first visible item at the last render.
+    anchor: Option<K>,
+    /// Index of the first visible item.
+    offset: usize,
+    /// Number of visible rows at the last render.
+    height: usize,
+    /// The selected item that was last scrolled into view.
+    scrolled_to: Option<K>,
+}
+
+impl<K> Default for VirtualListState<K> {
+    fn default() -> Self {
+        Self {
+            selected: None,
+            anchor: None,
+            offset: 0,
+            height: 0,
+            scrolled_to: None,
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash> VirtualListState<K> {
+    /// Constructs a new [`VirtualListState`] scrolled to the top, without
+    /// selection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the key of the selected item.
+    pub fn selected(&self) -> Option<&K> {
+        self.selected.as_ref()
+    }
+
+    /// Selects the item with the given key, scrolling it into view on the
+    /// next render.
+    pub fn select(&mut self, key: Option<K>) {
+        self.selected = key;
+    }
+
+    /// Returns the index of the first visible item at the last render.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Selects the next item of the list.
+    pub fn select_next<T>(&mut self, list: &KeyedList<K, T>) {
+        let index = self
+            .selected_index(list)
+            .map_or(0, |index| (index + 1).min(list.len().saturating_sub(1)));
+        self.selected = list.get_index(index).map(|(key, _)| key.clone());
+    }
+
+    /// Selects the previous item of the list.
+    pub fn select_previous<T>(&mut self, list: &KeyedList<K, T>) {
+        let index = self
+            .selected_index(list)
+            .map_or(0, |index| index.saturating_sub(1));
+        self.selected = list.get_index(index).map(|(key, _)| key.clone());
+    }
+
+    /// Scrolls by the given number of rows, e.g. from a [`WheelEvent`].
+    ///
+    /// [`WheelEvent`]: crate::event::WheelEvent
+    pub fn scroll_by<T>(&mut self, rows: i32, list: &KeyedList<K, T>) {
+        let offset = self
+            .current_offset(list)
+            .saturating_add_signed(rows as isize);
+        let offset = offset.min(list.len().saturating_sub(self.height));
+        self.anchor = list.get_index(offset).map(|(key, _)| key.clone());
+        self.offset = offset;
+    }
+
+    /// Returns the index of the selected item.
+    fn selected_index<T>(&self, list: &KeyedList<K, T>) -> Option<usize> {
+        self.selected.as_ref().and_then(|key| list.index_of(key))
+    }
+
+    /// Returns the index of the first visible item, following its key.
+    fn current_offset<T>(&self, list: &KeyedList<K, T>) -> usize {
+        self.anchor
+            .as_ref()
+            .and_then(|key| list.index_of(key))
+            .unwrap_or(self.offset)
+    }
+}
+
+/// A list that only renders its visible items.
+///
+/// The items are read from a [`KeyedList`], so rendering a frame costs the
+/// same with a hundred or a million items, and updating the list doesn't
+/// require rebuilding a [`List`](ratatui::widgets::List) of all of them.
+///
+/// ```rust no_run
+/// use ratzilla::{
+///     ratatui::text::Line,
+///     widgets::{KeyedList, VirtualList, VirtualListState},
+/// };
+///
+/// let mut messages = KeyedList::new();
+/// messages.push(1, String::from("hello"));
+/// let mut state = VirtualListState::new();
+/// state.select(Some(1));
+///
+/// let list = VirtualList::new(&messages, |message: &String| Line::raw(message.as_str()));
+/// // frame.render_stateful_widget(list, area, &mut state);
+/// ```
+pub struct VirtualList<'a, K, T, F> {
+    /// The items.
+    list: &'a KeyedList<K, T>,
+    /// Renders an item.
+    render_item: F,
+    /// Style of the list.
+    style: Style,
+    /// Style of the selected item.
+    highlight_style: Style,
+}
+
+impl<'a, K, T, F> VirtualList<'a, K, T, F>
+where
+    F: Fn(&'a T) -> Line<'a>,
+{
+    /// Constructs a new [`VirtualList`] rendering the items of the list
+    /// with the given closure.
+    pub fn new(list: &'a KeyedList<K, T>, render_item: F) -> Self {
+        Self {
+            list,
+            render_item,
+            style: Style::default(),
+            highlight_style: Style::new().add_modifier(Modifier::REVERSED),
+        }
+    }
+
+    /// Sets the style of the list.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets the style of the selected item.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = style;
+        self
+    }
+}
+
+impl<'a, K, T, F> StatefulWidget for VirtualList<'a, K, T, F>
+where
+    K: Clone + Eq + Hash,
+    F: Fn(&'a T) -> Line<'a>,
+{
+    type State = VirtualListState<K>;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        buf.set_style(area, self.style);
+        let height = area.height as usize;
+        let mut offset = state
+            .current_offset(self.list)
+            .min(self.list.len().saturating_sub(height));
+        let selected = state.selected_index(self.list);
+        if let Some(index) = selected.filter(|_| state.scrolled_to != state.selected) {
+            if index < offset {
+                offset = index;
+            } else if index >= offset + height {
+                offset = index + 1 - height;
+            }
+            state.scrolled_to = state.selected.clone();
+        }
+        state.offset = offset;
+        state.height = height;
+        state.anchor = self.list.get_index(offset).map(|(key, _)| key.clone());
+
+        for (row, index) in (offset..self.list.len().min(offset + height)).enumerate() {
+            let (_, item) = &self.list.entries[index];
+            let line_area = Rect::new(area.x, area.y + row as u16, area.width, 1);
+            if selected == Some(index) {
+                buf.set_style(line_area, self.highlight_style);
+            }
+            (self.render_item)(item).render(line_area, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_changes() {
+        let mut list = KeyedList::new();
+        for key in ['a', 'b', 'c', 'd'] {
+            list.push(key, 0);
+        }
+        list.take_changes();
+
+        let changes = list.sync([('d', 0), ('a', 0), ('c', 1), ('e', 0)]);
+        assert_eq!(
+            changes,
+            [
+                ListChange::Removed { key: 'b', index: 1 },
+                ListChange::Inserted { key: 'e', index: 3 },
+                ListChange::Moved {
+                    key: 'd',
+                    from: 3,
+                    to: 0
+                },
+                ListChange::Updated { key: 'c', index: 2 },
+            ]
+        );
+        assert_eq!(list.index_of(&'c'), Some(2));
+        assert_eq!(list.take_changes(), changes);
+    }
+
+    #[test]
+    fn test_edits() {
+        let mut list = KeyedList::new();
+        list.push(1, "one");
+        list.push(2, "two");
+        list.insert(0, 3, "three");
+        list.move_to(&1, 2);
+        assert_eq!(list.remove(&2), Some("two"));
+        let keys: Vec<_> = list.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, [3, 1]);
+        assert_eq!(list.index_of(&1), Some(1));
+    }
+
+    #[test]
+    fn test_render_keeps_position() {
+        let mut list = KeyedList::new();
+        for key in 0..10 {
+            list.push(key, key.to_string());
+        }
+        let mut state = VirtualListState::new();
+        state.select(Some(5));
+        let area = Rect::new(0, 0, 3, 3);
+        let mut buf = Buffer::empty(area);
+        VirtualList::new(&list, |item: &String| Line::raw(item.as_str()))
+            .render(area, &mut buf, &mut state);
+        assert_eq!(state.offset(), 3);
+        assert_eq!(buf[(0, 0)].symbol(), "3");
+
+        // items inserted above the view don't scroll it
+        list.insert(0, 100, String::from("x"));
+        VirtualList::new(&list, |item: &String| Line::raw(item.as_str()))
+            .render(area, &mut buf, &mut state);
+        assert_eq!(state.offset(), 4);
+        assert_eq!(buf[(0, 0)].symbol(), "3");
+    }
+}
@@ -0,0 +1,133 @@
+//! ## Yew
+//!
+//! A [`RatzillaTerminal`] component owning the lifecycle of a terminal in a
+//! Yew app: the terminal is mounted into the `<div>` of the component,
+//! drawn with the latest draw function from the props, and
+//! [destroyed](crate::teardown::destroy) when the component is unmounted.
+//!
+//! Since the draw function is a prop, it can read the props and the state
+//! of the parent component. The terminal redraws whenever the parent
+//! renders:
+//!
+//! ```rust no_run
+//! use ratzilla::{
+//!     event::KeyEvent,
+//!     ratatui::widgets::Paragraph,
+//!     yew::{DrawFn, RatzillaTerminal},
+//! };
+//! use yew::prelude::*;
+//!
+//! #[function_component(App)]
+//! fn app() -> Html {
+//!     let count = use_state(|| 0);
+//!     let draw = DrawFn::new({
+//!         let count = *count;
+//!         move |frame| frame.render_widget(Paragraph::new(format!("count: {count}")), frame.area())
+//!     });
+//!     let on_key = Callback::from(move |_: KeyEvent| count.set(*count + 1));
+//!     html! { <RatzillaTerminal {draw} {on_key} /> }
+//! }
+//! ```
+//!
+//! Only one terminal can be mounted at a time, as [`teardown`](crate::teardown)
+//! destroys every terminal of the page.
+//!
+//! [`RatzillaTerminal`]: crate::yew::RatzillaTerminal
+
+use std::{cell::RefCell, fmt, io, rc::Rc};
+
+use ::yew::prelude::*;
+use ratatui::{Frame, Terminal};
+
+use crate::{event::KeyEvent, render_loop, teardown, DomBackend, WebRenderer};
+
+/// A draw function, called with the frame of the terminal.
+///
+/// Draw functions are equal if they are the same closure, so the component
+/// redraws the terminal when the parent passes a new one.
+#[derive(Clone)]
+pub struct DrawFn(Rc<dyn Fn(&mut Frame)>);
+
+impl DrawFn {
+    /// Constructs a new [`DrawFn`].
+    pub fn new<F>(draw: F) -> Self
+    where
+        F: Fn(&mut Frame) + 'static,
+    {
+        Self(Rc::new(draw))
+    }
+}
+
+impl PartialEq for DrawFn {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for DrawFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DrawFn")
+    }
+}
+
+/// Properties of a [`RatzillaTerminal`].
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct RatzillaTerminalProps {
+    /// Draws the terminal.
+    pub draw: DrawFn,
+    /// Handles key events.
+    #[prop_or_default]
+    pub on_key: Option<Callback<KeyEvent>>,
+    /// Id of the `<div>` holding the grid.
+    #[prop_or(AttrValue::Static("ratzilla-terminal"))]
+    pub id: AttrValue,
+    /// Classes of the `<div>` holding the grid.
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+/// A terminal drawn with the [`DomBackend`] in a `<div>`.
+///
+/// The grid fills the `<div>`, so its size is set by the styles of the app.
+#[function_component(RatzillaTerminal)]
+pub fn ratzilla_terminal(props: &RatzillaTerminalProps) -> Html {
+    // the terminal reads the latest props
+    let draw = use_mut_ref(|| props.draw.clone());
+    let on_key = use_mut_ref(|| props.on_key.clone());
+    *draw.borrow_mut() = props.draw.clone();
+    *on_key.borrow_mut() = props.on_key.clone();
+    render_loop::request_redraw();
+
+    {
+        let id = props.id.clone();
+        use_effect_with((), move |_| {
+            if let Err(error) = mount(&id, draw, on_key) {
+                web_sys::console::error_1(&format!("unable to mount the terminal: {error}").into());
+            }
+            teardown::destroy
+        });
+    }
+
+    html! { <div id={props.id.clone()} class={props.class.clone()} /> }
+}
+
+/// Mounts a terminal into the element with the given id.
+fn mount(
+    id: &str,
+    draw: Rc<RefCell<DrawFn>>,
+    on_key: Rc<RefCell<Option<Callback<KeyEvent>>>>,
+) -> io::Result<()> {
+    let terminal = Terminal::new(DomBackend::new_by_id(id)?)?;
+    terminal.on_key_event(move |event| {
+        // emitting may render the component, which replaces the callback
+        let callback = on_key.borrow().clone();
+        if let Some(callback) = callback {
+            callback.emit(event);
+        }
+    });
+    terminal.draw_web(move |frame| {
+        let draw = draw.borrow().clone();
+        (draw.0)(frame);
+    });
+    Ok(())
+}